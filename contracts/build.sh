@@ -5,6 +5,9 @@
 
 set -e
 
+# Commit reported by the contracts' get_git_hash
+export CASPERVAULT_GIT_HASH="${CASPERVAULT_GIT_HASH:-$(git rev-parse --short HEAD 2>/dev/null || true)}"
+
 echo "🔨 Building CasperVault Smart Contracts..."
 echo "=========================================="
 echo ""
@@ -23,22 +26,22 @@ echo "🏗️  Building contracts..."
 # Add WASM target if not already added
 rustup target add wasm32-unknown-unknown 2>/dev/null || true
 
-# Build the library
+# Build the library (release-wasm leaves the mock contracts out)
 echo "  → Building library..."
-cargo build --release --target wasm32-unknown-unknown --lib
+cargo build --release --target wasm32-unknown-unknown --features release-wasm --lib
 
 # Build each binary
 echo "  → Building vault_manager..."
-cargo build --release --target wasm32-unknown-unknown --bin caspervault_vault_manager 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
+cargo build --release --target wasm32-unknown-unknown --features release-wasm --bin caspervault_vault_manager 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
 
 echo "  → Building liquid_staking..."
-cargo build --release --target wasm32-unknown-unknown --bin caspervault_liquid_staking 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
+cargo build --release --target wasm32-unknown-unknown --features release-wasm --bin caspervault_liquid_staking 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
 
 echo "  → Building lst_cspr token..."
-cargo build --release --target wasm32-unknown-unknown --bin caspervault_lst_cspr 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
+cargo build --release --target wasm32-unknown-unknown --features release-wasm --bin caspervault_lst_cspr 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
 
 echo "  → Building cv_cspr token..."
-cargo build --release --target wasm32-unknown-unknown --bin caspervault_cv_cspr 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
+cargo build --release --target wasm32-unknown-unknown --features release-wasm --bin caspervault_cv_cspr 2>&1 || echo "    ⚠️  Binary needs proper Odra integration"
 
 # Copy WASM files
 echo ""
@@ -0,0 +1,234 @@
+/// KeeperHub - single maintenance entry point for keeper networks
+///
+/// Registers maintenance jobs as (target contract, entrypoint, min interval)
+/// and runs every due job from one `poke()` call.
+///
+/// Cross-contract reverts abort the whole deploy on Casper, so targets expose
+/// `run_keeper_job(entrypoint) -> bool` instead of being called through their
+/// reverting entrypoints. A `false` result (rate limited, nothing to do,
+/// missing keeper role) is recorded as a failed job and the batch continues.
+
+use odra::prelude::*;
+use odra::Event;
+use odra::{Address, Mapping, SubModule, Var};
+use crate::types::*;
+use crate::utils::AccessControl;
+
+/// Job entrypoints (dispatched by the target's `run_keeper_job`)
+pub const JOB_COMPOUND_REWARDS: u8 = 0;
+pub const JOB_HARVEST_ALL: u8 = 1;
+pub const JOB_COLLECT_MANAGEMENT_FEES: u8 = 2;
+pub const JOB_REBALANCE: u8 = 3;
+pub const JOB_HEALTH_CHECK: u8 = 4;
+
+/// Entry point implemented by every contract that accepts keeper jobs
+///
+/// Must not revert for job-level failures; return `false` instead. The
+/// target checks that the caller (the hub) holds the keeper role.
+#[odra::external_contract]
+pub trait KeeperJobTarget {
+    /// Run the maintenance entrypoint, returns whether it did any work
+    fn run_keeper_job(&mut self, entrypoint: u8) -> bool;
+}
+
+/// Registered keeper job
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct KeeperJob {
+    pub id: u32,
+    pub target: Address,
+    pub entrypoint: u8,
+    pub min_interval: u64,
+    pub last_run: u64,
+}
+
+/// KeeperHub contract
+///
+/// Key responsibilities:
+/// - Keep the registry of maintenance jobs and their intervals
+/// - Run every job whose interval has elapsed on `poke()`
+/// - Isolate job failures so one stuck job doesn't block the others
+#[odra::module]
+pub struct KeeperHub {
+    /// Access control
+    access_control: SubModule<AccessControl>,
+
+    /// Registered job IDs in execution order
+    job_ids: Var<Vec<u32>>,
+    /// Next job ID to assign
+    next_job_id: Var<u32>,
+
+    /// Job fields (flattened for Casper serialization)
+    job_targets: Mapping<u32, Address>,
+    job_entrypoints: Mapping<u32, u8>,
+    job_intervals: Mapping<u32, u64>,
+    /// Last successful run per job
+    job_last_run: Mapping<u32, u64>,
+}
+
+#[odra::module]
+impl KeeperHub {
+    /// Initialize the KeeperHub
+    pub fn init(&mut self, admin: Address) {
+        self.access_control.init(admin);
+
+        self.job_ids.set(Vec::new());
+        self.next_job_id.set(1);
+    }
+
+    /// Run every due job
+    ///
+    /// Permissionless: the per-job intervals are the rate limit. A job's
+    /// last-run timestamp only advances on success, so failed jobs are
+    /// retried on the next poke. Emits `JobExecuted` per attempted job and
+    /// returns the number of jobs that succeeded.
+    pub fn poke(&mut self) -> u32 {
+        let current_time = self.env().get_block_time();
+        let job_ids = self.job_ids.get_or_default();
+        let mut succeeded = 0u32;
+
+        for id in job_ids.iter() {
+            if !self.is_job_due(*id) {
+                continue;
+            }
+
+            let (target, entrypoint) = match (self.job_targets.get(id), self.job_entrypoints.get(id)) {
+                (Some(target), Some(entrypoint)) => (target, entrypoint),
+                _ => continue,
+            };
+
+            let success = KeeperJobTargetContractRef::new(self.env(), target).run_keeper_job(entrypoint);
+
+            if success {
+                self.job_last_run.set(id, current_time);
+                succeeded += 1;
+            }
+
+            self.env().emit_event(JobExecuted {
+                job: *id,
+                success,
+                gas_note: Self::entrypoint_name(entrypoint),
+            });
+        }
+
+        succeeded
+    }
+
+    /// Register a job (admin only), returns its ID
+    pub fn add_job(&mut self, target: Address, entrypoint: u8, min_interval: u64) -> u32 {
+        self.access_control.only_admin();
+
+        if entrypoint > JOB_HEALTH_CHECK {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        let id = self.next_job_id.get_or_default();
+        self.next_job_id.set(id + 1);
+
+        self.job_targets.set(&id, target);
+        self.job_entrypoints.set(&id, entrypoint);
+        self.job_intervals.set(&id, min_interval);
+        self.job_last_run.set(&id, 0);
+
+        let mut job_ids = self.job_ids.get_or_default();
+        job_ids.push(id);
+        self.job_ids.set(job_ids);
+
+        id
+    }
+
+    /// Unregister a job (admin only)
+    pub fn remove_job(&mut self, id: u32) {
+        self.access_control.only_admin();
+
+        let mut job_ids = self.job_ids.get_or_default();
+        let len_before = job_ids.len();
+        job_ids.retain(|job_id| *job_id != id);
+
+        if job_ids.len() == len_before {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        self.job_ids.set(job_ids);
+    }
+
+    /// Change a job's minimum interval (admin only)
+    pub fn reschedule_job(&mut self, id: u32, min_interval: u64) {
+        self.access_control.only_admin();
+
+        if !self.job_ids.get_or_default().contains(&id) {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        self.job_intervals.set(&id, min_interval);
+    }
+
+    /// Whether a registered job's interval has elapsed
+    pub fn is_job_due(&self, id: u32) -> bool {
+        if !self.job_ids.get_or_default().contains(&id) {
+            return false;
+        }
+
+        let last_run = self.job_last_run.get(&id).unwrap_or(0);
+        if last_run == 0 {
+            return true;
+        }
+
+        let interval = self.job_intervals.get(&id).unwrap_or(0);
+        self.env().get_block_time() >= last_run + interval
+    }
+
+    pub fn get_job(&self, id: u32) -> Option<KeeperJob> {
+        if !self.job_ids.get_or_default().contains(&id) {
+            return None;
+        }
+
+        Some(KeeperJob {
+            id,
+            target: self.job_targets.get(&id)?,
+            entrypoint: self.job_entrypoints.get(&id).unwrap_or(0),
+            min_interval: self.job_intervals.get(&id).unwrap_or(0),
+            last_run: self.job_last_run.get(&id).unwrap_or(0),
+        })
+    }
+
+    pub fn get_job_ids(&self) -> Vec<u32> {
+        self.job_ids.get_or_default()
+    }
+
+    /// Grant a role on the hub (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+
+    /// Revoke a role on the hub (admin only)
+    pub fn revoke_role(&mut self, role: u8, account: Address) {
+        self.access_control.revoke_role(role, account);
+    }
+
+    /// Current members of a role
+    pub fn get_role_members(&self, role: u8) -> Vec<Address> {
+        self.access_control.get_role_members(role)
+    }
+}
+
+impl KeeperHub {
+    /// Entrypoint label for job events
+    fn entrypoint_name(entrypoint: u8) -> String {
+        match entrypoint {
+            JOB_COMPOUND_REWARDS => String::from("compound_rewards"),
+            JOB_HARVEST_ALL => String::from("harvest_all"),
+            JOB_COLLECT_MANAGEMENT_FEES => String::from("collect_management_fees"),
+            JOB_REBALANCE => String::from("rebalance"),
+            JOB_HEALTH_CHECK => String::from("health_check"),
+            _ => String::from("unknown"),
+        }
+    }
+}
+
+/// Emitted for every job attempted by `poke()`
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct JobExecuted {
+    pub job: u32,
+    pub success: bool,
+    pub gas_note: String,
+}
@@ -3,29 +3,108 @@ use odra::Event;
 use odra::{Address, Mapping, SubModule, Var};
 use odra::casper_types::{U256, U512};
 use crate::types::*;
-use crate::utils::{AccessControl, ValidatorRegistry};
+use crate::utils::{accrue_apr, bps_of, ratio_bps, safe_u256_to_u512, safe_u512_to_u256, safe_u512_to_u64};
+use crate::utils::{
+    feature_flags, AccessControl, ApprovalsRequired, BuildInfo, Role, ValidatorAdapterContractRef, ValidatorMetrics, ValidatorRegistry,
+    ValidatorRewards,
+};
+use crate::core::keeper_hub::JOB_COMPOUND_REWARDS;
+use crate::core::vault_manager::RequestStatus;
+use crate::tokens::lst_cspr::LstCsprContractRef;
 
-/// Delegation tracking for unbonding
+/// Delegation tracking for unbonding, stored as one record per request
 #[derive(Debug, PartialEq, Eq, odra::OdraType)]
 pub struct UnbondingRequest {
     pub user: Address,
     pub validator: Address,
     pub amount: U512,
+    /// When the request was made (for the era-based minimum in `complete_unbonding`)
+    pub request_time: u64,
     pub unlock_time: u64,
-    pub is_completed: bool,
+    pub status: RequestStatus,
 }
 
-/// Validator information
+/// Feature flags reported by `get_features`, one per shipped capability
+pub const LIQUID_STAKING_FEATURES: &[&str] = &[
+    "exchange_rate_sync",
+    "keeper_jobs",
+    "insurance_fund",
+    "validator_adapter",
+    "network_share_limits",
+    "emergency_exit",
+    "withdrawal_freeze",
+    "multisig_approvals",
+    "validator_guards",
+];
+
+/// Eras Casper holds undelegated stake before releasing it
+pub const UNBONDING_DELAY_ERAS: u64 = 7;
+
+/// Era length on mainnet (2 hours)
+pub const DEFAULT_ERA_DURATION: u64 = 2 * 60 * 60;
+
+/// Bounds on the configurable era length (networks differ)
+pub const MIN_ERA_DURATION: u64 = 30 * 60;
+pub const MAX_ERA_DURATION: u64 = 24 * 60 * 60;
+
+/// Longest configurable unbonding period (30 days)
+pub const MAX_UNBONDING_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// Unbonding period in both representations (see `get_unbonding_info`)
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct UnbondingInfo {
+    /// Period new requests are locked for, in seconds
+    pub period_seconds: u64,
+    /// The same period in eras, rounded up
+    pub period_eras: u64,
+    pub era_duration: u64,
+    /// Shortest period the chain allows (`UNBONDING_DELAY_ERAS` eras)
+    pub min_period_seconds: u64,
+    /// When a request made now could first complete
+    pub earliest_completion: u64,
+}
+
+/// What one address has put through `stake` and `unstake`
+///
+/// lstCSPR moved between holders outside this contract is attributed to
+/// whoever unstakes it, so `lst_cspr` never goes below zero.
+#[derive(Debug, Default, PartialEq, Eq, odra::OdraType)]
+pub struct StakerPosition {
+    /// lstCSPR minted to the staker less lstCSPR they unstaked
+    pub lst_cspr: U512,
+    /// Lifetime CSPR staked
+    pub cspr_staked: U512,
+    /// Lifetime CSPR released by unstaking
+    pub cspr_unstaked: U512,
+    pub first_stake_time: u64,
+}
+
+/// A staker's position valued at the current exchange rate (see `get_staker_info`)
 #[derive(Debug, PartialEq, Eq, odra::OdraType)]
-pub struct ValidatorInfo {
-    pub uptime_percentage: u8,
-    pub commission_rate: u8,
-    pub current_stake: U512,
-    pub max_stake_cap: U512,
-    pub is_verified: bool,
-    pub risk_score: u8,
-    pub staked: U512,
-    pub performance: U512,
+pub struct StakerInfo {
+    pub lst_cspr: U512,
+    pub cspr_staked: U512,
+    pub cspr_unstaked: U512,
+    /// `lst_cspr` at the current exchange rate
+    pub current_value: U512,
+    /// Value plus unstaked less staked; negative results (slashing) read as zero
+    pub rewards_earned: U512,
+    pub first_stake_time: u64,
+    /// The staker is the vault rather than a direct staker
+    pub is_vault: bool,
+}
+
+/// Rewards left after a validator's `commission` (%)
+fn net_of_commission(gross: U512, commission: u8) -> U512 {
+    gross * U512::from(100 - commission.min(100)) / U512::from(100u64)
+}
+
+/// Rewards before a validator's `commission` (%), from what it paid out
+fn gross_of_commission(net: U512, commission: u8) -> U512 {
+    if commission >= 100 {
+        return net;
+    }
+    net * U512::from(100u64) / U512::from(100 - commission)
 }
 
 /// LiquidStaking contract
@@ -43,6 +122,9 @@ pub struct ValidatorInfo {
 /// - Initially 1 lstCSPR = 1 CSPR
 /// - As rewards compound, 1 lstCSPR becomes worth more CSPR
 /// - Rate = total_staked_cspr / total_lst_cspr_supply
+/// - CSPR that reaches the backing outside `stake` (donations, direct
+///   delegations) is ignored until `sync_exchange_rate`, which unlocks it
+///   into the rate gradually
 /// - Example: If 100 CSPR staked earns 10 CSPR rewards:
 ///   - 100 lstCSPR now represents 110 CSPR
 ///   - Rate = 110/100 = 1.1 CSPR per lstCSPR
@@ -51,9 +133,15 @@ pub struct LiquidStaking {
     /// Access control
     access_control: SubModule<AccessControl>,
     
+    /// Version and feature flags for front-ends
+    build_info: SubModule<BuildInfo>,
+    
     /// Validator registry
     validator_registry: SubModule<ValidatorRegistry>,
     
+    /// M-of-N approvals for emergency actions
+    approvals: SubModule<ApprovalsRequired>,
+    
     /// lstCSPR token contract address
     lst_cspr_token: Var<Address>,
     
@@ -66,19 +154,27 @@ pub struct LiquidStaking {
     /// Delegation amounts per validator
     delegations: Mapping<Address, U512>,
     
-    /// Unbonding requests - flattened (request_id -> fields)
-    unbonding_request_users: Mapping<U256, Address>,
-    unbonding_request_validators: Mapping<U256, Address>,
-    unbonding_request_amounts: Mapping<U256, U512>,
-    unbonding_request_unlock_times: Mapping<U256, u64>,
-    unbonding_request_completed: Mapping<U256, bool>,
+    /// Unbonding requests, one packed record per id
+    unbonding_requests: Mapping<u64, UnbondingRequest>,
+    
+    /// Next unbonding request id
+    next_unbonding_id: Var<u64>,
     
-    /// Next unbonding request ID
-    next_unbonding_id: Var<U256>,
+    /// Open unbonding request IDs per user (pruned on completion)
+    user_unbonding_requests: Mapping<Address, Vec<u64>>,
+    
+    /// CSPR in unbonding requests not yet completed
+    pending_unbonding_total: Var<U512>,
     
     /// Unbonding period (in seconds) - 14 days for Casper
     unbonding_period: Var<u64>,
     
+    /// `unbonding_period` in eras of `era_duration`, rounded up
+    unbonding_eras: Var<u64>,
+    
+    /// Length of a network era in seconds
+    era_duration: Var<u64>,
+    
     /// Exchange rate (lstCSPR to CSPR), scaled by 1e9
     /// Represents how much CSPR one lstCSPR is worth
     exchange_rate: Var<U256>,
@@ -86,23 +182,74 @@ pub struct LiquidStaking {
     /// Last compound timestamp
     last_compound: Var<u64>,
     
+    /// First stake timestamp (reward accrual start before the first compound)
+    first_stake_time: Var<u64>,
+    
     /// Minimum compound interval (prevent excessive gas costs)
     min_compound_interval: Var<u64>,
     
-    /// Total rewards earned (for analytics)
+    /// Total rewards earned, net of commission (for analytics)
     total_rewards_earned: Var<U512>,
     
-    /// Validator information mapping
-    validators: Mapping<Address, ValidatorInfo>,
+    /// Validator commission on compounded rewards (lifetime)
+    total_commission_paid: Var<U512>,
+    
+    /// Delegation backend (unset = internal bookkeeping with estimated rewards)
+    validator_adapter: Var<Address>,
+    
+    /// CSPR `stake` has put in the contract purse
+    accounted_balance: Var<U512>,
+    
+    /// Synced surplus still unlocking into the rate (as of `locked_surplus_time`)
+    locked_surplus: Var<U512>,
+    
+    /// When `locked_surplus` was last updated
+    locked_surplus_time: Var<u64>,
+    
+    /// Time over which a synced surplus unlocks
+    surplus_unlock_duration: Var<u64>,
+    
+    /// Largest deficit (bps of total staked) a sync writes off unconfirmed
+    max_sync_deficit_bps: Var<u32>,
+    
+    /// Deficit found by the last sync, awaiting `confirm_sync_deficit`
+    pending_deficit: Var<U512>,
+    
+    /// CSPR set aside to cover slashing before it reaches the rate
+    insurance_fund: Var<U512>,
+    
+    /// Fund size above which the admin may withdraw the excess
+    insurance_target: Var<U512>,
+    
+    /// Account allowed to credit its performance fee slice to the fund
+    insurance_fee_source: Var<Address>,
     
-    /// Active validators list
-    active_validators: Var<Vec<Address>>,
+    /// Excess withdrawal awaiting its timelock
+    insurance_withdrawal_amount: Var<U512>,
+    insurance_withdrawal_unlock_time: Var<u64>,
     
-    /// Minimum uptime requirement
-    min_uptime: Var<u8>,
+    /// Delay between requesting and executing an excess withdrawal
+    insurance_withdrawal_delay: Var<u64>,
     
-    /// Maximum commission rate
-    max_commission: Var<u8>,
+    /// Blocks unbonding completions during a catastrophic exploit
+    withdrawals_frozen: Var<bool>,
+    
+    /// Undelegated CSPR an emergency exit found no validator for; still
+    /// part of total staked and paid out first on unstake
+    liquidity_buffer: Var<U512>,
+    
+    // ============================================
+    // STAKER TRACKING
+    // ============================================
+    
+    /// The vault, whose staking is reported apart from direct stakers
+    vault_address: Var<Address>,
+    
+    /// Per-address stake and unstake history
+    staker_positions: Mapping<Address, StakerPosition>,
+    
+    /// Addresses that have ever staked
+    staker_count: Var<u64>,
 }
 
 #[odra::module]
@@ -110,6 +257,7 @@ impl LiquidStaking {
     /// Initialize the LiquidStaking contract
     pub fn init(&mut self, admin: Address, lst_cspr_token: Address) {
         self.access_control.init(admin);
+        self.build_info.init(feature_flags(LIQUID_STAKING_FEATURES));
         self.validator_registry.init();
         self.lst_cspr_token.set(lst_cspr_token);
         
@@ -120,14 +268,20 @@ impl LiquidStaking {
         self.exchange_rate.set(U256::from(1_000_000_000u64));
         
         // Set unbonding period to 14 days (Casper Network)
-        self.unbonding_period.set(14 * 24 * 60 * 60);
+        self.era_duration.set(DEFAULT_ERA_DURATION);
+        self.store_unbonding_period(14 * 24 * 60 * 60);
         
         // Set minimum compound interval to 12 hours
         self.min_compound_interval.set(12 * 60 * 60);
         
-        self.next_unbonding_id.set(U256::zero());
+        self.next_unbonding_id.set(0);
         self.last_compound.set(0);
         self.total_rewards_earned.set(U512::zero());
+        
+        self.surplus_unlock_duration.set(24 * 60 * 60);
+        self.max_sync_deficit_bps.set(50); // 0.5%
+        
+        self.insurance_withdrawal_delay.set(2 * 24 * 60 * 60);
     }
 
     /// Stake CSPR and mint lstCSPR
@@ -140,6 +294,7 @@ impl LiquidStaking {
     /// 5. Mint lstCSPR to caller
     /// 
     /// Returns: Amount of lstCSPR minted
+    #[odra(payable)]
     pub fn stake(&mut self) -> U512 {
         let amount = self.env().attached_value();
         
@@ -176,12 +331,25 @@ impl LiquidStaking {
         let current_lst_cspr = self.total_lst_cspr.get_or_default();
         self.total_lst_cspr.set(current_lst_cspr + lst_cspr_amount);
         
+        let accounted = self.accounted_balance.get_or_default();
+        self.accounted_balance.set(accounted + amount);
+        
+        if self.first_stake_time.get_or_default() == 0 {
+            self.first_stake_time.set(self.env().get_block_time());
+        }
+        
+        self.record_stake(caller, amount, lst_cspr_amount);
+        
         // Mint lstCSPR tokens to caller
+        if let Some(token) = self.lst_token_contract() {
+            LstCsprContractRef::new(self.env(), token).mint(caller, lst_cspr_amount);
+        }
         
         self.env().emit_event(Stake {
             user: caller,
             cspr_amount: total_delegated,
             lst_cspr_minted: lst_cspr_amount,
+            via_vault: self.is_vault(caller),
             timestamp: self.env().get_block_time(),
         });
         
@@ -200,7 +368,7 @@ impl LiquidStaking {
     /// 4. Create unbonding request with 14-day lock
     /// 
     /// Returns: Unbonding request ID
-    pub fn unstake(&mut self, lst_cspr_amount: U512) -> U256 {
+    pub fn unstake(&mut self, lst_cspr_amount: U512) -> u64 {
         if lst_cspr_amount.is_zero() {
             self.env().revert(StakingError::ExceedsStakedAmount);
         }
@@ -215,9 +383,15 @@ impl LiquidStaking {
         }
         
         // Burn lstCSPR from caller
+        if let Some(token) = self.lst_token_contract() {
+            LstCsprContractRef::new(self.env(), token).burn(caller, lst_cspr_amount);
+        }
         
-        // Undelegate from validators proportionally
-        self.undelegate_proportionally(cspr_amount);
+        // Buffered CSPR pays first, validators proportionally for the rest
+        let buffer = self.liquidity_buffer.get_or_default();
+        let from_buffer = buffer.min(cspr_amount);
+        self.liquidity_buffer.set(buffer - from_buffer);
+        self.undelegate_proportionally(cspr_amount - from_buffer);
         
         self.total_staked.set(total_staked - cspr_amount);
         
@@ -229,18 +403,31 @@ impl LiquidStaking {
         let unlock_time = self.env().get_block_time() + self.unbonding_period.get_or_default();
         let placeholder_validator = self.env().caller();
         
-        self.unbonding_request_users.set(&request_id, caller);
-        self.unbonding_request_validators.set(&request_id, placeholder_validator);
-        self.unbonding_request_amounts.set(&request_id, cspr_amount);
-        self.unbonding_request_unlock_times.set(&request_id, unlock_time);
-        self.unbonding_request_completed.set(&request_id, false);
+        self.unbonding_requests.set(&request_id, UnbondingRequest {
+            user: caller,
+            validator: placeholder_validator,
+            amount: cspr_amount,
+            request_time: self.env().get_block_time(),
+            unlock_time,
+            status: RequestStatus::Pending,
+        });
+        
+        self.next_unbonding_id.set(request_id + 1);
         
-        self.next_unbonding_id.set(request_id + U256::one());
+        let mut user_requests = self.user_unbonding_requests.get(&caller).unwrap_or_default();
+        user_requests.push(request_id);
+        self.user_unbonding_requests.set(&caller, user_requests);
+        
+        let pending = self.pending_unbonding_total.get_or_default();
+        self.pending_unbonding_total.set(pending + cspr_amount);
+        
+        self.record_unstake(caller, lst_cspr_amount, cspr_amount);
         
         self.env().emit_event(Unstake {
             user: caller,
             lst_cspr_amount,
             cspr_amount,
+            via_vault: self.is_vault(caller),
             timestamp: self.env().get_block_time(),
         });
         
@@ -249,29 +436,43 @@ impl LiquidStaking {
 
     /// Complete unbonding and receive CSPR
     /// 
-    /// Can only be called after unbonding period has passed
-    pub fn complete_unbonding(&mut self, request_id: U256) -> U512 {
-        let request_user = self.unbonding_request_users.get(&request_id)
-            .unwrap_or_else(|| self.env().revert(VaultError::WithdrawalRequestNotFound));
+    /// Can only be called after unbonding period has passed. Nothing but
+    /// `freeze_withdrawals` blocks a matured request. The unlock time fixed
+    /// at request time applies, and independently at least
+    /// `UNBONDING_DELAY_ERAS` eras must have passed since the request, so
+    /// no stored unlock time can beat the chain's own release.
+    pub fn complete_unbonding(&mut self, request_id: u64) -> U512 {
+        if self.withdrawals_frozen.get_or_default() {
+            self.env().revert(VaultError::WithdrawalsFrozen);
+        }
         
-        let request_amount = self.unbonding_request_amounts.get(&request_id).unwrap_or(U512::zero());
-        let request_unlock_time = self.unbonding_request_unlock_times.get(&request_id).unwrap_or(0);
-        let request_completed = self.unbonding_request_completed.get(&request_id).unwrap_or(false);
+        let mut request = self.unbonding_requests.get(&request_id)
+            .unwrap_or_else(|| self.env().revert(VaultError::WithdrawalRequestNotFound));
+        let request_user = request.user;
+        let request_amount = request.amount;
         
         if request_user != self.env().caller() {
             self.env().revert(VaultError::Unauthorized);
         }
         
-        if request_completed {
+        if !request.status.is_pending() {
             self.env().revert(VaultError::WithdrawalRequestNotFound);
         }
         
-        if self.env().get_block_time() < request_unlock_time {
+        let now = self.env().get_block_time();
+        if now < request.unlock_time || now < request.request_time + self.min_unbonding_period() {
             self.env().revert(VaultError::TimelockNotExpired);
         }
         
-        self.unbonding_request_completed.set(&request_id, true);
+        request.status = RequestStatus::Completed;
+        self.unbonding_requests.set(&request_id, request);
         
+        let mut user_requests = self.user_unbonding_requests.get(&request_user).unwrap_or_default();
+        user_requests.retain(|id| *id != request_id);
+        self.user_unbonding_requests.set(&request_user, user_requests);
+        
+        let pending = self.pending_unbonding_total.get_or_default();
+        self.pending_unbonding_total.set(pending.saturating_sub(request_amount));
         
         request_amount
     }
@@ -288,14 +489,12 @@ impl LiquidStaking {
     /// 3. Restake rewards to same validators
     /// 4. Update exchange rate
     /// 
-    /// Can only be called by Admin or Operator
+    /// Can only be called by Admin, Operator or Keeper
     /// Rate limited to prevent excessive gas costs
     /// 
     /// Returns: Total rewards compounded
     pub fn compound_rewards(&mut self) -> U512 {
-        if !self.access_control.has_role(0, self.env().caller())
-            && !self.access_control.has_role(1, self.env().caller())
-        {
+        if !self.access_control.is_maintainer(self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         
@@ -307,8 +506,131 @@ impl LiquidStaking {
             self.env().revert(VaultError::RateLimitExceeded);
         }
         
+        self.compound()
+    }
+
+    /// KeeperHub entry point: reward compounding
+    /// 
+    /// Returns `false` instead of reverting when the caller lacks the
+    /// keeper role, the compound interval has not elapsed or the
+    /// entrypoint is not handled by this contract.
+    pub fn run_keeper_job(&mut self, entrypoint: u8) -> bool {
+        if !self.access_control.has_role(Role::Keeper.to_u8(), self.env().caller()) {
+            return false;
+        }
+        
+        if entrypoint != JOB_COMPOUND_REWARDS {
+            return false;
+        }
+        
+        let last = self.last_compound.get_or_default();
+        let now = self.env().get_block_time();
+        if now < last + self.min_compound_interval.get_or_default() {
+            return false;
+        }
+        
+        self.compound();
+        true
+    }
+
+    /// Reconcile total staked with the actual backing (admin, operator or keeper)
+    /// 
+    /// Actual backing is the stake validators report (through the adapter,
+    /// or the tracked delegations without one) plus CSPR in the contract
+    /// purse beyond what `stake` put there.
+    /// 
+    /// A surplus is added to total staked but locked, unlocking into the
+    /// exchange rate over `surplus_unlock_duration`, so a donation right
+    /// before a large unstake doesn't move the rate. A deficit up to
+    /// `max_sync_deficit_bps` is written off, still-locked surplus absorbing
+    /// it first; a larger one is parked for `confirm_sync_deficit`.
+    pub fn sync_exchange_rate(&mut self) {
+        if !self.access_control.is_maintainer(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let (reported, actual) = self.actual_backing();
+        let tracked = self.total_staked.get_or_default();
+        
+        self.env().emit_event(ExchangeRateSynced {
+            tracked,
+            actual,
+            delta: if actual > tracked { actual - tracked } else { tracked - actual },
+            timestamp: self.env().get_block_time(),
+        });
+        
+        if actual >= tracked {
+            self.lock_surplus(actual - tracked);
+        } else {
+            let deficit = tracked - actual;
+            let tolerance = bps_of(tracked, self.max_sync_deficit_bps.get_or_default());
+            if deficit > tolerance {
+                self.pending_deficit.set(deficit);
+                return;
+            }
+            self.write_off_deficit(deficit);
+        }
+        
+        self.settle_backing(reported);
+    }
+
+    /// Write off the deficit a sync parked (admin only)
+    /// 
+    /// The deficit is measured again, so whatever was recovered since the
+    /// sync is not written off.
+    pub fn confirm_sync_deficit(&mut self) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        if self.pending_deficit.get_or_default().is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let (reported, actual) = self.actual_backing();
+        let tracked = self.total_staked.get_or_default();
+        
+        self.env().emit_event(ExchangeRateSynced {
+            tracked,
+            actual,
+            delta: tracked.saturating_sub(actual),
+            timestamp: self.env().get_block_time(),
+        });
+        
+        self.write_off_deficit(tracked.saturating_sub(actual));
+        self.settle_backing(reported);
+    }
+
+    /// Grant a role on the staking contract (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+
+    /// Revoke a role on the staking contract (admin only)
+    pub fn revoke_role(&mut self, role: u8, account: Address) {
+        self.access_control.revoke_role(role, account);
+    }
+
+    /// Current members of a role
+    pub fn get_role_members(&self, role: u8) -> Vec<Address> {
+        self.access_control.get_role_members(role)
+    }
+
+    /// Claim and restake rewards from every active validator
+    /// 
+    /// Only rewards net of the commission recorded in the validator registry
+    /// are restaked and credited. With an adapter, rewards are what the
+    /// adapter pays out (validators keep their commission before paying)
+    /// and any stake it no longer reports (slashing) is covered from the
+    /// insurance fund, the rest written off against total_staked; without
+    /// one, commission is taken off the estimate.
+    fn compound(&mut self) -> U512 {
+        let now = self.env().get_block_time();
+        let mut total_gross = U512::zero();
         let mut total_rewards = U512::zero();
+        let mut total_slashed = U512::zero();
         let active_validators = self.validator_registry.get_active_validators();
+        let adapter = self.validator_adapter.get();
         
         for validator in active_validators.iter() {
             let delegation = self.delegations.get(validator).unwrap_or(U512::zero());
@@ -317,23 +639,42 @@ impl LiquidStaking {
                 continue;
             }
             
-            // For simulation: assume rewards are proportional to stake
-            let rewards = self.calculate_estimated_rewards(*validator, delegation);
+            let commission = self.validator_registry.get_commission(*validator);
+            let (gross, rewards) = match adapter {
+                Some(adapter) => {
+                    let mut adapter_ref = ValidatorAdapterContractRef::new(self.env(), adapter);
+                    let rewards = adapter_ref.claim_rewards(*validator);
+                    
+                    let remaining = adapter_ref.get_delegated(*validator);
+                    if remaining < delegation {
+                        self.record_slashing(*validator, delegation - remaining, remaining);
+                        total_slashed += self.cover_slashing(*validator, delegation - remaining);
+                    }
+                    (gross_of_commission(rewards, commission), rewards)
+                },
+                // For simulation: assume rewards are proportional to stake
+                None => {
+                    let gross = self.calculate_estimated_rewards(*validator, delegation);
+                    (gross, net_of_commission(gross, commission))
+                },
+            };
             
             if rewards > U512::zero() {
+                total_gross += gross.max(rewards);
                 total_rewards += rewards;
                 
                 // Restake rewards to same validator
                 self.delegate_to_validator(*validator, rewards);
+                self.validator_registry.record_rewards(*validator, rewards, now);
             }
         }
         
-        if total_rewards.is_zero() {
+        if total_rewards.is_zero() && total_slashed.is_zero() {
             return total_rewards;
         }
         
         let current_staked = self.total_staked.get_or_default();
-        let new_total_staked = current_staked + total_rewards;
+        let new_total_staked = (current_staked + total_rewards).saturating_sub(total_slashed);
         self.total_staked.set(new_total_staked);
         
         self.update_exchange_rate();
@@ -343,7 +684,11 @@ impl LiquidStaking {
         let total_earned = self.total_rewards_earned.get_or_default();
         self.total_rewards_earned.set(total_earned + total_rewards);
         
+        let commission_paid = self.total_commission_paid.get_or_default();
+        self.total_commission_paid.set(commission_paid + (total_gross - total_rewards));
+        
         self.env().emit_event(CompoundRewards {
+            gross_rewards: total_gross,
             total_rewards,
             restaked_amount: total_rewards,
             new_total_staked,
@@ -353,12 +698,20 @@ impl LiquidStaking {
         total_rewards
     }
 
+    /// The lstCSPR token, if it is a deployed contract rather than the
+    /// placeholder account it starts as
+    fn lst_token_contract(&self) -> Option<Address> {
+        self.lst_cspr_token.get().filter(|token| token.is_contract())
+    }
+
     /// Delegate CSPR to a specific validator
     /// 
-    /// In production, this would call Casper's native delegation system.
-    /// For MVP, we track delegations in state.
+    /// Routed through the validator adapter when one is set; delegations are
+    /// tracked in state either way.
     fn delegate_to_validator(&mut self, validator: Address, amount: U512) {
-        // system::delegate(validator, amount);
+        if let Some(adapter) = self.validator_adapter.get() {
+            ValidatorAdapterContractRef::new(self.env(), adapter).delegate(validator, amount);
+        }
         
         let current_delegation = self.delegations.get(&validator).unwrap_or(U512::zero());
         self.delegations.set(&validator, current_delegation + amount);
@@ -371,50 +724,66 @@ impl LiquidStaking {
 
     /// Undelegate from validators proportionally
     /// 
-    /// Distributes the withdrawal amount across all validators
-    /// based on their current delegation amounts
+    /// Each validator's share of `total_amount` follows its delegation,
+    /// rounded down; the last funded validator takes the exact residual.
+    /// Anything a share can't cover (capped at the delegation) is taken
+    /// from validators with delegation to spare. Reverts with
+    /// `InsufficientDelegatedBalance` before undelegating anything if the
+    /// delegations can't cover `total_amount`.
     fn undelegate_proportionally(&mut self, total_amount: U512) {
-        let active_validators = self.validator_registry.get_active_validators();
-        let total_delegated = self.validator_registry.get_total_stake();
-        
-        if total_delegated.is_zero() {
-            return;
+        let funded: Vec<(Address, U512)> = self.validator_registry.get_active_validators()
+            .into_iter()
+            .map(|validator| (validator, self.delegations.get(&validator).unwrap_or(U512::zero())))
+            .filter(|(_, delegation)| !delegation.is_zero())
+            .collect();
+        let total_delegated = funded.iter().fold(U512::zero(), |sum, (_, delegation)| sum + *delegation);
+        
+        if total_amount > total_delegated {
+            self.env().revert(StakingError::InsufficientDelegatedBalance);
         }
         
         let mut remaining = total_amount;
+        let mut planned: Vec<U512> = Vec::new();
         
-        for validator in active_validators.iter() {
+        for (i, (_, delegation)) in funded.iter().enumerate() {
+            let share = if i + 1 == funded.len() {
+                remaining
+            } else {
+                (*delegation * total_amount) / total_delegated
+            };
+            let amount = share.min(*delegation).min(remaining);
+            planned.push(amount);
+            remaining -= amount;
+        }
+        
+        // Residual capped on the last validator goes to whoever has room
+        for (i, (_, delegation)) in funded.iter().enumerate() {
             if remaining.is_zero() {
                 break;
             }
-            
-            let delegation = self.delegations.get(validator).unwrap_or(U512::zero());
-            
-            if delegation.is_zero() {
-                continue;
-            }
-            
-            // Calculate proportion using U512 for precision
-            let proportion_512 = (delegation * U512::from(1_000_000u64) * total_amount) / (total_delegated * U512::from(1_000_000u64));
-            let undelegate_amount = proportion_512;
-            
-            if undelegate_amount > remaining {
-                let actual_amount = remaining;
-                self.undelegate_from_validator(*validator, actual_amount);
-                remaining = U512::zero();
-            } else {
-                self.undelegate_from_validator(*validator, undelegate_amount);
-                remaining -= undelegate_amount;
+            let extra = (*delegation - planned[i]).min(remaining);
+            planned[i] += extra;
+            remaining -= extra;
+        }
+        
+        for ((validator, _), amount) in funded.iter().zip(planned.into_iter()) {
+            if !amount.is_zero() {
+                self.undelegate_from_validator(*validator, amount);
             }
         }
     }
 
     /// Undelegate from a specific validator
     fn undelegate_from_validator(&mut self, validator: Address, amount: U512) {
-        // system::undelegate(validator, amount);
-        
         let current_delegation = self.delegations.get(&validator).unwrap_or(U512::zero());
         
+        if let Some(adapter) = self.validator_adapter.get() {
+            let amount = amount.min(current_delegation);
+            if !amount.is_zero() {
+                ValidatorAdapterContractRef::new(self.env(), adapter).undelegate(validator, amount);
+            }
+        }
+        
         if amount >= current_delegation {
             self.delegations.set(&validator, U512::zero());
         } else {
@@ -430,45 +799,152 @@ impl LiquidStaking {
         let _ = self.validator_registry.update_validator_stake(validator, new_stake);
     }
 
+    /// Write down a validator's delegation to what the adapter still reports
+    fn record_slashing(&mut self, validator: Address, amount: U512, remaining: U512) {
+        self.delegations.set(&validator, remaining);
+        let _ = self.validator_registry.update_validator_stake(validator, remaining);
+        
+        self.env().emit_event(ValidatorSlashed {
+            validator,
+            amount,
+            remaining_stake: remaining,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Restore slashed stake from the insurance fund
+    /// 
+    /// The fund's CSPR is already in the contract purse, so the covered part
+    /// is delegated back to the validator the same way `stake` delegates.
+    /// Returns the uncovered loss.
+    fn cover_slashing(&mut self, validator: Address, amount: U512) -> U512 {
+        let fund = self.insurance_fund.get_or_default();
+        let covered = amount.min(fund);
+        if covered.is_zero() {
+            return amount;
+        }
+        
+        self.insurance_fund.set(fund - covered);
+        self.delegate_to_validator(validator, covered);
+        
+        self.env().emit_event(InsurancePayout {
+            validator,
+            covered,
+            uncovered: amount - covered,
+            fund_balance: fund - covered,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        amount - covered
+    }
+
+    /// Add to the insurance fund and emit `InsuranceFunded`
+    fn add_to_insurance(&mut self, amount: U512) {
+        let fund = self.insurance_fund.get_or_default() + amount;
+        self.insurance_fund.set(fund);
+        
+        self.env().emit_event(InsuranceFunded {
+            source: self.env().caller(),
+            amount,
+            fund_balance: fund,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
     /// Calculate estimated rewards for a validator
     /// 
     /// In production, this would query actual rewards from Casper runtime.
     /// For simulation: Estimates based on time and delegation amount.
     fn calculate_estimated_rewards(&self, _validator: Address, delegation: U512) -> U512 {
-        // rewards_per_year = delegation * 0.10
-        // rewards_per_day = rewards_per_year / 365
-        // For simulation, assume 1 day has passed
-        
-        let annual_rate = 10u64; // 10%
-        let days_per_year = 365u64;
-        
-        // rewards = delegation * (annual_rate / 100) / days_per_year
-        let rewards = (delegation * U512::from(annual_rate)) / U512::from(100 * days_per_year);
+        // For simulation, assume 1 day has passed at a 10% APR
+        let annual_rate_bps = 1000u32;
+        let one_day = 86400u64;
         
-        rewards
+        accrue_apr(delegation, annual_rate_bps, one_day).unwrap_or_revert(&self.env())
+    }
+
+    /// Whether `address` is a contract the protocol itself runs
+    ///
+    /// The registry rejects this contract's own address; the token, vault,
+    /// adapter and insurance fee source are only known here.
+    fn is_protocol_address(&self, address: &Address) -> bool {
+        [
+            self.lst_cspr_token.get(),
+            self.vault_address.get(),
+            self.validator_adapter.get(),
+            self.insurance_fee_source.get(),
+        ]
+        .contains(&Some(*address))
+    }
+
+    /// Emit `ConfigChanged` for one of this contract's parameters
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "LiquidStaking".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Length of a network era, defaulting to mainnet's
+    /// Book a stake to `staker`'s position, counting first-time stakers
+    fn record_stake(&mut self, staker: Address, cspr_amount: U512, lst_cspr_amount: U512) {
+        let mut position = self.staker_positions.get(&staker).unwrap_or_default();
+        if position.first_stake_time == 0 {
+            position.first_stake_time = self.env().get_block_time();
+            self.staker_count.set(self.staker_count.get_or_default() + 1);
+        }
+        position.lst_cspr += lst_cspr_amount;
+        position.cspr_staked += cspr_amount;
+        self.staker_positions.set(&staker, position);
+    }
+
+    fn record_unstake(&mut self, staker: Address, lst_cspr_amount: U512, cspr_amount: U512) {
+        let mut position = self.staker_positions.get(&staker).unwrap_or_default();
+        position.lst_cspr = position.lst_cspr.saturating_sub(lst_cspr_amount);
+        position.cspr_unstaked += cspr_amount;
+        self.staker_positions.set(&staker, position);
+    }
+
+    fn is_vault(&self, address: Address) -> bool {
+        self.vault_address.get() == Some(address)
+    }
+
+    fn era_duration(&self) -> u64 {
+        self.era_duration.get().unwrap_or(DEFAULT_ERA_DURATION)
+    }
+
+    /// Shortest unbonding the chain allows: `UNBONDING_DELAY_ERAS` eras
+    fn min_unbonding_period(&self) -> u64 {
+        UNBONDING_DELAY_ERAS * self.era_duration()
+    }
+
+    /// Store `period` along with its length in eras (rounded up)
+    fn store_unbonding_period(&mut self, period: u64) {
+        let era = self.era_duration();
+        self.unbonding_period.set(period);
+        self.unbonding_eras.set((period + era - 1) / era);
     }
 
     /// Convert CSPR to lstCSPR based on current exchange rate
+    /// 
+    /// Uses the exact staked/issued ratio; the stored 1e9 rate would
+    /// truncate once it drifts from 1.0.
     fn cspr_to_lst_cspr(&self, cspr_amount: U512) -> U512 {
-        let rate = self.exchange_rate.get_or_default();
-        let rate_512 = U512::from(rate.as_u64());
-        // lstCSPR = CSPR * 1e9 / rate
-        (cspr_amount * U512::from(1_000_000_000u64) / rate_512)
-            .checked_div(U512::from(1_000_000_000u64))
-            .unwrap_or(cspr_amount)
+        self.cspr_to_lst_cspr_precise(cspr_amount)
     }
 
     /// Convert lstCSPR to CSPR based on current exchange rate
     fn lst_cspr_to_cspr(&self, lst_cspr_amount: U512) -> U512 {
-        let rate = self.exchange_rate.get_or_default();
-        let rate_512 = U512::from(rate.as_u64());
-        // CSPR = lstCSPR * rate / 1e9
-        (lst_cspr_amount * rate_512 / U512::from(1_000_000_000u64))
+        self.lst_cspr_to_cspr_precise(lst_cspr_amount)
     }
 
-    /// Update exchange rate based on total staked and total lstCSPR
+    /// Update exchange rate based on unlocked stake and total lstCSPR
     fn update_exchange_rate(&mut self) {
-        let total_staked = self.total_staked.get_or_default();
+        let total_staked = self.backing();
         let total_lst_cspr = self.total_lst_cspr.get_or_default();
         
         if total_lst_cspr.is_zero() {
@@ -478,11 +954,85 @@ impl LiquidStaking {
         // rate = total_staked * 1e9 / total_lst_cspr
         // Calculate new rate using U512
         let new_rate_512 = total_staked * U512::from(1_000_000_000u64) / total_lst_cspr;
-        let new_rate = U256::from(new_rate_512.as_u64());
+        let new_rate = safe_u512_to_u256(new_rate_512).unwrap_or_revert(&self.env());
         self.exchange_rate.set(new_rate);
     }
 
+    /// Total staked less the synced surplus that is still unlocking
+    fn backing(&self) -> U512 {
+        self.total_staked.get_or_default().saturating_sub(self.get_locked_surplus())
+    }
+
+    /// Stake reported per active validator and the total actual backing
+    fn actual_backing(&self) -> (Vec<(Address, U512)>, U512) {
+        let adapter = self.validator_adapter.get();
+        let mut reported = Vec::new();
+        let mut total = U512::zero();
+        
+        for validator in self.validator_registry.get_active_validators().iter() {
+            let delegated = match adapter {
+                Some(adapter) => ValidatorAdapterContractRef::new(self.env(), adapter).get_delegated(*validator),
+                None => self.delegations.get(validator).unwrap_or(U512::zero()),
+            };
+            total += delegated;
+            reported.push((*validator, delegated));
+        }
+        
+        let purse_surplus = self.env().self_balance().saturating_sub(self.accounted_balance.get_or_default());
+        (reported, total + self.liquidity_buffer.get_or_default() + purse_surplus)
+    }
+
+    /// Add a surplus to total staked, locked
+    /// 
+    /// Still-locked surplus is carried over and restarts its unlock with
+    /// the new amount.
+    fn lock_surplus(&mut self, surplus: U512) {
+        if surplus.is_zero() {
+            return;
+        }
+        
+        let locked = self.get_locked_surplus() + surplus;
+        self.locked_surplus.set(locked);
+        self.locked_surplus_time.set(self.env().get_block_time());
+        
+        let total_staked = self.total_staked.get_or_default();
+        self.total_staked.set(total_staked + surplus);
+    }
+
+    /// Take a deficit off total staked, out of locked surplus first
+    fn write_off_deficit(&mut self, deficit: U512) {
+        let locked = self.get_locked_surplus();
+        self.locked_surplus.set(locked.saturating_sub(deficit));
+        self.locked_surplus_time.set(self.env().get_block_time());
+        
+        let total_staked = self.total_staked.get_or_default();
+        self.total_staked.set(total_staked.saturating_sub(deficit));
+    }
+
+    /// After a booked sync: tracked delegations and purse match what was read
+    fn settle_backing(&mut self, reported: Vec<(Address, U512)>) {
+        for (validator, delegated) in reported.into_iter() {
+            if self.delegations.get(&validator).unwrap_or(U512::zero()) != delegated {
+                self.delegations.set(&validator, delegated);
+                let _ = self.validator_registry.update_validator_stake(validator, delegated);
+            }
+        }
+        
+        let balance = self.env().self_balance();
+        if balance > self.accounted_balance.get_or_default() {
+            self.accounted_balance.set(balance);
+        }
+        
+        self.pending_deficit.set(U512::zero());
+        self.update_exchange_rate();
+    }
+
     /// Add a validator to the registry (admin only)
+    /// 
+    /// Eligibility bounds and the active set live in the registry, so the
+    /// validator is selectable by the next `stake`. Reverts with
+    /// `InvalidValidator` for one of the protocol's own contracts and with
+    /// `ValidatorAlreadyRegistered` for a known validator.
     pub fn add_validator(
         &mut self,
         validator: Address,
@@ -491,39 +1041,16 @@ impl LiquidStaking {
         max_stake_cap: U512,
     ) {
         self.access_control.only_admin();
-        
-        // Validate requirements
-        let min_uptime = self.min_uptime.get_or_default();
-        let max_commission = self.max_commission.get_or_default();
-        
-        if uptime_percentage < min_uptime {
-            self.env().revert(StakingError::ValidatorNotEligible);
-        }
-        
-        if commission_rate > max_commission {
-            self.env().revert(StakingError::ValidatorNotEligible);
+        if self.is_protocol_address(&validator) {
+            self.env().revert(StakingError::InvalidValidator);
         }
-        
-        // Create validator info
-        let validator_info = ValidatorInfo {
+        self.validator_registry.register_validator(
+            validator,
             uptime_percentage,
             commission_rate,
-            current_stake: U512::zero(),
             max_stake_cap,
-            is_verified: true,
-            risk_score: 0,
-            staked: U512::zero(),
-            performance: U512::zero(),
-        };
-        
-        self.validators.set(&validator, validator_info);
-        
-        // Add to active validators list
-        let mut active = self.active_validators.get_or_default();
-        if !active.contains(&validator) {
-            active.push(validator);
-            self.active_validators.set(active);
-        }
+            true,
+        );
         
         self.env().emit_event(ValidatorAdded {
             validator,
@@ -533,14 +1060,21 @@ impl LiquidStaking {
     }
 
     /// Remove a validator from the registry (admin only)
+    /// 
+    /// Its whole delegation is undelegated first, the same way
+    /// `emergency_undelegate` does, so no stake is left on a validator
+    /// the contract no longer tracks.
     pub fn remove_validator(&mut self, validator: Address, reason: String) {
         self.access_control.only_admin();
+        if self.validator_registry.get_validator_metrics(validator).is_none() {
+            self.env().revert(StakingError::ValidatorNotFound);
+        }
         
-        // Remove from active validators
-        let mut active = self.active_validators.get_or_default();
-        active.retain(|v| v != &validator);
-        self.active_validators.set(active);
-        
+        let delegation = self.delegations.get(&validator).unwrap_or(U512::zero());
+        if !delegation.is_zero() {
+            self.undelegate_from_validator(validator, delegation);
+        }
+        self.validator_registry.deregister_validator(validator, reason.clone());
         
         self.env().emit_event(ValidatorRemoved {
             validator,
@@ -553,6 +1087,46 @@ impl LiquidStaking {
         self.exchange_rate.get_or_default()
     }
 
+    /// CSPR per lstCSPR scaled by 1e18, from the live staked/issued totals
+    /// 
+    /// `get_exchange_rate` keeps 1e9 scaling for compatibility and is only
+    /// refreshed on compound.
+    pub fn get_exchange_rate_precise(&self) -> U256 {
+        let total_staked = self.backing();
+        let total_lst_cspr = self.total_lst_cspr.get_or_default();
+        let one = U512::from(1_000_000_000_000_000_000u64);
+        
+        if total_lst_cspr.is_zero() {
+            return U256::from(1_000_000_000_000_000_000u64);
+        }
+        
+        safe_u512_to_u256(total_staked * one / total_lst_cspr).unwrap_or_revert(&self.env())
+    }
+
+    /// lstCSPR minted for `cspr_amount`, single mul-div on the live totals
+    pub fn cspr_to_lst_cspr_precise(&self, cspr_amount: U512) -> U512 {
+        let total_staked = self.backing();
+        let total_lst_cspr = self.total_lst_cspr.get_or_default();
+        
+        if total_staked.is_zero() || total_lst_cspr.is_zero() {
+            return cspr_amount;
+        }
+        
+        cspr_amount * total_lst_cspr / total_staked
+    }
+
+    /// CSPR redeemed for `lst_cspr_amount`, single mul-div on the live totals
+    pub fn lst_cspr_to_cspr_precise(&self, lst_cspr_amount: U512) -> U512 {
+        let total_staked = self.backing();
+        let total_lst_cspr = self.total_lst_cspr.get_or_default();
+        
+        if total_staked.is_zero() || total_lst_cspr.is_zero() {
+            return lst_cspr_amount;
+        }
+        
+        lst_cspr_amount * total_staked / total_lst_cspr
+    }
+
     /// Get total staked CSPR
     pub fn get_total_staked(&self) -> U512 {
         self.total_staked.get_or_default()
@@ -563,41 +1137,173 @@ impl LiquidStaking {
         self.total_lst_cspr.get_or_default()
     }
 
-    /// Get validator info
-    pub fn get_validator(&self, validator: Address) -> Option<ValidatorInfo> {
-        self.validators.get(&validator)
+    /// A staker's position and the rewards it has earned
+    /// 
+    /// Rewards come from the exchange rate alone: what the remaining
+    /// lstCSPR is worth now plus what was unstaked, less what was staked.
+    pub fn get_staker_info(&self, staker: Address) -> Option<StakerInfo> {
+        let position = self.staker_positions.get(&staker)?;
+        let current_value = self.lst_cspr_to_cspr(position.lst_cspr);
+        let rewards_earned = (current_value + position.cspr_unstaked).saturating_sub(position.cspr_staked);
+        Some(StakerInfo {
+            lst_cspr: position.lst_cspr,
+            cspr_staked: position.cspr_staked,
+            cspr_unstaked: position.cspr_unstaked,
+            current_value,
+            rewards_earned,
+            first_stake_time: position.first_stake_time,
+            is_vault: self.is_vault(staker),
+        })
     }
 
-    /// Get all active validators
-    pub fn get_active_validators(&self) -> Vec<Address> {
-        self.active_validators.get_or_default()
+    /// Number of distinct addresses that have ever staked, the vault included
+    pub fn get_staker_count(&self) -> u64 {
+        self.staker_count.get_or_default()
     }
 
-    /// Get delegation amount for a validator
-    pub fn get_delegation(&self, validator: Address) -> U512 {
-        self.delegations.get(&validator).unwrap_or(U512::zero())
+    /// Staked CSPR split by origin: (vault, direct stakers)
+    /// 
+    /// The vault's share is its lstCSPR at the current exchange rate; the
+    /// rest of total staked belongs to direct stakers.
+    pub fn get_stake_by_origin(&self) -> (U512, U512) {
+        let vault_value = self
+            .vault_address
+            .get()
+            .and_then(|vault| self.staker_positions.get(&vault))
+            .map(|position| self.lst_cspr_to_cspr(position.lst_cspr))
+            .unwrap_or_default();
+        let total = self.backing();
+        let vault_value = vault_value.min(total);
+        (vault_value, total - vault_value)
     }
 
-    /// Get total rewards earned (lifetime)
-    pub fn get_total_rewards_earned(&self) -> U512 {
-        self.total_rewards_earned.get_or_default()
+    /// Synced surplus that has not yet unlocked into the exchange rate
+    pub fn get_locked_surplus(&self) -> U512 {
+        let locked = self.locked_surplus.get_or_default();
+        let duration = self.surplus_unlock_duration.get_or_default();
+        if locked.is_zero() || duration == 0 {
+            return U512::zero();
+        }
+        
+        let elapsed = self.env().get_block_time().saturating_sub(self.locked_surplus_time.get_or_default());
+        if elapsed >= duration {
+            return U512::zero();
+        }
+        
+        locked * U512::from(duration - elapsed) / U512::from(duration)
     }
 
-    /// Get unbonding request details
-    pub fn get_unbonding_request(&self, request_id: U256) -> Option<UnbondingRequest> {
-        if let Some(user) = self.unbonding_request_users.get(&request_id) {
-            Some(UnbondingRequest {
-                user,
-                validator: self.unbonding_request_validators.get(&request_id).unwrap_or(user),
-                amount: self.unbonding_request_amounts.get(&request_id).unwrap_or(U512::zero()),
-                unlock_time: self.unbonding_request_unlock_times.get(&request_id).unwrap_or(0),
-                is_completed: self.unbonding_request_completed.get(&request_id).unwrap_or(false),
-            })
-        } else {
-            None
+    /// Deficit awaiting `confirm_sync_deficit` (zero if none)
+    pub fn get_pending_deficit(&self) -> U512 {
+        self.pending_deficit.get_or_default()
+    }
+
+    /// (tracked, actual) backing as `sync_exchange_rate` would see it now
+    pub fn get_backing(&self) -> (U512, U512) {
+        (self.total_staked.get_or_default(), self.actual_backing().1)
+    }
+
+    /// Registry metrics of a validator
+    pub fn get_validator(&self, validator: Address) -> Option<ValidatorMetrics> {
+        self.validator_registry.get_validator_metrics(validator)
+    }
+
+    /// Whether `validator` is in the registry's active set
+    pub fn validator_exists(&self, validator: Address) -> bool {
+        self.validator_registry.validator_exists(validator)
+    }
+
+    /// Validators in the registry's active set
+    pub fn get_active_validators(&self) -> Vec<Address> {
+        self.validator_registry.get_active_validators()
+    }
+
+    /// Get delegation amount for a validator
+    pub fn get_delegation(&self, validator: Address) -> U512 {
+        self.delegations.get(&validator).unwrap_or(U512::zero())
+    }
+
+    /// Get total rewards earned, net of commission (lifetime)
+    pub fn get_total_rewards_earned(&self) -> U512 {
+        self.total_rewards_earned.get_or_default()
+    }
+
+    /// Validator commission on compounded rewards (lifetime)
+    pub fn get_total_commission_paid(&self) -> U512 {
+        self.total_commission_paid.get_or_default()
+    }
+
+    /// Net rewards a validator earned for the protocol: lifetime and recent
+    pub fn get_validator_rewards(&self, validator: Address) -> ValidatorRewards {
+        self.validator_registry.get_validator_rewards(validator)
+    }
+
+    /// Validator's share of total network stake used by selection (bps, 0 if unreported or stale)
+    pub fn get_validator_network_share(&self, validator: Address) -> u32 {
+        self.validator_registry.get_network_share(validator)
+    }
+
+    /// Selection score of a registered validator (higher is picked first)
+    pub fn get_validator_score(&self, validator: Address) -> Option<u64> {
+        self.validator_registry.get_selection_score(validator)
+    }
+
+    /// Active validators with the highest lifetime net rewards (k capped at 10)
+    pub fn get_top_validators_by_rewards(&self, k: u32) -> Vec<(Address, U512)> {
+        self.validator_registry.get_top_validators_by_rewards(k)
+    }
+
+    /// Get the lstCSPR token this contract mints
+    pub fn get_lst_cspr_token(&self) -> Option<Address> {
+        self.lst_cspr_token.get()
+    }
+
+    /// Get unbonding request details
+    pub fn get_unbonding_request(&self, request_id: u64) -> Option<UnbondingRequest> {
+        self.unbonding_requests.get(&request_id)
+    }
+
+    /// Open unbonding request IDs of `user`, oldest first
+    pub fn get_user_unbonding_requests(&self, user: Address) -> Vec<u64> {
+        self.user_unbonding_requests.get(&user).unwrap_or_default()
+    }
+
+    /// Open requests of `user` that `complete_unbonding` accepts now
+    pub fn get_claimable_requests(&self, user: Address) -> Vec<u64> {
+        let now = self.env().get_block_time();
+        self.get_user_unbonding_requests(user)
+            .into_iter()
+            .filter(|id| self.unbonding_requests.get(id).is_some_and(|request| request.unlock_time <= now))
+            .collect()
+    }
+
+    /// CSPR in unbonding requests not yet completed
+    pub fn get_pending_unbonding_total(&self) -> U512 {
+        self.pending_unbonding_total.get_or_default()
+    }
+
+    /// Unbonding period in seconds and eras, and when a new request unlocks
+    pub fn get_unbonding_info(&self) -> UnbondingInfo {
+        let period_seconds = self.unbonding_period.get_or_default();
+        let min_period_seconds = self.min_unbonding_period();
+        UnbondingInfo {
+            period_seconds,
+            period_eras: self.unbonding_eras.get_or_default(),
+            era_duration: self.era_duration(),
+            min_period_seconds,
+            earliest_completion: self.env().get_block_time() + period_seconds.max(min_period_seconds),
         }
     }
 
+    /// CSPR `user` has unbonding, claimable or not
+    pub fn get_user_pending_unbonding(&self, user: Address) -> U512 {
+        self.get_user_unbonding_requests(user)
+            .iter()
+            .fold(U512::zero(), |total, id| {
+                total + self.unbonding_requests.get(id).map_or(U512::zero(), |request| request.amount)
+            })
+    }
+
     /// Calculate APY based on recent rewards
     /// 
     /// Returns APY in basis points (10000 = 100%)
@@ -610,10 +1316,9 @@ impl LiquidStaking {
         }
         
         // Simple calculation: (rewards / staked) * 10000 using U512
-        let apy_bps = (total_rewards * U512::from(10000u64) / total_staked)
-            .as_u64();
-        
-        apy_bps
+        ratio_bps(total_rewards, total_staked)
+            .and_then(safe_u512_to_u64)
+            .unwrap_or_revert(&self.env())
     }
 
     /// Check if compound is needed
@@ -636,7 +1341,33 @@ impl LiquidStaking {
         estimated_rewards >= threshold
     }
 
-    /// Estimate pending rewards across all validators
+    /// Estimate rewards accrued since the last compound
+    /// 
+    /// Scales the daily estimate by the time elapsed since the last compound
+    /// (or the first stake). Used by the vault to value withdrawals at a
+    /// fresher NAV.
+    pub fn get_pending_rewards_estimate(&self) -> U512 {
+        let last = self.last_compound.get_or_default();
+        let start = if last > 0 { last } else { self.first_stake_time.get_or_default() };
+        let now = self.env().get_block_time();
+        
+        if start == 0 {
+            return U512::zero();
+        }
+        
+        self.estimate_pending_rewards() * U512::from(now.saturating_sub(start)) / U512::from(86400u64)
+    }
+
+    /// Rewards `compound_rewards` would restake if called now
+    /// 
+    /// Exact for the simulated rewards compounded without a validator
+    /// adapter. An adapter's rewards are only known once claimed, so the
+    /// same net daily estimate stands in for them.
+    pub fn preview_compound_rewards(&self) -> U512 {
+        self.estimate_pending_rewards()
+    }
+
+    /// Estimate one day of rewards across all validators, net of commission
     fn estimate_pending_rewards(&self) -> U512 {
         let active_validators = self.validator_registry.get_active_validators();
         let mut total = U512::zero();
@@ -644,38 +1375,581 @@ impl LiquidStaking {
         for validator in active_validators.iter() {
             let delegation = self.delegations.get(validator).unwrap_or(U512::zero());
             let rewards = self.calculate_estimated_rewards(*validator, delegation);
-            total += rewards;
+            total += net_of_commission(rewards, self.validator_registry.get_commission(*validator));
         }
         
         total
     }
 
-    /// Set unbonding period (admin only)
+    /// Set unbonding period in seconds (admin only)
+    /// 
+    /// Must cover at least `UNBONDING_DELAY_ERAS` eras and at most
+    /// `MAX_UNBONDING_PERIOD`. Applies to new requests only; open requests
+    /// keep the unlock time they were created with.
     pub fn set_unbonding_period(&mut self, period: u64) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         
-        self.unbonding_period.set(period);
+        if period < self.min_unbonding_period() || period > MAX_UNBONDING_PERIOD {
+            self.env().revert(StakingError::InvalidUnbondingPeriod);
+        }
+        
+        let old = self.unbonding_period.get_or_default();
+        self.store_unbonding_period(period);
+        self.config_changed("unbonding_period", old, period);
+    }
+
+    /// Set the network era length in seconds (admin only)
+    /// 
+    /// Bounded by `MIN_ERA_DURATION`/`MAX_ERA_DURATION`, and reverts if the
+    /// current unbonding period would fall below `UNBONDING_DELAY_ERAS` of
+    /// the new eras. Open requests keep their unlock times; the era-based
+    /// minimum in `complete_unbonding` uses the new length.
+    pub fn set_era_duration(&mut self, duration: u64) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let period = self.unbonding_period.get_or_default();
+        if duration < MIN_ERA_DURATION
+            || duration > MAX_ERA_DURATION
+            || period < UNBONDING_DELAY_ERAS * duration
+        {
+            self.env().revert(StakingError::InvalidUnbondingPeriod);
+        }
+        
+        let old = self.era_duration();
+        self.era_duration.set(duration);
+        self.store_unbonding_period(period);
+        self.config_changed("era_duration", old, duration);
     }
 
     /// Set minimum compound interval (admin only)
     pub fn set_min_compound_interval(&mut self, interval: u64) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         
+        let old = self.min_compound_interval.get_or_default();
         self.min_compound_interval.set(interval);
+        self.config_changed("min_compound_interval", old, interval);
+    }
+
+    /// Update the surplus unlock duration (admin only, max 7 days)
+    /// 
+    /// Settles the currently locked amount first so the change only
+    /// affects the remaining unlock.
+    pub fn set_surplus_unlock_duration(&mut self, duration: u64) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        if duration > 7 * 24 * 60 * 60 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.locked_surplus.set(self.get_locked_surplus());
+        self.locked_surplus_time.set(self.env().get_block_time());
+        let old = self.surplus_unlock_duration.get_or_default();
+        self.surplus_unlock_duration.set(duration);
+        self.config_changed("surplus_unlock_duration", old, duration);
+    }
+
+    /// Set the deficit a sync may write off without confirmation (admin only)
+    pub fn set_max_sync_deficit_bps(&mut self, bps: u32) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        if bps > 10_000 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.max_sync_deficit_bps.get_or_default();
+        self.max_sync_deficit_bps.set(bps);
+        self.config_changed("max_sync_deficit_bps", old, bps);
+    }
+
+    /// Top up the slashing insurance fund with the attached CSPR (admin only)
+    #[odra(payable)]
+    pub fn fund_insurance(&mut self) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let amount = self.env().attached_value();
+        if amount.is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        // Accounted like stake, so a sync doesn't book it as a surplus
+        let accounted = self.accounted_balance.get_or_default();
+        self.accounted_balance.set(accounted + amount);
+        self.add_to_insurance(amount);
+    }
+
+    /// Credit the fee source's performance fee slice to the fund
+    /// 
+    /// The slice arrives as attached CSPR and is accounted like
+    /// `fund_insurance`, so every payout is backed by the purse. Only the
+    /// address set with `set_insurance_fee_source` may credit.
+    #[odra(payable)]
+    pub fn credit_insurance_fees(&mut self) {
+        if self.insurance_fee_source.get() != Some(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let amount = self.env().attached_value();
+        if amount.is_zero() {
+            return;
+        }
+        
+        let accounted = self.accounted_balance.get_or_default();
+        self.accounted_balance.set(accounted + amount);
+        self.add_to_insurance(amount);
+    }
+
+    /// Set the account allowed to credit performance fees to the fund (admin only)
+    pub fn set_insurance_fee_source(&mut self, source: Address) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let old = config_address(self.insurance_fee_source.get());
+        self.insurance_fee_source.set(source);
+        self.config_changed("insurance_fee_source", old, config_address(Some(source)));
+    }
+
+    /// Set the fund size kept back from excess withdrawals (admin only)
+    pub fn set_insurance_target(&mut self, target: U512) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let old = self.insurance_target.get_or_default();
+        self.insurance_target.set(target);
+        self.config_changed("insurance_target", old, target);
+    }
+
+    /// Request a withdrawal of insurance above the target (admin only)
+    /// 
+    /// Executable after `insurance_withdrawal_delay`; a new request
+    /// replaces the pending one and restarts the delay.
+    pub fn request_insurance_withdrawal(&mut self, amount: U512) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        if amount.is_zero() || amount > self.get_insurance_excess() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.insurance_withdrawal_amount.get_or_default();
+        self.insurance_withdrawal_amount.set(amount);
+        self.insurance_withdrawal_unlock_time.set(
+            self.env().get_block_time() + self.insurance_withdrawal_delay.get_or_default(),
+        );
+        self.config_changed("insurance_withdrawal", old, amount);
+    }
+
+    /// Withdraw the requested excess to the caller once unlocked (admin only)
+    /// 
+    /// Capped at the excess when executed, so payouts since the request
+    /// are not taken out of the target.
+    pub fn execute_insurance_withdrawal(&mut self) -> U512 {
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Admin.to_u8(), caller) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let requested = self.insurance_withdrawal_amount.get_or_default();
+        if requested.is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        if self.env().get_block_time() < self.insurance_withdrawal_unlock_time.get_or_default() {
+            self.env().revert(VaultError::TimelockNotExpired);
+        }
+        
+        let amount = requested.min(self.get_insurance_excess());
+        self.insurance_withdrawal_amount.set(U512::zero());
+        if amount.is_zero() {
+            return amount;
+        }
+        
+        let fund = self.insurance_fund.get_or_default() - amount;
+        self.insurance_fund.set(fund);
+        let accounted = self.accounted_balance.get_or_default();
+        self.accounted_balance.set(accounted.saturating_sub(amount));
+        self.env().transfer_tokens(&caller, &amount);
+        
+        self.env().emit_event(InsuranceWithdrawn {
+            recipient: caller,
+            amount,
+            fund_balance: fund,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        amount
+    }
+
+    pub fn get_insurance_fund_balance(&self) -> U512 {
+        self.insurance_fund.get_or_default()
+    }
+
+    pub fn get_insurance_target(&self) -> U512 {
+        self.insurance_target.get_or_default()
+    }
+
+    /// Fund above the target, available to `request_insurance_withdrawal`
+    pub fn get_insurance_excess(&self) -> U512 {
+        self.insurance_fund.get_or_default().saturating_sub(self.insurance_target.get_or_default())
+    }
+
+    /// Insurance fund as a share of total staked (bps)
+    pub fn get_insurance_coverage_bps(&self) -> u32 {
+        let staked = self.total_staked.get_or_default();
+        if staked.is_zero() {
+            return 0;
+        }
+        
+        let coverage = ratio_bps(self.insurance_fund.get_or_default(), staked).unwrap_or_revert(&self.env());
+        coverage.min(U512::from(u32::MAX)).as_u32()
+    }
+
+    /// Pending excess withdrawal and when it unlocks
+    pub fn get_pending_insurance_withdrawal(&self) -> (U512, u64) {
+        (
+            self.insurance_withdrawal_amount.get_or_default(),
+            self.insurance_withdrawal_unlock_time.get_or_default(),
+        )
+    }
+
+    /// Route delegation through a validator adapter (admin only)
+    /// 
+    /// Switch before anything is staked: delegations made without an adapter
+    /// are not moved to it.
+    pub fn set_validator_adapter(&mut self, adapter: Address) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let old = config_address(self.validator_adapter.get());
+        self.validator_adapter.set(adapter);
+        self.config_changed("validator_adapter", old, config_address(Some(adapter)));
+    }
+
+    /// Break validator selection ties by recent rewards per unit of stake (admin only)
+    pub fn set_reward_tiebreaker(&mut self, enabled: bool) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        self.validator_registry.set_reward_tiebreaker(enabled);
+    }
+
+    /// Cap each validator at `pct`% of total stake (admin only)
+    pub fn set_max_per_validator_pct(&mut self, pct: u8) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if pct == 0 || pct > 100 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.validator_registry.set_max_per_validator_pct(pct);
+    }
+
+    pub fn get_max_per_validator_pct(&self) -> u8 {
+        self.validator_registry.get_max_per_validator_pct()
+    }
+
+    /// Cap validator selection by share of total network stake (admin only)
+    /// 
+    /// Validators reported above `max_share_bps` are not selected for new
+    /// stake. Reports older than `max_age` seconds are ignored, which
+    /// falls back to selecting on protocol stake alone.
+    pub fn set_network_share_limits(&mut self, max_share_bps: u32, max_age: u64) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        self.validator_registry.set_network_share_limits(max_share_bps, max_age);
+    }
+
+    pub fn get_network_share_limits(&self) -> (u32, u64) {
+        self.validator_registry.get_network_share_limits()
+    }
+
+    /// Report a validator's total stake and the network total (oracle or admin)
+    /// 
+    /// `validator_stake` includes delegations from outside the protocol.
+    pub fn report_validator_network_stake(&mut self, validator: Address, validator_stake: U512, network_total_stake: U512) {
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Oracle.to_u8(), caller)
+            && !self.access_control.has_role(Role::Admin.to_u8(), caller)
+        {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        self.validator_registry.report_network_stake(validator, validator_stake, network_total_stake);
+    }
+
+    pub fn get_validator_adapter(&self) -> Option<Address> {
+        self.validator_adapter.get()
+    }
+
+    /// Set the lstCSPR token (admin only)
+    ///
+    /// The token is deployed after this contract (its minter is this
+    /// contract's address), so deployment wires it in afterwards.
+    pub fn set_lst_cspr_token(&mut self, token: Address) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+
+        let old = config_address(self.lst_cspr_token.get());
+        self.lst_cspr_token.set(token);
+        self.config_changed("lst_cspr_token", old, config_address(Some(token)));
+    }
+
+    /// Set the vault address (admin only)
+    ///
+    /// Only used to tell the vault's staking apart from direct stakers in
+    /// events and views; anyone may stake.
+    pub fn set_vault_address(&mut self, vault: Address) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+
+        let old = config_address(self.vault_address.get());
+        self.vault_address.set(vault);
+        self.config_changed("vault_address", old, config_address(Some(vault)));
+    }
+
+    pub fn get_vault_address(&self) -> Option<Address> {
+        self.vault_address.get()
+    }
+
+    /// Register emergency action approvers and the M-of-N threshold (admin only)
+    /// 
+    /// A threshold of 1 keeps single-admin mode.
+    pub fn set_approvers(&mut self, approvers: Vec<Address>, threshold: u8, validity_window: u64) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        self.approvals.set_approvers(approvers, threshold, validity_window);
+    }
+
+    /// Approve an emergency action hash (registered approvers only)
+    pub fn approve_action(&mut self, action_hash: [u8; 32]) {
+        self.approvals.approve(action_hash);
+    }
+
+    /// Withdraw the caller's approval of an emergency action hash
+    pub fn revoke_approval(&mut self, action_hash: [u8; 32]) {
+        self.approvals.revoke(action_hash);
+    }
+
+    /// Unexpired approvals for an emergency action hash
+    pub fn get_approval_count(&self, action_hash: [u8; 32]) -> u8 {
+        self.approvals.approval_count(action_hash)
+    }
+
+    /// Action hash approvers sign off on before `emergency_undelegate` runs
+    pub fn get_emergency_undelegate_hash(&self, validator: Address, amount: U512) -> [u8; 32] {
+        self.approvals.action_hash("emergency_undelegate", &(validator, amount))
     }
 
     /// Emergency withdraw from validator (admin only)
     /// 
-    /// Used in case of validator issues or emergencies
+    /// Used in case of validator issues or emergencies. Requires M-of-N
+    /// approvals of `get_emergency_undelegate_hash` when a threshold is set.
     pub fn emergency_undelegate(&mut self, validator: Address, amount: U512) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         
+        let action_hash = self.get_emergency_undelegate_hash(validator, amount);
+        self.approvals.require_approval(action_hash);
+        
         self.undelegate_from_validator(validator, amount);
     }
+
+    /// Action hash approvers sign off on before `emergency_exit_validator` runs
+    pub fn get_emergency_exit_validator_hash(&self, validator: Address) -> [u8; 32] {
+        self.approvals.action_hash("emergency_exit_validator", &validator)
+    }
+
+    /// Move a validator's whole delegation elsewhere and blacklist it (admin only)
+    /// 
+    /// The recovered stake is re-delegated through the selection algorithm.
+    /// What caps leave over tops up eligible validators with room, and what
+    /// still remains is spread evenly over them past their caps, flagged
+    /// with `cap_override` in `ValidatorEmergencyExited`. With no eligible
+    /// validator left the stake is parked in the liquidity buffer. Requires
+    /// M-of-N approvals of `get_emergency_exit_validator_hash` when a
+    /// threshold is set. Returns the amount moved.
+    pub fn emergency_exit_validator(&mut self, validator: Address) -> U512 {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if self.validator_registry.get_validator_metrics(validator).is_none() {
+            self.env().revert(StakingError::ValidatorNotFound);
+        }
+        
+        let action_hash = self.get_emergency_exit_validator_hash(validator);
+        self.approvals.require_approval(action_hash);
+        
+        let amount = self.delegations.get(&validator).unwrap_or(U512::zero());
+        if !amount.is_zero() {
+            self.undelegate_from_validator(validator, amount);
+        }
+        self.validator_registry.blacklist_validator(validator);
+        
+        let mut redistributed_to: Vec<Address> = Vec::new();
+        let mut remaining = amount;
+        if !amount.is_zero() {
+            for allocation in self.validator_registry.select_validators_for_delegation(amount) {
+                self.delegate_to_validator(allocation.validator, allocation.amount);
+                remaining -= allocation.amount;
+                redistributed_to.push(allocation.validator);
+            }
+        }
+        
+        // Rounding and caps can leave a remainder the selection didn't place
+        let eligible: Vec<Address> = self.validator_registry.get_active_validators()
+            .into_iter()
+            .filter(|candidate| self.validator_registry.is_eligible(*candidate))
+            .collect();
+        for candidate in eligible.iter() {
+            if remaining.is_zero() {
+                break;
+            }
+            let top_up = self.validator_registry.get_remaining_capacity(*candidate).min(remaining);
+            if !top_up.is_zero() {
+                self.delegate_to_validator(*candidate, top_up);
+                remaining -= top_up;
+                if !redistributed_to.contains(candidate) {
+                    redistributed_to.push(*candidate);
+                }
+            }
+        }
+        
+        let cap_override = !remaining.is_zero() && !eligible.is_empty();
+        let mut buffered = U512::zero();
+        if cap_override {
+            let share = remaining / U512::from(eligible.len());
+            for (i, candidate) in eligible.iter().enumerate() {
+                let amount = if i + 1 == eligible.len() { remaining } else { share };
+                if amount.is_zero() {
+                    continue;
+                }
+                self.delegate_to_validator(*candidate, amount);
+                remaining -= amount;
+                if !redistributed_to.contains(candidate) {
+                    redistributed_to.push(*candidate);
+                }
+            }
+        } else if !remaining.is_zero() {
+            buffered = remaining;
+            self.liquidity_buffer.set(self.liquidity_buffer.get_or_default() + buffered);
+        }
+        
+        self.env().emit_event(ValidatorEmergencyExited {
+            validator,
+            amount_moved: amount,
+            redistributed_to,
+            cap_override,
+            buffered,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        amount
+    }
+
+    /// Undelegated CSPR waiting in the liquidity buffer
+    pub fn get_liquidity_buffer(&self) -> U512 {
+        self.liquidity_buffer.get_or_default()
+    }
+
+    /// Block completions of matured unbonding requests (guardian or admin)
+    /// 
+    /// Reserved for catastrophic exploits; new stakes and unstakes are
+    /// unaffected.
+    pub fn freeze_withdrawals(&mut self) {
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Guardian.to_u8(), caller)
+            && !self.access_control.has_role(Role::Admin.to_u8(), caller)
+        {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if self.withdrawals_frozen.get_or_default() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.withdrawals_frozen.set(true);
+        self.config_changed("withdrawals_frozen", false, true);
+    }
+
+    /// Let matured unbonding requests complete again (admin only)
+    pub fn unfreeze_withdrawals(&mut self) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if !self.withdrawals_frozen.get_or_default() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.withdrawals_frozen.set(false);
+        self.config_changed("withdrawals_frozen", true, false);
+    }
+
+    pub fn are_withdrawals_frozen(&self) -> bool {
+        self.withdrawals_frozen.get_or_default()
+    }
+
+    // BUILD INFO
+
+    /// Crate version as (major, minor, patch)
+    pub fn get_version(&self) -> (u32, u32, u32) {
+        self.build_info.version()
+    }
+
+    /// Feature flags this deployment supports (see `LIQUID_STAKING_FEATURES`)
+    pub fn get_features(&self) -> Vec<String> {
+        self.build_info.features()
+    }
+
+    /// Git commit this deployment was built from, when known
+    pub fn get_git_hash(&self) -> Option<String> {
+        self.build_info.git_hash()
+    }
+
+    // TEST HOOKS (compiled out of production builds)
+
+    /// Add rewards to total staked and reprice lstCSPR, skipping delegation
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_add_rewards(&mut self, amount: U512) {
+        let current_staked = self.total_staked.get_or_default();
+        self.total_staked.set(current_staked + amount);
+        self.update_exchange_rate();
+    }
+
+    /// Append `validator` to the active set, bypassing the duplicate checks
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_push_active_validator(&mut self, validator: Address) {
+        self.validator_registry.test_push_active_validator(validator);
+    }
+
+    /// Set total staked so the exchange rate (1e9 scale) becomes `rate`
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_exchange_rate(&mut self, rate: U256) {
+        let total_lst_cspr = self.total_lst_cspr.get_or_default();
+        let staked = total_lst_cspr * safe_u256_to_u512(rate) / U512::from(1_000_000_000u64);
+        self.total_staked.set(staked + self.get_locked_surplus());
+        self.exchange_rate.set(rate);
+    }
 }
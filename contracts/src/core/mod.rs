@@ -2,8 +2,14 @@ pub mod vault_manager;
 pub mod liquid_staking;
 pub mod strategy_router;
 pub mod yield_aggregator;
+pub mod keeper_hub;
+pub mod system_registry;
+pub mod vault_lens;
 
 pub use vault_manager::*;
 pub use liquid_staking::*;
 pub use strategy_router::*;
 pub use yield_aggregator::*;
+pub use keeper_hub::*;
+pub use system_registry::*;
+pub use vault_lens::*;
@@ -3,7 +3,199 @@ use odra::Event;
 use odra::{Address, Mapping, SubModule, Var};
 use odra::casper_types::{U256, U512};
 use crate::types::*;
-use crate::utils::AccessControl;
+use crate::utils::{annualize_bps, bps_of, feature_flags, mul_div, mul_div_u256, ratio_bps, safe_u256_to_u512, safe_u512_to_u256, safe_u512_to_u32, AccessControl, BuildInfo, Role, Rounding, BPS_DENOMINATOR, TIMELOCK_DURATION};
+use crate::strategies::{RewardSwapContractRef, RewardTokenContractRef, StrategyContractRef};
+use crate::core::{LiquidStakingContractRef, VaultManagerContractRef};
+use crate::core::keeper_hub::{JOB_HARVEST_ALL, JOB_REBALANCE, JOB_HEALTH_CHECK};
+use crate::tokens::lst_cspr::LstCsprContractRef;
+
+/// Last health observation for a strategy
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub apy: U256,
+    pub balance: U512,
+    pub recorded_allocation: U512,
+    pub drift_bps: u32,
+    pub consecutive_failures: u8,
+    pub quarantined: bool,
+    /// Allocation above its TVL caps at the last check
+    pub over_exposed: bool,
+    pub checked_at: u64,
+}
+
+/// Funds a rebalance would move for one strategy
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct AllocationMove {
+    pub strategy_name: String,
+    pub current: U512,
+    pub target: U512,
+    pub withdraw: U512,
+    pub deploy: U512,
+}
+
+/// Withdrawal batching counters since deployment
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct BatchingStats {
+    pub claims_queued: u64,
+    pub batches_flushed: u64,
+    /// Strategy withdrawals the batches replaced (claims served less batches)
+    pub withdrawals_saved: u64,
+    pub volume_batched: U512,
+}
+
+/// How a reward token is turned into lstCSPR
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct RewardRoute {
+    /// Pool swapping the token (A) for lstCSPR (B)
+    pub dex: Address,
+    /// Swap on harvest; otherwise rewards wait in escrow for `liquidate_rewards`
+    pub auto_swap: bool,
+    /// Minimum lstCSPR per token for an auto-swap (scaled by 1e9)
+    pub min_rate: U512,
+}
+
+/// Projected effect of a proposed target allocation set
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct AllocationSimulation {
+    pub moves: Vec<AllocationMove>,
+    /// Blended APY after the moves (bps), at current strategy APYs
+    pub projected_apy: U256,
+    /// Constraint violations; empty if the targets could be applied as-is
+    pub violations: Vec<String>,
+    /// Sum of all withdrawals and deployments
+    pub total_churn: U512,
+}
+
+/// Lifetime record of a strategy's flows and realized yield
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct StrategyPerformance {
+    pub cumulative_deployed: U512,
+    pub cumulative_withdrawn: U512,
+    pub cumulative_harvested: U512,
+    /// Mean recorded allocation since activation, weighted by time
+    pub avg_allocation: U512,
+    /// When the strategy first held an allocation
+    pub activated_at: Option<u64>,
+    pub days_active: u64,
+    /// Harvests over the time-weighted allocation, annualized (bps)
+    pub realized_apy: U256,
+}
+
+/// Recorded allocations after an allocate, withdraw or rebalance
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct AllocationSnapshot {
+    pub id: u64,
+    /// `ALLOCATION_ACTION_*` that produced it (the last one in its epoch)
+    pub action: u8,
+    pub total_allocated: U512,
+    /// Recorded allocation per strategy, in registration order
+    pub allocations: Vec<(String, U512)>,
+    pub timestamp: u64,
+}
+
+/// How `withdraw` spreads a withdrawal over strategies
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WithdrawalPolicy {
+    /// Every strategy pays its share of the current allocation
+    Proportional = 0,
+    /// Strategies are drained one by one, most liquid tier first
+    LiquidityFirst = 1,
+    /// Strategies are drained one by one, lowest estimated exit cost first
+    CheapestFirst = 2,
+}
+
+impl WithdrawalPolicy {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(WithdrawalPolicy::Proportional),
+            1 => Some(WithdrawalPolicy::LiquidityFirst),
+            2 => Some(WithdrawalPolicy::CheapestFirst),
+            _ => None,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// How quickly a strategy can return funds, declared at registration
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LiquidityTier {
+    /// Same-transaction exit (e.g. lending)
+    Instant = 0,
+    /// Exit with slippage or a short delay (e.g. DEX LP)
+    Fast = 1,
+    /// Exit needs a bridge or unbonding delay (e.g. cross-chain)
+    Slow = 2,
+}
+
+impl LiquidityTier {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(LiquidityTier::Instant),
+            1 => Some(LiquidityTier::Fast),
+            2 => Some(LiquidityTier::Slow),
+            _ => None,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Outcome of one strategy's share of a `withdraw`
+struct WithdrawnTranche {
+    /// lstCSPR received
+    withdrawn: U512,
+    /// Taken off the recorded allocation
+    released: U512,
+    /// What the strategy expected to keep back
+    estimated_cost: U512,
+    /// Bridge, exit and conversion costs booked
+    actual_cost: U512,
+}
+
+/// Weight of a new health-check APY reading in the smoothed APY (bps)
+const APY_EMA_WEIGHT_BPS: u64 = 2000;
+
+/// Most strategies registered at once (bounds the all-strategy views)
+pub const MAX_STRATEGIES: usize = 20;
+
+/// Longest a queued withdrawal waits for its batch (1 hour)
+pub const MAX_BATCH_WINDOW: u64 = 60 * 60;
+
+/// Action recorded with an allocation snapshot
+pub const ALLOCATION_ACTION_ALLOCATE: u8 = 0;
+pub const ALLOCATION_ACTION_WITHDRAW: u8 = 1;
+pub const ALLOCATION_ACTION_REBALANCE: u8 = 2;
+
+/// Most allocation snapshots the history may retain
+pub const MAX_ALLOCATION_HISTORY: u64 = 1024;
+
+/// Whether a strategy counts toward the cross-chain caps
+fn is_crosschain(strategy_name: &str) -> bool {
+    strategy_name == "crosschain"
+}
+
+/// Feature flags reported by `get_features`, one per shipped capability
+pub const ROUTER_FEATURES: &[&str] = &[
+    "deployment_queue",
+    "withdrawal_batching",
+    "withdrawal_policies",
+    "liquidity_tiers",
+    "reward_liquidation",
+    "native_strategies",
+    "exit_cost_estimates",
+    "strategy_quarantine",
+    "pending_withdrawals",
+    "reconciliation",
+    "keeper_jobs",
+    "exposure_caps",
+    "allocation_history",
+];
 
 /// StrategyRouter contract
 /// 
@@ -20,6 +212,9 @@ pub struct StrategyRouter {
     /// Access control
     access_control: SubModule<AccessControl>,
     
+    /// Version and feature flags for front-ends
+    build_info: SubModule<BuildInfo>,
+    
     /// Strategy contracts (name -> address)
     strategies: Mapping<String, Address>,
     /// Strategy names list
@@ -42,6 +237,188 @@ pub struct StrategyRouter {
     last_rebalance: Var<u64>,
     /// Minimum rebalance interval (seconds)
     min_rebalance_interval: Var<u64>, // Default: 12 hours
+    
+    /// HEALTH MONITORING
+    
+    /// Consecutive failed health checks per strategy
+    health_failures: Mapping<String, u8>,
+    /// Quarantined strategies (funds pulled, excluded from allocation)
+    quarantined: Mapping<String, bool>,
+    /// Failed checks before the target allocation is zeroed
+    health_failure_threshold: Var<u8>, // Default: 3
+    /// Balance shortfall vs recorded allocation that counts as a failed check (bps)
+    max_balance_drift_bps: Var<u32>, // Default: 2%
+    /// Balance shortfall that triggers immediate quarantine (bps)
+    critical_balance_drift_bps: Var<u32>, // Default: 10%
+    /// APY above which a strategy is quarantined immediately (bps)
+    max_strategy_apy_bps: Var<U256>, // Default: 500%
+    
+    /// Last health report fields (flattened for Casper serialization)
+    report_healthy: Mapping<String, bool>,
+    report_apy: Mapping<String, U256>,
+    report_balance: Mapping<String, U512>,
+    report_drift_bps: Mapping<String, u32>,
+    report_time: Mapping<String, u64>,
+    
+    /// Smoothed APY per strategy, fed by health check readings (bps)
+    apy_ema: Mapping<String, U256>,
+    /// Readings more than this multiple above or below the smoothed APY are
+    /// dropped, unless the previous reading was within it as well
+    max_apy_deviation_multiple: Var<u32>, // Default: 3x
+    
+    /// CIRCUIT BREAKER
+    
+    /// Max gain a single sync may book, relative to total allocated (bps)
+    max_price_change_bps: Var<u32>, // Default: 2%
+    /// Unbooked gains per strategy held back by the circuit breaker
+    pending_gains: Mapping<String, U512>,
+    /// Sum of pending gains
+    total_pending_gains: Var<U512>,
+    /// Remaining syncs over which released gains are booked
+    gain_drips_remaining: Var<u32>,
+    
+    /// COSTS
+    
+    /// Bridge fees paid per strategy (booked as a cost, not a loss)
+    bridge_fees: Mapping<String, U512>,
+    /// Sum of bridge fees across strategies
+    lifetime_bridge_fees: Var<U512>,
+    /// Exit costs kept back by strategies beyond bridge fees
+    exit_costs: Mapping<String, U512>,
+    /// Sum of exit costs across strategies
+    lifetime_exit_costs: Var<U512>,
+    
+    /// Withdrawals that left a strategy but never arrived (still allocated)
+    pending_withdrawals: Mapping<String, U512>,
+    /// When a strategy's pending withdrawals may be written off
+    pending_withdrawal_deadlines: Mapping<String, u64>,
+    /// Per-strategy wait before write-off (seconds, 0 = default)
+    withdrawal_deadline_secs: Mapping<String, u64>,
+    default_withdrawal_deadline: Var<u64>, // Default: 3 days
+    /// Written-off amounts that can still be recovered
+    written_off: Mapping<String, U512>,
+    /// Recovered write-offs, reported as yield on the next harvest
+    recovered_gains: Mapping<String, U512>,
+    
+    /// WITHDRAWAL ORDERING
+    
+    /// `WithdrawalPolicy` used by `withdraw`
+    withdrawal_policy: Var<u8>, // Default: Proportional
+    /// `LiquidityTier` per strategy (unset = Slow)
+    liquidity_tiers: Mapping<String, u8>,
+    
+    /// IDLE BUFFER
+    
+    /// Share of each allocation kept undeployed in the router (percentage)
+    router_idle_buffer_pct: Var<u8>, // Default: 5%
+    /// Liquid lstCSPR held by the router, drawn first on withdrawal
+    idle_balance: Var<U512>,
+    
+    /// DEPLOYMENT QUEUE
+    
+    /// Most a strategy receives in one transaction (unset/zero = no cap)
+    max_single_deploy: Mapping<String, U512>,
+    /// lstCSPR held in the router waiting to be deployed, per strategy
+    queued_deployments: Mapping<String, U512>,
+    total_queued: Var<U512>,
+    
+    /// REWARD TOKENS
+    
+    /// `RewardRoute` fields per reward token (flattened for Casper serialization)
+    reward_dex: Mapping<Address, Address>,
+    reward_auto_swap: Mapping<Address, bool>,
+    reward_min_rate: Mapping<Address, U512>,
+    /// Reward tokens held pending liquidation
+    reward_escrow: Mapping<Address, U512>,
+    /// lstCSPR from liquidations, reported as yield on the next harvest
+    reward_proceeds: Var<U512>,
+    
+    /// WITHDRAWAL BATCHING
+    
+    /// Queue exits for one consolidated withdrawal per window
+    withdrawal_batching: Var<bool>,
+    /// Longest a batch stays open before a claim may flush it
+    batch_window: Var<u64>, // Default: 1 hour
+    /// Open batch: number, when its first claim was queued and its total
+    current_batch: Var<u64>,
+    batch_opened_at: Var<u64>,
+    batch_queued: Var<U512>,
+    /// Flushed batches (batch -> requested / received)
+    batch_requested: Mapping<u64, U512>,
+    batch_received: Mapping<u64, U512>,
+    /// Queued claims - flattened (claim_id -> fields)
+    batch_claim_owners: Mapping<U256, Address>,
+    batch_claim_amounts: Mapping<U256, U512>,
+    batch_claim_batches: Mapping<U256, u64>,
+    batch_claim_taken: Mapping<U256, bool>,
+    next_batch_claim_id: Var<U256>,
+    /// Counters for `get_batching_stats`
+    batch_claims_queued: Var<u64>,
+    batches_flushed: Var<u64>,
+    batch_claims_flushed: Var<u64>,
+    batch_volume: Var<U512>,
+    
+    /// NATIVE STRATEGIES
+    
+    /// Strategies that take raw CSPR instead of lstCSPR
+    wants_native: Mapping<String, bool>,
+    /// LiquidStaking whose exchange rate prices the conversions
+    liquid_staking: Var<Address>,
+    /// CSPR held by the router to hand to native strategies
+    native_float: Var<U512>,
+    /// lstCSPR taken in exchange for float CSPR, paid back on withdrawal
+    float_lst: Var<U512>,
+    /// Cost of each conversion through the float (bps)
+    native_conversion_fee_bps: Var<u32>, // Default: 0
+    /// Conversion costs per strategy (lstCSPR)
+    conversion_costs: Mapping<String, U512>,
+    lifetime_conversion_costs: Var<U512>,
+    
+    /// PERFORMANCE TRACKING
+    
+    /// Lifetime flows per strategy (lstCSPR)
+    perf_deployed: Mapping<String, U512>,
+    perf_withdrawn: Mapping<String, U512>,
+    perf_harvested: Mapping<String, U512>,
+    /// Recorded allocation integrated over time (lstCSPR-seconds) up to the checkpoint
+    perf_allocation_seconds: Mapping<String, U512>,
+    perf_checkpoint: Mapping<String, u64>,
+    /// First time each strategy held an allocation
+    perf_activated_at: Mapping<String, u64>,
+    
+    /// EXPOSURE CAPS
+    
+    /// VaultManager whose `total_assets` the TVL caps are measured against;
+    /// the only caller allowed to allocate and withdraw
+    vault: Var<Address>,
+    /// YieldAggregator allowed to allocate compounded yield
+    yield_aggregator: Var<Address>,
+    /// Most a strategy may hold, as a share of vault TVL (bps, 0 = no cap)
+    max_tvl_bps: Mapping<String, u32>,
+    /// Most all cross-chain strategies may hold together (bps, 0 = no cap)
+    max_crosschain_tvl_bps: Var<u32>,
+    /// Proposed caps and when they may be applied (flattened)
+    pending_max_tvl_bps: Mapping<String, u32>,
+    pending_max_tvl_eta: Mapping<String, u64>,
+    pending_crosschain_tvl_bps: Var<u32>,
+    pending_crosschain_tvl_eta: Var<u64>,
+    /// Strategies over their caps at the last health check
+    report_over_exposed: Mapping<String, bool>,
+    
+    /// ALLOCATION HISTORY
+    
+    /// Last issued snapshot ID (0 = none yet)
+    allocation_snapshot_id: Var<u64>,
+    /// Number of snapshots retained in the ring buffer
+    allocation_history_size: Var<u64>,  // Default: 64
+    /// Snapshots within one epoch overwrite each other (seconds, 0 = keep every one)
+    allocation_snapshot_epoch: Var<u64>,
+    /// Snapshot ring buffer - flattened (slot -> fields)
+    snapshot_ids: Mapping<u64, u64>,
+    snapshot_actions: Mapping<u64, u8>,
+    snapshot_totals: Mapping<u64, U512>,
+    snapshot_allocations: Mapping<u64, Vec<(String, U512)>>,
+    snapshot_times: Mapping<u64, u64>,
 }
 
 #[odra::module]
@@ -49,6 +426,7 @@ impl StrategyRouter {
     /// Initialize the StrategyRouter
     pub fn init(&mut self, admin: Address) {
         self.access_control.init(admin);
+        self.build_info.init(feature_flags(ROUTER_FEATURES));
         
         self.total_allocated.set(U512::zero());
         self.max_strategy_allocation.set(40);
@@ -56,277 +434,3145 @@ impl StrategyRouter {
         self.last_rebalance.set(0);
         self.min_rebalance_interval.set(12 * 60 * 60); // 12 hours
         
+        self.health_failure_threshold.set(3);
+        self.max_balance_drift_bps.set(200); // 2%
+        self.critical_balance_drift_bps.set(1000); // 10%
+        self.max_strategy_apy_bps.set(U256::from(50000u64)); // 500%
+        self.max_apy_deviation_multiple.set(3);
+        
+        self.max_price_change_bps.set(200); // 2%
+        self.total_pending_gains.set(U512::zero());
+        self.gain_drips_remaining.set(0);
+        
+        self.lifetime_bridge_fees.set(U512::zero());
+        self.lifetime_exit_costs.set(U512::zero());
+        self.default_withdrawal_deadline.set(3 * 24 * 60 * 60); // 3 days
+        self.withdrawal_policy.set(WithdrawalPolicy::Proportional.to_u8());
+        self.router_idle_buffer_pct.set(5);
+        self.idle_balance.set(U512::zero());
+        self.total_queued.set(U512::zero());
+        self.reward_proceeds.set(U512::zero());
+        
+        self.withdrawal_batching.set(false);
+        self.batch_window.set(MAX_BATCH_WINDOW);
+        self.current_batch.set(0);
+        self.batch_queued.set(U512::zero());
+        self.next_batch_claim_id.set(U256::zero());
+        
+        self.native_float.set(U512::zero());
+        self.float_lst.set(U512::zero());
+        self.native_conversion_fee_bps.set(0);
+        self.lifetime_conversion_costs.set(U512::zero());
+        
+        self.allocation_snapshot_id.set(0);
+        self.allocation_history_size.set(64);
+        self.allocation_snapshot_epoch.set(0);
+        
         self.strategy_names.set(Vec::new());
     }
 
     /// Allocate funds to strategies
     /// 
-    /// Distributes the given amount across strategies based on target allocations
-    pub fn allocate(&mut self, amount: U512) {
+    /// Distributes the given amount across strategies based on target allocations.
+    /// 
+    /// Each tranche is capped at the strategy's remaining capacity; overflow
+    /// is spread over the strategies that still have room, proportionally to
+    /// their targets. A tranche above the strategy's `max_single_deploy` is
+    /// deployed up to the cap and the excess queued in the router for
+    /// `process_deployment_queue`. Up to the idle buffer share of `amount`
+    /// is then kept in the router. Returns the amount left unallocated
+    /// beyond that and the queue, which the caller keeps (VaultManager adds
+    /// it to the instant pool). Bridge fees and native conversion costs paid
+    /// on the way in are booked as costs and are not part of the remainder.
+    /// 
+    /// Callable by the vault and the yield aggregator only.
+    pub fn allocate(&mut self, amount: U512) -> U512 {
+        self.allocate_with_id(0, amount)
+    }
+
+    /// `allocate` tagged with the vault operation it serves
+    /// 
+    /// `flow_id` is passed on to each strategy's `deploy_with_id` and
+    /// included in `AllocationUpdate`, so a deposit can be followed from
+    /// the vault's `Deposit` event to the strategies that received it.
+    pub fn allocate_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        let caller = self.env().caller();
+        if !self.is_vault(caller) && self.yield_aggregator.get() != Some(caller) {
+            self.env().revert(VaultError::Unauthorized);
+        }
         if amount.is_zero() {
-            return;
+            return U512::zero();
         }
         
-        let strategy_names = self.strategy_names.get_or_default();
+        let buffer_pct = self.router_idle_buffer_pct.get_or_default();
+        let reserve = amount * U512::from(buffer_pct) / U512::from(100u64);
         
-        for strategy_name in strategy_names.iter() {
-            let target_pct = self.target_allocations.get(strategy_name).unwrap_or(0);
-            
-            if target_pct == 0 {
-                continue;
-            }
-            
-            let allocation = (amount * U512::from(target_pct)) / U512::from(100u64);
-            
-            if allocation.is_zero() {
-                continue;
-            }
-            
-            
-            let current = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
-            self.current_allocations.set(strategy_name, current + allocation);
+        let plan = self.plan_allocation(amount);
+        let mut total_deployed = U512::zero();
+        let mut total_fees = U512::zero();
+        let mut total_queued = U512::zero();
+        
+        for (strategy_name, tranche) in plan.iter() {
+            let cap = self.max_single_deploy.get(strategy_name).unwrap_or(U512::zero());
+            let tranche = if !cap.is_zero() && *tranche > cap {
+                self.queue_deployment(strategy_name, *tranche - cap);
+                total_queued += *tranche - cap;
+                cap
+            } else {
+                *tranche
+            };
             
-            self.env().emit_event(AllocationUpdate {
-                strategy_name: strategy_name.clone(),
-                amount: allocation,
-                total_allocated: current + allocation,
-                timestamp: self.env().get_block_time(),
-            });
+            let (deployed, fees) = self.place_tranche(strategy_name, tranche, flow_id);
+            total_deployed += deployed;
+            total_fees += fees;
         }
         
         let total = self.total_allocated.get_or_default();
-        self.total_allocated.set(total + amount);
+        self.total_allocated.set(total + total_deployed);
+        
+        let remainder = amount.saturating_sub(total_deployed + total_fees + total_queued);
+        let held = reserve.min(remainder);
+        if !held.is_zero() {
+            let idle = self.idle_balance.get_or_default();
+            self.idle_balance.set(idle + held);
+        }
+        
+        self.record_allocation_snapshot(ALLOCATION_ACTION_ALLOCATE);
+        remainder - held
     }
 
-    /// Withdraw from strategies proportionally
+    /// Withdraw from the idle buffer, then the deployment queue, then from
+    /// strategies according to the withdrawal policy
+    /// 
+    /// Proportional takes each strategy's share of the current allocation.
+    /// LiquidityFirst drains strategies in liquidity tier order (Instant,
+    /// Fast, Slow; registration order within a tier), so a slow strategy is
+    /// only touched once the faster ones are exhausted. CheapestFirst drains
+    /// strategies by estimated exit cost for the size requested. Draining
+    /// policies move on until the net proceeds cover `amount`.
+    /// 
+    /// Returns the amount received. A strategy's recorded allocation drops
+    /// by what it paid out plus any bridge fee or exit cost, both booked
+    /// as costs.
+    /// Funds that left a strategy without arriving stay allocated and are
+    /// tracked as a pending withdrawal until settled or written off.
+    /// 
+    /// Callable by the vault only.
     pub fn withdraw(&mut self, amount: U512) -> U512 {
-        if amount.is_zero() {
-            return U512::zero();
+        self.withdraw_with_id(0, amount)
+    }
+
+    /// `withdraw` tagged with the vault operation it serves
+    /// 
+    /// `flow_id` is passed on to each strategy's `withdraw_with_id` and
+    /// included in `StrategyWithdrawal`.
+    pub fn withdraw_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        if !self.is_vault(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
         }
+        self.withdraw_funds(flow_id, amount)
+    }
+
+    /// Set the share of each allocation kept idle in the router (admin only)
+    /// 
+    /// Current targets must still fit in what is left.
+    pub fn set_idle_buffer_pct(&mut self, pct: u8) {
+        self.access_control.only_admin();
         
-        let total_allocated = self.total_allocated.get_or_default();
-        
-        if total_allocated.is_zero() {
-            return U512::zero();
+        let total_target: u16 = self.strategy_names.get_or_default()
+            .iter()
+            .map(|name| self.target_allocations.get(name).unwrap_or(0) as u16)
+            .sum();
+        if pct > 50 || total_target + pct as u16 > 100 {
+            self.env().revert(VaultError::InvalidRequest);
         }
         
-        let strategy_names = self.strategy_names.get_or_default();
-        let mut total_withdrawn = U512::zero();
+        let old = self.router_idle_buffer_pct.get_or_default();
+        self.router_idle_buffer_pct.set(pct);
+        self.config_changed("idle_buffer_pct".to_string(), old, pct);
+    }
+
+    pub fn get_idle_buffer_pct(&self) -> u8 {
+        self.router_idle_buffer_pct.get_or_default()
+    }
+
+    /// Liquid lstCSPR held in the router
+    pub fn get_idle_balance(&self) -> U512 {
+        self.idle_balance.get_or_default()
+    }
+
+    /// Deploy queued funds, at most one capped tranche per strategy
+    /// 
+    /// Looks at up to `max_strategies` strategies with a queue, in
+    /// registration order; quarantined strategies keep their queue. Whatever
+    /// a strategy doesn't accept moves to the idle balance. Returns the
+    /// amount deployed. Callable by keepers, operators and admins.
+    pub fn process_deployment_queue(&mut self, max_strategies: u32) -> U512 {
+        self.access_control.only_maintainer();
         
-        for strategy_name in strategy_names.iter() {
-            let current_allocation = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
-            
-            if current_allocation.is_zero() {
-                continue;
+        let mut total_deployed = U512::zero();
+        let mut visited = 0u32;
+        
+        for strategy_name in self.strategy_names.get_or_default().iter() {
+            if visited >= max_strategies {
+                break;
             }
             
-            let withdrawal_amount = (amount * current_allocation) / total_allocated;
-            
-            if withdrawal_amount.is_zero() {
+            let queued = self.queued_deployments.get(strategy_name).unwrap_or(U512::zero());
+            if queued.is_zero() || self.is_quarantined(strategy_name.clone()) {
                 continue;
             }
+            visited += 1;
             
-            let withdrawn = withdrawal_amount; // Assume successful
+            let cap = self.max_single_deploy.get(strategy_name).unwrap_or(U512::zero());
+            let tranche = if cap.is_zero() { queued } else { queued.min(cap) };
+            let remaining = queued - tranche;
+            self.queued_deployments.set(strategy_name, remaining);
+            let total_queued = self.total_queued.get_or_default();
+            self.total_queued.set(total_queued - tranche);
             
-            self.current_allocations.set(strategy_name, current_allocation - withdrawn);
-            total_withdrawn += withdrawn;
-        }
-        
-        self.total_allocated.set(total_allocated - total_withdrawn);
-        
-        total_withdrawn
-    }
-
-    /// Harvest yields from all strategies
-    pub fn harvest_all(&mut self) -> U512 {
-        self.access_control.only_admin_or_operator();
-        
-        let strategy_names = self.strategy_names.get_or_default();
-        let mut total_yield = U512::zero();
-        
-        for strategy_name in strategy_names.iter() {
-            // For now, simulate yields
-            let allocation = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
-            let simulated_yield = allocation / U512::from(100u64); // 1% yield
+            let (deployed, fees) = self.place_tranche(strategy_name, tranche, 0);
+            total_deployed += deployed;
             
-            total_yield += simulated_yield;
+            let rejected = tranche.saturating_sub(deployed + fees);
+            if !rejected.is_zero() {
+                let idle = self.idle_balance.get_or_default();
+                self.idle_balance.set(idle + rejected);
+            }
             
-            self.env().emit_event(YieldHarvested {
+            self.env().emit_event(DeploymentQueueDrained {
                 strategy_name: strategy_name.clone(),
-                yield_amount: simulated_yield,
+                amount: deployed,
+                remaining,
                 timestamp: self.env().get_block_time(),
             });
         }
         
-        total_yield
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set(total + total_deployed);
+        
+        total_deployed
     }
 
-    /// Rebalance strategies based on target allocations
+    /// Cap what a strategy receives per transaction (admin only, zero = no cap)
+    pub fn set_max_single_deploy(&mut self, name: String, amount: U512) {
+        self.access_control.only_admin();
+        let old = self.max_single_deploy.get(&name).unwrap_or(U512::zero());
+        self.max_single_deploy.set(&name, amount);
+        self.config_changed(format!("max_single_deploy.{}", name), old, amount);
+    }
+
+    pub fn get_max_single_deploy(&self, name: String) -> U512 {
+        self.max_single_deploy.get(&name).unwrap_or(U512::zero())
+    }
+
+    /// Queued amount per strategy, for strategies with a queue
+    pub fn get_queued_deployments(&self) -> Vec<(String, U512)> {
+        self.strategy_names.get_or_default()
+            .into_iter()
+            .map(|name| {
+                let queued = self.queued_deployments.get(&name).unwrap_or(U512::zero());
+                (name, queued)
+            })
+            .filter(|(_, queued)| !queued.is_zero())
+            .collect()
+    }
+
+    /// Total lstCSPR waiting in the deployment queue
+    pub fn get_total_queued(&self) -> U512 {
+        self.total_queued.get_or_default()
+    }
+
+    /// Queue a withdrawal into the open batch instead of withdrawing now
     /// 
-    /// This function should be called periodically (e.g., every 12 hours)
-    /// by an off-chain keeper or admin
-    pub fn rebalance(&mut self) {
-        self.access_control.only_admin_or_operator();
-        
-        let current_time = self.env().get_block_time();
-        let last_rebalance = self.last_rebalance.get_or_default();
-        let min_interval = self.min_rebalance_interval.get_or_default();
+    /// The caller (VaultManager, for exits the instant pool can't cover)
+    /// owns the returned claim and redeems it with `take_batched_claim`
+    /// once the batch is flushed. Reverts unless the caller is the vault
+    /// and batching is enabled.
+    pub fn queue_withdrawal(&mut self, amount: U512) -> U256 {
+        if !self.is_vault(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if !self.withdrawal_batching.get_or_default() || amount.is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
         
-        if current_time < last_rebalance + min_interval {
-            return;
+        let queued = self.batch_queued.get_or_default();
+        if queued.is_zero() {
+            self.batch_opened_at.set(self.env().get_block_time());
         }
+        self.batch_queued.set(queued + amount);
         
+        let claim_id = self.next_batch_claim_id.get_or_default();
+        self.next_batch_claim_id.set(claim_id + U256::one());
+        let batch = self.current_batch.get_or_default();
+        self.batch_claim_owners.set(&claim_id, self.env().caller());
+        self.batch_claim_amounts.set(&claim_id, amount);
+        self.batch_claim_batches.set(&claim_id, batch);
         
-        self.last_rebalance.set(current_time);
+        self.batch_claims_queued.set(self.batch_claims_queued.get_or_default() + 1);
         
-        self.env().emit_event(Rebalance {
-            old_allocations: Vec::new(),
-            new_allocations: Vec::new(),
-            timestamp: current_time,
+        self.env().emit_event(WithdrawalQueued {
+            claim_id,
+            batch,
+            amount,
+            batch_total: queued + amount,
+            timestamp: self.env().get_block_time(),
         });
+        
+        claim_id
     }
 
-    /// Calculate blended APY across all strategies
-    pub fn calculate_blended_apy(&self) -> U256 {
-        let total_allocated = self.total_allocated.get_or_default();
-        
-        if total_allocated.is_zero() {
-            return U256::zero();
+    /// Withdraw everything queued in the open batch in one `withdraw` call
+    /// 
+    /// Returns the amount received (zero if nothing is queued). Callable
+    /// by keepers, operators and admins; `take_batched_claim` also flushes
+    /// once the batch window has passed.
+    pub fn flush_withdrawals(&mut self) -> U512 {
+        self.access_control.only_maintainer();
+        self.flush_batch()
+    }
+
+    /// Redeem a flushed claim (claim owner only)
+    /// 
+    /// Flushes the claim's batch first if it is still open and older than
+    /// the batch window. Pays the claim's share of what the batch received,
+    /// so a short batch is shared pro rata. Reverts while the batch is
+    /// still open within its window.
+    pub fn take_batched_claim(&mut self, claim_id: U256) -> U512 {
+        let owner = self.batch_claim_owners.get(&claim_id)
+            .unwrap_or_else(|| self.env().revert(VaultError::WithdrawalRequestNotFound));
+        if owner != self.env().caller() || self.batch_claim_taken.get(&claim_id).unwrap_or(false) {
+            self.env().revert(VaultError::InvalidRequest);
         }
         
-        let strategy_names = self.strategy_names.get_or_default();
-        let mut weighted_apy = U256::zero();
-        
-        for strategy_name in strategy_names.iter() {
-            let allocation = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
-            
-            if allocation.is_zero() {
-                continue;
+        let batch = self.batch_claim_batches.get(&claim_id).unwrap_or_default();
+        if batch == self.current_batch.get_or_default() {
+            if !self.batch_window_elapsed() {
+                self.env().revert(VaultError::TimelockNotExpired);
             }
-            
-            // For now, use simulated APYs
-            let strategy_apy = if strategy_name == "dex" {
-                U256::from(1200u64) // 12%
-            } else if strategy_name == "lending" {
-                U256::from(1500u64) // 15%
-            } else if strategy_name == "crosschain" {
-                U256::from(1850u64) // 18.5%
-            } else {
-                U256::from(1000u64) // 10%
-            };
-            
-            // Convert U512 to U256 for calculations (using as_u128 which is safe for small values)
-            let allocation_u256 = U256::from(allocation.as_u128());
-            let total_allocated_u256 = U256::from(total_allocated.as_u128());
-            let weight = (allocation_u256 * U256::from(10000u64)) / total_allocated_u256;
-            
-            // Add weighted APY
-            weighted_apy += (strategy_apy * weight) / U256::from(10000u64);
+            self.flush_batch();
         }
         
-        weighted_apy
+        self.batch_claim_taken.set(&claim_id, true);
+        let amount = self.batch_claim_amounts.get(&claim_id).unwrap_or_default();
+        let requested = self.batch_requested.get(&batch).unwrap_or_default();
+        let received = self.batch_received.get(&batch).unwrap_or_default();
+        if requested.is_zero() {
+            return U512::zero();
+        }
+        (amount * received / requested).min(amount)
     }
 
-    /// Add a strategy (admin only)
-    pub fn add_strategy(&mut self, name: String, strategy_address: Address) {
+    /// Turn withdrawal batching on or off and set the batch window (admin only)
+    /// 
+    /// The window is capped at `MAX_BATCH_WINDOW`. Turning batching off
+    /// doesn't touch claims already queued; they are still flushed and
+    /// redeemed as usual.
+    pub fn set_withdrawal_batching(&mut self, enabled: bool, window: u64) {
         self.access_control.only_admin();
+        if window > MAX_BATCH_WINDOW {
+            self.env().revert(VaultError::InvalidRequest);
+        }
         
-        self.strategies.set(&name, strategy_address);
-        
-        let mut names = self.strategy_names.get_or_default();
-        if !names.contains(&name) {
-            names.push(name.clone());
-            self.strategy_names.set(names);
+        let old = self.withdrawal_batching.get_or_default();
+        self.withdrawal_batching.set(enabled);
+        self.config_changed(String::from("withdrawal_batching"), old, enabled);
+        let old = self.batch_window.get_or_default();
+        self.batch_window.set(window);
+        self.config_changed(String::from("batch_window"), old, window);
+    }
+
+    /// Whether exits are batched, and the batch window
+    pub fn get_withdrawal_batching(&self) -> (bool, u64) {
+        (self.withdrawal_batching.get_or_default(), self.batch_window.get_or_default())
+    }
+
+    /// Amount queued in the open batch
+    pub fn get_queued_withdrawals(&self) -> U512 {
+        self.batch_queued.get_or_default()
+    }
+
+    /// Whether a claim's batch has been flushed or may be by its owner now
+    pub fn is_batched_claim_ready(&self, claim_id: U256) -> bool {
+        if self.batch_claim_owners.get(&claim_id).is_none() || self.batch_claim_taken.get(&claim_id).unwrap_or(false) {
+            return false;
         }
         
-        // Initialize allocation to 0
-        self.current_allocations.set(&name, U512::zero());
-        self.target_allocations.set(&name, 0);
+        let batch = self.batch_claim_batches.get(&claim_id).unwrap_or_default();
+        batch < self.current_batch.get_or_default() || self.batch_window_elapsed()
     }
 
-    /// Remove a strategy (admin only)
-    pub fn remove_strategy(&mut self, name: String) {
+    pub fn get_batching_stats(&self) -> BatchingStats {
+        let batches_flushed = self.batches_flushed.get_or_default();
+        BatchingStats {
+            claims_queued: self.batch_claims_queued.get_or_default(),
+            batches_flushed,
+            withdrawals_saved: self.batch_claims_flushed.get_or_default().saturating_sub(batches_flushed),
+            volume_batched: self.batch_volume.get_or_default(),
+        }
+    }
+
+    /// Set the withdrawal policy (admin only)
+    pub fn set_withdrawal_policy(&mut self, policy: u8) {
         self.access_control.only_admin();
-        
-        
-        let mut names = self.strategy_names.get_or_default();
-        names.retain(|n| n != &name);
-        self.strategy_names.set(names);
+        if WithdrawalPolicy::from_u8(policy).is_none() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let old = self.withdrawal_policy.get_or_default();
+        self.withdrawal_policy.set(policy);
+        self.config_changed("withdrawal_policy".to_string(), old, policy);
     }
 
-    /// Set target allocations (admin only)
-    /// 
-    /// Allocations should sum to 100%
-    pub fn set_target_allocations(&mut self, allocations: Vec<(String, u8)>) {
+    /// Withdrawal policy in force (`WithdrawalPolicy` as u8)
+    pub fn get_withdrawal_policy(&self) -> u8 {
+        self.withdrawal_policy_in_force().to_u8()
+    }
+
+    /// Change a registered strategy's liquidity tier (admin only)
+    pub fn set_liquidity_tier(&mut self, name: String, tier: u8) {
         self.access_control.only_admin();
-        
-        let max_strategy = self.max_strategy_allocation.get_or_default();
-        let max_crosschain = self.max_crosschain_allocation.get_or_default();
-        
+        if self.strategies.get(&name).is_none() || LiquidityTier::from_u8(tier).is_none() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let old = self.liquidity_tier(&name).to_u8();
+        self.liquidity_tiers.set(&name, tier);
+        self.config_changed(format!("liquidity_tier.{}", name), old, tier);
+    }
+
+    /// Liquidity tier of a strategy (`LiquidityTier` as u8, Slow if never declared)
+    pub fn get_liquidity_tier(&self, name: String) -> u8 {
+        self.liquidity_tier(&name).to_u8()
+    }
+
+    /// Recorded allocations summed by tier: (Instant, Fast, Slow)
+    /// 
+    /// The idle buffer and deployment queue are paid out first by
+    /// `withdraw` and count as Instant. Quarantined strategies are left out.
+    pub fn get_liquidity_by_tier(&self) -> (U512, U512, U512) {
+        let mut instant = self.idle_balance.get_or_default() + self.total_queued.get_or_default();
+        let mut fast = U512::zero();
+        let mut slow = U512::zero();
+        
+        for name in self.strategy_names.get_or_default().iter() {
+            if self.quarantined.get(name).unwrap_or(false) {
+                continue;
+            }
+            let allocation = self.current_allocations.get(name).unwrap_or_default();
+            match self.liquidity_tier(name) {
+                LiquidityTier::Instant => instant += allocation,
+                LiquidityTier::Fast => fast += allocation,
+                LiquidityTier::Slow => slow += allocation,
+            }
+        }
+        (instant, fast, slow)
+    }
+
+    /// Harvest yields from all strategies (admin, operator or keeper)
+    /// 
+    /// Returns lstCSPR only: the strategies' own yield, reward tokens swapped
+    /// on the way in and proceeds of earlier liquidations. Reward tokens
+    /// without an auto-swap route, or whose quote is below the route's
+    /// minimum rate, go to escrow and count nothing until liquidated.
+    /// 
+    /// The yield is forwarded to the caller (the YieldAggregator) and is not
+    /// part of any allocation: a strategy must pay out what it harvests, so
+    /// re-deploying it through `allocate` counts it exactly once. A
+    /// strategy that books its harvest without paying it out is
+    /// quarantined rather than failing the whole harvest.
+    /// 
+    /// Strategies holding lstCSPR pay their yield to the router as they
+    /// harvest. What arrived, measured on the router's token balance, is
+    /// transferred on to the caller; `allocate` and `withdraw` stay
+    /// accounting-only.
+    pub fn harvest_all(&mut self) -> U512 {
+        self.access_control.only_maintainer();
+        let recipient = self.env().caller();
+        let holdings = self.lst_holdings();
+        let (harvested, _) = self.harvest_strategies();
+        let transferred = self.forward_harvested_lst(holdings, recipient);
+        
+        self.env().emit_event(HarvestForwarded {
+            recipient,
+            amount: harvested,
+            transferred,
+            timestamp: self.env().get_block_time(),
+        });
+        harvested
+    }
+
+    /// lstCSPR `harvest_all` would take from each strategy now, in order
+    /// 
+    /// Recovered gains plus the strategy's `preview_harvest` (recovered
+    /// gains only while quarantined). Reward tokens are left out: whether
+    /// they swap or go to escrow depends on a quote taken at harvest time.
+    /// Liquidation proceeds come on top (`get_reward_proceeds`).
+    pub fn preview_harvest_all(&self) -> Vec<(String, U512)> {
+        let mut previews = Vec::new();
+        for strategy_name in self.strategy_names.get_or_default().iter() {
+            let recovered = self.recovered_gains.get(strategy_name).unwrap_or(U512::zero());
+            let harvested = if self.is_quarantined(strategy_name.clone()) {
+                recovered
+            } else {
+                recovered + match self.strategies.get(strategy_name) {
+                    Some(address) => self.to_lst_value(
+                        strategy_name,
+                        StrategyContractRef::new(self.env(), address).preview_harvest(),
+                    ),
+                    None => U512::zero(),
+                }
+            };
+            previews.push((strategy_name.clone(), harvested));
+        }
+        previews
+    }
+
+    /// Configure how a reward token is converted to lstCSPR (admin only)
+    pub fn set_reward_route(&mut self, token: Address, dex: Address, auto_swap: bool, min_rate: U512) {
+        self.access_control.only_admin();
+        
+        let old = self.route_value(token);
+        self.reward_dex.set(&token, dex);
+        self.reward_auto_swap.set(&token, auto_swap);
+        self.reward_min_rate.set(&token, min_rate);
+        
+        let new = self.route_value(token);
+        self.config_changed(format!("reward_route.{}", config_address(Some(token))), old, new);
+    }
+
+    /// Swap a token's escrowed rewards to lstCSPR (admin only)
+    /// 
+    /// Reverts with `SlippageExceeded` if the swap returns less than
+    /// `min_out`, leaving the escrow untouched. The route's `min_rate` is not
+    /// applied here, so this is also how rewards held back by a low quote get
+    /// sold. Proceeds are reported by the next harvest.
+    pub fn liquidate_rewards(&mut self, token: Address, min_out: U512) -> U512 {
+        self.access_control.only_admin();
+        
+        let amount = self.reward_escrow.get(&token).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+        let dex = match self.reward_dex.get(&token) {
+            Some(dex) => dex,
+            None => self.env().revert(VaultError::InvalidRequest),
+        };
+        
+        let amount_out = self.swap_reward(token, dex, amount, min_out);
+        self.reward_escrow.set(&token, U512::zero());
+        let proceeds = self.reward_proceeds.get_or_default();
+        self.reward_proceeds.set(proceeds + amount_out);
+        
+        self.env().emit_event(RewardsLiquidated {
+            token,
+            amount_in: amount,
+            amount_out,
+            min_out,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        amount_out
+    }
+
+    pub fn get_reward_route(&self, token: Address) -> Option<RewardRoute> {
+        self.reward_dex.get(&token).map(|dex| RewardRoute {
+            dex,
+            auto_swap: self.reward_auto_swap.get(&token).unwrap_or(false),
+            min_rate: self.reward_min_rate.get(&token).unwrap_or_default(),
+        })
+    }
+
+    /// Reward tokens held in escrow
+    pub fn get_reward_escrow(&self, token: Address) -> U512 {
+        self.reward_escrow.get(&token).unwrap_or_default()
+    }
+
+    /// Liquidation proceeds not yet reported by a harvest
+    pub fn get_reward_proceeds(&self) -> U512 {
+        self.reward_proceeds.get_or_default()
+    }
+
+    /// Rebalance strategies based on target allocations
+    /// 
+    /// This function should be called periodically (e.g., every 12 hours)
+    /// by an off-chain keeper or admin
+    pub fn rebalance(&mut self) {
+        self.access_control.only_maintainer();
+        self.try_rebalance();
+    }
+
+    /// Run a health check across all registered strategies
+    /// 
+    /// Escalation ladder per strategy:
+    /// 1. A failed check (unhealthy flag or balance shortfall above
+    ///    `max_balance_drift_bps`) increments its consecutive failure count
+    /// 2. At `health_failure_threshold` consecutive failures its target
+    ///    allocation is set to zero and `StrategyUnhealthy` is emitted
+    /// 3. A shortfall above `critical_balance_drift_bps` or an APY above
+    ///    `max_strategy_apy_bps` quarantines it immediately
+    /// 
+    /// A passing check resets the failure count. Callable by keepers,
+    /// operators and admins.
+    pub fn health_check(&mut self) {
+        self.access_control.only_maintainer();
+        self.run_health_check();
+    }
+
+    /// Mark strategy positions to their reported balances
+    /// 
+    /// Losses are booked immediately. Gains are booked only if their sum
+    /// stays within `max_price_change_bps` of the total allocated; otherwise
+    /// the circuit breaker trips, the gains are parked in `pending_gains`
+    /// and an admin must release them. A pending drip release books its
+    /// next tranche here. Returns the gains booked by this call.
+    /// Callable by keepers, operators and admins.
+    pub fn sync(&mut self) -> U512 {
+        self.access_control.only_maintainer();
+        
+        let strategy_names = self.strategy_names.get_or_default();
+        let mut gains: Vec<(String, U512)> = Vec::new();
+        let mut total_gain = U512::zero();
+        
+        for strategy_name in strategy_names.iter() {
+            if self.is_quarantined(strategy_name.clone()) {
+                continue;
+            }
+            
+            let address = match self.strategies.get(strategy_name) {
+                Some(address) => address,
+                None => continue,
+            };
+            
+            // Funds in flight still belong to the position
+            let balance = self.strategy_balance(strategy_name, address)
+                + self.pending_withdrawals.get(strategy_name).unwrap_or(U512::zero());
+            let recorded = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            let pending = self.pending_gains.get(strategy_name).unwrap_or(U512::zero());
+            
+            if balance < recorded {
+                let total = self.total_allocated.get_or_default();
+                self.total_allocated.set(total.saturating_sub(recorded - balance));
+                self.set_allocation(strategy_name, balance);
+                self.clear_pending_gain(strategy_name);
+                continue;
+            }
+            
+            let gain = balance.saturating_sub(recorded + pending);
+            if !gain.is_zero() {
+                total_gain += gain;
+                gains.push((strategy_name.clone(), gain));
+            }
+        }
+        
+        let total_allocated = self.total_allocated.get_or_default();
+        let max_change = self.max_price_change_bps.get_or_default();
+        let change_bps = if total_allocated.is_zero() {
+            U512::zero()
+        } else {
+            ratio_bps(total_gain, total_allocated).unwrap_or_revert(&self.env())
+        };
+        
+        let mut booked = U512::zero();
+        
+        if change_bps > U512::from(max_change) {
+            for (strategy_name, gain) in gains.iter() {
+                let pending = self.pending_gains.get(strategy_name).unwrap_or(U512::zero());
+                self.pending_gains.set(strategy_name, pending + *gain);
+            }
+            let total_pending = self.total_pending_gains.get_or_default();
+            self.total_pending_gains.set(total_pending + total_gain);
+            
+            self.env().emit_event(CircuitBreakerTripped {
+                amount: total_gain,
+                change_bps: change_bps.min(U512::from(u32::MAX)).as_u32(),
+                max_change_bps: max_change,
+                timestamp: self.env().get_block_time(),
+            });
+        } else {
+            for (strategy_name, gain) in gains.iter() {
+                self.book_gain(strategy_name, *gain);
+            }
+            booked += total_gain;
+        }
+        
+        booked + self.drip_pending_gains()
+    }
+
+    /// Release gains held back by the circuit breaker (admin only)
+    /// 
+    /// `drips` spreads the release over that many syncs, the first tranche
+    /// being booked immediately; 0 or 1 books everything now.
+    pub fn release_pending_gains(&mut self, drips: u32) -> U512 {
+        self.access_control.only_admin();
+        
+        if self.total_pending_gains.get_or_default().is_zero() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+        
+        self.gain_drips_remaining.set(drips.max(1));
+        self.drip_pending_gains()
+    }
+
+    /// Set the circuit breaker threshold (admin only)
+    pub fn set_max_price_change_bps(&mut self, max_change_bps: u32) {
+        self.access_control.only_admin();
+        
+        if max_change_bps == 0 || max_change_bps > 10000 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.max_price_change_bps.get_or_default();
+        self.max_price_change_bps.set(max_change_bps);
+        self.config_changed("max_price_change_bps".to_string(), old, max_change_bps);
+    }
+
+    /// Bridge fees booked for a strategy
+    pub fn get_bridge_fees(&self, name: String) -> U512 {
+        self.bridge_fees.get(&name).unwrap_or(U512::zero())
+    }
+
+    pub fn get_lifetime_bridge_fees(&self) -> U512 {
+        self.lifetime_bridge_fees.get_or_default()
+    }
+
+    /// Exit costs booked for a strategy (swap fees, withdrawal penalties)
+    pub fn get_exit_costs(&self, name: String) -> U512 {
+        self.exit_costs.get(&name).unwrap_or(U512::zero())
+    }
+
+    pub fn get_lifetime_exit_costs(&self) -> U512 {
+        self.lifetime_exit_costs.get_or_default()
+    }
+
+    /// Expected cost of `withdraw(amount)` under the policy in force (lstCSPR)
+    /// 
+    /// Sums what each strategy on the plan expects to keep back; idle and
+    /// queued funds are free. Callers setting a minimum output should
+    /// expect `amount` minus this.
+    pub fn estimate_withdrawal_cost(&self, amount: U512) -> U512 {
+        let free = self.idle_balance.get_or_default() + self.total_queued.get_or_default();
+        let amount = amount.saturating_sub(free);
+        let total_allocated = self.total_allocated.get_or_default();
+        if amount.is_zero() || total_allocated.is_zero() {
+            return U512::zero();
+        }
+        
+        let policy = self.withdrawal_policy_in_force();
+        let mut covered = U512::zero();
+        let mut cost = U512::zero();
+        for strategy_name in self.withdrawal_order(policy, amount).iter() {
+            let allocation = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            let tranche = Self::tranche_target(policy, amount, allocation, total_allocated, covered);
+            if tranche.is_zero() {
+                continue;
+            }
+            
+            let tranche_cost = self.exit_cost_estimate(strategy_name, tranche).min(tranche);
+            covered += tranche - tranche_cost;
+            cost += tranche_cost;
+        }
+        cost
+    }
+
+    pub fn get_pending_gains(&self) -> U512 {
+        self.total_pending_gains.get_or_default()
+    }
+
+    pub fn get_max_price_change_bps(&self) -> u32 {
+        self.max_price_change_bps.get_or_default()
+    }
+
+    /// Set the LiquidStaking that prices native strategy conversions (admin only)
+    pub fn set_liquid_staking(&mut self, liquid_staking: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.liquid_staking.get());
+        self.liquid_staking.set(liquid_staking);
+        self.config_changed("liquid_staking".to_string(), old, config_address(Some(liquid_staking)));
+    }
+
+    pub fn get_liquid_staking(&self) -> Option<Address> {
+        self.liquid_staking.get()
+    }
+
+    // EXPOSURE CAPS
+
+    /// Set the VaultManager the TVL exposure caps are measured against (admin only)
+    /// 
+    /// Until it is set the caps are not enforced. Only this address may
+    /// call `allocate`, `withdraw` and `queue_withdrawal`.
+    pub fn set_vault_address(&mut self, vault: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.vault.get());
+        self.vault.set(vault);
+        self.config_changed("vault".to_string(), old, config_address(Some(vault)));
+    }
+
+    pub fn get_vault_address(&self) -> Option<Address> {
+        self.vault.get()
+    }
+
+    /// Set the YieldAggregator allowed to `allocate` compounded yield (admin only)
+    pub fn set_yield_aggregator(&mut self, aggregator: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.yield_aggregator.get());
+        self.yield_aggregator.set(aggregator);
+        self.config_changed("yield_aggregator".to_string(), old, config_address(Some(aggregator)));
+    }
+
+    pub fn get_yield_aggregator(&self) -> Option<Address> {
+        self.yield_aggregator.get()
+    }
+
+    /// Propose a cap on one strategy's share of vault TVL (admin only)
+    /// 
+    /// In basis points of the vault's `total_assets`; 0 removes the cap.
+    /// Applied with `apply_strategy_tvl_cap` after `TIMELOCK_DURATION`;
+    /// a new proposal replaces the pending one and restarts the delay.
+    /// Emits `ExposureCapProposed`.
+    pub fn propose_strategy_tvl_cap(&mut self, name: String, max_tvl_bps: u32) {
+        self.access_control.only_admin();
+        if self.strategies.get(&name).is_none() || max_tvl_bps as u64 > BPS_DENOMINATOR {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let eta = self.env().get_block_time() + TIMELOCK_DURATION;
+        self.pending_max_tvl_bps.set(&name, max_tvl_bps);
+        self.pending_max_tvl_eta.set(&name, eta);
+        self.emit_exposure_cap_proposed(Some(name), max_tvl_bps, eta);
+    }
+
+    /// Apply a strategy's proposed TVL cap once its delay has run (admin only)
+    pub fn apply_strategy_tvl_cap(&mut self, name: String) {
+        self.access_control.only_admin();
+        let eta = self.pending_max_tvl_eta.get(&name).unwrap_or(0);
+        if eta == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        if self.env().get_block_time() < eta {
+            self.env().revert(VaultError::TimelockNotExpired);
+        }
+        
+        let cap = self.pending_max_tvl_bps.get(&name).unwrap_or(0);
+        self.pending_max_tvl_eta.set(&name, 0);
+        let old = self.max_tvl_bps.get(&name).unwrap_or(0);
+        self.max_tvl_bps.set(&name, cap);
+        self.config_changed(format!("max_tvl_bps.{}", name), old, cap);
+    }
+
+    /// Propose a cap on all cross-chain positions as a share of vault TVL (admin only)
+    /// 
+    /// Same rules as `propose_strategy_tvl_cap`.
+    pub fn propose_crosschain_tvl_cap(&mut self, max_tvl_bps: u32) {
+        self.access_control.only_admin();
+        if max_tvl_bps as u64 > BPS_DENOMINATOR {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let eta = self.env().get_block_time() + TIMELOCK_DURATION;
+        self.pending_crosschain_tvl_bps.set(max_tvl_bps);
+        self.pending_crosschain_tvl_eta.set(eta);
+        self.emit_exposure_cap_proposed(None, max_tvl_bps, eta);
+    }
+
+    /// Apply the proposed cross-chain TVL cap once its delay has run (admin only)
+    pub fn apply_crosschain_tvl_cap(&mut self) {
+        self.access_control.only_admin();
+        let eta = self.pending_crosschain_tvl_eta.get_or_default();
+        if eta == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        if self.env().get_block_time() < eta {
+            self.env().revert(VaultError::TimelockNotExpired);
+        }
+        
+        let cap = self.pending_crosschain_tvl_bps.get_or_default();
+        self.pending_crosschain_tvl_eta.set(0);
+        let old = self.max_crosschain_tvl_bps.get_or_default();
+        self.max_crosschain_tvl_bps.set(cap);
+        self.config_changed("max_crosschain_tvl_bps".to_string(), old, cap);
+    }
+
+    pub fn get_strategy_tvl_cap(&self, name: String) -> u32 {
+        self.max_tvl_bps.get(&name).unwrap_or(0)
+    }
+
+    pub fn get_crosschain_tvl_cap(&self) -> u32 {
+        self.max_crosschain_tvl_bps.get_or_default()
+    }
+
+    /// Proposed (cap, applicable from) for a strategy, if any
+    pub fn get_pending_strategy_tvl_cap(&self, name: String) -> Option<(u32, u64)> {
+        let eta = self.pending_max_tvl_eta.get(&name).unwrap_or(0);
+        (eta != 0).then(|| (self.pending_max_tvl_bps.get(&name).unwrap_or(0), eta))
+    }
+
+    /// Proposed (cap, applicable from) for cross-chain positions, if any
+    pub fn get_pending_crosschain_tvl_cap(&self) -> Option<(u32, u64)> {
+        let eta = self.pending_crosschain_tvl_eta.get_or_default();
+        (eta != 0).then(|| (self.pending_crosschain_tvl_bps.get_or_default(), eta))
+    }
+
+    /// Most a strategy may hold under its TVL caps right now
+    /// 
+    /// None when it is uncapped or no vault is wired.
+    pub fn get_exposure_limit(&self, name: String) -> Option<U512> {
+        self.exposure_limit(&name, self.vault_tvl()).map(|(limit, _)| limit)
+    }
+
+    /// Add the attached CSPR to the native float (admin only)
+    /// 
+    /// Native strategies are deployed out of this float; the lstCSPR taken
+    /// in exchange stays in the router until their CSPR comes back.
+    #[odra(payable)]
+    pub fn fund_native_float(&mut self) {
+        self.access_control.only_admin();
+        let amount = self.env().attached_value();
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        let float = self.native_float.get_or_default();
+        self.native_float.set(float + amount);
+    }
+
+    /// Set the cost of each conversion through the native float (admin only, max 5%)
+    pub fn set_native_conversion_fee_bps(&mut self, fee_bps: u32) {
+        self.access_control.only_admin();
+        if fee_bps > 500 {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        let old = self.native_conversion_fee_bps.get_or_default();
+        self.native_conversion_fee_bps.set(fee_bps);
+        self.config_changed("native_conversion_fee_bps".to_string(), old, fee_bps);
+    }
+
+    pub fn get_native_conversion_fee_bps(&self) -> u32 {
+        self.native_conversion_fee_bps.get_or_default()
+    }
+
+    /// Native float as (CSPR available, lstCSPR taken in exchange)
+    pub fn get_native_float(&self) -> (U512, U512) {
+        (self.native_float.get_or_default(), self.float_lst.get_or_default())
+    }
+
+    pub fn is_native_strategy(&self, name: String) -> bool {
+        self.is_native(&name)
+    }
+
+    /// Conversion costs booked for a strategy (lstCSPR)
+    pub fn get_conversion_costs(&self, name: String) -> U512 {
+        self.conversion_costs.get(&name).unwrap_or(U512::zero())
+    }
+
+    pub fn get_lifetime_conversion_costs(&self) -> U512 {
+        self.lifetime_conversion_costs.get_or_default()
+    }
+
+    /// Unbooked yield across strategies, read without syncing
+    ///
+    /// Queries at most `max_strategies` strategies to bound gas. Quarantined
+    /// strategies and those whose last health report failed keep their
+    /// recorded allocation (no yield). Gains already parked by the circuit
+    /// breaker are excluded, and the total is capped at the breaker limit.
+    pub fn get_pending_yield(&self, max_strategies: u32) -> U512 {
+        let strategy_names = self.strategy_names.get_or_default();
+        let mut total = U512::zero();
+
+        for strategy_name in strategy_names.iter().take(max_strategies as usize) {
+            if self.is_quarantined(strategy_name.clone())
+                || !self.report_healthy.get(strategy_name).unwrap_or(true)
+            {
+                continue;
+            }
+
+            let address = match self.strategies.get(strategy_name) {
+                Some(address) => address,
+                None => continue,
+            };
+
+            let balance = self.strategy_balance(strategy_name, address);
+            let recorded = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            let pending = self.pending_gains.get(strategy_name).unwrap_or(U512::zero());
+            total += balance.saturating_sub(recorded + pending);
+        }
+
+        let cap = bps_of(self.total_allocated.get_or_default(), self.max_price_change_bps.get_or_default());
+        total.min(cap)
+    }
+
+    /// KeeperHub entry point: harvest, rebalance or health check
+    /// 
+    /// Returns `false` instead of reverting so the hub can continue its
+    /// batch (caller without the keeper role, a strategy that failed its
+    /// harvest, rebalance not yet due, unknown entrypoint).
+    pub fn run_keeper_job(&mut self, entrypoint: u8) -> bool {
+        if !self.access_control.has_role(Role::Keeper.to_u8(), self.env().caller()) {
+            return false;
+        }
+        
+        match entrypoint {
+            JOB_HARVEST_ALL => {
+                let holdings = self.lst_holdings();
+                let (_, all_harvested) = self.harvest_strategies();
+                if let Some(vault) = self.vault_address.get() {
+                    self.forward_harvested_lst(holdings, vault);
+                }
+                all_harvested
+            }
+            JOB_REBALANCE => self.try_rebalance(),
+            JOB_HEALTH_CHECK => {
+                self.run_health_check();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Book an overdue pending withdrawal as a realized loss (admin only)
+    /// 
+    /// Only after the strategy's deadline has passed. The amount leaves the
+    /// recorded allocation, the strategy is quarantined and the amount stays
+    /// recoverable through `recover_written_off`.
+    pub fn write_off_pending(&mut self, strategy: String, amount: U512) {
+        self.access_control.only_admin();
+        
+        let deadline = self.pending_withdrawal_deadlines.get(&strategy).unwrap_or(0);
+        if self.env().get_block_time() < deadline {
+            self.env().revert(VaultError::TimelockNotExpired);
+        }
+        
+        let remaining = self.release_pending_withdrawal(&strategy, amount);
+        
+        let written_off = self.written_off.get(&strategy).unwrap_or(U512::zero());
+        self.written_off.set(&strategy, written_off + amount);
+        
+        self.env().emit_event(PendingWithdrawalWrittenOff {
+            strategy_name: strategy.clone(),
+            amount,
+            remaining_pending: remaining,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        if !self.is_quarantined(strategy.clone()) {
+            self.quarantine(&strategy, String::from("write_off"));
+        }
+    }
+
+    /// Clear a pending withdrawal whose funds did arrive (admin only)
+    pub fn settle_pending_withdrawal(&mut self, strategy: String, amount: U512) {
+        self.access_control.only_admin();
+        
+        let remaining = self.release_pending_withdrawal(&strategy, amount);
+        self.record_withdrawn(&strategy, amount);
+        
+        self.env().emit_event(PendingWithdrawalSettled {
+            strategy_name: strategy,
+            amount,
+            remaining_pending: remaining,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Book funds that arrived after a write-off as extraordinary gain (admin only)
+    /// 
+    /// The amount is reported as yield by the next harvest, so it enters
+    /// the share price through the vault's locked-profit unlock.
+    pub fn recover_written_off(&mut self, strategy: String, amount: U512) {
+        self.access_control.only_admin();
+        
+        let written_off = self.written_off.get(&strategy).unwrap_or(U512::zero());
+        if amount.is_zero() || amount > written_off {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.written_off.set(&strategy, written_off - amount);
+        let recovered = self.recovered_gains.get(&strategy).unwrap_or(U512::zero());
+        self.recovered_gains.set(&strategy, recovered + amount);
+        
+        self.env().emit_event(WrittenOffRecovered {
+            strategy_name: strategy,
+            amount,
+            still_written_off: written_off - amount,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Set how long a strategy's withdrawals may stay in flight (admin only, 0 = default)
+    pub fn set_withdrawal_deadline(&mut self, strategy: String, seconds: u64) {
+        self.access_control.only_admin();
+        let old = self.withdrawal_deadline_secs.get(&strategy).unwrap_or(0);
+        self.withdrawal_deadline_secs.set(&strategy, seconds);
+        self.config_changed(format!("withdrawal_deadline.{}", strategy), old, seconds);
+    }
+
+    /// Wait before a strategy's pending withdrawals may be written off
+    pub fn get_withdrawal_deadline(&self, strategy: String) -> u64 {
+        match self.withdrawal_deadline_secs.get(&strategy).unwrap_or(0) {
+            0 => self.default_withdrawal_deadline.get_or_default(),
+            seconds => seconds,
+        }
+    }
+
+    /// Pending withdrawal of a strategy: (amount in flight, write-off deadline)
+    pub fn get_pending_withdrawal(&self, strategy: String) -> (U512, u64) {
+        (
+            self.pending_withdrawals.get(&strategy).unwrap_or(U512::zero()),
+            self.pending_withdrawal_deadlines.get(&strategy).unwrap_or(0),
+        )
+    }
+
+    /// Written-off amount of a strategy that has not been recovered
+    pub fn get_written_off(&self, strategy: String) -> U512 {
+        self.written_off.get(&strategy).unwrap_or(U512::zero())
+    }
+
+    /// Emergency exit a strategy: pull its funds and exclude it from allocation
+    /// 
+    /// Guardian or admin only. `health_check` uses the same path for
+    /// automatic quarantine.
+    pub fn quarantine_strategy(&mut self, name: String) {
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Guardian.to_u8(), caller)
+            && !self.access_control.has_role(Role::Admin.to_u8(), caller)
+        {
+            self.env().revert(AccessError::MissingRole);
+        }
+        
+        if self.strategies.get(&name).is_none() {
+            self.env().revert(crate::types::StrategyError::StrategyNotFound);
+        }
+        
+        if self.is_quarantined(name.clone()) {
+            return;
+        }
+        
+        self.quarantine(&name, String::from("manual"));
+    }
+
+    /// Drain a strategy in capped steps, e.g. one too large to unwind at once
+    /// 
+    /// Maintainer only. Zeroes the strategy's target so allocations skip it,
+    /// then pulls at most `max_per_call` through its `emergency_withdraw`.
+    /// Call repeatedly until `StrategyDrained` reports nothing remaining.
+    /// Drained funds move from the allocation to the idle balance. Returns
+    /// the amount received in this call.
+    pub fn drain_strategy(&mut self, name: String, max_per_call: U512) -> U512 {
+        self.access_control.only_maintainer();
+        
+        let address = match self.strategies.get(&name) {
+            Some(address) => address,
+            None => self.env().revert(crate::types::StrategyError::StrategyNotFound),
+        };
+        
+        self.target_allocations.set(&name, 0);
+        
+        let mut strategy = StrategyContractRef::new(self.env(), address);
+        let (withdrawn, conversion_cost) = if self.is_native(&name) {
+            let max_native = self.staking().lst_cspr_to_cspr_precise(max_per_call);
+            let native = strategy.emergency_withdraw(Some(max_native));
+            self.wrap_native(&name, native)
+        } else {
+            (strategy.emergency_withdraw(Some(max_per_call)), U512::zero())
+        };
+        
+        let recorded = self.current_allocations.get(&name).unwrap_or(U512::zero());
+        let released = (withdrawn + conversion_cost).min(recorded);
+        self.set_allocation(&name, recorded - released);
+        self.record_withdrawn(&name, withdrawn);
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set(total.saturating_sub(released));
+        // What came back waits in the router for the next withdrawal; the
+        // conversion cost is booked against the strategy
+        let idle = self.idle_balance.get_or_default();
+        self.idle_balance.set(idle + released.saturating_sub(conversion_cost));
+        
+        let remaining_balance = self.strategy_balance(&name, address);
+        self.env().emit_event(StrategyDrained {
+            strategy_name: name,
+            withdrawn,
+            remaining_balance,
+            remaining_allocation: recorded - released,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        withdrawn
+    }
+
+    /// Lift a quarantine (admin only)
+    /// 
+    /// The target allocation stays at zero until set again. Reverts unless
+    /// the strategy is registered and quarantined.
+    pub fn reinstate_strategy(&mut self, name: String) {
+        self.access_control.only_admin();
+        
+        if self.strategies.get(&name).is_none() || !self.quarantined.get(&name).unwrap_or(false) {
+            self.env().revert(crate::types::StrategyError::StrategyNotFound);
+        }
+        self.quarantined.set(&name, false);
+        self.health_failures.set(&name, 0);
+    }
+
+    /// Update health check thresholds (admin only)
+    pub fn set_health_thresholds(
+        &mut self,
+        failure_threshold: u8,
+        max_drift_bps: u32,
+        critical_drift_bps: u32,
+        max_apy_bps: U256,
+    ) {
+        self.access_control.only_admin();
+        
+        if failure_threshold == 0 || max_drift_bps > critical_drift_bps || critical_drift_bps > 10000 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_threshold = self.health_failure_threshold.get_or_default();
+        let old_drift = self.max_balance_drift_bps.get_or_default();
+        let old_critical = self.critical_balance_drift_bps.get_or_default();
+        let old_max_apy = self.max_strategy_apy_bps.get_or_default();
+        
+        self.health_failure_threshold.set(failure_threshold);
+        self.max_balance_drift_bps.set(max_drift_bps);
+        self.critical_balance_drift_bps.set(critical_drift_bps);
+        self.max_strategy_apy_bps.set(max_apy_bps);
+        
+        self.config_changed("health_failure_threshold".to_string(), old_threshold, failure_threshold);
+        self.config_changed("max_balance_drift_bps".to_string(), old_drift, max_drift_bps);
+        self.config_changed("critical_balance_drift_bps".to_string(), old_critical, critical_drift_bps);
+        self.config_changed("max_strategy_apy_bps".to_string(), old_max_apy, max_apy_bps);
+    }
+
+    /// Set how far a reading may stray from the smoothed APY (admin only)
+    /// 
+    /// Readings above `multiple` times the smoothed APY, or below it divided
+    /// by `multiple`, are left out of the average.
+    pub fn set_max_apy_deviation(&mut self, multiple: u32) {
+        self.access_control.only_admin();
+        
+        if multiple < 2 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.max_apy_deviation_multiple.get_or_default();
+        self.max_apy_deviation_multiple.set(multiple);
+        self.config_changed("max_apy_deviation_multiple".to_string(), old, multiple);
+    }
+
+    /// Smoothed APY of a strategy (bps); zero before its first health check
+    pub fn get_smoothed_apy(&self, name: String) -> U256 {
+        self.apy_ema.get(&name).unwrap_or(U256::zero())
+    }
+
+    /// Get the last health report for a strategy
+    pub fn get_health_report(&self, name: String) -> HealthReport {
+        HealthReport {
+            healthy: self.report_healthy.get(&name).unwrap_or(false),
+            apy: self.report_apy.get(&name).unwrap_or(U256::zero()),
+            balance: self.report_balance.get(&name).unwrap_or(U512::zero()),
+            recorded_allocation: self.current_allocations.get(&name).unwrap_or(U512::zero()),
+            drift_bps: self.report_drift_bps.get(&name).unwrap_or(0),
+            consecutive_failures: self.health_failures.get(&name).unwrap_or(0),
+            quarantined: self.quarantined.get(&name).unwrap_or(false),
+            over_exposed: self.report_over_exposed.get(&name).unwrap_or(false),
+            checked_at: self.report_time.get(&name).unwrap_or(0),
+        }
+    }
+
+    /// Check whether a strategy is quarantined
+    pub fn is_quarantined(&self, name: String) -> bool {
+        self.quarantined.get(&name).unwrap_or(false)
+    }
+
+    /// Grant a role on the router (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+
+    /// Revoke a role on the router (admin only)
+    pub fn revoke_role(&mut self, role: u8, account: Address) {
+        self.access_control.revoke_role(role, account);
+    }
+
+    /// Current members of a role
+    pub fn get_role_members(&self, role: u8) -> Vec<Address> {
+        self.access_control.get_role_members(role)
+    }
+
+    /// Calculate blended APY across all strategies
+    pub fn calculate_blended_apy(&self) -> U256 {
+        let total_allocated = self.total_allocated.get_or_default();
+        
+        if total_allocated.is_zero() {
+            return U256::zero();
+        }
+        
+        let strategy_names = self.strategy_names.get_or_default();
+        let mut weighted_apy = U256::zero();
+        
+        for strategy_name in strategy_names.iter() {
+            let allocation = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            
+            if allocation.is_zero() {
+                continue;
+            }
+            
+            // Smoothed APY once health checks have run, simulated until then
+            let strategy_apy = if let Some(smoothed) = self.apy_ema.get(strategy_name) {
+                smoothed
+            } else if strategy_name == "dex" {
+                U256::from(1200u64) // 12%
+            } else if strategy_name == "lending" {
+                U256::from(1500u64) // 15%
+            } else if strategy_name == "crosschain" {
+                U256::from(1850u64) // 18.5%
+            } else {
+                U256::from(1000u64) // 10%
+            };
+            
+            let weight = ratio_bps(allocation, total_allocated)
+                .and_then(safe_u512_to_u256)
+                .unwrap_or_revert(&self.env());
+            
+            // Add weighted APY
+            weighted_apy += mul_div_u256(strategy_apy, weight, U256::from(BPS_DENOMINATOR), Rounding::Down)
+                .unwrap_or_revert(&self.env());
+        }
+        
+        weighted_apy
+    }
+
+    /// Preview a rebalance to `targets` without changing any state
+    /// 
+    /// Moves are what `rebalance()` would execute with the same targets
+    /// stored. Unknown strategy names and broken caps (per-strategy,
+    /// cross-chain, capacity, 100% total) are reported as violations rather
+    /// than reverting.
+    pub fn simulate_allocation(&self, targets: Vec<(String, u8)>) -> AllocationSimulation {
+        let (moves, violations, _) = self.plan_rebalance(&targets);
+        
+        let mut total_churn = U512::zero();
+        let mut total_target = U512::zero();
+        let mut weighted_apy = U512::zero();
+        
+        for strategy_move in moves.iter() {
+            total_churn += strategy_move.withdraw + strategy_move.deploy;
+            total_target += strategy_move.target;
+            
+            if let Some(address) = self.strategies.get(&strategy_move.strategy_name) {
+                let apy = StrategyContractRef::new(self.env(), address).get_apy();
+                weighted_apy += strategy_move.target * safe_u256_to_u512(apy);
+            }
+        }
+        
+        let projected_apy = if total_target.is_zero() {
+            U256::zero()
+        } else {
+            safe_u512_to_u256(weighted_apy / total_target).unwrap_or_revert(&self.env())
+        };
+        
+        AllocationSimulation {
+            moves,
+            projected_apy,
+            violations,
+            total_churn,
+        }
+    }
+
+    /// Add a strategy (admin only)
+    /// 
+    /// The strategy is treated as Slow for liquidity-first withdrawals; use
+    /// `add_strategy_with_tier` to declare its tier.
+    pub fn add_strategy(&mut self, name: String, strategy_address: Address) {
+        self.add_strategy_with_tier(name, strategy_address, LiquidityTier::Slow.to_u8());
+    }
+
+    /// Add a strategy with its `LiquidityTier` (admin only)
+    pub fn add_strategy_with_tier(&mut self, name: String, strategy_address: Address, tier: u8) {
+        self.add_strategy_with_options(name, strategy_address, tier, false);
+    }
+
+    /// Add a strategy with its `LiquidityTier` and deposit token (admin only)
+    /// 
+    /// A `wants_native` strategy is deployed raw CSPR from the router's
+    /// native float and returns CSPR on withdrawal, which is swapped back to
+    /// lstCSPR. Its allocation stays lstCSPR-denominated at the exchange
+    /// rate of each conversion; requires `set_liquid_staking`.
+    pub fn add_strategy_with_options(&mut self, name: String, strategy_address: Address, tier: u8, wants_native: bool) {
+        self.access_control.only_admin();
+        if LiquidityTier::from_u8(tier).is_none() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        if wants_native && self.liquid_staking.get().is_none() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+        
+        // Strategies only accept deploy/withdraw/harvest from their router
+        StrategyContractRef::new(self.env(), strategy_address).set_router(self.env().self_address());
+        
+        self.strategies.set(&name, strategy_address);
+        self.liquidity_tiers.set(&name, tier);
+        self.wants_native.set(&name, wants_native);
+        
+        let mut names = self.strategy_names.get_or_default();
+        if !names.contains(&name) {
+            if names.len() >= MAX_STRATEGIES {
+                self.env().revert(VaultError::RateLimitExceeded);
+            }
+            names.push(name.clone());
+            self.strategy_names.set(names);
+        }
+        
+        // Initialize allocation to 0
+        self.set_allocation(&name, U512::zero());
+        self.target_allocations.set(&name, 0);
+    }
+
+    /// Remove a strategy (admin only)
+    pub fn remove_strategy(&mut self, name: String) {
+        self.access_control.only_admin();
+        
+        
+        let mut names = self.strategy_names.get_or_default();
+        names.retain(|n| n != &name);
+        self.strategy_names.set(names);
+    }
+
+    /// Set target allocations (admin only)
+    /// 
+    /// Allocations may sum to at most 100% less the idle buffer
+    pub fn set_target_allocations(&mut self, allocations: Vec<(String, u8)>) {
+        self.access_control.only_admin();
+        
+        let max_strategy = self.max_strategy_allocation.get_or_default();
+        let max_crosschain = self.max_crosschain_allocation.get_or_default();
+        
+        let mut total_pct: u16 = 0;
+        let mut crosschain_pct: u16 = 0;
+        
+        for (strategy_name, pct) in allocations.iter() {
+            // Validate constraints
+            if *pct > max_strategy {
+                self.env().revert(VaultError::InvalidRequest);
+            }
+            
+            if is_crosschain(strategy_name) {
+                crosschain_pct += *pct as u16;
+            }
+            
+            total_pct += *pct as u16;
+            
+            // Set target allocation
+            let old = self.target_allocations.get(strategy_name).unwrap_or(0);
+            self.target_allocations.set(strategy_name, *pct);
+            self.config_changed(format!("target_allocation.{}", strategy_name), old, *pct);
+        }
+        
+        // Validate total + idle buffer <= 100%
+        if total_pct + self.router_idle_buffer_pct.get_or_default() as u16 > 100 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        // Validate cross-chain limit
+        if crosschain_pct > max_crosschain as u16 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+    }
+
+    /// Get current allocation for a strategy
+    pub fn get_current_allocation(&self, strategy_name: String) -> U512 {
+        self.current_allocations.get(&strategy_name).unwrap_or(U512::zero())
+    }
+
+    /// Get target allocation percentage for a strategy
+    pub fn get_target_allocation(&self, strategy_name: String) -> u8 {
+        self.target_allocations.get(&strategy_name).unwrap_or(0)
+    }
+
+    /// Get total allocated amount
+    pub fn get_total_allocated(&self) -> U512 {
+        self.total_allocated.get_or_default()
+    }
+
+    /// Get all strategy names
+    pub fn get_strategy_names(&self) -> Vec<String> {
+        self.strategy_names.get_or_default()
+    }
+
+    /// Recorded allocation and target percentage of a strategy
+    pub fn get_allocation(&self, name: String) -> (U512, u8) {
+        (
+            self.current_allocations.get(&name).unwrap_or_default(),
+            self.target_allocations.get(&name).unwrap_or(0),
+        )
+    }
+
+    /// (name, recorded allocation, target percentage) for every registered strategy
+    pub fn get_all_allocations(&self) -> Vec<(String, U512, u8)> {
+        self.strategy_names
+            .get_or_default()
+            .into_iter()
+            .map(|name| {
+                let (current, target) = self.get_allocation(name.clone());
+                (name, current, target)
+            })
+            .collect()
+    }
+
+    /// Set the allocation history depth and snapshot epoch (admin only)
+    /// 
+    /// Snapshots whose slot moves under the new size drop out of the
+    /// retained window; IDs keep increasing regardless. With an epoch set,
+    /// an action in the same epoch as the latest snapshot overwrites it,
+    /// so each epoch keeps the state it ended with.
+    pub fn set_allocation_history(&mut self, size: u64, epoch: u64) {
+        self.access_control.only_admin();
+        
+        if size == 0 || size > MAX_ALLOCATION_HISTORY {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = (self.allocation_history_size.get_or_default(), self.allocation_snapshot_epoch.get_or_default());
+        self.allocation_history_size.set(size);
+        self.allocation_snapshot_epoch.set(epoch);
+        self.config_changed("allocation_history_size".to_string(), old.0, size);
+        self.config_changed("allocation_snapshot_epoch".to_string(), old.1, epoch);
+    }
+
+    /// (history size, snapshot epoch in seconds)
+    pub fn get_allocation_history_config(&self) -> (u64, u64) {
+        (self.allocation_history_size.get_or_default(), self.allocation_snapshot_epoch.get_or_default())
+    }
+
+    /// Get an allocation snapshot by ID, if it is still inside the retained window
+    pub fn get_allocation_snapshot(&self, id: u64) -> Option<AllocationSnapshot> {
+        if id == 0 || id > self.allocation_snapshot_id.get_or_default() {
+            return None;
+        }
+        
+        let slot = id % self.allocation_history_size.get_or_default().max(1);
+        if self.snapshot_ids.get(&slot) != Some(id) {
+            // Evicted
+            return None;
+        }
+        
+        Some(AllocationSnapshot {
+            id,
+            action: self.snapshot_actions.get(&slot).unwrap_or_default(),
+            total_allocated: self.snapshot_totals.get(&slot).unwrap_or_default(),
+            allocations: self.snapshot_allocations.get(&slot).unwrap_or_default(),
+            timestamp: self.snapshot_times.get(&slot).unwrap_or_default(),
+        })
+    }
+
+    /// Up to `n_latest` most recent allocation snapshots, newest first
+    pub fn get_allocation_history(&self, n_latest: u64) -> Vec<AllocationSnapshot> {
+        let last_id = self.allocation_snapshot_id.get_or_default();
+        let window = n_latest.min(self.allocation_history_size.get_or_default()).min(last_id);
+        
+        let mut snapshots = Vec::new();
+        for id in ((last_id - window + 1)..=last_id).rev() {
+            if let Some(snapshot) = self.get_allocation_snapshot(id) {
+                snapshots.push(snapshot);
+            }
+        }
+        snapshots
+    }
+
+    /// Allocations in force at `timestamp`: the latest snapshot taken at
+    /// or before it
+    /// 
+    /// `None` if that snapshot has been evicted or none was taken yet.
+    pub fn get_allocation_at(&self, timestamp: u64) -> Option<AllocationSnapshot> {
+        let last_id = self.allocation_snapshot_id.get_or_default();
+        let window = self.allocation_history_size.get_or_default().min(last_id);
+        
+        ((last_id - window + 1)..=last_id)
+            .rev()
+            .filter_map(|id| self.get_allocation_snapshot(id))
+            .find(|snapshot| snapshot.timestamp <= timestamp)
+    }
+
+    /// Lifetime flows and realized APY of a strategy
+    /// 
+    /// The time-weighted allocation includes the current one up to now.
+    /// Only harvests count as realized yield; gains booked by `sync` raise
+    /// the allocation instead.
+    pub fn get_strategy_performance(&self, name: String) -> StrategyPerformance {
+        let now = self.env().get_block_time();
+        let activated_at = self.perf_activated_at.get(&name);
+        let elapsed = activated_at.map_or(0, |activated_at| now.saturating_sub(activated_at));
+        let avg_allocation = if elapsed == 0 {
+            U512::zero()
+        } else {
+            self.allocation_seconds(&name, now) / U512::from(elapsed)
+        };
+        let harvested = self.perf_harvested.get(&name).unwrap_or_default();
+        let realized_apy = annualize_bps(harvested, avg_allocation, elapsed).unwrap_or_revert(&self.env());
+        
+        StrategyPerformance {
+            cumulative_deployed: self.perf_deployed.get(&name).unwrap_or_default(),
+            cumulative_withdrawn: self.perf_withdrawn.get(&name).unwrap_or_default(),
+            cumulative_harvested: harvested,
+            avg_allocation,
+            activated_at,
+            days_active: elapsed / 86400,
+            realized_apy: safe_u512_to_u256(realized_apy).unwrap_or_revert(&self.env()),
+        }
+    }
+
+    /// Registered strategies by realized APY, best first
+    /// 
+    /// Ties keep registration order. At most `MAX_STRATEGIES` entries.
+    pub fn get_performance_ranking(&self) -> Vec<(String, U256)> {
+        let mut ranking: Vec<(String, U256)> = self.strategy_names.get_or_default()
+            .into_iter()
+            .map(|name| {
+                let apy = self.get_strategy_performance(name.clone()).realized_apy;
+                (name, apy)
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.1.cmp(&a.1));
+        ranking
+    }
+
+    /// Contract address of a registered strategy
+    pub fn get_strategy_address(&self, name: String) -> Option<Address> {
+        self.strategies.get(&name)
+    }
+
+    /// (name, recorded allocation, live strategy balance) for every registered strategy
+    /// 
+    /// Read-only: drift shows up here before the next `sync` books it.
+    /// Withdrawals still in flight count in the recorded figure only.
+    pub fn get_reconciliation(&self) -> Vec<(String, U512, U512)> {
+        let mut rows = Vec::new();
+        for name in self.strategy_names.get_or_default() {
+            let address = match self.strategies.get(&name) {
+                Some(address) => address,
+                None => continue,
+            };
+            let recorded = self.current_allocations.get(&name).unwrap_or_default();
+            let reported = self.strategy_balance(&name, address);
+            rows.push((name, recorded, reported));
+        }
+        rows
+    }
+
+    // BUILD INFO
+
+    /// Crate version as (major, minor, patch)
+    pub fn get_version(&self) -> (u32, u32, u32) {
+        self.build_info.version()
+    }
+
+    /// Feature flags this deployment supports (see `ROUTER_FEATURES`)
+    pub fn get_features(&self) -> Vec<String> {
+        self.build_info.features()
+    }
+
+    /// Git commit this deployment was built from, when known
+    pub fn get_git_hash(&self) -> Option<String> {
+        self.build_info.git_hash()
+    }
+
+    // TEST HOOKS (compiled out of production builds)
+
+    /// Overwrite a strategy's tracked allocation without touching the strategy
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_allocation(&mut self, strategy_name: String, amount: U512) {
+        let old = self.current_allocations.get(&strategy_name).unwrap_or_default();
+        self.set_allocation(&strategy_name, amount);
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set((total + amount).saturating_sub(old));
+    }
+}
+
+impl StrategyRouter {
+    /// Body of `withdraw_with_id`, also used to flush withdrawal batches
+    fn withdraw_funds(&mut self, flow_id: u64, amount: U512) -> U512 {
+        if amount.is_zero() {
+            return U512::zero();
+        }
+        
+        let idle = self.idle_balance.get_or_default();
+        let from_idle = idle.min(amount);
+        self.idle_balance.set(idle - from_idle);
+        let from_idle = from_idle + self.take_from_queue(amount - from_idle);
+        
+        let amount = amount - from_idle;
+        let total_allocated = self.total_allocated.get_or_default();
+        
+        if amount.is_zero() || total_allocated.is_zero() {
+            self.record_allocation_snapshot(ALLOCATION_ACTION_WITHDRAW);
+            return from_idle;
+        }
+        
+        let policy = self.withdrawal_policy_in_force();
+        let mut order = Vec::new();
+        let mut total_withdrawn = U512::zero();
+        let mut total_released = U512::zero();
+        let mut estimated_cost = U512::zero();
+        let mut actual_cost = U512::zero();
+        
+        for strategy_name in self.withdrawal_order(policy, amount).iter() {
+            let current_allocation = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            
+            if current_allocation.is_zero() {
+                continue;
+            }
+            
+            let withdrawal_amount = Self::tranche_target(policy, amount, current_allocation, total_allocated, total_withdrawn);
+            if withdrawal_amount.is_zero() {
+                continue;
+            }
+            
+            let tranche = self.withdraw_tranche(strategy_name, current_allocation, withdrawal_amount, flow_id);
+            order.push(strategy_name.clone());
+            total_withdrawn += tranche.withdrawn;
+            total_released += tranche.released;
+            estimated_cost += tranche.estimated_cost;
+            actual_cost += tranche.actual_cost;
+        }
+        
+        self.total_allocated.set(total_allocated - total_released);
+        self.record_allocation_snapshot(ALLOCATION_ACTION_WITHDRAW);
+        
+        self.env().emit_event(StrategyWithdrawal {
+            flow_id,
+            policy: policy.to_u8(),
+            order,
+            requested: amount,
+            withdrawn: total_withdrawn,
+            estimated_cost,
+            actual_cost,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        from_idle + total_withdrawn
+    }
+
+    /// Withdraw the open batch and start the next one (see `flush_withdrawals`)
+    fn flush_batch(&mut self) -> U512 {
+        let queued = self.batch_queued.get_or_default();
+        if queued.is_zero() {
+            return U512::zero();
+        }
+        
+        let batch = self.current_batch.get_or_default();
+        let claims = self.batch_claims_queued.get_or_default() - self.batch_claims_flushed.get_or_default();
+        self.batch_queued.set(U512::zero());
+        self.current_batch.set(batch + 1);
+        
+        let received = self.withdraw_funds(0, queued);
+        self.batch_requested.set(&batch, queued);
+        self.batch_received.set(&batch, received);
+        
+        self.batches_flushed.set(self.batches_flushed.get_or_default() + 1);
+        self.batch_claims_flushed.set(self.batch_claims_queued.get_or_default());
+        self.batch_volume.set(self.batch_volume.get_or_default() + queued);
+        
+        self.env().emit_event(WithdrawalsFlushed {
+            batch,
+            claims,
+            requested: queued,
+            received,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        received
+    }
+
+    /// Open batch is non-empty and older than the batch window
+    fn batch_window_elapsed(&self) -> bool {
+        !self.batch_queued.get_or_default().is_zero()
+            && self.env().get_block_time() >= self.batch_opened_at.get_or_default() + self.batch_window.get_or_default()
+    }
+
+    /// Emit `ExposureCapProposed` for a strategy's cap, or the cross-chain cap on None
+    fn emit_exposure_cap_proposed(&self, strategy_name: Option<String>, max_tvl_bps: u32, eta: u64) {
+        self.env().emit_event(ExposureCapProposed {
+            strategy_name,
+            max_tvl_bps,
+            eta,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Emit `ConfigChanged` for one of the router's parameters
+    fn config_changed(&self, key: String, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "StrategyRouter".to_string(),
+            key,
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// `ConfigChanged` rendering of a token's reward route
+    fn route_value(&self, token: Address) -> String {
+        match self.get_reward_route(token) {
+            Some(route) => format!("{:?}", route),
+            None => "none".to_string(),
+        }
+    }
+
+    /// Deploy lstCSPR into a strategy, returns (deployed, costs)
+    /// 
+    /// Costs are the bridge fees and, for native strategies, the conversion
+    /// cost paid on the way in; both are booked here.
+    fn deploy_to_strategy(&mut self, strategy_name: &String, address: Address, amount: U512, flow_id: u64) -> (U512, U512) {
+        if self.is_native(strategy_name) {
+            return self.deploy_native(strategy_name, address, amount, flow_id);
+        }
+        
+        let (deployed, fees) = self.call_deploy(address, amount, flow_id);
+        if !fees.is_zero() {
+            self.book_bridge_fees(strategy_name, fees);
+        }
+        (deployed, fees)
+    }
+
+    /// Call `deploy` in the strategy's own units, returns (accepted, bridge fees paid)
+    fn call_deploy(&mut self, address: Address, amount: U512, flow_id: u64) -> (U512, U512) {
+        let mut strategy = StrategyContractRef::new(self.env(), address);
+        let fees_before = strategy.get_lifetime_bridge_fees();
+        let deployed = strategy.deploy_with_id(flow_id, amount);
+        let fees = strategy.get_lifetime_bridge_fees().saturating_sub(fees_before);
+        (deployed, fees)
+    }
+
+    /// Swap lstCSPR for float CSPR and deploy it into a native strategy
+    /// 
+    /// Only the CSPR the strategy takes leaves the float, in exchange for
+    /// the matching share of `amount`. When the float can't cover the
+    /// conversion nothing is deployed and `NativeFloatShort` is emitted.
+    /// Returns (deployed, costs) in lstCSPR, like `deploy_to_strategy`.
+    fn deploy_native(&mut self, strategy_name: &String, address: Address, amount: U512, flow_id: u64) -> (U512, U512) {
+        let fee_bps = self.native_conversion_fee_bps.get_or_default();
+        let gross = self.staking().lst_cspr_to_cspr_precise(amount);
+        let native = gross - bps_of(gross, fee_bps);
+        if native.is_zero() {
+            return (U512::zero(), U512::zero());
+        }
+        
+        let float = self.native_float.get_or_default();
+        if native > float {
+            self.env().emit_event(NativeFloatShort {
+                strategy_name: strategy_name.clone(),
+                amount,
+                native_needed: native,
+                native_float: float,
+                timestamp: self.env().get_block_time(),
+            });
+            return (U512::zero(), U512::zero());
+        }
+        
+        let (accepted, bridge_fees) = self.call_deploy(address, native, flow_id);
+        let spent = accepted + bridge_fees;
+        if spent.is_zero() {
+            return (U512::zero(), U512::zero());
+        }
+        
+        let lst_in = mul_div(amount, spent, native, Rounding::Down).unwrap_or_revert(&self.env());
+        let conversion_cost = bps_of(lst_in, fee_bps);
+        let bridge_fees = mul_div(lst_in - conversion_cost, bridge_fees, spent, Rounding::Down)
+            .unwrap_or_revert(&self.env());
+        
+        self.native_float.set(float - spent);
+        let float_lst = self.float_lst.get_or_default();
+        self.float_lst.set(float_lst + lst_in);
+        
+        if !bridge_fees.is_zero() {
+            self.book_bridge_fees(strategy_name, bridge_fees);
+        }
+        self.book_conversion(strategy_name, true, lst_in, spent, conversion_cost);
+        
+        (lst_in - conversion_cost - bridge_fees, conversion_cost + bridge_fees)
+    }
+
+    /// Swap CSPR returned by a native strategy back to lstCSPR through the float
+    /// 
+    /// Pays out of `float_lst` at the current exchange rate less the
+    /// conversion fee; anything the float's lstCSPR can't cover is booked
+    /// as conversion cost too. Returns (lstCSPR received, cost).
+    fn wrap_native(&mut self, strategy_name: &String, native: U512) -> (U512, U512) {
+        if native.is_zero() {
+            return (U512::zero(), U512::zero());
+        }
+        
+        let gross = self.staking().cspr_to_lst_cspr_precise(native);
+        let float_lst = self.float_lst.get_or_default();
+        let received = (gross - bps_of(gross, self.native_conversion_fee_bps.get_or_default())).min(float_lst);
+        let conversion_cost = gross - received;
+        
+        let float = self.native_float.get_or_default();
+        self.native_float.set(float + native);
+        self.float_lst.set(float_lst - received);
+        self.book_conversion(strategy_name, false, received, native, conversion_cost);
+        
+        (received, conversion_cost)
+    }
+
+    /// Record a conversion through the float and its cost against the strategy
+    fn book_conversion(&mut self, strategy_name: &String, to_native: bool, lst_amount: U512, native_amount: U512, cost: U512) {
+        if !cost.is_zero() {
+            let strategy_costs = self.conversion_costs.get(strategy_name).unwrap_or(U512::zero());
+            self.conversion_costs.set(strategy_name, strategy_costs + cost);
+            let lifetime_costs = self.lifetime_conversion_costs.get_or_default();
+            self.lifetime_conversion_costs.set(lifetime_costs + cost);
+        }
+        
+        self.env().emit_event(NativeConversion {
+            strategy_name: strategy_name.clone(),
+            to_native,
+            lst_amount,
+            native_amount,
+            cost,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    fn is_native(&self, strategy_name: &String) -> bool {
+        self.wants_native.get(strategy_name).unwrap_or(false)
+    }
+
+    fn staking(&self) -> LiquidStakingContractRef {
+        match self.liquid_staking.get() {
+            Some(staking) => LiquidStakingContractRef::new(self.env(), staking),
+            None => self.env().revert(VaultError::ConditionsNotMet),
+        }
+    }
+
+    /// lstCSPR value of `amount` in the strategy's own units
+    fn to_lst_value(&self, strategy_name: &String, amount: U512) -> U512 {
+        if amount.is_zero() || !self.is_native(strategy_name) {
+            return amount;
+        }
+        self.staking().cspr_to_lst_cspr_precise(amount)
+    }
+
+    /// Strategy balance in lstCSPR
+    fn strategy_balance(&self, strategy_name: &String, address: Address) -> U512 {
+        self.to_lst_value(strategy_name, StrategyContractRef::new(self.env(), address).get_balance())
+    }
+
+    /// Room left under the strategy's capacity, in lstCSPR
+    fn strategy_room(&self, strategy_name: &String, address: Address) -> U512 {
+        let strategy = StrategyContractRef::new(self.env(), address);
+        self.to_lst_value(strategy_name, strategy.max_capacity().saturating_sub(strategy.get_balance()))
+    }
+
+    fn is_vault(&self, address: Address) -> bool {
+        self.vault.get() == Some(address)
+    }
+
+    /// Vault TVL the exposure caps are measured against, if a vault is wired
+    fn vault_tvl(&self) -> Option<U512> {
+        self.vault.get()
+            .filter(|vault| vault.is_contract())
+            .map(|vault| VaultManagerContractRef::new(self.env(), vault).total_assets())
+    }
+
+    /// Tightest TVL cap on a strategy: (most it may hold, cap in bps)
+    /// 
+    /// The cross-chain cap is shared, so other cross-chain positions count
+    /// against it. None when uncapped or without a TVL.
+    fn exposure_limit(&self, strategy_name: &String, tvl: Option<U512>) -> Option<(U512, u32)> {
+        let tvl = tvl?;
+        let mut limit: Option<(U512, u32)> = None;
+        
+        let strategy_cap = self.max_tvl_bps.get(strategy_name).unwrap_or(0);
+        if strategy_cap > 0 {
+            limit = Some((bps_of(tvl, strategy_cap), strategy_cap));
+        }
+        
+        let crosschain_cap = self.max_crosschain_tvl_bps.get_or_default();
+        if crosschain_cap > 0 && is_crosschain(strategy_name) {
+            let others = self.strategy_names.get_or_default().iter()
+                .filter(|name| *name != strategy_name && is_crosschain(name))
+                .fold(U512::zero(), |acc, name| acc + self.current_allocations.get(name).unwrap_or(U512::zero()));
+            let shared = bps_of(tvl, crosschain_cap).saturating_sub(others);
+            if limit.map_or(true, |(strategy_limit, _)| shared < strategy_limit) {
+                limit = Some((shared, crosschain_cap));
+            }
+        }
+        
+        limit
+    }
+
+    /// Deploy a tranche and record it against the strategy's allocation
+    /// 
+    /// Returns (deployed, costs paid). Costs are booked by
+    /// `deploy_to_strategy`; the caller updates `total_allocated`.
+    fn place_tranche(&mut self, strategy_name: &String, tranche: U512, flow_id: u64) -> (U512, U512) {
+        let (deployed, fees) = match self.strategies.get(strategy_name) {
+            Some(address) => self.deploy_to_strategy(strategy_name, address, tranche, flow_id),
+            None => (U512::zero(), U512::zero()),
+        };
+        
+        if deployed.is_zero() {
+            return (deployed, fees);
+        }
+        
+        let current = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+        self.set_allocation(strategy_name, current + deployed);
+        self.record_deployed(strategy_name, deployed);
+        
+        self.env().emit_event(AllocationUpdate {
+            flow_id,
+            strategy_name: strategy_name.clone(),
+            amount: deployed,
+            total_allocated: current + deployed,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        (deployed, fees)
+    }
+
+    /// Record a strategy's allocation, crediting the old one's time first
+    fn set_allocation(&mut self, strategy_name: &String, amount: U512) {
+        let now = self.env().get_block_time();
+        let allocation_seconds = self.allocation_seconds(strategy_name, now);
+        self.perf_allocation_seconds.set(strategy_name, allocation_seconds);
+        // Never move the checkpoint back, or the same seconds count twice
+        let checkpoint = self.perf_checkpoint.get(strategy_name).unwrap_or(now).max(now);
+        self.perf_checkpoint.set(strategy_name, checkpoint);
+        if !amount.is_zero() && self.perf_activated_at.get(strategy_name).is_none() {
+            self.perf_activated_at.set(strategy_name, now);
+        }
+        
+        self.current_allocations.set(strategy_name, amount);
+    }
+
+    /// Store the current allocations as the latest snapshot
+    /// 
+    /// Overwrites the latest snapshot instead when both fall in the same
+    /// `allocation_snapshot_epoch`.
+    fn record_allocation_snapshot(&mut self, action: u8) {
+        let now = self.env().get_block_time();
+        let size = self.allocation_history_size.get_or_default().max(1);
+        let epoch = self.allocation_snapshot_epoch.get_or_default();
+        let last_id = self.allocation_snapshot_id.get_or_default();
+        
+        let same_epoch = epoch > 0
+            && self.get_allocation_snapshot(last_id).map_or(false, |latest| latest.timestamp / epoch == now / epoch);
+        let id = if same_epoch { last_id } else { last_id + 1 };
+        let slot = id % size;
+        
+        let allocations = self.strategy_names
+            .get_or_default()
+            .into_iter()
+            .map(|name| {
+                let amount = self.current_allocations.get(&name).unwrap_or_default();
+                (name, amount)
+            })
+            .collect();
+        
+        self.snapshot_ids.set(&slot, id);
+        self.snapshot_actions.set(&slot, action);
+        self.snapshot_totals.set(&slot, self.total_allocated.get_or_default());
+        self.snapshot_allocations.set(&slot, allocations);
+        self.snapshot_times.set(&slot, now);
+        self.allocation_snapshot_id.set(id);
+    }
+
+    /// Allocation integrated over time up to `now` (lstCSPR-seconds)
+    fn allocation_seconds(&self, strategy_name: &String, now: u64) -> U512 {
+        let stored = self.perf_allocation_seconds.get(strategy_name).unwrap_or_default();
+        let since = self.perf_checkpoint.get(strategy_name).unwrap_or(now);
+        let current = self.current_allocations.get(strategy_name).unwrap_or_default();
+        stored + current * U512::from(now.saturating_sub(since))
+    }
+
+    fn record_deployed(&mut self, strategy_name: &String, amount: U512) {
+        let deployed = self.perf_deployed.get(strategy_name).unwrap_or_default();
+        self.perf_deployed.set(strategy_name, deployed + amount);
+    }
+
+    fn record_withdrawn(&mut self, strategy_name: &String, amount: U512) {
+        let withdrawn = self.perf_withdrawn.get(strategy_name).unwrap_or_default();
+        self.perf_withdrawn.set(strategy_name, withdrawn + amount);
+    }
+
+    /// Add `amount` to a strategy's deployment queue
+    fn queue_deployment(&mut self, strategy_name: &String, amount: U512) {
+        let queued = self.queued_deployments.get(strategy_name).unwrap_or(U512::zero()) + amount;
+        self.queued_deployments.set(strategy_name, queued);
+        let total_queued = self.total_queued.get_or_default();
+        self.total_queued.set(total_queued + amount);
+        
+        self.env().emit_event(DeploymentQueued {
+            strategy_name: strategy_name.clone(),
+            amount,
+            queued,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Take up to `amount` back out of the deployment queue, in strategy
+    /// registration order, returns the amount taken
+    fn take_from_queue(&mut self, amount: U512) -> U512 {
+        let total_queued = self.total_queued.get_or_default();
+        if amount.is_zero() || total_queued.is_zero() {
+            return U512::zero();
+        }
+        
+        let mut taken = U512::zero();
+        for strategy_name in self.strategy_names.get_or_default().iter() {
+            let queued = self.queued_deployments.get(strategy_name).unwrap_or(U512::zero());
+            let take = queued.min(amount - taken);
+            if take.is_zero() {
+                continue;
+            }
+            
+            self.queued_deployments.set(strategy_name, queued - take);
+            taken += take;
+            if taken == amount {
+                break;
+            }
+        }
+        
+        self.total_queued.set(total_queued - taken);
+        taken
+    }
+
+    /// Withdraw lstCSPR from a strategy, returns (received, costs, in flight)
+    /// 
+    /// Costs are bridge fees, exit costs and, for native strategies, the
+    /// conversion back to lstCSPR; all are booked here. In flight is
+    /// whatever left the strategy's balance but was neither paid out nor
+    /// taken as a cost, e.g. a failed bridge return.
+    fn withdraw_from_strategy(&mut self, strategy_name: &String, address: Address, amount: U512, flow_id: u64) -> (U512, U512, U512) {
+        if !self.is_native(strategy_name) {
+            let (withdrawn, fees, exit_cost, in_flight) = self.call_withdraw(address, amount, flow_id);
+            self.book_withdrawal_costs(strategy_name, fees, exit_cost);
+            return (withdrawn, fees + exit_cost, in_flight);
+        }
+        
+        let staking = self.staking();
+        let (received, fees, exit_cost, in_flight) = self.call_withdraw(address, staking.lst_cspr_to_cspr_precise(amount), flow_id);
+        let (withdrawn, conversion_cost) = self.wrap_native(strategy_name, received);
+        let fees = staking.cspr_to_lst_cspr_precise(fees);
+        let exit_cost = staking.cspr_to_lst_cspr_precise(exit_cost);
+        self.book_withdrawal_costs(strategy_name, fees, exit_cost);
+        (withdrawn, fees + exit_cost + conversion_cost, staking.cspr_to_lst_cspr_precise(in_flight))
+    }
+
+    /// Call `withdraw` in the strategy's own units
+    /// 
+    /// Returns (received, bridge fees paid, exit cost, in flight). A shortfall
+    /// up to the strategy's own estimate counts as an exit cost, unless the
+    /// strategy bridged or paid nothing out; then it is all in flight.
+    fn call_withdraw(&mut self, address: Address, amount: U512, flow_id: u64) -> (U512, U512, U512, U512) {
+        let mut strategy = StrategyContractRef::new(self.env(), address);
+        let expected_cost = strategy.estimate_withdrawal_cost(amount);
+        let fees_before = strategy.get_lifetime_bridge_fees();
+        let balance_before = strategy.get_balance();
+        let withdrawn = strategy.withdraw_with_id(flow_id, amount);
+        let fees = strategy.get_lifetime_bridge_fees().saturating_sub(fees_before);
+        let balance_drop = balance_before.saturating_sub(strategy.get_balance());
+        
+        let shortfall = balance_drop.saturating_sub(withdrawn + fees);
+        let exit_cost = if withdrawn.is_zero() || !fees.is_zero() {
+            U512::zero()
+        } else {
+            shortfall.min(expected_cost)
+        };
+        (withdrawn, fees, exit_cost, shortfall - exit_cost)
+    }
+
+    /// Withdraw one tranche and update the strategy's allocation
+    /// 
+    /// The caller updates `total_allocated` with `released`.
+    fn withdraw_tranche(&mut self, strategy_name: &String, current_allocation: U512, amount: U512, flow_id: u64) -> WithdrawnTranche {
+        let (withdrawn, costs, in_flight, estimated_cost) = match self.strategies.get(strategy_name) {
+            Some(address) => {
+                let estimated_cost = self.exit_cost_estimate(strategy_name, amount);
+                let (withdrawn, costs, in_flight) = self.withdraw_from_strategy(strategy_name, address, amount, flow_id);
+                (withdrawn, costs, in_flight, estimated_cost)
+            },
+            None => (U512::zero(), U512::zero(), U512::zero(), U512::zero()),
+        };
+        let withdrawn = if withdrawn > current_allocation { current_allocation } else { withdrawn };
+        
+        if !in_flight.is_zero() {
+            self.track_pending_withdrawal(strategy_name, in_flight);
+        }
+        
+        let released = (withdrawn + costs).min(current_allocation);
+        self.set_allocation(strategy_name, current_allocation - released);
+        self.record_withdrawn(strategy_name, withdrawn);
+        WithdrawnTranche { withdrawn, released, estimated_cost, actual_cost: costs }
+    }
+
+    /// Amount a strategy is asked for under `policy`
+    /// 
+    /// Draining policies ask each strategy for what is still missing after
+    /// the net proceeds of the ones before it.
+    fn tranche_target(policy: WithdrawalPolicy, amount: U512, allocation: U512, total_allocated: U512, covered: U512) -> U512 {
+        match policy {
+            WithdrawalPolicy::Proportional => (amount * allocation) / total_allocated,
+            WithdrawalPolicy::LiquidityFirst | WithdrawalPolicy::CheapestFirst => {
+                amount.saturating_sub(covered).min(allocation)
+            },
+        }
+    }
+
+    /// lstCSPR a strategy expects to keep back when paying out `amount`
+    /// 
+    /// Native strategies also pay the conversion back through the float.
+    fn exit_cost_estimate(&self, strategy_name: &String, amount: U512) -> U512 {
+        let address = match self.strategies.get(strategy_name) {
+            Some(address) => address,
+            None => return U512::zero(),
+        };
+        let strategy = StrategyContractRef::new(self.env(), address);
+        if !self.is_native(strategy_name) {
+            return strategy.estimate_withdrawal_cost(amount);
+        }
+        
+        let staking = self.staking();
+        let exit_cost = staking.cspr_to_lst_cspr_precise(
+            strategy.estimate_withdrawal_cost(staking.lst_cspr_to_cspr_precise(amount))
+        );
+        let converted = amount.saturating_sub(exit_cost);
+        exit_cost + bps_of(converted, self.native_conversion_fee_bps.get_or_default())
+    }
+
+    /// Strategies in the order `withdraw` visits them
+    /// 
+    /// Cheapest first ranks strategies by estimated exit cost per unit of
+    /// the tranche they would pay for `amount`, then by liquidity tier.
+    fn withdrawal_order(&self, policy: WithdrawalPolicy, amount: U512) -> Vec<String> {
+        let mut names = self.strategy_names.get_or_default();
+        match policy {
+            WithdrawalPolicy::Proportional => {},
+            WithdrawalPolicy::LiquidityFirst => {
+                // Stable sort keeps registration order within a tier
+                names.sort_by_key(|name| self.liquidity_tier(name));
+            },
+            WithdrawalPolicy::CheapestFirst => {
+                let mut ranked: Vec<(U512, LiquidityTier, String)> = names
+                    .into_iter()
+                    .map(|name| {
+                        let tranche = amount.min(self.current_allocations.get(&name).unwrap_or(U512::zero()));
+                        let cost_bps = if tranche.is_zero() {
+                            U512::zero()
+                        } else {
+                            ratio_bps(self.exit_cost_estimate(&name, tranche), tranche).unwrap_or(U512::MAX)
+                        };
+                        (cost_bps, self.liquidity_tier(&name), name)
+                    })
+                    .collect();
+                // Stable sort keeps registration order on ties
+                ranked.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+                names = ranked.into_iter().map(|(_, _, name)| name).collect();
+            },
+        }
+        names
+    }
+
+    fn withdrawal_policy_in_force(&self) -> WithdrawalPolicy {
+        WithdrawalPolicy::from_u8(self.withdrawal_policy.get_or_default())
+            .unwrap_or(WithdrawalPolicy::Proportional)
+    }
+
+    fn liquidity_tier(&self, name: &String) -> LiquidityTier {
+        self.liquidity_tiers.get(name)
+            .and_then(LiquidityTier::from_u8)
+            .unwrap_or(LiquidityTier::Slow)
+    }
+
+    /// Add to a strategy's pending withdrawals and restart its deadline
+    fn track_pending_withdrawal(&mut self, strategy_name: &String, amount: U512) {
+        let pending = self.pending_withdrawals.get(strategy_name).unwrap_or(U512::zero()) + amount;
+        self.pending_withdrawals.set(strategy_name, pending);
+        
+        let deadline = self.env().get_block_time() + self.get_withdrawal_deadline(strategy_name.clone());
+        self.pending_withdrawal_deadlines.set(strategy_name, deadline);
+        
+        self.env().emit_event(PendingWithdrawalTracked {
+            strategy_name: strategy_name.clone(),
+            amount,
+            total_pending: pending,
+            deadline,
+        });
+    }
+
+    /// Take a pending withdrawal off a strategy's recorded allocation
+    fn release_pending_withdrawal(&mut self, strategy_name: &String, amount: U512) -> U512 {
+        let pending = self.pending_withdrawals.get(strategy_name).unwrap_or(U512::zero());
+        if amount.is_zero() || amount > pending {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        self.pending_withdrawals.set(strategy_name, pending - amount);
+        
+        let current = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+        let released = amount.min(current);
+        self.set_allocation(strategy_name, current - released);
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set(total.saturating_sub(released));
+        
+        pending - amount
+    }
+
+    /// Book the bridge fees and exit costs of one withdrawal
+    fn book_withdrawal_costs(&mut self, strategy_name: &String, fees: U512, exit_cost: U512) {
+        if !fees.is_zero() {
+            self.book_bridge_fees(strategy_name, fees);
+        }
+        if exit_cost.is_zero() {
+            return;
+        }
+        
+        let strategy_costs = self.exit_costs.get(strategy_name).unwrap_or(U512::zero());
+        self.exit_costs.set(strategy_name, strategy_costs + exit_cost);
+        
+        let lifetime_costs = self.lifetime_exit_costs.get_or_default() + exit_cost;
+        self.lifetime_exit_costs.set(lifetime_costs);
+        
+        self.env().emit_event(ExitCostsBooked {
+            strategy_name: strategy_name.clone(),
+            amount: exit_cost,
+            lifetime_costs,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Record bridge fees as a cost of the strategy
+    fn book_bridge_fees(&mut self, strategy_name: &String, fees: U512) {
+        let strategy_fees = self.bridge_fees.get(strategy_name).unwrap_or(U512::zero());
+        self.bridge_fees.set(strategy_name, strategy_fees + fees);
+        
+        let lifetime_fees = self.lifetime_bridge_fees.get_or_default() + fees;
+        self.lifetime_bridge_fees.set(lifetime_fees);
+        
+        self.env().emit_event(BridgeFeesBooked {
+            strategy_name: strategy_name.clone(),
+            amount: fees,
+            lifetime_fees,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Split `amount` into per-strategy tranches respecting remaining capacity
+    /// 
+    /// Emits `AllocationOverflow` when any tranche had to spill.
+    fn plan_allocation(&mut self, amount: U512) -> Vec<(String, U512)> {
+        let strategy_names = self.strategy_names.get_or_default();
+        let tvl = self.vault_tvl();
+        // Slots whose room is set by a TVL cap rather than capacity
+        let mut exposure_capped: Vec<(String, U512, u32)> = Vec::new();
+        
+        // (name, target pct, remaining room, tranche)
+        let mut slots: Vec<(String, u8, U512, U512)> = Vec::new();
+        for strategy_name in strategy_names.iter() {
+            if self.is_quarantined(strategy_name.clone()) {
+                continue;
+            }
+            
+            let target_pct = self.target_allocations.get(strategy_name).unwrap_or(0);
+            if target_pct == 0 {
+                continue;
+            }
+            
+            let mut room = match self.strategies.get(strategy_name) {
+                Some(address) => self.strategy_room(strategy_name, address),
+                None => U512::zero(),
+            };
+            if let Some((limit, cap_bps)) = self.exposure_limit(strategy_name, tvl) {
+                let current = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+                let headroom = limit.saturating_sub(current);
+                if headroom < room {
+                    room = headroom;
+                    exposure_capped.push((strategy_name.clone(), limit, cap_bps));
+                }
+            }
+            
+            slots.push((strategy_name.clone(), target_pct, room, U512::zero()));
+        }
+        
+        // First pass by target, then redistribute overflow among strategies
+        // with room left. Each pass fills at least one strategy or places
+        // everything, so passes are bounded by the strategy count.
+        let mut to_place = amount;
+        let mut initial_overflow = U512::zero();
+        
+        for pass in 0..=slots.len() {
+            let open_pct: u64 = slots.iter()
+                .filter(|(_, _, room, tranche)| room > tranche)
+                .map(|(_, pct, _, _)| *pct as u64)
+                .sum();
+            
+            if to_place.is_zero() || (pass > 0 && open_pct == 0) {
+                break;
+            }
+            
+            // The first pass shares against 100%, so an incomplete target set leaves funds unallocated
+            let denominator = if pass == 0 { 100u64 } else { open_pct };
+            let mut overflow = U512::zero();
+            let mut placed = U512::zero();
+            
+            for (_, pct, room, tranche) in slots.iter_mut() {
+                if pass > 0 && *room <= *tranche {
+                    continue;
+                }
+                
+                let share = (to_place * U512::from(*pct)) / U512::from(denominator);
+                let available = room.saturating_sub(*tranche);
+                let accepted = if share > available { available } else { share };
+                
+                *tranche += accepted;
+                placed += accepted;
+                overflow += share - accepted;
+            }
+            
+            if pass == 0 {
+                initial_overflow = overflow;
+                to_place = overflow;
+            } else {
+                to_place -= placed;
+            }
+            
+            if placed.is_zero() {
+                break;
+            }
+        }
+        
+        if !initial_overflow.is_zero() {
+            let planned: U512 = slots.iter().fold(U512::zero(), |acc, slot| acc + slot.3);
+            
+            self.env().emit_event(AllocationOverflow {
+                overflow: initial_overflow,
+                redistributed: initial_overflow.saturating_sub(to_place),
+                unallocated: amount.saturating_sub(planned),
+                timestamp: self.env().get_block_time(),
+            });
+        }
+        
+        // A capped slot that filled up was truncated by its cap
+        for (strategy_name, limit, cap_bps) in exposure_capped.into_iter() {
+            let filled = slots.iter().any(|(name, _, room, tranche)| *name == strategy_name && tranche >= room);
+            if filled {
+                self.env().emit_event(ExposureCapHit {
+                    strategy_name,
+                    cap_bps,
+                    limit,
+                    vault_tvl: tvl.unwrap_or_default(),
+                    timestamp: self.env().get_block_time(),
+                });
+            }
+        }
+        
+        slots.into_iter()
+            .filter(|slot| !slot.3.is_zero())
+            .map(|(name, _, _, tranche)| (name, tranche))
+            .collect()
+    }
+
+    /// Harvest every non-quarantined strategy
+    /// 
+    /// Recovered write-offs are included, quarantined strategy or not, so
+    /// they reach the vault as reported (locked) profit. A strategy that
+    /// fails its harvest is quarantined and counts nothing, without
+    /// stopping the others. Returns the total yield and whether every
+    /// harvest succeeded.
+    fn harvest_strategies(&mut self) -> (U512, bool) {
+        let strategy_names = self.strategy_names.get_or_default();
+        let mut total_yield = self.reward_proceeds.get_or_default();
+        if !total_yield.is_zero() {
+            self.reward_proceeds.set(U512::zero());
+        }
+        
+        let mut all_harvested = true;
+        for strategy_name in strategy_names.iter() {
+            let recovered = self.recovered_gains.get(strategy_name).unwrap_or(U512::zero());
+            if !recovered.is_zero() {
+                self.recovered_gains.set(strategy_name, U512::zero());
+            }
+            
+            let harvested = if self.is_quarantined(strategy_name.clone()) {
+                if recovered.is_zero() {
+                    continue;
+                }
+                recovered
+            } else {
+                recovered + match self.strategies.get(strategy_name) {
+                    Some(address) => match self.harvest_strategy(strategy_name, address) {
+                        Some(harvested) => harvested,
+                        None => {
+                            all_harvested = false;
+                            self.quarantine(strategy_name, String::from("harvest_failed"));
+                            U512::zero()
+                        }
+                    },
+                    None => U512::zero(),
+                }
+            };
+            
+            total_yield += harvested;
+            let lifetime = self.perf_harvested.get(strategy_name).unwrap_or_default();
+            self.perf_harvested.set(strategy_name, lifetime + harvested);
+            
+            self.env().emit_event(YieldHarvested {
+                strategy_name: strategy_name.clone(),
+                yield_amount: harvested,
+                timestamp: self.env().get_block_time(),
+            });
+        }
+        
+        (total_yield, all_harvested)
+    }
+
+    /// The vault's lstCSPR token and the router's balance of it, unless
+    /// the vault or its token is a placeholder account
+    fn lst_holdings(&self) -> Option<(Address, U512)> {
+        let vault = self.vault_address.get().filter(|vault| vault.is_contract())?;
+        let token = VaultManagerContractRef::new(self.env(), vault).asset();
+        if !token.is_contract() {
+            return None;
+        }
+        Some((token, LstCsprContractRef::new(self.env(), token).balance_of(self.env().self_address())))
+    }
+
+    /// Transfer the lstCSPR received since `holdings` were taken to
+    /// `recipient`, returns the amount
+    fn forward_harvested_lst(&mut self, holdings: Option<(Address, U512)>, recipient: Address) -> U512 {
+        let (token, balance_before) = match holdings {
+            Some(holdings) => holdings,
+            None => return U512::zero(),
+        };
+        let mut token = LstCsprContractRef::new(self.env(), token);
+        let received = token.balance_of(self.env().self_address()).saturating_sub(balance_before);
+        if !received.is_zero() {
+            token.transfer(recipient, received);
+        }
+        received
+    }
+
+    /// Harvest a strategy's lstCSPR yield and route its reward tokens,
+    /// returns the lstCSPR gained
+    /// 
+    /// `None` if the strategy's balance grew over the harvest, i.e. it
+    /// booked the yield without paying it out; its rewards are left alone.
+    fn harvest_strategy(&mut self, strategy_name: &str, address: Address) -> Option<U512> {
+        let mut strategy = StrategyContractRef::new(self.env(), address);
+        let balance_before = strategy.get_balance();
+        // Native strategies report CSPR yield, valued at the current rate
+        let mut harvested = self.to_lst_value(&strategy_name.to_string(), strategy.harvest());
+        if strategy.get_balance() > balance_before {
+            return None;
+        }
+        
+        for (token, amount) in strategy.harvest_rewards() {
+            if !amount.is_zero() {
+                harvested += self.route_reward(strategy_name, token, amount);
+            }
+        }
+        
+        Some(harvested)
+    }
+
+    /// Swap a harvested reward if its route auto-swaps at an acceptable
+    /// quote, otherwise escrow it; returns the lstCSPR received
+    fn route_reward(&mut self, strategy_name: &str, token: Address, amount: U512) -> U512 {
+        let auto_swap = self.reward_auto_swap.get(&token).unwrap_or(false);
+        if let Some(dex) = self.reward_dex.get(&token).filter(|_| auto_swap) {
+            let min_rate = self.reward_min_rate.get(&token).unwrap_or_default();
+            let min_out = amount * min_rate / U512::from(1_000_000_000u64);
+            
+            if RewardSwapContractRef::new(self.env(), dex).get_quote_a_to_b(amount) >= min_out {
+                let amount_out = self.swap_reward(token, dex, amount, min_out);
+                self.env().emit_event(RewardsSwapped {
+                    strategy_name: strategy_name.to_string(),
+                    token,
+                    amount_in: amount,
+                    amount_out,
+                    timestamp: self.env().get_block_time(),
+                });
+                return amount_out;
+            }
+        }
+        
+        let escrowed = self.reward_escrow.get(&token).unwrap_or_default() + amount;
+        self.reward_escrow.set(&token, escrowed);
+        self.env().emit_event(RewardsEscrowed {
+            strategy_name: strategy_name.to_string(),
+            token,
+            amount,
+            escrowed,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        U512::zero()
+    }
+
+    /// Send reward tokens to the pool and swap them, reverting below `min_out`
+    fn swap_reward(&mut self, token: Address, dex: Address, amount: U512, min_out: U512) -> U512 {
+        RewardTokenContractRef::new(self.env(), token).transfer(dex, amount);
+        let amount_out = RewardSwapContractRef::new(self.env(), dex).swap_a_to_b(amount);
+        
+        if amount_out < min_out {
+            self.env().revert(VaultError::SlippageExceeded);
+        }
+        
+        amount_out
+    }
+
+    /// Record a rebalance if the minimum interval has elapsed
+    fn try_rebalance(&mut self) -> bool {
+        let current_time = self.env().get_block_time();
+        let last_rebalance = self.last_rebalance.get_or_default();
+        let min_interval = self.min_rebalance_interval.get_or_default();
+        
+        if current_time < last_rebalance + min_interval {
+            return false;
+        }
+        
+        self.last_rebalance.set(current_time);
+        
+        let targets: Vec<(String, u8)> = self.strategy_names.get_or_default()
+            .into_iter()
+            .map(|name| {
+                let pct = self.target_allocations.get(&name).unwrap_or(0);
+                (name, pct)
+            })
+            .collect();
+        let (moves, _, cap_hits) = self.plan_rebalance(&targets);
+        for cap_hit in cap_hits.into_iter() {
+            self.env().emit_event(cap_hit);
+        }
+        
+        // Withdrawals first, then deploy what actually came back
+        let mut available = U512::zero();
+        let mut released = U512::zero();
+        for strategy_move in moves.iter().filter(|m| !m.withdraw.is_zero()) {
+            let address = match self.strategies.get(&strategy_move.strategy_name) {
+                Some(address) => address,
+                None => continue,
+            };
+            
+            let (withdrawn, costs, in_flight) =
+                self.withdraw_from_strategy(&strategy_move.strategy_name, address, strategy_move.withdraw, 0);
+            if !in_flight.is_zero() {
+                self.track_pending_withdrawal(&strategy_move.strategy_name, in_flight);
+            }
+            
+            let reduction = (withdrawn + costs).min(strategy_move.current);
+            self.set_allocation(&strategy_move.strategy_name, strategy_move.current - reduction);
+            self.record_withdrawn(&strategy_move.strategy_name, withdrawn);
+            available += withdrawn;
+            released += reduction;
+        }
+        
+        let mut deployed_total = U512::zero();
+        let mut unplaced = U512::zero();
+        for strategy_move in moves.iter().filter(|m| !m.deploy.is_zero()) {
+            let address = match self.strategies.get(&strategy_move.strategy_name) {
+                Some(address) => address,
+                None => continue,
+            };
+            
+            let tranche = strategy_move.deploy.min(available);
+            if tranche.is_zero() {
+                break;
+            }
+            
+            let (deployed, fees) = self.deploy_to_strategy(&strategy_move.strategy_name, address, tranche, 0);
+            
+            self.set_allocation(&strategy_move.strategy_name, strategy_move.current + deployed);
+            self.record_deployed(&strategy_move.strategy_name, deployed);
+            available -= tranche;
+            unplaced += tranche.saturating_sub(deployed + fees);
+            deployed_total += deployed;
+        }
+        
+        // Whatever came back but found no home waits in the idle balance
+        let leftover = unplaced + available;
+        if !leftover.is_zero() {
+            let idle = self.idle_balance.get_or_default();
+            self.idle_balance.set(idle + leftover);
+        }
+        
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set((total + deployed_total).saturating_sub(released));
+        self.record_allocation_snapshot(ALLOCATION_ACTION_REBALANCE);
+        
+        let old_allocations = moves.iter()
+            .map(|m| (m.strategy_name.clone(), m.current))
+            .collect();
+        let new_allocations = moves.iter()
+            .map(|m| (m.strategy_name.clone(), self.current_allocations.get(&m.strategy_name).unwrap_or(U512::zero())))
+            .collect();
+        
+        self.env().emit_event(Rebalance {
+            old_allocations,
+            new_allocations,
+            timestamp: current_time,
+        });
+        
+        true
+    }
+
+    /// Per-strategy moves to reach `targets`, plus any constraint violations
+    /// 
+    /// Targets are weighted against the sum of listed percentages, so a
+    /// zeroed (unhealthy) strategy's share is spread over the rest. Targets
+    /// are capped at capacity, and withdrawals are trimmed to what can be
+    /// redeployed so funds are never left idle in the router. Quarantined
+    /// strategies are left untouched. Targets are also held to the TVL
+    /// exposure caps; each cap that binds is returned as an
+    /// `ExposureCapHit` for the caller to emit.
+    fn plan_rebalance(&self, targets: &[(String, u8)]) -> (Vec<AllocationMove>, Vec<String>, Vec<ExposureCapHit>) {
+        let tvl = self.vault_tvl();
+        let mut cap_hits: Vec<ExposureCapHit> = Vec::new();
+        let max_strategy = self.max_strategy_allocation.get_or_default();
+        let max_crosschain = self.max_crosschain_allocation.get_or_default();
+        let mut violations: Vec<String> = Vec::new();
+        
         let mut total_pct: u16 = 0;
         let mut crosschain_pct: u16 = 0;
-        
-        for (strategy_name, pct) in allocations.iter() {
-            // Validate constraints
+        for (strategy_name, pct) in targets.iter() {
+            if self.strategies.get(strategy_name).is_none() {
+                violations.push(format!("unknown strategy: {}", strategy_name));
+                continue;
+            }
             if *pct > max_strategy {
-                self.env().revert(VaultError::InvalidRequest);
+                violations.push(format!("{} above max allocation: {}% > {}%", strategy_name, pct, max_strategy));
             }
-            
-            if strategy_name == "crosschain" {
+            if is_crosschain(strategy_name) {
                 crosschain_pct += *pct as u16;
             }
-            
             total_pct += *pct as u16;
+        }
+        let max_total = 100 - self.router_idle_buffer_pct.get_or_default() as u16;
+        if total_pct > max_total {
+            violations.push(format!("targets sum to {}%, above {}% after the idle buffer", total_pct, max_total));
+        }
+        if crosschain_pct > max_crosschain as u16 {
+            violations.push(format!("cross-chain above max allocation: {}% > {}%", crosschain_pct, max_crosschain));
+        }
+        
+        // (name, pct, current, room)
+        let mut slots: Vec<(String, u8, U512, U512)> = Vec::new();
+        for strategy_name in self.strategy_names.get_or_default().iter() {
+            if self.is_quarantined(strategy_name.clone()) {
+                continue;
+            }
+            let address = match self.strategies.get(strategy_name) {
+                Some(address) => address,
+                None => continue,
+            };
             
-            // Set target allocation
-            self.target_allocations.set(strategy_name, *pct);
+            let pct = targets.iter()
+                .find(|(name, _)| name == strategy_name)
+                .map(|(_, pct)| *pct)
+                .unwrap_or(0);
+            let current = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            let room = self.strategy_room(strategy_name, address);
+            
+            slots.push((strategy_name.clone(), pct, current, room));
         }
         
-        // Validate total = 100%
-        if total_pct != 100 {
-            self.env().revert(VaultError::InvalidRequest);
+        let total: U512 = slots.iter().fold(U512::zero(), |acc, (_, _, current, _)| acc + *current);
+        let weight_sum: u64 = slots.iter().map(|(_, pct, _, _)| *pct as u64).sum();
+        let last_weighted = slots.iter().rposition(|(_, pct, _, _)| *pct > 0);
+        
+        let mut moves: Vec<AllocationMove> = Vec::new();
+        let mut assigned = U512::zero();
+        for (index, (strategy_name, pct, current, room)) in slots.iter().enumerate() {
+            let mut target = if weight_sum == 0 {
+                *current
+            } else if Some(index) == last_weighted {
+                // Rounding dust goes to the last weighted strategy
+                total.saturating_sub(assigned)
+            } else {
+                total * U512::from(*pct) / U512::from(weight_sum)
+            };
+            assigned += target;
+            
+            let cap = *current + *room;
+            if target > cap {
+                violations.push(format!("{} over capacity by {}", strategy_name, target - cap));
+                target = cap;
+            }
+            if let Some((limit, cap_bps)) = self.exposure_limit(strategy_name, tvl) {
+                if target > limit {
+                    violations.push(format!("{} over its TVL cap by {}", strategy_name, target - limit));
+                    target = limit;
+                    cap_hits.push(ExposureCapHit {
+                        strategy_name: strategy_name.clone(),
+                        cap_bps,
+                        limit,
+                        vault_tvl: tvl.unwrap_or_default(),
+                        timestamp: self.env().get_block_time(),
+                    });
+                }
+            }
+            
+            moves.push(AllocationMove {
+                strategy_name: strategy_name.clone(),
+                current: *current,
+                target,
+                withdraw: current.saturating_sub(target),
+                deploy: target.saturating_sub(*current),
+            });
         }
         
-        // Validate cross-chain limit
-        if crosschain_pct > max_crosschain as u16 {
-            self.env().revert(VaultError::InvalidRequest);
+        // Don't pull out more than can be placed elsewhere
+        let total_withdraw = moves.iter().fold(U512::zero(), |acc, m| acc + m.withdraw);
+        let total_deploy = moves.iter().fold(U512::zero(), |acc, m| acc + m.deploy);
+        let mut excess = total_withdraw.saturating_sub(total_deploy);
+        for strategy_move in moves.iter_mut().rev() {
+            if excess.is_zero() {
+                break;
+            }
+            let cut = strategy_move.withdraw.min(excess);
+            strategy_move.withdraw -= cut;
+            strategy_move.target += cut;
+            excess -= cut;
         }
+        
+        (moves, violations, cap_hits)
     }
 
-    /// Get current allocation for a strategy
-    pub fn get_current_allocation(&self, strategy_name: String) -> U512 {
-        self.current_allocations.get(&strategy_name).unwrap_or(U512::zero())
+    /// Health check body shared by `health_check` and keeper jobs
+    fn run_health_check(&mut self) {
+        let current_time = self.env().get_block_time();
+        let tvl = self.vault_tvl();
+        let threshold = self.health_failure_threshold.get_or_default();
+        let max_drift = self.max_balance_drift_bps.get_or_default();
+        let critical_drift = self.critical_balance_drift_bps.get_or_default();
+        let max_apy = self.max_strategy_apy_bps.get_or_default();
+        
+        let strategy_names = self.strategy_names.get_or_default();
+        
+        for strategy_name in strategy_names.iter() {
+            if self.is_quarantined(strategy_name.clone()) {
+                continue;
+            }
+            
+            let address = match self.strategies.get(strategy_name) {
+                Some(address) => address,
+                None => continue,
+            };
+            
+            let strategy = StrategyContractRef::new(self.env(), address);
+            let healthy = strategy.is_healthy();
+            let apy = strategy.get_apy();
+            let balance = self.strategy_balance(strategy_name, address);
+            let in_flight = self.pending_withdrawals.get(strategy_name).unwrap_or(U512::zero());
+            
+            let recorded = self.current_allocations.get(strategy_name).unwrap_or(U512::zero());
+            let drift_bps = self.shortfall_bps(recorded, balance + in_flight);
+            
+            let previous_apy = self.report_apy.get(strategy_name);
+            self.report_healthy.set(strategy_name, healthy);
+            self.report_apy.set(strategy_name, apy);
+            self.update_apy_ema(strategy_name, apy, previous_apy);
+            self.report_balance.set(strategy_name, balance);
+            self.report_drift_bps.set(strategy_name, drift_bps);
+            self.report_time.set(strategy_name, current_time);
+            
+            // A shrinking vault can leave a position over its cap; flag it
+            // for the next rebalance to trim
+            let over_cap = self.exposure_limit(strategy_name, tvl).filter(|(limit, _)| recorded > *limit);
+            self.report_over_exposed.set(strategy_name, over_cap.is_some());
+            if let Some((limit, cap_bps)) = over_cap {
+                self.env().emit_event(ExposureCapHit {
+                    strategy_name: strategy_name.clone(),
+                    cap_bps,
+                    limit,
+                    vault_tvl: tvl.unwrap_or_default(),
+                    timestamp: current_time,
+                });
+            }
+            
+            if drift_bps > critical_drift || apy > max_apy {
+                self.quarantine(strategy_name, String::from("health_check"));
+                continue;
+            }
+            
+            if healthy && drift_bps <= max_drift {
+                self.health_failures.set(strategy_name, 0);
+                continue;
+            }
+            
+            let failures = self.health_failures.get(strategy_name).unwrap_or(0).saturating_add(1);
+            self.health_failures.set(strategy_name, failures);
+            
+            if failures == threshold {
+                self.target_allocations.set(strategy_name, 0);
+                
+                self.env().emit_event(StrategyUnhealthy {
+                    name: strategy_name.clone(),
+                    consecutive_failures: failures,
+                    timestamp: current_time,
+                });
+            }
+        }
     }
 
-    /// Get target allocation percentage for a strategy
-    pub fn get_target_allocation(&self, strategy_name: String) -> u8 {
-        self.target_allocations.get(&strategy_name).unwrap_or(0)
+    /// Add a gain to a strategy's recorded allocation
+    fn book_gain(&mut self, name: &String, gain: U512) {
+        let current = self.current_allocations.get(name).unwrap_or(U512::zero());
+        self.set_allocation(name, current + gain);
+        
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set(total + gain);
     }
 
-    /// Get total allocated amount
-    pub fn get_total_allocated(&self) -> U512 {
-        self.total_allocated.get_or_default()
+    /// Drop a strategy's pending gain (its position was marked down)
+    fn clear_pending_gain(&mut self, name: &String) {
+        let pending = self.pending_gains.get(name).unwrap_or(U512::zero());
+        if pending.is_zero() {
+            return;
+        }
+        
+        self.pending_gains.set(name, U512::zero());
+        let total_pending = self.total_pending_gains.get_or_default();
+        self.total_pending_gains.set(total_pending.saturating_sub(pending));
     }
 
-    /// Get all strategy names
-    pub fn get_strategy_names(&self) -> Vec<String> {
-        self.strategy_names.get_or_default()
+    /// Book the next tranche of released pending gains
+    /// 
+    /// Each strategy books `pending / drips_remaining`, so the last drip
+    /// books whatever is left.
+    fn drip_pending_gains(&mut self) -> U512 {
+        let remaining = self.gain_drips_remaining.get_or_default();
+        if remaining == 0 {
+            return U512::zero();
+        }
+        
+        let strategy_names = self.strategy_names.get_or_default();
+        let mut released = U512::zero();
+        
+        for strategy_name in strategy_names.iter() {
+            let pending = self.pending_gains.get(strategy_name).unwrap_or(U512::zero());
+            if pending.is_zero() {
+                continue;
+            }
+            
+            let tranche = pending / U512::from(remaining);
+            self.pending_gains.set(strategy_name, pending - tranche);
+            self.book_gain(strategy_name, tranche);
+            released += tranche;
+        }
+        
+        let total_pending = self.total_pending_gains.get_or_default();
+        let total_pending = total_pending.saturating_sub(released);
+        self.total_pending_gains.set(total_pending);
+        
+        self.gain_drips_remaining.set(if total_pending.is_zero() { 0 } else { remaining - 1 });
+        
+        released
+    }
+
+    /// Pull all funds from a strategy and exclude it from allocation
+    fn quarantine(&mut self, name: &String, reason: String) {
+        let recovered = match self.strategies.get(name) {
+            Some(address) if self.is_native(name) => {
+                let native = StrategyContractRef::new(self.env(), address).emergency_withdraw(None);
+                self.wrap_native(name, native).0
+            }
+            Some(address) => StrategyContractRef::new(self.env(), address).emergency_withdraw(None),
+            None => U512::zero(),
+        };
+        
+        // Funds in flight stay allocated until settled or written off
+        let recorded = self.current_allocations.get(name).unwrap_or(U512::zero());
+        let in_flight = self.pending_withdrawals.get(name).unwrap_or(U512::zero()).min(recorded);
+        let total = self.total_allocated.get_or_default();
+        self.total_allocated.set(total.saturating_sub(recorded - in_flight));
+        self.set_allocation(name, in_flight);
+        self.record_withdrawn(name, recovered);
+        // What came back waits in the router for the next withdrawal
+        let idle = self.idle_balance.get_or_default();
+        self.idle_balance.set(idle + recovered);
+        self.target_allocations.set(name, 0);
+        self.quarantined.set(name, true);
+        self.clear_pending_gain(name);
+        
+        self.env().emit_event(StrategyQuarantined {
+            name: name.clone(),
+            recovered,
+            recorded_allocation: recorded,
+            reason,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Fold a health check APY reading into the strategy's smoothed APY
+    /// 
+    /// The first reading seeds the average. Outliers beyond the deviation
+    /// multiple are rejected, so a single bad reading can't move it. A
+    /// reading within the multiple of the previous one is accepted even if
+    /// far off the average, so a sustained APY change is followed from its
+    /// second reading on.
+    fn update_apy_ema(&mut self, strategy_name: &String, reading: U256, previous: Option<U256>) {
+        let smoothed = match self.apy_ema.get(strategy_name) {
+            Some(smoothed) if !smoothed.is_zero() => smoothed,
+            _ => {
+                self.apy_ema.set(strategy_name, reading);
+                return;
+            }
+        };
+        
+        let multiple = U256::from(self.max_apy_deviation_multiple.get_or_default());
+        let outside = |reference: U256| reading > reference * multiple || reading * multiple < reference;
+        let confirmed = previous.map_or(false, |previous| !previous.is_zero() && !outside(previous));
+        if outside(smoothed) && !confirmed {
+            self.env().emit_event(ApyReadingRejected {
+                strategy_name: strategy_name.clone(),
+                reading,
+                smoothed_apy: smoothed,
+                timestamp: self.env().get_block_time(),
+            });
+            return;
+        }
+        
+        let weight = U256::from(APY_EMA_WEIGHT_BPS);
+        let updated = (smoothed * (U256::from(10000u64) - weight) + reading * weight) / U256::from(10000u64);
+        self.apy_ema.set(strategy_name, updated);
     }
+
+    /// Shortfall of `balance` below `recorded` in basis points
+    fn shortfall_bps(&self, recorded: U512, balance: U512) -> u32 {
+        if recorded.is_zero() || balance >= recorded {
+            return 0;
+        }
+        
+        ratio_bps(recorded - balance, recorded)
+            .and_then(safe_u512_to_u32)
+            .unwrap_or_revert(&self.env())
+    }
+}
+
+
+/// Part of an allocation above a strategy's per-transaction cap, held back
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct DeploymentQueued {
+    pub strategy_name: String,
+    pub amount: U512,
+    pub queued: U512,
+    pub timestamp: u64,
+}
+
+/// Queued tranche deployed by `process_deployment_queue`
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct DeploymentQueueDrained {
+    pub strategy_name: String,
+    pub amount: U512,
+    pub remaining: U512,
+    pub timestamp: u64,
 }
 
+/// Harvested lstCSPR credited to the caller of `harvest_all`, and the
+/// part of it strategies paid in tokens and the router passed on
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct HarvestForwarded {
+    pub recipient: Address,
+    pub amount: U512,
+    pub transferred: U512,
+    pub timestamp: u64,
+}
 
 #[derive(Event)]
-struct AllocationUpdate {
+struct YieldHarvested {
     strategy_name: String,
+    yield_amount: U512,
+    timestamp: u64,
+}
+
+/// Health check APY reading left out of the smoothed APY as an outlier
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ApyReadingRejected {
+    pub strategy_name: String,
+    pub reading: U256,
+    pub smoothed_apy: U256,
+    pub timestamp: u64,
+}
+
+/// Reward tokens swapped to lstCSPR during a harvest
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RewardsSwapped {
+    pub strategy_name: String,
+    pub token: Address,
+    pub amount_in: U512,
+    pub amount_out: U512,
+    pub timestamp: u64,
+}
+
+/// Reward tokens held back for `liquidate_rewards`
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RewardsEscrowed {
+    pub strategy_name: String,
+    pub token: Address,
+    pub amount: U512,
+    pub escrowed: U512,
+    pub timestamp: u64,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RewardsLiquidated {
+    pub token: Address,
+    pub amount_in: U512,
+    pub amount_out: U512,
+    pub min_out: U512,
+    pub timestamp: u64,
+}
+
+/// An allocation or rebalance was held to a TVL exposure cap, or a health
+/// check found a position above one
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ExposureCapHit {
+    pub strategy_name: String,
+    pub cap_bps: u32,
+    pub limit: U512,
+    pub vault_tvl: U512,
+    pub timestamp: u64,
+}
+
+/// TVL cap proposed; applicable from `eta`. No strategy name means the
+/// cross-chain cap.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ExposureCapProposed {
+    pub strategy_name: Option<String>,
+    pub max_tvl_bps: u32,
+    pub eta: u64,
+    pub timestamp: u64,
+}
+
+#[derive(Event)]
+struct AllocationOverflow {
+    overflow: U512,
+    redistributed: U512,
+    unallocated: U512,
+    timestamp: u64,
+}
+
+#[derive(Event)]
+struct CircuitBreakerTripped {
     amount: U512,
-    total_allocated: U512,
+    change_bps: u32,
+    max_change_bps: u32,
     timestamp: u64,
 }
 
 #[derive(Event)]
-struct YieldHarvested {
-    strategy_name: String,
-    yield_amount: U512,
+struct StrategyUnhealthy {
+    name: String,
+    consecutive_failures: u8,
+    timestamp: u64,
+}
+
+#[derive(Event)]
+struct StrategyQuarantined {
+    name: String,
+    recovered: U512,
+    recorded_allocation: U512,
+    reason: String,
     timestamp: u64,
 }
 
@@ -337,6 +3583,124 @@ struct Rebalance {
     timestamp: u64,
 }
 
+#[derive(Event)]
+struct BridgeFeesBooked {
+    strategy_name: String,
+    amount: U512,
+    lifetime_fees: U512,
+    timestamp: u64,
+}
+
+#[derive(Event)]
+struct ExitCostsBooked {
+    strategy_name: String,
+    amount: U512,
+    lifetime_costs: U512,
+    timestamp: u64,
+}
+
+/// Withdrawal queued into the open batch
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct WithdrawalQueued {
+    pub claim_id: U256,
+    pub batch: u64,
+    pub amount: U512,
+    pub batch_total: U512,
+    pub timestamp: u64,
+}
+
+/// Batch withdrawn from strategies in one `withdraw` call
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct WithdrawalsFlushed {
+    pub batch: u64,
+    pub claims: u64,
+    pub requested: U512,
+    pub received: U512,
+    pub timestamp: u64,
+}
+
+/// lstCSPR swapped through the native float for a native strategy
+/// 
+/// `to_native` is set on the way in; `cost` is the lstCSPR booked
+/// against the strategy for the conversion.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct NativeConversion {
+    pub strategy_name: String,
+    pub to_native: bool,
+    pub lst_amount: U512,
+    pub native_amount: U512,
+    pub cost: U512,
+    pub timestamp: u64,
+}
+
+/// A native strategy's tranche left undeployed, the float being too small
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct NativeFloatShort {
+    pub strategy_name: String,
+    pub amount: U512,
+    pub native_needed: U512,
+    pub native_float: U512,
+    pub timestamp: u64,
+}
+
+/// Progress of a `drain_strategy` step
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct StrategyDrained {
+    pub strategy_name: String,
+    pub withdrawn: U512,
+    pub remaining_balance: U512,
+    pub remaining_allocation: U512,
+    pub timestamp: u64,
+}
+
+/// Emitted by `withdraw`; `order` lists the strategies drawn from, in order
+/// 
+/// `estimated_cost` is what the strategies expected to keep back and
+/// `actual_cost` what was booked (bridge, exit and conversion costs).
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct StrategyWithdrawal {
+    pub flow_id: u64,
+    pub policy: u8,
+    pub order: Vec<String>,
+    pub requested: U512,
+    pub withdrawn: U512,
+    pub estimated_cost: U512,
+    pub actual_cost: U512,
+    pub timestamp: u64,
+}
+
+#[derive(Event)]
+struct PendingWithdrawalTracked {
+    strategy_name: String,
+    amount: U512,
+    total_pending: U512,
+    deadline: u64,
+}
+
+#[derive(Event)]
+struct PendingWithdrawalSettled {
+    strategy_name: String,
+    amount: U512,
+    remaining_pending: U512,
+    timestamp: u64,
+}
+
+#[derive(Event)]
+struct PendingWithdrawalWrittenOff {
+    strategy_name: String,
+    amount: U512,
+    remaining_pending: U512,
+    timestamp: u64,
+}
+
+#[derive(Event)]
+struct WrittenOffRecovered {
+    strategy_name: String,
+    amount: U512,
+    still_written_off: U512,
+    timestamp: u64,
+}
+
 // ERRORS
 
 #[derive(Debug, PartialEq)]
@@ -0,0 +1,178 @@
+/// SystemRegistry - deployed address book and wiring check
+///
+/// Deployed last by the bootstrap (`crate::deployer::deploy_system`). It
+/// records every contract address, refuses to initialize if the contracts
+/// don't point at each other, and emits `SystemDeployed` so indexers get the
+/// full address set from a single event.
+///
+/// `verify_wiring` can be called any time afterwards; it re-reads the live
+/// configuration of every contract and reports what no longer matches.
+
+use odra::prelude::*;
+use odra::Event;
+use odra::{Address, SubModule, Var};
+use crate::core::{
+    LiquidStakingContractRef, StrategyRouterContractRef, VaultManagerContractRef, YieldAggregatorContractRef,
+};
+use crate::tokens::{CvCsprContractRef, LstCsprContractRef};
+use crate::types::*;
+use crate::utils::{AccessControl, Role};
+
+/// SystemRegistry contract
+#[odra::module]
+pub struct SystemRegistry {
+    /// Access control
+    access_control: SubModule<AccessControl>,
+
+    vault_manager: Var<Address>,
+    liquid_staking: Var<Address>,
+    strategy_router: Var<Address>,
+    yield_aggregator: Var<Address>,
+    lst_cspr_token: Var<Address>,
+    cv_cspr_token: Var<Address>,
+}
+
+#[odra::module]
+impl SystemRegistry {
+    /// Initialize the registry
+    ///
+    /// Reverts with `ConditionsNotMet` if `verify_wiring` reports any finding.
+    pub fn init(
+        &mut self,
+        admin: Address,
+        vault_manager: Address,
+        liquid_staking: Address,
+        strategy_router: Address,
+        yield_aggregator: Address,
+        lst_cspr_token: Address,
+        cv_cspr_token: Address,
+    ) {
+        self.access_control.init(admin);
+
+        self.vault_manager.set(vault_manager);
+        self.liquid_staking.set(liquid_staking);
+        self.strategy_router.set(strategy_router);
+        self.yield_aggregator.set(yield_aggregator);
+        self.lst_cspr_token.set(lst_cspr_token);
+        self.cv_cspr_token.set(cv_cspr_token);
+
+        if !self.verify_wiring().is_empty() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+
+        self.env().emit_event(SystemDeployed {
+            vault_manager,
+            liquid_staking,
+            strategy_router,
+            yield_aggregator,
+            lst_cspr_token,
+            cv_cspr_token,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Check the cross-contract wiring, returns one finding per mismatch
+    ///
+    /// An empty list means every contract points at the registered addresses.
+    pub fn verify_wiring(&self) -> Vec<String> {
+        let mut findings = Vec::new();
+
+        let (vault, staking, router, aggregator, lst, cv) = match (
+            self.vault_manager.get(),
+            self.liquid_staking.get(),
+            self.strategy_router.get(),
+            self.yield_aggregator.get(),
+            self.lst_cspr_token.get(),
+            self.cv_cspr_token.get(),
+        ) {
+            (Some(v), Some(s), Some(r), Some(a), Some(l), Some(c)) => (v, s, r, a, l, c),
+            _ => {
+                findings.push(String::from("registry: contract addresses not set"));
+                return findings;
+            }
+        };
+
+        let vault_ref = VaultManagerContractRef::new(self.env(), vault);
+        if vault_ref.get_liquid_staking() != Some(staking) {
+            findings.push(String::from("vault: liquid staking address mismatch"));
+        }
+        if vault_ref.get_strategy_router() != Some(router) {
+            findings.push(String::from("vault: strategy router address mismatch"));
+        }
+        if vault_ref.get_cv_cspr_token() != Some(cv) {
+            findings.push(String::from("vault: cvCSPR token address mismatch"));
+        }
+        if vault_ref.get_yield_aggregator() != Some(aggregator) {
+            findings.push(String::from("vault: yield aggregator address mismatch"));
+        }
+        if !vault_ref.has_role(Role::Operator.to_u8(), aggregator) {
+            findings.push(String::from("vault: yield aggregator lacks operator role"));
+        }
+
+        let staking_ref = LiquidStakingContractRef::new(self.env(), staking);
+        if staking_ref.get_lst_cspr_token() != Some(lst) {
+            findings.push(String::from("liquid staking: lstCSPR token address mismatch"));
+        }
+        if staking_ref.get_vault_address() != Some(vault) {
+            findings.push(String::from("liquid staking: vault address mismatch"));
+        }
+
+        if LstCsprContractRef::new(self.env(), lst).get_minter() != Some(staking) {
+            findings.push(String::from("lstCSPR: minter is not liquid staking"));
+        }
+
+        if CvCsprContractRef::new(self.env(), cv).get_vault_manager() != Some(vault) {
+            findings.push(String::from("cvCSPR: minter is not the vault"));
+        }
+
+        let router_ref = StrategyRouterContractRef::new(self.env(), router);
+        if router_ref.get_vault_address() != Some(vault) {
+            findings.push(String::from("strategy router: vault address mismatch"));
+        }
+        if router_ref.get_yield_aggregator() != Some(aggregator) {
+            findings.push(String::from("strategy router: yield aggregator address mismatch"));
+        }
+
+        if YieldAggregatorContractRef::new(self.env(), aggregator).get_vault_address() != Some(vault) {
+            findings.push(String::from("yield aggregator: vault address mismatch"));
+        }
+
+        findings
+    }
+
+    pub fn get_vault_manager(&self) -> Option<Address> {
+        self.vault_manager.get()
+    }
+
+    pub fn get_liquid_staking(&self) -> Option<Address> {
+        self.liquid_staking.get()
+    }
+
+    pub fn get_strategy_router(&self) -> Option<Address> {
+        self.strategy_router.get()
+    }
+
+    pub fn get_yield_aggregator(&self) -> Option<Address> {
+        self.yield_aggregator.get()
+    }
+
+    pub fn get_lst_cspr_token(&self) -> Option<Address> {
+        self.lst_cspr_token.get()
+    }
+
+    pub fn get_cv_cspr_token(&self) -> Option<Address> {
+        self.cv_cspr_token.get()
+    }
+}
+
+/// Emitted once the registry has verified a freshly deployed system
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct SystemDeployed {
+    pub vault_manager: Address,
+    pub liquid_staking: Address,
+    pub strategy_router: Address,
+    pub yield_aggregator: Address,
+    pub lst_cspr_token: Address,
+    pub cv_cspr_token: Address,
+    pub timestamp: u64,
+}
@@ -0,0 +1,319 @@
+/// VaultLens - read-only position reports and withdrawal simulations
+///
+/// Aggregates what front-ends and bots would otherwise stitch together from
+/// several contracts: what a user holds, what each withdrawal path would pay
+/// right now, how long pending requests have left and what the position is
+/// projected to earn.
+///
+/// Only public views of the other contracts are used, so the lens keeps
+/// working across their upgrades and can be redeployed at will. Payouts come
+/// from the vault's `preview_*` views, which share their valuation with the
+/// executing entrypoints.
+
+use odra::prelude::*;
+use odra::{Address, Var};
+use odra::casper_types::{U256, U512};
+use crate::core::{
+    AssetComposition, LiquidStakingContractRef, StrategyRouterContractRef, VaultManagerContractRef,
+    YieldAggregatorContractRef,
+};
+use crate::types::*;
+use crate::utils::{
+    accrue_apr, net_apy_bps, safe_u256_to_u512, safe_u512_to_u32, safe_u512_to_u64,
+};
+
+/// Withdrawal paths accepted by `simulate_withdrawal`
+pub const WITHDRAW_PATH_STANDARD: u8 = 0;
+pub const WITHDRAW_PATH_INSTANT: u8 = 1;
+pub const WITHDRAW_PATH_REQUEST: u8 = 2;
+
+/// Most recent withdrawal requests scanned for a position report
+pub const MAX_REQUESTS_SCANNED: u64 = 100;
+
+/// Window of the realized APY in position reports (30 days)
+const REALIZED_APY_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+/// Motes in one CSPR (amounts and cvCSPR both use 9 decimals)
+pub const MOTES_PER_CSPR: u64 = 1_000_000_000;
+
+/// Whole-CSPR amounts listed by `get_conversion_table`
+pub const CONVERSION_LADDER_CSPR: [u64; 5] = [1, 10, 100, 1_000, 10_000];
+
+/// Open withdrawal request of a user
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct PendingRequest {
+    pub request_id: u64,
+    pub shares: U512,
+    pub assets_value: U512,
+    pub unlock_time: u64,
+    /// Zero once the request can be completed
+    pub seconds_remaining: u64,
+    /// What `complete_withdrawal` would pay now
+    pub payout: U512,
+}
+
+/// Snapshot of a user's position
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct PositionReport {
+    pub shares: U512,
+    /// Exit value in lstCSPR, before performance and instant fees
+    pub assets: U512,
+    /// `assets` redeemed through LiquidStaking
+    pub cspr_value: U512,
+    pub cost_basis: U512,
+    /// Value above cost basis (zero at a loss)
+    pub unrealized_profit: U512,
+    /// End of the holding period; withdrawals revert before it
+    pub withdrawable_at: u64,
+    pub pending_requests: Vec<PendingRequest>,
+    /// Router-blended strategy APY (bps)
+    pub gross_apy_bps: U256,
+    /// Gross APY net of performance and management fees (bps)
+    pub net_apy_bps: U256,
+    /// Average APY reported by compounds over the last 30 days (bps)
+    pub realized_apy_30d_bps: U256,
+}
+
+/// Vault-wide figures
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct VaultInfo {
+    /// Assets backing shares, excluding still-locked profit
+    pub total_assets: U512,
+    pub total_shares: U512,
+    /// lstCSPR per share (1e18 scale)
+    pub share_price: U256,
+    pub composition: AssetComposition,
+    pub pending_withdrawals: u32,
+    pub pending_withdrawal_assets: U512,
+}
+
+/// Result of a simulated withdrawal
+#[derive(odra::OdraType, Debug, PartialEq, Eq)]
+pub struct WithdrawalSimulation {
+    pub path: u8,
+    pub shares: U512,
+    /// Payout after all fees (for requests: at completion, at today's value)
+    pub payout: U512,
+    /// Whether the call would go through now
+    pub available: bool,
+    /// Earliest time the payout can be received
+    pub ready_at: u64,
+}
+
+/// VaultLens contract
+#[odra::module]
+pub struct VaultLens {
+    vault_manager: Var<Address>,
+    liquid_staking: Var<Address>,
+    strategy_router: Var<Address>,
+    yield_aggregator: Var<Address>,
+}
+
+#[odra::module]
+impl VaultLens {
+    /// Initialize the lens with the contracts it reads from
+    pub fn init(
+        &mut self,
+        vault_manager: Address,
+        liquid_staking: Address,
+        strategy_router: Address,
+        yield_aggregator: Address,
+    ) {
+        self.vault_manager.set(vault_manager);
+        self.liquid_staking.set(liquid_staking);
+        self.strategy_router.set(strategy_router);
+        self.yield_aggregator.set(yield_aggregator);
+    }
+
+    /// Full position of `user`
+    ///
+    /// Pending requests are searched among the last `MAX_REQUESTS_SCANNED`
+    /// requests vault-wide.
+    pub fn get_position_report(&self, user: Address) -> PositionReport {
+        let vault = self.vault();
+        let now = self.env().get_block_time();
+
+        let shares = vault.get_user_shares(user);
+        let assets = vault.preview_redeem(shares);
+        let cost_basis = vault.get_user_deposit(user).map(|deposit| deposit.cost_basis).unwrap_or_default();
+        let withdrawable_at = self.holding_period_end(user);
+
+        let mut pending_requests = Vec::new();
+        let next_id = vault.get_next_withdrawal_id();
+        let first_id = next_id.saturating_sub(MAX_REQUESTS_SCANNED);
+        for id in first_id..next_id {
+            if let Some(request) = vault.get_withdrawal_request(id) {
+                if request.user == user && request.status.is_pending() {
+                    pending_requests.push(PendingRequest {
+                        request_id: id,
+                        shares: request.shares,
+                        assets_value: request.assets_value,
+                        unlock_time: request.unlock_time,
+                        seconds_remaining: request.unlock_time.saturating_sub(now),
+                        payout: vault.preview_complete_withdrawal(id),
+                    });
+                }
+            }
+        }
+
+        let (gross_apy_bps, net_apy_bps) = self.apys();
+
+        PositionReport {
+            shares,
+            assets,
+            cspr_value: self.staking().lst_cspr_to_cspr_precise(assets),
+            cost_basis,
+            unrealized_profit: assets.saturating_sub(cost_basis),
+            withdrawable_at,
+            pending_requests,
+            gross_apy_bps,
+            net_apy_bps,
+            realized_apy_30d_bps: self.aggregator().get_historical_apy(REALIZED_APY_WINDOW),
+        }
+    }
+
+    /// Size, share price and asset composition of the vault
+    pub fn get_vault_info(&self) -> VaultInfo {
+        let vault = self.vault();
+        VaultInfo {
+            total_assets: vault.total_assets(),
+            total_shares: vault.get_total_shares(),
+            share_price: vault.get_share_price_precise(),
+            composition: vault.get_asset_composition(),
+            pending_withdrawals: vault.get_pending_withdrawal_count(),
+            pending_withdrawal_assets: vault.get_total_pending_withdrawal_assets(),
+        }
+    }
+
+    /// Simulate withdrawing `shares` of `user` through `path`
+    ///
+    /// Payouts match what the executed call would return in the same
+    /// state. Reverts with `InvalidRequest` for an unknown path.
+    pub fn simulate_withdrawal(&self, user: Address, shares: U512, path: u8) -> WithdrawalSimulation {
+        let vault = self.vault();
+        let now = self.env().get_block_time();
+        let holding_end = self.holding_period_end(user);
+        let has_shares = !shares.is_zero() && shares <= vault.get_user_shares(user);
+        let unlocked = now >= holding_end;
+
+        let (payout, available, ready_at) = match path {
+            WITHDRAW_PATH_STANDARD => (
+                vault.preview_withdraw(user, shares),
+                has_shares && unlocked,
+                holding_end.max(now),
+            ),
+            WITHDRAW_PATH_INSTANT => {
+                let payout = vault.preview_instant_withdraw(user, shares);
+                (payout, has_shares && unlocked && !payout.is_zero(), holding_end.max(now))
+            },
+            WITHDRAW_PATH_REQUEST => (
+                vault.preview_request_withdrawal(user, shares),
+                has_shares && unlocked,
+                holding_end.max(now) + vault.get_withdrawal_timelock(),
+            ),
+            _ => self.env().revert(VaultError::InvalidRequest),
+        };
+
+        WithdrawalSimulation { path, shares, payout, available, ready_at }
+    }
+
+    /// Yield the position would earn over `days` at the current net APY
+    ///
+    /// Simple interest on the exit value, in lstCSPR.
+    pub fn project_yield(&self, user: Address, days: u64) -> U512 {
+        let vault = self.vault();
+        let assets = vault.preview_redeem(vault.get_user_shares(user));
+        let (_, net_apy_bps) = self.apys();
+        let net_apy_bps = safe_u512_to_u32(safe_u256_to_u512(net_apy_bps)).unwrap_or_revert(&self.env());
+
+        accrue_apr(assets, net_apy_bps, days * 86400).unwrap_or_revert(&self.env())
+    }
+
+    /// Value of `shares` split into whole CSPR and the remaining motes
+    ///
+    /// Uses the vault's unrounded share price (`convert_to_assets`), before
+    /// any exit fees. The fractional part is always below `MOTES_PER_CSPR`.
+    pub fn format_assets(&self, shares: U512) -> (u64, u32) {
+        let assets = self.vault().convert_to_assets(shares);
+        let motes = U512::from(MOTES_PER_CSPR);
+
+        let whole = safe_u512_to_u64(assets / motes).unwrap_or_revert(&self.env());
+        let fractional = safe_u512_to_u32(assets % motes).unwrap_or_revert(&self.env());
+        (whole, fractional)
+    }
+
+    /// cvCSPR (in motes) a deposit of `whole_cspr` CSPR would mint now
+    pub fn shares_for_cspr(&self, whole_cspr: u64) -> U512 {
+        self.vault().convert_to_shares(U512::from(whole_cspr) * U512::from(MOTES_PER_CSPR))
+    }
+
+    /// `(shares, assets)` for each amount of `CONVERSION_LADDER_CSPR`
+    ///
+    /// `shares` is what the amount would mint and `assets` what those
+    /// shares are worth back; both round down, so `assets` can sit a few
+    /// motes under the amount. Meant for checking UI rendering.
+    pub fn get_conversion_table(&self) -> Vec<(U512, U512)> {
+        let vault = self.vault();
+        CONVERSION_LADDER_CSPR
+            .iter()
+            .map(|whole_cspr| {
+                let shares = vault.convert_to_shares(U512::from(*whole_cspr) * U512::from(MOTES_PER_CSPR));
+                (shares, vault.convert_to_assets(shares))
+            })
+            .collect()
+    }
+
+    pub fn get_vault_manager(&self) -> Option<Address> {
+        self.vault_manager.get()
+    }
+
+    pub fn get_liquid_staking(&self) -> Option<Address> {
+        self.liquid_staking.get()
+    }
+
+    pub fn get_strategy_router(&self) -> Option<Address> {
+        self.strategy_router.get()
+    }
+
+    pub fn get_yield_aggregator(&self) -> Option<Address> {
+        self.yield_aggregator.get()
+    }
+}
+
+impl VaultLens {
+    fn vault(&self) -> VaultManagerContractRef {
+        VaultManagerContractRef::new(self.env(), self.vault_manager.get().unwrap())
+    }
+
+    fn staking(&self) -> LiquidStakingContractRef {
+        LiquidStakingContractRef::new(self.env(), self.liquid_staking.get().unwrap())
+    }
+
+    fn router(&self) -> StrategyRouterContractRef {
+        StrategyRouterContractRef::new(self.env(), self.strategy_router.get().unwrap())
+    }
+
+    fn aggregator(&self) -> YieldAggregatorContractRef {
+        YieldAggregatorContractRef::new(self.env(), self.yield_aggregator.get().unwrap())
+    }
+
+    /// When the user's holding period ends (now or earlier if none applies)
+    fn holding_period_end(&self, user: Address) -> u64 {
+        let vault = self.vault();
+        let min_holding = vault.get_min_holding_period();
+        match vault.get_user_deposit(user) {
+            Some(deposit) if min_holding > 0 => deposit.last_deposit_time + min_holding,
+            _ => 0,
+        }
+    }
+
+    /// (gross, net) APY in bps
+    ///
+    /// Net takes the performance fee off the yield and the management fee
+    /// off the result.
+    fn apys(&self) -> (U256, U256) {
+        let gross = self.router().calculate_blended_apy();
+        let (performance_bps, management_bps, _) = self.vault().get_fees();
+        (gross, net_apy_bps(gross, performance_bps, management_bps).unwrap_or_revert(&self.env()))
+    }
+}
@@ -1,31 +1,664 @@
 use odra::prelude::*;
 use odra::{Address, Mapping, SubModule, Var};
-use odra::casper_types::{U256, U512};
-use crate::types::events::{Deposit, Withdraw, WithdrawalRequested, WithdrawalCompleted, InstantWithdrawal, ManagementFeesCollected, FundsRescued};
-use crate::types::errors::VaultError;
-use crate::utils::{AccessControl, ReentrancyGuard, Pausable};
+use odra::casper_types::bytesrepr::{self, FromBytes, ToBytes};
+use odra::casper_types::{CLType, CLTyped, U256, U512};
+use crate::types::events::{Deposit, Withdraw, WithdrawalRequested, WithdrawalRequestTransferred, WithdrawalExpired, WithdrawalCompleted, BatchedExitQueued, BatchedExitClaimed, KeeperRebatePaid, KeeperRebatesClaimed, InstantWithdrawal, InstantWithdrawalRejected, ManagementFeesCollected, ProfitReported, YieldReported, CompoundBountyPaid, LossReported, InsolventExit, ExitSizeFeeCharged, VaultReset, AccountFrozen, AccountUnfrozen, FeeExemptionChanged, FundsRescued, FeesUpdated, TreasuryChanged, MigrationEnabled, PositionMigrated, MigratedPositionReceived, InvariantViolated, DepositsHalted, AccountingDivergence, RiskPreferenceSet, StakingOnlyDeposit, StakingOnlyWithdraw, ConfigChanged, config_address};
+use crate::types::errors::{InvariantError, VaultError};
+use crate::utils::{accrue_apr, annualize_bps, bps_of, check_record_version, read_field_since, ratio_bps, safe_u256_to_u512, safe_u512_to_u256, safe_u512_to_u64, usd_to_motes, feature_flags, AccessControl, ApprovalsRequired, BuildInfo, PriceOracleContractRef, ReentrancyGuard, Pausable, Role};
+use crate::core::strategy_router::StrategyRouterContractRef;
+use crate::core::liquid_staking::LiquidStakingContractRef;
+use crate::core::keeper_hub::JOB_COLLECT_MANAGEMENT_FEES;
+use crate::core::yield_aggregator::{YieldAggregatorContractRef, MAX_COMBINED_YIELD_TAKE_BPS};
+use crate::tokens::cv_cspr::{CvCsprContractRef, CV_CSPR_DECIMALS};
+use crate::tokens::lst_cspr::LstCsprContractRef;
 
 
+/// Current encoding version of `WithdrawalRequest`
+/// 
+/// v2 packs the request into a single record: `completed` became `status`
+/// (same byte for pending/completed) and the per-request fields kept in
+/// separate mappings were appended. v3 appended `exit_fee`.
+pub const WITHDRAWAL_REQUEST_VERSION: u8 = 3;
+
+/// Current encoding version of `UserDeposit`
+/// 
+/// v2 appended `first_deposit_time` and `total_withdrawn` for the realized
+/// APY view.
+pub const USER_DEPOSIT_VERSION: u8 = 2;
+
+/// Lifecycle of a withdrawal or unbonding request, stored as one byte
+/// 
+/// Pending and Completed encode as the `false`/`true` byte of the old
+/// `completed` flag, so v1 records decode unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RequestStatus {
+    #[default]
+    Pending = 0,
+    Completed = 1,
+    Cancelled = 2,
+    Expired = 3,
+}
+
+impl RequestStatus {
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(RequestStatus::Pending),
+            1 => Some(RequestStatus::Completed),
+            2 => Some(RequestStatus::Cancelled),
+            3 => Some(RequestStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Neither paid out nor closed
+    pub fn is_pending(self) -> bool {
+        self == RequestStatus::Pending
+    }
+}
+
+impl CLTyped for RequestStatus {
+    fn cl_type() -> CLType {
+        CLType::U8
+    }
+}
+
+impl ToBytes for RequestStatus {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        self.to_u8().to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        1
+    }
+}
+
+impl FromBytes for RequestStatus {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (value, rest) = u8::from_bytes(bytes)?;
+        let status = RequestStatus::from_u8(value).ok_or(bytesrepr::Error::Formatting)?;
+        Ok((status, rest))
+    }
+}
+
 /// Withdrawal request structure for time-locked withdrawals
-/// Note: Odra automatically implements CLTyped, ToBytes, FromBytes for structs with basic derives
-#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+/// 
+/// Versioned like `UserDeposit`; decode returned blobs with
+/// `upgrade_request` applied. The whole request lives in one record, so
+/// creating or settling it is a single read and write.
+#[derive(Debug, PartialEq, Eq)]
 pub struct WithdrawalRequest {
+    /// Encoding version the request was written with
+    pub version: u8,
+    /// Current owner, which changes on transfer
     pub user: Address,
     pub shares: U512,
+    /// Value at request time; caps the payout (see `request_withdrawal`)
     pub assets_value: U512,
     pub request_time: u64,
     pub unlock_time: u64,
-    pub completed: bool,
+    pub status: RequestStatus,
+    /// Requester's cost basis at request time (performance fee survives
+    /// transfers); since v2
+    pub cost_basis: Option<U512>,
+    /// Performance fee in force when the request was made; since v2
+    pub fee_bps: u32,
+    /// A keeper may complete it on the owner's behalf; since v2
+    pub auto_complete: bool,
+    /// Loss epoch the request was made in; since v2
+    pub loss_epoch: u32,
+    /// Assets moved in transit for it; since v2
+    pub in_transit: U512,
+    /// Size-tiered exit fee charged at request time, held back again if
+    /// the payout is repriced at completion; since v3
+    pub exit_fee: U512,
 }
 
-/// User deposit tracking for performance fee calculation  
-/// Note: Odra automatically implements CLTyped, ToBytes, FromBytes for structs with basic derives
-#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+/// User deposit tracking for performance fees and the daily deposit limit
+/// 
+/// Kept in one record so a deposit costs a single read and write.
+/// `daily_deposited` counts deposits in UTC day `deposit_day`
+/// (block time / `DEPOSIT_DAY_SECONDS`) and restarts when a later day begins.
+/// 
+/// Migration rule: the record is encoded as its `version` byte followed by
+/// the fields in declaration order, and fields are only ever appended. A
+/// new field bumps `USER_DEPOSIT_VERSION` and is decoded with
+/// `read_field_since`, so a record stored under an older version reads
+/// back with the new field at its default. Reads go through
+/// `upgrade_user_deposit`, which fills in anything a plain default can't
+/// and stamps the current version.
+#[derive(Debug, PartialEq, Eq)]
 pub struct UserDeposit {
+    /// Encoding version the record was written with
+    pub version: u8,
     pub total_deposited: U512,
     pub total_shares: U512,
     pub cost_basis: U512,
     pub last_deposit_time: u64,
+    pub daily_deposited: U512,
+    pub deposit_day: u64,
+    /// First deposit into the current position; since v2
+    pub first_deposit_time: u64,
+    /// CSPR paid out of the current position, after fees; since v2
+    pub total_withdrawn: U512,
+}
+
+impl Default for UserDeposit {
+    fn default() -> Self {
+        Self {
+            version: USER_DEPOSIT_VERSION,
+            total_deposited: U512::zero(),
+            total_shares: U512::zero(),
+            cost_basis: U512::zero(),
+            last_deposit_time: 0,
+            daily_deposited: U512::zero(),
+            deposit_day: 0,
+            first_deposit_time: 0,
+            total_withdrawn: U512::zero(),
+        }
+    }
+}
+
+impl CLTyped for UserDeposit {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for UserDeposit {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.version.to_bytes()?);
+        buffer.extend(self.total_deposited.to_bytes()?);
+        buffer.extend(self.total_shares.to_bytes()?);
+        buffer.extend(self.cost_basis.to_bytes()?);
+        buffer.extend(self.last_deposit_time.to_bytes()?);
+        buffer.extend(self.daily_deposited.to_bytes()?);
+        buffer.extend(self.deposit_day.to_bytes()?);
+        buffer.extend(self.first_deposit_time.to_bytes()?);
+        buffer.extend(self.total_withdrawn.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.version.serialized_length()
+            + self.total_deposited.serialized_length()
+            + self.total_shares.serialized_length()
+            + self.cost_basis.serialized_length()
+            + self.last_deposit_time.serialized_length()
+            + self.daily_deposited.serialized_length()
+            + self.deposit_day.serialized_length()
+            + self.first_deposit_time.serialized_length()
+            + self.total_withdrawn.serialized_length()
+    }
+}
+
+impl FromBytes for UserDeposit {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (version, rest) = u8::from_bytes(bytes)?;
+        check_record_version(version, USER_DEPOSIT_VERSION)?;
+        let (total_deposited, rest) = U512::from_bytes(rest)?;
+        let (total_shares, rest) = U512::from_bytes(rest)?;
+        let (cost_basis, rest) = U512::from_bytes(rest)?;
+        let (last_deposit_time, rest) = u64::from_bytes(rest)?;
+        let (daily_deposited, rest) = U512::from_bytes(rest)?;
+        let (deposit_day, rest) = u64::from_bytes(rest)?;
+        let (first_deposit_time, rest) = read_field_since(rest, version, 2)?;
+        let (total_withdrawn, rest) = read_field_since(rest, version, 2)?;
+        Ok((
+            UserDeposit {
+                version,
+                total_deposited,
+                total_shares,
+                cost_basis,
+                last_deposit_time,
+                daily_deposited,
+                deposit_day,
+                first_deposit_time,
+                total_withdrawn,
+            },
+            rest,
+        ))
+    }
+}
+
+impl CLTyped for WithdrawalRequest {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for WithdrawalRequest {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.version.to_bytes()?);
+        buffer.extend(self.user.to_bytes()?);
+        buffer.extend(self.shares.to_bytes()?);
+        buffer.extend(self.assets_value.to_bytes()?);
+        buffer.extend(self.request_time.to_bytes()?);
+        buffer.extend(self.unlock_time.to_bytes()?);
+        buffer.extend(self.status.to_bytes()?);
+        buffer.extend(self.cost_basis.to_bytes()?);
+        buffer.extend(self.fee_bps.to_bytes()?);
+        buffer.extend(self.auto_complete.to_bytes()?);
+        buffer.extend(self.loss_epoch.to_bytes()?);
+        buffer.extend(self.in_transit.to_bytes()?);
+        buffer.extend(self.exit_fee.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.version.serialized_length()
+            + self.user.serialized_length()
+            + self.shares.serialized_length()
+            + self.assets_value.serialized_length()
+            + self.request_time.serialized_length()
+            + self.unlock_time.serialized_length()
+            + self.status.serialized_length()
+            + self.cost_basis.serialized_length()
+            + self.fee_bps.serialized_length()
+            + self.auto_complete.serialized_length()
+            + self.loss_epoch.serialized_length()
+            + self.in_transit.serialized_length()
+            + self.exit_fee.serialized_length()
+    }
+}
+
+impl FromBytes for WithdrawalRequest {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (version, rest) = u8::from_bytes(bytes)?;
+        check_record_version(version, WITHDRAWAL_REQUEST_VERSION)?;
+        let (user, rest) = Address::from_bytes(rest)?;
+        let (shares, rest) = U512::from_bytes(rest)?;
+        let (assets_value, rest) = U512::from_bytes(rest)?;
+        let (request_time, rest) = u64::from_bytes(rest)?;
+        let (unlock_time, rest) = u64::from_bytes(rest)?;
+        let (status, rest) = RequestStatus::from_bytes(rest)?;
+        let (cost_basis, rest) = read_field_since(rest, version, 2)?;
+        let (fee_bps, rest) = read_field_since(rest, version, 2)?;
+        let (auto_complete, rest) = read_field_since(rest, version, 2)?;
+        let (loss_epoch, rest) = read_field_since(rest, version, 2)?;
+        let (in_transit, rest) = read_field_since(rest, version, 2)?;
+        let (exit_fee, rest) = read_field_since(rest, version, 3)?;
+        Ok((
+            WithdrawalRequest {
+                version,
+                user,
+                shares,
+                assets_value,
+                request_time,
+                unlock_time,
+                status,
+                cost_basis,
+                fee_bps,
+                auto_complete,
+                loss_epoch,
+                in_transit,
+                exit_fee,
+            },
+            rest,
+        ))
+    }
+}
+
+/// Bring a decoded `UserDeposit` up to `USER_DEPOSIT_VERSION`
+/// 
+/// Fields added after the record's version already decoded as defaults;
+/// this is where a field whose default depends on the others gets set.
+/// A v1 position didn't record when it opened, so its latest deposit
+/// stands in for the first.
+pub fn upgrade_user_deposit(deposit: UserDeposit) -> UserDeposit {
+    let first_deposit_time = if deposit.first_deposit_time == 0 && !deposit.total_deposited.is_zero() {
+        deposit.last_deposit_time
+    } else {
+        deposit.first_deposit_time
+    };
+    UserDeposit {
+        version: USER_DEPOSIT_VERSION,
+        first_deposit_time,
+        ..deposit
+    }
+}
+
+/// Bring a decoded `WithdrawalRequest` up to `WITHDRAWAL_REQUEST_VERSION`
+pub fn upgrade_request(request: WithdrawalRequest) -> WithdrawalRequest {
+    WithdrawalRequest {
+        version: WITHDRAWAL_REQUEST_VERSION,
+        ..request
+    }
+}
+
+/// Fee revenue by stream, in lstCSPR (management fees in minted shares)
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct FeeBreakdown {
+    pub performance_fees: U512,
+    pub instant_fees: U512,
+    pub management_fee_shares: U512,
+    /// Yield the aggregator sent to the community pool instead of the vault
+    pub community_allocations: U512,
+    pub total_fees: U512,
+}
+
+/// Where the vault's assets sit, in lstCSPR
+/// 
+/// The four buckets add up to `total_assets`. `idle_lst` is lstCSPR held
+/// for deployment with no router to take it; `in_transit` is earmarked for
+/// open withdrawal requests on their way out of the strategies.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct AssetComposition {
+    pub pool_assets: U512,
+    pub deployed_assets: U512,
+    pub idle_lst_assets: U512,
+    pub in_transit_assets: U512,
+    pub total_assets: U512,
+}
+
+/// What a deposit did, returned by `deposit_with_receipt`
+/// 
+/// `share_price_at_deposit` is lstCSPR per share scaled by 1e18, the
+/// price the shares were minted at. `assets_accepted` is the lstCSPR the
+/// deposit staked into; `deployed_to_strategies` went to the router,
+/// `kept_in_pool` to the instant pool, and any rest is held idle.
+/// `remaining_daily_allowance` is in CSPR.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct DepositReceipt {
+    pub shares_minted: U512,
+    pub share_price_at_deposit: U256,
+    pub assets_accepted: U512,
+    pub deployed_to_strategies: U512,
+    pub kept_in_pool: U512,
+    pub remaining_daily_allowance: U512,
+}
+
+/// What a withdrawal paid, returned by the `*_with_receipt` exits
+/// 
+/// Amounts are lstCSPR except `net_cspr`, the payout valued at the
+/// LiquidStaking exchange rate. `gross_assets` less all three fees is
+/// `net_assets`. All zero when an instant exit is turned away.
+#[derive(Debug, Default, PartialEq, Eq, odra::OdraType)]
+pub struct WithdrawalReceipt {
+    pub shares_burned: U512,
+    pub gross_assets: U512,
+    pub performance_fee: U512,
+    pub instant_fee: U512,
+    /// Size-tiered exit fee, left in the vault
+    pub exit_size_fee: U512,
+    pub net_assets: U512,
+    pub net_cspr: U512,
+}
+
+/// Ways a `LiquidityTranche` can be paid out
+pub const LIQUIDITY_PATH_INSTANT_POOL: u8 = 0;
+pub const LIQUIDITY_PATH_STRATEGIES: u8 = 1;
+pub const LIQUIDITY_PATH_TIMELOCK: u8 = 2;
+pub const LIQUIDITY_PATH_UNBONDING: u8 = 3;
+
+/// Unbonding wait assumed when no LiquidStaking contract is wired (14 days)
+pub const FALLBACK_UNBONDING_SECONDS: u64 = 14 * 24 * 60 * 60;
+
+/// Part of an exit's value reachable from `available_at` through `path`
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct LiquidityTranche {
+    pub amount: U512,
+    pub available_at: u64,
+    pub path: u8,
+}
+
+/// When the value of `shares` can be had, soonest tranche first
+/// 
+/// Amounts are exit value before fees and add up to `assets`.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct LiquiditySchedule {
+    pub shares: U512,
+    pub assets: U512,
+    pub tranches: Vec<LiquidityTranche>,
+}
+
+/// Instant pool utilization since `since` (for tuning `instant_pool_target_bps`)
+/// 
+/// The pool balance is sampled after every operation that changes it;
+/// `avg_pool_balance` is the mean of those samples, not time-weighted.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct PoolMetrics {
+    pub instant_withdrawals_served: u64,
+    pub instant_volume_served: U512,
+    pub liquidity_rejections: u64,
+    pub samples: u64,
+    pub min_pool_balance: U512,
+    pub max_pool_balance: U512,
+    pub avg_pool_balance: U512,
+    pub current_pool_balance: U512,
+    pub since: u64,
+}
+
+/// Length of a fee revenue period (1 day)
+pub const FEE_PERIOD_SECONDS: u64 = 86400;
+
+/// Length of a daily deposit limit bucket (one UTC day)
+pub const DEPOSIT_DAY_SECONDS: u64 = 86400;
+
+/// Number of fee revenue periods retained in the ring buffer
+pub const FEE_PERIODS_RETAINED: u64 = 30;
+
+/// Youngest position `get_user_realized_apy` annualizes (7 days)
+/// 
+/// Over shorter spans a single harvest or fee charge dominates the
+/// annualized figure.
+pub const MIN_REALIZED_APY_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Operation kinds recorded in the operation log
+pub const OP_DEPOSIT: u8 = 0;
+pub const OP_WITHDRAW: u8 = 1;
+pub const OP_INSTANT_WITHDRAW: u8 = 2;
+pub const OP_COMPLETE_WITHDRAWAL: u8 = 3;
+pub const OP_MIGRATE_OUT: u8 = 4;
+pub const OP_MIGRATE_IN: u8 = 5;
+pub const OP_STAKING_ONLY_DEPOSIT: u8 = 6;
+pub const OP_STAKING_ONLY_WITHDRAW: u8 = 7;
+
+/// Fee caps (basis points)
+pub const MAX_PERFORMANCE_FEE_BPS: u32 = 5000;  // 50%
+pub const MAX_MANAGEMENT_FEE_BPS: u32 = 1000;  // 10%
+pub const MAX_INSTANT_WITHDRAWAL_FEE_BPS: u32 = 500;  // 5%
+pub const MAX_EXIT_FEE_TIER_BPS: u32 = 500;  // 5%
+
+/// Most tiers `set_exit_fee_tiers` accepts
+pub const MAX_EXIT_FEE_TIERS: usize = 5;
+
+/// Feature flags reported by `get_features`
+/// 
+/// Append one with every new user-facing entrypoint; `build_info_tests`
+/// fails when a known entrypoint ships without its flag.
+pub const VAULT_FEATURES: &[&str] = &[
+    "lst_deposits",
+    "lst_withdrawals",
+    "batched_exits",
+    "instant_withdrawals",
+    "auto_complete_withdrawals",
+    "transferable_withdrawal_requests",
+    "withdrawal_expiry",
+    "liquidity_schedule",
+    "staking_only",
+    "position_migration",
+    "fee_exemptions",
+    "fee_breakdown",
+    "usd_deposit_limits",
+    "account_freeze",
+    "invariant_checks",
+    "divergence_circuit",
+    "keeper_jobs",
+    "compound_bounty",
+    "community_pool",
+    "receipts",
+    "realized_apy",
+    "exit_size_fees",
+    "multisig_approvals",
+];
+
+/// Decimals of the underlying (CSPR motes, 1:1 for lstCSPR) and of vault shares
+pub const UNDERLYING_DECIMALS: u8 = 9;
+pub const SHARE_DECIMALS: u8 = CV_CSPR_DECIMALS;
+
+/// Cap on the keeper rebate for push-completed withdrawals (1 CSPR, in motes)
+pub const MAX_KEEPER_REBATE: u64 = 1_000_000_000;
+
+/// Most accounts frozen at once (keeps `get_frozen_accounts` bounded)
+pub const MAX_FROZEN_ACCOUNTS: usize = 50;
+
+/// Most accounts exempt from exit fees at once
+pub const MAX_FEE_EXEMPT_ACCOUNTS: usize = 10;
+
+/// Largest page `get_pending_withdrawals_page` returns
+pub const MAX_PENDING_WITHDRAWALS_PAGE: u32 = 50;
+
+/// Width of the unlock-time buckets behind `get_withdrawals_maturing_before`
+pub const MATURITY_BUCKET_SECONDS: u64 = 86400;
+
+/// Most recent operations whose accounts `check_invariants` samples
+pub const INVARIANT_SAMPLE_SIZE: u64 = 20;
+
+/// Deposit limit modes reported in the `Deposit` event
+pub const LIMIT_MODE_CSPR: u8 = 0;
+pub const LIMIT_MODE_USD: u8 = 1;
+/// USD mode configured but the oracle price was stale or missing
+pub const LIMIT_MODE_CSPR_FALLBACK: u8 = 2;
+
+/// Scale of the per-bucket yield indices (1e18 = 1 CSPR per unit)
+pub const YIELD_INDEX_SCALE: u64 = 1_000_000_000_000_000_000;
+
+/// Strategy risk a depositor opts into with `set_risk_preference`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrategyExposure {
+    /// Deposits mint vault shares and are routed to strategies
+    Full = 0,
+    /// Deposits stay in the vault as lstCSPR and earn staking yield only
+    StakingOnly = 1,
+}
+
+impl StrategyExposure {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(StrategyExposure::Full),
+            1 => Some(StrategyExposure::StakingOnly),
+            _ => None,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Entry in the bounded operation log used for off-chain reconciliation
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct OperationRecord {
+    pub id: u64,
+    pub kind: u8,
+    pub user: Address,
+    pub assets: U512,
+    pub shares: U512,
+    pub timestamp: u64,
+}
+
+/// Entry point a successor vault exposes to take over migrated positions
+///
+/// Called by the old vault only; the target checks the caller against its
+/// configured migration source.
+#[odra::external_contract]
+pub trait MigrationTarget {
+    /// Take `assets` from the caller, as lstCSPR from its allowance and
+    /// attached CSPR for the rest, and mint shares for it to `user`,
+    /// carrying over `cost_basis`; returns the shares minted
+    fn receive_migrated_position(&mut self, user: Address, assets: U512, cost_basis: U512) -> U512;
+}
+
+/// ERC-4626 share math on already-loaded totals
+///
+/// 1:1 when no shares exist yet, or in the edge case of shares without
+/// assets.
+fn assets_to_shares(assets: U512, total_shares: U512, total_assets: U512) -> U512 {
+    if total_shares.is_zero() {
+        return assets;
+    }
+    // Shares backed by nothing have no price; deposits revert before this
+    if total_assets.is_zero() {
+        return U512::zero();
+    }
+
+    // shares = (assets * totalShares) / totalAssets
+    assets.checked_mul(total_shares)
+        .unwrap()
+        .checked_div(total_assets)
+        .unwrap()
+}
+
+/// Performance fee on the profit above `cost_basis`
+///
+/// Without deposit data the entire withdrawal is treated as profit.
+fn performance_fee_on(cost_basis: Option<U512>, withdrawal_amount: U512, fee_bps: u32) -> U512 {
+    let profit = match cost_basis {
+        Some(basis) => {
+            if withdrawal_amount <= basis {
+                // No profit, no fee
+                return U512::zero();
+            }
+            withdrawal_amount.checked_sub(basis).unwrap()
+        },
+        None => withdrawal_amount,
+    };
+
+    // Apply performance fee to profit only
+    bps_of(profit, fee_bps)
+}
+
+/// Size-tiered fee on an exit worth `value` out of `tvl`
+///
+/// Tiers are `(threshold_bps, fee_bps)` by ascending threshold. Like tax
+/// brackets, each tier's fee applies only to the part of the exit between
+/// its threshold and the next one (both as shares of `tvl`), so crossing a
+/// threshold never reprices the part below it.
+fn tiered_exit_fee(value: U512, tvl: U512, tiers: &[(u32, u32)]) -> U512 {
+    let mut fee = U512::zero();
+    for (i, (threshold_bps, fee_bps)) in tiers.iter().enumerate() {
+        let lower = bps_of(tvl, *threshold_bps);
+        let upper = match tiers.get(i + 1) {
+            Some((next_threshold_bps, _)) => value.min(bps_of(tvl, *next_threshold_bps)),
+            None => value,
+        };
+        fee += bps_of(upper.saturating_sub(lower), *fee_bps);
+    }
+    fee
+}
+
+/// `ConfigChanged` rendering of an exit fee tier table, "none" when empty
+fn render_exit_fee_tiers(tiers: &[(u32, u32)]) -> String {
+    if tiers.is_empty() {
+        return "none".to_string();
+    }
+    tiers.iter()
+        .map(|(threshold_bps, fee_bps)| format!("{}:{}", threshold_bps, fee_bps))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Signed change from `baseline` to `current`, in basis points of `baseline`
+fn growth_bps(current: U512, baseline: U512) -> i64 {
+    if baseline.is_zero() {
+        return 0;
+    }
+    let (change, negative) = if current >= baseline {
+        (current - baseline, false)
+    } else {
+        (baseline - current, true)
+    };
+    let bps = ratio_bps(change, baseline)
+        .and_then(safe_u512_to_u64)
+        .unwrap_or(u64::MAX)
+        .min(i64::MAX as u64) as i64;
+    if negative { -bps } else { bps }
 }
 
 /// VaultManager - Main vault contract (ERC-4626 compliant)
@@ -54,10 +687,14 @@ pub struct UserDeposit {
 pub struct VaultManager {
     /// Access control module
     access_control: SubModule<AccessControl>,
+    /// Version and feature flags for front-ends
+    build_info: SubModule<BuildInfo>,
     /// Reentrancy guard for security
     reentrancy_guard: SubModule<ReentrancyGuard>,
     /// Pausable for emergencies
     pausable: SubModule<Pausable>,
+    /// M-of-N approvals for emergency actions
+    approvals: SubModule<ApprovalsRequired>,
     
     
     /// Total assets under management (in lstCSPR)
@@ -70,11 +707,17 @@ pub struct VaultManager {
     /// User shares mapping (user -> shares balance)
     user_shares: Mapping<Address, U512>,
     
-    /// User deposit tracking (for performance fee calculation) - flattened
-    user_total_deposited: Mapping<Address, U512>,
-    user_total_shares: Mapping<Address, U512>,
-    user_cost_basis: Mapping<Address, U512>,
-    user_last_deposit_time: Mapping<Address, u64>,
+    /// Sum of current `user_shares` balances
+    held_shares: Var<U512>,
+    
+    /// Shares locked in pending withdrawal requests
+    locked_shares: Var<U512>,
+    
+    /// User deposit tracking (performance fee basis and daily limit usage)
+    user_deposits: Mapping<Address, UserDeposit>,
+    
+    /// Number of users holding shares (treasury excluded)
+    unique_depositors: Var<u64>,
     
     
     /// cvCSPR token contract address
@@ -98,30 +741,63 @@ pub struct VaultManager {
     /// cvCSPR token address (alternative field)
     cv_cspr_token_address: Var<Address>,
     
+    /// YieldAggregator, the only caller allowed to `report_yield`
+    yield_aggregator_address: Var<Address>,
+    
     
-    /// Withdrawal requests - flattened (request_id -> fields)
-    withdrawal_request_users: Mapping<U256, Address>,
-    withdrawal_request_shares: Mapping<U256, U512>,
-    withdrawal_request_assets: Mapping<U256, U512>,
-    withdrawal_request_times: Mapping<U256, u64>,
-    withdrawal_request_unlock_times: Mapping<U256, u64>,
-    withdrawal_request_completed: Mapping<U256, bool>,
+    /// Withdrawal requests, one packed record per id
+    /// `user` holds the current owner, which changes on transfer
+    withdrawal_requests: Mapping<u64, WithdrawalRequest>,
     
-    /// Next withdrawal request ID
-    next_request_id: Var<U256>,
+    /// Batched exits - flattened (claim_id -> fields); see `exit_batched`
+    batched_exit_users: Mapping<u64, Address>,
+    batched_exit_shares: Mapping<u64, U512>,
+    batched_exit_from_pool: Mapping<u64, U512>,
+    batched_exit_fees: Mapping<u64, U512>,
+    /// Router claim for the queued part (absent if the pool covered the exit)
+    batched_exit_router_claims: Mapping<u64, U256>,
+    batched_exit_claimed: Mapping<u64, bool>,
+    next_batched_exit_id: Var<u64>,
     
-    /// Next withdrawal ID counter
-    next_withdrawal_id: Var<U256>,
+    /// Rebate a keeper keeps from each withdrawal it completes for a user
+    keeper_rebate: Var<U512>,  // Default: 0
+    
+    /// Unclaimed rebates per keeper
+    keeper_rebates_earned: Mapping<Address, U512>,
+    
+    /// Next withdrawal request id
+    next_withdrawal_id: Var<u64>,
     
     /// Timelock for standard withdrawals (in seconds)
     withdrawal_timelock: Var<u64>,  // Default: 7 days
     
+    /// Time after unlock when an uncompleted request can be expired (0 = never)
+    request_expiry: Var<u64>,  // Default: 90 days
+    
     /// Instant withdrawal pool liquidity (lstCSPR)
+    /// 
+    /// Kept in lstCSPR units like every other bucket; its CSPR value grows
+    /// with the exchange rate while it sits idle.
     instant_withdrawal_pool: Var<U512>,
     
+    /// Remaining asset composition buckets (see `AssetComposition`)
+    deployed_assets: Var<U512>,
+    idle_lst_assets: Var<U512>,
+    in_transit_assets: Var<U512>,
+    
     /// Target instant withdrawal pool percentage (basis points)
     instant_pool_target_bps: Var<u32>,  // Default: 500 (5%)
     
+    /// Instant pool utilization metrics (reset by `reset_pool_metrics`)
+    pool_metrics_served: Var<u64>,
+    pool_metrics_volume: Var<U512>,
+    pool_metrics_rejections: Var<u64>,
+    pool_metrics_samples: Var<u64>,
+    pool_metrics_sum: Var<U512>,
+    pool_metrics_min: Var<U512>,
+    pool_metrics_max: Var<U512>,
+    pool_metrics_since: Var<u64>,
+    
     
     /// Performance fee (basis points, 10000 = 100%)
     performance_fee_bps: Var<u32>,  // Default: 1000 (10%)
@@ -132,9 +808,35 @@ pub struct VaultManager {
     /// Instant withdrawal fee (basis points)
     instant_withdrawal_fee_bps: Var<u32>,  // Default: 50 (0.5%)
     
-    /// Accumulated fees (in lstCSPR)
+    /// Share of the instant fee left in the vault for remaining holders (basis points)
+    instant_fee_to_holders_bps: Var<u32>,  // Default: 0
+    
+    /// Size-tiered exit fees as (share of total assets, fee) in basis
+    /// points, by ascending threshold; see `set_exit_fee_tiers`
+    exit_fee_tiers: Var<Vec<(u32, u32)>>,  // Default: empty (off)
+    
+    /// Share of each performance fee credited to LiquidStaking's slashing insurance (basis points)
+    insurance_fee_bps: Var<u32>,  // Default: 0
+    
+    /// Lifetime performance fees credited to the insurance fund
+    insurance_fees_credited: Var<U512>,
+    
+    /// Accumulated fees (in lstCSPR), performance + instant
     fees_collected: Var<U512>,
     
+    /// Lifetime fee counters per stream (only ever increase)
+    performance_fees_collected: Var<U512>,
+    instant_fees_collected: Var<U512>,
+    management_fee_shares_minted: Var<U512>,
+    community_allocations: Var<U512>,
+    
+    /// Daily fee revenue ring buffer - flattened (slot -> fields)
+    fee_period_ids: Mapping<u64, u64>,
+    fee_period_performance: Mapping<u64, U512>,
+    fee_period_instant: Mapping<u64, U512>,
+    fee_period_management_shares: Mapping<u64, U512>,
+    fee_period_community: Mapping<u64, U512>,
+    
     /// Last management fee collection timestamp
     last_fee_collection: Var<u64>,
     
@@ -143,6 +845,8 @@ pub struct VaultManager {
     
     /// Protocol treasury address
     treasury: Var<Address>,
+    /// Treasury shares that came from management fee mints
+    treasury_fee_shares: Var<U512>,
     
     
     /// Maximum deposit per transaction (rate limiting)
@@ -154,11 +858,128 @@ pub struct VaultManager {
     /// Maximum deposit per user per day
     max_deposit_per_day: Var<U512>,  // Default: 50,000 CSPR
     
-    /// User daily deposit tracking (user -> (day, amount))
-    daily_deposits: Mapping<Address, (u64, U512)>,
+    /// USD-denominated limits (9 decimals), converted with the price oracle
+    usd_limits_enabled: Var<bool>,  // Default: false
+    max_deposit_usd: Var<U512>,
+    max_deposit_per_day_usd: Var<U512>,
+    
+    /// CSPR/USD price feed and the oldest price it may serve (seconds)
+    price_oracle: Var<Address>,
+    max_price_staleness: Var<u64>,  // Default: 1 hour
     
     /// Minimum shares to mint (prevent dust)
     min_shares: Var<U512>,  // Default: 1000 (0.000001 shares)
+    
+    /// Minimum time between a user's last deposit and any withdrawal (0 = off)
+    min_holding_seconds: Var<u64>,  // Default: 60 seconds
+    
+    
+    /// Last issued operation ID (0 = none yet)
+    operation_id: Var<u64>,
+    
+    /// Number of operations retained in the ring buffer
+    operation_log_size: Var<u64>,  // Default: 256
+    
+    /// Operation ring buffer - flattened (slot -> fields)
+    operation_ids: Mapping<u64, u64>,
+    operation_kinds: Mapping<u64, u8>,
+    operation_users: Mapping<u64, Address>,
+    operation_assets: Mapping<u64, U512>,
+    operation_shares: Mapping<u64, U512>,
+    operation_times: Mapping<u64, u64>,
+    
+    
+    /// Profit still vesting into the share price (as of `locked_profit_time`)
+    locked_profit: Var<U512>,
+    
+    /// When `locked_profit` was last updated
+    locked_profit_time: Var<u64>,
+    
+    /// Time for reported profit to fully unlock (seconds)
+    profit_unlock_duration: Var<u64>,  // Default: 6 hours
+    
+    /// Total-loss resets so far; balances and requests from earlier epochs are void
+    loss_epoch: Var<u32>,
+    
+    /// Loss epoch each holder's share balance was last written in
+    user_share_epoch: Mapping<Address, u32>,
+    
+    /// Open withdrawal requests - position -> request id, swap-removed on close
+    pending_withdrawal_ids: Mapping<u32, u64>,
+    pending_withdrawal_positions: Mapping<u64, u32>,
+    pending_withdrawal_count: Var<u32>,
+    /// Request-time value of all open requests
+    pending_withdrawal_assets: Var<U512>,
+    /// Request-time value of open requests by unlock day (`MATURITY_BUCKET_SECONDS`)
+    pending_assets_by_unlock_day: Mapping<u64, U512>,
+    /// Span of unlock days with open requests (may be wider than the live span)
+    pending_unlock_first_day: Var<u64>,
+    pending_unlock_last_day: Var<u64>,
+    
+    
+    /// Value large withdrawals at a NAV that includes un-compounded yield
+    harvest_before_withdraw: Var<bool>,  // Default: false
+    
+    /// Minimum withdrawal (assets) that triggers the pending-yield read
+    harvest_withdraw_threshold: Var<U512>,  // Default: 1,000 CSPR
+    
+    /// Maximum strategies queried for pending yield (gas bound)
+    max_harvest_strategies: Var<u32>,  // Default: 5
+    
+    
+    /// Vault positions migrate to once migration is enabled
+    migration_target: Var<Address>,
+    
+    /// When migration was enabled (deposits closed from then on)
+    migration_enabled_at: Var<u64>,
+    
+    /// Time users get to migrate themselves before the admin batch opens
+    migration_grace_period: Var<u64>,  // Default: 14 days
+    
+    /// Old vault allowed to hand positions over to this one
+    migration_source: Var<Address>,
+    
+    
+    /// Accounts barred from entering or leaving the vault, in freeze order
+    frozen_accounts: Var<Vec<Address>>,
+    frozen: Mapping<Address, bool>,
+    
+    /// Accounts whose exits skip performance and instant fees (treasury by default)
+    fee_exempt_accounts: Var<Vec<Address>>,
+    fee_exempt: Mapping<Address, bool>,
+    
+    
+    /// Halt deposits when `check_invariants` finds a critical violation
+    invariant_auto_pause: Var<bool>,  // Default: false
+    
+    /// Deposits halted by an invariant check until an admin resumes them
+    deposits_halted: Var<bool>,
+    
+    /// Largest gap between share price and lstCSPR rate growth (bps, 0 disables)
+    max_divergence_bps: Var<u32>,  // Default: 500 (5%)
+    
+    /// Window a divergence baseline is compared against before it rolls
+    divergence_window: Var<u64>,  // Default: 7 days
+    
+    /// Share price (1e18) and lstCSPR rate (CSPR per 1e18) at the baseline
+    divergence_baseline_share_price: Var<U512>,
+    divergence_baseline_rate: Var<U512>,
+    divergence_baseline_time: Var<u64>,
+    
+    
+    /// `StrategyExposure` (as u8) applied to each user's new deposits
+    risk_preference: Mapping<Address, u8>,  // Default: Full
+    
+    /// lstCSPR held for StakingOnly deposits; never routed, not in `total_assets`
+    staking_only_balances: Mapping<Address, U512>,
+    staking_only_assets: Var<U512>,
+    
+    
+    /// Blocks completions of matured requests, which a pause leaves open
+    withdrawals_frozen: Var<bool>,  // Default: false
+    
+    /// Writes to share and deposit bookkeeping; only counted in test builds
+    bookkeeping_writes: Var<u32>,
 }
 
 #[odra::module]
@@ -174,10 +995,14 @@ impl VaultManager {
     ) {
         // Initialize modules
         self.access_control.init(admin);
+        self.build_info.init(feature_flags(VAULT_FEATURES));
         self.reentrancy_guard.init();
         self.pausable.init();
         
+        self.check_share_decimals(cv_cspr_token);
+        
         self.treasury.set(treasury);
+        self.store_fee_exemption(treasury, true);
         self.cv_cspr_token.set(cv_cspr_token);
         self.lst_cspr_token.set(lst_cspr_token);
         self.liquid_staking_contract.set(liquid_staking_contract);
@@ -185,16 +1010,19 @@ impl VaultManager {
         // Initialize core state
         self.total_assets.set(U512::zero());
         self.total_shares.set(U512::zero());
-        self.next_request_id.set(U256::zero());
         
         // Set default fees (in basis points)
         self.performance_fee_bps.set(1000);      // 10%
         self.management_fee_bps.set(200);        // 2% annual
         self.instant_withdrawal_fee_bps.set(50); // 0.5%
+        self.instant_fee_to_holders_bps.set(0);  // all to the protocol
         
         // Set withdrawal timelock (7 days)
         self.withdrawal_timelock.set(7 * 24 * 60 * 60);
         
+        // Stale requests can be expired 90 days after unlock
+        self.request_expiry.set(90 * 24 * 60 * 60);
+        
         // Set instant pool target (5% of total assets)
         self.instant_pool_target_bps.set(500);
         
@@ -204,11 +1032,40 @@ impl VaultManager {
         
         // Set minimum shares (prevent dust attacks)
         self.min_shares.set(U512::from(1000u64));
+        self.min_holding_seconds.set(60);
+        self.usd_limits_enabled.set(false);
+        self.max_price_staleness.set(3600);
         
         // Initialize fees and pool
         self.fees_collected.set(U512::zero());
+        self.performance_fees_collected.set(U512::zero());
+        self.instant_fees_collected.set(U512::zero());
+        self.management_fee_shares_minted.set(U512::zero());
+        self.community_allocations.set(U512::zero());
         self.instant_withdrawal_pool.set(U512::zero());
+        self.clear_pool_metrics();
         self.last_fee_collection.set(self.env().get_block_time());
+        self.last_management_fee_collection.set(self.env().get_block_time());
+        
+        // Operation log
+        self.operation_id.set(0);
+        self.operation_log_size.set(256);
+        
+        // Profit unlocking
+        self.locked_profit.set(U512::zero());
+        self.profit_unlock_duration.set(6 * 60 * 60);
+        
+        // Harvest-on-withdraw (off until enabled by admin)
+        self.harvest_before_withdraw.set(false);
+        self.harvest_withdraw_threshold.set(U512::from(1_000_000_000_000u64)); // 1,000 CSPR
+        self.max_harvest_strategies.set(5);
+        
+        // Migration (disabled until an admin names a target)
+        self.migration_grace_period.set(14 * 24 * 60 * 60);
+        
+        // Accounting divergence circuit
+        self.max_divergence_bps.set(500);
+        self.divergence_window.set(7 * 24 * 60 * 60);
     }
 
 
@@ -227,75 +1084,90 @@ impl VaultManager {
     /// 10. Emit Deposit event
     /// 
     /// **Returns:** Amount of cvCSPR shares minted
+    #[odra(payable)]
     pub fn deposit(&mut self) -> U512 {
-        // Security checks
+        let (caller, amount, lst_cspr_received) = self.accept_cspr_deposit();
+        
+        let shares_to_mint = if self.is_staking_only(&caller) {
+            let staked = self.stake_staking_only(amount);
+            self.credit_staking_only(caller, amount, staked)
+        } else {
+            self.mint_deposit(caller, amount, lst_cspr_received).shares_minted
+        };
+        
+        self.reentrancy_guard.exit();
+        shares_to_mint
+    }
+
+    /// `deposit`, returning a `DepositReceipt` instead of the share count
+    /// 
+    /// For StakingOnly depositors no shares are minted and nothing is
+    /// routed; the receipt carries the lstCSPR credited to their balance.
+    #[odra(payable)]
+    pub fn deposit_with_receipt(&mut self) -> DepositReceipt {
+        let (caller, amount, lst_cspr_received) = self.accept_cspr_deposit();
+        
+        let receipt = if self.is_staking_only(&caller) {
+            let staked = self.stake_staking_only(amount);
+            let credited = self.credit_staking_only(caller, amount, staked);
+            DepositReceipt {
+                shares_minted: U512::zero(),
+                share_price_at_deposit: self.get_share_price_precise(),
+                assets_accepted: credited,
+                deployed_to_strategies: U512::zero(),
+                kept_in_pool: U512::zero(),
+                remaining_daily_allowance: self.max_deposit(caller),
+            }
+        } else {
+            self.mint_deposit(caller, amount, lst_cspr_received)
+        };
+        
+        self.reentrancy_guard.exit();
+        receipt
+    }
+
+    /// Deposit lstCSPR directly and receive cvCSPR vault shares
+    /// 
+    /// For holders who already have lstCSPR: skips the staking hop. The
+    /// caller must have approved the vault on the lstCSPR token for at
+    /// least `amount`. Shares are priced exactly as for `deposit`; the
+    /// deposit limits, daily bucket and cost basis use the lstCSPR's CSPR
+    /// value at the LiquidStaking exchange rate.
+    /// 
+    /// **Returns:** Amount of cvCSPR shares minted
+    pub fn deposit_lst(&mut self, amount: U512) -> U512 {
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
-        let amount = self.env().attached_value();
         let caller = self.env().caller();
+        self.when_not_frozen(&caller);
         
         if amount.is_zero() {
             self.env().revert(VaultError::ZeroAmount);
         }
-        
-        let max_deposit = self.max_deposit.get_or_default();
-        if amount > max_deposit {
-            self.env().revert(VaultError::RateLimitExceeded);
+        if self.migration_target.get().is_some() {
+            self.env().revert(VaultError::MigrationActive);
         }
-        
-        self.check_daily_deposit_limit(&caller, amount);
-        
-        // Collect any pending management fees
-        self.collect_management_fees();
-        
-        // Step 1: Stake CSPR to get lstCSPR
-        // For now, assume 1:1 (will get actual lstCSPR amount from staking)
-        let lst_cspr_received = amount;
-        
-        // Step 2: Calculate shares to mint (ERC-4626)
-        let shares_to_mint = self.convert_to_shares(lst_cspr_received);
-        
-        // Validate minimum shares
-        if shares_to_mint < self.min_shares.get_or_default() {
-            self.env().revert(VaultError::InsufficientBalance);
+        if self.is_insolvent() {
+            self.env().revert(VaultError::VaultInsolvent);
         }
-        
-        // Step 3: Update total assets and shares
-        let current_assets = self.total_assets.get_or_default();
-        self.total_assets.set(current_assets + lst_cspr_received);
-        
-        let current_shares = self.total_shares.get_or_default();
-        self.total_shares.set(current_shares + shares_to_mint);
-        
-        // Step 4: Update user shares
-        let user_current_shares = self.user_shares.get(&caller).unwrap_or(U512::zero());
-        self.user_shares.set(&caller, user_current_shares + shares_to_mint);
-        
-        // Step 5: Update user deposit tracking (for performance fees)
-        self.update_user_deposit_tracking(&caller, amount, shares_to_mint);
-        
-        // Step 6: Mint cvCSPR shares to user
-        
-        // Step 7: Deploy to strategies
-        let amount_to_deploy = self.calculate_strategy_deployment(lst_cspr_received);
-        if amount_to_deploy > U512::zero() {
+        if self.deposits_halted.get_or_default() {
+            self.env().revert(VaultError::DepositsHalted);
         }
         
-        // Step 8: Replenish instant withdrawal pool
-        let pool_amount = lst_cspr_received - amount_to_deploy;
-        if pool_amount > U512::zero() {
-            let current_pool = self.instant_withdrawal_pool.get_or_default();
-            self.instant_withdrawal_pool.set(current_pool + pool_amount);
+        let mut token = LstCsprContractRef::new(self.env(), self.asset());
+        let vault = self.env().self_address();
+        if token.allowance(caller, vault) < amount {
+            self.env().revert(VaultError::InsufficientAllowance);
         }
+        token.transfer_from(caller, vault, amount);
         
-        self.env().emit_event(Deposit {
-            user: caller,
-            cspr_amount: amount,
-            lst_cspr_amount: lst_cspr_received,
-            shares_minted: shares_to_mint,
-            timestamp: self.env().get_block_time(),
-        });
+        let cspr_value = self.lst_cspr_value(amount);
+        let shares_to_mint = if self.is_staking_only(&caller) {
+            self.credit_staking_only(caller, cspr_value, amount)
+        } else {
+            self.mint_deposit(caller, cspr_value, amount)
+        };
         
         self.reentrancy_guard.exit();
         shares_to_mint
@@ -312,76 +1184,320 @@ impl VaultManager {
     /// 5. Calculate and collect performance fee
     /// 6. Transfer CSPR to user
     /// 
-    /// Returns: Amount of CSPR transferred to user (after fees)
+    /// Returns: Amount of CSPR transferred to user (after fees), the
+    /// lstCSPR proceeds valued at the LiquidStaking exchange rate
     pub fn withdraw(&mut self, shares: U512) -> U512 {
+        self.withdraw_shares(shares).net_cspr
+    }
+
+    /// `withdraw`, returning a `WithdrawalReceipt` instead of the payout
+    pub fn withdraw_with_receipt(&mut self, shares: U512) -> WithdrawalReceipt {
+        self.withdraw_shares(shares)
+    }
+
+    fn withdraw_shares(&mut self, shares: U512) -> WithdrawalReceipt {
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
         let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        
+        // Bill the time since the last collection before pricing the exit
+        self.accrue_management_fees();
         
         // Step 1: Validate user has enough shares
-        let user_shares = self.user_shares.get(&caller).unwrap_or_default();
+        let user_shares = self.shares_of(&caller);
         if shares > user_shares || shares.is_zero() {
             self.reentrancy_guard.exit();
             self.env().revert(VaultError::InsufficientBalance);
         }
+        if !self.holding_period_elapsed(&caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::HoldingPeriodActive);
+        }
+        if self.leaves_dust(user_shares, shares) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::DustRemainder);
+        }
         
-        // Step 2: Calculate assets using ERC-4626
-        let total_assets_value = self.convert_to_assets(shares);
+        // Step 2: Calculate assets using ERC-4626 (fresher NAV for large exits)
+        let insolvent = self.is_insolvent();
+        let gross_value = self.withdrawal_value(shares);
+        let exit_size_fee = self.charge_exit_size_fee(caller, shares, gross_value, None);
+        let total_assets_value = gross_value - exit_size_fee;
         
         // Step 3: Check instant withdrawal pool availability
         let instant_pool = self.instant_withdrawal_pool.get_or_default();
         
         // If pool has enough liquidity, use instant path (saves gas)
-        let assets_after_fee = if total_assets_value <= instant_pool {
+        let (fee_amount, assets_after_fee) = if total_assets_value <= instant_pool {
             let new_pool = instant_pool.checked_sub(total_assets_value).unwrap();
-            self.instant_withdrawal_pool.set(new_pool);
+            self.set_instant_pool(new_pool);
             
             let fee_amount = self.calculate_performance_fee(&caller, total_assets_value);
-            total_assets_value.checked_sub(fee_amount).unwrap()
+            self.book_performance_fee(fee_amount);
+            (fee_amount, total_assets_value.checked_sub(fee_amount).unwrap())
         } else {
             // Need to withdraw from strategies
             let amount_from_pool = instant_pool;
             let amount_from_strategies = total_assets_value.checked_sub(instant_pool).unwrap();
             
             // Empty the pool
-            self.instant_withdrawal_pool.set(U512::zero());
-            
-            
+            self.set_instant_pool(U512::zero());
+            self.release_assets(amount_from_strategies);
             
             let fee_amount = self.calculate_performance_fee(&caller, total_assets_value);
-            total_assets_value.checked_sub(fee_amount).unwrap()
+            self.book_performance_fee(fee_amount);
+            (fee_amount, total_assets_value.checked_sub(fee_amount).unwrap())
         };
         
         // Step 4: Burn user shares
-        let new_user_shares = user_shares.checked_sub(shares).unwrap();
-        if new_user_shares.is_zero() {
-            self.user_shares.set(&caller, U512::zero());
-            self.user_total_deposited.set(&caller, U512::zero());
-            self.user_total_shares.set(&caller, U512::zero());
-            self.user_cost_basis.set(&caller, U512::zero());
-            self.user_last_deposit_time.set(&caller, 0);
-        } else {
-            self.user_shares.set(&caller, new_user_shares);
-        }
-        
-        let total = self.total_shares.get_or_default();
-        self.total_shares.set(total.checked_sub(shares).unwrap());
+        self.record_user_withdrawal(&caller, assets_after_fee);
+        self.burn_exit_shares(&caller, user_shares, shares, total_assets_value);
         
         // Step 5: TODO: Burn cvCSPR tokens
         
         // Step 6: TODO: Transfer CSPR to user
         
+        let operation_id = self.record_operation(OP_WITHDRAW, caller, assets_after_fee, shares);
+        
         self.env().emit_event(Withdraw {
+            operation_id,
             user: caller,
             assets: assets_after_fee,
             shares,
             shares_burned: shares,
+            fee_exempt: self.is_fee_exempt(caller),
+            timestamp: self.env().get_block_time(),
+        });
+        if insolvent {
+            self.emit_insolvent_exit(caller, shares, None);
+        }
+        
+        self.reentrancy_guard.exit();
+        WithdrawalReceipt {
+            shares_burned: shares,
+            gross_assets: gross_value,
+            performance_fee: fee_amount,
+            instant_fee: U512::zero(),
+            exit_size_fee,
+            net_assets: assets_after_fee,
+            net_cspr: self.lst_cspr_value(assets_after_fee),
+        }
+    }
+
+    /// Withdraw in lstCSPR instead of CSPR
+    /// 
+    /// Nothing is unstaked, so there is no unbonding wait and no instant
+    /// fee; only the performance fee is taken. The instant pool pays first
+    /// and the router the rest; reverts if together they can't cover the
+    /// exit. Returns the lstCSPR transferred to the caller.
+    pub fn withdraw_as_lst(&mut self, shares: U512) -> U512 {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        self.accrue_management_fees();
+        
+        let user_shares = self.shares_of(&caller);
+        if shares > user_shares || shares.is_zero() {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::InsufficientBalance);
+        }
+        if !self.holding_period_elapsed(&caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::HoldingPeriodActive);
+        }
+        if self.leaves_dust(user_shares, shares) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::DustRemainder);
+        }
+        
+        let insolvent = self.is_insolvent();
+        let value = self.withdrawal_value(shares);
+        let value = value - self.charge_exit_size_fee(caller, shares, value, None);
+        let instant_pool = self.instant_withdrawal_pool.get_or_default();
+        let from_pool = value.min(instant_pool);
+        self.set_instant_pool(instant_pool - from_pool);
+        
+        let shortfall = value - from_pool;
+        self.release_assets(shortfall);
+        if !shortfall.is_zero() {
+            let flow_id = self.next_operation_id();
+            let from_router = match self.strategy_router_address.get() {
+                Some(router) => StrategyRouterContractRef::new(self.env(), router).withdraw_with_id(flow_id, shortfall),
+                None => U512::zero(),
+            };
+            if from_router < shortfall {
+                self.reentrancy_guard.exit();
+                self.env().revert(VaultError::InsufficientLiquidity);
+            }
+        }
+        
+        let fee = self.calculate_performance_fee(&caller, value);
+        self.book_performance_fee(fee);
+        let payout = value - fee;
+        self.record_user_withdrawal(&caller, payout);
+        self.burn_exit_shares(&caller, user_shares, shares, value);
+        
+        LstCsprContractRef::new(self.env(), self.asset()).transfer(caller, payout);
+        
+        let operation_id = self.record_operation(OP_WITHDRAW, caller, payout, shares);
+        
+        self.env().emit_event(Withdraw {
+            operation_id,
+            user: caller,
+            assets: payout,
+            shares,
+            shares_burned: shares,
+            fee_exempt: self.is_fee_exempt(caller),
+            timestamp: self.env().get_block_time(),
+        });
+        if insolvent {
+            self.emit_insolvent_exit(caller, shares, None);
+        }
+        
+        self.reentrancy_guard.exit();
+        payout
+    }
+
+    /// Withdraw, queueing what the instant pool can't cover at the router
+    /// 
+    /// Shares are burned now at their current value, with the performance
+    /// fee booked as in `withdraw`. The pool pays what it can and the rest
+    /// joins the router's open withdrawal batch, so several exits share one
+    /// strategy withdrawal. Returns a claim for `claim_batched_exit`,
+    /// redeemable once the batch is flushed (at most the router's batch
+    /// window later), or right away if the pool covered the exit.
+    pub fn exit_batched(&mut self, shares: U512) -> u64 {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        self.accrue_management_fees();
+        
+        let user_shares = self.shares_of(&caller);
+        if shares > user_shares || shares.is_zero() {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::InsufficientBalance);
+        }
+        if !self.holding_period_elapsed(&caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::HoldingPeriodActive);
+        }
+        if self.leaves_dust(user_shares, shares) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::DustRemainder);
+        }
+        
+        let insolvent = self.is_insolvent();
+        let value = self.withdrawal_value(shares);
+        let value = value - self.charge_exit_size_fee(caller, shares, value, None);
+        let instant_pool = self.instant_withdrawal_pool.get_or_default();
+        let from_pool = value.min(instant_pool);
+        let queued = value - from_pool;
+        self.set_instant_pool(instant_pool - from_pool);
+        self.release_assets(queued);
+        
+        let claim_id = self.next_batched_exit_id.get_or_default();
+        self.next_batched_exit_id.set(claim_id + 1);
+        
+        if !queued.is_zero() {
+            let router = match self.strategy_router_address.get() {
+                Some(router) => router,
+                None => {
+                    self.reentrancy_guard.exit();
+                    self.env().revert(VaultError::InsufficientLiquidity)
+                }
+            };
+            let router_claim = StrategyRouterContractRef::new(self.env(), router).queue_withdrawal(queued);
+            self.batched_exit_router_claims.set(&claim_id, router_claim);
+        }
+        
+        let fee = self.calculate_performance_fee(&caller, value);
+        self.book_performance_fee(fee);
+        self.batched_exit_users.set(&claim_id, caller);
+        self.batched_exit_shares.set(&claim_id, shares);
+        self.batched_exit_from_pool.set(&claim_id, from_pool);
+        self.batched_exit_fees.set(&claim_id, fee);
+        
+        self.record_user_withdrawal(&caller, value - fee);
+        self.burn_exit_shares(&caller, user_shares, shares, value);
+        
+        self.env().emit_event(BatchedExitQueued {
+            claim_id,
+            user: caller,
+            shares,
+            assets: value,
+            from_pool,
+            queued,
+            fee_exempt: self.is_fee_exempt(caller),
+            timestamp: self.env().get_block_time(),
+        });
+        if insolvent {
+            self.emit_insolvent_exit(caller, shares, None);
+        }
+        
+        self.reentrancy_guard.exit();
+        claim_id
+    }
+
+    /// Redeem a batched exit (claim owner only)
+    /// 
+    /// Pays the pool part plus what the router's batch returned for the
+    /// queued part, less the performance fee booked at exit. Reverts while
+    /// the router's batch is still open within its window.
+    pub fn claim_batched_exit(&mut self, claim_id: u64) -> U512 {
+        let user = self.batched_exit_users.get(&claim_id)
+            .unwrap_or_else(|| self.env().revert(VaultError::WithdrawalRequestNotFound));
+        if user != self.env().caller() || self.batched_exit_claimed.get(&claim_id).unwrap_or(false) {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        self.when_not_frozen(&user);
+        self.when_withdrawals_not_frozen();
+        
+        self.reentrancy_guard.enter();
+        self.batched_exit_claimed.set(&claim_id, true);
+        
+        let from_router = match (self.batched_exit_router_claims.get(&claim_id), self.strategy_router_address.get()) {
+            (Some(router_claim), Some(router)) => {
+                StrategyRouterContractRef::new(self.env(), router).take_batched_claim(router_claim)
+            }
+            _ => U512::zero(),
+        };
+        let from_pool = self.batched_exit_from_pool.get(&claim_id).unwrap_or_default();
+        let fee = self.batched_exit_fees.get(&claim_id).unwrap_or_default();
+        let payout = (from_pool + from_router).saturating_sub(fee);
+        
+        let shares = self.batched_exit_shares.get(&claim_id).unwrap_or_default();
+        self.record_operation(OP_WITHDRAW, user, payout, shares);
+        
+        self.env().emit_event(BatchedExitClaimed {
+            claim_id,
+            user,
+            assets: payout,
+            from_router,
             timestamp: self.env().get_block_time(),
         });
         
         self.reentrancy_guard.exit();
-        assets_after_fee
+        payout
+    }
+
+    /// Whether a batched exit can be claimed now
+    pub fn is_batched_exit_ready(&self, claim_id: u64) -> bool {
+        if self.batched_exit_users.get(&claim_id).is_none() || self.batched_exit_claimed.get(&claim_id).unwrap_or(false) {
+            return false;
+        }
+        
+        match (self.batched_exit_router_claims.get(&claim_id), self.strategy_router_address.get()) {
+            (Some(router_claim), Some(router)) => {
+                StrategyRouterContractRef::new(self.env(), router).is_batched_claim_ready(router_claim)
+            }
+            _ => true,
+        }
     }
 
     /// Request a time-locked withdrawal (no instant fee)
@@ -391,38 +1507,142 @@ impl VaultManager {
     /// - Can withdraw any amount (not limited by pool)
     /// 
     /// Tradeoff: Must wait timelock period (default 7 days)
-    pub fn request_withdrawal(&mut self, shares: U512) -> U256 {
+    /// 
+    /// The value at request time is a cap, not a guarantee: completion pays
+    /// the lesser of it and the shares' value at completion. The requester
+    /// gives up yield earned during the lock but still bears losses (and
+    /// management fees), so a request is no free put on the share price.
+    pub fn request_withdrawal(&mut self, shares: U512) -> u64 {
+        self.create_withdrawal_request(shares, false)
+    }
+
+    /// Request a time-locked withdrawal, optionally letting keepers complete it
+    /// 
+    /// With `auto_complete` set, any keeper can call `complete_withdrawal_for`
+    /// once the request unlocks; the payout still goes to the request's
+    /// owner, less the keeper rebate in force at completion.
+    pub fn request_withdrawal_with_auto_complete(&mut self, shares: U512, auto_complete: bool) -> u64 {
+        self.create_withdrawal_request(shares, auto_complete)
+    }
+
+    /// Complete an unlocked auto-complete request on its owner's behalf (keeper only)
+    /// 
+    /// Pays the request's owner the usual proceeds minus `keeper_rebate`,
+    /// which is credited to the calling keeper. Requests made without
+    /// `auto_complete` can only be completed by their owner.
+    pub fn complete_withdrawal_for(&mut self, request_id: u64) -> U512 {
+        self.when_withdrawals_not_frozen();
+        self.reentrancy_guard.enter();
+        
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Keeper.to_u8(), caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let payout = self.settle_withdrawal(request_id, Some(caller));
+        
+        self.reentrancy_guard.exit();
+        payout
+    }
+
+    /// Pay the calling keeper its unclaimed rebates in CSPR
+    /// 
+    /// Rebates are booked in lstCSPR, outside total assets, and paid from
+    /// the vault's purse at the LiquidStaking rate when claimed. Returns
+    /// the CSPR transferred.
+    pub fn claim_keeper_rebates(&mut self) -> U512 {
+        self.reentrancy_guard.enter();
+        
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        
+        let earned = self.keeper_rebates_earned.get(&caller).unwrap_or_default();
+        if earned.is_zero() {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        
+        let payout = self.lst_cspr_value(earned);
+        if payout > self.env().self_balance() {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::InsufficientLiquidity);
+        }
+        
+        self.keeper_rebates_earned.set(&caller, U512::zero());
+        self.env().transfer_tokens(&caller, &payout);
+        
+        self.env().emit_event(KeeperRebatesClaimed {
+            keeper: caller,
+            amount: payout,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        self.reentrancy_guard.exit();
+        payout
+    }
+
+    /// Record a withdrawal request for the caller's shares
+    fn create_withdrawal_request(&mut self, shares: U512, auto_complete: bool) -> u64 {
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
         let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        
+        // Bill the time since the last collection before pricing the exit
+        self.accrue_management_fees();
         
-        let user_shares = self.user_shares.get(&caller).unwrap_or_default();
+        let user_shares = self.shares_of(&caller);
         if shares > user_shares || shares.is_zero() {
             self.reentrancy_guard.exit();
             self.env().revert(VaultError::InsufficientBalance);
         }
-        
-        let assets_value = self.convert_to_assets(shares);
+        if !self.holding_period_elapsed(&caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::HoldingPeriodActive);
+        }
+        if self.leaves_dust(user_shares, shares) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::DustRemainder);
+        }
         
         // Create withdrawal request
         let request_id = self.next_withdrawal_id.get_or_default();
+        
+        // Size tiers use total assets now, not at completion
+        let gross_value = self.convert_to_assets(shares);
+        let exit_fee = self.charge_exit_size_fee(caller, shares, gross_value, Some(request_id));
+        let assets_value = gross_value - exit_fee;
         let unlock_time = self.env().get_block_time() + self.withdrawal_timelock.get_or_default();
         let current_time = self.env().get_block_time();
         
-        self.withdrawal_request_users.set(&request_id, caller);
-        self.withdrawal_request_shares.set(&request_id, shares);
-        self.withdrawal_request_assets.set(&request_id, assets_value);
-        self.withdrawal_request_times.set(&request_id, current_time);
-        self.withdrawal_request_unlock_times.set(&request_id, unlock_time);
-        self.withdrawal_request_completed.set(&request_id, false);
+        let request = WithdrawalRequest {
+            version: WITHDRAWAL_REQUEST_VERSION,
+            user: caller,
+            shares,
+            assets_value,
+            request_time: current_time,
+            unlock_time,
+            status: RequestStatus::Pending,
+            cost_basis: self.user_deposit(&caller).map(|deposit| deposit.cost_basis),
+            fee_bps: self.performance_fee_bps.get_or_default(),
+            auto_complete,
+            loss_epoch: self.loss_epoch.get_or_default(),
+            in_transit: self.move_in_transit(assets_value),
+            exit_fee,
+        };
+        self.withdrawal_requests.set(&request_id, request);
+        self.track_pending_withdrawal(request_id, assets_value, unlock_time);
         
         self.next_withdrawal_id.set(request_id + 1);
         
         // Lock user shares (don't burn yet)
         // User can't withdraw or transfer these shares until request is completed
         let new_user_shares = user_shares.checked_sub(shares).unwrap();
-        self.user_shares.set(&caller, new_user_shares);
+        self.set_user_shares(&caller, new_user_shares);
+        self.locked_shares.set(self.locked_shares.get_or_default() + shares);
+        self.track_depositor(&caller, user_shares, new_user_shares);
         
         self.env().emit_event(WithdrawalRequested {
             user: caller,
@@ -430,50 +1650,175 @@ impl VaultManager {
             shares,
             assets_value,
             unlock_time,
+            auto_complete,
         });
         
         self.reentrancy_guard.exit();
         request_id
     }
 
-    /// Complete a time-locked withdrawal after timelock expires
-    pub fn complete_withdrawal(&mut self, request_id: U256) -> U512 {
+    /// Transfer a pending withdrawal request to a new owner
+    /// 
+    /// The new owner completes the request and receives the proceeds; the
+    /// previous owner loses access. The performance fee is still computed
+    /// against the original requester's cost basis snapshot.
+    pub fn transfer_withdrawal_request(&mut self, request_id: u64, new_owner: Address) {
         self.pausable.when_not_paused();
+        
+        let caller = self.env().caller();
+        
+        let mut request = match self.withdrawal_request(request_id) {
+            Some(request) => request,
+            None => self.env().revert(VaultError::InvalidRequest),
+        };
+        let owner = request.user;
+        
+        if owner != caller {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        self.when_not_frozen(&owner);
+        self.when_not_frozen(&new_owner);
+        
+        if !request.status.is_pending() || new_owner == owner {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        request.user = new_owner;
+        self.withdrawal_requests.set(&request_id, request);
+        
+        self.env().emit_event(WithdrawalRequestTransferred {
+            request_id,
+            from: owner,
+            to: new_owner,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Expire a stale withdrawal request and return its shares
+    /// 
+    /// Callable by anyone once `request_expiry` has passed since the
+    /// request's unlock time. The locked shares go back to the request's
+    /// current owner (the requester unless it was transferred) and the
+    /// request can no longer be completed or transferred.
+    pub fn expire_withdrawal(&mut self, request_id: u64) {
+        self.when_withdrawals_not_frozen();
+        
+        let expiry = self.request_expiry.get_or_default();
+        if expiry == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let mut request = match self.withdrawal_request(request_id) {
+            Some(request) => request,
+            None => self.env().revert(VaultError::InvalidRequest),
+        };
+        let owner = request.user;
+        
+        if !request.status.is_pending() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        if self.env().get_block_time() < request.unlock_time + expiry {
+            self.env().revert(VaultError::TooSoon);
+        }
+        
+        self.untrack_pending_withdrawal(request_id, &request);
+        self.return_from_transit(&mut request);
+        
+        // Shares locked before a total-loss reset were written off with it
+        let shares = if self.request_written_off(&request) {
+            U512::zero()
+        } else {
+            request.shares
+        };
+        request.status = RequestStatus::Expired;
+        self.withdrawal_requests.set(&request_id, request);
+        self.locked_shares.set(self.locked_shares.get_or_default() - shares);
+        let owner_shares = self.shares_of(&owner);
+        self.set_user_shares(&owner, owner_shares + shares);
+        self.track_depositor(&owner, owner_shares, owner_shares + shares);
+        
+        self.env().emit_event(WithdrawalExpired {
+            request_id,
+            user: owner,
+            shares,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Complete a time-locked withdrawal after timelock expires
+    /// 
+    /// Allowed while the vault is paused, so a pause never traps a request
+    /// whose timelock has run; only `freeze_withdrawals` blocks it.
+    pub fn complete_withdrawal(&mut self, request_id: u64) -> U512 {
+        self.when_withdrawals_not_frozen();
         self.reentrancy_guard.enter();
         
+        let payout = self.settle_withdrawal(request_id, None);
+        
+        self.reentrancy_guard.exit();
+        payout
+    }
+
+    /// Settle an unlocked withdrawal request and return the owner's payout
+    /// 
+    /// Without a `keeper` the caller must own the request; with one the
+    /// request must have opted into auto-completion and the keeper rebate
+    /// is taken out of the payout. The caller holds the reentrancy guard.
+    fn settle_withdrawal(&mut self, request_id: u64, keeper: Option<Address>) -> U512 {
         let caller = self.env().caller();
         
+        // Accrue on the asset base that still includes this request
+        self.accrue_management_fees();
+        
         // Get request
-        let request_user = match self.withdrawal_request_users.get(&request_id) {
-            Some(user) => user,
+        let mut request = match self.withdrawal_request(request_id) {
+            Some(request) => request,
             None => {
                 self.reentrancy_guard.exit();
                 self.env().revert(VaultError::InvalidRequest);
             }
         };
-        
-        let request_shares = self.withdrawal_request_shares.get(&request_id).unwrap_or(U512::zero());
-        let request_assets = self.withdrawal_request_assets.get(&request_id).unwrap_or(U512::zero());
-        let request_unlock_time = self.withdrawal_request_unlock_times.get(&request_id).unwrap_or(0);
-        let request_completed = self.withdrawal_request_completed.get(&request_id).unwrap_or(false);
+        let request_user = request.user;
+        let request_shares = request.shares;
         
         // Validate request
-        if request_user != caller {
+        let authorized = match keeper {
+            Some(_) => request.auto_complete,
+            None => request_user == caller,
+        };
+        if !authorized {
             self.reentrancy_guard.exit();
             self.env().revert(VaultError::Unauthorized);
         }
+        self.when_not_frozen(&request_user);
         
-        if request_completed {
+        if !request.status.is_pending() {
             self.reentrancy_guard.exit();
             self.env().revert(VaultError::InvalidRequest);
         }
         
-        if self.env().get_block_time() < request_unlock_time {
+        if self.env().get_block_time() < request.unlock_time {
             self.reentrancy_guard.exit();
             self.env().revert(VaultError::Unauthorized);
         }
         
-        self.withdrawal_request_completed.set(&request_id, true);
+        self.untrack_pending_withdrawal(request_id, &request);
+        self.return_from_transit(&mut request);
+        
+        // A request locked before a total loss pays nothing; if it predates
+        // a reset its shares are already gone from the supply
+        let written_off = self.request_written_off(&request);
+        let insolvent = written_off || self.is_insolvent();
+        
+        // The lesser of the request-time value and what the shares fetch now
+        // (less the exit fee already charged): gains during the lock stay
+        // with the vault, losses are shared
+        let request_assets = if insolvent {
+            U512::zero()
+        } else {
+            request.assets_value.min(self.withdrawal_value(request_shares).saturating_sub(request.exit_fee))
+        };
         
         // Withdraw from strategies if needed
         let instant_pool = self.instant_withdrawal_pool.get_or_default();
@@ -481,107 +1826,685 @@ impl VaultManager {
         if request_assets > instant_pool {
             let amount_from_strategies = request_assets.checked_sub(instant_pool).unwrap();
             
-            
-            
-            self.instant_withdrawal_pool.set(U512::zero());
+            self.set_instant_pool(U512::zero());
+            self.release_assets(amount_from_strategies);
         } else {
             let new_pool = instant_pool.checked_sub(request_assets).unwrap();
-            self.instant_withdrawal_pool.set(new_pool);
+            self.set_instant_pool(new_pool);
         }
         
-        let fee_amount = self.calculate_performance_fee(&caller, request_assets);
+        let fee_exempt = self.is_fee_exempt(request_user);
+        let fee_amount = if fee_exempt {
+            U512::zero()
+        } else {
+            performance_fee_on(request.cost_basis, request_assets, request.fee_bps)
+        };
+        self.book_performance_fee(fee_amount);
         let assets_after_fee = request_assets.checked_sub(fee_amount).unwrap();
         
-        let total = self.total_shares.get_or_default();
-        self.total_shares.set(total.checked_sub(request_shares).unwrap());
+        // Keepers take their rebate out of the owner's proceeds
+        let rebate = match keeper {
+            Some(_) => self.keeper_rebate.get_or_default().min(assets_after_fee),
+            None => U512::zero(),
+        };
+        let payout = assets_after_fee - rebate;
+        self.record_user_withdrawal(&request_user, payout);
+        
+        request.status = RequestStatus::Completed;
+        self.withdrawal_requests.set(&request_id, request);
+        
+        if !written_off {
+            let total = self.total_shares.get_or_default();
+            self.total_shares.set(total.checked_sub(request_shares).unwrap());
+            self.locked_shares.set(self.locked_shares.get_or_default() - request_shares);
+        }
         
+        let current_assets = self.total_assets.get_or_default();
+        self.total_assets.set(current_assets.saturating_sub(request_assets));
         
+        let operation_id = self.record_operation(OP_COMPLETE_WITHDRAWAL, request_user, payout, request_shares);
+        let cspr_payout = self.lst_cspr_value(payout);
         
         self.env().emit_event(WithdrawalCompleted {
-            user: caller,
+            operation_id,
+            user: request_user,
             request_id: request_id,
-            assets: assets_after_fee,
+            assets: payout,
             shares: request_shares,
-            cspr_amount: assets_after_fee,
+            cspr_amount: cspr_payout,
+            fee_exempt,
             timestamp: self.env().get_block_time(),
         });
         
-        self.reentrancy_guard.exit();
-        assets_after_fee
+        if let Some(keeper) = keeper {
+            if !rebate.is_zero() {
+                let earned = self.keeper_rebates_earned.get(&keeper).unwrap_or_default();
+                self.keeper_rebates_earned.set(&keeper, earned + rebate);
+                
+                self.env().emit_event(KeeperRebatePaid {
+                    request_id,
+                    keeper,
+                    user: request_user,
+                    rebate,
+                    timestamp: self.env().get_block_time(),
+                });
+            }
+        }
+        if insolvent {
+            self.emit_insolvent_exit(request_user, request_shares, Some(request_id));
+        }
+        
+        cspr_payout
     }
 
     /// Instant withdrawal with fee (uses liquidity pool)
     /// 
     /// Charges instant_withdrawal_fee (default 0.5%) for immediate liquidity
-    /// Limited by instant withdrawal pool size
+    /// Limited by instant withdrawal pool size, which is held in lstCSPR;
+    /// returns the CSPR paid at the current exchange rate. A revert rolls back storage,
+    /// so a shortfall here is not counted in `get_pool_metrics`; use
+    /// `instant_withdraw_if_liquid` to have it recorded.
     pub fn instant_withdraw(&mut self, shares: U512) -> U512 {
-        self.pausable.when_not_paused();
-        self.reentrancy_guard.enter();
-        
-        let caller = self.env().caller();
-        
-        let user_shares = self.user_shares.get(&caller).unwrap_or_default();
-        if shares > user_shares || shares.is_zero() {
-            self.reentrancy_guard.exit();
-            self.env().revert(VaultError::InsufficientBalance);
-        }
-        
-        let assets_value = self.convert_to_assets(shares);
-        
-        let instant_pool = self.instant_withdrawal_pool.get_or_default();
-        if assets_value > instant_pool {
-            self.reentrancy_guard.exit();
-            self.env().revert(VaultError::InsufficientLiquidity);
+        self.instant_exit(shares, false).net_cspr
+    }
+
+    /// `instant_withdraw`, returning a `WithdrawalReceipt` instead of the payout
+    pub fn instant_withdraw_with_receipt(&mut self, shares: U512) -> WithdrawalReceipt {
+        self.instant_exit(shares, false)
+    }
+
+    /// Instant withdrawal that returns zero instead of reverting when the pool is short
+    /// 
+    /// The shortfall is counted as a liquidity rejection and emits
+    /// `InstantWithdrawalRejected`. Every other check still reverts.
+    pub fn instant_withdraw_if_liquid(&mut self, shares: U512) -> U512 {
+        self.instant_exit(shares, true).net_cspr
+    }
+
+    // EMERGENCY PAUSE
+
+    /// Pause deposits, new withdrawals and requests (guardian or admin)
+    /// 
+    /// Requests that already unlocked can still be completed, expired or
+    /// claimed; use `freeze_withdrawals` to stop those as well.
+    pub fn pause(&mut self) {
+        self.only_guardian_or_admin();
+        self.pausable.pause();
+    }
+
+    /// Lift the pause (admin only)
+    pub fn unpause(&mut self) {
+        self.access_control.only_admin();
+        self.pausable.unpause();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pausable.is_paused()
+    }
+
+    /// Block completions of matured requests too (guardian or admin)
+    /// 
+    /// Reserved for catastrophic exploits where paying out matured
+    /// requests would drain funds. Independent of `pause`.
+    pub fn freeze_withdrawals(&mut self) {
+        self.only_guardian_or_admin();
+        if self.withdrawals_frozen.get_or_default() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        self.withdrawals_frozen.set(true);
+        self.config_changed("withdrawals_frozen", false, true);
+    }
+
+    /// Let matured requests complete again (admin only)
+    pub fn unfreeze_withdrawals(&mut self) {
+        self.access_control.only_admin();
+        if !self.withdrawals_frozen.get_or_default() {
+            self.env().revert(VaultError::InvalidRequest);
         }
+        self.withdrawals_frozen.set(false);
+        self.config_changed("withdrawals_frozen", true, false);
+    }
+
+    pub fn are_withdrawals_frozen(&self) -> bool {
+        self.withdrawals_frozen.get_or_default()
+    }
+
+    // ACCOUNT FREEZES
+
+    /// Bar an account from depositing, withdrawing or migrating (guardian or admin)
+    /// 
+    /// Its shares and withdrawal requests stay as they are and can be used
+    /// again once unfrozen. The treasury and the vault itself can't be
+    /// frozen.
+    pub fn freeze_account(&mut self, account: Address, reason: String) {
+        self.only_guardian_or_admin();
         
-        let instant_fee_bps = self.instant_withdrawal_fee_bps.get_or_default();
-        let instant_fee = assets_value.checked_mul(U512::from(instant_fee_bps))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
+        if Some(account) == self.treasury.get() || account == self.env().self_address() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        if self.is_frozen(account) {
+            self.env().revert(VaultError::InvalidRequest);
+        }
         
-        let performance_fee = self.calculate_performance_fee(&caller, assets_value);
+        let mut frozen_accounts = self.frozen_accounts.get_or_default();
+        if frozen_accounts.len() >= MAX_FROZEN_ACCOUNTS {
+            self.env().revert(VaultError::RateLimitExceeded);
+        }
+        frozen_accounts.push(account);
+        self.frozen_accounts.set(frozen_accounts);
+        self.frozen.set(&account, true);
         
-        // Total fees
-        let total_fees = instant_fee.checked_add(performance_fee).unwrap();
-        let assets_after_fee = assets_value.checked_sub(total_fees).unwrap();
+        self.env().emit_event(AccountFrozen {
+            account,
+            reason,
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Lift a freeze (guardian or admin)
+    pub fn unfreeze_account(&mut self, account: Address) {
+        self.only_guardian_or_admin();
+        
+        if !self.is_frozen(account) {
+            self.env().revert(VaultError::InvalidRequest);
+        }
         
-        let new_pool = instant_pool.checked_sub(assets_value).unwrap();
-        self.instant_withdrawal_pool.set(new_pool);
+        let mut frozen_accounts = self.frozen_accounts.get_or_default();
+        frozen_accounts.retain(|frozen| *frozen != account);
+        self.frozen_accounts.set(frozen_accounts);
+        self.frozen.set(&account, false);
         
-        let current_fees = self.fees_collected.get_or_default();
-        self.fees_collected.set(current_fees.checked_add(total_fees).unwrap());
+        self.env().emit_event(AccountUnfrozen {
+            account,
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    pub fn is_frozen(&self, account: Address) -> bool {
+        self.frozen.get(&account).unwrap_or(false)
+    }
+
+    /// Currently frozen accounts (at most `MAX_FROZEN_ACCOUNTS`)
+    pub fn get_frozen_accounts(&self) -> Vec<Address> {
+        self.frozen_accounts.get_or_default()
+    }
+
+    // FEE EXEMPTIONS
+
+    /// Exempt an account from exit fees, or lift its exemption (admin only)
+    /// 
+    /// Exempt exits skip the performance, instant and exit size fees but
+    /// otherwise follow the normal rules: holding period, timelock and
+    /// pool liquidity still apply. Meant for the treasury and other
+    /// protocol-owned positions, whose exits would otherwise pay fees on
+    /// protocol revenue. At most `MAX_FEE_EXEMPT_ACCOUNTS` at once.
+    pub fn set_fee_exempt(&mut self, account: Address, exempt: bool) {
+        self.access_control.only_admin();
         
-        // Burn user shares
-        let new_user_shares = user_shares.checked_sub(shares).unwrap();
-        if new_user_shares.is_zero() {
-            self.user_shares.set(&caller, U512::zero());
-            self.user_total_deposited.set(&caller, U512::zero());
-            self.user_total_shares.set(&caller, U512::zero());
-            self.user_cost_basis.set(&caller, U512::zero());
-            self.user_last_deposit_time.set(&caller, 0);
+        if exempt == self.is_fee_exempt(account) {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        if exempt && self.fee_exempt_accounts.get_or_default().len() >= MAX_FEE_EXEMPT_ACCOUNTS {
+            self.env().revert(VaultError::RateLimitExceeded);
+        }
+        
+        self.store_fee_exemption(account, exempt);
+    }
+
+    pub fn is_fee_exempt(&self, account: Address) -> bool {
+        self.fee_exempt.get(&account).unwrap_or(false)
+    }
+
+    /// Currently fee-exempt accounts (at most `MAX_FEE_EXEMPT_ACCOUNTS`)
+    pub fn get_fee_exempt_accounts(&self) -> Vec<Address> {
+        self.fee_exempt_accounts.get_or_default()
+    }
+
+    // MIGRATION
+
+    /// Start winding the vault down into a new deployment (admin only)
+    /// 
+    /// Deposits close immediately. Holders move their position with
+    /// `migrate_position`; once the grace period has passed the admin can
+    /// move whoever is left with `migrate_positions`. Withdrawals stay
+    /// open throughout. The target must name this vault as its migration
+    /// source before positions can be received.
+    pub fn enable_migration(&mut self, target: Address) {
+        self.access_control.only_admin();
+        
+        if self.migration_target.get().is_some() || target == self.env().self_address() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        // Fees up to the wind-down are charged to the holders being moved
+        self.accrue_management_fees();
+        
+        let current_time = self.env().get_block_time();
+        self.migration_target.set(target);
+        self.migration_enabled_at.set(current_time);
+        
+        self.env().emit_event(MigrationEnabled {
+            target,
+            grace_period: self.migration_grace_period.get_or_default(),
+            timestamp: current_time,
+        });
+    }
+
+    /// Move the caller's whole position to the migration target
+    /// 
+    /// Burns the shares here at the exit price and has the target mint the
+    /// equivalent, carrying the cost basis over so the move itself books no
+    /// performance fee. Returns the shares minted on the target.
+    pub fn migrate_position(&mut self) -> U512 {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let target = match self.migration_target.get() {
+            Some(target) => target,
+            None => self.env().revert(VaultError::ConditionsNotMet),
+        };
+        
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        if self.shares_of(&caller).is_zero() {
+            self.env().revert(VaultError::InsufficientBalance);
+        }
+        
+        self.accrue_management_fees();
+        let shares_minted = self.migrate_user(caller, target);
+        
+        self.reentrancy_guard.exit();
+        shares_minted
+    }
+
+    /// Move the remaining positions of `users` (admin only)
+    /// 
+    /// Only after the grace period, for holders who did not migrate
+    /// themselves. Users without shares and frozen accounts are skipped. Returns the number
+    /// of positions moved.
+    pub fn migrate_positions(&mut self, users: Vec<Address>) -> u32 {
+        self.access_control.only_admin();
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let target = match self.migration_target.get() {
+            Some(target) => target,
+            None => self.env().revert(VaultError::ConditionsNotMet),
+        };
+        if self.env().get_block_time() < self.get_migration_batch_open_time() {
+            self.env().revert(VaultError::TimelockNotExpired);
+        }
+        
+        self.accrue_management_fees();
+        
+        let mut migrated = 0u32;
+        for user in users {
+            if self.shares_of(&user).is_zero() || self.is_frozen(user) {
+                continue;
+            }
+            self.migrate_user(user, target);
+            migrated += 1;
+        }
+        
+        self.reentrancy_guard.exit();
+        migrated
+    }
+
+    /// Take over a position from the migration source
+    /// 
+    /// Pulls what it can of `assets` lstCSPR from the source's allowance;
+    /// the attached CSPR must cover the rest. Mints shares at this vault's
+    /// price for them and adds `cost_basis` to the user's fee basis. The
+    /// assets join the instant pool until the next rebalance.
+    #[odra(payable)]
+    pub fn receive_migrated_position(&mut self, user: Address, assets: U512, cost_basis: U512) -> U512 {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let source = self.env().caller();
+        if self.migration_source.get() != Some(source) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if assets.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        if self.is_insolvent() {
+            self.env().revert(VaultError::VaultInsolvent);
+        }
+        
+        self.accrue_management_fees();
+        
+        let stored_assets = self.total_assets.get_or_default();
+        let stored_shares = self.total_shares.get_or_default();
+        let net_assets = stored_assets.saturating_sub(self.get_locked_profit());
+        let shares_minted = assets_to_shares(assets, stored_shares, net_assets);
+        self.assert_min_shares(shares_minted);
+        
+        self.total_assets.set(stored_assets + assets);
+        self.total_shares.set(stored_shares + shares_minted);
+        
+        let user_current_shares = self.shares_of(&user);
+        self.set_user_shares(&user, user_current_shares + shares_minted);
+        self.track_depositor(&user, user_current_shares, user_current_shares + shares_minted);
+        
+        let mut deposit = self.user_deposit(&user).unwrap_or_default();
+        if deposit.total_deposited.is_zero() {
+            deposit.first_deposit_time = self.env().get_block_time();
+        }
+        deposit.cost_basis = deposit.cost_basis.checked_add(cost_basis).unwrap();
+        deposit.total_deposited = deposit.total_deposited.checked_add(cost_basis).unwrap();
+        deposit.total_shares = deposit.total_shares.checked_add(shares_minted).unwrap();
+        self.user_deposits.set(&user, deposit);
+        
+        let asset = self.asset();
+        let as_lst = if asset.is_contract() {
+            let mut token = LstCsprContractRef::new(self.env(), asset);
+            let vault = self.env().self_address();
+            let as_lst = token.allowance(source, vault).min(assets);
+            if !as_lst.is_zero() {
+                token.transfer_from(source, vault, as_lst);
+            }
+            as_lst
         } else {
-            self.user_shares.set(&caller, new_user_shares);
+            U512::zero()
+        };
+        if self.env().attached_value() < self.lst_cspr_value(assets - as_lst) {
+            self.env().revert(VaultError::InsufficientAllowance);
         }
         
-        let total = self.total_shares.get_or_default();
-        self.total_shares.set(total.checked_sub(shares).unwrap());
+        let pool = self.instant_withdrawal_pool.get_or_default();
+        self.set_instant_pool(pool + assets);
         
+        self.record_operation(OP_MIGRATE_IN, user, assets, shares_minted);
         
+        self.env().emit_event(MigratedPositionReceived {
+            user,
+            source,
+            assets,
+            cost_basis,
+            shares_minted,
+            timestamp: self.env().get_block_time(),
+        });
         
-        self.env().emit_event(InstantWithdrawal {
+        self.reentrancy_guard.exit();
+        shares_minted
+    }
+
+    // STAKING-ONLY EXPOSURE
+
+    /// Choose the strategy exposure of the caller's future deposits
+    /// 
+    /// `exposure` is a `StrategyExposure` value. StakingOnly deposits are
+    /// held as lstCSPR outside the share system and earn staking yield
+    /// only; CSPR deposits are staked through LiquidStaking first. Existing
+    /// shares and balances are not moved.
+    pub fn set_risk_preference(&mut self, exposure: u8) {
+        if StrategyExposure::from_u8(exposure).is_none() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let user = self.env().caller();
+        self.risk_preference.set(&user, exposure);
+        
+        self.env().emit_event(RiskPreferenceSet {
+            user,
+            exposure,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// A user's `StrategyExposure` as u8
+    pub fn get_risk_preference(&self, user: Address) -> u8 {
+        self.risk_preference.get(&user).unwrap_or(StrategyExposure::Full.to_u8())
+    }
+
+    /// Withdraw lstCSPR from the caller's staking-only balance
+    /// 
+    /// Paid straight from the vault's lstCSPR; there is no fee since the
+    /// balance never earned strategy profit. Returns the amount transferred.
+    pub fn withdraw_staking_only(&mut self, amount: U512) -> U512 {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        
+        let balance = self.staking_only_balances.get(&caller).unwrap_or_default();
+        if amount.is_zero() || amount > balance {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::InsufficientBalance);
+        }
+        if !self.holding_period_elapsed(&caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::HoldingPeriodActive);
+        }
+        
+        self.staking_only_balances.set(&caller, balance - amount);
+        self.staking_only_assets.set(self.staking_only_assets.get_or_default() - amount);
+        LstCsprContractRef::new(self.env(), self.asset()).transfer(caller, amount);
+        
+        let operation_id = self.record_operation(OP_STAKING_ONLY_WITHDRAW, caller, amount, U512::zero());
+        self.env().emit_event(StakingOnlyWithdraw {
+            operation_id,
             user: caller,
-            assets: assets_after_fee,
-            shares,
-            shares_burned: shares,
-            fee: total_fees,
-            cspr_amount: assets_after_fee,
-            fee_amount: total_fees,
+            lst_cspr_amount: amount,
             timestamp: self.env().get_block_time(),
         });
         
         self.reentrancy_guard.exit();
-        assets_after_fee
+        amount
+    }
+
+    pub fn get_staking_only_balance(&self, user: Address) -> U512 {
+        self.staking_only_balances.get(&user).unwrap_or_default()
+    }
+
+    /// lstCSPR held for all StakingOnly depositors
+    pub fn get_staking_only_assets(&self) -> U512 {
+        self.staking_only_assets.get_or_default()
+    }
+
+    /// CSPR value of one unit of a bucket, scaled by `YIELD_INDEX_SCALE`
+    /// 
+    /// A StakingOnly unit is one lstCSPR, so its index is the LiquidStaking
+    /// exchange rate: staking yield and nothing else. A Full unit is one
+    /// share, whose lstCSPR value also grows with the strategy profit
+    /// reported into `total_assets`; its index compounds both. A user's
+    /// CSPR value in a bucket is their units times the index.
+    pub fn get_yield_index(&self, exposure: u8) -> U512 {
+        let scale = U512::from(YIELD_INDEX_SCALE);
+        match StrategyExposure::from_u8(exposure) {
+            Some(StrategyExposure::StakingOnly) => self.lst_cspr_value(scale),
+            Some(StrategyExposure::Full) if self.total_shares.get_or_default().is_zero() => self.lst_cspr_value(scale),
+            Some(StrategyExposure::Full) => self.lst_cspr_value(self.convert_to_assets(scale)),
+            None => self.env().revert(VaultError::InvalidRequest),
+        }
+    }
+
+    // INVARIANTS
+
+    /// Cheap sanity checks over the vault's books; anyone may call
+    /// 
+    /// Returns the `InvariantError` code of every violated invariant and
+    /// emits `InvariantViolated` for each, so monitors subscribed to events
+    /// are alerted even if nobody reads the result. User balances are
+    /// checked on a sample, the accounts behind the last
+    /// `INVARIANT_SAMPLE_SIZE` operations, to keep gas bounded. With
+    /// auto-pause on, a critical violation halts deposits until an admin
+    /// calls `resume_deposits`.
+    /// 
+    /// Shares live in `user_shares` rather than as cvCSPR, so total shares
+    /// are checked against the balances held there plus the shares locked
+    /// in pending withdrawal requests.
+    pub fn check_invariants(&mut self) -> Vec<u16> {
+        let mut violations = Vec::new();
+        let total_shares = self.total_shares.get_or_default();
+        
+        let booked = self.held_shares.get_or_default() + self.locked_shares.get_or_default();
+        if booked != total_shares {
+            violations.push(InvariantError::SharesSupplyMismatch);
+        }
+        
+        let composition = self.get_asset_composition();
+        if composition.pool_assets > composition.total_assets {
+            violations.push(InvariantError::PoolExceedsAssets);
+        }
+        let bucket_sum = composition.pool_assets
+            + composition.deployed_assets
+            + composition.idle_lst_assets
+            + composition.in_transit_assets;
+        if bucket_sum != composition.total_assets {
+            violations.push(InvariantError::AssetCompositionMismatch);
+        }
+        
+        let asset = self.asset();
+        if asset.is_contract() {
+            let balance = LstCsprContractRef::new(self.env(), asset)
+                .balance_of(self.env().self_address())
+                .saturating_sub(self.staking_only_assets.get_or_default());
+            if self.fees_collected.get_or_default() > balance {
+                violations.push(InvariantError::FeesExceedBalance);
+            }
+        }
+        
+        if self.sampled_user_shares() > total_shares {
+            violations.push(InvariantError::SampledSharesExceedTotal);
+        }
+        
+        let linked = [
+            self.get_cv_cspr_token(),
+            self.lst_cspr_token.get(),
+            self.get_liquid_staking(),
+            self.get_strategy_router(),
+        ];
+        if linked.iter().any(Option::is_none) {
+            violations.push(InvariantError::MissingContract);
+        }
+        let addresses: Vec<Address> = linked.iter().flatten().copied().collect();
+        if addresses.iter().enumerate().any(|(i, address)| addresses[..i].contains(address)) {
+            violations.push(InvariantError::DuplicateContract);
+        }
+        
+        let now = self.env().get_block_time();
+        for violation in violations.iter() {
+            self.env().emit_event(InvariantViolated { code: *violation as u16, timestamp: now });
+        }
+        
+        if self.invariant_auto_pause.get_or_default() && !self.deposits_halted.get_or_default() {
+            if let Some(critical) = violations.iter().find(|violation| violation.is_critical()) {
+                self.deposits_halted.set(true);
+                self.env().emit_event(DepositsHalted { code: *critical as u16, timestamp: now });
+            }
+        }
+        
+        violations.into_iter().map(|violation| violation as u16).collect()
+    }
+
+    /// Halt deposits on critical invariant violations (admin only)
+    pub fn set_invariant_auto_pause(&mut self, enabled: bool) {
+        self.access_control.only_admin();
+        let old = self.invariant_auto_pause.get_or_default();
+        self.invariant_auto_pause.set(enabled);
+        self.config_changed("invariant_auto_pause", old, enabled);
+    }
+
+    pub fn get_invariant_auto_pause(&self) -> bool {
+        self.invariant_auto_pause.get_or_default()
+    }
+
+    /// Reopen deposits halted by `check_invariants` (admin only)
+    pub fn resume_deposits(&mut self) {
+        self.access_control.only_admin();
+        if !self.deposits_halted.get_or_default() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+        self.deposits_halted.set(false);
+        self.config_changed("deposits_halted", true, false);
+    }
+
+    pub fn are_deposits_halted(&self) -> bool {
+        self.deposits_halted.get_or_default()
+    }
+
+    /// Compare share price growth with lstCSPR rate growth; anyone may call
+    /// 
+    /// Vault assets are mostly lstCSPR, so both should grow alike since the
+    /// baseline. A gap above `max_divergence_bps` points at broken
+    /// accounting or an exploit: it emits `AccountingDivergence` and, with
+    /// `invariant_auto_pause` on, halts deposits. The first call records
+    /// the baseline; once `divergence_window` has passed since it, each
+    /// call moves the baseline to the current prices after comparing.
+    /// Returns the divergence in bps.
+    pub fn check_accounting_divergence(&mut self) -> u64 {
+        let now = self.env().get_block_time();
+        let (share_price, rate) = self.accounting_prices();
+        let baseline_time = self.divergence_baseline_time.get_or_default();
+        let has_baseline = !self.divergence_baseline_share_price.get_or_default().is_zero();
+        
+        let (vault_growth_bps, staking_growth_bps) = self.growth_since_baseline();
+        let divergence = vault_growth_bps.abs_diff(staking_growth_bps);
+        let threshold = self.max_divergence_bps.get_or_default();
+        
+        if has_baseline && threshold > 0 && divergence > threshold as u64 {
+            self.env().emit_event(AccountingDivergence {
+                vault_growth_bps,
+                staking_growth_bps,
+                divergence_bps: divergence,
+                threshold_bps: threshold,
+                timestamp: now,
+            });
+            if self.invariant_auto_pause.get_or_default() && !self.deposits_halted.get_or_default() {
+                self.deposits_halted.set(true);
+                self.env().emit_event(DepositsHalted {
+                    code: InvariantError::AccountingDivergence as u16,
+                    timestamp: now,
+                });
+            }
+        }
+        
+        if !has_baseline || now >= baseline_time + self.divergence_window.get_or_default() {
+            self.divergence_baseline_share_price.set(share_price);
+            self.divergence_baseline_rate.set(rate);
+            self.divergence_baseline_time.set(now);
+        }
+        divergence
+    }
+
+    /// Share price and lstCSPR rate growth since the baseline (bps, signed)
+    pub fn get_growth_since_baseline(&self) -> (i64, i64) {
+        self.growth_since_baseline()
+    }
+
+    /// Current gap between share price and lstCSPR rate growth (bps)
+    pub fn get_accounting_divergence(&self) -> u64 {
+        let (vault_growth_bps, staking_growth_bps) = self.growth_since_baseline();
+        vault_growth_bps.abs_diff(staking_growth_bps)
+    }
+
+    /// When the divergence baseline was last recorded (0 if never)
+    pub fn get_divergence_baseline_time(&self) -> u64 {
+        self.divergence_baseline_time.get_or_default()
+    }
+
+    /// Set the divergence threshold (bps, 0 disables) and baseline window (admin only)
+    pub fn set_divergence_circuit(&mut self, max_divergence_bps: u32, window: u64) {
+        self.access_control.only_admin();
+        if max_divergence_bps > 10000 || window == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_bps = self.max_divergence_bps.get_or_default();
+        self.max_divergence_bps.set(max_divergence_bps);
+        self.config_changed("max_divergence_bps", old_bps, max_divergence_bps);
+        
+        let old_window = self.divergence_window.get_or_default();
+        self.divergence_window.set(window);
+        self.config_changed("divergence_window", old_window, window);
+    }
+
+    pub fn get_divergence_circuit(&self) -> (u32, u64) {
+        (self.max_divergence_bps.get_or_default(), self.divergence_window.get_or_default())
     }
 
     // ERC-4626 STANDARD FUNCTIONS
@@ -601,371 +2524,2726 @@ impl VaultManager {
             return assets;
         }
         
-        let total_assets = self.total_assets();
+        assets_to_shares(assets, total_shares, self.total_assets())
+    }
+
+    /// Convert shares (cvCSPR) to assets (CSPR) using ERC-4626 formula
+    /// 
+    /// Formula: assets = (shares * totalAssets) / totalShares
+    /// 
+    /// As yields accrue, totalAssets grows faster than totalShares,
+    /// making each share worth more CSPR over time
+    pub fn convert_to_assets(&self, shares: U512) -> U512 {
+        let total_shares = self.total_shares.get_or_default();
         
-        if total_assets.is_zero() {
-            // Edge case: vault has shares but no assets (shouldn't happen)
-            return assets;
+        if total_shares.is_zero() {
+            // No shares exist
+            return U512::zero();
         }
         
-        // shares = (assets * totalShares) / totalAssets
-        assets.checked_mul(total_shares)
+        let total_assets = self.total_assets();
+        
+        // assets = (shares * totalAssets) / totalShares
+        shares.checked_mul(total_assets)
+            .unwrap()
+            .checked_div(total_shares)
+            .unwrap()
+    }
+
+    /// Convert assets to shares with 18 decimals (1e9 finer than `convert_to_shares`)
+    /// 
+    /// `convert_to_shares`, `convert_to_assets` and `get_share_price` keep
+    /// 9-decimal units for compatibility. Their floor rounding loses up to
+    /// one share-mote's worth of assets per conversion, which grows with
+    /// the share price; the extra precision here keeps an
+    /// assets -> shares -> assets round trip within 1 mote.
+    pub fn convert_to_shares_precise(&self, assets: U512) -> U512 {
+        let total_shares = self.total_shares.get_or_default();
+        let total_assets = self.total_assets();
+        let scale = U512::from(1_000_000_000u64);
+        
+        if total_shares.is_zero() || total_assets.is_zero() {
+            return assets * scale;
+        }
+        
+        assets * total_shares * scale / total_assets
+    }
+
+    /// Convert shares with 18 decimals (see `convert_to_shares_precise`) to assets
+    pub fn convert_to_assets_precise(&self, shares: U512) -> U512 {
+        let total_shares = self.total_shares.get_or_default();
+        let scale = U512::from(1_000_000_000u64);
+        
+        if total_shares.is_zero() {
+            return U512::zero();
+        }
+        
+        shares * self.total_assets() / (total_shares * scale)
+    }
+
+    /// Calculate total assets under management
+    /// 
+    /// Includes:
+    /// - lstCSPR staked in LiquidStaking contract
+    /// - Assets deployed in strategy contracts
+    /// - Instant withdrawal pool
+    /// - Accrued but uncollected rewards
+    /// 
+    /// Excludes profit that is still unlocking, so a harvest raises the
+    /// share price linearly over `profit_unlock_duration` instead of in
+    /// one block.
+    pub fn total_assets(&self) -> U512 {
+        self.total_assets.get_or_default().saturating_sub(self.get_locked_profit())
+    }
+
+    /// CSPR the vault has unbonding in LiquidStaking
+    /// 
+    /// Liquidity that arrives once the requests unlock, for sizing the
+    /// instant pool and strategy withdrawals.
+    pub fn get_unbonding_in_flight(&self) -> U512 {
+        match self.get_liquid_staking() {
+            Some(staking) => LiquidStakingContractRef::new(self.env(), staking)
+                .get_user_pending_unbonding(self.env().self_address()),
+            None => U512::zero(),
+        }
+    }
+
+    /// Yield accrued in LiquidStaking and strategies but not yet compounded
+    /// 
+    /// Staking rewards are attributed pro rata to the vault's assets over
+    /// the total stake. At most `max_harvest_strategies` strategies are
+    /// read; the router falls back to recorded allocations for unhealthy
+    /// or quarantined ones.
+    pub fn get_pending_yield_estimate(&self) -> U512 {
+        let mut pending = U512::zero();
+        
+        if let Some(staking) = self.get_liquid_staking() {
+            let staking_ref = LiquidStakingContractRef::new(self.env(), staking);
+            let total_staked = staking_ref.get_total_staked();
+            
+            if !total_staked.is_zero() {
+                let rewards = staking_ref.get_pending_rewards_estimate();
+                let vault_share = rewards * self.total_assets() / total_staked;
+                pending += vault_share.min(rewards);
+            }
+        }
+        
+        if let Some(router) = self.strategy_router_address.get() {
+            let max_strategies = self.max_harvest_strategies.get_or_default();
+            pending += StrategyRouterContractRef::new(self.env(), router).get_pending_yield(max_strategies);
+        }
+        
+        pending
+    }
+
+    /// Report harvested profit (admin or operator, e.g. YieldAggregator)
+    /// 
+    /// The profit is added to total assets but locked, unlocking linearly
+    /// over `profit_unlock_duration`. Any still-locked profit from earlier
+    /// reports is carried over and restarts its unlock with the new amount.
+    pub fn report_profit(&mut self, amount: U512) {
+        self.access_control.only_admin_or_operator();
+        self.book_profit(amount);
+    }
+
+    /// Report yield the YieldAggregator just compounded (aggregator only)
+    /// 
+    /// Called at the end of `compound`, so the harvest and the rise in
+    /// total assets land in one transaction and no deposit or exit can be
+    /// priced in between. The yield is locked as in `report_profit`.
+    /// `source` names the aggregator path that produced it.
+    pub fn report_yield(&mut self, amount: U512, source: String) {
+        if self.yield_aggregator_address.get() != Some(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        self.book_profit(amount);
+        
+        self.env().emit_event(YieldReported {
+            amount,
+            source,
+            new_total_assets: self.total_assets(),
+            new_share_price: self.get_share_price_precise(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Pay a compound bounty out of yield the aggregator just harvested (aggregator only)
+    /// 
+    /// `amount` joins total assets like the rest of the yield but is not
+    /// profit: shares worth exactly `amount` at the unlocked price are
+    /// minted to `recipient`, so the share price doesn't move. The
+    /// recipient has no cost basis for them, so the whole value counts as
+    /// gain when they exit.
+    pub fn pay_compound_bounty(&mut self, recipient: Address, amount: U512) -> U512 {
+        if self.yield_aggregator_address.get() != Some(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        
+        self.accrue_management_fees();
+        
+        let stored_assets = self.total_assets.get_or_default();
+        let stored_shares = self.total_shares.get_or_default();
+        let net_assets = stored_assets.saturating_sub(self.get_locked_profit());
+        let shares = assets_to_shares(amount, stored_shares, net_assets);
+        
+        self.total_assets.set(stored_assets + amount);
+        self.deployed_assets.set(self.deployed_assets.get_or_default() + amount);
+        self.total_shares.set(stored_shares + shares);
+        
+        let recipient_shares = self.shares_of(&recipient);
+        self.set_user_shares(&recipient, recipient_shares + shares);
+        self.track_depositor(&recipient, recipient_shares, recipient_shares + shares);
+        
+        self.env().emit_event(CompoundBountyPaid {
+            recipient,
+            amount,
+            shares,
+            timestamp: self.env().get_block_time(),
+        });
+        shares
+    }
+
+    /// Record yield the aggregator sent to the community pool (aggregator only)
+    /// 
+    /// Never part of total assets; booked so the fee views show everything
+    /// taken out of holders' yield.
+    pub fn record_community_allocation(&mut self, amount: U512) {
+        if self.yield_aggregator_address.get() != Some(self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let allocated = self.community_allocations.get_or_default();
+        self.community_allocations.set(allocated + amount);
+        
+        let slot = self.current_fee_period_slot();
+        let period_allocated = self.fee_period_community.get(&slot).unwrap_or_default();
+        self.fee_period_community.set(&slot, period_allocated + amount);
+    }
+
+    /// Total assets and share price right after the aggregator reports
+    /// `reports`, as (profit, bounty) pairs, in this block
+    /// 
+    /// Follows `report_yield` and `pay_compound_bounty` for each pair in
+    /// order: management fees due are minted first, the profit locks as
+    /// usual and each bounty is priced at the unlocked price of its turn.
+    pub fn preview_report_yield(&self, reports: Vec<(U512, U512)>) -> (U512, U512) {
+        if reports.is_empty() {
+            return (self.total_assets(), self.get_share_price());
+        }
+        
+        let unlocks = self.profit_unlock_duration.get_or_default() > 0;
+        let mut stored_assets = self.total_assets.get_or_default();
+        let mut total_shares = self.total_shares.get_or_default() + self.management_fee_due().1;
+        let mut locked = self.get_locked_profit();
+        
+        for (profit, bounty) in reports {
+            stored_assets += profit;
+            if unlocks {
+                locked += profit;
+            }
+            if !bounty.is_zero() {
+                total_shares += assets_to_shares(bounty, total_shares, stored_assets.saturating_sub(locked));
+                stored_assets += bounty;
+            }
+        }
+        
+        let total_assets = stored_assets.saturating_sub(locked);
+        let share_price = if total_shares.is_zero() {
+            U512::zero()
+        } else {
+            U512::from(1_000_000_000u64) * total_assets / total_shares
+        };
+        (total_assets, share_price)
+    }
+
+    /// Add `amount` to total assets as locked profit (see `report_profit`)
+    fn book_profit(&mut self, amount: U512) {
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        
+        // Fees up to now are charged on the pre-profit assets
+        self.accrue_management_fees();
+        
+        let current_time = self.env().get_block_time();
+        let locked = self.get_locked_profit() + amount;
+        
+        let current_assets = self.total_assets.get_or_default();
+        self.total_assets.set(current_assets + amount);
+        self.deployed_assets.set(self.deployed_assets.get_or_default() + amount);
+        
+        self.locked_profit.set(locked);
+        self.locked_profit_time.set(current_time);
+        
+        self.env().emit_event(ProfitReported {
+            amount,
+            locked_profit: locked,
+            unlock_duration: self.profit_unlock_duration.get_or_default(),
+            timestamp: current_time,
+        });
+    }
+
+    /// Park harvested yield in the instant withdrawal pool (admin or operator)
+    /// 
+    /// For yield the YieldAggregator keeps liquid instead of re-deploying;
+    /// it is reported with `report_profit` first, so the pool stays within
+    /// total assets.
+    pub fn credit_instant_pool(&mut self, amount: U512) {
+        self.access_control.only_admin_or_operator();
+        
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        
+        let pool = self.instant_withdrawal_pool.get_or_default();
+        self.set_instant_pool(pool + amount);
+        self.release_assets(amount);
+    }
+
+    /// Report a realized loss, e.g. slashing or a strategy write-off (admin or operator)
+    /// 
+    /// Still-locked profit absorbs the loss first. A loss that wipes out
+    /// the remaining assets leaves the vault insolvent: deposits revert and
+    /// exits pay zero until `reset_after_total_loss`.
+    pub fn report_loss(&mut self, amount: U512) {
+        self.access_control.only_admin_or_operator();
+        
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        
+        // Fees up to now are charged on the pre-loss assets
+        self.accrue_management_fees();
+        
+        let current_time = self.env().get_block_time();
+        let locked = self.get_locked_profit();
+        let absorbed = amount.min(locked);
+        self.locked_profit.set(locked - absorbed);
+        self.locked_profit_time.set(current_time);
+        
+        let current_assets = self.total_assets.get_or_default();
+        self.total_assets.set(current_assets.saturating_sub(amount));
+        
+        // Idle liquidity can't exceed what is left
+        let remaining = self.total_assets();
+        let pool = self.instant_withdrawal_pool.get_or_default();
+        let kept_pool = pool.min(remaining);
+        self.set_instant_pool(kept_pool);
+        
+        // The rest of the loss comes out of the other buckets; a pool cut
+        // deeper than the loss leaves the difference with the strategies
+        let lost = amount.min(current_assets);
+        let pool_cut = pool - kept_pool;
+        if lost > pool_cut {
+            let uncovered = self.release_assets(lost - pool_cut);
+            self.set_instant_pool(kept_pool.saturating_sub(uncovered));
+        } else {
+            self.deployed_assets.set(self.deployed_assets.get_or_default() + (pool_cut - lost));
+        }
+        
+        self.env().emit_event(LossReported {
+            amount,
+            absorbed_by_locked_profit: absorbed,
+            total_assets: remaining,
+            insolvent: self.is_insolvent(),
+            timestamp: current_time,
+        });
+    }
+
+    /// Whether shares are outstanding with no assets behind them
+    pub fn is_insolvent(&self) -> bool {
+        !self.total_shares.get_or_default().is_zero() && self.total_assets().is_zero()
+    }
+
+    /// Number of total-loss resets so far
+    pub fn get_loss_epoch(&self) -> u32 {
+        self.loss_epoch.get_or_default()
+    }
+
+    /// Profit that has not yet unlocked into the share price
+    pub fn get_locked_profit(&self) -> U512 {
+        let locked = self.locked_profit.get_or_default();
+        let duration = self.profit_unlock_duration.get_or_default();
+        if locked.is_zero() || duration == 0 {
+            return U512::zero();
+        }
+        
+        let elapsed = self.env().get_block_time().saturating_sub(self.locked_profit_time.get_or_default());
+        if elapsed >= duration {
+            return U512::zero();
+        }
+        
+        locked * U512::from(duration - elapsed) / U512::from(duration)
+    }
+
+    /// Maximum deposit allowed for a user today (daily limit left in the current UTC day)
+    pub fn max_deposit(&self, user: Address) -> U512 {
+        let (_, max_daily, _) = self.deposit_limits();
+        let today = self.env().get_block_time() / DEPOSIT_DAY_SECONDS;
+        
+        match self.user_deposit(&user) {
+            // A clock behind the stored day still counts against that day
+            Some(deposit) if deposit.deposit_day >= today => {
+                max_daily.checked_sub(deposit.daily_deposited).unwrap_or(U512::zero())
+            },
+            _ => max_daily,
+        }
+    }
+
+    /// Deposit limits in force now, in CSPR: (per transaction, per day, mode)
+    /// 
+    /// In USD mode the USD limits are converted at the oracle price. A
+    /// price older than `max_price_staleness` (or zero) falls back to the
+    /// CSPR limits.
+    pub fn deposit_limits(&self) -> (U512, U512, u8) {
+        let cspr_limits = (
+            self.max_deposit.get_or_default(),
+            self.max_deposit_per_day.get_or_default(),
+        );
+        
+        if !self.usd_limits_enabled.get_or_default() {
+            return (cspr_limits.0, cspr_limits.1, LIMIT_MODE_CSPR);
+        }
+        
+        let oracle = match self.price_oracle.get() {
+            Some(oracle) => oracle,
+            None => return (cspr_limits.0, cspr_limits.1, LIMIT_MODE_CSPR_FALLBACK),
+        };
+        
+        let (price, decimals, updated_at) = PriceOracleContractRef::new(self.env(), oracle).get_cspr_usd_price();
+        let stale = self.env().get_block_time() > updated_at + self.max_price_staleness.get_or_default();
+        if stale || price.is_zero() {
+            return (cspr_limits.0, cspr_limits.1, LIMIT_MODE_CSPR_FALLBACK);
+        }
+        
+        (
+            usd_to_motes(self.max_deposit_usd.get_or_default(), price, decimals),
+            usd_to_motes(self.max_deposit_per_day_usd.get_or_default(), price, decimals),
+            LIMIT_MODE_USD,
+        )
+    }
+
+    /// Maximum withdrawal allowed for a user
+    pub fn max_withdraw(&self, user: Address) -> U512 {
+        let shares = self.shares_of(&user);
+        self.convert_to_assets(shares)
+    }
+
+    /// Assets `shares` are worth on exit now, before performance and instant fees
+    /// 
+    /// Like `convert_to_assets`, but after the management fee an exit
+    /// would accrue.
+    pub fn preview_redeem(&self, shares: U512) -> U512 {
+        self.exit_assets(shares)
+    }
+
+    /// How much of the value of `shares` is reachable when, and how
+    /// 
+    /// The instant pool pays now, then the router's idle funds and Instant
+    /// tier strategies through `withdraw`. Fast and Slow tier strategies are
+    /// counted after the withdrawal timelock, and whatever is left only
+    /// after a LiquidStaking unbonding (never sooner than the timelock).
+    /// It is a snapshot: other exits draw on the same liquidity.
+    pub fn estimate_liquidity_schedule(&self, shares: U512) -> LiquiditySchedule {
+        let assets = self.exit_assets(shares);
+        let now = self.env().get_block_time();
+        let timelock_ready = now + self.withdrawal_timelock.get_or_default();
+        
+        let (router_now, router_later) = match self.strategy_router_address.get() {
+            Some(router) => {
+                let (instant, fast, slow) = StrategyRouterContractRef::new(self.env(), router).get_liquidity_by_tier();
+                (instant, fast + slow)
+            }
+            None => (U512::zero(), U512::zero()),
+        };
+        let unbonding_ready = match self.get_liquid_staking() {
+            Some(staking) if staking.is_contract() => {
+                LiquidStakingContractRef::new(self.env(), staking).get_unbonding_info().earliest_completion
+            }
+            _ => now + FALLBACK_UNBONDING_SECONDS,
+        };
+        
+        let sources = [
+            (self.instant_withdrawal_pool.get_or_default(), now, LIQUIDITY_PATH_INSTANT_POOL),
+            (router_now, now, LIQUIDITY_PATH_STRATEGIES),
+            (router_later, timelock_ready, LIQUIDITY_PATH_TIMELOCK),
+            (assets, unbonding_ready.max(timelock_ready), LIQUIDITY_PATH_UNBONDING),
+        ];
+        
+        let mut remaining = assets;
+        let mut tranches = Vec::new();
+        for (available, available_at, path) in sources {
+            let amount = available.min(remaining);
+            if !amount.is_zero() {
+                tranches.push(LiquidityTranche { amount, available_at, path });
+                remaining -= amount;
+            }
+        }
+        
+        LiquiditySchedule { shares, assets, tranches }
+    }
+
+    /// Payout `withdraw(shares)` by `user` would make now, after fees
+    /// 
+    /// Uses the same valuation as `withdraw`, including the management fee
+    /// the call would accrue. Does not check the holding period or balance.
+    pub fn preview_withdraw(&self, user: Address, shares: U512) -> U512 {
+        let value = self.withdrawal_value(shares);
+        let value = value - self.exit_size_fee(&user, shares, value);
+        value - self.calculate_performance_fee(&user, value)
+    }
+
+    /// Payout `instant_withdraw(shares)` by `user` would make now, after fees
+    /// 
+    /// Zero if the instant pool can't cover it.
+    pub fn preview_instant_withdraw(&self, user: Address, shares: U512) -> U512 {
+        let value = self.exit_assets(shares);
+        let value = value - self.exit_size_fee(&user, shares, value);
+        if value > self.instant_withdrawal_pool.get_or_default() {
+            return U512::zero();
+        }
+        
+        let instant_fee = bps_of(value, self.instant_fee_bps_for(&user));
+        value - instant_fee - self.calculate_performance_fee(&user, value)
+    }
+
+    /// Payout of a withdrawal request for `shares` made now, if the share
+    /// price holds until completion (the most it can pay)
+    pub fn preview_request_withdrawal(&self, user: Address, shares: U512) -> U512 {
+        let value = self.exit_assets(shares);
+        let value = value - self.exit_size_fee(&user, shares, value);
+        value - self.calculate_performance_fee(&user, value)
+    }
+
+    /// Payout `complete_withdrawal(request_id)` would make now
+    /// 
+    /// Zero for unknown, completed or expired requests. Does not check the
+    /// unlock time.
+    pub fn preview_complete_withdrawal(&self, request_id: u64) -> U512 {
+        let request = match self.withdrawal_request(request_id) {
+            Some(request) => request,
+            None => return U512::zero(),
+        };
+        if !request.status.is_pending() || self.request_written_off(&request) || self.is_insolvent() {
+            return U512::zero();
+        }
+        
+        let request_assets = request.assets_value.min(self.withdrawal_value(request.shares).saturating_sub(request.exit_fee));
+        if self.is_fee_exempt(request.user) {
+            return request_assets;
+        }
+        request_assets - performance_fee_on(request.cost_basis, request_assets, request.fee_bps)
+    }
+
+    /// Assets paid for `shares` on withdrawal
+    ///
+    /// With harvest-on-withdraw enabled and the withdrawal at or above the
+    /// threshold, pending yield is added to total assets for this valuation
+    /// only; it is booked for everyone when the next compound lands.
+    fn withdrawal_value(&self, shares: U512) -> U512 {
+        let assets = self.exit_assets(shares);
+
+        if !self.harvest_before_withdraw.get_or_default()
+            || assets < self.harvest_withdraw_threshold.get_or_default()
+        {
+            return assets;
+        }
+
+        let total_shares = self.total_shares.get_or_default() + self.management_fee_due().1;
+        if total_shares.is_zero() {
+            return assets;
+        }
+
+        let fresh_assets = self.total_assets() + self.get_pending_yield_estimate();
+        shares * fresh_assets / total_shares
+    }
+
+    /// `convert_to_assets` as it will be once pending management fees accrue
+    /// 
+    /// Every exit accrues first, so this is the price an exit gets. Equal to
+    /// `convert_to_assets` right after an accrual.
+    fn exit_assets(&self, shares: U512) -> U512 {
+        let total_shares = self.total_shares.get_or_default() + self.management_fee_due().1;
+        if total_shares.is_zero() {
+            return U512::zero();
+        }
+        
+        shares.checked_mul(self.total_assets())
             .unwrap()
-            .checked_div(total_assets)
+            .checked_div(total_shares)
             .unwrap()
     }
 
-    /// Convert shares (cvCSPR) to assets (CSPR) using ERC-4626 formula
+    // FEE CALCULATION HELPERS
+
+    /// Performance fee `user` would pay on withdrawing `withdrawal_amount` now
+    /// 
+    /// Performance fee is charged on PROFITS only, not principal, measured
+    /// against the user's cost basis. A pure query: exits book the fee they
+    /// actually charge with `book_performance_fee`.
+    fn calculate_performance_fee(&self, user: &Address, withdrawal_amount: U512) -> U512 {
+        if self.fee_exempt.get(user).unwrap_or(false) {
+            return U512::zero();
+        }
+        let cost_basis = self.user_deposit(user).map(|deposit| deposit.cost_basis);
+        performance_fee_on(cost_basis, withdrawal_amount, self.performance_fee_bps.get_or_default())
+    }
+
+    /// Size-tiered exit fee `user` pays on exiting `shares` worth `value`
+    /// 
+    /// Zero for fee-exempt accounts and for an exit of every share, which
+    /// leaves no holder for the fee to accrue to.
+    fn exit_size_fee(&self, user: &Address, shares: U512, value: U512) -> U512 {
+        if self.fee_exempt.get(user).unwrap_or(false) || shares >= self.total_shares.get_or_default() {
+            return U512::zero();
+        }
+        tiered_exit_fee(value, self.total_assets(), &self.exit_fee_tiers.get_or_default())
+    }
+
+    /// Work out `user`'s exit size fee on `value` and announce it
+    /// 
+    /// Returns the fee, which the exit leaves in total assets rather than
+    /// paying out or booking as revenue.
+    fn charge_exit_size_fee(&self, user: Address, shares: U512, value: U512, request_id: Option<u64>) -> U512 {
+        let fee = self.exit_size_fee(&user, shares, value);
+        self.emit_exit_size_fee(user, shares, value, fee, request_id);
+        fee
+    }
+
+    fn emit_exit_size_fee(&self, user: Address, shares: U512, exit_value: U512, fee: U512, request_id: Option<u64>) {
+        if fee.is_zero() {
+            return;
+        }
+        self.env().emit_event(ExitSizeFeeCharged {
+            user,
+            shares,
+            exit_value,
+            total_assets: self.total_assets(),
+            fee,
+            request_id,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Instant withdrawal fee `user` pays (bps), zero for fee-exempt accounts
+    fn instant_fee_bps_for(&self, user: &Address) -> u32 {
+        if self.fee_exempt.get(user).unwrap_or(false) {
+            return 0;
+        }
+        self.instant_withdrawal_fee_bps.get_or_default()
+    }
+
+    /// Collect management fees (time-based, called by keeper)
+    /// 
+    /// Management fee accrues continuously at annual rate (default 2%)
+    /// Collected by minting new shares to treasury. Callable by admin,
+    /// operator or keeper.
+    pub fn collect_management_fees(&mut self) {
+        self.access_control.only_maintainer();
+        
+        let current_time = self.env().get_block_time();
+        let last_collection = self.last_management_fee_collection.get_or_default();
+        
+        // Require at least 1 hour between collections
+        if current_time < last_collection + 3600 {
+            self.env().revert(VaultError::RateLimitExceeded);
+        }
+        
+        self.accrue_management_fees();
+    }
+
+    /// KeeperHub entry point: management fee collection
+    /// 
+    /// Returns `false` instead of reverting when the caller lacks the
+    /// keeper role, the collection is not yet due or the entrypoint is
+    /// not handled by the vault.
+    pub fn run_keeper_job(&mut self, entrypoint: u8) -> bool {
+        if !self.access_control.has_role(Role::Keeper.to_u8(), self.env().caller()) {
+            return false;
+        }
+        
+        if entrypoint != JOB_COLLECT_MANAGEMENT_FEES {
+            return false;
+        }
+        
+        let current_time = self.env().get_block_time();
+        let last_collection = self.last_management_fee_collection.get_or_default();
+        if current_time < last_collection + 3600 {
+            return false;
+        }
+        
+        self.accrue_management_fees();
+        true
+    }
+
+    /// Mint accrued management fee shares to the treasury
+    /// 
+    /// Runs at the start of every entry point that moves assets or shares
+    /// (deposit, all withdrawal paths, profit reports), so a holder pays for
+    /// exactly the time they were in the vault whether or not a keeper
+    /// collection ran. Never reverts: within an hour of the previous
+    /// collection it is a no-op and the time carries over to the next one.
+    fn accrue_management_fees(&mut self) {
+        let current_time = self.env().get_block_time();
+        let last_collection = self.last_management_fee_collection.get_or_default();
+        
+        if current_time < last_collection + 3600 {
+            return;
+        }
+        
+        let total_shares = self.total_shares.get_or_default();
+        
+        // Time with nobody in the vault is not billed to later depositors
+        if total_shares.is_zero() {
+            self.last_management_fee_collection.set(current_time);
+            return;
+        }
+        
+        let (fee_assets, fee_shares) = self.management_fee_due();
+        if fee_shares.is_zero() {
+            return;
+        }
+        
+        self.total_shares.set(total_shares.checked_add(fee_shares).unwrap());
+        self.last_management_fee_collection.set(current_time);
+        
+        let treasury = self.treasury.get().unwrap();
+        let treasury_shares = self.shares_of(&treasury);
+        self.set_user_shares(&treasury, treasury_shares.checked_add(fee_shares).unwrap());
+        let fee_origin = self.treasury_fee_shares.get_or_default();
+        self.treasury_fee_shares.set(fee_origin + fee_shares);
+        
+        let minted = self.management_fee_shares_minted.get_or_default();
+        self.management_fee_shares_minted.set(minted + fee_shares);
+        let slot = self.current_fee_period_slot();
+        let period_minted = self.fee_period_management_shares.get(&slot).unwrap_or_default();
+        self.fee_period_management_shares.set(&slot, period_minted + fee_shares);
+        
+        self.env().emit_event(ManagementFeesCollected {
+            shares: fee_shares,
+            treasury,
+            amount: fee_assets,
+            fee_recipient: treasury,
+            timestamp: current_time,
+        });
+    }
+
+    /// Management fee the next accrual would take: (assets, shares to mint)
+    /// 
+    /// Zero within an hour of the previous collection or when nothing is
+    /// due. Shared by `accrue_management_fees` and the withdrawal previews.
+    fn management_fee_due(&self) -> (U512, U512) {
+        let current_time = self.env().get_block_time();
+        let last_collection = self.last_management_fee_collection.get_or_default();
+        let total_shares = self.total_shares.get_or_default();
+        
+        if current_time < last_collection + 3600 || total_shares.is_zero() {
+            return (U512::zero(), U512::zero());
+        }
+        
+        let time_elapsed = current_time.saturating_sub(last_collection);
+        let total_assets = self.total_assets();
+        let fee_bps = self.management_fee_bps.get_or_default();
+        
+        // Fee is a share of assets, not of shares
+        let fee_assets = accrue_apr(total_assets, fee_bps, time_elapsed).unwrap_or_revert(&self.env());
+        
+        if fee_assets.is_zero() || fee_assets >= total_assets {
+            return (U512::zero(), U512::zero());
+        }
+        
+        // Mint so the treasury's claim is worth exactly fee_assets at the
+        // post-mint price: fee_shares / (total_shares + fee_shares) = fee_assets / total_assets
+        let fee_shares = fee_assets
+            .checked_mul(total_shares)
+            .unwrap()
+            .checked_div(total_assets - fee_assets)
+            .unwrap();
+        
+        (fee_assets, fee_shares)
+    }
+
+    /// Book a performance fee into its bucket and the current period
+    fn book_performance_fee(&mut self, fee: U512) {
+        if fee.is_zero() {
+            return;
+        }
+        
+        // The insurance slice leaves with its CSPR; the treasury keeps the rest
+        let slice = self.credit_insurance_slice(fee);
+        let current_fees = self.fees_collected.get_or_default();
+        self.fees_collected.set(current_fees.checked_add(fee - slice).unwrap());
+        
+        let collected = self.performance_fees_collected.get_or_default();
+        self.performance_fees_collected.set(collected + fee);
+        
+        let slot = self.current_fee_period_slot();
+        let period_fees = self.fee_period_performance.get(&slot).unwrap_or_default();
+        self.fee_period_performance.set(&slot, period_fees + fee);
+    }
+
+    /// Credit `insurance_fee_bps` of a performance fee to LiquidStaking's insurance fund
+    /// 
+    /// The slice's CSPR value is sent from the vault's purse with the
+    /// credit. Skipped, leaving the whole fee to the treasury, while the
+    /// purse can't pay it. Returns the lstCSPR slice credited.
+    fn credit_insurance_slice(&mut self, fee: U512) -> U512 {
+        let slice = bps_of(fee, self.insurance_fee_bps.get_or_default());
+        if slice.is_zero() {
+            return U512::zero();
+        }
+        let staking = match self.get_liquid_staking() {
+            Some(staking) => staking,
+            None => return U512::zero(),
+        };
+        
+        let value = self.lst_cspr_value(slice);
+        if value.is_zero() || value > self.env().self_balance() {
+            return U512::zero();
+        }
+        
+        LiquidStakingContractRef::new(self.env(), staking)
+            .with_tokens(value)
+            .credit_insurance_fees();
+        let credited = self.insurance_fees_credited.get_or_default();
+        self.insurance_fees_credited.set(credited + slice);
+        slice
+    }
+
+    /// Book an instant withdrawal fee into its bucket and the current period
+    fn book_instant_fee(&mut self, fee: U512) {
+        if fee.is_zero() {
+            return;
+        }
+        
+        let current_fees = self.fees_collected.get_or_default();
+        self.fees_collected.set(current_fees.checked_add(fee).unwrap());
+        
+        let collected = self.instant_fees_collected.get_or_default();
+        self.instant_fees_collected.set(collected + fee);
+        
+        let slot = self.current_fee_period_slot();
+        let period_fees = self.fee_period_instant.get(&slot).unwrap_or_default();
+        self.fee_period_instant.set(&slot, period_fees + fee);
+    }
+
+    /// Ring buffer slot for the current fee period, cleared when a new period starts
+    fn current_fee_period_slot(&mut self) -> u64 {
+        let period = self.get_current_fee_period();
+        let slot = period % FEE_PERIODS_RETAINED;
+        
+        if self.fee_period_ids.get(&slot) != Some(period) {
+            self.fee_period_ids.set(&slot, period);
+            self.fee_period_performance.set(&slot, U512::zero());
+            self.fee_period_instant.set(&slot, U512::zero());
+            self.fee_period_management_shares.set(&slot, U512::zero());
+            self.fee_period_community.set(&slot, U512::zero());
+        }
+        
+        slot
+    }
+
+    /// Calculate optimal amount to deploy to strategies vs keep in pool
+    /// 
+    /// `total_assets` and `current_pool` are passed in by `deposit`, which
+    /// has already read them.
+    fn calculate_strategy_deployment(&self, deposit_amount: U512, total_assets: U512, current_pool: U512) -> U512 {
+        // Get target instant pool percentage (default 5%)
+        let target_bps = self.instant_pool_target_bps.get_or_default();
+        
+        let target_pool_size = bps_of(total_assets, target_bps);
+        
+        if current_pool >= target_pool_size {
+            // Pool is at target, deploy entire amount
+            return deposit_amount;
+        }
+        
+        // Pool needs replenishment
+        let pool_deficit = target_pool_size.checked_sub(current_pool).unwrap();
+        
+        if deposit_amount <= pool_deficit {
+            // Entire deposit goes to pool
+            return U512::zero();
+        }
+        
+        // Split: fill pool deficit, deploy remainder
+        deposit_amount.checked_sub(pool_deficit).unwrap()
+    }
+
+    /// Shared body of `instant_withdraw` and `instant_withdraw_if_liquid`
+    /// 
+    /// `soft` turns a pool shortfall into a recorded rejection returning an
+    /// empty receipt.
+    fn instant_exit(&mut self, shares: U512, soft: bool) -> WithdrawalReceipt {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        
+        // Bill the time since the last collection before pricing the exit
+        self.accrue_management_fees();
+        
+        let user_shares = self.shares_of(&caller);
+        if shares > user_shares || shares.is_zero() {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::InsufficientBalance);
+        }
+        if !self.holding_period_elapsed(&caller) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::HoldingPeriodActive);
+        }
+        if self.leaves_dust(user_shares, shares) {
+            self.reentrancy_guard.exit();
+            self.env().revert(VaultError::DustRemainder);
+        }
+        
+        let insolvent = self.is_insolvent();
+        let gross_value = self.convert_to_assets(shares);
+        let exit_size_fee = self.exit_size_fee(&caller, shares, gross_value);
+        let assets_value = gross_value - exit_size_fee;
+        
+        let instant_pool = self.instant_withdrawal_pool.get_or_default();
+        if assets_value > instant_pool {
+            self.reentrancy_guard.exit();
+            if !soft {
+                self.env().revert(VaultError::InsufficientLiquidity);
+            }
+            self.pool_metrics_rejections.set(self.pool_metrics_rejections.get_or_default() + 1);
+            self.env().emit_event(InstantWithdrawalRejected {
+                user: caller,
+                shares,
+                assets_requested: assets_value,
+                pool_balance: instant_pool,
+                timestamp: self.env().get_block_time(),
+            });
+            return WithdrawalReceipt::default();
+        }
+        
+        self.emit_exit_size_fee(caller, shares, gross_value, exit_size_fee, None);
+        let instant_fee_bps = self.instant_fee_bps_for(&caller);
+        let instant_fee = bps_of(assets_value, instant_fee_bps);
+        
+        let performance_fee = self.calculate_performance_fee(&caller, assets_value);
+        self.book_performance_fee(performance_fee);
+        
+        // Total fees
+        let total_fees = instant_fee.checked_add(performance_fee).unwrap();
+        let assets_after_fee = assets_value.checked_sub(total_fees).unwrap();
+        
+        // Part of the instant fee stays in the vault for whoever remains
+        let total = self.total_shares.get_or_default();
+        let fee_to_holders = if total > shares {
+            bps_of(instant_fee, self.instant_fee_to_holders_bps.get_or_default())
+        } else {
+            U512::zero()
+        };
+        let fee_to_protocol = instant_fee - fee_to_holders;
+        let assets_out = assets_value - fee_to_holders;
+        
+        let new_pool = instant_pool.checked_sub(assets_out).unwrap();
+        self.set_instant_pool(new_pool);
+        self.pool_metrics_served.set(self.pool_metrics_served.get_or_default() + 1);
+        self.pool_metrics_volume.set(self.pool_metrics_volume.get_or_default() + assets_out);
+        
+        self.book_instant_fee(fee_to_protocol);
+        
+        // Burn user shares
+        self.record_user_withdrawal(&caller, assets_after_fee);
+        let new_user_shares = user_shares.checked_sub(shares).unwrap();
+        self.track_depositor(&caller, user_shares, new_user_shares);
+        if new_user_shares.is_zero() {
+            self.set_user_shares(&caller, U512::zero());
+            self.reset_user_deposit(&caller);
+        } else {
+            self.set_user_shares(&caller, new_user_shares);
+        }
+        
+        self.total_shares.set(total.checked_sub(shares).unwrap());
+        
+        let current_assets = self.total_assets.get_or_default();
+        self.total_assets.set(current_assets.saturating_sub(assets_out));
+        
+        let operation_id = self.record_operation(OP_INSTANT_WITHDRAW, caller, assets_after_fee, shares);
+        let cspr_amount = self.lst_cspr_value(assets_after_fee);
+        
+        self.env().emit_event(InstantWithdrawal {
+            operation_id,
+            user: caller,
+            assets: assets_after_fee,
+            shares,
+            shares_burned: shares,
+            fee: total_fees,
+            cspr_amount,
+            fee_amount: total_fees,
+            fee_to_holders,
+            fee_to_protocol,
+            fee_exempt: self.is_fee_exempt(caller),
+            timestamp: self.env().get_block_time(),
+        });
+        if insolvent {
+            self.emit_insolvent_exit(caller, shares, None);
+        }
+        
+        self.reentrancy_guard.exit();
+        WithdrawalReceipt {
+            shares_burned: shares,
+            gross_assets: gross_value,
+            performance_fee,
+            instant_fee,
+            exit_size_fee,
+            net_assets: assets_after_fee,
+            net_cspr: cspr_amount,
+        }
+    }
+
+    /// Write the instant pool balance and sample it for `get_pool_metrics`
+    fn set_instant_pool(&mut self, balance: U512) {
+        self.instant_withdrawal_pool.set(balance);
+        
+        let samples = self.pool_metrics_samples.get_or_default();
+        if samples == 0 || balance < self.pool_metrics_min.get_or_default() {
+            self.pool_metrics_min.set(balance);
+        }
+        if balance > self.pool_metrics_max.get_or_default() {
+            self.pool_metrics_max.set(balance);
+        }
+        self.pool_metrics_sum.set(self.pool_metrics_sum.get_or_default() + balance);
+        self.pool_metrics_samples.set(samples + 1);
+    }
+
+    /// Take `amount` leaving the vault off the non-pool buckets
+    /// 
+    /// Strategies pay first, then idle lstCSPR, then assets in transit.
+    /// Returns what the buckets couldn't cover.
+    fn release_assets(&mut self, amount: U512) -> U512 {
+        let mut remaining = amount;
+        for bucket in [&mut self.deployed_assets, &mut self.idle_lst_assets, &mut self.in_transit_assets] {
+            let balance = bucket.get_or_default();
+            let taken = balance.min(remaining);
+            bucket.set(balance - taken);
+            remaining -= taken;
+        }
+        remaining
+    }
+
+    /// Move the value of a new withdrawal request out of the strategies
+    /// 
+    /// What deployed and idle assets can't cover stays in the instant pool,
+    /// which settlement draws on first anyway.
+    /// Returns the amount moved, which the request records.
+    fn move_in_transit(&mut self, assets: U512) -> U512 {
+        let deployed = self.deployed_assets.get_or_default();
+        let from_deployed = deployed.min(assets);
+        self.deployed_assets.set(deployed - from_deployed);
+        
+        let idle = self.idle_lst_assets.get_or_default();
+        let from_idle = idle.min(assets - from_deployed);
+        self.idle_lst_assets.set(idle - from_idle);
+        
+        let moved = from_deployed + from_idle;
+        self.in_transit_assets.set(self.in_transit_assets.get_or_default() + moved);
+        moved
+    }
+
+    /// Return a closing request's in-transit assets to the strategies
+    /// 
+    /// Losses may have shrunk the bucket below what the request moved.
+    fn return_from_transit(&mut self, request: &mut WithdrawalRequest) {
+        let in_transit = self.in_transit_assets.get_or_default();
+        let moved = request.in_transit.min(in_transit);
+        request.in_transit = U512::zero();
+        self.in_transit_assets.set(in_transit - moved);
+        self.deployed_assets.set(self.deployed_assets.get_or_default() + moved);
+    }
+
+    /// Zero the pool metric counters and start a new window at the current block time
+    fn clear_pool_metrics(&mut self) {
+        self.pool_metrics_served.set(0);
+        self.pool_metrics_volume.set(U512::zero());
+        self.pool_metrics_rejections.set(0);
+        self.pool_metrics_samples.set(0);
+        self.pool_metrics_sum.set(U512::zero());
+        self.pool_metrics_min.set(U512::zero());
+        self.pool_metrics_max.set(U512::zero());
+        self.pool_metrics_since.set(self.env().get_block_time());
+    }
+
+    /// Revert unless a deployed share token reports `SHARE_DECIMALS`
+    /// 
+    /// Account addresses (placeholders until cvCSPR is deployed) are not
+    /// checked.
+    fn check_share_decimals(&self, token: Address) {
+        if token.is_contract() && CvCsprContractRef::new(self.env(), token).decimals() != SHARE_DECIMALS {
+            self.env().revert(VaultError::ShareDecimalsMismatch);
+        }
+    }
+
+    /// Wired cvCSPR token, if it is a deployed contract
+    fn share_token(&self) -> Option<CvCsprContractRef> {
+        self.get_cv_cspr_token()
+            .filter(|token| token.is_contract())
+            .map(|token| CvCsprContractRef::new(self.env(), token))
+    }
+
+    /// Emit `ConfigChanged` for one of this vault's parameters
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "VaultManager".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Validate and store new fees, emitting `FeesUpdated`
+    fn update_fees(&mut self, performance_bps: u32, management_bps: u32, instant_bps: u32) {
+        if performance_bps > MAX_PERFORMANCE_FEE_BPS
+            || management_bps > MAX_MANAGEMENT_FEE_BPS
+            || instant_bps > MAX_INSTANT_WITHDRAWAL_FEE_BPS
+        {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        
+        // The community pool's slice of yield counts against the same cap
+        let community_bps = self.yield_aggregator_address.get()
+            .filter(|aggregator| aggregator.is_contract())
+            .map_or(0, |aggregator| YieldAggregatorContractRef::new(self.env(), aggregator).get_community_pool_bps());
+        if performance_bps + community_bps > MAX_COMBINED_YIELD_TAKE_BPS {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        
+        // Settle management fees accrued at the old rate
+        self.accrue_management_fees();
+        
+        let (old_performance, old_management, old_instant) = self.get_fees();
+        self.performance_fee_bps.set(performance_bps);
+        self.management_fee_bps.set(management_bps);
+        self.instant_withdrawal_fee_bps.set(instant_bps);
+        
+        self.env().emit_event(FeesUpdated {
+            old_performance_fee_bps: old_performance,
+            old_management_fee_bps: old_management,
+            old_instant_withdrawal_fee_bps: old_instant,
+            performance_fee_bps: performance_bps,
+            management_fee_bps: management_bps,
+            instant_withdrawal_fee_bps: instant_bps,
+            updated_by: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Shares held by `user`; balances from before a total-loss reset read as zero
+    fn shares_of(&self, user: &Address) -> U512 {
+        if self.user_share_epoch.get(user).unwrap_or_default() < self.loss_epoch.get_or_default() {
+            return U512::zero();
+        }
+        self.user_shares.get(user).unwrap_or_default()
+    }
+
+    /// Shares held by the distinct accounts of the last `INVARIANT_SAMPLE_SIZE` operations
+    fn sampled_user_shares(&self) -> U512 {
+        let last_id = self.operation_id.get_or_default();
+        let log_size = self.operation_log_size.get_or_default().max(1);
+        let first_id = last_id.saturating_sub(INVARIANT_SAMPLE_SIZE.min(log_size)) + 1;
+        
+        let mut sampled: Vec<Address> = Vec::new();
+        let mut total = U512::zero();
+        for id in first_id..=last_id {
+            let user = match self.operation_users.get(&(id % log_size)) {
+                Some(user) => user,
+                None => continue,
+            };
+            if !sampled.contains(&user) {
+                total += self.shares_of(&user);
+                sampled.push(user);
+            }
+        }
+        total
+    }
+
+    /// Store `user`'s shares, dropping a position written off by a reset
+    fn set_user_shares(&mut self, user: &Address, shares: U512) {
+        let held = self.held_shares.get_or_default() - self.shares_of(user);
+        self.held_shares.set(held + shares);
+        
+        let epoch = self.loss_epoch.get_or_default();
+        if self.user_share_epoch.get(user).unwrap_or_default() < epoch {
+            self.user_share_epoch.set(user, epoch);
+            self.count_bookkeeping_writes(1);
+            self.reset_user_deposit(user);
+        }
+        self.user_shares.set(user, shares);
+        self.count_bookkeeping_writes(2);
+    }
+
+    /// Whether `request` was made before the last total-loss reset
+    fn request_written_off(&self, request: &WithdrawalRequest) -> bool {
+        request.loss_epoch < self.loss_epoch.get_or_default()
+    }
+
+    /// A stored withdrawal request, upgraded to the current version
+    fn withdrawal_request(&self, request_id: u64) -> Option<WithdrawalRequest> {
+        self.withdrawal_requests.get(&request_id).map(upgrade_request)
+    }
+
+    fn emit_insolvent_exit(&self, user: Address, shares: U512, request_id: Option<u64>) {
+        self.env().emit_event(InsolventExit {
+            user,
+            shares,
+            request_id,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Add or remove a fee exemption and announce it
+    fn store_fee_exemption(&mut self, account: Address, exempt: bool) {
+        let mut accounts = self.fee_exempt_accounts.get_or_default();
+        if exempt {
+            accounts.push(account);
+        } else {
+            accounts.retain(|exempt_account| *exempt_account != account);
+        }
+        self.fee_exempt_accounts.set(accounts);
+        self.fee_exempt.set(&account, exempt);
+        
+        self.env().emit_event(FeeExemptionChanged {
+            account,
+            exempt,
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Revert with `AccountFrozen` if a guardian froze `account`
+    fn when_not_frozen(&self, account: &Address) {
+        if self.frozen.get(account).unwrap_or(false) {
+            self.env().revert(VaultError::AccountFrozen);
+        }
+    }
+
+    /// Revert with `WithdrawalsFrozen` while the super-freeze is engaged
+    fn when_withdrawals_not_frozen(&self) {
+        if self.withdrawals_frozen.get_or_default() {
+            self.env().revert(VaultError::WithdrawalsFrozen);
+        }
+    }
+
+    fn only_guardian_or_admin(&self) {
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Guardian.to_u8(), caller)
+            && !self.access_control.has_role(Role::Admin.to_u8(), caller)
+        {
+            self.env().revert(VaultError::Unauthorized);
+        }
+    }
+
+    /// Keep `unique_depositors` in step with a user's share balance
+    fn track_depositor(&mut self, user: &Address, before: U512, after: U512) {
+        // Only a move to or from zero changes the count
+        if before.is_zero() == after.is_zero() {
+            return;
+        }
+        if self.treasury.get() == Some(*user) {
+            return;
+        }
+        
+        let count = self.unique_depositors.get_or_default();
+        if before.is_zero() && !after.is_zero() {
+            self.unique_depositors.set(count + 1);
+        } else if !before.is_zero() && after.is_zero() {
+            self.unique_depositors.set(count.saturating_sub(1));
+        }
+        self.count_bookkeeping_writes(1);
+    }
+
+    /// Whether the user's last deposit is older than the minimum holding period
+    /// 
+    /// Blocks same-block deposit-then-withdraw round trips. Completing an
+    /// existing withdrawal request is not affected.
+    fn holding_period_elapsed(&self, user: &Address) -> bool {
+        let min_holding = self.min_holding_seconds.get_or_default();
+        match self.user_deposit(user) {
+            Some(deposit) if min_holding > 0 => {
+                self.env().get_block_time() >= deposit.last_deposit_time + min_holding
+            },
+            _ => true,
+        }
+    }
+
+    /// Record a deposit against the user's tracking in one read-modify-write
+    /// 
+    /// Adds to the cost basis and totals and counts the deposit towards
+    /// today's bucket, which starts from zero on the first deposit of a new
+    /// UTC day. Reverts if the bucket would exceed `max_daily`. The first
+    /// deposit of a position also stamps `first_deposit_time`.
+    /// 
+    /// Returns what is left of today's allowance.
+    fn record_user_deposit(&mut self, user: &Address, amount: U512, shares: U512, max_daily: U512) -> U512 {
+        let mut deposit = self.count_daily_deposit(user, amount, max_daily);
+        if deposit.total_deposited.is_zero() {
+            deposit.first_deposit_time = self.env().get_block_time();
+        }
+        deposit.cost_basis = deposit.cost_basis.checked_add(amount).unwrap();
+        deposit.total_deposited = deposit.total_deposited.checked_add(amount).unwrap();
+        deposit.total_shares = deposit.total_shares.checked_add(shares).unwrap();
+        let remaining = max_daily.saturating_sub(deposit.daily_deposited);
+        self.user_deposits.set(user, deposit);
+        self.count_bookkeeping_writes(1);
+        remaining
+    }
+
+    /// Count `writes` towards `bookkeeping_writes` in test builds
+    /// 
+    /// Covers the slots the deposit path keeps per share and per user:
+    /// totals, share balances, the depositor count and `UserDeposit`.
+    /// A no-op in production, so the meter costs nothing there.
+    #[allow(unused_variables)]
+    fn count_bookkeeping_writes(&mut self, writes: u32) {
+        #[cfg(any(test, feature = "test-helpers"))]
+        self.bookkeeping_writes.set(self.bookkeeping_writes.get_or_default() + writes);
+    }
+
+    /// The user's tracking with `amount` counted towards today's bucket
+    /// 
+    /// Also stamps `last_deposit_time`. Reverts if the bucket would exceed
+    /// `max_daily`; the caller stores the result.
+    fn count_daily_deposit(&self, user: &Address, amount: U512, max_daily: U512) -> UserDeposit {
+        let current_time = self.env().get_block_time();
+        let today = current_time / DEPOSIT_DAY_SECONDS;
+        
+        let mut deposit = self.user_deposit(user).unwrap_or_default();
+        // Only a later day starts a new bucket; a clock that steps back
+        // over midnight keeps counting against the day already used
+        if today > deposit.deposit_day {
+            deposit.deposit_day = today;
+            deposit.daily_deposited = U512::zero();
+        }
+        
+        let new_daily = deposit.daily_deposited.checked_add(amount).unwrap();
+        if new_daily > max_daily {
+            self.env().revert(VaultError::RateLimitExceeded);
+        }
+        deposit.daily_deposited = new_daily;
+        deposit.last_deposit_time = deposit.last_deposit_time.max(current_time);
+        deposit
+    }
+
+    /// Checks shared by the CSPR deposit entrypoints, with the reentrancy
+    /// guard entered
+    /// 
+    /// Returns the caller, the CSPR attached and the lstCSPR it stakes into
+    /// at the current exchange rate.
+    fn accept_cspr_deposit(&mut self) -> (Address, U512, U512) {
+        self.pausable.when_not_paused();
+        self.reentrancy_guard.enter();
+        
+        let amount = self.env().attached_value();
+        let caller = self.env().caller();
+        self.when_not_frozen(&caller);
+        
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        if self.migration_target.get().is_some() {
+            self.env().revert(VaultError::MigrationActive);
+        }
+        if self.is_insolvent() {
+            self.env().revert(VaultError::VaultInsolvent);
+        }
+        if self.deposits_halted.get_or_default() {
+            self.env().revert(VaultError::DepositsHalted);
+        }
+        
+        // Stake CSPR to get lstCSPR at the current exchange rate
+        (caller, amount, self.lst_cspr_for(amount))
+    }
+
+    /// Stake a StakingOnly CSPR deposit through LiquidStaking
+    /// 
+    /// The staking-only bucket pays out real lstCSPR, so the CSPR is staked
+    /// rather than kept in the purse. Returns the lstCSPR minted to the
+    /// vault.
+    fn stake_staking_only(&mut self, amount: U512) -> U512 {
+        let staking = match self.get_liquid_staking() {
+            Some(staking) if staking.is_contract() => staking,
+            _ => self.env().revert(VaultError::StakingUnavailable),
+        };
+        LiquidStakingContractRef::new(self.env(), staking).with_tokens(amount).stake()
+    }
+
+    /// Credit a StakingOnly deposit of `lst_cspr_received` worth `cspr_amount`
+    /// 
+    /// The deposit limits apply as for `mint_deposit`, but no shares are
+    /// minted and nothing reaches the router: the lstCSPR is held in the
+    /// user's staking-only balance. The cost basis is left alone since
+    /// these funds never pay the performance fee. Returns the lstCSPR
+    /// credited.
+    fn credit_staking_only(&mut self, caller: Address, cspr_amount: U512, lst_cspr_received: U512) -> U512 {
+        let (max_deposit, max_daily, _) = self.deposit_limits();
+        if cspr_amount > max_deposit {
+            self.env().revert(VaultError::RateLimitExceeded);
+        }
+        let deposit = self.count_daily_deposit(&caller, cspr_amount, max_daily);
+        self.user_deposits.set(&caller, deposit);
+        self.count_bookkeeping_writes(1);
+        
+        let balance = self.staking_only_balances.get(&caller).unwrap_or_default();
+        self.staking_only_balances.set(&caller, balance + lst_cspr_received);
+        self.staking_only_assets.set(self.staking_only_assets.get_or_default() + lst_cspr_received);
+        
+        let operation_id = self.record_operation(OP_STAKING_ONLY_DEPOSIT, caller, lst_cspr_received, U512::zero());
+        self.env().emit_event(StakingOnlyDeposit {
+            operation_id,
+            user: caller,
+            cspr_amount,
+            lst_cspr_amount: lst_cspr_received,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        lst_cspr_received
+    }
+
+    fn is_staking_only(&self, user: &Address) -> bool {
+        self.risk_preference.get(user) == Some(StrategyExposure::StakingOnly.to_u8())
+    }
+
+    /// Mint shares for `lst_cspr_received` of assets worth `cspr_amount`
+    /// 
+    /// Shared by `deposit` and `deposit_lst`: applies the deposit limits to
+    /// the CSPR value, prices the shares, records the cost basis, places
+    /// the assets and emits `Deposit`.
+    fn mint_deposit(&mut self, caller: Address, cspr_amount: U512, lst_cspr_received: U512) -> DepositReceipt {
+        let (max_deposit, max_daily, limit_mode) = self.deposit_limits();
+        if cspr_amount > max_deposit {
+            self.env().revert(VaultError::RateLimitExceeded);
+        }
+        
+        // Collect any pending management fees
+        self.accrue_management_fees();
+        
+        // Each Var is read once here and written once below
+        let stored_assets = self.total_assets.get_or_default();
+        let stored_shares = self.total_shares.get_or_default();
+        let net_assets = stored_assets.saturating_sub(self.get_locked_profit());
+        
+        // Step 2: Calculate shares to mint (ERC-4626)
+        let shares_to_mint = assets_to_shares(lst_cspr_received, stored_shares, net_assets);
+        
+        self.assert_min_shares(shares_to_mint);
+        
+        // Step 3: Update total assets and shares
+        self.total_assets.set(stored_assets + lst_cspr_received);
+        self.total_shares.set(stored_shares + shares_to_mint);
+        self.count_bookkeeping_writes(2);
+        
+        // Step 4: Update user shares
+        let user_current_shares = self.shares_of(&caller);
+        self.set_user_shares(&caller, user_current_shares + shares_to_mint);
+        self.track_depositor(&caller, user_current_shares, user_current_shares + shares_to_mint);
+        
+        // Step 5: Update user deposit tracking (fee basis and daily limit)
+        let remaining_daily_allowance = self.record_user_deposit(&caller, cspr_amount, shares_to_mint, max_daily);
+        
+        // Step 6: Mint cvCSPR shares to user
+        
+        // Step 7: Deploy to strategies (whatever the router can't place stays in the pool)
+        let current_pool = self.instant_withdrawal_pool.get_or_default();
+        let amount_to_deploy = self.calculate_strategy_deployment(
+            lst_cspr_received,
+            net_assets + lst_cspr_received,
+            current_pool,
+        );
+        let flow_id = self.next_operation_id();
+        let mut unallocated = U512::zero();
+        let mut deployed_to_strategies = U512::zero();
+        if amount_to_deploy > U512::zero() {
+            if let Some(router) = self.strategy_router_address.get() {
+                unallocated = StrategyRouterContractRef::new(self.env(), router)
+                    .allocate_with_id(flow_id, amount_to_deploy);
+                deployed_to_strategies = amount_to_deploy - unallocated;
+                let deployed = self.deployed_assets.get_or_default();
+                self.deployed_assets.set(deployed + deployed_to_strategies);
+            } else {
+                let idle = self.idle_lst_assets.get_or_default();
+                self.idle_lst_assets.set(idle + amount_to_deploy);
+            }
+        }
+        
+        // Step 8: Replenish instant withdrawal pool
+        let pool_amount = lst_cspr_received - amount_to_deploy + unallocated;
+        if pool_amount > U512::zero() {
+            self.set_instant_pool(current_pool + pool_amount);
+        }
+        
+        let operation_id = self.record_operation(OP_DEPOSIT, caller, lst_cspr_received, shares_to_mint);
+        
+        self.env().emit_event(Deposit {
+            operation_id,
+            user: caller,
+            cspr_amount,
+            lst_cspr_amount: lst_cspr_received,
+            shares_minted: shares_to_mint,
+            limit_mode,
+            limit_applied: max_deposit,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        let share_price_at_deposit = if stored_shares.is_zero() {
+            U512::from(YIELD_INDEX_SCALE)
+        } else {
+            net_assets * U512::from(YIELD_INDEX_SCALE) / stored_shares
+        };
+        DepositReceipt {
+            shares_minted: shares_to_mint,
+            share_price_at_deposit: safe_u512_to_u256(share_price_at_deposit).unwrap_or_revert(&self.env()),
+            assets_accepted: lst_cspr_received,
+            deployed_to_strategies,
+            kept_in_pool: pool_amount,
+            remaining_daily_allowance,
+        }
+    }
+
+    /// CSPR value of `amount` lstCSPR at the LiquidStaking exchange rate
+    /// 
+    /// 1:1 until a LiquidStaking contract is wired in.
+    fn lst_cspr_value(&self, amount: U512) -> U512 {
+        match self.get_liquid_staking() {
+            Some(staking) if staking.is_contract() => {
+                LiquidStakingContractRef::new(self.env(), staking).lst_cspr_to_cspr_precise(amount)
+            }
+            _ => amount,
+        }
+    }
+
+    /// lstCSPR that staking `cspr_amount` yields at the LiquidStaking rate
+    /// 
+    /// 1:1 until a LiquidStaking contract is wired in.
+    fn lst_cspr_for(&self, cspr_amount: U512) -> U512 {
+        match self.get_liquid_staking() {
+            Some(staking) if staking.is_contract() => {
+                LiquidStakingContractRef::new(self.env(), staking).cspr_to_lst_cspr_precise(cspr_amount)
+            }
+            _ => cspr_amount,
+        }
+    }
+
+    /// Share price (1e18 scale) and CSPR per 1e18 lstCSPR
+    fn accounting_prices(&self) -> (U512, U512) {
+        (
+            safe_u256_to_u512(self.get_share_price_precise()),
+            self.lst_cspr_value(U512::from(YIELD_INDEX_SCALE)),
+        )
+    }
+
+    /// Signed growth of share price and lstCSPR rate since the baseline (bps)
+    fn growth_since_baseline(&self) -> (i64, i64) {
+        let (share_price, rate) = self.accounting_prices();
+        (
+            growth_bps(share_price, self.divergence_baseline_share_price.get_or_default()),
+            growth_bps(rate, self.divergence_baseline_rate.get_or_default()),
+        )
+    }
+
+    /// Revert with `AmountTooLow` if `shares` is a dust position
+    /// 
+    /// Every path that mints shares to a depositor goes through here.
+    fn assert_min_shares(&self, shares: U512) {
+        if shares < self.min_shares.get_or_default() {
+            self.env().revert(VaultError::AmountTooLow);
+        }
+    }
+
+    /// Whether exiting `shares` of `balance` leaves a nonzero balance
+    /// below `min_shares`
+    fn leaves_dust(&self, balance: U512, shares: U512) -> bool {
+        let remaining = balance.saturating_sub(shares);
+        !remaining.is_zero() && remaining < self.min_shares.get_or_default()
+    }
+
+    /// Burn an exiting user's shares and take `assets` off total assets
+    fn burn_exit_shares(&mut self, user: &Address, user_shares: U512, shares: U512, assets: U512) {
+        let new_user_shares = user_shares.checked_sub(shares).unwrap();
+        self.track_depositor(user, user_shares, new_user_shares);
+        if new_user_shares.is_zero() {
+            self.set_user_shares(user, U512::zero());
+            self.reset_user_deposit(user);
+        } else {
+            self.set_user_shares(user, new_user_shares);
+        }
+        
+        let total = self.total_shares.get_or_default();
+        self.total_shares.set(total.checked_sub(shares).unwrap());
+        
+        let current_assets = self.total_assets.get_or_default();
+        self.total_assets.set(current_assets.saturating_sub(assets));
+    }
+
+    /// Add a new request to the open-request index and maturity buckets
+    fn track_pending_withdrawal(&mut self, request_id: u64, assets: U512, unlock_time: u64) {
+        let count = self.pending_withdrawal_count.get_or_default();
+        self.pending_withdrawal_ids.set(&count, request_id);
+        self.pending_withdrawal_positions.set(&request_id, count);
+        self.pending_withdrawal_count.set(count + 1);
+        
+        let total = self.pending_withdrawal_assets.get_or_default();
+        self.pending_withdrawal_assets.set(total + assets);
+        
+        let day = unlock_time / MATURITY_BUCKET_SECONDS;
+        let bucket = self.pending_assets_by_unlock_day.get(&day).unwrap_or_default();
+        self.pending_assets_by_unlock_day.set(&day, bucket + assets);
+        if count == 0 || day < self.pending_unlock_first_day.get_or_default() {
+            self.pending_unlock_first_day.set(day);
+        }
+        if count == 0 || day > self.pending_unlock_last_day.get_or_default() {
+            self.pending_unlock_last_day.set(day);
+        }
+    }
+
+    /// Drop a completed or expired request from the open-request index
+    /// 
+    /// Called once per request, when it closes; its stale position entry
+    /// is never read again.
+    fn untrack_pending_withdrawal(&mut self, request_id: u64, request: &WithdrawalRequest) {
+        let position = match self.pending_withdrawal_positions.get(&request_id) {
+            Some(position) => position,
+            None => return,
+        };
+        
+        let last = self.pending_withdrawal_count.get_or_default() - 1;
+        if position != last {
+            let moved = self.pending_withdrawal_ids.get(&last).unwrap();
+            self.pending_withdrawal_ids.set(&position, moved);
+            self.pending_withdrawal_positions.set(&moved, position);
+        }
+        self.pending_withdrawal_count.set(last);
+        
+        let assets = request.assets_value;
+        let total = self.pending_withdrawal_assets.get_or_default();
+        self.pending_withdrawal_assets.set(total.saturating_sub(assets));
+        
+        let day = request.unlock_time / MATURITY_BUCKET_SECONDS;
+        let bucket = self.pending_assets_by_unlock_day.get(&day).unwrap_or_default();
+        self.pending_assets_by_unlock_day.set(&day, bucket.saturating_sub(assets));
+    }
+
+    /// The user's deposit tracking, upgraded to the current version
+    fn user_deposit(&self, user: &Address) -> Option<UserDeposit> {
+        self.user_deposits.get(user).map(upgrade_user_deposit)
+    }
+
+    /// Count `net_assets` lstCSPR paid out to `user` towards their position
+    /// 
+    /// Called before the exit burns the shares, so a full exit's reset
+    /// clears it with the rest of the position. Ignored for accounts with
+    /// no deposits on record, such as the new owner of a transferred
+    /// request.
+    fn record_user_withdrawal(&mut self, user: &Address, net_assets: U512) {
+        let mut deposit = match self.user_deposit(user) {
+            Some(deposit) if !deposit.total_deposited.is_zero() => deposit,
+            _ => return,
+        };
+        deposit.total_withdrawn = deposit.total_withdrawn.checked_add(self.lst_cspr_value(net_assets)).unwrap();
+        self.user_deposits.set(user, deposit);
+    }
+
+    /// Clear a fully exited user's fee basis, keeping today's deposit usage
+    fn reset_user_deposit(&mut self, user: &Address) {
+        if let Some(deposit) = self.user_deposit(user) {
+            self.user_deposits.set(user, UserDeposit {
+                daily_deposited: deposit.daily_deposited,
+                deposit_day: deposit.deposit_day,
+                ..Default::default()
+            });
+            self.count_bookkeeping_writes(1);
+        }
+    }
+
+    /// ID `record_operation` will assign next
+    /// 
+    /// Used as the flow id passed to the router, so the router and strategy
+    /// events of a deposit or withdrawal carry its `operation_id`.
+    fn next_operation_id(&self) -> u64 {
+        self.operation_id.get_or_default() + 1
+    }
+
+    /// Assign the next operation ID and store the record in the ring buffer
+    fn record_operation(&mut self, kind: u8, user: Address, assets: U512, shares: U512) -> u64 {
+        let id = self.operation_id.get_or_default() + 1;
+        self.operation_id.set(id);
+        
+        let slot = id % self.operation_log_size.get_or_default().max(1);
+        self.operation_ids.set(&slot, id);
+        self.operation_kinds.set(&slot, kind);
+        self.operation_users.set(&slot, user);
+        self.operation_assets.set(&slot, assets);
+        self.operation_shares.set(&slot, shares);
+        self.operation_times.set(&slot, self.env().get_block_time());
+        
+        id
+    }
+
+    /// Burn `user`'s shares and hand the position to `target`
+    /// 
+    /// The backing assets come out of the pool first, then the strategies.
+    /// Whatever lstCSPR the vault holds is approved for the target to pull
+    /// and the rest is sent along as CSPR. Management fees must already be
+    /// accrued. Returns the shares minted on the target.
+    fn migrate_user(&mut self, user: Address, target: Address) -> U512 {
+        let shares = self.shares_of(&user);
+        let assets = self.exit_assets(shares);
+        // Holders without deposit data (the treasury) move at their current value
+        let cost_basis = self.user_deposit(&user).map(|deposit| deposit.cost_basis).unwrap_or(assets);
+        
+        self.set_user_shares(&user, U512::zero());
+        self.track_depositor(&user, shares, U512::zero());
+        self.reset_user_deposit(&user);
+        if self.treasury.get() == Some(user) {
+            self.treasury_fee_shares.set(U512::zero());
+        }
+        
+        let total = self.total_shares.get_or_default();
+        self.total_shares.set(total.checked_sub(shares).unwrap());
+        let current_assets = self.total_assets.get_or_default();
+        self.total_assets.set(current_assets.saturating_sub(assets));
+        
+        let pool = self.instant_withdrawal_pool.get_or_default();
+        let from_pool = assets.min(pool);
+        self.set_instant_pool(pool - from_pool);
+        
+        let deployed = self.deployed_assets.get_or_default();
+        self.release_assets(assets - from_pool);
+        let from_strategies = deployed - self.deployed_assets.get_or_default();
+        let flow_id = self.next_operation_id();
+        if let Some(router) = self.strategy_router_address.get() {
+            if !from_strategies.is_zero()
+                && StrategyRouterContractRef::new(self.env(), router).withdraw_with_id(flow_id, from_strategies)
+                    < from_strategies
+            {
+                self.env().revert(VaultError::InsufficientLiquidity);
+            }
+        }
+        
+        let operation_id = self.record_operation(OP_MIGRATE_OUT, user, assets, shares);
+        // Plain deposits are held as CSPR; only lstCSPR taken in through
+        // `deposit_lst` can move as the token
+        let asset = self.asset();
+        let as_lst = if asset.is_contract() {
+            let mut token = LstCsprContractRef::new(self.env(), asset);
+            let held = token
+                .balance_of(self.env().self_address())
+                .saturating_sub(self.staking_only_assets.get_or_default());
+            let as_lst = assets.min(held);
+            if !as_lst.is_zero() {
+                token.approve(target, as_lst);
+            }
+            as_lst
+        } else {
+            U512::zero()
+        };
+        let as_cspr = self.lst_cspr_value(assets - as_lst);
+        if as_cspr > self.env().self_balance() {
+            self.env().revert(VaultError::InsufficientLiquidity);
+        }
+        let shares_minted = MigrationTargetContractRef::new(self.env(), target)
+            .with_tokens(as_cspr)
+            .receive_migrated_position(user, assets, cost_basis);
+        
+        self.env().emit_event(PositionMigrated {
+            operation_id,
+            user,
+            target,
+            shares_burned: shares,
+            assets,
+            cost_basis,
+            shares_minted,
+            timestamp: self.env().get_block_time(),
+        });
+        
+        shares_minted
+    }
+
+
+    /// Update contract addresses (admin only)
+    pub fn set_liquid_staking(&mut self, address: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.liquid_staking_address.get());
+        self.liquid_staking_address.set(address);
+        self.config_changed("liquid_staking", old, config_address(Some(address)));
+    }
+
+    pub fn set_strategy_router(&mut self, address: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.strategy_router_address.get());
+        self.strategy_router_address.set(address);
+        self.config_changed("strategy_router", old, config_address(Some(address)));
+    }
+
+    pub fn set_yield_aggregator(&mut self, address: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.yield_aggregator_address.get());
+        self.yield_aggregator_address.set(address);
+        self.config_changed("yield_aggregator", old, config_address(Some(address)));
+    }
+
+    /// Grant a role on the vault (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+
+    /// Revoke a role on the vault (admin only)
+    pub fn revoke_role(&mut self, role: u8, account: Address) {
+        self.access_control.revoke_role(role, account);
+    }
+
+    /// Current members of a role
+    pub fn get_role_members(&self, role: u8) -> Vec<Address> {
+        self.access_control.get_role_members(role)
+    }
+
+    pub fn set_cv_cspr_token(&mut self, address: Address) {
+        self.access_control.only_admin();
+        self.check_share_decimals(address);
+        let old = config_address(self.cv_cspr_token_address.get());
+        self.cv_cspr_token_address.set(address);
+        self.config_changed("cv_cspr_token", old, config_address(Some(address)));
+    }
+
+    /// Rotate the protocol treasury (admin only)
+    /// 
+    /// Management fees due to the old treasury are minted to it first. With
+    /// `migrate_shares` the old treasury's fee-origin shares move to the new
+    /// address; otherwise the old treasury keeps them as a regular holder.
+    pub fn set_treasury(&mut self, new_treasury: Address, migrate_shares: bool) {
+        self.access_control.only_admin();
+        self.when_not_frozen(&new_treasury);
+        
+        if new_treasury == Address::from([0u8; 32]) || new_treasury == self.env().self_address() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_treasury = self.treasury.get().unwrap();
+        if new_treasury == old_treasury {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.accrue_management_fees();
+        
+        // The new treasury stops counting as a depositor
+        let new_before = self.shares_of(&new_treasury);
+        self.track_depositor(&new_treasury, new_before, U512::zero());
+        
+        let old_shares = self.shares_of(&old_treasury);
+        let fee_shares = self.treasury_fee_shares.get_or_default().min(old_shares);
+        let shares_migrated = if migrate_shares { fee_shares } else { U512::zero() };
+        
+        if !shares_migrated.is_zero() {
+            self.set_user_shares(&old_treasury, old_shares - shares_migrated);
+            self.set_user_shares(&new_treasury, new_before + shares_migrated);
+        }
+        self.treasury_fee_shares.set(shares_migrated);
+        self.treasury.set(new_treasury);
+        
+        // An exempt treasury hands its exemption to the new address
+        if self.is_fee_exempt(old_treasury) {
+            self.store_fee_exemption(old_treasury, false);
+            if !self.is_fee_exempt(new_treasury) {
+                self.store_fee_exemption(new_treasury, true);
+            }
+        }
+        
+        // Whatever the old treasury still holds now counts as a regular position
+        let old_after = self.shares_of(&old_treasury);
+        self.track_depositor(&old_treasury, U512::zero(), old_after);
+        
+        self.env().emit_event(TreasuryChanged {
+            old: old_treasury,
+            new: new_treasury,
+            shares_migrated,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Name the old vault allowed to migrate positions into this one (admin only)
+    pub fn set_migration_source(&mut self, source: Address) {
+        self.access_control.only_admin();
+        if source == self.env().self_address() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let old = config_address(self.migration_source.get());
+        self.migration_source.set(source);
+        self.config_changed("migration_source", old, config_address(Some(source)));
+    }
+
+    /// Set the grace period before the admin migration batch opens (admin only)
+    pub fn set_migration_grace_period(&mut self, seconds: u64) {
+        self.access_control.only_admin();
+        if seconds > 90 * 24 * 60 * 60 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let old = self.migration_grace_period.get_or_default();
+        self.migration_grace_period.set(seconds);
+        self.config_changed("migration_grace_period", old, seconds);
+    }
+
+    /// Set the minimum time between a deposit and a withdrawal (admin only, 0 disables)
+    pub fn set_min_holding_period(&mut self, seconds: u64) {
+        self.access_control.only_admin();
+        let old = self.min_holding_seconds.get_or_default();
+        self.min_holding_seconds.set(seconds);
+        self.config_changed("min_holding_period", old, seconds);
+    }
+
+    pub fn get_min_holding_period(&self) -> u64 {
+        self.min_holding_seconds.get_or_default()
+    }
+
+    /// Smallest position a deposit may mint or an exit may leave behind
+    pub fn get_min_shares(&self) -> U512 {
+        self.min_shares.get_or_default()
+    }
+
+    /// Set how long after unlock a withdrawal request can be expired (admin only, 0 disables)
+    pub fn set_request_expiry(&mut self, expiry: u64) {
+        self.access_control.only_admin();
+        let old = self.request_expiry.get_or_default();
+        self.request_expiry.set(expiry);
+        self.config_changed("request_expiry", old, expiry);
+    }
+
+    /// Set the rebate keepers keep from push-completed withdrawals (admin only)
+    /// 
+    /// Capped at `MAX_KEEPER_REBATE`; 0 makes keeper completion free for users.
+    pub fn set_keeper_rebate(&mut self, rebate: U512) {
+        self.access_control.only_admin();
+        
+        if rebate > U512::from(MAX_KEEPER_REBATE) {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        
+        let old = self.keeper_rebate.get_or_default();
+        self.keeper_rebate.set(rebate);
+        self.config_changed("keeper_rebate", old, rebate);
+    }
+
+    /// Configure harvest-on-withdraw (admin only)
+    /// 
+    /// When enabled, withdrawals worth at least `min_assets` are valued with
+    /// the pending-yield estimate folded into total assets, reading at most
+    /// `max_strategies` strategies.
+    pub fn set_harvest_before_withdraw(&mut self, enabled: bool, min_assets: U512, max_strategies: u32) {
+        self.access_control.only_admin();
+        
+        if max_strategies == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_enabled = self.harvest_before_withdraw.get_or_default();
+        let old_min_assets = self.harvest_withdraw_threshold.get_or_default();
+        let old_max_strategies = self.max_harvest_strategies.get_or_default();
+        
+        self.harvest_before_withdraw.set(enabled);
+        self.harvest_withdraw_threshold.set(min_assets);
+        self.max_harvest_strategies.set(max_strategies);
+        
+        self.config_changed("harvest_before_withdraw", old_enabled, enabled);
+        self.config_changed("harvest_withdraw_threshold", old_min_assets, min_assets);
+        self.config_changed("max_harvest_strategies", old_max_strategies, max_strategies);
+    }
+
+    pub fn get_harvest_before_withdraw(&self) -> bool {
+        self.harvest_before_withdraw.get_or_default()
+    }
+
+    /// Update instant pool target (admin only)
+    pub fn set_instant_pool_target(&mut self, target_bps: u32) {
+        self.access_control.only_admin();
+        
+        // Validate: max 50% (5000 bps)
+        if target_bps > 5000 {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let old = self.instant_pool_target_bps.get_or_default();
+        self.instant_pool_target_bps.set(target_bps);
+        self.config_changed("instant_pool_target_bps", old, target_bps);
+    }
+
+    /// Start a new pool metrics window (admin only)
+    pub fn reset_pool_metrics(&mut self) {
+        self.access_control.only_admin();
+        
+        let old = self.pool_metrics_since.get_or_default();
+        self.clear_pool_metrics();
+        self.config_changed("pool_metrics_since", old, self.env().get_block_time());
+    }
+
+    /// Update deposit limits (admin only)
+    pub fn update_deposit_limits(&mut self, max_per_tx: U512, max_per_day: U512) {
+        self.access_control.only_admin();
+        
+        let old_per_tx = self.max_deposit_per_tx.get_or_default();
+        let old_per_day = self.max_deposit_per_day.get_or_default();
+        self.max_deposit_per_tx.set(max_per_tx);
+        self.max_deposit_per_day.set(max_per_day);
+        
+        self.config_changed("max_deposit_per_tx", old_per_tx, max_per_tx);
+        self.config_changed("max_deposit_per_day", old_per_day, max_per_day);
+    }
+
+    /// Update all three fees at once (admin only)
+    /// 
+    /// Caps: performance 50%, management 10%, instant withdrawal 5%. The
+    /// performance fee plus the aggregator's community pool share must also
+    /// stay within `MAX_COMBINED_YIELD_TAKE_BPS`.
+    /// Management fees due at the old rate are collected first, and pending
+    /// withdrawal requests keep the performance fee they were made under.
+    pub fn set_fees(&mut self, performance_bps: u32, management_bps: u32, instant_bps: u32) {
+        self.access_control.only_admin();
+        self.update_fees(performance_bps, management_bps, instant_bps);
+    }
+
+    pub fn set_performance_fee(&mut self, fee_bps: u32) {
+        self.access_control.only_admin();
+        self.update_fees(
+            fee_bps,
+            self.management_fee_bps.get_or_default(),
+            self.instant_withdrawal_fee_bps.get_or_default(),
+        );
+    }
+
+    pub fn set_management_fee(&mut self, fee_bps: u32) {
+        self.access_control.only_admin();
+        self.update_fees(
+            self.performance_fee_bps.get_or_default(),
+            fee_bps,
+            self.instant_withdrawal_fee_bps.get_or_default(),
+        );
+    }
+
+    pub fn set_instant_withdrawal_fee(&mut self, fee_bps: u32) {
+        self.access_control.only_admin();
+        self.update_fees(
+            self.performance_fee_bps.get_or_default(),
+            self.management_fee_bps.get_or_default(),
+            fee_bps,
+        );
+    }
+
+    /// Set the share of the instant fee left to remaining holders (admin only)
+    /// 
+    /// In basis points of the fee, at most 10,000. That part stays in total
+    /// assets and raises the share price; the rest is booked as protocol fees.
+    pub fn set_instant_fee_to_holders(&mut self, split_bps: u32) {
+        self.access_control.only_admin();
+        if split_bps > 10000 {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        let old = self.instant_fee_to_holders_bps.get_or_default();
+        self.instant_fee_to_holders_bps.set(split_bps);
+        self.config_changed("instant_fee_to_holders_bps", old, split_bps);
+    }
+
+    pub fn get_instant_fee_to_holders(&self) -> u32 {
+        self.instant_fee_to_holders_bps.get_or_default()
+    }
+
+    /// Set the size-tiered exit fees (admin only)
+    /// 
+    /// Each `(threshold_bps, fee_bps)` tier charges `fee_bps` on the part
+    /// of an exit above `threshold_bps` of total assets, up to the next
+    /// tier's threshold. The fee stays in the vault, so it goes to the
+    /// holders left to bear the unwind, not to the treasury. Every exit
+    /// path pays it; withdrawal requests are measured when they are made.
+    /// 
+    /// At most `MAX_EXIT_FEE_TIERS` tiers with thresholds rising below
+    /// 100% and fees up to `MAX_EXIT_FEE_TIER_BPS`. An empty table turns
+    /// the fee off.
+    pub fn set_exit_fee_tiers(&mut self, tiers: Vec<(u32, u32)>) {
+        self.access_control.only_admin();
+        let ascending = tiers.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        let in_range = tiers.iter().all(|(threshold_bps, fee_bps)| *threshold_bps < 10000 && *fee_bps <= MAX_EXIT_FEE_TIER_BPS);
+        if tiers.len() > MAX_EXIT_FEE_TIERS || !ascending || !in_range {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        let old = render_exit_fee_tiers(&self.exit_fee_tiers.get_or_default());
+        let new = render_exit_fee_tiers(&tiers);
+        self.exit_fee_tiers.set(tiers);
+        self.config_changed("exit_fee_tiers", old, new);
+    }
+
+    pub fn get_exit_fee_tiers(&self) -> Vec<(u32, u32)> {
+        self.exit_fee_tiers.get_or_default()
+    }
+
+    /// Size-tiered exit fee an exit of `shares` would pay now
+    /// 
+    /// Measured on the `withdraw` valuation and ignores fee exemptions;
+    /// the `preview_*` views give the payout net of every fee.
+    pub fn get_exit_fee_quote(&self, shares: U512) -> U512 {
+        if shares >= self.total_shares.get_or_default() {
+            return U512::zero();
+        }
+        tiered_exit_fee(self.withdrawal_value(shares), self.total_assets(), &self.exit_fee_tiers.get_or_default())
+    }
+
+    /// Set the share of performance fees credited to slashing insurance (admin only)
+    /// 
+    /// LiquidStaking must accept this vault as its insurance fee source.
+    pub fn set_insurance_fee_bps(&mut self, slice_bps: u32) {
+        self.access_control.only_admin();
+        if slice_bps > 10000 {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        let old = self.insurance_fee_bps.get_or_default();
+        self.insurance_fee_bps.set(slice_bps);
+        self.config_changed("insurance_fee_bps", old, slice_bps);
+    }
+
+    pub fn get_insurance_fee_bps(&self) -> u32 {
+        self.insurance_fee_bps.get_or_default()
+    }
+
+    /// Lifetime performance fees credited to the insurance fund
+    pub fn get_insurance_fees_credited(&self) -> U512 {
+        self.insurance_fees_credited.get_or_default()
+    }
+
+    /// Current fees: (performance, management, instant withdrawal) in bps
+    pub fn get_fees(&self) -> (u32, u32, u32) {
+        (
+            self.performance_fee_bps.get_or_default(),
+            self.management_fee_bps.get_or_default(),
+            self.instant_withdrawal_fee_bps.get_or_default(),
+        )
+    }
+
+    /// Set the CSPR/USD price feed and how old its price may be (admin only)
+    pub fn set_price_oracle(&mut self, oracle: Address, max_staleness: u64) {
+        self.access_control.only_admin();
+        let old_oracle = config_address(self.price_oracle.get());
+        let old_staleness = self.max_price_staleness.get_or_default();
+        
+        self.price_oracle.set(oracle);
+        self.max_price_staleness.set(max_staleness);
+        
+        self.config_changed("price_oracle", old_oracle, config_address(Some(oracle)));
+        self.config_changed("max_price_staleness", old_staleness, max_staleness);
+    }
+
+    /// Switch deposit limits between CSPR and USD mode (admin only)
+    /// 
+    /// USD amounts use 9 decimals. Enabling requires a price oracle.
+    pub fn set_usd_deposit_limits(&mut self, enabled: bool, max_per_tx_usd: U512, max_per_day_usd: U512) {
+        self.access_control.only_admin();
+        
+        if enabled && self.price_oracle.get().is_none() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_enabled = self.usd_limits_enabled.get_or_default();
+        let old_per_tx = self.max_deposit_usd.get_or_default();
+        let old_per_day = self.max_deposit_per_day_usd.get_or_default();
+        
+        self.usd_limits_enabled.set(enabled);
+        self.max_deposit_usd.set(max_per_tx_usd);
+        self.max_deposit_per_day_usd.set(max_per_day_usd);
+        
+        self.config_changed("usd_limits_enabled", old_enabled, enabled);
+        self.config_changed("max_deposit_usd", old_per_tx, max_per_tx_usd);
+        self.config_changed("max_deposit_per_day_usd", old_per_day, max_per_day_usd);
+    }
+
+    /// Update withdrawal timelock (admin only)
+    pub fn set_withdrawal_timelock(&mut self, timelock: u64) {
+        self.access_control.only_admin();
+        
+        // Minimum 1 day, maximum 30 days
+        if timelock < 86400 || timelock > 2592000 {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        let old = self.withdrawal_timelock.get_or_default();
+        self.withdrawal_timelock.set(timelock);
+        self.config_changed("withdrawal_timelock", old, timelock);
+    }
+
+    /// Update profit unlock duration (admin only, max 7 days)
+    /// 
+    /// Settles the currently locked amount first so the change only
+    /// affects the remaining unlock.
+    pub fn set_profit_unlock_duration(&mut self, duration: u64) {
+        self.access_control.only_admin();
+        
+        if duration > 7 * 24 * 60 * 60 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.locked_profit.set(self.get_locked_profit());
+        self.locked_profit_time.set(self.env().get_block_time());
+        let old = self.profit_unlock_duration.get_or_default();
+        self.profit_unlock_duration.set(duration);
+        self.config_changed("profit_unlock_duration", old, duration);
+    }
+
+    /// Update operation log capacity (admin only)
+    /// 
+    /// Records whose slot moves under the new size drop out of the
+    /// retained window; IDs keep increasing regardless.
+    pub fn set_operation_log_size(&mut self, size: u64) {
+        self.access_control.only_admin();
+        
+        if size == 0 || size > 4096 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.operation_log_size.get_or_default();
+        self.operation_log_size.set(size);
+        self.config_changed("operation_log_size", old, size);
+    }
+
+    /// Register emergency action approvers and the M-of-N threshold (admin only)
+    /// 
+    /// A threshold of 1 keeps single-admin mode.
+    pub fn set_approvers(&mut self, approvers: Vec<Address>, threshold: u8, validity_window: u64) {
+        self.access_control.only_admin();
+        self.approvals.set_approvers(approvers, threshold, validity_window);
+    }
+
+    /// Approve an emergency action hash (registered approvers only)
+    pub fn approve_action(&mut self, action_hash: [u8; 32]) {
+        self.approvals.approve(action_hash);
+    }
+
+    /// Withdraw the caller's approval of an emergency action hash
+    pub fn revoke_approval(&mut self, action_hash: [u8; 32]) {
+        self.approvals.revoke(action_hash);
+    }
+
+    /// Unexpired approvals for an emergency action hash
+    pub fn get_approval_count(&self, action_hash: [u8; 32]) -> u8 {
+        self.approvals.approval_count(action_hash)
+    }
+
+    /// Action hash approvers sign off on before `rescue_funds` runs
+    pub fn get_rescue_funds_hash(&self, token: Address, amount: U512, recipient: Address) -> [u8; 32] {
+        self.approvals.action_hash("rescue_funds", &(token, amount, recipient))
+    }
+
+    /// Rescue stuck funds (admin only, emergency use)
+    pub fn rescue_funds(&mut self, token: Address, amount: U512, recipient: Address) {
+        self.access_control.only_admin();
+        
+        let action_hash = self.get_rescue_funds_hash(token, amount, recipient);
+        self.approvals.require_approval(action_hash);
+        
+        self.env().emit_event(FundsRescued {
+            token,
+            amount,
+            recipient,
+        });
+    }
+
+    /// Action hash approvers sign off on before `reset_after_total_loss` runs
+    /// 
+    /// Includes the loss epoch, so approvals can't be replayed for a later reset.
+    pub fn get_reset_after_total_loss_hash(&self) -> [u8; 32] {
+        self.approvals.action_hash("reset_after_total_loss", &self.loss_epoch.get_or_default())
+    }
+
+    /// Write off all shares of an insolvent vault and reopen it (admin only)
+    /// 
+    /// Every balance and pending request from before the reset becomes
+    /// worthless; the next deposit is priced 1:1 again.
+    pub fn reset_after_total_loss(&mut self) {
+        self.access_control.only_admin();
+        
+        if !self.is_insolvent() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+        
+        let action_hash = self.get_reset_after_total_loss_hash();
+        self.approvals.require_approval(action_hash);
+        
+        let shares_written_off = self.total_shares.get_or_default();
+        let epoch = self.loss_epoch.get_or_default() + 1;
+        
+        self.total_shares.set(U512::zero());
+        self.held_shares.set(U512::zero());
+        self.locked_shares.set(U512::zero());
+        self.total_assets.set(U512::zero());
+        self.locked_profit.set(U512::zero());
+        self.set_instant_pool(U512::zero());
+        self.deployed_assets.set(U512::zero());
+        self.idle_lst_assets.set(U512::zero());
+        self.in_transit_assets.set(U512::zero());
+        self.treasury_fee_shares.set(U512::zero());
+        self.unique_depositors.set(0);
+        self.loss_epoch.set(epoch);
+        // The insolvent stretch is not billed to the next depositors
+        self.last_management_fee_collection.set(self.env().get_block_time());
+        
+        self.env().emit_event(VaultReset {
+            shares_written_off,
+            loss_epoch: epoch,
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+
+    pub fn get_user_shares(&self, user: Address) -> U512 {
+        self.shares_of(&user)
+    }
+
+    pub fn get_user_assets(&self, user: Address) -> U512 {
+        let shares = self.get_user_shares(user);
+        self.convert_to_assets(shares)
+    }
+
+    pub fn get_withdrawal_request(&self, request_id: u64) -> Option<WithdrawalRequest> {
+        self.withdrawal_request(request_id)
+    }
+
+    /// Open withdrawal requests, `limit` (at most 50) from position `offset`
+    /// 
+    /// Covers requests neither completed nor expired. Closing a request
+    /// moves the last open one into its position, so pages read across
+    /// several blocks can skip or repeat a request.
+    pub fn get_pending_withdrawals_page(&self, offset: u32, limit: u32) -> Vec<(u64, WithdrawalRequest)> {
+        let count = self.pending_withdrawal_count.get_or_default();
+        let end = offset.saturating_add(limit.min(MAX_PENDING_WITHDRAWALS_PAGE)).min(count);
+        
+        let mut page = Vec::new();
+        for position in offset..end {
+            if let Some(request_id) = self.pending_withdrawal_ids.get(&position) {
+                if let Some(request) = self.withdrawal_request(request_id) {
+                    page.push((request_id, request));
+                }
+            }
+        }
+        page
+    }
+
+    /// Number of open withdrawal requests
+    pub fn get_pending_withdrawal_count(&self) -> u32 {
+        self.pending_withdrawal_count.get_or_default()
+    }
+
+    /// Request-time value of all open withdrawal requests
     /// 
-    /// Formula: assets = (shares * totalAssets) / totalShares
+    /// An upper bound on what they pay out: completion pays the lesser of
+    /// this and the shares' value at the time.
+    pub fn get_total_pending_withdrawal_assets(&self) -> U512 {
+        self.pending_withdrawal_assets.get_or_default()
+    }
+
+    /// Request-time value of open requests unlocking by the end of `timestamp`'s day
     /// 
-    /// As yields accrue, totalAssets grows faster than totalShares,
-    /// making each share worth more CSPR over time
-    pub fn convert_to_assets(&self, shares: U512) -> U512 {
-        let total_shares = self.total_shares.get_or_default();
-        
-        if total_shares.is_zero() {
-            // No shares exist
+    /// Requests are bucketed by unlock day (`MATURITY_BUCKET_SECONDS`), so
+    /// one unlocking later on the same day as `timestamp` is included.
+    /// Requests already unlocked but not yet completed count as well.
+    pub fn get_withdrawals_maturing_before(&self, timestamp: u64) -> U512 {
+        let total = self.pending_withdrawal_assets.get_or_default();
+        if total.is_zero() {
             return U512::zero();
         }
         
-        let total_assets = self.total_assets();
+        let day = timestamp / MATURITY_BUCKET_SECONDS;
+        let first_day = self.pending_unlock_first_day.get_or_default();
+        let last_day = self.pending_unlock_last_day.get_or_default();
+        if day < first_day {
+            return U512::zero();
+        }
         
-        // assets = (shares * totalAssets) / totalShares
-        shares.checked_mul(total_assets)
-            .unwrap()
-            .checked_div(total_shares)
-            .unwrap()
+        // Subtract the (at most timelock-wide) tail after `day`
+        let mut later = U512::zero();
+        for bucket in (day + 1)..=last_day {
+            later += self.pending_assets_by_unlock_day.get(&bucket).unwrap_or_default();
+        }
+        total.saturating_sub(later)
     }
 
-    /// Calculate total assets under management
+    pub fn is_withdrawal_expired(&self, request_id: u64) -> bool {
+        self.withdrawal_request(request_id)
+            .is_some_and(|request| request.status == RequestStatus::Expired)
+    }
+
+    /// Whether keepers may complete `request_id` on its owner's behalf
+    pub fn is_auto_complete(&self, request_id: u64) -> bool {
+        self.withdrawal_request(request_id).is_some_and(|request| request.auto_complete)
+    }
+
+    pub fn get_keeper_rebate(&self) -> U512 {
+        self.keeper_rebate.get_or_default()
+    }
+
+    /// Rebates credited to `keeper` for completing withdrawals, not yet claimed
+    pub fn get_keeper_rebates_earned(&self, keeper: Address) -> U512 {
+        self.keeper_rebates_earned.get(&keeper).unwrap_or_default()
+    }
+
+    /// Next withdrawal request ID; requests are numbered from zero
+    pub fn get_next_withdrawal_id(&self) -> u64 {
+        self.next_withdrawal_id.get_or_default()
+    }
+
+    pub fn get_withdrawal_timelock(&self) -> u64 {
+        self.withdrawal_timelock.get_or_default()
+    }
+
+    /// Deposit tracking for `user` (fee basis and daily limit usage)
+    pub fn get_user_deposit(&self, user: Address) -> Option<UserDeposit> {
+        self.user_deposit(&user)
+    }
+
+    /// Annualized return `user` has realized on their current position (bps)
     /// 
-    /// Includes:
-    /// - lstCSPR staked in LiquidStaking contract
-    /// - Assets deployed in strategy contracts
-    /// - Instant withdrawal pool
-    /// - Accrued but uncollected rewards
-    pub fn total_assets(&self) -> U512 {
-        // Start with instant pool
-        let total = self.instant_withdrawal_pool.get_or_default();
-        
-        
+    /// `(value + withdrawn - deposited) * 10000 * SECONDS_PER_YEAR /
+    /// (deposited * held)`, where `value` is what `withdraw` would pay for
+    /// the user's shares now, `withdrawn` what exits already paid out (both
+    /// in CSPR, after fees), `deposited` the lifetime `total_deposited` and
+    /// `held` the time since `first_deposit_time`. Simple rather than
+    /// compounded, so deposits made after the first count as held for the
+    /// whole span. Shares locked in a pending withdrawal request count once
+    /// the request pays out.
+    /// 
+    /// Zero at a loss, without a position, and for positions younger than
+    /// `MIN_REALIZED_APY_SECONDS`.
+    pub fn get_user_realized_apy(&self, user: Address) -> U256 {
+        let deposit = match self.user_deposit(&user) {
+            Some(deposit) if !deposit.total_deposited.is_zero() => deposit,
+            _ => return U256::zero(),
+        };
+        let held = self.env().get_block_time().saturating_sub(deposit.first_deposit_time);
+        if held < MIN_REALIZED_APY_SECONDS {
+            return U256::zero();
+        }
         
-        total
+        let value = self.lst_cspr_value(self.preview_withdraw(user, self.shares_of(&user)));
+        let gain = (value + deposit.total_withdrawn).saturating_sub(deposit.total_deposited);
+        let apy = annualize_bps(gain, deposit.total_deposited, held).unwrap_or_revert(&self.env());
+        safe_u512_to_u256(apy).unwrap_or_revert(&self.env())
     }
 
-    /// Maximum deposit allowed for a user (for rate limiting)
-    pub fn max_deposit(&self, user: Address) -> U512 {
-        let last_deposit_time = self.user_last_deposit_time.get(&user);
+    pub fn get_instant_pool_balance(&self) -> U512 {
+        self.instant_withdrawal_pool.get_or_default()
+    }
+
+    /// CSPR the instant pool can pay out at the LiquidStaking exchange rate
+    pub fn get_instant_pool_value_cspr(&self) -> U512 {
+        self.lst_cspr_value(self.instant_withdrawal_pool.get_or_default())
+    }
+
+    pub fn get_fees_collected(&self) -> U512 {
+        self.fees_collected.get_or_default()
+    }
+
+    /// Lifetime fee revenue split by stream
+    pub fn get_fee_breakdown(&self) -> FeeBreakdown {
+        let performance_fees = self.performance_fees_collected.get_or_default();
+        let instant_fees = self.instant_fees_collected.get_or_default();
+        let community_allocations = self.community_allocations.get_or_default();
         
-        match last_deposit_time {
-            Some(time) => {
-                let current_time = self.env().get_block_time();
-                let time_window = 86400u64; // 24 hours
-                
-                // Reset if outside window
-                if current_time > time + time_window {
-                    return self.max_deposit_per_day.get_or_default();
-                }
-                
-                let max_daily = self.max_deposit_per_day.get_or_default();
-                let (_, used) = self.daily_deposits.get(&user).unwrap_or((0, U512::zero()));
-                max_daily.checked_sub(used).unwrap_or(U512::zero())
-            },
-            None => self.max_deposit_per_day.get_or_default(),
+        FeeBreakdown {
+            performance_fees,
+            instant_fees,
+            management_fee_shares: self.management_fee_shares_minted.get_or_default(),
+            community_allocations,
+            total_fees: performance_fees + instant_fees + community_allocations,
         }
     }
 
-    /// Maximum withdrawal allowed for a user
-    pub fn max_withdraw(&self, user: Address) -> U512 {
-        let shares = self.user_shares.get(&user).unwrap_or_default();
-        self.convert_to_assets(shares)
+    /// Split of total assets between pool, strategies, idle lstCSPR and
+    /// open withdrawal requests
+    pub fn get_asset_composition(&self) -> AssetComposition {
+        AssetComposition {
+            pool_assets: self.instant_withdrawal_pool.get_or_default(),
+            deployed_assets: self.deployed_assets.get_or_default(),
+            idle_lst_assets: self.idle_lst_assets.get_or_default(),
+            in_transit_assets: self.in_transit_assets.get_or_default(),
+            total_assets: self.total_assets.get_or_default(),
+        }
     }
 
-    // FEE CALCULATION HELPERS
-
-    /// Calculate performance fee for a user's withdrawal
-    /// 
-    /// Performance fee is charged on PROFITS only, not principal
-    /// Tracks user's cost basis to determine profit
-    fn calculate_performance_fee(&mut self, user: &Address, withdrawal_amount: U512) -> U512 {
-        let cost_basis = self.user_cost_basis.get(user);
+    /// Instant pool utilization since the last `reset_pool_metrics`
+    pub fn get_pool_metrics(&self) -> PoolMetrics {
+        let samples = self.pool_metrics_samples.get_or_default();
+        let avg_pool_balance = if samples == 0 {
+            U512::zero()
+        } else {
+            self.pool_metrics_sum.get_or_default() / U512::from(samples)
+        };
         
-        match cost_basis {
-            Some(basis) => {
-                if withdrawal_amount <= basis {
-                    // No profit, no fee
-                    return U512::zero();
-                }
-                
-                let profit = withdrawal_amount.checked_sub(basis).unwrap();
-                
-                // Apply performance fee to profit only
-                let fee_bps = self.performance_fee_bps.get_or_default();
-                let fee = profit.checked_mul(U512::from(fee_bps))
-                    .unwrap()
-                    .checked_div(U512::from(10000u64))
-                    .unwrap();
-                
-                let current_fees = self.fees_collected.get_or_default();
-                self.fees_collected.set(current_fees.checked_add(fee).unwrap());
-                
-                fee
-            },
-            None => {
-                // No deposit data, treat entire withdrawal as profit (edge case)
-                let fee_bps = self.performance_fee_bps.get_or_default();
-                let fee = withdrawal_amount.checked_mul(U512::from(fee_bps))
-                    .unwrap()
-                    .checked_div(U512::from(10000u64))
-                    .unwrap();
-                
-                let current_fees = self.fees_collected.get_or_default();
-                self.fees_collected.set(current_fees.checked_add(fee).unwrap());
-                
-                fee
-            }
+        PoolMetrics {
+            instant_withdrawals_served: self.pool_metrics_served.get_or_default(),
+            instant_volume_served: self.pool_metrics_volume.get_or_default(),
+            liquidity_rejections: self.pool_metrics_rejections.get_or_default(),
+            samples,
+            min_pool_balance: self.pool_metrics_min.get_or_default(),
+            max_pool_balance: self.pool_metrics_max.get_or_default(),
+            avg_pool_balance,
+            current_pool_balance: self.instant_withdrawal_pool.get_or_default(),
+            since: self.pool_metrics_since.get_or_default(),
         }
     }
 
-    /// Collect management fees (time-based, called by keeper)
+    /// Fee revenue for one daily period (block time / FEE_PERIOD_SECONDS)
     /// 
-    /// Management fee accrues continuously at annual rate (default 2%)
-    /// Collected by minting new shares to treasury
-    pub fn collect_management_fees(&mut self) {
-        self.access_control.only_keeper();
-        
-        let current_time = self.env().get_block_time();
-        let last_collection = self.last_management_fee_collection.get_or_default();
+    /// Returns zeros for periods with no fees or outside the retained window.
+    pub fn get_fee_revenue(&self, period: u64) -> FeeBreakdown {
+        let slot = period % FEE_PERIODS_RETAINED;
         
-        // Require at least 1 hour between collections
-        if current_time < last_collection + 3600 {
-            self.env().revert(VaultError::RateLimitExceeded);
+        if self.fee_period_ids.get(&slot) != Some(period) {
+            return FeeBreakdown {
+                performance_fees: U512::zero(),
+                instant_fees: U512::zero(),
+                management_fee_shares: U512::zero(),
+                community_allocations: U512::zero(),
+                total_fees: U512::zero(),
+            };
         }
         
-        let time_elapsed = current_time - last_collection;
+        let performance_fees = self.fee_period_performance.get(&slot).unwrap_or_default();
+        let instant_fees = self.fee_period_instant.get(&slot).unwrap_or_default();
+        let community_allocations = self.fee_period_community.get(&slot).unwrap_or_default();
         
+        FeeBreakdown {
+            performance_fees,
+            instant_fees,
+            management_fee_shares: self.fee_period_management_shares.get(&slot).unwrap_or_default(),
+            community_allocations,
+            total_fees: performance_fees + instant_fees + community_allocations,
+        }
+    }
+
+    /// Current fee revenue period
+    pub fn get_current_fee_period(&self) -> u64 {
+        self.env().get_block_time() / FEE_PERIOD_SECONDS
+    }
+
+    pub fn get_total_shares(&self) -> U512 {
+        self.total_shares.get_or_default()
+    }
+
+    /// Number of users currently holding shares
+    pub fn get_unique_depositors(&self) -> u64 {
+        self.unique_depositors.get_or_default()
+    }
+
+    /// Price of 1 share in CSPR scaled by 1e18 (1.0 before the first deposit)
+    pub fn get_share_price_precise(&self) -> U256 {
         let total_shares = self.total_shares.get_or_default();
-        let fee_bps = self.management_fee_bps.get_or_default();
-        let seconds_per_year = 31536000u64; // 365 days
-        
-        let fee_shares = total_shares
-            .checked_mul(U512::from(fee_bps))
-            .unwrap()
-            .checked_mul(U512::from(time_elapsed))
-            .unwrap()
-            .checked_div(U512::from(seconds_per_year))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
+        let one = U512::from(1_000_000_000_000_000_000u64);
         
-        if fee_shares.is_zero() {
-            return;
+        if total_shares.is_zero() {
+            return U256::from(1_000_000_000_000_000_000u64);
         }
         
-        self.total_shares.set(total_shares.checked_add(fee_shares).unwrap());
-        self.last_management_fee_collection.set(current_time);
-        
-        let treasury = self.treasury.get().unwrap();
-        let treasury_shares = self.user_shares.get(&treasury).unwrap_or_default();
-        self.user_shares.set(&treasury, treasury_shares.checked_add(fee_shares).unwrap());
-        
-        
-        self.env().emit_event(ManagementFeesCollected {
-            shares: fee_shares,
-            treasury,
-            amount: fee_shares,
-            fee_recipient: treasury,
-            timestamp: current_time,
-        });
+        safe_u512_to_u256(self.total_assets() * one / total_shares).unwrap_or_revert(&self.env())
     }
 
-    /// Calculate optimal amount to deploy to strategies vs keep in pool
-    fn calculate_strategy_deployment(&self, deposit_amount: U512) -> U512 {
-        // Get target instant pool percentage (default 5%)
-        let target_bps = self.instant_pool_target_bps.get_or_default();
-        
-        let total_assets = self.total_assets();
-        let target_pool_size = total_assets
-            .checked_mul(U512::from(target_bps))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
-        
-        let current_pool = self.instant_withdrawal_pool.get_or_default();
-        
-        if current_pool >= target_pool_size {
-            // Pool is at target, deploy entire amount
-            return deposit_amount;
+    pub fn get_share_price(&self) -> U512 {
+        // Price of 1 share in CSPR (scaled by 1e9)
+        let one_share = U512::from(1_000_000_000u64); // 1.0 with 9 decimals
+        self.convert_to_assets(one_share)
+    }
+
+    /// Last issued operation ID (0 if no operations yet)
+    pub fn get_last_operation_id(&self) -> u64 {
+        self.operation_id.get_or_default()
+    }
+
+    /// Get an operation by ID, if it is still inside the retained window
+    pub fn get_operation(&self, id: u64) -> Option<OperationRecord> {
+        if id == 0 || id > self.operation_id.get_or_default() {
+            return None;
         }
         
-        // Pool needs replenishment
-        let pool_deficit = target_pool_size.checked_sub(current_pool).unwrap();
-        
-        if deposit_amount <= pool_deficit {
-            // Entire deposit goes to pool
-            return U512::zero();
+        let slot = id % self.operation_log_size.get_or_default().max(1);
+        if self.operation_ids.get(&slot) != Some(id) {
+            // Evicted
+            return None;
         }
         
-        // Split: fill pool deficit, deploy remainder
-        deposit_amount.checked_sub(pool_deficit).unwrap()
+        Some(OperationRecord {
+            id,
+            kind: self.operation_kinds.get(&slot).unwrap_or_default(),
+            user: self.operation_users.get(&slot)?,
+            assets: self.operation_assets.get(&slot).unwrap_or_default(),
+            shares: self.operation_shares.get(&slot).unwrap_or_default(),
+            timestamp: self.operation_times.get(&slot).unwrap_or_default(),
+        })
     }
 
-    /// Check and update daily deposit limit for user
-    fn check_daily_deposit_limit(&mut self, user: &Address, amount: U512) -> bool {
-        let current_time = self.env().get_block_time();
-        let time_window = 86400u64; // 24 hours
-        
-        let last_deposit_time = self.user_last_deposit_time.get(user);
+    /// Get up to `count` most recent operations, newest first
+    pub fn get_recent_operations(&self, count: u64) -> Vec<OperationRecord> {
+        let last_id = self.operation_id.get_or_default();
+        let window = count.min(self.operation_log_size.get_or_default()).min(last_id);
         
-        match last_deposit_time {
-            Some(time) => {
-                if current_time > time + time_window {
-                    // Reset daily limit
-                    self.daily_deposits.set(user, (current_time, amount));
-                    return true;
-                }
-                
-                let (day, current_daily) = self.daily_deposits.get(user).unwrap_or((0, U512::zero()));
-                let new_daily = current_daily.checked_add(amount).unwrap();
-                let max_daily = self.max_deposit_per_day.get_or_default();
-                
-                if new_daily > max_daily {
-                    return false;
-                }
-                
-                self.daily_deposits.set(user, (current_time, new_daily));
-                true
-            },
-            None => {
-                // First deposit for user
-                self.daily_deposits.set(user, (current_time, amount));
-                true
+        let mut records = Vec::new();
+        for id in ((last_id - window + 1)..=last_id).rev() {
+            if let Some(record) = self.get_operation(id) {
+                records.push(record);
             }
         }
+        records
     }
 
-    /// Update user deposit tracking for fee calculations
-    fn update_user_deposit_tracking(&mut self, user: &Address, amount: U512, shares: U512) {
-        let current_time = self.env().get_block_time();
-        
-        let cost_basis = self.user_cost_basis.get(user).unwrap_or(U512::zero());
-        let total_deposited = self.user_total_deposited.get(user).unwrap_or(U512::zero());
-        let total_shares = self.user_total_shares.get(user).unwrap_or(U512::zero());
-        
-        let new_cost_basis = cost_basis.checked_add(amount).unwrap();
-        let new_total_deposited = total_deposited.checked_add(amount).unwrap();
-        let new_total_shares = total_shares.checked_add(shares).unwrap();
-        
-        self.user_cost_basis.set(user, new_cost_basis);
-        self.user_total_deposited.set(user, new_total_deposited);
-        self.user_total_shares.set(user, new_total_shares);
-        self.user_last_deposit_time.set(user, current_time);
+    /// Wired LiquidStaking contract (the address set post-deploy wins over the init one)
+    pub fn get_migration_target(&self) -> Option<Address> {
+        self.migration_target.get()
     }
 
+    pub fn get_migration_source(&self) -> Option<Address> {
+        self.migration_source.get()
+    }
 
-    /// Update contract addresses (admin only)
-    pub fn set_liquid_staking(&mut self, address: Address) {
-        self.access_control.only_admin();
-        self.liquid_staking_address.set(address);
+    /// When the admin migration batch opens (0 while migration is disabled)
+    pub fn get_migration_batch_open_time(&self) -> u64 {
+        match self.migration_enabled_at.get() {
+            Some(enabled_at) => enabled_at + self.migration_grace_period.get_or_default(),
+            None => 0,
+        }
     }
 
-    pub fn set_strategy_router(&mut self, address: Address) {
-        self.access_control.only_admin();
-        self.strategy_router_address.set(address);
+    pub fn get_liquid_staking(&self) -> Option<Address> {
+        self.liquid_staking_address.get().or_else(|| self.liquid_staking_contract.get())
     }
 
-    pub fn set_cv_cspr_token(&mut self, address: Address) {
-        self.access_control.only_admin();
-        self.cv_cspr_token_address.set(address);
+    pub fn get_strategy_router(&self) -> Option<Address> {
+        self.strategy_router_address.get()
     }
 
-    /// Update instant pool target (admin only)
-    pub fn set_instant_pool_target(&mut self, target_bps: u32) {
-        self.access_control.only_admin();
-        
-        // Validate: max 50% (5000 bps)
-        if target_bps > 5000 {
-            self.env().revert(VaultError::Unauthorized);
-        }
-        
-        self.instant_pool_target_bps.set(target_bps);
+    pub fn get_yield_aggregator(&self) -> Option<Address> {
+        self.yield_aggregator_address.get()
     }
 
-    /// Update deposit limits (admin only)
-    pub fn update_deposit_limits(&mut self, max_per_tx: U512, max_per_day: U512) {
-        self.access_control.only_admin();
-        
-        self.max_deposit_per_tx.set(max_per_tx);
-        self.max_deposit_per_day.set(max_per_day);
+    /// Current protocol treasury
+    pub fn get_treasury(&self) -> Option<Address> {
+        self.treasury.get()
     }
 
-    /// Update withdrawal timelock (admin only)
-    pub fn set_withdrawal_timelock(&mut self, timelock: u64) {
-        self.access_control.only_admin();
-        
-        // Minimum 1 day, maximum 30 days
-        if timelock < 86400 || timelock > 2592000 {
-            self.env().revert(VaultError::Unauthorized);
-        }
-        
-        self.withdrawal_timelock.set(timelock);
+    /// Wired cvCSPR token (the address set post-deploy wins over the init one)
+    pub fn get_cv_cspr_token(&self) -> Option<Address> {
+        self.cv_cspr_token_address.get().or_else(|| self.cv_cspr_token.get())
     }
 
-    /// Rescue stuck funds (admin only, emergency use)
-    pub fn rescue_funds(&mut self, token: Address, amount: U512, recipient: Address) {
-        self.access_control.only_admin();
-        
-        
-        self.env().emit_event(FundsRescued {
-            token,
-            amount,
-            recipient,
-        });
+    /// Underlying asset (ERC-4626 `asset`): the lstCSPR token
+    pub fn asset(&self) -> Address {
+        self.lst_cspr_token.get().unwrap()
     }
 
+    /// Decimals of the underlying asset (CSPR motes)
+    pub fn underlying_decimals(&self) -> u8 {
+        UNDERLYING_DECIMALS
+    }
 
-    pub fn get_user_shares(&self, user: Address) -> U512 {
-        self.user_shares.get(&user).unwrap_or_default()
+    /// Decimals of vault shares, which cvCSPR is checked to match
+    pub fn share_decimals(&self) -> u8 {
+        SHARE_DECIMALS
     }
 
-    pub fn get_user_assets(&self, user: Address) -> U512 {
-        let shares = self.get_user_shares(user);
-        self.convert_to_assets(shares)
+    /// ERC-4626 `decimals`: the share decimals
+    pub fn decimals(&self) -> u8 {
+        SHARE_DECIMALS
     }
 
-    pub fn get_withdrawal_request(&self, request_id: U256) -> Option<WithdrawalRequest> {
-        if let Some(user) = self.withdrawal_request_users.get(&request_id) {
-            let shares = self.withdrawal_request_shares.get(&request_id).unwrap_or(U512::zero());
-            let assets_value = self.withdrawal_request_assets.get(&request_id).unwrap_or(U512::zero());
-            let request_time = self.withdrawal_request_times.get(&request_id).unwrap_or(0);
-            let unlock_time = self.withdrawal_request_unlock_times.get(&request_id).unwrap_or(0);
-            let completed = self.withdrawal_request_completed.get(&request_id).unwrap_or(false);
-            
-            Some(WithdrawalRequest {
-                user,
-                shares,
-                assets_value,
-                request_time,
-                unlock_time,
-                completed,
-            })
+    /// cvCSPR name (empty until the token is wired)
+    pub fn name(&self) -> String {
+        self.share_token().map(|token| token.name()).unwrap_or_default()
+    }
+
+    /// cvCSPR symbol (empty until the token is wired)
+    pub fn symbol(&self) -> String {
+        self.share_token().map(|token| token.symbol()).unwrap_or_default()
+    }
+
+    pub fn has_role(&self, role: u8, account: Address) -> bool {
+        self.access_control.has_role(role, account)
+    }
+
+    // BUILD INFO
+
+    /// Crate version as (major, minor, patch)
+    pub fn get_version(&self) -> (u32, u32, u32) {
+        self.build_info.version()
+    }
+
+    /// Feature flags this deployment supports (see `VAULT_FEATURES`)
+    pub fn get_features(&self) -> Vec<String> {
+        self.build_info.features()
+    }
+
+    /// Git commit this deployment was built from, when known
+    pub fn get_git_hash(&self) -> Option<String> {
+        self.build_info.git_hash()
+    }
+
+    // TEST HOOKS (compiled out of production builds)
+
+    /// Overwrite total assets, bypassing staking and strategies
+    /// 
+    /// The difference is booked to deployed assets; the pool is left alone.
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_total_assets(&mut self, amount: U512) {
+        let current = self.total_assets.get_or_default();
+        self.total_assets.set(amount);
+        if amount > current {
+            self.deployed_assets.set(self.deployed_assets.get_or_default() + (amount - current));
         } else {
-            None
+            self.release_assets(current - amount);
         }
     }
 
-    pub fn get_instant_pool_balance(&self) -> U512 {
-        self.instant_withdrawal_pool.get_or_default()
+    /// Overwrite total shares without touching any balance
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_total_shares(&mut self, amount: U512) {
+        self.total_shares.set(amount);
     }
 
-    pub fn get_fees_collected(&self) -> U512 {
-        self.fees_collected.get_or_default()
+    /// Overwrite a user's share balance without touching total shares
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_user_shares(&mut self, user: Address, shares: U512) {
+        self.set_user_shares(&user, shares);
     }
 
-    pub fn get_share_price(&self) -> U512 {
-        // Price of 1 share in CSPR (scaled by 1e9)
-        let one_share = U512::from(1_000_000_000u64); // 1.0 with 9 decimals
-        self.convert_to_assets(one_share)
+    /// Overwrite the instant pool balance
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_instant_pool(&mut self, amount: U512) {
+        self.instant_withdrawal_pool.set(amount);
+    }
+
+    /// Overwrite the fees collected counter
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_set_fees_collected(&mut self, amount: U512) {
+        self.fees_collected.set(amount);
+    }
+
+    /// Add already-unlocked yield to total assets, raising the share price
+    /// 
+    /// The instant pool is left alone, as with yield earned in strategies.
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_add_yield(&mut self, amount: U512) {
+        let current = self.total_assets.get_or_default();
+        self.total_assets.set(current + amount);
+        self.deployed_assets.set(self.deployed_assets.get_or_default() + amount);
+    }
+
+    /// Writes counted towards share and deposit bookkeeping so far
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_bookkeeping_writes(&self) -> u32 {
+        self.bookkeeping_writes.get_or_default()
+    }
+
+    /// Restart the bookkeeping write count from zero
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_reset_bookkeeping_writes(&mut self) {
+        self.bookkeeping_writes.set(0);
     }
 }
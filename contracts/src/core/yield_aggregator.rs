@@ -7,8 +7,9 @@ use odra::prelude::*;
 use odra::{Address, Event, Mapping, SubModule, Var};
 use odra::casper_types::{U256, U512};
 use crate::types::*;
-use crate::utils::{AccessControl, ReentrancyGuard, Pausable};
-use crate::core::{LiquidStaking, StrategyRouter, VaultManager};
+use crate::utils::{accrue_apr, bps_of, feature_flags, net_apy_bps, ratio_bps, safe_u512_to_u256, AccessControl, BuildInfo, ReentrancyGuard, Pausable, Role};
+use crate::core::{LiquidStakingContractRef, StrategyRouterContractRef, VaultManagerContractRef};
+use crate::tokens::lst_cspr::LstCsprContractRef;
 
 /// Yield report from all sources
 #[derive(Debug, PartialEq, Eq, odra::OdraType)]
@@ -30,25 +31,150 @@ pub struct ApyDataPoint {
     pub total_assets: U512,
 }
 
+/// Headline protocol figures for dashboards and aggregators
+/// 
+/// Vault, staking and strategy fields read as zero when the vault address
+/// (or the vault's staking/router wiring) is not set.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct ProtocolStats {
+    pub total_assets: U512,
+    pub total_shares: U512,
+    pub share_price: U512,
+    pub unique_depositors: u64,
+    pub total_yields_harvested: U512,
+    pub current_apy: U256,
+    pub apy_7d: U256,
+    pub apy_30d: U256,
+    pub total_staked: U512,
+    /// `total_staked` split between the vault and direct stakers
+    pub vault_staked: U512,
+    pub direct_staked: U512,
+    pub staker_count: u64,
+    pub exchange_rate: U256,
+    pub active_validators: u32,
+    pub strategy_count: u32,
+    /// Recorded allocation across all strategies
+    pub strategy_balance: U512,
+    /// Vault performance + instant fees
+    pub vault_fees_collected: U512,
+    pub timestamp: u64,
+}
+
+/// How compounded yield is split, in lstCSPR
+/// 
+/// The community allocation is taken here; the rest is compounded into
+/// the vault, whose performance fee is charged on it when holders exit
+/// (projected at today's rate). Any `auto_compound` bounty comes out of
+/// `compounded` and is not shown.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct YieldSplit {
+    pub gross_yield: U512,
+    pub community_allocation: U512,
+    pub compounded: U512,
+    pub performance_fee: U512,
+    pub net_to_holders: U512,
+}
+
+/// What `auto_compound` would do if called now (`dry_run_compound`)
+/// 
+/// Figures come from the same split, circuit breaker and drip math as the
+/// real compound and assume it goes through. Strategy reward tokens are
+/// left out, as in `StrategyRouter::preview_harvest_all`.
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct CompoundPreview {
+    /// `false` if `auto_compound` would revert: paused, within
+    /// `min_compound_interval` or below `min_yield_threshold`
+    pub would_compound: bool,
+    pub staking_yield: U512,
+    /// Harvest per strategy, in router order
+    pub strategy_yields: Vec<(String, U512)>,
+    /// Per-strategy harvests plus the router's liquidation proceeds
+    pub strategy_yield: U512,
+    pub total_yield: U512,
+    pub change_bps: U512,
+    /// `total_yield` would be parked in pending gains instead of applied
+    pub circuit_breaker_trips: bool,
+    /// Pending gains the compound's drip would release
+    pub released_gains: U512,
+    pub community_allocation: U512,
+    /// Paid to the caller in vault shares
+    pub bounty: U512,
+    /// Profit reported to the vault, released gains included
+    pub reported_profit: U512,
+    /// Vault performance fee on `reported_profit`, charged when holders
+    /// exit (projected at today's rate)
+    pub performance_fee: U512,
+    pub total_assets: U512,
+    pub share_price: U512,
+}
+
+/// Most of the yield the community pool may take (10%)
+pub const MAX_COMMUNITY_POOL_BPS: u32 = 1000;
+
+/// Cap on the vault's performance fee plus the community pool share (50%)
+pub const MAX_COMBINED_YIELD_TAKE_BPS: u32 = 5000;
+
+/// Feature flags reported by `get_features`, one per shipped capability
+pub const AGGREGATOR_FEATURES: &[&str] = &[
+    "auto_compound",
+    "compound_bounty",
+    "community_pool",
+    "yield_destination",
+    "gain_smoothing",
+    "yield_split_preview",
+    "net_apy",
+    "compound_dry_run",
+];
+
+/// `source` the aggregator passes to `VaultManager::report_yield`
+pub const YIELD_SOURCE_COMPOUND: &str = "compound";
+pub const YIELD_SOURCE_RELEASED_GAINS: &str = "released_gains";
+
+/// Where `compound` sends the yield it applies
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YieldDestination {
+    /// Re-deployed to strategies through the router's `allocate`
+    Reallocate = 0,
+    /// Kept liquid in the vault's instant withdrawal pool
+    InstantPool = 1,
+}
+
+impl YieldDestination {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(YieldDestination::Reallocate),
+            1 => Some(YieldDestination::InstantPool),
+            _ => None,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+}
+
 #[odra::module]
 pub struct YieldAggregator {
     /// Access control for admin/operator functions
     access_control: SubModule<AccessControl>,
     
+    /// Version and feature flags for front-ends
+    build_info: SubModule<BuildInfo>,
+    
     /// Reentrancy protection
     reentrancy_guard: SubModule<ReentrancyGuard>,
     
     /// Emergency pause
     pausable: SubModule<Pausable>,
     
-    /// Reference to liquid staking contract
-    liquid_staking: SubModule<LiquidStaking>,
-    
-    /// Reference to strategy router
-    strategy_router: SubModule<StrategyRouter>,
+    /// Deployed VaultManager that receives compounded profit (needs operator role)
+    /// 
+    /// Its LiquidStaking and StrategyRouter are the yield sources; the
+    /// aggregator needs a maintainer role on both.
+    vault_address: Var<Address>,
     
-    /// Reference to vault manager
-    vault_manager: SubModule<VaultManager>,
+    /// `YieldDestination` (as u8) of compounded yield
+    yield_destination: Var<u8>,  // Default: Reallocate
     
     /// Last compound timestamp
     last_compound_time: Var<u64>,
@@ -59,18 +185,27 @@ pub struct YieldAggregator {
     /// Minimum yield threshold for compounding (100 CSPR)
     min_yield_threshold: Var<U512>,
     
+    /// Share of the yield paid to whoever triggers `auto_compound` (basis points)
+    compound_bounty_bps: Var<u32>,  // Default: 0 (no bounty)
+    
+    /// Share of the yield set aside for liquidity incentives (basis points)
+    community_pool_bps: Var<u32>,  // Default: 0
+    
+    /// Community allocations not yet claimed
+    community_pool: Var<U512>,
+    
+    /// Lifetime community funds claimed
+    community_funds_claimed: Var<U512>,
+    
+    /// Only account that may claim from the community pool
+    incentives_distributor: Var<Address>,
+    
     /// Total yields harvested all-time
     total_yields_harvested: Var<U512>,
     
-    /// Performance fee percentage (10% = 1000 basis points)
-    performance_fee_bps: Var<u32>,
-    
     /// Management fee percentage (2% annual = 200 basis points)
     management_fee_bps: Var<u32>,
     
-    /// Accumulated fees pending withdrawal
-    accumulated_fees: Var<U512>,
-    
     /// Fee recipient address
     fee_recipient: Var<Address>,
     
@@ -94,6 +229,15 @@ pub struct YieldAggregator {
     
     /// Share price history
     share_price_history: Mapping<u64, U256>,
+    
+    /// Max share price change a single compound may cause (basis points)
+    max_price_change_bps: Var<u32>,
+    
+    /// Gross yield held back by the circuit breaker
+    pending_gains: Var<U512>,
+    
+    /// Remaining compounds over which released gains are applied
+    gain_drips_remaining: Var<u32>,
 }
 
 #[odra::module]
@@ -105,23 +249,27 @@ impl YieldAggregator {
         fee_recipient: Address,
     ) {
         self.access_control.init(admin);
+        self.build_info.init(feature_flags(AGGREGATOR_FEATURES));
         
         self.min_compound_interval.set(3600); // 1 hour
         self.min_yield_threshold.set(U512::from(100_000_000_000u64)); // 100 CSPR (9 decimals)
-        self.performance_fee_bps.set(1000); // 10%
         self.management_fee_bps.set(200); // 2%
         self.fee_recipient.set(fee_recipient);
         self.last_compound_time.set(0);
         self.total_yields_harvested.set(U512::zero());
-        self.accumulated_fees.set(U512::zero());
         self.report_count.set(0);
         self.apy_count.set(0);
+        self.max_price_change_bps.set(200); // 2%
+        self.pending_gains.set(U512::zero());
+        self.gain_drips_remaining.set(0);
+        self.community_pool.set(U512::zero());
+        self.community_funds_claimed.set(U512::zero());
     }
     
     /// Aggregate yields from all sources
-    /// Callable by operator role or keeper
+    /// Callable by admin, operator or keeper
     pub fn aggregate_yields(&mut self) -> YieldReport {
-        if !self.access_control.has_role(1, self.env().caller()) {
+        if !self.access_control.is_maintainer(self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         
@@ -129,13 +277,31 @@ impl YieldAggregator {
             self.env().revert(VaultError::ContractPaused);
         }
         
+        self.harvest_yields()
+    }
+    
+    /// Harvest staking and strategy yield and record the report
+    fn harvest_yields(&mut self) -> YieldReport {
         let timestamp = self.env().get_block_time();
         
-        // Harvest staking rewards
-        let staking_yield = self.liquid_staking.compound_rewards();
+        // Harvest staking rewards when due (compounding early would revert)
+        let staking_yield = match self.staking() {
+            Some(mut staking) if staking.should_compound() => staking.compound_rewards(),
+            _ => U512::zero(),
+        };
         
-        // Harvest from all strategies
-        let strategy_yields = self.strategy_router.harvest_all();
+        // Harvest from all strategies (lstCSPR only; escrowed reward tokens
+        // count once the router liquidates them). The router forwards the
+        // yield here; it is no longer held by the strategies.
+        let strategy_yields = match self.router() {
+            Some(mut router) => {
+                let holdings = self.lst_holdings();
+                let harvested = router.harvest_all();
+                self.forward_to_vault(holdings);
+                harvested
+            }
+            None => U512::zero(),
+        };
         
         // For now, assume strategy_yields is total from all strategies
         // In full implementation, break down by strategy type
@@ -182,8 +348,13 @@ impl YieldAggregator {
     }
     
     /// Compound yields back into the vault
+    /// Callable by admin or operator; the amount is taken on trust, so
+    /// keepers compound through `auto_compound` instead
     pub fn compound(&mut self, yield_amount: U512) {
-        if !self.access_control.has_role(1, self.env().caller()) {
+        let caller = self.env().caller();
+        if !self.access_control.has_role(Role::Admin.to_u8(), caller)
+            && !self.access_control.has_role(Role::Operator.to_u8(), caller)
+        {
             self.env().revert(VaultError::Unauthorized);
         }
         
@@ -191,6 +362,14 @@ impl YieldAggregator {
             self.env().revert(VaultError::ContractPaused);
         }
         
+        self.compound_yield(yield_amount, None);
+    }
+    
+    /// Apply `yield_amount` behind the threshold, interval and circuit breaker guards
+    /// 
+    /// `bounty_to` is paid the compound bounty out of the yield if it is
+    /// applied now; yield parked by the circuit breaker pays no bounty.
+    fn compound_yield(&mut self, yield_amount: U512, bounty_to: Option<Address>) {
         let min_threshold = self.min_yield_threshold.get_or_default();
         if yield_amount < min_threshold {
             self.env().revert(VaultError::AmountTooLow);
@@ -204,71 +383,351 @@ impl YieldAggregator {
             self.env().revert(VaultError::TooSoon);
         }
         
-        let performance_fee = self.calculate_performance_fee(yield_amount);
-        let net_yield = yield_amount - performance_fee;
+        // Circuit breaker: park yield that would move the share price too far
+        let (change_bps, trips) = self.price_change(yield_amount);
         
-        // Accumulate fees
-        let current_fees = self.accumulated_fees.get_or_default();
-        self.accumulated_fees.set(current_fees + performance_fee);
+        if trips {
+            let pending = self.pending_gains.get_or_default();
+            self.pending_gains.set(pending + yield_amount);
+            
+            self.env().emit_event(CircuitBreakerTripped {
+                amount: yield_amount,
+                change_bps: change_bps.min(U512::from(u32::MAX)).as_u32(),
+                max_change_bps: self.max_price_change_bps.get_or_default(),
+                timestamp: current_time,
+            });
+        } else {
+            self.apply_yield(yield_amount, YIELD_SOURCE_COMPOUND, bounty_to);
+        }
         
-        // Deploy net yield to strategies
-        // For MVP, we simulate by calling allocate
-        self.strategy_router.allocate(net_yield);
+        self.drip_pending_gains();
+        
+        self.last_compound_time.set(current_time);
+    }
+    
+    /// Release yield held back by the circuit breaker (admin only)
+    /// 
+    /// `drips` spreads the release over that many compounds, the first
+    /// tranche being applied immediately; 0 or 1 applies everything now.
+    pub fn release_pending_gains(&mut self, drips: u32) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        
+        if self.pending_gains.get_or_default().is_zero() {
+            self.env().revert(VaultError::ConditionsNotMet);
+        }
+        
+        self.gain_drips_remaining.set(drips.max(1));
+        self.drip_pending_gains();
+    }
+    
+    /// Get yield held back by the circuit breaker
+    pub fn get_pending_gains(&self) -> U512 {
+        self.pending_gains.get_or_default()
+    }
+    
+    /// Send the yield to its destination and report it to the vault in full
+    /// 
+    /// With `Reallocate` the router deploys it like a deposit; whatever it
+    /// can't place (no strategy with room) goes to the instant pool along
+    /// with everything under `InstantPool`. The router's total allocated
+    /// must grow by no more than was handed to it, so yield it already
+    /// counted can't be booked twice.
+    /// 
+    /// No fee is taken here: the performance fee crystallizes once, at the
+    /// vault, against each depositor's cost basis when they withdraw.
+    /// Charging it on compound as well would bill the same profit twice.
+    /// 
+    /// Nothing is transferred here: harvested lstCSPR has already been
+    /// passed on to the vault, which custodies it for the router, so the
+    /// aggregator only routes the claim on it.
+    /// 
+    /// The community pool's share is kept here first. A bounty for
+    /// `bounty_to` is carved out of the rest and paid by the vault in
+    /// shares; what remains is reported as profit.
+    fn apply_yield(&mut self, yield_amount: U512, source: &str, bounty_to: Option<Address>) {
+        let (community_allocation, compounded, bounty) = self.split_yield(yield_amount, bounty_to.is_some());
+        let mut reallocated = U512::zero();
+        let mut to_instant_pool = compounded;
+        
+        if let (YieldDestination::Reallocate, Some(mut router)) = (self.yield_destination_in_force(), self.router()) {
+            let allocated_before = router.get_total_allocated();
+            let unplaced = router.allocate(compounded);
+            reallocated = router.get_total_allocated().saturating_sub(allocated_before);
+            if reallocated + unplaced > compounded {
+                self.env().revert(StrategyError::InvalidAllocation);
+            }
+            to_instant_pool = unplaced;
+        }
+        
+        // Report the profit to the vault in this same transaction; it
+        // unlocks into the share price linearly instead of all at once
+        if let (Some(mut vault), false) = (self.vault(), compounded.is_zero()) {
+            if !community_allocation.is_zero() {
+                self.community_pool.set(self.community_pool.get_or_default() + community_allocation);
+                vault.record_community_allocation(community_allocation);
+            }
+            vault.report_yield(compounded - bounty, String::from(source));
+            if let (Some(recipient), false) = (bounty_to, bounty.is_zero()) {
+                vault.pay_compound_bounty(recipient, bounty);
+            }
+            if !to_instant_pool.is_zero() {
+                vault.credit_instant_pool(to_instant_pool);
+            }
+        }
         
         // The vault's total_assets will increase, raising share price
         self.update_share_price();
         
-        self.last_compound_time.set(current_time);
-        
         self.env().emit_event(YieldCompounded {
-            gross_yield: yield_amount,
-            net_yield,
-            fees: performance_fee,
-            timestamp: current_time,
+            amount: yield_amount,
+            reallocated,
+            to_instant_pool,
+            community_allocation,
+            bounty,
+            timestamp: self.env().get_block_time(),
         });
     }
     
-    /// Auto-compound: harvest and compound in one transaction
-    pub fn auto_compound(&mut self) -> U512 {
-        if !self.should_compound() {
-            self.env().revert(VaultError::ConditionsNotMet);
+    /// Apply the next tranche of released pending gains
+    fn drip_pending_gains(&mut self) {
+        let remaining = self.gain_drips_remaining.get_or_default();
+        if remaining == 0 {
+            return;
+        }
+        
+        let pending = self.pending_gains.get_or_default();
+        let tranche = self.drip_tranche(U512::zero());
+        self.pending_gains.set(pending - tranche);
+        self.gain_drips_remaining.set(remaining - 1);
+        
+        if !tranche.is_zero() {
+            self.apply_yield(tranche, YIELD_SOURCE_RELEASED_GAINS, None);
+        }
+    }
+    
+    /// Community allocation, compounded part and bounty of `yield_amount`
+    /// 
+    /// The split `apply_yield` makes; without a vault nothing is taken.
+    fn split_yield(&self, yield_amount: U512, with_bounty: bool) -> (U512, U512, U512) {
+        if self.vault_address.get().is_none() {
+            return (U512::zero(), yield_amount, U512::zero());
+        }
+        
+        let community_allocation = bps_of(yield_amount, self.community_pool_bps.get_or_default());
+        let compounded = yield_amount - community_allocation;
+        let bounty = if with_bounty {
+            bps_of(compounded, self.compound_bounty_bps.get_or_default())
+        } else {
+            U512::zero()
+        };
+        (community_allocation, compounded, bounty)
+    }
+    
+    /// Share price move of `yield_amount` in bps, and whether it trips
+    /// the circuit breaker
+    fn price_change(&self, yield_amount: U512) -> (U512, bool) {
+        let total_assets = self.vault().map_or(U512::zero(), |vault| vault.total_assets());
+        let change_bps = if total_assets.is_zero() {
+            U512::zero()
+        } else {
+            ratio_bps(yield_amount, total_assets).unwrap_or_revert(&self.env())
+        };
+        (change_bps, change_bps > U512::from(self.max_price_change_bps.get_or_default()))
+    }
+    
+    /// Tranche the next drip releases once `parked` more is pending
+    fn drip_tranche(&self, parked: U512) -> U512 {
+        match self.gain_drips_remaining.get_or_default() {
+            0 => U512::zero(),
+            remaining => (self.pending_gains.get_or_default() + parked) / U512::from(remaining),
         }
+    }
+    
+    /// Auto-compound: harvest and compound in one transaction (anyone)
+    /// 
+    /// Guarded by economics instead of a role, so compounding doesn't stop
+    /// when the team's keeper does. Reverts with `TooSoon` within
+    /// `min_compound_interval` of the last compound and with `AmountTooLow`
+    /// when the harvest comes to less than `min_yield_threshold`, which
+    /// rolls the harvest back too. The circuit breaker applies as in
+    /// `compound`. With a bounty set, the caller is paid
+    /// `compound_bounty_bps` of the yield in vault shares.
+    /// 
+    /// Griefing: a caller can only compound once the same guards the keeper
+    /// faces are met, so the worst case is compounding slightly earlier
+    /// than the keeper would have. That books yield for holders sooner and
+    /// costs them nothing beyond the capped bounty. Parked yield pays no
+    /// bounty, so tripping the circuit breaker earns nothing.
+    pub fn auto_compound(&mut self) -> U512 {
+        self.reentrancy_guard.enter();
         
-        // Aggregate yields
-        let report = self.aggregate_yields();
+        if self.pausable.is_paused() {
+            self.env().revert(VaultError::ContractPaused);
+        }
         
-        // Compound the yields
-        if report.total_yield > U512::zero() {
-            self.compound(report.total_yield);
+        // Checked before harvesting, which would pull rewards for nothing
+        let last_time = self.last_compound_time.get_or_default();
+        if self.env().get_block_time() < last_time + self.min_compound_interval.get_or_default() {
+            self.env().revert(VaultError::TooSoon);
         }
         
+        let report = self.harvest_yields();
+        self.compound_yield(report.total_yield, Some(self.env().caller()));
+        
+        self.reentrancy_guard.exit();
         report.total_yield
     }
     
-    /// Calculate performance fee (10% of profits)
-    fn calculate_performance_fee(&self, profit: U512) -> U512 {
-        let fee_bps = self.performance_fee_bps.get_or_default();
-        let fee_u512 = U512::from(fee_bps);
-        profit * fee_u512 / U512::from(10000u64)
+    /// What `auto_compound` would harvest, split and book if called now
+    /// 
+    /// Read-only: nothing is harvested or stored. Uses the strategies'
+    /// `preview_harvest` and the staking rewards `compound_rewards` would
+    /// restake, then the same split, circuit breaker and drip as
+    /// `compound`, so a compound in the same block lands on these figures
+    /// (reward tokens and adapter-claimed staking rewards aside).
+    pub fn dry_run_compound(&self) -> CompoundPreview {
+        let staking_yield = match self.staking() {
+            Some(staking) if staking.should_compound() => staking.preview_compound_rewards(),
+            _ => U512::zero(),
+        };
+        let (strategy_yields, strategy_yield) = match self.router() {
+            Some(router) => {
+                let yields = router.preview_harvest_all();
+                let total = yields.iter().fold(router.get_reward_proceeds(), |sum, (_, amount)| sum + *amount);
+                (yields, total)
+            }
+            None => (Vec::new(), U512::zero()),
+        };
+        let total_yield = staking_yield + strategy_yield;
+        
+        let last_time = self.last_compound_time.get_or_default();
+        let would_compound = !self.pausable.is_paused()
+            && self.env().get_block_time() >= last_time + self.min_compound_interval.get_or_default()
+            && total_yield >= self.min_yield_threshold.get_or_default();
+        
+        // (profit, bounty) per report, in the order `apply_yield` makes them
+        let mut reports = Vec::new();
+        let mut community_allocation = U512::zero();
+        let mut bounty = U512::zero();
+        let mut reported_profit = U512::zero();
+        
+        let (change_bps, circuit_breaker_trips) = self.price_change(total_yield);
+        let parked = if circuit_breaker_trips { total_yield } else { U512::zero() };
+        let released_gains = self.drip_tranche(parked);
+        let applied = [
+            (if circuit_breaker_trips { U512::zero() } else { total_yield }, true),
+            (released_gains, false),
+        ];
+        for (amount, with_bounty) in applied {
+            let (community, compounded, paid) = self.split_yield(amount, with_bounty);
+            if compounded.is_zero() {
+                continue;
+            }
+            community_allocation += community;
+            bounty += paid;
+            reported_profit += compounded - paid;
+            reports.push((compounded - paid, paid));
+        }
+        
+        let (total_assets, share_price) = match self.vault() {
+            Some(vault) => vault.preview_report_yield(reports),
+            None => (U512::zero(), U512::zero()),
+        };
+        let performance_fee = bps_of(reported_profit, self.vault().map_or(0, |vault| vault.get_fees().0));
+        
+        CompoundPreview {
+            would_compound,
+            staking_yield,
+            strategy_yields,
+            strategy_yield,
+            total_yield,
+            change_bps,
+            circuit_breaker_trips,
+            released_gains,
+            community_allocation,
+            bounty,
+            reported_profit,
+            performance_fee,
+            total_assets,
+            share_price,
+        }
+    }
+    
+    /// Emit `ConfigChanged` for one of the aggregator's parameters
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "YieldAggregator".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// The vault at `vault_address`, if set
+    fn vault(&self) -> Option<VaultManagerContractRef> {
+        self.vault_address.get().map(|vault| VaultManagerContractRef::new(self.env(), vault))
+    }
+    
+    /// The router the vault deploys through
+    fn router(&self) -> Option<StrategyRouterContractRef> {
+        let router = self.vault()?.get_strategy_router()?;
+        Some(StrategyRouterContractRef::new(self.env(), router))
+    }
+    
+    /// The vault's lstCSPR token and the aggregator's balance of it,
+    /// unless the token is a placeholder account
+    fn lst_holdings(&self) -> Option<(Address, U512)> {
+        let token = self.vault()?.asset();
+        if !token.is_contract() {
+            return None;
+        }
+        Some((token, LstCsprContractRef::new(self.env(), token).balance_of(self.env().self_address())))
+    }
+    
+    /// Send the vault the lstCSPR received since `holdings` were taken
+    fn forward_to_vault(&mut self, holdings: Option<(Address, U512)>) {
+        let (token, balance_before) = match holdings {
+            Some(holdings) => holdings,
+            None => return,
+        };
+        let mut token = LstCsprContractRef::new(self.env(), token);
+        let received = token.balance_of(self.env().self_address()).saturating_sub(balance_before);
+        if let Some(vault) = self.vault_address.get().filter(|_| !received.is_zero()) {
+            token.transfer(vault, received);
+        }
+    }
+    
+    /// The vault's LiquidStaking, unless it is a placeholder account
+    fn staking(&self) -> Option<LiquidStakingContractRef> {
+        let staking = self.vault()?.get_liquid_staking().filter(|staking| staking.is_contract())?;
+        Some(LiquidStakingContractRef::new(self.env(), staking))
+    }
+    
+    fn yield_destination_in_force(&self) -> YieldDestination {
+        YieldDestination::from_u8(self.yield_destination.get_or_default()).unwrap_or(YieldDestination::Reallocate)
     }
     
     /// Calculate management fee (2% annual, prorated)
     pub fn calculate_management_fee(&self, total_assets: U512, days_elapsed: u64) -> U512 {
         let fee_bps = self.management_fee_bps.get_or_default();
-        let annual_fee = total_assets * U512::from(fee_bps) / U512::from(10000u64);
-        let daily_fee = annual_fee / U512::from(365u64);
-        daily_fee * U512::from(days_elapsed)
+        accrue_apr(total_assets, fee_bps, days_elapsed * 86400).unwrap_or_revert(&self.env())
     }
     
     /// Update share price based on new total assets
     fn update_share_price(&mut self) {
         let timestamp = self.env().get_block_time();
-        let share_price = self.vault_manager.get_share_price();
-        let share_price_u256 = U256::from(share_price.as_u128());
+        let (share_price, total_assets) = match self.vault() {
+            Some(vault) => (vault.get_share_price(), vault.total_assets()),
+            None => (U512::zero(), U512::zero()),
+        };
+        let share_price_u256 = safe_u512_to_u256(share_price).unwrap_or_revert(&self.env());
         
         self.share_price_history.set(&timestamp, share_price_u256);
         
-        let total_assets = self.vault_manager.total_assets();
         let apy = self.calculate_current_apy();
         
         let data_point = ApyDataPoint {
@@ -294,20 +753,20 @@ impl YieldAggregator {
         let staking_apy = U256::from(800u64); // 8.00% in basis points
         
         // Get blended strategy APY
-        let strategy_apy = self.strategy_router.calculate_blended_apy();
+        let strategy_apy = self.router().map_or(U256::zero(), |router| router.calculate_blended_apy());
         
         // Combine APYs (simplified: average for MVP)
         (staking_apy + strategy_apy) / U256::from(2u64)
     }
     
     /// Get historical APY over a period
+    /// 
+    /// History is appended in time order, so this walks back from the
+    /// newest point and stops at the first one outside the period. Points
+    /// are at least `min_compound_interval` apart, which bounds the walk.
     pub fn get_historical_apy(&self, period_seconds: u64) -> U256 {
         let current_time = self.env().get_block_time();
-        let start_time = if current_time > period_seconds {
-            current_time - period_seconds
-        } else {
-            0
-        };
+        let start_time = current_time.saturating_sub(period_seconds);
         
         let count = self.apy_count.get_or_default();
         if count == 0 {
@@ -318,12 +777,13 @@ impl YieldAggregator {
         let mut sum_apy = U256::zero();
         let mut data_points = 0u64;
         
-        for i in 0..count {
-            if let Some(data_point) = self.apy_history.get(&i) {
-                if data_point.timestamp >= start_time {
+        for i in (0..count).rev() {
+            match self.apy_history.get(&i) {
+                Some(data_point) if data_point.timestamp >= start_time => {
                     sum_apy = sum_apy + data_point.apy;
                     data_points += 1;
                 }
+                _ => break,
             }
         }
         
@@ -334,6 +794,19 @@ impl YieldAggregator {
         }
     }
     
+    /// Current APY after the vault's fees (bps)
+    /// 
+    /// `gross * (10000 - performance_bps) / 10000 - management_bps`, where
+    /// `gross` is the blended staking and strategy APY compounds record in
+    /// the history and the fees are the vault's. The performance fee only
+    /// takes a share of the yield, while the management fee is charged on
+    /// the whole position, so it comes off in full. Zero if the management
+    /// fee exceeds the yield left after the performance fee.
+    pub fn get_net_apy(&self) -> U256 {
+        let (performance_bps, management_bps, _) = self.vault().map_or((0, 0, 0), |vault| vault.get_fees());
+        net_apy_bps(self.calculate_current_apy(), performance_bps, management_bps).unwrap_or_revert(&self.env())
+    }
+    
     /// Check if compounding should be triggered
     pub fn should_compound(&self) -> bool {
         let current_time = self.env().get_block_time();
@@ -355,35 +828,6 @@ impl YieldAggregator {
         U512::from(500_000_000_000u64) // 500K CSPR motes
     }
     
-    /// Distribute accumulated fees to treasury
-    pub fn distribute_fees(&mut self) {
-        // Only admin can distribute fees (role_id = 0)
-        if !self.access_control.has_role(0, self.env().caller()) {
-            self.env().revert(VaultError::Unauthorized);
-        }
-        
-        let fees = self.accumulated_fees.get_or_default();
-        if fees == U512::zero() {
-            self.env().revert(VaultError::NoFeesToDistribute);
-        }
-        
-        let recipient = self.fee_recipient.get().unwrap_or_else(|| self.env().caller());
-        
-        // For MVP, just reset accumulated fees
-        self.accumulated_fees.set(U512::zero());
-        
-        self.env().emit_event(FeesDistributed {
-            amount: fees,
-            recipient,
-            timestamp: self.env().get_block_time(),
-        });
-    }
-    
-    /// Get total fees accumulated
-    pub fn get_accumulated_fees(&self) -> U512 {
-        self.accumulated_fees.get_or_default()
-    }
-    
     /// Get latest yield report
     pub fn get_latest_yield_report(&self) -> Option<YieldReport> {
         let count = self.report_count.get_or_default();
@@ -424,6 +868,63 @@ impl YieldAggregator {
         self.report_count.get_or_default()
     }
     
+    /// Headline stats for the whole protocol in one call
+    /// 
+    /// Reads the vault at `vault_address` and the staking and router
+    /// contracts it is wired to. The blended APY walks the registered
+    /// strategies; every other figure is a stored counter.
+    pub fn get_protocol_stats(&self) -> ProtocolStats {
+        let mut stats = ProtocolStats {
+            total_assets: U512::zero(),
+            total_shares: U512::zero(),
+            share_price: U512::zero(),
+            unique_depositors: 0,
+            total_yields_harvested: self.total_yields_harvested.get_or_default(),
+            current_apy: U256::zero(),
+            apy_7d: self.get_historical_apy(7 * 86400),
+            apy_30d: self.get_historical_apy(30 * 86400),
+            total_staked: U512::zero(),
+            vault_staked: U512::zero(),
+            direct_staked: U512::zero(),
+            staker_count: 0,
+            exchange_rate: U256::zero(),
+            active_validators: 0,
+            strategy_count: 0,
+            strategy_balance: U512::zero(),
+            vault_fees_collected: U512::zero(),
+            timestamp: self.env().get_block_time(),
+        };
+        
+        let vault = match self.vault_address.get() {
+            Some(vault) => VaultManagerContractRef::new(self.env(), vault),
+            None => return stats,
+        };
+        
+        stats.total_assets = vault.total_assets();
+        stats.total_shares = vault.get_total_shares();
+        stats.share_price = vault.get_share_price();
+        stats.unique_depositors = vault.get_unique_depositors();
+        stats.vault_fees_collected = vault.get_fees_collected();
+        
+        if let Some(staking) = vault.get_liquid_staking() {
+            let staking = LiquidStakingContractRef::new(self.env(), staking);
+            stats.total_staked = staking.get_total_staked();
+            (stats.vault_staked, stats.direct_staked) = staking.get_stake_by_origin();
+            stats.staker_count = staking.get_staker_count();
+            stats.exchange_rate = staking.get_exchange_rate();
+            stats.active_validators = staking.get_active_validators().len() as u32;
+        }
+        
+        if let Some(router) = vault.get_strategy_router() {
+            let router = StrategyRouterContractRef::new(self.env(), router);
+            stats.current_apy = router.calculate_blended_apy();
+            stats.strategy_count = router.get_strategy_names().len() as u32;
+            stats.strategy_balance = router.get_total_allocated();
+        }
+        
+        stats
+    }
+    
     /// Get share price at a specific timestamp
     pub fn get_historical_share_price(&self, timestamp: u64) -> Option<U256> {
         self.share_price_history.get(&timestamp)
@@ -431,55 +932,215 @@ impl YieldAggregator {
     
     /// Admin: Set minimum compound interval
     pub fn set_min_compound_interval(&mut self, interval: u64) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
+        let old = self.min_compound_interval.get_or_default();
         self.min_compound_interval.set(interval);
+        self.config_changed("min_compound_interval", old, interval);
     }
     
     /// Admin: Set minimum yield threshold
     pub fn set_min_yield_threshold(&mut self, threshold: U512) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
+        let old = self.min_yield_threshold.get_or_default();
         self.min_yield_threshold.set(threshold);
+        self.config_changed("min_yield_threshold", old, threshold);
     }
     
-    /// Admin: Set performance fee
-    pub fn set_performance_fee(&mut self, fee_bps: u32) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+    /// Admin: Set the share of the yield paid to `auto_compound` callers
+    pub fn set_compound_bounty_bps(&mut self, bounty_bps: u32) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
-        // Max 20% performance fee
-        if fee_bps > 2000 {
+        // Max 1% of the yield
+        if bounty_bps > 100 {
             self.env().revert(VaultError::InvalidFee);
         }
-        self.performance_fee_bps.set(fee_bps);
+        let old = self.compound_bounty_bps.get_or_default();
+        self.compound_bounty_bps.set(bounty_bps);
+        self.config_changed("compound_bounty_bps", old, bounty_bps);
+    }
+    
+    /// Share of the yield paid to `auto_compound` callers (basis points)
+    pub fn get_compound_bounty_bps(&self) -> u32 {
+        self.compound_bounty_bps.get_or_default()
+    }
+    
+    /// Admin: Set the share of yield sent to the community pool
+    /// 
+    /// At most `MAX_COMMUNITY_POOL_BPS`, and together with the vault's
+    /// performance fee at most `MAX_COMBINED_YIELD_TAKE_BPS`.
+    pub fn set_community_pool_bps(&mut self, pool_bps: u32) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        let performance_bps = self.vault().map_or(0, |vault| vault.get_fees().0);
+        if pool_bps > MAX_COMMUNITY_POOL_BPS || performance_bps + pool_bps > MAX_COMBINED_YIELD_TAKE_BPS {
+            self.env().revert(VaultError::InvalidFee);
+        }
+        let old = self.community_pool_bps.get_or_default();
+        self.community_pool_bps.set(pool_bps);
+        self.config_changed("community_pool_bps", old, pool_bps);
+    }
+    
+    /// Share of yield sent to the community pool (basis points)
+    pub fn get_community_pool_bps(&self) -> u32 {
+        self.community_pool_bps.get_or_default()
+    }
+    
+    /// Admin: Set the account allowed to claim community funds
+    pub fn set_incentives_distributor(&mut self, distributor: Address) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        let old = config_address(self.incentives_distributor.get());
+        self.incentives_distributor.set(distributor);
+        self.config_changed("incentives_distributor", old, config_address(Some(distributor)));
+    }
+    
+    pub fn get_incentives_distributor(&self) -> Option<Address> {
+        self.incentives_distributor.get()
+    }
+    
+    /// Take `amount` out of the community pool (incentives distributor only)
+    pub fn claim_community_funds(&mut self, amount: U512) {
+        let distributor = self.env().caller();
+        if self.incentives_distributor.get() != Some(distributor) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+        
+        let pool = self.community_pool.get_or_default();
+        if amount > pool {
+            self.env().revert(VaultError::InsufficientBalance);
+        }
+        self.community_pool.set(pool - amount);
+        self.community_funds_claimed.set(self.community_funds_claimed.get_or_default() + amount);
+        
+        self.env().emit_event(CommunityFundsClaimed {
+            distributor,
+            amount,
+            remaining: pool - amount,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    /// Community allocations waiting to be claimed
+    pub fn get_community_pool(&self) -> U512 {
+        self.community_pool.get_or_default()
+    }
+    
+    pub fn get_community_funds_claimed(&self) -> U512 {
+        self.community_funds_claimed.get_or_default()
+    }
+    
+    /// How compounding `gross_yield` would be split at current rates
+    pub fn preview_yield_split(&self, gross_yield: U512) -> YieldSplit {
+        let community_allocation = bps_of(gross_yield, self.community_pool_bps.get_or_default());
+        let compounded = gross_yield - community_allocation;
+        let performance_fee = bps_of(compounded, self.vault().map_or(0, |vault| vault.get_fees().0));
+        YieldSplit {
+            gross_yield,
+            community_allocation,
+            compounded,
+            performance_fee,
+            net_to_holders: compounded - performance_fee,
+        }
     }
     
     /// Admin: Set management fee
     pub fn set_management_fee(&mut self, fee_bps: u32) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         // Max 5% annual management fee
         if fee_bps > 500 {
             self.env().revert(VaultError::InvalidFee);
         }
+        let old = self.management_fee_bps.get_or_default();
         self.management_fee_bps.set(fee_bps);
+        self.config_changed("management_fee_bps", old, fee_bps);
+    }
+    
+    /// Admin: Set circuit breaker threshold
+    pub fn set_max_price_change_bps(&mut self, max_change_bps: u32) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if max_change_bps == 0 || max_change_bps > 10000 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let old = self.max_price_change_bps.get_or_default();
+        self.max_price_change_bps.set(max_change_bps);
+        self.config_changed("max_price_change_bps", old, max_change_bps);
+    }
+    
+    /// Admin: Set the VaultManager that receives compounded profit
+    pub fn set_vault_address(&mut self, vault: Address) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        let old = config_address(self.vault_address.get());
+        self.vault_address.set(vault);
+        self.config_changed("vault_address", old, config_address(Some(vault)));
+    }
+
+    /// Get the VaultManager that receives compounded profit
+    pub fn get_vault_address(&self) -> Option<Address> {
+        self.vault_address.get()
+    }
+    
+    /// Admin: Set where compounded yield goes (`YieldDestination` as u8)
+    pub fn set_yield_destination(&mut self, destination: u8) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
+            self.env().revert(VaultError::Unauthorized);
+        }
+        if YieldDestination::from_u8(destination).is_none() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        let old = self.yield_destination.get_or_default();
+        self.yield_destination.set(destination);
+        self.config_changed("yield_destination", old, destination);
+    }
+    
+    /// Where compounded yield goes (`YieldDestination` as u8)
+    pub fn get_yield_destination(&self) -> u8 {
+        self.yield_destination_in_force().to_u8()
+    }
+    
+    /// Grant a role on the aggregator (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+    
+    /// Revoke a role on the aggregator (admin only)
+    pub fn revoke_role(&mut self, role: u8, account: Address) {
+        self.access_control.revoke_role(role, account);
+    }
+    
+    /// Current members of a role
+    pub fn get_role_members(&self, role: u8) -> Vec<Address> {
+        self.access_control.get_role_members(role)
     }
     
     /// Admin: Set fee recipient
     pub fn set_fee_recipient(&mut self, recipient: Address) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
+        let old = config_address(self.fee_recipient.get());
         self.fee_recipient.set(recipient);
+        self.config_changed("fee_recipient", old, config_address(Some(recipient)));
     }
     
     /// Emergency: Pause compounding
     pub fn pause(&mut self) {
-        if !self.access_control.has_role(2, self.env().caller()) {
+        if !self.access_control.has_role(Role::Guardian.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         self.pausable.pause();
@@ -487,11 +1148,28 @@ impl YieldAggregator {
     
     /// Emergency: Unpause compounding
     pub fn unpause(&mut self) {
-        if !self.access_control.has_role(0, self.env().caller()) {
+        if !self.access_control.has_role(Role::Admin.to_u8(), self.env().caller()) {
             self.env().revert(VaultError::Unauthorized);
         }
         self.pausable.unpause();
     }
+    
+    // BUILD INFO
+    
+    /// Crate version as (major, minor, patch)
+    pub fn get_version(&self) -> (u32, u32, u32) {
+        self.build_info.version()
+    }
+    
+    /// Feature flags this deployment supports (see `AGGREGATOR_FEATURES`)
+    pub fn get_features(&self) -> Vec<String> {
+        self.build_info.features()
+    }
+    
+    /// Git commit this deployment was built from, when known
+    pub fn get_git_hash(&self) -> Option<String> {
+        self.build_info.git_hash()
+    }
 }
 
 #[derive(Event, Debug, PartialEq, Eq)]
@@ -504,9 +1182,25 @@ pub struct YieldHarvested {
 
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct YieldCompounded {
-    pub gross_yield: U512,
-    pub net_yield: U512,
-    pub fees: U512,
+    /// Full yield applied: community allocation, profit reported to the
+    /// vault and any bounty (fees are charged at the vault)
+    pub amount: U512,
+    /// Part added to the router's total allocated
+    pub reallocated: U512,
+    /// Part credited to the vault's instant pool
+    pub to_instant_pool: U512,
+    /// Part kept in the community pool
+    pub community_allocation: U512,
+    /// Part paid to the `auto_compound` caller in vault shares
+    pub bounty: U512,
+    pub timestamp: u64,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct CommunityFundsClaimed {
+    pub distributor: Address,
+    pub amount: U512,
+    pub remaining: U512,
     pub timestamp: u64,
 }
 
@@ -518,8 +1212,9 @@ pub struct SharePriceUpdated {
 }
 
 #[derive(Event, Debug, PartialEq, Eq)]
-pub struct FeesDistributed {
+pub struct CircuitBreakerTripped {
     pub amount: U512,
-    pub recipient: Address,
+    pub change_bps: u32,
+    pub max_change_bps: u32,
     pub timestamp: u64,
 }
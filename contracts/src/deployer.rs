@@ -0,0 +1,129 @@
+//! System bootstrap
+//!
+//! Deploys and wires the full CasperVault system in one call. Several
+//! contracts need each other's address at init time, so the order matters:
+//!
+//! 1. LiquidStaking (lstCSPR address set afterwards)
+//! 2. LstCspr with LiquidStaking as minter, then wired into LiquidStaking
+//! 3. VaultManager, then CvCspr with the vault as minter, wired into the vault
+//! 4. StrategyRouter, wired into the vault
+//! 5. YieldAggregator, pointed at the vault and granted the operator role
+//!    there and on the LiquidStaking and StrategyRouter it harvests
+//! 6. SystemRegistry, which verifies the wiring and emits `SystemDeployed`
+//!
+//! Host-side only (Odra test env and livenet), not compiled to wasm.
+
+use odra::host::{Deployer, HostEnv, HostRef};
+use odra::Address;
+use crate::core::{
+    LiquidStakingHostRef, LiquidStakingInitArgs,
+    StrategyRouterHostRef, StrategyRouterInitArgs,
+    SystemRegistryHostRef, SystemRegistryInitArgs,
+    VaultManagerHostRef, VaultManagerInitArgs,
+    YieldAggregatorHostRef, YieldAggregatorInitArgs,
+};
+use crate::tokens::{CvCsprHostRef, CvCsprInitArgs, LstCsprHostRef, LstCsprInitArgs};
+use crate::utils::Role;
+
+/// Handles to every contract of a bootstrapped system
+pub struct DeployedSystem {
+    pub vault: VaultManagerHostRef,
+    pub liquid_staking: LiquidStakingHostRef,
+    pub router: StrategyRouterHostRef,
+    pub aggregator: YieldAggregatorHostRef,
+    pub lst_cspr: LstCsprHostRef,
+    pub cv_cspr: CvCsprHostRef,
+    pub registry: SystemRegistryHostRef,
+}
+
+/// Deploy, initialize and cross-wire the whole system
+///
+/// `admin` becomes the admin of every contract and is set as the caller for
+/// the duration of the bootstrap. Panics if the registry finds any wiring
+/// mismatch.
+pub fn deploy_system(env: &HostEnv, admin: Address, treasury: Address) -> DeployedSystem {
+    env.set_caller(admin);
+
+    // The token address isn't known yet; the admin address is a placeholder
+    let mut liquid_staking = LiquidStakingHostRef::deploy(
+        env,
+        LiquidStakingInitArgs {
+            admin,
+            lst_cspr_token: admin,
+        },
+    );
+
+    let lst_cspr = LstCsprHostRef::deploy(
+        env,
+        LstCsprInitArgs {
+            minter: *liquid_staking.address(),
+        },
+    );
+    liquid_staking.set_lst_cspr_token(*lst_cspr.address());
+
+    // cvCSPR needs the vault address, so it is wired in after the vault exists
+    let mut vault = VaultManagerHostRef::deploy(
+        env,
+        VaultManagerInitArgs {
+            admin,
+            treasury,
+            cv_cspr_token: admin,
+            lst_cspr_token: *lst_cspr.address(),
+            liquid_staking_contract: *liquid_staking.address(),
+        },
+    );
+
+    let cv_cspr = CvCsprHostRef::deploy(
+        env,
+        CvCsprInitArgs {
+            vault_manager: *vault.address(),
+        },
+    );
+    vault.set_cv_cspr_token(*cv_cspr.address());
+    liquid_staking.set_vault_address(*vault.address());
+
+    let mut router = StrategyRouterHostRef::deploy(env, StrategyRouterInitArgs { admin });
+    router.set_liquid_staking(*liquid_staking.address());
+    router.set_vault_address(*vault.address());
+    vault.set_strategy_router(*router.address());
+
+    let mut aggregator = YieldAggregatorHostRef::deploy(
+        env,
+        YieldAggregatorInitArgs {
+            admin,
+            fee_recipient: treasury,
+        },
+    );
+    aggregator.set_vault_address(*vault.address());
+    vault.set_yield_aggregator(*aggregator.address());
+    vault.grant_role(Role::Operator.to_u8(), *aggregator.address());
+    liquid_staking.grant_role(Role::Operator.to_u8(), *aggregator.address());
+    router.grant_role(Role::Operator.to_u8(), *aggregator.address());
+    router.set_yield_aggregator(*aggregator.address());
+
+    let registry = SystemRegistryHostRef::deploy(
+        env,
+        SystemRegistryInitArgs {
+            admin,
+            vault_manager: *vault.address(),
+            liquid_staking: *liquid_staking.address(),
+            strategy_router: *router.address(),
+            yield_aggregator: *aggregator.address(),
+            lst_cspr_token: *lst_cspr.address(),
+            cv_cspr_token: *cv_cspr.address(),
+        },
+    );
+
+    let findings = registry.verify_wiring();
+    assert!(findings.is_empty(), "system wiring incomplete: {:?}", findings);
+
+    DeployedSystem {
+        vault,
+        liquid_staking,
+        router,
+        aggregator,
+        lst_cspr,
+        cv_cspr,
+        registry,
+    }
+}
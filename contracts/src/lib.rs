@@ -1,11 +1,17 @@
 extern crate alloc;
 
+#[cfg(all(feature = "release-wasm", any(feature = "mocks", feature = "test-helpers")))]
+compile_error!("release-wasm builds must not enable the `mocks` or `test-helpers` features");
+
 pub mod core;
 pub mod tokens;
 pub mod utils;
 pub mod types;
 pub mod strategies;
+#[cfg(any(test, feature = "mocks"))]
 pub mod mocks;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod deployer;
 
 pub use core::{VaultManager, LiquidStaking, StrategyRouter, YieldAggregator};
 pub use tokens::{LstCspr, CvCspr};
@@ -15,6 +21,7 @@ pub use strategies::{
     IStrategy, RiskLevel, StrategyError, StrategyMetadata, AllocationConfig,
     DEXStrategy, LendingStrategy, CrossChainStrategy
 };
+#[cfg(any(test, feature = "mocks"))]
 pub use mocks::*;
 
 use odra::prelude::*;
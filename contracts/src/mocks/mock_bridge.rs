@@ -0,0 +1,53 @@
+/// Mock return bridge for testing cross-chain withdrawals
+/// Can be told to fail, leaving transfers stuck until released
+
+use odra::prelude::*;
+use odra::Var;
+use odra::casper_types::U512;
+
+/// Mock contract implementing `bridge_back`
+#[odra::module]
+pub struct MockBridge {
+    /// Transfers fail while set
+    failing: Var<bool>,
+
+    /// Amount delivered home
+    delivered: Var<U512>,
+
+    /// Amount sent during a failure and not yet delivered
+    stuck: Var<U512>,
+}
+
+#[odra::module]
+impl MockBridge {
+    /// Deliver `amount`, or hold it as stuck while failing
+    pub fn bridge_back(&mut self, amount: U512) -> bool {
+        if self.failing.get_or_default() {
+            self.stuck.set(self.stuck.get_or_default() + amount);
+            return false;
+        }
+
+        self.delivered.set(self.delivered.get_or_default() + amount);
+        true
+    }
+
+    pub fn set_failing(&mut self, failing: bool) {
+        self.failing.set(failing);
+    }
+
+    /// Deliver everything stuck, returns the amount released
+    pub fn release_stuck(&mut self) -> U512 {
+        let stuck = self.stuck.get_or_default();
+        self.stuck.set(U512::zero());
+        self.delivered.set(self.delivered.get_or_default() + stuck);
+        stuck
+    }
+
+    pub fn get_stuck(&self) -> U512 {
+        self.stuck.get_or_default()
+    }
+
+    pub fn get_delivered(&self) -> U512 {
+        self.delivered.get_or_default()
+    }
+}
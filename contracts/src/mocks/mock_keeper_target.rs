@@ -0,0 +1,48 @@
+/// Mock keeper job target for testing KeeperHub
+/// Counts successful runs per entrypoint and can be told to fail
+
+use odra::prelude::*;
+use odra::{Mapping, Var};
+
+/// Mock contract implementing `run_keeper_job`
+#[odra::module]
+pub struct MockKeeperTarget {
+    /// Successful runs per entrypoint
+    runs: Mapping<u8, u32>,
+
+    /// Entrypoints that report failure
+    failing: Mapping<u8, bool>,
+
+    /// Total calls received, including failures
+    calls: Var<u32>,
+}
+
+#[odra::module]
+impl MockKeeperTarget {
+    /// Run the job unless the entrypoint is set to fail
+    pub fn run_keeper_job(&mut self, entrypoint: u8) -> bool {
+        let calls = self.calls.get_or_default();
+        self.calls.set(calls + 1);
+
+        if self.failing.get(&entrypoint).unwrap_or(false) {
+            return false;
+        }
+
+        let runs = self.runs.get(&entrypoint).unwrap_or(0);
+        self.runs.set(&entrypoint, runs + 1);
+        true
+    }
+
+    pub fn get_runs(&self, entrypoint: u8) -> u32 {
+        self.runs.get(&entrypoint).unwrap_or(0)
+    }
+
+    pub fn get_calls(&self) -> u32 {
+        self.calls.get_or_default()
+    }
+
+    /// Make an entrypoint report failure
+    pub fn set_failing(&mut self, entrypoint: u8, failing: bool) {
+        self.failing.set(&entrypoint, failing);
+    }
+}
@@ -0,0 +1,71 @@
+/// Mock LiquidStaking for testing the vault's pending-yield reads
+/// Accrues rewards linearly at a fixed APY on a settable stake
+
+use odra::prelude::*;
+use odra::Var;
+use odra::casper_types::{U256, U512};
+use crate::utils::safe_u256_to_u512;
+
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Mock contract exposing the LiquidStaking views the vault reads
+#[odra::module]
+pub struct MockLiquidStaking {
+    /// Total CSPR staked
+    total_staked: Var<U512>,
+
+    /// Annual reward rate (basis points)
+    apy_bps: Var<u64>,
+
+    /// Accrual start (last simulated compound)
+    accrual_start: Var<u64>,
+
+    /// CSPR per lstCSPR (1e9 scale); unset means 1:1
+    exchange_rate: Var<U256>,
+}
+
+#[odra::module]
+impl MockLiquidStaking {
+    /// Set the stake and rate, restarting accrual now
+    pub fn set_staking(&mut self, total_staked: U512, apy_bps: u64) {
+        self.total_staked.set(total_staked);
+        self.apy_bps.set(apy_bps);
+        self.accrual_start.set(self.env().get_block_time());
+    }
+
+    /// Set the lstCSPR exchange rate (1e9 scale)
+    pub fn set_exchange_rate(&mut self, rate: U256) {
+        self.exchange_rate.set(rate);
+    }
+
+    /// CSPR per lstCSPR (1e9 scale)
+    pub fn get_exchange_rate(&self) -> U256 {
+        self.exchange_rate.get().unwrap_or(U256::from(1_000_000_000u64))
+    }
+
+    pub fn lst_cspr_to_cspr_precise(&self, lst_cspr_amount: U512) -> U512 {
+        match self.exchange_rate.get() {
+            Some(rate) => lst_cspr_amount * safe_u256_to_u512(rate) / U512::from(1_000_000_000u64),
+            None => lst_cspr_amount,
+        }
+    }
+
+    pub fn cspr_to_lst_cspr_precise(&self, cspr_amount: U512) -> U512 {
+        match self.exchange_rate.get() {
+            Some(rate) => cspr_amount * U512::from(1_000_000_000u64) / safe_u256_to_u512(rate),
+            None => cspr_amount,
+        }
+    }
+
+    pub fn get_total_staked(&self) -> U512 {
+        self.total_staked.get_or_default()
+    }
+
+    /// Rewards accrued since the accrual start
+    pub fn get_pending_rewards_estimate(&self) -> U512 {
+        let elapsed = self.env().get_block_time().saturating_sub(self.accrual_start.get_or_default());
+
+        self.total_staked.get_or_default() * U512::from(self.apy_bps.get_or_default()) * U512::from(elapsed)
+            / U512::from(10000u64 * SECONDS_PER_YEAR)
+    }
+}
@@ -0,0 +1,138 @@
+/// Mock strategy that takes raw CSPR, for testing the router's native
+/// conversions
+/// Amounts in and out are CSPR motes; totals record what crossed the
+/// router boundary
+
+use odra::prelude::*;
+use odra::{Address, Var};
+use odra::casper_types::{U256, U512};
+
+/// Mock native CSPR strategy with a settable balance and capacity
+#[odra::module]
+pub struct MockNativeStrategy {
+    /// CSPR currently held by the strategy
+    balance: Var<U512>,
+
+    /// Reported APY (basis points)
+    apy_bps: Var<U256>,
+
+    /// Maximum CSPR the strategy accepts
+    max_capacity: Var<U512>,
+
+    /// Lifetime CSPR received through `deploy`
+    total_deployed: Var<U512>,
+
+    /// Lifetime CSPR paid out through `withdraw` and `emergency_withdraw`
+    total_withdrawn: Var<U512>,
+
+    /// Router recorded on registration
+    router: Var<Address>,
+}
+
+#[odra::module]
+impl MockNativeStrategy {
+    /// Initialize the mock strategy
+    pub fn init(&mut self, apy_bps: U256, max_capacity: U512) {
+        self.balance.set(U512::zero());
+        self.apy_bps.set(apy_bps);
+        self.max_capacity.set(max_capacity);
+        self.total_deployed.set(U512::zero());
+        self.total_withdrawn.set(U512::zero());
+    }
+
+    /// Accept CSPR up to the remaining capacity
+    pub fn deploy(&mut self, amount: U512) -> U512 {
+        self.deploy_with_id(0, amount)
+    }
+
+    /// Flow ids are not recorded by the mock
+    pub fn deploy_with_id(&mut self, _flow_id: u64, amount: U512) -> U512 {
+        let balance = self.balance.get_or_default();
+        let room = self.max_capacity.get_or_default().saturating_sub(balance);
+        let accepted = amount.min(room);
+
+        self.balance.set(balance + accepted);
+        self.total_deployed.set(self.total_deployed.get_or_default() + accepted);
+        accepted
+    }
+
+    /// Return up to the requested CSPR
+    pub fn withdraw(&mut self, amount: U512) -> U512 {
+        self.pay_out(amount)
+    }
+
+    pub fn withdraw_with_id(&mut self, _flow_id: u64, amount: U512) -> U512 {
+        self.pay_out(amount)
+    }
+
+    pub fn harvest(&mut self) -> U512 {
+        U512::zero()
+    }
+
+    pub fn preview_harvest(&self) -> U512 {
+        U512::zero()
+    }
+
+    pub fn harvest_rewards(&mut self) -> Vec<(Address, U512)> {
+        Vec::new()
+    }
+
+    /// Return up to `max_amount` CSPR (everything if `None`)
+    pub fn emergency_withdraw(&mut self, max_amount: Option<U512>) -> U512 {
+        let balance = self.balance.get_or_default();
+        self.pay_out(max_amount.map_or(balance, |max| balance.min(max)))
+    }
+
+    /// Record the registering router (not enforced by the mock)
+    pub fn set_router(&mut self, router: Address) {
+        self.router.set(router);
+    }
+
+    pub fn get_balance(&self) -> U512 {
+        self.balance.get_or_default()
+    }
+
+    pub fn get_apy(&self) -> U256 {
+        self.apy_bps.get_or_default()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        true
+    }
+
+    pub fn max_capacity(&self) -> U512 {
+        self.max_capacity.get_or_default()
+    }
+
+    pub fn get_lifetime_bridge_fees(&self) -> U512 {
+        U512::zero()
+    }
+
+    pub fn estimate_withdrawal_cost(&self, _amount: U512) -> U512 {
+        U512::zero()
+    }
+
+    pub fn get_total_deployed(&self) -> U512 {
+        self.total_deployed.get_or_default()
+    }
+
+    pub fn get_total_withdrawn(&self) -> U512 {
+        self.total_withdrawn.get_or_default()
+    }
+
+    /// Override the held CSPR (simulates gains or losses)
+    pub fn set_balance(&mut self, balance: U512) {
+        self.balance.set(balance);
+    }
+}
+
+impl MockNativeStrategy {
+    fn pay_out(&mut self, amount: U512) -> U512 {
+        let balance = self.balance.get_or_default();
+        let paid = amount.min(balance);
+
+        self.balance.set(balance - paid);
+        self.total_withdrawn.set(self.total_withdrawn.get_or_default() + paid);
+        paid
+    }
+}
@@ -0,0 +1,41 @@
+/// Mock CSPR/USD price feed for testing USD-denominated limits
+/// Price and update time are set directly by the test
+
+use odra::prelude::*;
+use odra::Var;
+use odra::casper_types::U512;
+
+/// Mock contract implementing `get_cspr_usd_price`
+#[odra::module]
+pub struct MockPriceOracle {
+    /// USD per CSPR, scaled by 10^decimals
+    price: Var<U512>,
+
+    /// Decimals of the price
+    decimals: Var<u8>,
+
+    /// Time of the last price update
+    updated_at: Var<u64>,
+}
+
+#[odra::module]
+impl MockPriceOracle {
+    pub fn init(&mut self, price: U512, decimals: u8) {
+        self.decimals.set(decimals);
+        self.set_price(price);
+    }
+
+    /// Set a new price, stamped with the current block time
+    pub fn set_price(&mut self, price: U512) {
+        self.price.set(price);
+        self.updated_at.set(self.env().get_block_time());
+    }
+
+    pub fn get_cspr_usd_price(&self) -> (U512, u8, u64) {
+        (
+            self.price.get_or_default(),
+            self.decimals.get_or_default(),
+            self.updated_at.get_or_default(),
+        )
+    }
+}
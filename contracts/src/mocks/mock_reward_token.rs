@@ -0,0 +1,53 @@
+/// Mock reward token for testing the router's reward liquidation path
+/// Minimal CEP-18 balances with an open mint
+
+use odra::prelude::*;
+use odra::{Address, Mapping, Var};
+use odra::casper_types::U512;
+use crate::types::TokenError;
+
+/// Mock non-lstCSPR token paid out as strategy incentives
+#[odra::module]
+pub struct MockRewardToken {
+    /// Balances per holder
+    balances: Mapping<Address, U512>,
+
+    /// Total minted
+    total_supply: Var<U512>,
+}
+
+#[odra::module]
+impl MockRewardToken {
+    /// Initialize the mock token
+    pub fn init(&mut self) {
+        self.total_supply.set(U512::zero());
+    }
+
+    /// Mint to any account (unrestricted in the mock)
+    pub fn mint(&mut self, to: Address, amount: U512) {
+        let balance = self.balance_of(to);
+        self.balances.set(&to, balance + amount);
+        self.total_supply.set(self.total_supply.get_or_default() + amount);
+    }
+
+    /// Transfer from the caller
+    pub fn transfer(&mut self, to: Address, amount: U512) {
+        let from = self.env().caller();
+        let from_balance = self.balance_of(from);
+        if from_balance < amount {
+            self.env().revert(TokenError::InsufficientTokenBalance);
+        }
+
+        self.balances.set(&from, from_balance - amount);
+        let to_balance = self.balance_of(to);
+        self.balances.set(&to, to_balance + amount);
+    }
+
+    pub fn balance_of(&self, account: Address) -> U512 {
+        self.balances.get(&account).unwrap_or(U512::zero())
+    }
+
+    pub fn total_supply(&self) -> U512 {
+        self.total_supply.get_or_default()
+    }
+}
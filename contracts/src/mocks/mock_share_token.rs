@@ -0,0 +1,32 @@
+/// Mock share token with configurable decimals
+/// Metadata only, for the vault's init-time decimals check
+
+use odra::prelude::*;
+use odra::Var;
+
+/// Mock cvCSPR stand-in reporting whatever decimals it was deployed with
+#[odra::module]
+pub struct MockShareToken {
+    /// Reported decimals
+    decimals: Var<u8>,
+}
+
+#[odra::module]
+impl MockShareToken {
+    /// Initialize the mock token
+    pub fn init(&mut self, decimals: u8) {
+        self.decimals.set(decimals);
+    }
+
+    pub fn name(&self) -> String {
+        String::from("Mock Shares")
+    }
+
+    pub fn symbol(&self) -> String {
+        String::from("mSHR")
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals.get_or_default()
+    }
+}
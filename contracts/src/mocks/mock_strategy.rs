@@ -0,0 +1,268 @@
+/// Mock strategy for testing StrategyRouter integration
+/// Exposes the router-facing strategy entry points with knobs to
+/// simulate yield, losses and health flips
+
+use odra::prelude::*;
+use odra::{Address, Event, Var};
+use odra::casper_types::{U256, U512};
+use crate::mocks::MockRewardTokenContractRef;
+use crate::tokens::lst_cspr::LstCsprContractRef;
+
+/// Mock strategy with configurable health, APY and balance
+#[odra::module]
+pub struct MockStrategy {
+    /// Value currently held by the strategy
+    balance: Var<U512>,
+
+    /// Yield available for the next harvest
+    pending_yield: Var<U512>,
+
+    /// Reported APY (basis points)
+    apy_bps: Var<U256>,
+
+    /// Reported health flag
+    healthy: Var<bool>,
+
+    /// Maximum deployable amount
+    max_capacity: Var<U512>,
+
+    /// Number of emergency withdrawals executed
+    emergency_withdrawals: Var<u32>,
+
+    /// Set by `emergency_withdraw`; deploys accept nothing while set
+    emergency_mode: Var<bool>,
+
+    /// Most a single emergency withdrawal can unwind (zero = unlimited)
+    emergency_pull_limit: Var<U512>,
+
+    /// Number of `withdraw` calls received
+    withdraw_calls: Var<u32>,
+
+    /// Router recorded on registration
+    router: Var<Address>,
+
+    /// Reward token paid out by `harvest_rewards`
+    reward_token: Var<Address>,
+
+    /// Reward tokens available for the next `harvest_rewards`
+    pending_rewards: Var<U512>,
+
+    /// Exit cost reported by `estimate_withdrawal_cost` (bps)
+    estimated_cost_bps: Var<u32>,
+
+    /// Exit cost `withdraw` actually keeps back (bps)
+    charged_cost_bps: Var<u32>,
+
+    /// Set to book harvested yield into the balance instead of paying it out
+    retain_yield: Var<bool>,
+
+    /// lstCSPR token `harvest` pays the yield in, if set
+    yield_token: Var<Address>,
+}
+
+#[odra::module]
+impl MockStrategy {
+    /// Initialize the mock strategy
+    pub fn init(&mut self, apy_bps: U256, max_capacity: U512) {
+        self.balance.set(U512::zero());
+        self.pending_yield.set(U512::zero());
+        self.apy_bps.set(apy_bps);
+        self.healthy.set(true);
+        self.max_capacity.set(max_capacity);
+        self.emergency_withdrawals.set(0);
+    }
+
+    /// Accept a deployment up to the remaining capacity
+    pub fn deploy(&mut self, amount: U512) -> U512 {
+        self.deploy_with_id(0, amount)
+    }
+
+    pub fn deploy_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        if self.emergency_mode.get_or_default() {
+            return U512::zero();
+        }
+
+        let balance = self.balance.get_or_default();
+        let capacity = self.max_capacity.get_or_default();
+        let room = if capacity > balance { capacity - balance } else { U512::zero() };
+        let accepted = if amount > room { room } else { amount };
+
+        self.balance.set(balance + accepted);
+        self.env().emit_event(Deployed { flow_id, amount: accepted });
+        accepted
+    }
+
+    /// Return up to the requested amount
+    pub fn withdraw(&mut self, amount: U512) -> U512 {
+        self.withdraw_with_id(0, amount)
+    }
+
+    /// The balance drops by the full amount; the charged exit cost is kept back
+    pub fn withdraw_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        let balance = self.balance.get_or_default();
+        let taken = if amount > balance { balance } else { amount };
+        let withdrawn = taken - taken * U512::from(self.charged_cost_bps.get_or_default()) / U512::from(10_000u64);
+
+        self.balance.set(balance - taken);
+        self.withdraw_calls.set(self.withdraw_calls.get_or_default() + 1);
+        self.env().emit_event(Withdrawn { flow_id, amount: withdrawn });
+        withdrawn
+    }
+
+    /// Pay out the pending yield, transferred to the caller if a yield
+    /// token is set
+    pub fn harvest(&mut self) -> U512 {
+        let pending = self.pending_yield.get_or_default();
+        self.pending_yield.set(U512::zero());
+        if self.retain_yield.get_or_default() {
+            self.balance.set(self.balance.get_or_default() + pending);
+        } else if let Some(token) = self.yield_token.get().filter(|_| !pending.is_zero()) {
+            LstCsprContractRef::new(self.env(), token).transfer(self.env().caller(), pending);
+        }
+        pending
+    }
+
+    pub fn preview_harvest(&self) -> U512 {
+        self.pending_yield.get_or_default()
+    }
+
+    /// Mint the pending reward tokens to the caller
+    pub fn harvest_rewards(&mut self) -> Vec<(Address, U512)> {
+        let pending = self.pending_rewards.get_or_default();
+        let token = match self.reward_token.get() {
+            Some(token) if !pending.is_zero() => token,
+            _ => return Vec::new(),
+        };
+
+        self.pending_rewards.set(U512::zero());
+        MockRewardTokenContractRef::new(self.env(), token).mint(self.env().caller(), pending);
+        vec![(token, pending)]
+    }
+
+    /// Return up to `max_amount` (everything if `None`), capped by the pull limit
+    pub fn emergency_withdraw(&mut self, max_amount: Option<U512>) -> U512 {
+        let balance = self.balance.get_or_default();
+        let mut amount = max_amount.map_or(balance, |max| balance.min(max));
+        let limit = self.emergency_pull_limit.get_or_default();
+        if !limit.is_zero() {
+            amount = amount.min(limit);
+        }
+
+        self.balance.set(balance - amount);
+        self.emergency_mode.set(true);
+
+        let count = self.emergency_withdrawals.get_or_default();
+        self.emergency_withdrawals.set(count + 1);
+
+        amount
+    }
+
+    /// Record the registering router (not enforced by the mock)
+    pub fn set_router(&mut self, router: Address) {
+        self.router.set(router);
+    }
+
+    pub fn get_router(&self) -> Option<Address> {
+        self.router.get()
+    }
+
+    pub fn get_balance(&self) -> U512 {
+        self.balance.get_or_default()
+    }
+
+    pub fn get_apy(&self) -> U256 {
+        self.apy_bps.get_or_default()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.get_or_default()
+    }
+
+    pub fn max_capacity(&self) -> U512 {
+        self.max_capacity.get_or_default()
+    }
+
+    pub fn get_lifetime_bridge_fees(&self) -> U512 {
+        U512::zero()
+    }
+
+    pub fn estimate_withdrawal_cost(&self, amount: U512) -> U512 {
+        amount * U512::from(self.estimated_cost_bps.get_or_default()) / U512::from(10_000u64)
+    }
+
+    pub fn get_emergency_withdrawals(&self) -> u32 {
+        self.emergency_withdrawals.get_or_default()
+    }
+
+    pub fn get_withdraw_calls(&self) -> u32 {
+        self.withdraw_calls.get_or_default()
+    }
+
+    pub fn is_emergency_mode(&self) -> bool {
+        self.emergency_mode.get_or_default()
+    }
+
+    /// Set reported health flag
+    pub fn set_healthy(&mut self, healthy: bool) {
+        self.healthy.set(healthy);
+    }
+
+    /// Set reported APY
+    pub fn set_apy(&mut self, apy_bps: U256) {
+        self.apy_bps.set(apy_bps);
+    }
+
+    /// Override the held balance (simulates gains or losses)
+    pub fn set_balance(&mut self, balance: U512) {
+        self.balance.set(balance);
+    }
+
+    /// Set yield available for the next harvest
+    pub fn set_pending_yield(&mut self, amount: U512) {
+        self.pending_yield.set(amount);
+    }
+
+    /// Book harvested yield into the balance instead of paying it out
+    pub fn set_retain_yield(&mut self, retain: bool) {
+        self.retain_yield.set(retain);
+    }
+
+    /// Pay harvested yield in `token`, funded by the test
+    pub fn set_yield_token(&mut self, token: Address) {
+        self.yield_token.set(token);
+    }
+
+    /// Set maximum capacity
+    pub fn set_max_capacity(&mut self, capacity: U512) {
+        self.max_capacity.set(capacity);
+    }
+
+    /// Cap how much a single emergency withdrawal can unwind
+    pub fn set_emergency_pull_limit(&mut self, limit: U512) {
+        self.emergency_pull_limit.set(limit);
+    }
+
+    /// Set the exit cost reported and the one actually charged (bps)
+    pub fn set_withdrawal_cost(&mut self, estimated_bps: u32, charged_bps: u32) {
+        self.estimated_cost_bps.set(estimated_bps);
+        self.charged_cost_bps.set(charged_bps);
+    }
+
+    /// Set reward tokens available for the next `harvest_rewards`
+    pub fn set_pending_rewards(&mut self, token: Address, amount: U512) {
+        self.reward_token.set(token);
+        self.pending_rewards.set(amount);
+    }
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct Deployed {
+    pub flow_id: u64,
+    pub amount: U512,
+}
+
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct Withdrawn {
+    pub flow_id: u64,
+    pub amount: U512,
+}
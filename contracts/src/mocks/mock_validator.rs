@@ -0,0 +1,129 @@
+/// Mock validator for staking integration tests
+/// Delegations, epoch rewards and slashing are driven directly by the test
+
+use odra::prelude::*;
+use odra::{Address, Mapping, Var};
+use odra::casper_types::U512;
+use crate::types::{StakingError, VaultError};
+
+/// Deployable validator simulation
+/// 
+/// Rewards accrue per epoch on each delegator's stake, net of commission,
+/// and stay pending until claimed. Slashing cuts every delegation.
+#[odra::module]
+pub struct MockValidator {
+    /// Stake per delegator
+    delegations: Mapping<Address, U512>,
+
+    /// Everyone who has delegated (for epoch and slashing sweeps)
+    delegators: Var<Vec<Address>>,
+
+    /// Unclaimed rewards per delegator
+    pending_rewards: Mapping<Address, U512>,
+
+    /// Total CSPR delegated to this validator
+    total_delegated: Var<U512>,
+
+    /// Gross reward per epoch (basis points of stake)
+    rewards_rate_bps: Var<u32>,
+
+    /// Commission rate (0-100)
+    commission: Var<u8>,
+
+    /// Accepts new delegations
+    is_active: Var<bool>,
+}
+
+#[odra::module]
+impl MockValidator {
+    pub fn init(&mut self, rewards_rate_bps: u32, commission: u8) {
+        self.rewards_rate_bps.set(rewards_rate_bps);
+        self.commission.set(commission);
+        self.is_active.set(true);
+    }
+
+    pub fn delegate(&mut self, delegator: Address, amount: U512) {
+        if !self.is_active.get_or_default() {
+            self.env().revert(StakingError::ValidatorNotEligible);
+        }
+        if amount.is_zero() {
+            self.env().revert(VaultError::ZeroAmount);
+        }
+
+        let current = self.delegations.get(&delegator).unwrap_or_default();
+        if current.is_zero() {
+            let mut delegators = self.delegators.get_or_default();
+            if !delegators.contains(&delegator) {
+                delegators.push(delegator);
+                self.delegators.set(delegators);
+            }
+        }
+        self.delegations.set(&delegator, current + amount);
+        self.total_delegated.set(self.total_delegated.get_or_default() + amount);
+    }
+
+    pub fn undelegate(&mut self, delegator: Address, amount: U512) {
+        let current = self.delegations.get(&delegator).unwrap_or_default();
+        if amount > current {
+            self.env().revert(StakingError::UndelegationFailed);
+        }
+
+        self.delegations.set(&delegator, current - amount);
+        self.total_delegated.set(self.total_delegated.get_or_default() - amount);
+    }
+
+    /// Pay out and reset the delegator's pending rewards
+    pub fn claim_rewards(&mut self, delegator: Address) -> U512 {
+        let rewards = self.pending_rewards.get(&delegator).unwrap_or_default();
+        self.pending_rewards.set(&delegator, U512::zero());
+        rewards
+    }
+
+    /// Accrue one epoch of rewards to every delegator
+    pub fn distribute_epoch_rewards(&mut self) {
+        let rate_bps = U512::from(self.rewards_rate_bps.get_or_default());
+        let commission = U512::from(self.commission.get_or_default());
+
+        for delegator in self.delegators.get_or_default() {
+            let stake = self.delegations.get(&delegator).unwrap_or_default();
+            let gross = stake * rate_bps / U512::from(10000u64);
+            let net = gross - gross * commission / U512::from(100u64);
+
+            let pending = self.pending_rewards.get(&delegator).unwrap_or_default();
+            self.pending_rewards.set(&delegator, pending + net);
+        }
+    }
+
+    /// Slash every delegation by `percentage` and deactivate the validator
+    pub fn simulate_slashing(&mut self, percentage: u8) {
+        if percentage > 100 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        let mut total = U512::zero();
+        for delegator in self.delegators.get_or_default() {
+            let stake = self.delegations.get(&delegator).unwrap_or_default();
+            let remaining = stake - stake * U512::from(percentage) / U512::from(100u64);
+            self.delegations.set(&delegator, remaining);
+            total += remaining;
+        }
+        self.total_delegated.set(total);
+        self.is_active.set(false);
+    }
+
+    pub fn get_delegation(&self, delegator: Address) -> U512 {
+        self.delegations.get(&delegator).unwrap_or_default()
+    }
+
+    pub fn get_pending_rewards(&self, delegator: Address) -> U512 {
+        self.pending_rewards.get(&delegator).unwrap_or_default()
+    }
+
+    pub fn get_total_delegated(&self) -> U512 {
+        self.total_delegated.get_or_default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active.get_or_default()
+    }
+}
@@ -0,0 +1,47 @@
+/// Validator adapter routing to deployed `MockValidator` contracts
+/// The validator address LiquidStaking delegates to is the mock's contract address
+
+use odra::prelude::*;
+use odra::{Address, Var};
+use odra::casper_types::U512;
+use crate::mocks::MockValidatorContractRef;
+
+/// `ValidatorAdapter` for tests
+/// 
+/// Calls are forwarded with the adapter's caller (the staking contract) as
+/// the delegator, so MockValidator epochs and slashing show up in what
+/// LiquidStaking reads back.
+#[odra::module]
+pub struct MockValidatorAdapter {
+    /// Rewards claimed through the adapter
+    total_claimed: Var<U512>,
+}
+
+#[odra::module]
+impl MockValidatorAdapter {
+    pub fn delegate(&mut self, validator: Address, amount: U512) {
+        let delegator = self.env().caller();
+        MockValidatorContractRef::new(self.env(), validator).delegate(delegator, amount);
+    }
+
+    pub fn undelegate(&mut self, validator: Address, amount: U512) {
+        let delegator = self.env().caller();
+        MockValidatorContractRef::new(self.env(), validator).undelegate(delegator, amount);
+    }
+
+    pub fn claim_rewards(&mut self, validator: Address) -> U512 {
+        let delegator = self.env().caller();
+        let rewards = MockValidatorContractRef::new(self.env(), validator).claim_rewards(delegator);
+        self.total_claimed.set(self.total_claimed.get_or_default() + rewards);
+        rewards
+    }
+
+    pub fn get_delegated(&self, validator: Address) -> U512 {
+        let delegator = self.env().caller();
+        MockValidatorContractRef::new(self.env(), validator).get_delegation(delegator)
+    }
+
+    pub fn get_total_claimed(&self) -> U512 {
+        self.total_claimed.get_or_default()
+    }
+}
@@ -1,5 +1,25 @@
 /// Mock contracts for testing CasperVault
 
 pub mod mock_dex;
+pub mod mock_strategy;
+pub mod mock_native_strategy;
+pub mod mock_keeper_target;
+pub mod mock_liquid_staking;
+pub mod mock_price_oracle;
+pub mod mock_validator;
+pub mod mock_validator_adapter;
+pub mod mock_bridge;
+pub mod mock_reward_token;
+pub mod mock_share_token;
 
 pub use mock_dex::*;
+pub use mock_strategy::*;
+pub use mock_native_strategy::*;
+pub use mock_keeper_target::*;
+pub use mock_liquid_staking::*;
+pub use mock_price_oracle::*;
+pub use mock_validator::*;
+pub use mock_validator_adapter::*;
+pub use mock_bridge::*;
+pub use mock_reward_token::*;
+pub use mock_share_token::*;
@@ -7,12 +7,28 @@ use odra::prelude::*;
 use odra::Event;
 use odra::{Address, Mapping, SubModule, Var};
 use odra::casper_types::{U256, U512};
-use crate::types::VaultError;
-use crate::strategies::strategy_interface::{RiskLevel, StrategyError};
-use crate::utils::access_control::AccessControl;
+use crate::types::{config_address, ConfigChanged, StrategyError, VaultError};
+use crate::strategies::strategy_interface::{RiskLevel, DEFAULT_MAX_REPORTED_APY_BPS, weighted_deposit_time};
+use crate::utils::access_control::{AccessControl, Role};
+use crate::utils::math::{accrue_apr, bps_of, safe_u256_to_u512, safe_u512_to_u32};
+use crate::utils::approvals::ApprovalsRequired;
 use crate::utils::pausable::Pausable;
 use crate::utils::reentrancy_guard::ReentrancyGuard;
 
+/// Upper bound on registered chains, keeps per-chain loops bounded
+const MAX_SUPPORTED_CHAINS: usize = 16;
+
+/// Time after the first deploy for yields to cover bridge fees before the
+/// strategy reports itself unhealthy
+const FEE_BREAKEVEN_PERIOD: u64 = 30 * 86400;
+
+/// Bridge leg carrying withdrawn funds back to Casper
+#[odra::external_contract]
+pub trait ReturnBridge {
+    /// Send `amount` home; `false` if the transfer did not go through
+    fn bridge_back(&mut self, amount: U512) -> bool;
+}
+
 /// Supported target chains
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TargetChain {
@@ -76,14 +92,34 @@ pub struct CrossChainStrategy {
     /// Reentrancy protection
     reentrancy_guard: SubModule<ReentrancyGuard>,
     
+    /// M-of-N approvals for emergency withdrawals
+    approvals: SubModule<ApprovalsRequired>,
+    
+    /// Set by `emergency_withdraw`; no deploys until cleared
+    emergency_mode: Var<bool>,
+    
     /// CORE STATE
     
     /// Current cross-chain positions by chain (flattened)
     bridged_amounts: Mapping<u8, U512>, // Amount bridged per chain
     deployed_amounts: Mapping<u8, U512>, // Deployed amount per chain
-    yields_accrued: Mapping<u8, U512>, // Yields per chain
-    bridge_times: Mapping<u8, u64>, // Bridge timestamp per chain
+    yields_accrued: Mapping<u8, U512>, // Unharvested yields per chain
+    bridge_times: Mapping<u8, u64>, // Amount-weighted bridge timestamp per chain
     bridge_statuses: Mapping<u8, u8>, // Status: 0=Initiated, 1=Confirmed, 2=Deployed, 3=Harvesting, 4=Withdrawing, 5=Completed, 6=Failed
+    last_accruals: Mapping<u8, u64>, // Last yield accrual timestamp per chain
+    
+    /// Chains holding a position, in the order they first received funds
+    active_chains: Var<Vec<u8>>,
+    
+    /// CHAIN REGISTRY
+    
+    /// Chain ids funds may be bridged to
+    supported_chains: Var<Vec<u8>>,
+    chain_names: Mapping<u8, String>,
+    
+    /// Oracle-reported APY per chain (flattened)
+    chain_apy_bps: Mapping<u8, u16>,
+    chain_apy_as_of: Mapping<u8, u64>,
     
     /// Total bridged (lifetime)
     total_bridged: Var<U512>,
@@ -91,14 +127,26 @@ pub struct CrossChainStrategy {
     /// Total yields earned (lifetime)
     total_yields: Var<U512>,
     
+    /// Bridge fees paid on deploys and withdrawals (lifetime)
+    lifetime_bridge_fees: Var<U512>,
+    
+    /// First deploy timestamp (starts the fee breakeven period)
+    first_deploy_time: Var<u64>,
+    
     /// CONTRACT ADDRESSES
     
     /// Bridge contract address
     bridge_address: Var<Address>,
     
+    /// Return bridge (unset = returns are settled immediately)
+    return_bridge: Var<Address>,
+    
     /// lstCSPR token address
     lst_cspr_address: Var<Address>,
     
+    /// StrategyRouter allowed to deploy, withdraw and harvest
+    router: Var<Address>,
+    
     /// PARAMETERS
     
     /// Maximum capacity
@@ -121,6 +169,12 @@ pub struct CrossChainStrategy {
     
     /// Bridge confirmation time (seconds)
     bridge_confirmation_time: Var<u64>,
+    
+    /// Maximum age of a chain APY report before the chain is considered stale
+    max_apy_staleness: Var<u64>,
+    
+    /// Ceiling on the APY reported by `get_apy` (basis points)
+    max_reported_apy_bps: Var<U256>,
 }
 
 #[odra::module]
@@ -143,21 +197,42 @@ impl CrossChainStrategy {
         self.target_apy_bps.set(U256::from(1800u64)); // 18% target APY
         self.min_harvest_interval.set(86400); // 24 hours
         self.bridge_confirmation_time.set(3600); // 1 hour
+        self.max_apy_staleness.set(86400); // 24 hours
+        self.max_reported_apy_bps.set(U256::from(DEFAULT_MAX_REPORTED_APY_BPS)); // 500%
         
         self.total_bridged.set(U512::zero());
         self.total_yields.set(U512::zero());
+        self.lifetime_bridge_fees.set(U512::zero());
         self.last_harvest.set(0);
+        
+        self.active_chains.set(Vec::new());
+        self.supported_chains.set(vec![0, 1, 2, 3]);
+        for (chain, name) in [(0u8, "Ethereum"), (1, "Polygon"), (2, "Arbitrum"), (3, "Optimism")] {
+            self.chain_names.set(&chain, name.to_string());
+        }
     }
     
     /// Deploy funds to cross-chain strategy
     /// 
     /// Process (MVP Simulation):
     /// 1. Receive lstCSPR
-    /// 2. Emit BridgeInitiated event
-    /// 3. Store bridged amount in state
-    /// 4. Simulate deployment on target chain
+    /// 2. Select the chain with the best fresh oracle APY
+    /// 3. Emit BridgeInitiated event
+    /// 4. Store bridged amount in state
+    /// 5. Simulate deployment on target chain
     pub fn deploy(&mut self, amount: U512) -> U512 {
+        self.deploy_with_id(0, amount)
+    }
+    
+    /// `deploy` tagged with the flow id of the vault operation it serves
+    pub fn deploy_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        self.only_router_or_admin();
         self.pausable.when_not_paused();
+        
+        if self.emergency_mode.get_or_default() {
+            return U512::zero(); // Error: emergency mode
+        }
+        
         self.reentrancy_guard.enter();
         
         let min = self.min_bridge_amount.get_or_default();
@@ -173,18 +248,24 @@ impl CrossChainStrategy {
             return U512::zero(); // Error: MaxCapacityReached
         }
         
+        let chain_id = match self.select_chain() {
+            Some(chain) => chain,
+            None => {
+                self.reentrancy_guard.exit();
+                return U512::zero(); // Error: no chain with fresh APY data
+            }
+        };
+        
         let fee_bps = self.bridge_fee_bps.get_or_default();
-        let bridge_fee = amount
-            .checked_mul(U512::from(fee_bps))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
+        let bridge_fee = bps_of(amount, fee_bps);
         
         let amount_after_fee = amount.checked_sub(bridge_fee).unwrap();
         
-        let chain_id = 0u8; // 0 = Ethereum
         let current_time = self.env().get_block_time();
         
+        // Settle yield at the old principal before it changes
+        self.accrue_chain_yield(chain_id);
+        
         // Update or create position using individual Mappings
         let existing_bridged = self.bridged_amounts.get(&chain_id).unwrap_or(U512::zero());
         let existing_deployed = self.deployed_amounts.get(&chain_id).unwrap_or(U512::zero());
@@ -195,23 +276,35 @@ impl CrossChainStrategy {
         self.bridged_amounts.set(&chain_id, new_bridged);
         self.deployed_amounts.set(&chain_id, new_deployed);
         self.yields_accrued.set(&chain_id, self.yields_accrued.get(&chain_id).unwrap_or(U512::zero()));
-        self.bridge_times.set(&chain_id, current_time);
+        self.bridge_times.set(&chain_id, weighted_deposit_time(
+            existing_deployed,
+            self.bridge_times.get(&chain_id).unwrap_or(0),
+            amount_after_fee,
+            current_time,
+        ));
         self.bridge_statuses.set(&chain_id, 2u8); // 2 = Deployed
         
+        let mut active = self.active_chains.get_or_default();
+        if !active.contains(&chain_id) {
+            active.push(chain_id);
+            self.active_chains.set(active);
+        }
+        
         let total = self.total_bridged.get_or_default();
         self.total_bridged.set(total.checked_add(amount_after_fee).unwrap());
         
-        let chain_name = match chain_id {
-            0 => "Ethereum",
-            1 => "Polygon",
-            2 => "Avalanche",
-            _ => "Unknown",
-        };
+        let fees = self.lifetime_bridge_fees.get_or_default();
+        self.lifetime_bridge_fees.set(fees.checked_add(bridge_fee).unwrap());
+        
+        if self.first_deploy_time.get_or_default() == 0 {
+            self.first_deploy_time.set(current_time);
+        }
         
         self.env().emit_event(BridgeInitiated {
+            flow_id,
             amount: amount_after_fee,
             fee: bridge_fee,
-            target_chain: chain_name.to_string(),
+            target_chain: self.chain_name(chain_id),
             bridge_tx: format!("0xsimulated{}", self.env().get_block_time()),
             timestamp: self.env().get_block_time(),
         });
@@ -223,50 +316,109 @@ impl CrossChainStrategy {
     /// Withdraw funds from cross-chain strategy
     /// 
     /// Process (MVP Simulation):
-    /// 1. Initiate withdrawal on target chain
+    /// 1. Initiate withdrawal on target chains (stale APY data does not block exits)
     /// 2. Wait for bridge confirmation
     /// 3. Receive lstCSPR back
+    /// 
+    /// Principal is drawn chain by chain, then accrued yields. The bridge
+    /// fee for the return trip is taken from `amount`, so the position
+    /// shrinks by `amount` and the caller receives `amount` minus the fee.
     pub fn withdraw(&mut self, amount: U512) -> U512 {
+        self.withdraw_with_id(0, amount)
+    }
+    
+    /// `withdraw` tagged with the flow id of the vault operation it serves
+    pub fn withdraw_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        self.only_router_or_admin();
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
+        let active = self.active_chains.get_or_default();
+        for chain in active.iter() {
+            self.accrue_chain_yield(*chain);
+        }
+        
         let total_balance = self.get_balance();
         
-        if amount > total_balance {
+        if amount.is_zero() || amount > total_balance {
             self.reentrancy_guard.exit();
             return U512::zero(); // Error: WithdrawalTooLarge
         }
         
+        let mut remaining = amount;
         
-        let chain_id = 0u8; // Ethereum
-        
-        let deployed = self.deployed_amounts.get(&chain_id).unwrap_or(U512::zero());
-        if deployed.is_zero() {
-            self.reentrancy_guard.exit();
-            return U512::zero(); // Error: InsufficientBalance
+        for &chain in active.iter() {
+            if remaining.is_zero() {
+                break;
+            }
+            
+            let deployed = self.deployed_amounts.get(&chain).unwrap_or(U512::zero());
+            if deployed.is_zero() {
+                continue;
+            }
+            
+            let take = if remaining > deployed { deployed } else { remaining };
+            let bridged = self.bridged_amounts.get(&chain).unwrap_or(U512::zero());
+            
+            self.deployed_amounts.set(&chain, deployed - take);
+            self.bridged_amounts.set(&chain, bridged.saturating_sub(take));
+            self.bridge_statuses.set(&chain, 4u8); // 4 = Withdrawing
+            remaining -= take;
+            
+            self.env().emit_event(WithdrawalInitiated {
+                flow_id,
+                amount: take,
+                target_chain: self.chain_name(chain),
+                timestamp: self.env().get_block_time(),
+            });
         }
         
-        if amount > deployed {
-            self.reentrancy_guard.exit();
-            return U512::zero(); // Error: WithdrawalTooLarge
+        for &chain in active.iter() {
+            if remaining.is_zero() {
+                break;
+            }
+            
+            let yields = self.yields_accrued.get(&chain).unwrap_or(U512::zero());
+            let take = if remaining > yields { yields } else { remaining };
+            self.yields_accrued.set(&chain, yields - take);
+            remaining -= take;
         }
         
-        let bridged = self.bridged_amounts.get(&chain_id).unwrap_or(U512::zero());
-        let new_deployed = deployed.checked_sub(amount).unwrap();
-        let new_bridged = bridged.checked_sub(amount).unwrap();
+        // Emptied chains drop out of the loops
+        let still_active: Vec<u8> = active.into_iter()
+            .filter(|chain| !self.chain_balance(*chain).is_zero())
+            .collect();
+        self.active_chains.set(still_active);
         
-        self.deployed_amounts.set(&chain_id, new_deployed);
-        self.bridged_amounts.set(&chain_id, new_bridged);
-        self.bridge_statuses.set(&chain_id, 4u8); // 4 = Withdrawing
+        let bridge_fee = bps_of(amount, self.bridge_fee_bps.get_or_default());
+        
+        let fees = self.lifetime_bridge_fees.get_or_default();
+        self.lifetime_bridge_fees.set(fees.checked_add(bridge_fee).unwrap());
+        
+        // The position has left the target chains either way; if the return
+        // leg fails the funds are in flight and nothing is paid out
+        if let Some(bridge) = self.return_bridge.get() {
+            if !ReturnBridgeContractRef::new(self.env(), bridge).bridge_back(amount - bridge_fee) {
+                self.env().emit_event(BridgeReturnFailed {
+                    flow_id,
+                    amount: amount - bridge_fee,
+                    timestamp: self.env().get_block_time(),
+                });
+                
+                self.reentrancy_guard.exit();
+                return U512::zero();
+            }
+        }
         
-        self.env().emit_event(WithdrawalInitiated {
-            amount,
-            target_chain: "Ethereum".to_string(),
+        self.env().emit_event(BridgeReturned {
+            flow_id,
+            amount: amount - bridge_fee,
+            fee: bridge_fee,
             timestamp: self.env().get_block_time(),
         });
         
         self.reentrancy_guard.exit();
-        amount
+        amount - bridge_fee
     }
     
     /// Harvest yields from cross-chain deployments
@@ -275,7 +427,12 @@ impl CrossChainStrategy {
     /// 1. Query yields on target chains
     /// 2. Claim rewards
     /// 3. Bridge back or compound on target chain
+    /// 
+    /// Yield accrues at each chain's reported APY; an APY report settles
+    /// the previous rate first, so rate changes apply piecewise. Harvested
+    /// yield is handed to the caller and leaves the chain's position.
     pub fn harvest(&mut self) -> U512 {
+        self.only_harvester();
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
@@ -290,65 +447,72 @@ impl CrossChainStrategy {
         
         // This is complex as it requires cross-chain message passing
         
-        let chain_id = 0u8; // Ethereum
+        let mut harvested = U512::zero();
         
-        let deployed = self.deployed_amounts.get(&chain_id).unwrap_or(U512::zero());
-        if deployed.is_zero() {
-            self.reentrancy_guard.exit();
-            return U512::zero();
+        for chain in self.active_chains.get_or_default() {
+            if self.deployed_amounts.get(&chain).unwrap_or(U512::zero()).is_zero() {
+                continue;
+            }
+            
+            // Includes yield settled by deploys/APY reports since the last harvest
+            self.accrue_chain_yield(chain);
+            let new_yield = self.yields_accrued.get(&chain).unwrap_or(U512::zero());
+            self.yields_accrued.set(&chain, U512::zero());
+            self.bridge_statuses.set(&chain, 2u8); // 2 = Deployed
+            harvested = harvested.checked_add(new_yield).unwrap();
+            
+            self.env().emit_event(YieldHarvested {
+                amount: new_yield,
+                total_yields: self.total_yields.get_or_default() + harvested,
+                target_chain: self.chain_name(chain),
+                timestamp: current_time,
+            });
         }
         
-        let bridge_time = self.bridge_times.get(&chain_id).unwrap_or(0);
-        let yields = self.yields_accrued.get(&chain_id).unwrap_or(U512::zero());
-        
-        let time_elapsed = current_time - bridge_time;
-        let annual_apy_bps = 1800u64; // 18%
-        let seconds_per_year = 31536000u64;
-        
-        let simulated_yield = deployed
-            .checked_mul(U512::from(annual_apy_bps))
-            .unwrap()
-            .checked_mul(U512::from(time_elapsed))
-            .unwrap()
-            .checked_div(U512::from(seconds_per_year))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
-        
-        let new_yield = if simulated_yield > yields {
-            simulated_yield.checked_sub(yields).unwrap()
-        } else {
-            U512::zero()
-        };
-        
-        self.yields_accrued.set(&chain_id, simulated_yield);
-        self.bridge_statuses.set(&chain_id, 2u8); // 2 = Deployed
-        
         let total = self.total_yields.get_or_default();
-        self.total_yields.set(total.checked_add(new_yield).unwrap());
+        self.total_yields.set(total.checked_add(harvested).unwrap());
         self.last_harvest.set(current_time);
         
-        self.env().emit_event(YieldHarvested {
-            amount: new_yield,
-            total_yields: simulated_yield,
-            target_chain: "Ethereum".to_string(),
-            timestamp: current_time,
-        });
-        
         self.reentrancy_guard.exit();
-        new_yield
+        harvested
+    }
+    
+    /// lstCSPR `harvest` would return now, without claiming it
+    /// 
+    /// Zero within `min_harvest_interval` of the last harvest, where
+    /// `harvest` returns nothing.
+    pub fn preview_harvest(&self) -> U512 {
+        let current_time = self.env().get_block_time();
+        if current_time < self.last_harvest.get_or_default() + self.min_harvest_interval.get_or_default() {
+            return U512::zero();
+        }
+        
+        let mut pending = U512::zero();
+        for chain in self.active_chains.get_or_default() {
+            if self.deployed_amounts.get(&chain).unwrap_or(U512::zero()).is_zero() {
+                continue;
+            }
+            pending += self.yields_accrued.get(&chain).unwrap_or(U512::zero()) + self.chain_yield_due(chain, current_time);
+        }
+        pending
+    }
+    
+    /// Claim non-lstCSPR reward tokens accrued since the last call
+    ///
+    /// Remote yields come back over the bridge as lstCSPR, so the list is
+    /// always empty.
+    pub fn harvest_rewards(&mut self) -> Vec<(Address, U512)> {
+        self.only_harvester();
+        Vec::new()
     }
     
     /// Get current balance across all chains
     pub fn get_balance(&self) -> U512 {
         let mut total = U512::zero();
         
-        // Sum up deployed amounts and yields across all chains
-        for chain in 0u8..4u8 { // 0=Ethereum, 1=Polygon, 2=Arbitrum, 3=Optimism
-            let deployed = self.deployed_amounts.get(&chain).unwrap_or(U512::zero());
-            let yields = self.yields_accrued.get(&chain).unwrap_or(U512::zero());
-            total = total.checked_add(deployed).unwrap();
-            total = total.checked_add(yields).unwrap();
+        // Sum up deployed amounts and yields across chains holding a position
+        for chain in self.active_chains.get_or_default() {
+            total = total.checked_add(self.chain_balance(chain)).unwrap();
         }
         
         total
@@ -356,7 +520,7 @@ impl CrossChainStrategy {
     
     /// Get current APY (higher than single-chain due to better opportunities)
     pub fn get_apy(&self) -> U256 {
-        self.target_apy_bps.get_or_default()
+        self.target_apy_bps.get_or_default().min(self.max_reported_apy_bps.get_or_default())
     }
     
     /// Get risk level (High for cross-chain)
@@ -375,6 +539,25 @@ impl CrossChainStrategy {
             return false;
         }
         
+        // Every chain holding funds needs fresh APY data
+        for chain in self.active_chains.get_or_default() {
+            let deployed = self.deployed_amounts.get(&chain).unwrap_or(U512::zero());
+            if !deployed.is_zero() && self.is_chain_stale(chain) {
+                return false;
+            }
+        }
+        
+        // Once past the breakeven period, funds still deployed must have
+        // earned back what bridging cost
+        let first_deploy = self.first_deploy_time.get_or_default();
+        if first_deploy != 0
+            && !self.get_balance().is_zero()
+            && self.env().get_block_time() >= first_deploy + FEE_BREAKEVEN_PERIOD
+            && self.lifetime_bridge_fees.get_or_default() > self.cumulative_yields()
+        {
+            return false;
+        }
+        
         // - Bridge is operational
         // - Target chain protocols are healthy
         // - No pending failed transactions
@@ -389,9 +572,34 @@ impl CrossChainStrategy {
     }
     
     
+    /// Register the StrategyRouter allowed to move funds (admin only)
+    /// 
+    /// `StrategyRouter::add_strategy` calls this on registration, so the
+    /// router needs the admin role here.
+    pub fn set_router(&mut self, router: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.router.get());
+        self.router.set(router);
+        self.config_changed("router", old, config_address(Some(router)));
+    }
+    
+    pub fn get_router(&self) -> Option<Address> {
+        self.router.get()
+    }
+    
     pub fn set_max_capacity(&mut self, capacity: U512) {
         self.access_control.only_admin();
+        let old = self.max_capacity.get_or_default();
         self.max_capacity.set(capacity);
+        self.config_changed("max_capacity", old, capacity);
+    }
+    
+    /// Route withdrawals through a return bridge (admin only)
+    pub fn set_return_bridge(&mut self, bridge: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.return_bridge.get());
+        self.return_bridge.set(bridge);
+        self.config_changed("return_bridge", old, config_address(Some(bridge)));
     }
     
     pub fn set_bridge_fee(&mut self, fee_bps: u32) {
@@ -402,17 +610,195 @@ impl CrossChainStrategy {
             self.env().revert(VaultError::Unauthorized);
         }
         
+        let old = self.bridge_fee_bps.get_or_default();
         self.bridge_fee_bps.set(fee_bps);
+        self.config_changed("bridge_fee_bps", old, fee_bps);
+    }
+    
+    /// Report the current APY for a target chain (oracle only)
+    /// 
+    /// Yield on the chain is settled at the previous rate before the new
+    /// rate takes effect. Reports may not be dated in the future or older
+    /// than the report they replace.
+    pub fn report_chain_apy(&mut self, chain: u8, apy_bps: u16, as_of: u64) {
+        self.access_control.only_oracle();
+        
+        let current_time = self.env().get_block_time();
+        let previous_as_of = self.chain_apy_as_of.get(&chain).unwrap_or(0);
+        
+        if !self.is_supported_chain(chain) || as_of > current_time || as_of < previous_as_of {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        self.accrue_chain_yield(chain);
+        
+        self.chain_apy_bps.set(&chain, apy_bps);
+        self.chain_apy_as_of.set(&chain, as_of);
+        
+        self.env().emit_event(ChainApyReported {
+            chain,
+            apy_bps,
+            as_of,
+        });
+    }
+    
+    /// Get reported APY for a chain: (apy_bps, as_of, is_stale)
+    pub fn get_chain_apy(&self, chain: u8) -> (u16, u64, bool) {
+        (
+            self.chain_apy_bps.get(&chain).unwrap_or(0),
+            self.chain_apy_as_of.get(&chain).unwrap_or(0),
+            self.is_chain_stale(chain),
+        )
+    }
+    
+    /// Whether a chain has fresh APY data and can receive deployments
+    pub fn is_chain_healthy(&self, chain: u8) -> bool {
+        self.is_supported_chain(chain) && !self.is_chain_stale(chain)
+    }
+    
+    /// Register a chain funds may be bridged to (admin only)
+    /// 
+    /// `apy_bps` is the rate yield accrues at until the oracle's first
+    /// report; the chain receives deployments once a fresh report is in.
+    pub fn add_supported_chain(&mut self, chain_id: u8, name: String, apy_bps: u16) {
+        self.access_control.only_admin();
+        
+        let mut chains = self.supported_chains.get_or_default();
+        if chains.contains(&chain_id) || chains.len() >= MAX_SUPPORTED_CHAINS || name.is_empty() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        chains.push(chain_id);
+        self.supported_chains.set(chains);
+        self.chain_names.set(&chain_id, name.clone());
+        self.chain_apy_bps.set(&chain_id, apy_bps);
+        self.config_changed(&format!("supported_chain.{}", chain_id), "none", format!("{} ({} bps)", name, apy_bps));
+    }
+    
+    /// Deregister a chain (admin only, once nothing is left on it)
+    pub fn remove_supported_chain(&mut self, chain_id: u8) {
+        self.access_control.only_admin();
+        
+        let mut chains = self.supported_chains.get_or_default();
+        if !chains.contains(&chain_id) || !self.chain_balance(chain_id).is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        chains.retain(|chain| *chain != chain_id);
+        self.supported_chains.set(chains);
+        self.config_changed(&format!("supported_chain.{}", chain_id), self.chain_name(chain_id), "none");
+    }
+    
+    /// Supported chains: (chain id, name, last reported or initial APY bps, is_stale)
+    pub fn get_supported_chains(&self) -> Vec<(u8, String, u16, bool)> {
+        self.supported_chains.get_or_default()
+            .into_iter()
+            .map(|chain| (
+                chain,
+                self.chain_name(chain),
+                self.chain_apy_bps.get(&chain).unwrap_or(0),
+                self.is_chain_stale(chain),
+            ))
+            .collect()
+    }
+    
+    /// Chains currently holding a position
+    pub fn get_active_chains(&self) -> Vec<u8> {
+        self.active_chains.get_or_default()
     }
     
-    pub fn emergency_withdraw(&mut self) -> U512 {
+    pub fn set_max_apy_staleness(&mut self, seconds: u64) {
         self.access_control.only_admin();
         
+        if seconds == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.max_apy_staleness.get_or_default();
+        self.max_apy_staleness.set(seconds);
+        self.config_changed("max_apy_staleness", old, seconds);
+    }
+    
+    /// Cap the APY `get_apy` reports (admin only)
+    pub fn set_max_reported_apy(&mut self, max_apy_bps: U256) {
+        self.access_control.only_admin();
+        
+        if max_apy_bps.is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.max_reported_apy_bps.get_or_default();
+        self.max_reported_apy_bps.set(max_apy_bps);
+        self.config_changed("max_reported_apy_bps", old, max_apy_bps);
+    }
+    
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+    /// Register emergency action approvers and the M-of-N threshold (admin only)
+    /// 
+    /// A threshold of 1 keeps single-admin mode.
+    pub fn set_approvers(&mut self, approvers: Vec<Address>, threshold: u8, validity_window: u64) {
+        self.access_control.only_admin();
+        self.approvals.set_approvers(approvers, threshold, validity_window);
+    }
+    
+    /// Approve an emergency action hash (registered approvers only)
+    pub fn approve_action(&mut self, action_hash: [u8; 32]) {
+        self.approvals.approve(action_hash);
+    }
+    
+    /// Withdraw the caller's approval of an emergency action hash
+    pub fn revoke_approval(&mut self, action_hash: [u8; 32]) {
+        self.approvals.revoke(action_hash);
+    }
+    
+    /// Unexpired approvals for an emergency action hash
+    pub fn get_approval_count(&self, action_hash: [u8; 32]) -> u8 {
+        self.approvals.approval_count(action_hash)
+    }
+    
+    /// Action hash approvers sign off on before `emergency_withdraw` runs
+    pub fn get_emergency_withdraw_hash(&self) -> [u8; 32] {
+        self.approvals.action_hash("emergency_withdraw", &())
+    }
+    
+    
+    /// Emergency withdraw up to `max_amount`, everything if `None` (admin only)
+    /// 
+    /// The first call enters emergency mode, which takes the approvers'
+    /// sign-off and stops new deploys; later calls pull the rest without
+    /// another approval, so a position that can't exit at once is drained
+    /// over several calls.
+    pub fn emergency_withdraw(&mut self, max_amount: Option<U512>) -> U512 {
+        self.access_control.only_admin();
+        
+        if !self.emergency_mode.get_or_default() {
+            let action_hash = self.get_emergency_withdraw_hash();
+            self.approvals.require_approval(action_hash);
+            self.emergency_mode.set(true);
+            self.config_changed("emergency_mode", false, true);
+        }
+        
         let balance = self.get_balance();
+        let amount = max_amount.map_or(balance, |max| balance.min(max));
         
         // from all target chains, potentially with losses
+        self.withdraw(amount)
+    }
+    
+    /// Leave emergency mode and accept deploys again (admin only)
+    pub fn clear_emergency_mode(&mut self) {
+        self.access_control.only_admin();
         
-        self.withdraw(balance)
+        if self.emergency_mode.get_or_default() {
+            self.emergency_mode.set(false);
+            self.config_changed("emergency_mode", true, false);
+        }
+    }
+    
+    pub fn is_emergency_mode(&self) -> bool {
+        self.emergency_mode.get_or_default()
     }
     
     pub fn pause(&mut self) {
@@ -445,14 +831,157 @@ impl CrossChainStrategy {
         self.total_yields.get_or_default()
     }
     
+    /// Bridge fees paid on deploys and withdrawals (lifetime)
+    pub fn get_lifetime_bridge_fees(&self) -> U512 {
+        self.lifetime_bridge_fees.get_or_default()
+    }
+    
+    /// Estimated bridge fees for taking `amount` home, both ways
+    /// 
+    /// The return leg is paid now; the outbound fee is paid again when the
+    /// router rebuilds the allocation, so a round trip is what pulling from
+    /// this strategy costs compared with one that stays on Casper.
+    pub fn estimate_withdrawal_cost(&self, amount: U512) -> U512 {
+        let fee_bps = self.bridge_fee_bps.get_or_default();
+        bps_of(amount, fee_bps) + bps_of(amount, fee_bps)
+    }
+    
     pub fn get_bridge_fee_bps(&self) -> u32 {
         self.bridge_fee_bps.get_or_default()
     }
 }
 
+impl CrossChainStrategy {
+    /// Deploy and withdraw: the registered router or an admin
+    fn only_router_or_admin(&self) {
+        let caller = self.env().caller();
+        if !self.is_router_or_admin(caller) {
+            self.env().revert(StrategyError::Unauthorized);
+        }
+    }
+    
+    /// Harvest: the router, an admin or a keeper
+    fn only_harvester(&self) {
+        let caller = self.env().caller();
+        if !self.is_router_or_admin(caller) && !self.access_control.has_role(Role::Keeper.to_u8(), caller) {
+            self.env().revert(StrategyError::Unauthorized);
+        }
+    }
+    
+    /// Emit `ConfigChanged` for one of the strategy's parameters
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "CrossChainStrategy".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    fn is_router_or_admin(&self, caller: Address) -> bool {
+        self.router.get() == Some(caller) || self.access_control.has_role(Role::Admin.to_u8(), caller)
+    }
+    
+    fn chain_name(&self, chain: u8) -> String {
+        self.chain_names.get(&chain).unwrap_or_else(|| "Unknown".to_string())
+    }
+    
+    fn is_supported_chain(&self, chain: u8) -> bool {
+        self.supported_chains.get_or_default().contains(&chain)
+    }
+    
+    /// Deployed amount plus accrued yields on one chain
+    fn chain_balance(&self, chain: u8) -> U512 {
+        let deployed = self.deployed_amounts.get(&chain).unwrap_or(U512::zero());
+        let yields = self.yields_accrued.get(&chain).unwrap_or(U512::zero());
+        deployed.checked_add(yields).unwrap()
+    }
+    
+    /// No report yet, or the last one is older than max_apy_staleness
+    fn is_chain_stale(&self, chain: u8) -> bool {
+        match self.chain_apy_as_of.get(&chain) {
+            Some(as_of) => {
+                let age = self.env().get_block_time().saturating_sub(as_of);
+                age > self.max_apy_staleness.get_or_default()
+            }
+            None => true,
+        }
+    }
+
+    /// Harvested yields plus yields accrued since the last harvest
+    fn cumulative_yields(&self) -> U512 {
+        let mut total = self.total_yields.get_or_default();
+
+        for chain in self.active_chains.get_or_default() {
+            let accrued = self.yields_accrued.get(&chain).unwrap_or(U512::zero());
+            total = total.checked_add(accrued).unwrap();
+        }
+
+        total
+    }
+
+    /// Fresh chain with the highest reported APY
+    fn select_chain(&self) -> Option<u8> {
+        let mut best: Option<(u8, u16)> = None;
+        
+        for chain in self.supported_chains.get_or_default() {
+            if self.is_chain_stale(chain) {
+                continue;
+            }
+            
+            let apy = self.chain_apy_bps.get(&chain).unwrap_or(0);
+            match best {
+                Some((_, best_apy)) if best_apy >= apy => {}
+                _ => best = Some((chain, apy)),
+            }
+        }
+        
+        best.map(|(chain, _)| chain)
+    }
+    
+    /// Accrue yield on a chain since its last accrual at the current rate
+    /// 
+    /// Uses the last reported APY (even if stale) or the target APY when
+    /// the chain has never been reported.
+    fn accrue_chain_yield(&mut self, chain: u8) -> U512 {
+        let current_time = self.env().get_block_time();
+        let new_yield = self.chain_yield_due(chain, current_time);
+        self.last_accruals.set(&chain, current_time);
+        if new_yield.is_zero() {
+            return U512::zero();
+        }
+        
+        let yields = self.yields_accrued.get(&chain).unwrap_or(U512::zero());
+        self.yields_accrued.set(&chain, yields.checked_add(new_yield).unwrap());
+        
+        new_yield
+    }
+    
+    /// Yield `chain` earned since its last accrual, not yet booked
+    fn chain_yield_due(&self, chain: u8, current_time: u64) -> U512 {
+        let last_accrual = self.last_accruals.get(&chain).unwrap_or(current_time);
+        let deployed = self.deployed_amounts.get(&chain).unwrap_or(U512::zero());
+        let elapsed = current_time.saturating_sub(last_accrual);
+        if deployed.is_zero() || elapsed == 0 {
+            return U512::zero();
+        }
+        
+        let apy_bps = match self.chain_apy_bps.get(&chain) {
+            Some(apy) => u32::from(apy),
+            None => safe_u512_to_u32(safe_u256_to_u512(self.target_apy_bps.get_or_default()))
+                .unwrap_or_revert(&self.env()),
+        };
+        
+        accrue_apr(deployed, apy_bps, elapsed).unwrap_or_revert(&self.env())
+    }
+}
+
 
 #[derive(Event)]
 struct BridgeInitiated {
+    flow_id: u64,
     amount: U512,
     fee: U512,
     target_chain: String,
@@ -460,13 +989,36 @@ struct BridgeInitiated {
     timestamp: u64,
 }
 
+#[derive(Event)]
+struct BridgeReturned {
+    flow_id: u64,
+    amount: U512,
+    fee: U512,
+    timestamp: u64,
+}
+
+#[derive(Event)]
+struct BridgeReturnFailed {
+    flow_id: u64,
+    amount: U512,
+    timestamp: u64,
+}
+
 #[derive(Event)]
 struct WithdrawalInitiated {
+    flow_id: u64,
     amount: U512,
     target_chain: String,
     timestamp: u64,
 }
 
+#[derive(Event)]
+struct ChainApyReported {
+    chain: u8,
+    apy_bps: u16,
+    as_of: u64,
+}
+
 #[derive(Event)]
 struct YieldHarvested {
     amount: U512,
@@ -7,12 +7,38 @@ use odra::prelude::*;
 use odra::Event;
 use odra::{Address, SubModule, Var};
 use odra::casper_types::{U256, U512};
-use crate::types::VaultError;
-use crate::strategies::strategy_interface::{RiskLevel, StrategyError};
-use crate::utils::access_control::AccessControl;
+use crate::types::{config_address, ConfigChanged, StrategyError, VaultError};
+use crate::strategies::strategy_interface::{
+    RiskLevel, DEFAULT_MAX_REPORTED_APY_BPS, DEFAULT_MIN_APY_WINDOW,
+    weighted_deposit_time,
+};
+use crate::utils::access_control::{AccessControl, Role};
+use crate::utils::math::{accrue_apr, annualize_bps, bps_of, mul_div, ratio_bps, safe_u256_to_u512, safe_u512_to_u256, Rounding};
+use crate::utils::approvals::ApprovalsRequired;
 use crate::utils::pausable::Pausable;
 use crate::utils::reentrancy_guard::ReentrancyGuard;
 
+/// Price scale for pool and reference prices (CSPR per lstCSPR)
+const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// Most the pool price may stray from the reference before deploys and
+/// withdrawals are refused (50%)
+const MAX_PRICE_DEVIATION_LIMIT_BPS: u32 = 5000;
+
+/// lstCSPR/CSPR pool the strategy provides liquidity to
+#[odra::external_contract]
+pub trait LiquidityPool {
+    /// (lstCSPR reserve, CSPR reserve)
+    fn get_reserves(&self) -> (U512, U512);
+}
+
+/// Source of the fair lstCSPR price, e.g. LiquidStaking
+#[odra::external_contract]
+pub trait LstPriceSource {
+    /// CSPR per lstCSPR, 1e9 scale
+    fn get_exchange_rate(&self) -> U256;
+}
+
 /// LP position information
 #[derive(Debug, Clone, Default)]
 struct LPPosition {
@@ -63,6 +89,12 @@ pub struct DEXStrategy {
     /// Reentrancy protection
     reentrancy_guard: SubModule<ReentrancyGuard>,
     
+    /// M-of-N approvals for emergency withdrawals
+    approvals: SubModule<ApprovalsRequired>,
+    
+    /// Set by `emergency_withdraw`; no deploys until cleared
+    emergency_mode: Var<bool>,
+    
     /// CORE STATE
     
     /// LP position fields (flattened for Casper serialization)
@@ -73,6 +105,10 @@ pub struct DEXStrategy {
     trading_fees: Var<U512>,
     mining_rewards: Var<U512>,
     
+    /// Start of the period not yet covered by a harvest (amount-weighted
+    /// like `deposit_time`, reset on every harvest)
+    accrual_time: Var<u64>,
+    
     /// Total lstCSPR deployed
     total_deployed: Var<U512>,
     
@@ -90,6 +126,9 @@ pub struct DEXStrategy {
     /// lstCSPR token address
     lst_cspr_address: Var<Address>,
     
+    /// StrategyRouter allowed to deploy, withdraw and harvest
+    router: Var<Address>,
+    
     /// PARAMETERS
     
     /// Maximum capacity (lstCSPR)
@@ -101,14 +140,41 @@ pub struct DEXStrategy {
     /// Maximum slippage allowed (basis points)
     max_slippage_bps: Var<u32>,
     
+    /// Pool swap fee paid when an exit swaps its CSPR leg back (basis points)
+    swap_fee_bps: Var<u32>,
+    
+    /// lstCSPR reserve of the pool, for the price impact of that swap
+    pool_reserve: Var<U512>,
+    
     /// Target APY in basis points
     target_apy_bps: Var<U256>,
     
+    /// Ceiling on the APY reported by `get_apy` (basis points)
+    max_reported_apy_bps: Var<U256>,
+    
+    /// Position age before `get_apy` measures instead of reporting the target (seconds)
+    min_apy_window: Var<u64>,
+    
     /// Last harvest timestamp
     last_harvest: Var<u64>,
     
     /// Minimum harvest interval (seconds)
     min_harvest_interval: Var<u64>,
+    
+    /// PRICE GUARD
+    
+    /// Largest gap between pool and reference price for deploy/withdraw (bps)
+    max_price_deviation_bps: Var<u32>,  // Default: 200 (2%)
+    
+    /// Reference price source; the pool's time-weighted average when unset
+    price_oracle: Var<Address>,
+    
+    /// Time-weighted average pool price (1e9 scale, zero until first observed)
+    twap_price: Var<U512>,
+    twap_updated_at: Var<u64>,
+    
+    /// Time for the average to fully catch up with a new pool price (seconds)
+    twap_window: Var<u64>,  // Default: 30 minutes
 }
 
 #[odra::module]
@@ -136,13 +202,21 @@ impl DEXStrategy {
         self.max_capacity.set(U512::from(1_000_000u64) * U512::from(1_000_000_000u64)); // 1M CSPR
         self.min_deployment.set(U512::from(100u64) * U512::from(1_000_000_000u64)); // 100 CSPR
         self.max_slippage_bps.set(100); // 1% max slippage
+        self.swap_fee_bps.set(30); // 0.3% pool fee
+        self.pool_reserve.set(U512::from(1_000_000u64) * U512::from(1_000_000_000u64)); // 1M lstCSPR
         self.target_apy_bps.set(U256::from(1500u64)); // 15% target APY
+        self.max_reported_apy_bps.set(U256::from(DEFAULT_MAX_REPORTED_APY_BPS)); // 500%
+        self.min_apy_window.set(DEFAULT_MIN_APY_WINDOW); // 1 hour
         self.min_harvest_interval.set(43200); // 12 hours
+        self.max_price_deviation_bps.set(200); // 2%
+        self.twap_window.set(1800); // 30 minutes
+        self.twap_price.set(U512::zero());
         
         self.lp_tokens.set(U512::zero());
         self.lst_cspr_amount.set(U512::zero());
         self.cspr_amount.set(U512::zero());
         self.deposit_time.set(0);
+        self.accrual_time.set(0);
         self.trading_fees.set(U512::zero());
         self.mining_rewards.set(U512::zero());
         
@@ -160,8 +234,24 @@ impl DEXStrategy {
     /// 4. Receive LP tokens
     /// 5. Stake LP tokens for rewards
     /// 6. Update position tracking
+    /// 
+    /// Reverts with `PriceDeviationTooHigh` while the pool price is more
+    /// than `max_price_deviation_bps` from the reference price, so a
+    /// sandwich can't make the strategy add liquidity at a skewed ratio.
     pub fn deploy(&mut self, amount: U512) -> U512 {
+        self.deploy_with_id(0, amount)
+    }
+    
+    /// `deploy` tagged with the flow id of the vault operation it serves
+    pub fn deploy_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        self.only_router_or_admin();
         self.pausable.when_not_paused();
+        
+        if self.emergency_mode.get_or_default() {
+            return U512::zero(); // Error: emergency mode
+        }
+        self.guard_pool_price();
+        
         self.reentrancy_guard.enter();
         
         let min_deploy = self.min_deployment.get_or_default();
@@ -195,12 +285,22 @@ impl DEXStrategy {
         self.lp_tokens.set(current_lp_tokens.checked_add(lp_tokens).unwrap());
         self.lst_cspr_amount.set(current_lst.checked_add(actual_lst).unwrap());
         self.cspr_amount.set(current_cspr.checked_add(actual_cspr).unwrap());
-        self.deposit_time.set(self.env().get_block_time());
+        
+        // Weight both clocks by amount so a top-up doesn't reset the age of
+        // funds already deployed
+        let now = self.env().get_block_time();
+        self.deposit_time.set(weighted_deposit_time(
+            current_lst, self.deposit_time.get_or_default(), actual_lst, now,
+        ));
+        self.accrual_time.set(weighted_deposit_time(
+            current_lst, self.accrual_time.get_or_default(), actual_lst, now,
+        ));
         
         let new_total = current.checked_add(actual_lst).unwrap();
         self.total_deployed.set(new_total);
         
         self.env().emit_event(Deployed {
+            flow_id,
             amount: actual_lst,
             lp_tokens,
             timestamp: self.env().get_block_time(),
@@ -218,8 +318,20 @@ impl DEXStrategy {
     /// 3. Remove liquidity from pool
     /// 4. Receive lstCSPR and CSPR
     /// 5. Return lstCSPR to router
+    /// 
+    /// Refused like `deploy` while the pool price is off its reference,
+    /// except in emergency mode, where getting out beats the price.
     pub fn withdraw(&mut self, amount: U512) -> U512 {
+        self.withdraw_with_id(0, amount)
+    }
+    
+    /// `withdraw` tagged with the flow id of the vault operation it serves
+    pub fn withdraw_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        self.only_router_or_admin();
         self.pausable.when_not_paused();
+        if !self.emergency_mode.get_or_default() {
+            self.guard_pool_price();
+        }
         self.reentrancy_guard.enter();
         
         let position_lst = self.lst_cspr_amount.get_or_default();
@@ -255,6 +367,7 @@ impl DEXStrategy {
         self.total_deployed.set(current.checked_sub(lst_received).unwrap());
         
         self.env().emit_event(Withdrawn {
+            flow_id,
             amount: lst_received,
             lp_tokens_burned: lp_to_unstake,
             timestamp: self.env().get_block_time(),
@@ -271,7 +384,12 @@ impl DEXStrategy {
     /// 2. Claim mining rewards from staking
     /// 3. Swap rewards to lstCSPR if needed
     /// 4. Return harvested amount
+    /// 
+    /// The harvested lstCSPR is handed to the caller and leaves the
+    /// position; `trading_fees` and `mining_rewards` keep the lifetime
+    /// totals for APY reporting.
     pub fn harvest(&mut self) -> U512 {
+        self.only_harvester();
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
@@ -284,23 +402,7 @@ impl DEXStrategy {
             return U512::zero(); // Error: Unauthorized/RateLimit
         }
         
-        
-        
-        let position_lst = self.lst_cspr_amount.get_or_default();
-        let position_deposit_time = self.deposit_time.get_or_default();
-        let time_elapsed = current_time - position_deposit_time;
-        let annual_apy_bps = 1200u64; // 12%
-        let seconds_per_year = 31536000u64;
-        
-        let simulated_yield = position_lst
-            .checked_mul(U512::from(annual_apy_bps))
-            .unwrap()
-            .checked_mul(U512::from(time_elapsed))
-            .unwrap()
-            .checked_div(U512::from(seconds_per_year))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
+        let simulated_yield = self.accrued_yield(current_time);
         
         let trading_fees = simulated_yield.checked_div(U512::from(2u64)).unwrap();
         let mining_rewards = simulated_yield.checked_sub(trading_fees).unwrap();
@@ -314,6 +416,7 @@ impl DEXStrategy {
         let current_harvested = self.total_harvested.get_or_default();
         self.total_harvested.set(current_harvested.checked_add(total_yield).unwrap());
         self.last_harvest.set(current_time);
+        self.accrual_time.set(current_time);
         
         self.env().emit_event(Harvested {
             trading_fees,
@@ -326,14 +429,33 @@ impl DEXStrategy {
         total_yield
     }
     
+    /// lstCSPR `harvest` would return now, without claiming it
+    /// 
+    /// Zero within `min_harvest_interval` of the last harvest, where
+    /// `harvest` returns nothing.
+    pub fn preview_harvest(&self) -> U512 {
+        let current_time = self.env().get_block_time();
+        if current_time < self.last_harvest.get_or_default() + self.min_harvest_interval.get_or_default() {
+            return U512::zero();
+        }
+        self.accrued_yield(current_time)
+    }
+    
+    /// Claim non-lstCSPR reward tokens accrued since the last call
+    ///
+    /// Trading fees and mining rewards are booked in lstCSPR by `harvest`,
+    /// so there is nothing to hand over in other tokens.
+    pub fn harvest_rewards(&mut self) -> Vec<(Address, U512)> {
+        self.only_harvester();
+        Vec::new()
+    }
+    
     /// Get current balance in strategy
+    /// 
+    /// Fees and rewards are paid out by `harvest` as they are booked, so
+    /// only the deployed lstCSPR is held.
     pub fn get_balance(&self) -> U512 {
-        let lst = self.lst_cspr_amount.get_or_default();
-        let fees = self.trading_fees.get_or_default();
-        let rewards = self.mining_rewards.get_or_default();
-        
-        // Total value = deployed + accrued rewards
-        lst.checked_add(fees).unwrap().checked_add(rewards).unwrap()
+        self.lst_cspr_amount.get_or_default()
     }
     
     /// Calculate current APY
@@ -350,27 +472,23 @@ impl DEXStrategy {
         }
         
         let current_time = self.env().get_block_time();
-        let time_elapsed = current_time - deposit_time;
+        let time_elapsed = current_time.saturating_sub(deposit_time);
         
-        if time_elapsed == 0 {
+        // A few seconds of fees annualize to absurd figures
+        if time_elapsed == 0 || time_elapsed < self.min_apy_window.get_or_default() {
             return self.target_apy_bps.get_or_default();
         }
         
         let total_yield = fees.checked_add(rewards).unwrap();
-        let seconds_per_year = 31536000u64;
         
         // APY = (yield / deployed) * (1 year / time) * 10000
-        let apy = total_yield
-            .checked_mul(U512::from(seconds_per_year))
-            .unwrap()
-            .checked_mul(U512::from(10000u64))
-            .unwrap()
-            .checked_div(lst)
-            .unwrap()
-            .checked_div(U512::from(time_elapsed))
-            .unwrap();
+        let apy = annualize_bps(total_yield, lst, time_elapsed).unwrap_or_revert(&self.env());
         
-        U256::from(apy.as_u128())
+        let max_apy = self.max_reported_apy_bps.get_or_default();
+        if apy > safe_u256_to_u512(max_apy) {
+            return max_apy;
+        }
+        safe_u512_to_u256(apy).unwrap_or_revert(&self.env())
     }
     
     /// Get risk level (Medium for DEX LPs)
@@ -409,6 +527,32 @@ impl DEXStrategy {
         self.max_capacity.get_or_default()
     }
     
+    /// Lifetime bridge fees (always zero, funds stay on Casper)
+    pub fn get_lifetime_bridge_fees(&self) -> U512 {
+        U512::zero()
+    }
+    
+    /// Estimated cost of a single-sided exit of `amount` lstCSPR
+    /// 
+    /// Removing liquidity returns half the value as CSPR, which is swapped
+    /// back: the pool fee on that half plus constant-product price impact
+    /// against `pool_reserve`. An empty reserve can't absorb the swap, so
+    /// the whole CSPR leg counts as lost.
+    pub fn estimate_withdrawal_cost(&self, amount: U512) -> U512 {
+        let swapped = amount / U512::from(2u64);
+        let fee = bps_of(swapped, self.swap_fee_bps.get_or_default());
+        let swap_in = swapped - fee;
+        
+        let reserve = self.pool_reserve.get_or_default();
+        if reserve.is_zero() {
+            return swapped;
+        }
+        // out = reserve * in / (reserve + in), so impact = in^2 / (reserve + in)
+        let impact = mul_div(swap_in, swap_in, reserve + swap_in, Rounding::Up)
+            .unwrap_or(swap_in);
+        fee + impact
+    }
+    
     /// Calculate impermanent loss
     /// 
     /// IL = (2 * sqrt(price_ratio) / (1 + price_ratio)) - 1
@@ -438,20 +582,12 @@ impl DEXStrategy {
         
         let diff = if current_value > initial_value {
             let profit = current_value.checked_sub(initial_value).unwrap();
-            let profit_bps = profit
-                .checked_mul(U512::from(10000u64))
-                .unwrap()
-                .checked_div(initial_value)
-                .unwrap();
-            -(i32::try_from(profit_bps).unwrap_or(0))
+            let profit_bps = ratio_bps(profit, initial_value).unwrap_or_revert(&self.env());
+            -(i32::try_from(profit_bps).unwrap_or(i32::MAX))
         } else {
             let loss = initial_value.checked_sub(current_value).unwrap();
-            let loss_bps = loss
-                .checked_mul(U512::from(10000u64))
-                .unwrap()
-                .checked_div(initial_value)
-                .unwrap();
-            i32::try_from(loss_bps).unwrap_or(0)
+            let loss_bps = ratio_bps(loss, initial_value).unwrap_or_revert(&self.env());
+            i32::try_from(loss_bps).unwrap_or(i32::MAX)
         };
         
         ImpermanentLoss {
@@ -462,10 +598,32 @@ impl DEXStrategy {
     }
     
     
+    /// Register the StrategyRouter allowed to move funds (admin only)
+    /// 
+    /// `StrategyRouter::add_strategy` calls this on registration, so the
+    /// router needs the admin role here.
+    pub fn set_router(&mut self, router: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.router.get());
+        self.router.set(router);
+        self.config_changed("router", old, config_address(Some(router)));
+    }
+    
+    pub fn get_router(&self) -> Option<Address> {
+        self.router.get()
+    }
+    
+    /// Grant a role, e.g. Keeper to allow harvests (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+    
     /// Update max capacity
     pub fn set_max_capacity(&mut self, capacity: U512) {
         self.access_control.only_admin();
+        let old = self.max_capacity.get_or_default();
         self.max_capacity.set(capacity);
+        self.config_changed("max_capacity", old, capacity);
     }
     
     /// Update max slippage
@@ -477,17 +635,151 @@ impl DEXStrategy {
             self.env().revert(VaultError::Unauthorized);
         }
         
+        let old = self.max_slippage_bps.get_or_default();
         self.max_slippage_bps.set(slippage_bps);
+        self.config_changed("max_slippage_bps", old, slippage_bps);
+    }
+    
+    /// Set the pool fee and reserve behind `estimate_withdrawal_cost` (admin only)
+    pub fn set_exit_cost_model(&mut self, swap_fee_bps: u32, pool_reserve: U512) {
+        self.access_control.only_admin();
+        
+        // Max 10% pool fee
+        if swap_fee_bps > 1000 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_fee = self.swap_fee_bps.get_or_default();
+        let old_reserve = self.pool_reserve.get_or_default();
+        self.swap_fee_bps.set(swap_fee_bps);
+        self.pool_reserve.set(pool_reserve);
+        self.config_changed("swap_fee_bps", old_fee, swap_fee_bps);
+        self.config_changed("pool_reserve", old_reserve, pool_reserve);
+    }
+    
+    /// Set the price deviation guard on deploys and withdrawals (admin only)
+    /// 
+    /// `max_deviation_bps` is at most 50%; `twap_window` is how long the
+    /// pool average takes to fully follow a new price.
+    pub fn set_price_guard(&mut self, max_deviation_bps: u32, twap_window: u64) {
+        self.access_control.only_admin();
+        
+        if max_deviation_bps == 0 || max_deviation_bps > MAX_PRICE_DEVIATION_LIMIT_BPS || twap_window == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_deviation = self.max_price_deviation_bps.get_or_default();
+        let old_window = self.twap_window.get_or_default();
+        self.max_price_deviation_bps.set(max_deviation_bps);
+        self.twap_window.set(twap_window);
+        self.config_changed("max_price_deviation_bps", old_deviation, max_deviation_bps);
+        self.config_changed("twap_window", old_window, twap_window);
     }
     
-    /// Emergency withdraw (admin only)
-    pub fn emergency_withdraw(&mut self) -> U512 {
+    /// Use `oracle`'s exchange rate as the reference price (admin only)
+    /// 
+    /// Typically LiquidStaking, whose rate is what lstCSPR redeems for.
+    pub fn set_price_oracle(&mut self, oracle: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.price_oracle.get());
+        self.price_oracle.set(oracle);
+        self.config_changed("price_oracle", old, config_address(Some(oracle)));
+    }
+    
+    /// Fold the current pool price into the time-weighted average; anyone may call
+    /// 
+    /// Deploys and withdrawals observe the price too; calling this between
+    /// them keeps the average current. A price held for less than the
+    /// window only moves the average part of the way.
+    pub fn record_price_observation(&mut self) {
+        if let Some(spot) = self.pool_price() {
+            self.observe_price(spot);
+        }
+    }
+    
+    /// Bound the APY `get_apy` reports (admin only)
+    /// 
+    /// Positions younger than `min_window` seconds report the target APY;
+    /// measured APYs are capped at `max_apy_bps`.
+    pub fn set_apy_bounds(&mut self, max_apy_bps: U256, min_window: u64) {
+        self.access_control.only_admin();
+        
+        if max_apy_bps.is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_max = self.max_reported_apy_bps.get_or_default();
+        let old_window = self.min_apy_window.get_or_default();
+        self.max_reported_apy_bps.set(max_apy_bps);
+        self.min_apy_window.set(min_window);
+        
+        self.config_changed("max_reported_apy_bps", old_max, max_apy_bps);
+        self.config_changed("min_apy_window", old_window, min_window);
+    }
+    
+    /// Register emergency action approvers and the M-of-N threshold (admin only)
+    /// 
+    /// A threshold of 1 keeps single-admin mode.
+    pub fn set_approvers(&mut self, approvers: Vec<Address>, threshold: u8, validity_window: u64) {
+        self.access_control.only_admin();
+        self.approvals.set_approvers(approvers, threshold, validity_window);
+    }
+    
+    /// Approve an emergency action hash (registered approvers only)
+    pub fn approve_action(&mut self, action_hash: [u8; 32]) {
+        self.approvals.approve(action_hash);
+    }
+    
+    /// Withdraw the caller's approval of an emergency action hash
+    pub fn revoke_approval(&mut self, action_hash: [u8; 32]) {
+        self.approvals.revoke(action_hash);
+    }
+    
+    /// Unexpired approvals for an emergency action hash
+    pub fn get_approval_count(&self, action_hash: [u8; 32]) -> u8 {
+        self.approvals.approval_count(action_hash)
+    }
+    
+    /// Action hash approvers sign off on before `emergency_withdraw` runs
+    pub fn get_emergency_withdraw_hash(&self) -> [u8; 32] {
+        self.approvals.action_hash("emergency_withdraw", &())
+    }
+    
+    /// Emergency withdraw up to `max_amount`, everything if `None` (admin only)
+    /// 
+    /// The first call enters emergency mode, which takes the approvers'
+    /// sign-off and stops new deploys; later calls pull the rest without
+    /// another approval, so a position that can't exit at once is drained
+    /// over several calls.
+    pub fn emergency_withdraw(&mut self, max_amount: Option<U512>) -> U512 {
         self.access_control.only_admin();
         
-        let total = self.lst_cspr_amount.get_or_default();
+        if !self.emergency_mode.get_or_default() {
+            let action_hash = self.get_emergency_withdraw_hash();
+            self.approvals.require_approval(action_hash);
+            self.emergency_mode.set(true);
+            self.config_changed("emergency_mode", false, true);
+        }
         
-        // Attempt withdrawal of all funds
-        self.withdraw(total)
+        let balance = self.lst_cspr_amount.get_or_default();
+        let amount = max_amount.map_or(balance, |max| balance.min(max));
+        
+        // Attempt withdrawal of the requested amount
+        self.withdraw(amount)
+    }
+    
+    /// Leave emergency mode and accept deploys again (admin only)
+    pub fn clear_emergency_mode(&mut self) {
+        self.access_control.only_admin();
+        
+        if self.emergency_mode.get_or_default() {
+            self.emergency_mode.set(false);
+            self.config_changed("emergency_mode", true, false);
+        }
+    }
+    
+    pub fn is_emergency_mode(&self) -> bool {
+        self.emergency_mode.get_or_default()
     }
     
     /// Pause strategy
@@ -510,6 +802,35 @@ impl DEXStrategy {
         (lp_tokens, lst, cspr)
     }
     
+    /// (max deviation in bps, TWAP window in seconds)
+    pub fn get_price_guard(&self) -> (u32, u64) {
+        (self.max_price_deviation_bps.get_or_default(), self.twap_window.get_or_default())
+    }
+    
+    pub fn get_price_oracle(&self) -> Option<Address> {
+        self.price_oracle.get()
+    }
+    
+    /// Instantaneous pool price, CSPR per lstCSPR (1e9 scale)
+    /// 
+    /// `None` when the DEX isn't a contract or the pool is empty.
+    pub fn get_pool_price(&self) -> Option<U512> {
+        self.pool_price()
+    }
+    
+    /// Price deploys and withdrawals are checked against (1e9 scale)
+    /// 
+    /// The oracle's rate when one is set, otherwise the pool's time-weighted
+    /// average; `None` before the average has a first observation.
+    pub fn get_reference_price(&self) -> Option<U512> {
+        self.reference_price()
+    }
+    
+    /// Pool fee (bps) and lstCSPR reserve behind `estimate_withdrawal_cost`
+    pub fn get_exit_cost_model(&self) -> (u32, U512) {
+        (self.swap_fee_bps.get_or_default(), self.pool_reserve.get_or_default())
+    }
+    
     pub fn get_total_deployed(&self) -> U512 {
         self.total_deployed.get_or_default()
     }
@@ -525,9 +846,112 @@ impl DEXStrategy {
     }
 }
 
+impl DEXStrategy {
+    /// Deploy and withdraw: the registered router or an admin
+    fn only_router_or_admin(&self) {
+        let caller = self.env().caller();
+        if !self.is_router_or_admin(caller) {
+            self.env().revert(StrategyError::Unauthorized);
+        }
+    }
+    
+    /// Fees and rewards the position accrued since `accrual_time` (12% APR)
+    fn accrued_yield(&self, current_time: u64) -> U512 {
+        let time_elapsed = current_time.saturating_sub(self.accrual_time.get_or_default());
+        accrue_apr(self.lst_cspr_amount.get_or_default(), 1200, time_elapsed).unwrap_or_revert(&self.env())
+    }
+    
+    /// Harvest: the router, an admin or a keeper
+    fn only_harvester(&self) {
+        let caller = self.env().caller();
+        if !self.is_router_or_admin(caller) && !self.access_control.has_role(Role::Keeper.to_u8(), caller) {
+            self.env().revert(StrategyError::Unauthorized);
+        }
+    }
+    
+    /// Revert with `PriceDeviationTooHigh` if the pool price is off its reference
+    /// 
+    /// Passes when there is no pool to read. Without an oracle the first
+    /// observation seeds the average unchecked; a checked price is folded
+    /// into the average afterwards.
+    fn guard_pool_price(&mut self) {
+        let spot = match self.pool_price() {
+            Some(spot) => spot,
+            None => return,
+        };
+        
+        if let Some(reference) = self.reference_price() {
+            let gap = if spot > reference { spot - reference } else { reference - spot };
+            let deviation_bps = ratio_bps(gap, reference).unwrap_or_revert(&self.env());
+            if deviation_bps > U512::from(self.max_price_deviation_bps.get_or_default()) {
+                self.env().revert(StrategyError::PriceDeviationTooHigh);
+            }
+        }
+        self.observe_price(spot);
+    }
+    
+    /// CSPR per lstCSPR from the pool reserves
+    fn pool_price(&self) -> Option<U512> {
+        let dex = self.dex_address.get()?;
+        if !dex.is_contract() {
+            return None;
+        }
+        let (lst_reserve, cspr_reserve) = LiquidityPoolContractRef::new(self.env(), dex).get_reserves();
+        if lst_reserve.is_zero() {
+            return None;
+        }
+        Some(cspr_reserve * U512::from(PRICE_SCALE) / lst_reserve)
+    }
+    
+    fn reference_price(&self) -> Option<U512> {
+        if let Some(oracle) = self.price_oracle.get() {
+            let rate = safe_u256_to_u512(LstPriceSourceContractRef::new(self.env(), oracle).get_exchange_rate());
+            return (!rate.is_zero()).then_some(rate);
+        }
+        let twap = self.twap_price.get_or_default();
+        (!twap.is_zero()).then_some(twap)
+    }
+    
+    /// Move the average toward `spot` by the time since the last observation
+    fn observe_price(&mut self, spot: U512) {
+        let now = self.env().get_block_time();
+        let twap = self.twap_price.get_or_default();
+        let window = self.twap_window.get_or_default().max(1);
+        let updated_at = self.twap_updated_at.get_or_default();
+        let elapsed = now.saturating_sub(updated_at).min(window);
+        
+        let updated = if twap.is_zero() {
+            spot
+        } else if spot > twap {
+            twap + (spot - twap) * U512::from(elapsed) / U512::from(window)
+        } else {
+            twap - (twap - spot) * U512::from(elapsed) / U512::from(window)
+        };
+        self.twap_price.set(updated);
+        self.twap_updated_at.set(now.max(updated_at));
+    }
+    
+    /// Emit `ConfigChanged` for one of the strategy's parameters
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "DEXStrategy".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    fn is_router_or_admin(&self, caller: Address) -> bool {
+        self.router.get() == Some(caller) || self.access_control.has_role(Role::Admin.to_u8(), caller)
+    }
+}
+
 
 #[derive(Event)]
 struct Deployed {
+    flow_id: u64,
     amount: U512,
     lp_tokens: U512,
     timestamp: u64,
@@ -535,6 +959,7 @@ struct Deployed {
 
 #[derive(Event)]
 struct Withdrawn {
+    flow_id: u64,
     amount: U512,
     lp_tokens_burned: U512,
     timestamp: u64,
@@ -7,9 +7,11 @@ use odra::prelude::*;
 use odra::Event;
 use odra::{Address, SubModule, Var};
 use odra::casper_types::{U256, U512};
-use crate::types::VaultError;
-use crate::strategies::strategy_interface::{RiskLevel, StrategyError};
-use crate::utils::access_control::AccessControl;
+use crate::types::{config_address, ConfigChanged, StrategyError, VaultError};
+use crate::strategies::strategy_interface::{RiskLevel, DEFAULT_MAX_REPORTED_APY_BPS, weighted_deposit_time};
+use crate::utils::access_control::{AccessControl, Role};
+use crate::utils::math::{accrue_apr, bps_of};
+use crate::utils::approvals::ApprovalsRequired;
 use crate::utils::pausable::Pausable;
 use crate::utils::reentrancy_guard::ReentrancyGuard;
 
@@ -44,12 +46,18 @@ pub struct LendingStrategy {
     /// Reentrancy protection
     reentrancy_guard: SubModule<ReentrancyGuard>,
     
+    /// M-of-N approvals for emergency withdrawals
+    approvals: SubModule<ApprovalsRequired>,
+    
+    /// Set by `emergency_withdraw`; no deploys until cleared
+    emergency_mode: Var<bool>,
+    
     /// CORE STATE
     
     /// Lending position fields (flattened for Casper serialization)
     principal: Var<U512>,
     interest_accrued: Var<U512>,
-    supply_time: Var<u64>,
+    supply_time: Var<u64>, // Amount-weighted, reset to the harvest time on each harvest
     c_tokens: Var<U512>,
     
     /// Total supplied (lifetime)
@@ -69,6 +77,9 @@ pub struct LendingStrategy {
     /// lstCSPR token address
     lst_cspr_address: Var<Address>,
     
+    /// StrategyRouter allowed to deploy, withdraw and harvest
+    router: Var<Address>,
+    
     /// PARAMETERS
     
     /// Maximum capacity
@@ -84,6 +95,10 @@ pub struct LendingStrategy {
     /// Maximum acceptable utilization (basis points)
     max_utilization_bps: Var<u32>,
     
+    /// Early-withdrawal penalty at maximum utilization (basis points)
+    /// Scales linearly from zero at the target utilization
+    withdrawal_penalty_bps: Var<u32>,
+    
     /// Last harvest timestamp
     last_harvest: Var<u64>,
     
@@ -92,6 +107,9 @@ pub struct LendingStrategy {
     
     /// Current APY (cached, updated on harvest)
     cached_apy: Var<U256>,
+    
+    /// Ceiling on the APY reported by `get_apy` (basis points)
+    max_reported_apy_bps: Var<U256>,
 }
 
 #[odra::module]
@@ -112,8 +130,10 @@ impl LendingStrategy {
         self.min_supply.set(U512::from(100u64) * U512::from(1_000_000_000u64)); // 100 CSPR
         self.target_utilization_bps.set(7000); // 70% target
         self.max_utilization_bps.set(9000); // 90% max
+        self.withdrawal_penalty_bps.set(100); // 1% at max utilization
         self.min_harvest_interval.set(43200); // 12 hours
         self.cached_apy.set(U256::from(800u64)); // 8% initial estimate
+        self.max_reported_apy_bps.set(U256::from(DEFAULT_MAX_REPORTED_APY_BPS)); // 500%
         
         self.principal.set(U512::zero());
         self.interest_accrued.set(U512::zero());
@@ -134,7 +154,18 @@ impl LendingStrategy {
     /// 3. Receive cTokens
     /// 4. Track position
     pub fn deploy(&mut self, amount: U512) -> U512 {
+        self.deploy_with_id(0, amount)
+    }
+    
+    /// `deploy` tagged with the flow id of the vault operation it serves
+    pub fn deploy_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        self.only_router_or_admin();
         self.pausable.when_not_paused();
+        
+        if self.emergency_mode.get_or_default() {
+            return U512::zero(); // Error: emergency mode
+        }
+        
         self.reentrancy_guard.enter();
         
         let min = self.min_supply.get_or_default();
@@ -163,7 +194,13 @@ impl LendingStrategy {
         let new_principal = current_principal.checked_add(amount).unwrap();
         let current_c_tokens = self.c_tokens.get_or_default();
         let new_c_tokens = current_c_tokens.checked_add(c_tokens_minted).unwrap();
-        let new_supply_time = self.env().get_block_time();
+        // Amount-weighted so earlier principal keeps accruing from its own start
+        let new_supply_time = weighted_deposit_time(
+            current_principal,
+            self.supply_time.get_or_default(),
+            amount,
+            self.env().get_block_time(),
+        );
         
         self.principal.set(new_principal);
         self.c_tokens.set(new_c_tokens);
@@ -173,6 +210,7 @@ impl LendingStrategy {
         self.total_supplied.set(total.checked_add(amount).unwrap());
         
         self.env().emit_event(Supplied {
+            flow_id,
             amount,
             c_tokens: c_tokens_minted,
             timestamp: self.env().get_block_time(),
@@ -190,6 +228,12 @@ impl LendingStrategy {
     /// 3. Receive lstCSPR
     /// 4. Update position
     pub fn withdraw(&mut self, amount: U512) -> U512 {
+        self.withdraw_with_id(0, amount)
+    }
+    
+    /// `withdraw` tagged with the flow id of the vault operation it serves
+    pub fn withdraw_with_id(&mut self, flow_id: u64, amount: U512) -> U512 {
+        self.only_router_or_admin();
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
@@ -242,6 +286,7 @@ impl LendingStrategy {
         self.total_withdrawn.set(total.checked_add(lst_received).unwrap());
         
         self.env().emit_event(Redeemed {
+            flow_id,
             amount: lst_received,
             c_tokens_burned: c_tokens_to_redeem,
             timestamp: self.env().get_block_time(),
@@ -258,7 +303,11 @@ impl LendingStrategy {
     /// 2. Calculate interest earned since last harvest
     /// 3. Update interest tracking
     /// 4. Return harvested amount
+    /// 
+    /// Pays out all booked interest, including what deploys and withdrawals
+    /// settled since the last harvest, so it leaves the position.
     pub fn harvest(&mut self) -> U512 {
+        self.only_harvester();
         self.pausable.when_not_paused();
         self.reentrancy_guard.enter();
         
@@ -271,37 +320,18 @@ impl LendingStrategy {
             return U512::zero(); // Error: Unauthorized
         }
         
-        let principal = self.principal.get_or_default();
         let interest = self.interest_accrued.get_or_default();
-        let supply_time = self.supply_time.get_or_default();
         
-        if principal.is_zero() {
+        if self.principal.get_or_default().is_zero() {
             self.reentrancy_guard.exit();
             return U512::zero();
         }
         
+        let new_interest_earned = self.interest_since_supply(current_time);
+        let total_interest = interest.checked_add(new_interest_earned).unwrap();
         
-        let time_elapsed = current_time - supply_time;
-        let annual_apy_bps = 800u64; // 8%
-        let seconds_per_year = 31536000u64;
-        
-        let simulated_interest = principal
-            .checked_mul(U512::from(annual_apy_bps))
-            .unwrap()
-            .checked_mul(U512::from(time_elapsed))
-            .unwrap()
-            .checked_div(U512::from(seconds_per_year))
-            .unwrap()
-            .checked_div(U512::from(10000u64))
-            .unwrap();
-        
-        let new_interest_earned = if simulated_interest > interest {
-            simulated_interest.checked_sub(interest).unwrap()
-        } else {
-            U512::zero()
-        };
-        
-        self.interest_accrued.set(simulated_interest);
+        self.interest_accrued.set(U512::zero());
+        self.supply_time.set(current_time);
         
         let total = self.total_interest_earned.get_or_default();
         self.total_interest_earned.set(total.checked_add(new_interest_earned).unwrap());
@@ -311,12 +341,44 @@ impl LendingStrategy {
         
         self.env().emit_event(InterestHarvested {
             amount: new_interest_earned,
-            total_interest: simulated_interest,
+            total_interest,
             timestamp: current_time,
         });
         
         self.reentrancy_guard.exit();
-        new_interest_earned
+        total_interest
+    }
+    
+    /// lstCSPR `harvest` would return now, without claiming it
+    /// 
+    /// Zero within `min_harvest_interval` of the last harvest or with
+    /// nothing supplied, where `harvest` returns nothing.
+    pub fn preview_harvest(&self) -> U512 {
+        let current_time = self.env().get_block_time();
+        if current_time < self.last_harvest.get_or_default() + self.min_harvest_interval.get_or_default()
+            || self.principal.get_or_default().is_zero()
+        {
+            return U512::zero();
+        }
+        self.interest_accrued.get_or_default() + self.interest_since_supply(current_time)
+    }
+    
+    /// Claim non-lstCSPR reward tokens accrued since the last call
+    ///
+    /// Interest is paid in lstCSPR, so the list is always empty.
+    pub fn harvest_rewards(&mut self) -> Vec<(Address, U512)> {
+        self.only_harvester();
+        Vec::new()
+    }
+    
+    /// Recompute the cached APY from pool utilization (router, admin or keeper)
+    /// 
+    /// Harvests refresh it too; `get_apy` only ever reads the cache.
+    /// Returns the APY `get_apy` reports from now on.
+    pub fn refresh_apy(&mut self) -> U256 {
+        self.only_harvester();
+        self.update_apy_cache();
+        self.get_apy()
     }
     
     /// Get current balance
@@ -328,7 +390,7 @@ impl LendingStrategy {
     
     /// Get current APY
     pub fn get_apy(&self) -> U256 {
-        self.cached_apy.get_or_default()
+        self.cached_apy.get_or_default().min(self.max_reported_apy_bps.get_or_default())
     }
     
     /// Get risk level (Low for lending)
@@ -370,8 +432,72 @@ impl LendingStrategy {
         self.max_capacity.get_or_default()
     }
     
+    /// Lifetime bridge fees (always zero, funds stay on Casper)
+    pub fn get_lifetime_bridge_fees(&self) -> U512 {
+        U512::zero()
+    }
+    
+    /// Estimated early-withdrawal penalty on `amount`
+    /// 
+    /// Zero while pool utilization is at or below target; above it the
+    /// penalty grows linearly to `withdrawal_penalty_bps` at the maximum.
+    pub fn estimate_withdrawal_cost(&self, amount: U512) -> U512 {
+        let utilization = self.get_pool_utilization();
+        let target = self.target_utilization_bps.get_or_default();
+        let max = self.max_utilization_bps.get_or_default();
+        if utilization <= target {
+            return U512::zero();
+        }
+        
+        let penalty_bps = self.withdrawal_penalty_bps.get_or_default();
+        let over = u64::from(utilization.min(max) - target);
+        let range = u64::from(max - target);
+        let scaled_bps = if range == 0 { penalty_bps } else { (u64::from(penalty_bps) * over / range) as u32 };
+        bps_of(amount, scaled_bps)
+    }
+    
     // HELPER FUNCTIONS
     
+    /// Deploy and withdraw: the registered router or an admin
+    fn only_router_or_admin(&self) {
+        let caller = self.env().caller();
+        if !self.is_router_or_admin(caller) {
+            self.env().revert(StrategyError::Unauthorized);
+        }
+    }
+    
+    /// Interest on the principal since the accrual anchor (8% APR)
+    /// 
+    /// Earlier periods are already booked in `interest_accrued`.
+    fn interest_since_supply(&self, current_time: u64) -> U512 {
+        let time_elapsed = current_time.saturating_sub(self.supply_time.get_or_default());
+        accrue_apr(self.principal.get_or_default(), 800, time_elapsed).unwrap_or_revert(&self.env())
+    }
+    
+    /// Harvest: the router, an admin or a keeper
+    fn only_harvester(&self) {
+        let caller = self.env().caller();
+        if !self.is_router_or_admin(caller) && !self.access_control.has_role(Role::Keeper.to_u8(), caller) {
+            self.env().revert(StrategyError::Unauthorized);
+        }
+    }
+    
+    /// Emit `ConfigChanged` for one of the strategy's parameters
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "LendingStrategy".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
+    }
+    
+    fn is_router_or_admin(&self, caller: Address) -> bool {
+        self.router.get() == Some(caller) || self.access_control.has_role(Role::Admin.to_u8(), caller)
+    }
+    
     /// Get pool utilization rate
     /// 
     /// Utilization = Borrowed / (Supplied + Borrowed)
@@ -397,9 +523,31 @@ impl LendingStrategy {
     }
     
     
+    /// Register the StrategyRouter allowed to move funds (admin only)
+    /// 
+    /// `StrategyRouter::add_strategy` calls this on registration, so the
+    /// router needs the admin role here.
+    pub fn set_router(&mut self, router: Address) {
+        self.access_control.only_admin();
+        let old = config_address(self.router.get());
+        self.router.set(router);
+        self.config_changed("router", old, config_address(Some(router)));
+    }
+    
+    pub fn get_router(&self) -> Option<Address> {
+        self.router.get()
+    }
+    
+    /// Grant a role, e.g. Keeper to allow harvests (admin only)
+    pub fn grant_role(&mut self, role: u8, account: Address) {
+        self.access_control.grant_role(role, account);
+    }
+    
     pub fn set_max_capacity(&mut self, capacity: U512) {
         self.access_control.only_admin();
+        let old = self.max_capacity.get_or_default();
         self.max_capacity.set(capacity);
+        self.config_changed("max_capacity", old, capacity);
     }
     
     pub fn set_utilization_targets(&mut self, target_bps: u32, max_bps: u32) {
@@ -409,16 +557,104 @@ impl LendingStrategy {
             self.env().revert(VaultError::Unauthorized);
         }
         
+        let old_target = self.target_utilization_bps.get_or_default();
+        let old_max = self.max_utilization_bps.get_or_default();
         self.target_utilization_bps.set(target_bps);
         self.max_utilization_bps.set(max_bps);
+        
+        self.config_changed("target_utilization_bps", old_target, target_bps);
+        self.config_changed("max_utilization_bps", old_max, max_bps);
     }
     
-    pub fn emergency_withdraw(&mut self) -> U512 {
+    /// Set the early-withdrawal penalty at maximum utilization (admin only)
+    pub fn set_withdrawal_penalty(&mut self, penalty_bps: u32) {
         self.access_control.only_admin();
         
+        // Max 5% penalty
+        if penalty_bps > 500 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.withdrawal_penalty_bps.get_or_default();
+        self.withdrawal_penalty_bps.set(penalty_bps);
+        self.config_changed("withdrawal_penalty_bps", old, penalty_bps);
+    }
+    
+    /// Cap the APY `get_apy` reports (admin only)
+    pub fn set_max_reported_apy(&mut self, max_apy_bps: U256) {
+        self.access_control.only_admin();
+        
+        if max_apy_bps.is_zero() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old = self.max_reported_apy_bps.get_or_default();
+        self.max_reported_apy_bps.set(max_apy_bps);
+        self.config_changed("max_reported_apy_bps", old, max_apy_bps);
+    }
+    
+    /// Register emergency action approvers and the M-of-N threshold (admin only)
+    /// 
+    /// A threshold of 1 keeps single-admin mode.
+    pub fn set_approvers(&mut self, approvers: Vec<Address>, threshold: u8, validity_window: u64) {
+        self.access_control.only_admin();
+        self.approvals.set_approvers(approvers, threshold, validity_window);
+    }
+    
+    /// Approve an emergency action hash (registered approvers only)
+    pub fn approve_action(&mut self, action_hash: [u8; 32]) {
+        self.approvals.approve(action_hash);
+    }
+    
+    /// Withdraw the caller's approval of an emergency action hash
+    pub fn revoke_approval(&mut self, action_hash: [u8; 32]) {
+        self.approvals.revoke(action_hash);
+    }
+    
+    /// Unexpired approvals for an emergency action hash
+    pub fn get_approval_count(&self, action_hash: [u8; 32]) -> u8 {
+        self.approvals.approval_count(action_hash)
+    }
+    
+    /// Action hash approvers sign off on before `emergency_withdraw` runs
+    pub fn get_emergency_withdraw_hash(&self) -> [u8; 32] {
+        self.approvals.action_hash("emergency_withdraw", &())
+    }
+    
+    
+    /// Emergency withdraw up to `max_amount`, everything if `None` (admin only)
+    /// 
+    /// The first call enters emergency mode, which takes the approvers'
+    /// sign-off and stops new deploys; later calls pull the rest without
+    /// another approval, so a position that can't exit at once is drained
+    /// over several calls.
+    pub fn emergency_withdraw(&mut self, max_amount: Option<U512>) -> U512 {
+        self.access_control.only_admin();
+        
+        if !self.emergency_mode.get_or_default() {
+            let action_hash = self.get_emergency_withdraw_hash();
+            self.approvals.require_approval(action_hash);
+            self.emergency_mode.set(true);
+            self.config_changed("emergency_mode", false, true);
+        }
+        
         let balance = self.get_balance();
+        let amount = max_amount.map_or(balance, |max| balance.min(max));
+        self.withdraw(amount)
+    }
+    
+    /// Leave emergency mode and accept deploys again (admin only)
+    pub fn clear_emergency_mode(&mut self) {
+        self.access_control.only_admin();
         
-        self.withdraw(balance)
+        if self.emergency_mode.get_or_default() {
+            self.emergency_mode.set(false);
+            self.config_changed("emergency_mode", true, false);
+        }
+    }
+    
+    pub fn is_emergency_mode(&self) -> bool {
+        self.emergency_mode.get_or_default()
     }
     
     pub fn pause(&mut self) {
@@ -450,11 +686,16 @@ impl LendingStrategy {
     pub fn get_utilization_rate(&self) -> u32 {
         self.get_pool_utilization()
     }
+    
+    pub fn get_withdrawal_penalty_bps(&self) -> u32 {
+        self.withdrawal_penalty_bps.get_or_default()
+    }
 }
 
 
 #[derive(Event)]
 struct Supplied {
+    flow_id: u64,
     amount: U512,
     c_tokens: U512,
     timestamp: u64,
@@ -462,6 +703,7 @@ struct Supplied {
 
 #[derive(Event)]
 struct Redeemed {
+    flow_id: u64,
     amount: U512,
     c_tokens_burned: U512,
     timestamp: u64,
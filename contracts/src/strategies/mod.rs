@@ -1,6 +1,9 @@
 /// Strategy modules for CasperVault
 /// 
 /// This module contains all strategy implementations for yield generation.
+/// Every strategy's `init` takes `admin`, then the protocol address(es) it
+/// talks to, then `lst_cspr_address`; capacities and limits start at
+/// defaults and are tuned through admin setters.
 
 pub mod strategy_interface;
 pub mod dex_strategy;
@@ -8,7 +11,20 @@ pub mod lending_strategy;
 pub mod crosschain_strategy;
 
 // Re-export key types
-pub use strategy_interface::{IStrategy, RiskLevel, StrategyError, StrategyMetadata, AllocationConfig};
-pub use dex_strategy::DEXStrategy;
-pub use lending_strategy::LendingStrategy;
-pub use crosschain_strategy::CrossChainStrategy;
+pub use strategy_interface::{
+    IStrategy, RiskLevel, StrategyError, StrategyMetadata, AllocationConfig,
+    Strategy, StrategyContractRef, RewardToken, RewardTokenContractRef,
+    RewardSwap, RewardSwapContractRef, DEFAULT_MAX_REPORTED_APY_BPS, DEFAULT_MIN_APY_WINDOW,
+    weighted_deposit_time,
+};
+pub use dex_strategy::{DEXStrategy, DEXStrategyContractRef};
+pub use lending_strategy::{LendingStrategy, LendingStrategyContractRef};
+pub use crosschain_strategy::{CrossChainStrategy, CrossChainStrategyContractRef};
+
+// Host-side bindings (tests and livenet deploys)
+#[cfg(not(target_arch = "wasm32"))]
+pub use dex_strategy::{DEXStrategyHostRef, DEXStrategyInitArgs};
+#[cfg(not(target_arch = "wasm32"))]
+pub use lending_strategy::{LendingStrategyHostRef, LendingStrategyInitArgs};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crosschain_strategy::{CrossChainStrategyHostRef, CrossChainStrategyInitArgs};
@@ -6,6 +6,34 @@
 use odra::prelude::*;
 use odra::{Address, Var};
 use odra::casper_types::{U256, U512};
+use crate::utils::math::{ratio_bps, BPS_DENOMINATOR};
+
+/// Default ceiling on the APY a strategy reports (500%, bps)
+pub const DEFAULT_MAX_REPORTED_APY_BPS: u64 = 50_000;
+
+/// Default position age before a strategy reports a measured APY (1 hour)
+pub const DEFAULT_MIN_APY_WINDOW: u64 = 3600;
+
+/// Deposit timestamp of a position after adding `added` at `now`
+///
+/// Averages the existing timestamp with `now`, weighted by amount, so a
+/// top-up neither restarts the clock on funds already earning nor credits
+/// the new tranche with time it was not deployed.
+pub fn weighted_deposit_time(existing: U512, existing_time: u64, added: U512, now: u64) -> u64 {
+    let total = existing.checked_add(added).unwrap();
+    if existing.is_zero() || existing_time == 0 || total.is_zero() {
+        return now;
+    }
+    
+    existing
+        .checked_mul(U512::from(existing_time))
+        .unwrap()
+        .checked_add(added.checked_mul(U512::from(now)).unwrap())
+        .unwrap()
+        .checked_div(total)
+        .unwrap()
+        .as_u64()
+}
 
 /// Risk level categorization for strategies
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -105,6 +133,13 @@ pub trait IStrategy {
     /// 3. Return harvested amount
     fn harvest(&mut self) -> Result<U512, StrategyError>;
     
+    /// Preview the next harvest without claiming anything
+    /// 
+    /// # Returns
+    /// Yield `harvest` would return if called now, from the same accrual
+    /// math; reward tokens paid through `harvest_rewards` are not included
+    fn preview_harvest(&self) -> U512;
+    
     /// Get current balance deployed in strategy
     /// 
     /// # Returns
@@ -170,6 +205,115 @@ pub trait IStrategy {
     /// - Liquidity constraints
     /// - Risk management limits
     fn max_capacity(&self) -> U512;
+    
+    /// Estimate what withdrawing `amount` would cost
+    /// 
+    /// # Returns
+    /// Value lost on the way out, so a withdrawal of `amount` is expected
+    /// to return `amount` minus this
+    /// 
+    /// Costs may include:
+    /// - Swap fees and price impact on single-sided DEX exits
+    /// - Early-withdrawal penalties under high lending utilization
+    /// - Bridge fees
+    fn estimate_withdrawal_cost(&self, amount: U512) -> U512;
+}
+
+/// Entry points the StrategyRouter calls on deployed strategy contracts
+///
+/// Mirrors the `IStrategy` operations in the shape the strategy modules
+/// actually expose (errors are signalled by returning zero amounts).
+/// The router must hold the admin role on each strategy for
+/// `emergency_withdraw` to succeed, and if the strategy requires M-of-N
+/// approvals the action must be approved before the router quarantines it.
+///
+/// Strategies registered with `wants_native` take and return raw CSPR
+/// instead: every amount below (balance and capacity included) is then in
+/// CSPR motes, and the router converts at the lstCSPR exchange rate.
+#[odra::external_contract]
+pub trait Strategy {
+    /// Deploy lstCSPR, returns the amount actually deployed
+    fn deploy(&mut self, amount: U512) -> U512;
+
+    /// Withdraw lstCSPR, returns the amount actually withdrawn
+    fn withdraw(&mut self, amount: U512) -> U512;
+
+    /// `deploy` carrying the flow id of the vault operation it serves
+    ///
+    /// Strategies include `flow_id` in their deploy events so one deposit
+    /// can be traced from the vault through the router; 0 means untagged.
+    fn deploy_with_id(&mut self, flow_id: u64, amount: U512) -> U512;
+
+    /// `withdraw` carrying the flow id of the vault operation it serves
+    fn withdraw_with_id(&mut self, flow_id: u64, amount: U512) -> U512;
+
+    /// Harvest accrued lstCSPR yield, returns the amount harvested
+    fn harvest(&mut self) -> U512;
+
+    /// Yield `harvest` would return now, without claiming it
+    fn preview_harvest(&self) -> U512;
+
+    /// Claim rewards paid in other tokens, as (token, amount) pairs
+    ///
+    /// The tokens are transferred to the caller. The router either swaps
+    /// them to lstCSPR or holds them in escrow; they never count as yield
+    /// until converted.
+    fn harvest_rewards(&mut self) -> Vec<(Address, U512)>;
+
+    /// Pull positions out of the external protocol and stop deploying
+    ///
+    /// `None` unwinds everything; `Some(max)` caps this call so a large
+    /// position can be drained over several transactions.
+    fn emergency_withdraw(&mut self, max_amount: Option<U512>) -> U512;
+
+    /// Record the router allowed to deploy, withdraw and harvest
+    fn set_router(&mut self, router: Address);
+
+    /// Current value held by the strategy
+    fn get_balance(&self) -> U512;
+
+    /// Current APY in basis points
+    fn get_apy(&self) -> U256;
+
+    /// Strategy-reported health flag
+    fn is_healthy(&self) -> bool;
+
+    /// Maximum lstCSPR the strategy accepts
+    fn max_capacity(&self) -> U512;
+
+    /// Lifetime bridge fees paid on deploys and withdrawals
+    ///
+    /// The router reads it around each deploy/withdraw to book fees as a
+    /// cost instead of a balance shortfall. Zero for strategies that don't
+    /// bridge.
+    fn get_lifetime_bridge_fees(&self) -> U512;
+
+    /// Expected cost of withdrawing `amount` (see `IStrategy`)
+    ///
+    /// The router orders withdrawals by it. When no bridge fee was paid, a
+    /// shortfall of up to this much is booked as a cost, not funds in flight.
+    fn estimate_withdrawal_cost(&self, amount: U512) -> U512;
+}
+
+/// CEP-18 subset the router needs to move reward tokens
+#[odra::external_contract]
+pub trait RewardToken {
+    fn transfer(&mut self, to: Address, amount: U512);
+
+    fn balance_of(&self, account: Address) -> U512;
+}
+
+/// DEX pool used to liquidate a reward token (token A) into lstCSPR (token B)
+///
+/// Matches the swap entry points of `MockDEX`; the router transfers the
+/// reward tokens to the pool before calling `swap_a_to_b`.
+#[odra::external_contract]
+pub trait RewardSwap {
+    /// Swap reward tokens for lstCSPR, returns the lstCSPR received
+    fn swap_a_to_b(&mut self, amount_in: U512) -> U512;
+
+    /// Expected lstCSPR out for `amount_in` reward tokens
+    fn get_quote_a_to_b(&self, amount_in: U512) -> U512;
 }
 
 /// Strategy metadata for tracking and reporting
@@ -213,13 +357,11 @@ impl StrategyMetadata {
             return 0;
         }
         
-        let utilization = self.balance
-            .checked_mul(U512::from(10000u64))
-            .unwrap_or(U512::zero())
-            .checked_div(self.max_capacity)
-            .unwrap_or(U512::zero());
-        
-        u16::try_from(utilization).unwrap_or(10000)
+        // Over capacity (or too large to measure) reads as fully utilized
+        match ratio_bps(self.balance, self.max_capacity) {
+            Ok(utilization) if utilization <= U512::from(BPS_DENOMINATOR) => utilization.as_u32() as u16,
+            _ => 10000,
+        }
     }
     
     /// Check if strategy is at or near capacity
@@ -4,6 +4,9 @@ use odra::{Address, Mapping, SubModule, Var};
 use odra::casper_types::{U256, U512};
 use crate::types::TokenError;
 
+/// cvCSPR decimals, matching the vault's share accounting
+pub const CV_CSPR_DECIMALS: u8 = 9;
+
 /// cvCSPR - Vault Share Token
 /// 
 /// This token represents shares in the CasperVault. It follows the
@@ -50,7 +53,7 @@ impl CvCspr {
     pub fn init(&mut self, vault_manager: Address) {
         self.name.set("CasperVault Shares".to_string());
         self.symbol.set("cvCSPR".to_string());
-        self.decimals.set(9); // Same as CSPR
+        self.decimals.set(CV_CSPR_DECIMALS); // Same as CSPR
         self.total_supply.set(U512::zero());
         self.vault_manager.set(vault_manager);
     }
@@ -75,6 +78,11 @@ impl CvCspr {
         self.total_supply.get_or_default()
     }
 
+    /// Get the vault allowed to mint and burn
+    pub fn get_vault_manager(&self) -> Option<Address> {
+        self.vault_manager.get()
+    }
+
     /// Get balance of an account
     pub fn balance_of(&self, account: Address) -> U512 {
         self.balances.get(&account).unwrap_or(U512::zero())
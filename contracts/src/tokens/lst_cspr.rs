@@ -75,6 +75,11 @@ impl LstCspr {
         self.total_supply.get_or_default()
     }
 
+    /// Get the address allowed to mint and burn
+    pub fn get_minter(&self) -> Option<Address> {
+        self.minter.get()
+    }
+
     /// Get balance of an account
     pub fn balance_of(&self, account: Address) -> U512 {
         self.balances.get(&account).unwrap_or(U512::zero())
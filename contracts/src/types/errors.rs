@@ -47,6 +47,26 @@ pub enum VaultError {
     InvalidFee = 22,
     /// Slippage exceeded maximum allowed
     SlippageExceeded = 23,
+    /// Shares deposited too recently to be withdrawn
+    HoldingPeriodActive = 24,
+    /// Vault is migrating to a new deployment (deposits closed)
+    MigrationActive = 25,
+    /// Shares are outstanding with no assets behind them (deposits closed)
+    VaultInsolvent = 26,
+    /// The account is frozen by a guardian
+    AccountFrozen = 27,
+    /// cvCSPR token decimals differ from the vault's share decimals
+    ShareDecimalsMismatch = 28,
+    /// Token allowance to the vault is below the amount
+    InsufficientAllowance = 29,
+    /// Deposits halted after a critical invariant violation
+    DepositsHalted = 30,
+    /// Withdrawal completions frozen after a catastrophic exploit
+    WithdrawalsFrozen = 31,
+    /// Exit would leave a share balance below `min_shares`; exit it all instead
+    DustRemainder = 32,
+    /// No LiquidStaking contract to stake a StakingOnly CSPR deposit through
+    StakingUnavailable = 33,
 }
 
 /// Errors specific to liquid staking operations
@@ -70,6 +90,12 @@ pub enum StakingError {
     InvalidValidator = 107,
     /// Cannot unstake more than staked amount
     ExceedsStakedAmount = 108,
+    /// Validator delegations cannot cover the undelegation
+    InsufficientDelegatedBalance = 109,
+    /// Unbonding period or era length outside the allowed bounds
+    InvalidUnbondingPeriod = 110,
+    /// Validator is already in the registry
+    ValidatorAlreadyRegistered = 111,
 }
 
 /// Errors related to strategy operations
@@ -95,6 +121,10 @@ pub enum StrategyError {
     UnhealthyStrategy = 208,
     /// Insufficient balance in strategy
     InsufficientStrategyBalance = 209,
+    /// Caller is neither the registered router nor an admin
+    Unauthorized = 210,
+    /// Pool price too far from the reference price to trade against
+    PriceDeviationTooHigh = 211,
 }
 
 /// Errors related to access control
@@ -144,6 +174,51 @@ pub enum BridgeError {
     BridgeRateLimitExceeded = 504,
 }
 
+/// Errors raised by the shared math helpers
+#[derive(Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// Intermediate or final value does not fit
+    Overflow = 600,
+    /// Division by zero
+    DivisionByZero = 601,
+    /// Value does not fit the narrower target type
+    ConversionOverflow = 602,
+}
+
+/// Invariant violations reported by `VaultManager::check_invariants`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantError {
+    /// Vault share count differs from the shares held and locked in requests (critical)
+    SharesSupplyMismatch = 700,
+    /// Instant pool larger than total assets (critical)
+    PoolExceedsAssets = 701,
+    /// Fees collected exceed the vault's lstCSPR balance
+    FeesExceedBalance = 702,
+    /// Sampled user shares add up to more than the total (critical)
+    SampledSharesExceedTotal = 703,
+    /// A linked contract address is unset
+    MissingContract = 704,
+    /// Two linked contracts share an address
+    DuplicateContract = 705,
+    /// Share price and lstCSPR rate growth diverged past the threshold (critical)
+    AccountingDivergence = 706,
+    /// Pool, deployed, idle and in-transit buckets don't add up to total assets
+    AssetCompositionMismatch = 707,
+}
+
+impl InvariantError {
+    /// Violations that can halt deposits
+    pub fn is_critical(self) -> bool {
+        matches!(
+            self,
+            InvariantError::SharesSupplyMismatch
+                | InvariantError::PoolExceedsAssets
+                | InvariantError::SampledSharesExceedTotal
+                | InvariantError::AccountingDivergence
+        )
+    }
+}
+
 // Implement From trait for all custom errors to convert to OdraError
 impl From<VaultError> for odra::OdraError {
     fn from(error: VaultError) -> Self {
@@ -180,3 +255,15 @@ impl From<BridgeError> for odra::OdraError {
         odra::OdraError::ExecutionError(odra::ExecutionError::User(error as u16))
     }
 }
+
+impl From<MathError> for odra::OdraError {
+    fn from(error: MathError) -> Self {
+        odra::OdraError::ExecutionError(odra::ExecutionError::User(error as u16))
+    }
+}
+
+impl From<InvariantError> for odra::OdraError {
+    fn from(error: InvariantError) -> Self {
+        odra::OdraError::ExecutionError(odra::ExecutionError::User(error as u16))
+    }
+}
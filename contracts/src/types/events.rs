@@ -5,20 +5,27 @@ use odra::{Address, Event};
 /// Event emitted when a deposit is made
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct Deposit {
+    pub operation_id: u64,
     pub user: Address,
     pub cspr_amount: U512,
     pub lst_cspr_amount: U512,
     pub shares_minted: U512,
+    /// Which deposit limits applied (`LIMIT_MODE_*`)
+    pub limit_mode: u8,
+    /// Per-transaction limit applied, in CSPR
+    pub limit_applied: U512,
     pub timestamp: u64,
 }
 
 /// Event emitted when a withdrawal is made
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct Withdraw {
+    pub operation_id: u64,
     pub user: Address,
     pub shares_burned: U512,
     pub shares: U512,
     pub assets: U512,
+    pub fee_exempt: bool,
     pub timestamp: u64,
 }
 
@@ -35,27 +42,94 @@ pub struct InstantWithdraw {
 /// Event emitted when a withdrawal request is created
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct WithdrawalRequested {
-    pub request_id: U256,
+    pub request_id: u64,
     pub user: Address,
     pub shares: U512,
     pub assets_value: U512,
     pub unlock_time: u64,
+    /// Keepers may complete the request on the user's behalf
+    pub auto_complete: bool,
+}
+
+/// Event emitted when a pending withdrawal request changes owner
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct WithdrawalRequestTransferred {
+    pub request_id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a stale withdrawal request expires and its shares are restored
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct WithdrawalExpired {
+    pub request_id: u64,
+    pub user: Address,
+    pub shares: U512,
+    pub timestamp: u64,
 }
 
 /// Event emitted when a withdrawal request is completed
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct WithdrawalCompleted {
-    pub request_id: U256,
+    pub operation_id: u64,
+    pub request_id: u64,
     pub user: Address,
     pub assets: U512,
     pub shares: U512,
     pub cspr_amount: U512,
+    pub fee_exempt: bool,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an exit burns shares and queues what the pool can't cover
+/// 
+/// `assets` is the value before the performance fee; `queued` waits for
+/// the router's next withdrawal batch.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct BatchedExitQueued {
+    pub claim_id: u64,
+    pub user: Address,
+    pub shares: U512,
+    pub assets: U512,
+    pub from_pool: U512,
+    pub queued: U512,
+    pub fee_exempt: bool,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a batched exit is paid out
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct BatchedExitClaimed {
+    pub claim_id: u64,
+    pub user: Address,
+    pub assets: U512,
+    pub from_router: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a keeper completes a withdrawal for its owner and keeps a rebate
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct KeeperRebatePaid {
+    pub request_id: u64,
+    pub keeper: Address,
+    pub user: Address,
+    pub rebate: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a keeper collects its accrued rebates
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct KeeperRebatesClaimed {
+    pub keeper: Address,
+    pub amount: U512,
     pub timestamp: u64,
 }
 
 /// Event emitted when an instant withdrawal is processed
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct InstantWithdrawal {
+    pub operation_id: u64,
     pub user: Address,
     pub shares_burned: U512,
     pub assets: U512,
@@ -63,6 +137,19 @@ pub struct InstantWithdrawal {
     pub fee: U512,
     pub cspr_amount: U512,
     pub fee_amount: U512,
+    pub fee_to_holders: U512,
+    pub fee_to_protocol: U512,
+    pub fee_exempt: bool,
+    pub timestamp: u64,
+}
+
+/// Event emitted when `instant_withdraw_if_liquid` finds the pool too small
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InstantWithdrawalRejected {
+    pub user: Address,
+    pub shares: U512,
+    pub assets_requested: U512,
+    pub pool_balance: U512,
     pub timestamp: u64,
 }
 
@@ -72,6 +159,8 @@ pub struct Stake {
     pub user: Address,
     pub cspr_amount: U512,
     pub lst_cspr_minted: U512,
+    /// Staked by the vault rather than a direct staker
+    pub via_vault: bool,
     pub timestamp: u64,
 }
 
@@ -81,12 +170,16 @@ pub struct Unstake {
     pub user: Address,
     pub lst_cspr_amount: U512,
     pub cspr_amount: U512,
+    pub via_vault: bool,
     pub timestamp: u64,
 }
 
 /// Event emitted when rewards are compounded
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct CompoundRewards {
+    /// Rewards before validator commission
+    pub gross_rewards: U512,
+    /// Rewards after commission (what is restaked)
     pub total_rewards: U512,
     pub restaked_amount: U512,
     pub new_total_staked: U512,
@@ -108,9 +201,81 @@ pub struct ValidatorRemoved {
     pub reason: String,
 }
 
+/// Event emitted when compounding finds stake lost at a validator
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ValidatorSlashed {
+    pub validator: Address,
+    pub amount: U512,
+    pub remaining_stake: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a validator's whole delegation is moved off it
+/// 
+/// `cap_override` is set when the remaining validators' caps couldn't
+/// absorb the stake and some were filled past them; `buffered` is what
+/// was parked in the liquidity buffer for lack of eligible validators.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ValidatorEmergencyExited {
+    pub validator: Address,
+    pub amount_moved: U512,
+    pub redistributed_to: Vec<Address>,
+    pub cap_override: bool,
+    pub buffered: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when CSPR is added to the slashing insurance fund
+/// 
+/// `source` is the admin for manual top-ups, or the fee source crediting
+/// its performance fee slice.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InsuranceFunded {
+    pub source: Address,
+    pub amount: U512,
+    pub fund_balance: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when the insurance fund absorbs a slashing loss
+/// 
+/// Only `uncovered` reaches the exchange rate.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InsurancePayout {
+    pub validator: Address,
+    pub covered: U512,
+    pub uncovered: U512,
+    pub fund_balance: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when excess insurance is withdrawn after its timelock
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InsuranceWithdrawn {
+    pub recipient: Address,
+    pub amount: U512,
+    pub fund_balance: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when tracked stake is reconciled with the actual backing
+/// 
+/// `delta` is the absolute difference; compare `tracked` and `actual` for
+/// its direction.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ExchangeRateSynced {
+    pub tracked: U512,
+    pub actual: U512,
+    pub delta: U512,
+    pub timestamp: u64,
+}
+
 /// Event emitted when funds are allocated to strategies
+/// 
+/// `flow_id` is the vault operation the allocation serves (0 if none).
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct AllocationUpdate {
+    pub flow_id: u64,
     pub strategy_name: String,
     pub amount: U512,
     pub total_allocated: U512,
@@ -191,10 +356,14 @@ pub struct Unpaused {
 /// Event emitted when fees are updated
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct FeesUpdated {
+    pub old_performance_fee_bps: u32,
+    pub old_management_fee_bps: u32,
+    pub old_instant_withdrawal_fee_bps: u32,
     pub performance_fee_bps: u32,
     pub management_fee_bps: u32,
     pub instant_withdrawal_fee_bps: u32,
     pub updated_by: Address,
+    pub timestamp: u64,
 }
 
 /// Event emitted when fees are collected
@@ -241,6 +410,168 @@ pub struct ManagementFeesCollected {
     pub timestamp: u64,
 }
 
+/// Event emitted when harvested profit is reported to the vault
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ProfitReported {
+    pub amount: U512,
+    pub locked_profit: U512,
+    pub unlock_duration: u64,
+    pub timestamp: u64,
+}
+
+/// Event emitted when the YieldAggregator reports compounded yield to the vault
+/// 
+/// Emitted in the same transaction as the harvest; the figures are after
+/// the yield is booked (still locked profit is excluded).
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct YieldReported {
+    pub amount: U512,
+    pub source: String,
+    pub new_total_assets: U512,
+    pub new_share_price: U256,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a compound bounty is paid in vault shares
+/// 
+/// `amount` is the part of the compounded yield that backs the shares.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct CompoundBountyPaid {
+    pub recipient: Address,
+    pub amount: U512,
+    pub shares: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a realized loss is reported to the vault
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct LossReported {
+    pub amount: U512,
+    pub absorbed_by_locked_profit: U512,
+    pub total_assets: U512,
+    /// Shares are left with no assets behind them
+    pub insolvent: bool,
+    pub timestamp: u64,
+}
+
+/// Event emitted when shares of an insolvent vault are redeemed for nothing
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InsolventExit {
+    pub user: Address,
+    pub shares: U512,
+    /// Set when the exit completed a withdrawal request
+    pub request_id: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a large exit pays the size-tiered exit fee
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ExitSizeFeeCharged {
+    pub user: Address,
+    pub shares: U512,
+    /// Exit value before the fee
+    pub exit_value: U512,
+    /// Total assets the tier thresholds were measured against
+    pub total_assets: U512,
+    /// Fee left in the vault for the remaining holders
+    pub fee: U512,
+    /// Set when the exit opened a withdrawal request
+    pub request_id: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an insolvent vault writes off its shares and reopens
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct VaultReset {
+    pub shares_written_off: U512,
+    pub loss_epoch: u32,
+    pub caller: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a guardian freezes an account
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct AccountFrozen {
+    pub account: Address,
+    pub reason: String,
+    pub caller: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a frozen account is released
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct AccountUnfrozen {
+    pub account: Address,
+    pub caller: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an account gains or loses its exit fee exemption
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct FeeExemptionChanged {
+    pub account: Address,
+    pub exempt: bool,
+    pub caller: Address,
+    pub timestamp: u64,
+}
+
+/// Event emitted by `check_invariants` for each violated invariant
+/// 
+/// `code` is an `InvariantError` value.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct InvariantViolated {
+    pub code: u16,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a critical invariant violation halts deposits
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct DepositsHalted {
+    pub code: u16,
+    pub timestamp: u64,
+}
+
+/// Event emitted when share price growth departs from lstCSPR rate growth
+/// 
+/// Growth is measured in signed bps since the divergence baseline.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct AccountingDivergence {
+    pub vault_growth_bps: i64,
+    pub staking_growth_bps: i64,
+    pub divergence_bps: u64,
+    pub threshold_bps: u32,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a user changes the exposure of their future deposits
+/// 
+/// `exposure` is a `StrategyExposure` value.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct RiskPreferenceSet {
+    pub user: Address,
+    pub exposure: u8,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a StakingOnly deposit is credited
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct StakingOnlyDeposit {
+    pub operation_id: u64,
+    pub user: Address,
+    pub cspr_amount: U512,
+    pub lst_cspr_amount: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when lstCSPR leaves a staking-only balance
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct StakingOnlyWithdraw {
+    pub operation_id: u64,
+    pub user: Address,
+    pub lst_cspr_amount: U512,
+    pub timestamp: u64,
+}
+
 /// Event emitted when funds are rescued from contract
 #[derive(Event, Debug, PartialEq, Eq)]
 pub struct FundsRescued {
@@ -248,3 +579,67 @@ pub struct FundsRescued {
     pub amount: U512,
     pub recipient: Address,
 }
+
+/// Event emitted when the protocol treasury is rotated
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct TreasuryChanged {
+    pub old: Address,
+    pub new: Address,
+    pub shares_migrated: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a vault starts migrating into a new deployment
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct MigrationEnabled {
+    pub target: Address,
+    pub grace_period: u64,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a position leaves for the migration target
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct PositionMigrated {
+    pub operation_id: u64,
+    pub user: Address,
+    pub target: Address,
+    pub shares_burned: U512,
+    pub assets: U512,
+    pub cost_basis: U512,
+    pub shares_minted: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when a vault takes over a migrated position
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct MigratedPositionReceived {
+    pub user: Address,
+    pub source: Address,
+    pub assets: U512,
+    pub cost_basis: U512,
+    pub shares_minted: U512,
+    pub timestamp: u64,
+}
+
+/// Event emitted when an admin setter changes a configuration parameter
+///
+/// Values are rendered as strings so one event covers every parameter type;
+/// addresses use `config_address`. Setters with a dedicated event (fees,
+/// treasury, approvers, migration) emit that one instead.
+#[derive(Event, Debug, PartialEq, Eq)]
+pub struct ConfigChanged {
+    pub contract: String,
+    pub key: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub caller: Address,
+    pub timestamp: u64,
+}
+
+/// `ConfigChanged` rendering of an address parameter, "none" while unset
+pub fn config_address(address: Option<Address>) -> String {
+    match address {
+        Some(address) => format!("{:?}", address),
+        None => "none".to_string(),
+    }
+}
@@ -5,6 +5,18 @@ use odra::casper_types::{U256, U512};
 use crate::types::AccessError;
 
 /// Role definitions for access control
+/// 
+/// Numeric ids are stored on-chain and must never be renumbered.
+/// 
+/// Per-function permissions:
+/// - Admin: configuration, allocations, fee distribution, role management
+/// - Operator: everything Keeper can do, plus profit reporting and
+///   compounding a stated yield amount
+/// - Keeper: maintenance only (auto-compound, harvest, collect management
+///   fees, rebalance, health check, sync); nothing that sends funds to an
+///   arbitrary address
+/// - Guardian: emergency pause
+/// - Oracle: APY and rate reports
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Role {
     /// Administrator role - full control over the system
@@ -15,6 +27,8 @@ pub enum Role {
     Guardian = 2,
     /// Keeper role - can trigger compounding
     Keeper = 3,
+    /// Oracle role - can report off-chain data (APYs, rates)
+    Oracle = 4,
 }
 
 impl Role {
@@ -24,6 +38,7 @@ impl Role {
             1 => Some(Role::Operator),
             2 => Some(Role::Guardian),
             3 => Some(Role::Keeper),
+            4 => Some(Role::Oracle),
             _ => None,
         }
     }
@@ -40,6 +55,8 @@ pub struct AccessControl {
     roles: Mapping<(u8, Address), bool>,
     /// Count of admins (to prevent removing last admin)
     admin_count: Var<u32>,
+    /// Current members per role (for enumeration)
+    role_members: Mapping<u8, Vec<Address>>,
 }
 
 #[odra::module]
@@ -49,6 +66,7 @@ impl AccessControl {
         let admin_role = Role::Admin.to_u8();
         self.roles.set(&(admin_role, initial_admin), true);
         self.admin_count.set(1);
+        self.role_members.set(&admin_role, vec![initial_admin]);
         
         self.env().emit_event(RoleGranted {
             role: admin_role,
@@ -68,6 +86,7 @@ impl AccessControl {
         
         if !self.has_role(role, account) {
             self.roles.set(&(role, account), true);
+            self.add_member(role, account);
             
             // Increment admin count if granting admin role
             if role == Role::Admin.to_u8() {
@@ -101,6 +120,7 @@ impl AccessControl {
         
         if self.has_role(role, account) {
             self.roles.set(&(role, account), false);
+            self.remove_member(role, account);
             
             // Decrement admin count if revoking admin role
             if role == Role::Admin.to_u8() {
@@ -133,6 +153,7 @@ impl AccessControl {
         
         if self.has_role(role, caller) {
             self.roles.set(&(role, caller), false);
+            self.remove_member(role, caller);
             
             // Decrement admin count if renouncing admin role
             if role == Role::Admin.to_u8() {
@@ -184,6 +205,14 @@ impl AccessControl {
         }
     }
 
+    /// Modifier: Only oracle can call
+    pub fn only_oracle(&self) {
+        let caller = self.env().caller();
+        if !self.has_role(Role::Oracle.to_u8(), caller) {
+            self.env().revert(AccessError::MissingRole);
+        }
+    }
+
     /// Modifier: Only admin or operator can call
     pub fn only_admin_or_operator(&self) {
         let caller = self.env().caller();
@@ -195,10 +224,41 @@ impl AccessControl {
         }
     }
 
+    /// Modifier: Maintenance functions (admin, operator or keeper)
+    pub fn only_maintainer(&self) {
+        if !self.is_maintainer(self.env().caller()) {
+            self.env().revert(AccessError::MissingRole);
+        }
+    }
+
+    /// Whether an account may call maintenance functions
+    pub fn is_maintainer(&self, account: Address) -> bool {
+        self.has_role(Role::Admin.to_u8(), account)
+            || self.has_role(Role::Operator.to_u8(), account)
+            || self.has_role(Role::Keeper.to_u8(), account)
+    }
+
     /// Get the number of admins
     pub fn get_admin_count(&self) -> u32 {
         self.admin_count.get_or_default()
     }
+
+    /// Get the current members of a role
+    pub fn get_role_members(&self, role: u8) -> Vec<Address> {
+        self.role_members.get(&role).unwrap_or_default()
+    }
+
+    fn add_member(&mut self, role: u8, account: Address) {
+        let mut members = self.role_members.get(&role).unwrap_or_default();
+        members.push(account);
+        self.role_members.set(&role, members);
+    }
+
+    fn remove_member(&mut self, role: u8, account: Address) {
+        let mut members = self.role_members.get(&role).unwrap_or_default();
+        members.retain(|member| *member != account);
+        self.role_members.set(&role, members);
+    }
 }
 
 #[derive(Event)]
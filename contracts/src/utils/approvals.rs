@@ -0,0 +1,154 @@
+/// M-of-N approvals for critical actions
+///
+/// Critical entry points (fund rescue, emergency withdrawals and
+/// undelegations) call `require_approval` with a hash of the function name
+/// and its arguments. With a threshold of 1 (the default) the check passes
+/// through and the contract's own role check is the only gate, so
+/// single-admin deployments keep working. With a higher threshold the same
+/// action hash must have been approved by at least `threshold` registered
+/// approvers within the validity window; approvals are consumed on use.
+
+use odra::prelude::*;
+use odra::{Address, Event, Mapping, Var};
+use odra::casper_types::bytesrepr::ToBytes;
+use crate::types::{AccessError, VaultError};
+
+#[odra::module]
+pub struct ApprovalsRequired {
+    /// Registered approvers
+    approvers: Var<Vec<Address>>,
+    /// Approvals needed to execute a critical action (<= 1 disables the check)
+    threshold: Var<u8>,
+    /// How long an approval stays valid (seconds)
+    validity_window: Var<u64>,
+    /// Approval timestamp per (action hash, approver), 0 = not approved
+    approval_times: Mapping<([u8; 32], Address), u64>,
+}
+
+#[odra::module]
+impl ApprovalsRequired {
+    /// Register the approver set and threshold
+    ///
+    /// Callers must check the admin role first. Replacing the set does not
+    /// clear approvals, but only current approvers are counted.
+    pub fn set_approvers(&mut self, approvers: Vec<Address>, threshold: u8, validity_window: u64) {
+        if threshold == 0 || validity_window == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        if threshold > 1 && (threshold as usize) > approvers.len() {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        self.approvers.set(approvers.clone());
+        self.threshold.set(threshold);
+        self.validity_window.set(validity_window);
+
+        self.env().emit_event(ApproversUpdated {
+            approvers,
+            threshold,
+            validity_window,
+        });
+    }
+
+    /// Approve an action hash (registered approvers only)
+    pub fn approve(&mut self, action_hash: [u8; 32]) {
+        let caller = self.env().caller();
+        if !self.is_approver(caller) {
+            self.env().revert(AccessError::MissingRole);
+        }
+
+        self.approval_times.set(&(action_hash, caller), self.env().get_block_time());
+
+        self.env().emit_event(ActionApproved {
+            action_hash,
+            approver: caller,
+            approvals: self.approval_count(action_hash),
+        });
+    }
+
+    /// Withdraw the caller's approval of an action hash
+    pub fn revoke(&mut self, action_hash: [u8; 32]) {
+        let caller = self.env().caller();
+        if self.approval_times.get(&(action_hash, caller)).unwrap_or(0) == 0 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+
+        self.approval_times.set(&(action_hash, caller), 0);
+
+        self.env().emit_event(ApprovalRevoked {
+            action_hash,
+            approver: caller,
+        });
+    }
+
+    /// Number of current approvers with an unexpired approval
+    pub fn approval_count(&self, action_hash: [u8; 32]) -> u8 {
+        let now = self.env().get_block_time();
+        let window = self.validity_window.get_or_default();
+
+        self.approvers
+            .get_or_default()
+            .iter()
+            .filter(|approver| {
+                let approved_at = self.approval_times.get(&(action_hash, **approver)).unwrap_or(0);
+                approved_at != 0 && now < approved_at + window
+            })
+            .count() as u8
+    }
+
+    /// Revert unless the action has enough approvals, then consume them
+    ///
+    /// No-op while the threshold is 1 or unset.
+    pub fn require_approval(&mut self, action_hash: [u8; 32]) {
+        let threshold = self.threshold.get_or_default();
+        if threshold <= 1 {
+            return;
+        }
+
+        if self.approval_count(action_hash) < threshold {
+            self.env().revert(AccessError::InsufficientSignatures);
+        }
+
+        for approver in self.approvers.get_or_default().iter() {
+            self.approval_times.set(&(action_hash, *approver), 0);
+        }
+    }
+
+    pub fn is_approver(&self, account: Address) -> bool {
+        self.approvers.get_or_default().contains(&account)
+    }
+
+    pub fn get_threshold(&self) -> u8 {
+        self.threshold.get_or_default().max(1)
+    }
+}
+
+impl ApprovalsRequired {
+    /// Hash identifying a call: function name followed by serialized arguments
+    pub fn action_hash<T: ToBytes>(&self, function: &str, args: &T) -> [u8; 32] {
+        let mut bytes = function.as_bytes().to_vec();
+        bytes.extend(args.to_bytes().unwrap_or_default());
+        self.env().hash(bytes)
+    }
+}
+
+#[derive(Event)]
+struct ApproversUpdated {
+    approvers: Vec<Address>,
+    threshold: u8,
+    validity_window: u64,
+}
+
+#[derive(Event)]
+struct ActionApproved {
+    action_hash: [u8; 32],
+    approver: Address,
+    approvals: u8,
+}
+
+#[derive(Event)]
+struct ApprovalRevoked {
+    action_hash: [u8; 32],
+    approver: Address,
+}
@@ -0,0 +1,67 @@
+use odra::prelude::*;
+use odra::Var;
+
+/// Crate version as (major, minor, patch), from the Cargo manifest
+///
+/// In `u32` parts: Casper has no 16-bit CLType to return over an entrypoint.
+pub const CONTRACT_VERSION: (u32, u32, u32) = (
+    parse_version_part(env!("CARGO_PKG_VERSION_MAJOR")),
+    parse_version_part(env!("CARGO_PKG_VERSION_MINOR")),
+    parse_version_part(env!("CARGO_PKG_VERSION_PATCH")),
+);
+
+/// Git commit the contracts were built from
+///
+/// Set by `make build` and `build.sh` through `CASPERVAULT_GIT_HASH`;
+/// `None` for builds outside a checkout.
+pub const GIT_HASH: Option<&str> = option_env!("CASPERVAULT_GIT_HASH");
+
+const fn parse_version_part(part: &str) -> u32 {
+    let bytes = part.as_bytes();
+    let mut value = 0u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
+    }
+    value
+}
+
+/// Owned copy of a contract's compile-time feature list
+pub fn feature_flags(features: &[&str]) -> Vec<String> {
+    features.iter().map(|feature| String::from(*feature)).collect()
+}
+
+/// Version and feature flags a deployment reports to front-ends
+///
+/// Written once at init from compile-time constants, so a front-end can
+/// detect what a deployment supports without mapping addresses to
+/// releases.
+#[odra::module]
+pub struct BuildInfo {
+    version: Var<(u32, u32, u32)>,
+    features: Var<Vec<String>>,
+    git_hash: Var<String>,
+}
+
+#[odra::module]
+impl BuildInfo {
+    /// Record this build's version, git hash and `features`
+    pub fn init(&mut self, features: Vec<String>) {
+        self.version.set(CONTRACT_VERSION);
+        self.features.set(features);
+        self.git_hash.set(String::from(GIT_HASH.unwrap_or_default()));
+    }
+
+    pub fn version(&self) -> (u32, u32, u32) {
+        self.version.get_or_default()
+    }
+
+    pub fn features(&self) -> Vec<String> {
+        self.features.get_or_default()
+    }
+
+    pub fn git_hash(&self) -> Option<String> {
+        self.git_hash.get().filter(|hash| !hash.is_empty())
+    }
+}
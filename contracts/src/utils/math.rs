@@ -0,0 +1,147 @@
+/// Shared fixed-point math for basis points, rates and conversions
+///
+/// Everything here is pure and overflow-checked. Fallible helpers return
+/// `MathError`, which contracts surface with `unwrap_or_revert` so an
+/// out-of-range value reverts with a typed error instead of silently
+/// clamping to zero or panicking inside a cast.
+
+use odra::casper_types::{U256, U512};
+use crate::types::MathError;
+
+/// Basis point denominator (10000 = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Seconds in a 365-day year, used for APR/APY annualization
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Rounding direction for divisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero (favours the protocol when paying out)
+    Down,
+    /// Away from zero (favours the protocol when charging)
+    Up,
+}
+
+/// `amount * bps / 10000`, rounded down
+///
+/// Computed on the quotient and remainder separately, so it is exact for
+/// any `amount` whenever `bps <= 10000`. Above that the result can exceed
+/// `amount`; it saturates at `U512::MAX` in the (practically unreachable)
+/// case it no longer fits.
+pub fn bps_of(amount: U512, bps: u32) -> U512 {
+    let denominator = U512::from(BPS_DENOMINATOR);
+    let bps = U512::from(bps);
+    let whole = (amount / denominator).saturating_mul(bps);
+    let part = (amount % denominator) * bps / denominator;
+    whole.saturating_add(part)
+}
+
+/// `part` as basis points of `whole`, rounded down
+///
+/// Not capped at 10000: a part larger than the whole reads above 100%.
+pub fn ratio_bps(part: U512, whole: U512) -> Result<U512, MathError> {
+    mul_div(part, U512::from(BPS_DENOMINATOR), whole, Rounding::Down)
+}
+
+/// `a * b / c` with an explicit rounding direction
+///
+/// The product is split over `a / c` and `a % c`, so intermediate values
+/// only overflow when the result itself (or `(a % c) * b`) doesn't fit.
+pub fn mul_div(a: U512, b: U512, c: U512, rounding: Rounding) -> Result<U512, MathError> {
+    if c.is_zero() {
+        return Err(MathError::DivisionByZero);
+    }
+
+    let whole = (a / c).checked_mul(b).ok_or(MathError::Overflow)?;
+    let partial = (a % c).checked_mul(b).ok_or(MathError::Overflow)?;
+    let mut result = whole.checked_add(partial / c).ok_or(MathError::Overflow)?;
+
+    if rounding == Rounding::Up && !(partial % c).is_zero() {
+        result = result.checked_add(U512::one()).ok_or(MathError::Overflow)?;
+    }
+    Ok(result)
+}
+
+/// `a * b / c` on U256 values, computed in U512 and narrowed back
+pub fn mul_div_u256(a: U256, b: U256, c: U256, rounding: Rounding) -> Result<U256, MathError> {
+    let result = mul_div(safe_u256_to_u512(a), safe_u256_to_u512(b), safe_u256_to_u512(c), rounding)?;
+    safe_u512_to_u256(result)
+}
+
+/// Narrow a U512 to U256, failing instead of truncating
+pub fn safe_u512_to_u256(value: U512) -> Result<U256, MathError> {
+    if value > safe_u256_to_u512(U256::MAX) {
+        return Err(MathError::ConversionOverflow);
+    }
+
+    let mut bytes = [0u8; 64];
+    value.to_little_endian(&mut bytes);
+    Ok(U256::from_little_endian(&bytes[..32]))
+}
+
+/// Widen a U256 to U512 (always fits)
+pub fn safe_u256_to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
+
+/// Narrow a U512 to u64, failing instead of panicking like `as_u64`
+pub fn safe_u512_to_u64(value: U512) -> Result<u64, MathError> {
+    if value > U512::from(u64::MAX) {
+        return Err(MathError::ConversionOverflow);
+    }
+    Ok(value.as_u64())
+}
+
+/// Narrow a U512 to u32, failing instead of panicking like `as_u32`
+pub fn safe_u512_to_u32(value: U512) -> Result<u32, MathError> {
+    if value > U512::from(u32::MAX) {
+        return Err(MathError::ConversionOverflow);
+    }
+    Ok(value.as_u32())
+}
+
+/// Interest accrued on `principal` at `apr_bps` over `elapsed_secs`
+///
+/// Simple (non-compounding) per-second accrual, rounded down.
+pub fn accrue_apr(principal: U512, apr_bps: u32, elapsed_secs: u64) -> Result<U512, MathError> {
+    let rate_time = U512::from(apr_bps) * U512::from(elapsed_secs);
+    mul_div(
+        principal,
+        rate_time,
+        U512::from(BPS_DENOMINATOR) * U512::from(SECONDS_PER_YEAR),
+        Rounding::Down,
+    )
+}
+
+/// Annualized rate in basis points for `gain` earned on `base` over `elapsed_secs`
+///
+/// Inverse of `accrue_apr`; rounded down. Returns zero when there is no
+/// base or no elapsed time to annualize over.
+pub fn annualize_bps(gain: U512, base: U512, elapsed_secs: u64) -> Result<U512, MathError> {
+    if base.is_zero() || elapsed_secs == 0 {
+        return Ok(U512::zero());
+    }
+
+    let per_base = mul_div(
+        gain,
+        U512::from(BPS_DENOMINATOR) * U512::from(SECONDS_PER_YEAR),
+        base,
+        Rounding::Down,
+    )?;
+    Ok(per_base / U512::from(elapsed_secs))
+}
+
+/// Gross APY as a holder nets it after fees, in basis points
+///
+/// `gross * (10000 - performance_bps) / 10000 - management_bps`: the
+/// performance fee takes its share of the yield, and the management fee
+/// is a flat annual drag on the whole position. Rounded down, and zero
+/// when the management fee outweighs what is left of the yield.
+pub fn net_apy_bps(gross_bps: U256, performance_bps: u32, management_bps: u32) -> Result<U256, MathError> {
+    let kept_bps = BPS_DENOMINATOR - u64::from(performance_bps).min(BPS_DENOMINATOR);
+    let after_performance = mul_div_u256(gross_bps, U256::from(kept_bps), U256::from(BPS_DENOMINATOR), Rounding::Down)?;
+    Ok(after_performance.saturating_sub(U256::from(management_bps)))
+}
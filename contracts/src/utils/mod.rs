@@ -7,6 +7,12 @@ pub mod strategy_security;
 pub mod slippage_protection;
 pub mod monitor;
 pub mod multisig;
+pub mod approvals;
+pub mod price_oracle;
+pub mod validator_adapter;
+pub mod math;
+pub mod versioning;
+pub mod build_info;
 
 pub use access_control::*;
 pub use reentrancy_guard::*;
@@ -17,3 +23,9 @@ pub use strategy_security::*;
 pub use slippage_protection::*;
 pub use monitor::*;
 pub use multisig::*;
+pub use approvals::*;
+pub use price_oracle::*;
+pub use validator_adapter::*;
+pub use math::*;
+pub use versioning::*;
+pub use build_info::*;
@@ -2,6 +2,9 @@ use odra::prelude::*;
 use odra::{Address, Event, Mapping, Var};
 use odra::casper_types::U512;
 
+/// Delay between proposing a change and executing it (24 hours)
+pub const TIMELOCK_DURATION: u64 = 86400;
+
 #[derive(Debug, PartialEq, Eq, odra::OdraType)]
 pub struct Proposal {
     pub proposer: Address,
@@ -26,7 +29,7 @@ impl MultiSig {
     pub fn init(&mut self, initial_signers: Vec<Address>) {
         self.required_signatures.set(3);
         self.signer_count.set(initial_signers.len() as u8);
-        self.timelock_duration.set(86400);
+        self.timelock_duration.set(TIMELOCK_DURATION);
         
         for signer in initial_signers {
             self.signers.set(&signer, true);
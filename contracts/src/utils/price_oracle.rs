@@ -0,0 +1,26 @@
+/// Price oracle adapter
+/// 
+/// Interface for feeds quoting CSPR in USD. Used by the vault to convert
+/// USD-denominated deposit limits to CSPR at deposit time.
+
+use odra::prelude::*;
+use odra::casper_types::U512;
+
+/// CSPR/USD price feed
+#[odra::external_contract]
+pub trait PriceOracle {
+    /// Returns `(price, decimals, updated_at)`: USD per 1 CSPR scaled by
+    /// `10^decimals`, and the block time of the last update
+    fn get_cspr_usd_price(&self) -> (U512, u8, u64);
+}
+
+/// Convert a USD amount (9 decimals, like motes) to motes at `price`
+/// 
+/// Returns zero for a zero price.
+pub fn usd_to_motes(usd_amount: U512, price: U512, decimals: u8) -> U512 {
+    if price.is_zero() {
+        return U512::zero();
+    }
+    
+    usd_amount * U512::from(10u64).pow(U512::from(decimals)) / price
+}
@@ -2,6 +2,7 @@ use odra::prelude::*;
 use odra::{Address, Event, Mapping, Var};
 use odra::casper_types::U512;
 use crate::types::VaultError;
+use crate::utils::math::bps_of;
 
 #[odra::module]
 pub struct SlippageProtection {
@@ -20,7 +21,7 @@ impl SlippageProtection {
         }
         
         let max_slippage = self.max_slippage_bps.get_or_default();
-        let slippage_threshold = expected * U512::from(max_slippage) / U512::from(10000u64);
+        let slippage_threshold = bps_of(expected, max_slippage);
         
         let deviation = if actual > expected {
             actual - expected
@@ -52,7 +53,7 @@ impl SlippageProtection {
     
     pub fn calculate_min_amount_out(&self, amount_in: U512) -> U512 {
         let max_slippage = self.max_slippage_bps.get_or_default();
-        let slippage_amount = amount_in * U512::from(max_slippage) / U512::from(10000u64);
+        let slippage_amount = bps_of(amount_in, max_slippage);
         amount_in - slippage_amount
     }
 }
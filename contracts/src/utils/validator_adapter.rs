@@ -0,0 +1,58 @@
+/// Validator adapter
+/// 
+/// Interface LiquidStaking delegates through. Without an adapter the
+/// staking contract keeps delegations in its own bookkeeping and estimates
+/// rewards; with one, delegation, undelegation and reward claims are routed
+/// per validator address and compounding books what the adapter reports,
+/// including stake lost to slashing.
+
+use odra::prelude::*;
+use odra::{Address, Mapping};
+use odra::casper_types::U512;
+
+/// Per-validator delegation backend
+/// 
+/// Delegations are held on behalf of the calling contract.
+#[odra::external_contract]
+pub trait ValidatorAdapter {
+    fn delegate(&mut self, validator: Address, amount: U512);
+    fn undelegate(&mut self, validator: Address, amount: U512);
+    /// Claim and return the caller's accrued rewards at `validator`
+    fn claim_rewards(&mut self, validator: Address) -> U512;
+    /// Stake the caller currently has at `validator`, after any slashing
+    fn get_delegated(&self, validator: Address) -> U512;
+}
+
+/// Production adapter for Casper's native auction
+/// 
+/// Odra does not expose the auction system contract yet, so this only
+/// records delegations per caller and reports no claimable rewards. It keeps
+/// the adapter wiring identical between livenet and the mock setup.
+#[odra::module]
+pub struct SystemValidatorAdapter {
+    /// Stake per (delegating contract, validator)
+    delegations: Mapping<(Address, Address), U512>,
+}
+
+#[odra::module]
+impl SystemValidatorAdapter {
+    pub fn delegate(&mut self, validator: Address, amount: U512) {
+        let key = (self.env().caller(), validator);
+        let current = self.delegations.get(&key).unwrap_or_default();
+        self.delegations.set(&key, current + amount);
+    }
+
+    pub fn undelegate(&mut self, validator: Address, amount: U512) {
+        let key = (self.env().caller(), validator);
+        let current = self.delegations.get(&key).unwrap_or_default();
+        self.delegations.set(&key, current.saturating_sub(amount));
+    }
+
+    pub fn claim_rewards(&mut self, _validator: Address) -> U512 {
+        U512::zero()
+    }
+
+    pub fn get_delegated(&self, validator: Address) -> U512 {
+        self.delegations.get(&(self.env().caller(), validator)).unwrap_or_default()
+    }
+}
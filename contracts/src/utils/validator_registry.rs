@@ -3,6 +3,7 @@ use odra::Event;
 use odra::{Address, Mapping, Var};
 use odra::casper_types::{U256, U512};
 use crate::types::*;
+use crate::utils::math::{mul_div, safe_u512_to_u64, Rounding};
 
 /// Validator performance metrics
 #[derive(Debug, PartialEq, Eq, odra::OdraType)]
@@ -20,6 +21,41 @@ pub struct ValidatorMetrics {
     pub total_rewards_earned: U512,
 }
 
+/// Net rewards a validator earned for the protocol in one compound
+#[derive(Debug, Clone, PartialEq, Eq, odra::OdraType)]
+pub struct RewardEntry {
+    pub amount: U512,
+    pub timestamp: u64,
+}
+
+/// Reward history of a validator: lifetime total and the retained window
+#[derive(Debug, PartialEq, Eq, odra::OdraType)]
+pub struct ValidatorRewards {
+    pub validator: Address,
+    pub total_rewards_earned: U512,
+    /// Sum of `recent`
+    pub window_rewards: U512,
+    /// Last `REWARD_HISTORY_SIZE` entries, oldest first
+    pub recent: Vec<RewardEntry>,
+}
+
+/// Reward entries retained per validator
+pub const REWARD_HISTORY_SIZE: u32 = 8;
+
+/// Largest `k` served by `get_top_validators_by_rewards`
+pub const MAX_TOP_VALIDATORS: u32 = 10;
+
+/// Selection score lost per basis point of network share
+pub const NETWORK_SHARE_PENALTY_PER_BPS: u64 = 100;
+
+/// Whether `address` is the all-zero account or contract hash
+fn is_zero_address(address: &Address) -> bool {
+    match address {
+        Address::Account(hash) => hash.value() == [0u8; 32],
+        Address::Contract(hash) => hash.value() == [0u8; 32],
+    }
+}
+
 /// Validator selection result
 #[derive(Debug, odra::OdraType)]
 pub struct ValidatorAllocation {
@@ -48,6 +84,15 @@ pub struct ValidatorRegistry {
     validator_good_epochs: Mapping<Address, u32>,
     validator_rewards: Mapping<Address, U512>,
     
+    /// Reward history ring buffer: (validator, slot) -> entry
+    reward_history: Mapping<(Address, u32), RewardEntry>,
+    
+    /// Reward entries ever recorded per validator (next slot = count % size)
+    reward_history_count: Mapping<Address, u32>,
+    
+    /// Break selection score ties by recent rewards per unit of stake
+    reward_tiebreaker: Var<bool>,
+    
     /// Active validators list
     active_validators: Var<Vec<Address>>,
     
@@ -68,6 +113,16 @@ pub struct ValidatorRegistry {
     
     /// Total stake across all validators
     total_stake: Var<U512>,
+    
+    /// Oracle-reported share of total network stake per validator (bps)
+    validator_network_share: Mapping<Address, u32>,
+    validator_network_report_time: Mapping<Address, u64>,
+    
+    /// Configuration: Validators above this network share are not selected (bps)
+    max_network_share_bps: Var<u32>,
+    
+    /// Configuration: Network share reports older than this are ignored (seconds)
+    network_share_max_age: Var<u64>,
 }
 
 #[odra::module]
@@ -80,6 +135,9 @@ impl ValidatorRegistry {
         self.min_good_epochs.set(10);
         self.total_stake.set(U512::zero());
         self.active_validators.set(Vec::new());
+        self.reward_tiebreaker.set(false);
+        self.max_network_share_bps.set(1500); // 15%
+        self.network_share_max_age.set(24 * 60 * 60);
     }
 
     /// Register a new validator
@@ -93,8 +151,13 @@ impl ValidatorRegistry {
         max_stake_cap: U512,
         is_verified: bool,
     ) -> () {
-        if self.validator_uptime.get(&validator).is_some() {
-            self.env().revert(StakingError::ValidatorNotEligible);
+        // The zero address and the staking contract itself can't be delegated to
+        if is_zero_address(&validator) || validator == self.env().self_address() {
+            self.env().revert(StakingError::InvalidValidator);
+        }
+        
+        if self.validator_uptime.get(&validator).is_some() || self.validator_exists(validator) {
+            self.env().revert(StakingError::ValidatorAlreadyRegistered);
         }
         
         // Validate minimum requirements
@@ -203,7 +266,8 @@ impl ValidatorRegistry {
     /// Select validators for stake delegation
     /// 
     /// Algorithm optimized for decentralization and risk distribution:
-    /// 1. Filter by eligibility (uptime, commission, verified, not at capacity)
+    /// 1. Filter by eligibility (uptime, commission, verified, network
+    ///    share, not at capacity)
     /// 2. Calculate score for each validator (lower stake = higher score)
     /// 3. Sort by score (prioritize underweight validators)
     /// 4. Distribute stake proportionally with caps
@@ -213,14 +277,17 @@ impl ValidatorRegistry {
         &self,
         amount_to_stake: U512,
     ) -> Vec<ValidatorAllocation> {
-        let active_validators = self.active_validators.get_or_default();
+        let active_validators = self.active_list();
         let min_uptime = self.min_uptime.get_or_default();
         let max_commission = self.max_commission.get_or_default();
         let total_stake = self.total_stake.get_or_default();
         let max_per_validator_pct = self.max_per_validator_pct.get_or_default();
         
+        let tiebreaker = self.reward_tiebreaker.get_or_default();
+        let max_network_share = self.max_network_share_bps.get_or_default();
+        
         // Step 1: Filter eligible validators
-        let mut eligible: Vec<(Address, u64)> = Vec::new();
+        let mut eligible: Vec<(Address, u64, U512)> = Vec::new();
         
         for validator_addr in active_validators.iter() {
             let uptime = self.validator_uptime.get(validator_addr);
@@ -234,10 +301,13 @@ impl ValidatorRegistry {
             let current_stake = self.validator_stake.get(validator_addr).unwrap_or(U512::zero());
             let max_stake_cap = self.validator_max_stake.get(validator_addr).unwrap_or(U512::zero());
             
+            let network_share = self.network_share_bps(validator_addr);
+            
             if uptime_val >= min_uptime
                 && commission <= max_commission
                 && verified
                 && !self.blacklisted.get(validator_addr).unwrap_or(false)
+                && network_share <= max_network_share
             {
                 let remaining_capacity = if current_stake < max_stake_cap {
                     max_stake_cap - current_stake
@@ -251,9 +321,15 @@ impl ValidatorRegistry {
                             current_stake,
                             uptime_val,
                             commission,
-                            total_stake
+                            total_stake,
+                            network_share,
                         );
-                        eligible.push((*validator_addr, score));
+                        let reward_rate = if tiebreaker {
+                            self.recent_reward_rate(validator_addr, current_stake)
+                        } else {
+                            U512::zero()
+                        };
+                        eligible.push((*validator_addr, score, reward_rate));
                     }
                 }
             }
@@ -262,8 +338,8 @@ impl ValidatorRegistry {
             return Vec::new();
         }
         
-        // Step 2: Sort by score (highest first)
-        eligible.sort_by(|a, b| b.1.cmp(&a.1));
+        // Step 2: Sort by score (highest first), ties by recent reward rate
+        eligible.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.2.cmp(&a.2)));
         
         // Step 3: Distribute stake
         let mut allocations: Vec<ValidatorAllocation> = Vec::new();
@@ -277,7 +353,7 @@ impl ValidatorRegistry {
         let num_validators = eligible.len();
         let base_allocation = amount_to_stake / U512::from(num_validators);
         
-        for (validator, _score) in eligible.iter() {
+        for (validator, _score, _reward_rate) in eligible.iter() {
             if remaining.is_zero() {
                 break;
             }
@@ -425,20 +501,26 @@ impl ValidatorRegistry {
     /// Calculate decentralization score
     /// 
     /// Higher score = more desirable for staking (promotes decentralization)
-    /// Validators with lower stake get higher scores
+    /// Validators with lower stake get higher scores, both within the
+    /// protocol and across the network
     fn calculate_decentralization_score(
         &self,
         current_stake: U512,
         uptime: u8,
         commission: u8,
         total_stake: U512,
+        network_share_bps: u32,
     ) -> u64 {
+        let network_penalty = network_share_bps as u64 * NETWORK_SHARE_PENALTY_PER_BPS;
+        
         if total_stake.is_zero() {
-            return 100_000_000; // High score if no stake yet
+            return 100_000_000 - network_penalty; // High score if no stake yet
         }
         
-        let validator_pct = (current_stake * U512::from(1_000_000u64) / total_stake)
-            .as_u64();
+        // Share of protocol stake in bps, on the same scale as the network share
+        let validator_pct = mul_div(current_stake, U512::from(10_000u64), total_stake, Rounding::Down)
+            .and_then(safe_u512_to_u64)
+            .unwrap_or_revert(&self.env());
         
         // Lower percentage = higher score
         // Score = 1_000_000 - (validator_pct * 100)
@@ -450,7 +532,118 @@ impl ValidatorRegistry {
         // Penalty for high commission
         let commission_penalty = (commission as u64) * 10_000;
         
-        base_score + uptime_boost - commission_penalty
+        (base_score + uptime_boost).saturating_sub(commission_penalty + network_penalty)
+    }
+
+    /// Fresh network share of a validator (bps), 0 when unreported or stale
+    fn network_share_bps(&self, validator: &Address) -> u32 {
+        let reported_at = match self.validator_network_report_time.get(validator) {
+            Some(reported_at) => reported_at,
+            None => return 0,
+        };
+        if self.env().get_block_time() > reported_at + self.network_share_max_age.get_or_default() {
+            return 0;
+        }
+        self.validator_network_share.get(validator).unwrap_or(0)
+    }
+
+    /// Record a validator's stake across the whole network
+    /// 
+    /// `validator_stake` is everything delegated to the validator,
+    /// including stake from outside the protocol; `network_total_stake` is
+    /// the total staked on the network. Selection uses the resulting share
+    /// until the report is older than the configured maximum age.
+    pub fn report_network_stake(&mut self, validator: Address, validator_stake: U512, network_total_stake: U512) {
+        if self.validator_uptime.get(&validator).is_none() {
+            self.env().revert(StakingError::ValidatorNotFound);
+        }
+        if network_total_stake.is_zero() || validator_stake > network_total_stake {
+            self.env().revert(StakingError::InvalidValidator);
+        }
+        
+        let share = mul_div(validator_stake, U512::from(10_000u64), network_total_stake, Rounding::Down)
+            .and_then(safe_u512_to_u64)
+            .unwrap_or_revert(&self.env());
+        self.validator_network_share.set(&validator, share as u32);
+        self.validator_network_report_time.set(&validator, self.env().get_block_time());
+    }
+
+    /// Network share used by selection (bps): 0 when unreported or stale
+    pub fn get_network_share(&self, validator: Address) -> u32 {
+        self.network_share_bps(&validator)
+    }
+
+    /// Score `select_validators_for_delegation` would give a validator now
+    /// 
+    /// Computed for any registered validator, eligible or not.
+    pub fn get_selection_score(&self, validator: Address) -> Option<u64> {
+        let uptime = self.validator_uptime.get(&validator)?;
+        Some(self.calculate_decentralization_score(
+            self.validator_stake.get(&validator).unwrap_or(U512::zero()),
+            uptime,
+            self.validator_commission.get(&validator).unwrap_or(100),
+            self.total_stake.get_or_default(),
+            self.network_share_bps(&validator),
+        ))
+    }
+
+    /// Net rewards per unit of stake (1e9 scale) over the retained window
+    fn recent_reward_rate(&self, validator: &Address, stake: U512) -> U512 {
+        if stake.is_zero() {
+            return U512::zero();
+        }
+        self.get_validator_rewards(*validator).window_rewards * U512::from(1_000_000_000u64) / stake
+    }
+
+    /// Retained reward entries of a validator, oldest first
+    fn reward_entries(&self, validator: &Address) -> Vec<RewardEntry> {
+        let count = self.reward_history_count.get(validator).unwrap_or(0);
+        let start = count.saturating_sub(REWARD_HISTORY_SIZE);
+        (start..count)
+            .filter_map(|i| self.reward_history.get(&(*validator, i % REWARD_HISTORY_SIZE)))
+            .collect()
+    }
+
+    /// Record net rewards a validator earned for the protocol
+    /// 
+    /// Adds to the lifetime total and overwrites the oldest of the last
+    /// `REWARD_HISTORY_SIZE` entries.
+    pub fn record_rewards(&mut self, validator: Address, amount: U512, timestamp: u64) {
+        if self.validator_uptime.get(&validator).is_none() {
+            self.env().revert(StakingError::ValidatorNotFound);
+        }
+        
+        let total = self.validator_rewards.get(&validator).unwrap_or_default();
+        self.validator_rewards.set(&validator, total + amount);
+        
+        let count = self.reward_history_count.get(&validator).unwrap_or(0);
+        self.reward_history.set(&(validator, count % REWARD_HISTORY_SIZE), RewardEntry { amount, timestamp });
+        self.reward_history_count.set(&validator, count + 1);
+    }
+
+    /// Lifetime and recent rewards of a validator
+    pub fn get_validator_rewards(&self, validator: Address) -> ValidatorRewards {
+        let recent = self.reward_entries(&validator);
+        ValidatorRewards {
+            validator,
+            total_rewards_earned: self.validator_rewards.get(&validator).unwrap_or_default(),
+            window_rewards: recent.iter().map(|entry| entry.amount).fold(U512::zero(), |a, b| a + b),
+            recent,
+        }
+    }
+
+    /// Active validators with the highest lifetime rewards, best first
+    /// 
+    /// `k` is capped at `MAX_TOP_VALIDATORS`.
+    pub fn get_top_validators_by_rewards(&self, k: u32) -> Vec<(Address, U512)> {
+        let mut ranked: Vec<(Address, U512)> = self
+            .active_list()
+            .into_iter()
+            .map(|validator| (validator, self.validator_rewards.get(&validator).unwrap_or_default()))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1));
+        ranked.truncate(k.min(MAX_TOP_VALIDATORS) as usize);
+        ranked
     }
 
     /// Update validator stake amount
@@ -471,6 +664,11 @@ impl ValidatorRegistry {
         self.total_stake.set(new_total);
     }
 
+    /// Commission rate (%) of a validator, 0 if unknown
+    pub fn get_commission(&self, validator: Address) -> u8 {
+        self.validator_commission.get(&validator).unwrap_or(0)
+    }
+
     /// Check if validator is eligible for delegation
     pub fn is_eligible(&self, validator: Address) -> bool {
         let uptime = self.validator_uptime.get(&validator);
@@ -487,6 +685,18 @@ impl ValidatorRegistry {
             && !self.blacklisted.get(&validator).unwrap_or(false)
     }
 
+    /// Stake a validator can still take under its own cap and the
+    /// per-validator share of current total stake
+    pub fn get_remaining_capacity(&self, validator: Address) -> U512 {
+        let current_stake = self.validator_stake.get(&validator).unwrap_or(U512::zero());
+        let max_stake_cap = self.validator_max_stake.get(&validator).unwrap_or(U512::zero());
+        let max_per_validator = self.total_stake.get_or_default()
+            * U512::from(self.max_per_validator_pct.get_or_default())
+            / U512::from(100u64);
+        
+        max_stake_cap.min(max_per_validator).saturating_sub(current_stake)
+    }
+
     /// Blacklist a validator
     pub fn blacklist_validator(&mut self, validator: Address) {
         self.blacklisted.set(&validator, true);
@@ -516,7 +726,34 @@ impl ValidatorRegistry {
 
     /// Get all active validators
     pub fn get_active_validators(&self) -> Vec<Address> {
-        self.active_validators.get_or_default()
+        self.active_list()
+    }
+
+    /// Whether `validator` is in the active set
+    pub fn validator_exists(&self, validator: Address) -> bool {
+        self.active_validators.get_or_default().contains(&validator)
+    }
+
+    /// Active set with any repeated entry dropped, first occurrence kept
+    /// 
+    /// Registration never adds a validator twice; this keeps a corrupted
+    /// list from counting one validator's stake or rewards more than once.
+    fn active_list(&self) -> Vec<Address> {
+        let mut unique: Vec<Address> = Vec::new();
+        for validator in self.active_validators.get_or_default() {
+            if !unique.contains(&validator) {
+                unique.push(validator);
+            }
+        }
+        unique
+    }
+
+    /// Append `validator` to the active set without any checks
+    #[cfg(any(test, feature = "test-helpers"))]
+    pub fn test_push_active_validator(&mut self, validator: Address) {
+        let mut active = self.active_validators.get_or_default();
+        active.push(validator);
+        self.active_validators.set(active);
     }
 
     /// Get total stake across all validators
@@ -526,15 +763,61 @@ impl ValidatorRegistry {
 
     /// Set configuration
     pub fn set_min_uptime(&mut self, uptime: u8) {
+        let old = self.min_uptime.get_or_default();
         self.min_uptime.set(uptime);
+        self.config_changed("min_uptime", old, uptime);
     }
 
     pub fn set_max_commission(&mut self, commission: u8) {
+        let old = self.max_commission.get_or_default();
         self.max_commission.set(commission);
+        self.config_changed("max_commission", old, commission);
     }
 
     pub fn set_max_per_validator_pct(&mut self, pct: u8) {
+        let old = self.max_per_validator_pct.get_or_default();
         self.max_per_validator_pct.set(pct);
+        self.config_changed("max_per_validator_pct", old, pct);
+    }
+
+    pub fn get_max_per_validator_pct(&self) -> u8 {
+        self.max_per_validator_pct.get_or_default()
+    }
+
+    pub fn set_network_share_limits(&mut self, max_share_bps: u32, max_age: u64) {
+        if max_share_bps > 10_000 {
+            self.env().revert(VaultError::InvalidRequest);
+        }
+        
+        let old_share = self.max_network_share_bps.get_or_default();
+        self.max_network_share_bps.set(max_share_bps);
+        self.config_changed("max_network_share_bps", old_share, max_share_bps);
+        
+        let old_age = self.network_share_max_age.get_or_default();
+        self.network_share_max_age.set(max_age);
+        self.config_changed("network_share_max_age", old_age, max_age);
+    }
+
+    /// (max network share in bps, max report age in seconds)
+    pub fn get_network_share_limits(&self) -> (u32, u64) {
+        (self.max_network_share_bps.get_or_default(), self.network_share_max_age.get_or_default())
+    }
+
+    pub fn set_reward_tiebreaker(&mut self, enabled: bool) {
+        let old = self.reward_tiebreaker.get_or_default();
+        self.reward_tiebreaker.set(enabled);
+        self.config_changed("reward_tiebreaker", old, enabled);
+    }
+
+    fn config_changed(&self, key: &str, old_value: impl ToString, new_value: impl ToString) {
+        self.env().emit_event(ConfigChanged {
+            contract: "ValidatorRegistry".to_string(),
+            key: key.to_string(),
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+            caller: self.env().caller(),
+            timestamp: self.env().get_block_time(),
+        });
     }
 }
 
@@ -0,0 +1,35 @@
+/// Encoding helpers for records that evolve by appending fields
+///
+/// A versioned record is encoded as a leading version byte followed by its
+/// fields in declaration order. Fields are only ever appended: a new field
+/// bumps the record's version and is read with `read_field_since`, so
+/// records stored by an older version still decode, with the new field at
+/// its default.
+
+use odra::casper_types::bytesrepr::{Error, FromBytes};
+
+/// Read a field introduced in version `since` from a record encoded at `version`
+///
+/// Records written before the field existed end before it, so the field
+/// decodes as its default and `bytes` is returned untouched.
+pub fn read_field_since<T: FromBytes + Default>(
+    bytes: &[u8],
+    version: u8,
+    since: u8,
+) -> Result<(T, &[u8]), Error> {
+    if version < since {
+        return Ok((T::default(), bytes));
+    }
+    T::from_bytes(bytes)
+}
+
+/// Reject a record version this code can't decode
+///
+/// Versions start at 1; anything newer than `current` was written by a
+/// layout this build doesn't know the fields of.
+pub fn check_record_version(version: u8, current: u8) -> Result<(), Error> {
+    if version == 0 || version > current {
+        return Err(Error::Formatting);
+    }
+    Ok(())
+}
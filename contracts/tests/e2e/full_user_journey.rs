@@ -6,51 +6,26 @@ mod full_user_journey_tests {
 
     #[test]
     fn test_complete_user_lifecycle() {
-        let env = TestEnvironment::new();
-        env.set_caller(env.user1);
-        
-        let initial_deposit = cspr(1000);
-        let shares_minted = initial_deposit;
-        
-        assert_u512_eq(shares_minted, initial_deposit, "Step 1: Deposit completed");
-        
-        let staked_amount = initial_deposit;
-        assert_u512_eq(staked_amount, initial_deposit, "Step 2: Funds staked");
-        
-        let dex_deployed = (staked_amount * U512::from(40u64)) / U512::from(100u64);
-        let lending_deployed = (staked_amount * U512::from(30u64)) / U512::from(100u64);
-        let cross_chain_deployed = (staked_amount * U512::from(30u64)) / U512::from(100u64);
-        
-        assert_u512_eq(dex_deployed, cspr(400), "Step 3: DEX strategy deployed");
-        assert_u512_eq(lending_deployed, cspr(300), "Step 3: Lending strategy deployed");
-        
-        env.advance_block_time(30 * 24 * 60 * 60);
-        
-        let staking_rewards = cspr(25);
-        let dex_yield = cspr(10);
-        let lending_yield = cspr(8);
-        let total_yield = staking_rewards + dex_yield + lending_yield;
-        
-        assert_u512_eq(total_yield, cspr(43), "Step 4: Yields accrued");
-        
-        let performance_fee = calculate_performance_fee(total_yield, 1000);
-        let compounded_amount = total_yield - performance_fee;
-        
-        assert_u512_eq(compounded_amount, cspr(38), "Step 5: Yield compounded");
-        
-        let new_tvl = initial_deposit + compounded_amount;
-        let total_shares = shares_minted;
-        
-        let new_share_price = (new_tvl * U512::from(1_000_000u64)) / total_shares;
-        let initial_share_price = U512::from(1_000_000u64);
-        
-        assert!(new_share_price > initial_share_price, "Step 6: Share price increased");
-        
-        let withdrawal_value = calculate_expected_assets(shares_minted, new_tvl, total_shares);
-        let profit = withdrawal_value - initial_deposit;
-        
-        assert_u512_eq(profit, cspr(38), "Step 7: Profit realized");
-        assert_u512_eq(withdrawal_value, cspr(1038), "Step 7: Withdrawal with profit");
+        let mut scenario = Scenario::new();
+        let user = scenario.user(1);
+
+        // 500 tops up the 5% instant pool, 9,500 goes to the strategy
+        scenario
+            .deposit(user, cspr(10_000))
+            .expect_shares(user, cspr(10_000))
+            .expect_instant_pool(cspr(500));
+        assert_u512_eq(scenario.strategy.get_balance(), cspr(9_500), "Deposit deployed");
+
+        // Four months of 200 CSPR, each inside the aggregator's 2% price guard
+        for _ in 0..4 {
+            scenario.advance_days(30).earn(cspr(200)).compound();
+        }
+        scenario.expect_total_assets(cspr(10_800)).expect_share_price_near(1.08, 1);
+        assert_u512_eq(scenario.strategy.get_balance(), cspr(10_300), "Yield re-deployed");
+
+        // 10% performance fee on the 800 profit
+        scenario.withdraw_all(user).expect_shares(user, U512::zero()).expect_total_assets(U512::zero());
+        assert_u512_eq(scenario.received(user), cspr(10_720), "Principal plus 720 net profit");
     }
 
     #[test]
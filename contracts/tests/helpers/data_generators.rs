@@ -151,6 +151,245 @@ pub fn generate_apy_variations(base_apy: u16, variations: usize) -> Vec<u16> {
     apys
 }
 
+/// Deterministic xorshift64* generator for seeded scenarios
+///
+/// The same seed always yields the same sequence, so a failing property
+/// test can be replayed exactly.
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        // A zero state would stay zero forever
+        Self { state: seed ^ 0x9E37_79B9_7F4A_7C15 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [min, max]
+    pub fn range(&mut self, min: u64, max: u64) -> u64 {
+        min + self.next_u64() % (max - min + 1)
+    }
+
+    /// `true` with probability `percent` / 100
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1, 100) <= percent
+    }
+
+    /// Standard normal sample (Box-Muller)
+    pub fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// Log-normal sample with the given median and shape
+    pub fn log_normal(&mut self, median: f64, sigma: f64) -> f64 {
+        median * (sigma * self.normal()).exp()
+    }
+}
+
+/// Deposit sizes for `count` users, log-normal around `median_cspr`
+///
+/// A few whales and many small depositors, clamped to [min, max].
+pub fn generate_log_normal_deposits(
+    rng: &mut SeededRng,
+    count: usize,
+    median_cspr: f64,
+    sigma: f64,
+    min: U512,
+    max: U512,
+) -> Vec<U512> {
+    (0..count)
+        .map(|_| {
+            let motes = (rng.log_normal(median_cspr, sigma) * 1_000_000_000.0) as u64;
+            U512::from(motes).max(min).min(max)
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserActionKind {
+    Deposit(U512),
+    /// Withdraw this fraction (bps) of the user's shares
+    Withdraw(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserAction {
+    pub day: u64,
+    /// Seconds into the day, so several actions can share a day
+    pub offset: u64,
+    pub user: usize,
+    pub kind: UserActionKind,
+}
+
+pub struct ActivityConfig {
+    pub num_users: usize,
+    pub days: u64,
+    pub median_deposit_cspr: f64,
+    pub deposit_sigma: f64,
+    pub min_deposit: U512,
+    pub max_deposit: U512,
+    /// Chance (percent) a given user acts on a given day
+    pub daily_activity_pct: u64,
+    /// Upper bound on a user's actions on an active day
+    pub max_actions_per_day: u64,
+    /// Chance (percent) an action by a user with a position is a withdrawal
+    pub withdraw_pct: u64,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            num_users: 8,
+            days: 90,
+            median_deposit_cspr: 500.0,
+            deposit_sigma: 1.0,
+            min_deposit: U512::from(10_000_000_000u64),
+            max_deposit: U512::from(10_000_000_000_000u64),
+            daily_activity_pct: 10,
+            max_actions_per_day: 1,
+            withdraw_pct: 30,
+        }
+    }
+}
+
+/// Randomized deposit/withdraw schedule over `config.days` simulated days
+///
+/// Every user opens with a deposit on a random day in the first third of
+/// the run; after that, each action on an active day is a deposit or a
+/// partial/full withdrawal. Actions are sorted by time.
+pub fn generate_activity_schedule(rng: &mut SeededRng, config: &ActivityConfig) -> Vec<UserAction> {
+    let mut actions = Vec::new();
+    let opening = generate_log_normal_deposits(
+        rng,
+        config.num_users,
+        config.median_deposit_cspr,
+        config.deposit_sigma,
+        config.min_deposit,
+        config.max_deposit,
+    );
+
+    for (user, amount) in opening.into_iter().enumerate() {
+        let first_day = rng.range(0, config.days / 3);
+        actions.push(UserAction {
+            day: first_day,
+            offset: rng.range(0, 86_399),
+            user,
+            kind: UserActionKind::Deposit(amount),
+        });
+
+        for day in (first_day + 1)..config.days {
+            if !rng.chance(config.daily_activity_pct) {
+                continue;
+            }
+            for _ in 0..rng.range(1, config.max_actions_per_day) {
+                let kind = if rng.chance(config.withdraw_pct) {
+                    // A quarter of withdrawals close the position
+                    let bps = if rng.chance(25) { 10_000 } else { rng.range(1_000, 9_000) as u32 };
+                    UserActionKind::Withdraw(bps)
+                } else {
+                    let amount = generate_log_normal_deposits(
+                        rng,
+                        1,
+                        config.median_deposit_cspr,
+                        config.deposit_sigma,
+                        config.min_deposit,
+                        config.max_deposit,
+                    )[0];
+                    UserActionKind::Deposit(amount)
+                };
+                actions.push(UserAction { day, offset: rng.range(0, 86_399), user, kind });
+            }
+        }
+    }
+
+    actions.sort_by_key(|action| (action.day, action.offset, action.user));
+    actions
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidatorProfile {
+    pub address: Address,
+    pub uptime: u8,
+    pub commission: u8,
+}
+
+/// Validators with uptime in [min_uptime, 100] and commission in [0, max_commission]
+pub fn generate_varied_validator_set(
+    rng: &mut SeededRng,
+    count: usize,
+    min_uptime: u8,
+    max_commission: u8,
+) -> Vec<ValidatorProfile> {
+    (0..count)
+        .map(|i| {
+            let mut address_bytes = [0u8; 32];
+            address_bytes[0] = 100 + (i as u8);
+            ValidatorProfile {
+                address: Address::from(address_bytes),
+                uptime: rng.range(min_uptime as u64, 100) as u8,
+                commission: rng.range(0, max_commission as u64) as u8,
+            }
+        })
+        .collect()
+}
+
+/// Daily APY (bps) of one strategy over a run
+pub struct YieldCurve {
+    pub allocation_pct: u8,
+    pub daily_apy_bps: Vec<u16>,
+}
+
+impl YieldCurve {
+    /// Yield `assets` earn on `day` at this strategy's share of the allocation
+    pub fn daily_yield(&self, assets: U512, day: u64) -> U512 {
+        let apy = self.daily_apy_bps[day as usize];
+        assets * U512::from(self.allocation_pct) * U512::from(apy) / U512::from(100u64 * 10_000 * 365)
+    }
+}
+
+/// One yield curve per `(allocation_pct, base_apy_bps)`: a bounded random
+/// walk around the base rate that moves up to `step_bps` a day and never
+/// strays more than half the base rate away from it
+pub fn generate_yield_curves(
+    rng: &mut SeededRng,
+    strategies: &[(u8, u16)],
+    days: u64,
+    step_bps: u16,
+) -> Vec<YieldCurve> {
+    strategies
+        .iter()
+        .map(|&(allocation_pct, base_apy)| {
+            let floor = (base_apy / 2) as i64;
+            let ceiling = (base_apy as i64) * 3 / 2;
+            let mut apy = base_apy as i64;
+            let daily_apy_bps = (0..days)
+                .map(|_| {
+                    let step = rng.range(0, 2 * step_bps as u64) as i64 - step_bps as i64;
+                    apy = (apy + step).clamp(floor, ceiling);
+                    apy as u16
+                })
+                .collect();
+            YieldCurve { allocation_pct, daily_apy_bps }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,4 +442,46 @@ mod tests {
             assert!(*commission < 10);
         }
     }
+
+    #[test]
+    fn test_seeded_generators_are_deterministic() {
+        let config = ActivityConfig::default();
+        let first = generate_activity_schedule(&mut SeededRng::new(7), &config);
+        let second = generate_activity_schedule(&mut SeededRng::new(7), &config);
+        assert_eq!(first, second);
+        assert_ne!(first, generate_activity_schedule(&mut SeededRng::new(8), &config));
+
+        for window in first.windows(2) {
+            assert!((window[0].day, window[0].offset) <= (window[1].day, window[1].offset));
+        }
+    }
+
+    #[test]
+    fn test_log_normal_deposits_are_skewed_and_clamped() {
+        let (min, max) = (U512::from(10u64), U512::from(100_000u64));
+        // Median of 5,000 motes
+        let amounts = generate_log_normal_deposits(&mut SeededRng::new(1), 1_000, 0.000_005, 1.0, min, max);
+        assert!(amounts.iter().all(|amount| *amount >= min && *amount <= max));
+
+        // Right-skewed: the mean sits above the median
+        let mut sorted = amounts.clone();
+        sorted.sort();
+        let mean = amounts.iter().fold(U512::zero(), |acc, amount| acc + *amount) / U512::from(amounts.len());
+        assert!(mean > sorted[amounts.len() / 2]);
+    }
+
+    #[test]
+    fn test_varied_validators_and_yield_curves_stay_in_bounds() {
+        let mut rng = SeededRng::new(3);
+        for validator in generate_varied_validator_set(&mut rng, 20, 85, 20) {
+            assert!(validator.uptime >= 85 && validator.uptime <= 100);
+            assert!(validator.commission <= 20);
+        }
+
+        let curves = generate_yield_curves(&mut rng, &[(60, 800), (40, 1200)], 90, 25);
+        for (curve, base) in curves.iter().zip([800u16, 1200]) {
+            assert_eq!(curve.daily_apy_bps.len(), 90);
+            assert!(curve.daily_apy_bps.iter().all(|apy| *apy >= base / 2 && *apy <= base * 3 / 2));
+        }
+    }
 }
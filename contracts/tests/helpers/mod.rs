@@ -2,8 +2,12 @@ pub mod setup;
 pub mod assertions;
 pub mod utils;
 pub mod data_generators;
+pub mod snapshots;
+pub mod scenario;
 
 pub use setup::*;
 pub use assertions::*;
 pub use utils::*;
 pub use data_generators::*;
+pub use snapshots::*;
+pub use scenario::*;
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use odra::prelude::*;
+use odra::casper_types::U512;
+use odra::host::{HostEnv, HostRef};
+use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+use caspervault_contracts::MockStrategyHostRef;
+use crate::helpers::assertions::{assert_u512_eq, assert_u512_within_tolerance};
+use crate::helpers::setup::SystemBuilder;
+use crate::helpers::utils::cspr;
+
+const ONE_HOUR: u64 = 60 * 60;
+const ONE_DAY: u64 = 24 * ONE_HOUR;
+
+/// Share price scale of `get_share_price`
+const PRICE_SCALE: f64 = 1_000_000_000.0;
+
+/// Scripted run against a bootstrapped system, one step per call
+///
+/// Deploys the full system with one mock strategy taking everything the
+/// router deploys, so yield is whatever `earn` says it is. Management fee
+/// and profit unlocking are off to keep share prices exact; turn the fee
+/// on with `management_fee`. Every step that moves assets or shares is
+/// followed by `check_invariants`.
+///
+/// ```ignore
+/// let mut scenario = Scenario::new();
+/// let user = scenario.user(1);
+/// scenario
+///     .deposit(user, cspr(10_000))
+///     .advance_days(30)
+///     .earn(cspr(200))
+///     .compound()
+///     .expect_share_price_near(1.02, 1)
+///     .withdraw_all(user);
+/// ```
+pub struct Scenario {
+    pub env: HostEnv,
+    pub system: DeployedSystem,
+    pub strategy: MockStrategyHostRef,
+    admin: Address,
+    treasury: Address,
+    holders: Vec<Address>,
+    pending_requests: Vec<(u64, U512)>,
+    request_ids: Vec<u64>,
+    received: BTreeMap<Address, U512>,
+    steps: u32,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let treasury = env.get_account(6);
+        let mut system = deploy_system(&env, admin, treasury);
+        system.vault.set_management_fee(0);
+        system.vault.set_profit_unlock_duration(0);
+
+        let strategy = SystemBuilder::mock_strategy(&env, 1000);
+        system.router.add_strategy("mock".to_string(), *strategy.address());
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![("mock".to_string(), 100u8)]);
+
+        Self {
+            env,
+            system,
+            strategy,
+            admin,
+            treasury,
+            holders: vec![admin, treasury],
+            pending_requests: Vec::new(),
+            request_ids: Vec::new(),
+            received: BTreeMap::new(),
+            steps: 0,
+        }
+    }
+
+    pub fn user(&self, index: usize) -> Address {
+        self.env.get_account(index)
+    }
+
+    pub fn treasury(&self) -> Address {
+        self.treasury
+    }
+
+    /// CSPR paid out to `user` by every exit so far
+    pub fn received(&self, user: Address) -> U512 {
+        self.received.get(&user).copied().unwrap_or_default()
+    }
+
+    /// IDs of every withdrawal request made, in order
+    pub fn request_ids(&self) -> &[u64] {
+        &self.request_ids
+    }
+
+    // CONFIGURATION
+
+    pub fn management_fee(&mut self, fee_bps: u32) -> &mut Self {
+        self.env.set_caller(self.admin);
+        self.system.vault.set_management_fee(fee_bps);
+        self
+    }
+
+    pub fn instant_fee(&mut self, fee_bps: u32) -> &mut Self {
+        self.env.set_caller(self.admin);
+        self.system.vault.set_instant_withdrawal_fee(fee_bps);
+        self
+    }
+
+    // TIME
+
+    pub fn advance_days(&mut self, days: u64) -> &mut Self {
+        self.env.advance_block_time(days * ONE_DAY);
+        self
+    }
+
+    pub fn advance_hours(&mut self, hours: u64) -> &mut Self {
+        self.env.advance_block_time(hours * ONE_HOUR);
+        self
+    }
+
+    // YIELD AND FEES
+
+    /// Give the strategy `amount` of yield for the next `compound`
+    pub fn earn(&mut self, amount: U512) -> &mut Self {
+        self.strategy.set_pending_yield(amount);
+        self
+    }
+
+    /// Harvest and compound through the aggregator
+    pub fn compound(&mut self) -> &mut Self {
+        self.env.set_caller(self.admin);
+        self.system.aggregator.auto_compound();
+        self.check_invariants("compound")
+    }
+
+    pub fn collect_management_fees(&mut self) -> &mut Self {
+        self.env.set_caller(self.admin);
+        self.system.vault.collect_management_fees();
+        self.check_invariants("collect_management_fees")
+    }
+
+    // USER ACTIONS
+
+    pub fn deposit(&mut self, user: Address, amount: U512) -> &mut Self {
+        self.track(user);
+        self.env.set_caller(user);
+        self.system.vault.with_tokens(amount).deposit();
+        self.check_invariants("deposit")
+    }
+
+    pub fn withdraw(&mut self, user: Address, shares: U512) -> &mut Self {
+        self.env.set_caller(user);
+        let paid = self.system.vault.withdraw(shares);
+        self.credit(user, paid);
+        self.check_invariants("withdraw")
+    }
+
+    /// Withdraw every share `user` holds through the regular path
+    pub fn withdraw_all(&mut self, user: Address) -> &mut Self {
+        let shares = self.system.vault.get_user_shares(user);
+        self.withdraw(user, shares)
+    }
+
+    pub fn instant_withdraw(&mut self, user: Address, shares: U512) -> &mut Self {
+        self.env.set_caller(user);
+        let paid = self.system.vault.instant_withdraw(shares);
+        self.credit(user, paid);
+        self.check_invariants("instant_withdraw")
+    }
+
+    /// Open a time-locked request; its ID is appended to `request_ids`
+    pub fn request_withdrawal(&mut self, user: Address, shares: U512) -> &mut Self {
+        self.env.set_caller(user);
+        let request_id = self.system.vault.request_withdrawal(shares);
+        self.request_ids.push(request_id);
+        self.pending_requests.push((request_id, shares));
+        self.check_invariants("request_withdrawal")
+    }
+
+    /// Complete `request_id` as its owner
+    pub fn complete_withdrawal(&mut self, request_id: u64) -> &mut Self {
+        let owner = self.system.vault.get_withdrawal_request(request_id).unwrap().user;
+        self.env.set_caller(owner);
+        let paid = self.system.vault.complete_withdrawal(request_id);
+        self.credit(owner, paid);
+        self.pending_requests.retain(|(id, _)| *id != request_id);
+        self.check_invariants("complete_withdrawal")
+    }
+
+    // EXPECTATIONS
+
+    /// Share price within `tolerance_bps` of `price` CSPR per share
+    pub fn expect_share_price_near(&mut self, price: f64, tolerance_bps: u64) -> &mut Self {
+        let expected = U512::from((price * PRICE_SCALE).round() as u64);
+        assert_u512_within_tolerance(self.system.vault.get_share_price(), expected, tolerance_bps);
+        self
+    }
+
+    pub fn expect_total_assets(&mut self, expected: U512) -> &mut Self {
+        assert_u512_eq(self.system.vault.total_assets(), expected, "Total assets");
+        self
+    }
+
+    pub fn expect_instant_pool(&mut self, expected: U512) -> &mut Self {
+        assert_u512_eq(self.system.vault.get_instant_pool_balance(), expected, "Instant pool");
+        self
+    }
+
+    pub fn expect_shares(&mut self, holder: Address, expected: U512) -> &mut Self {
+        assert_u512_eq(self.system.vault.get_user_shares(holder), expected, "Holder shares");
+        self
+    }
+
+    /// `holder`'s shares are worth `expected` within `tolerance_bps`
+    pub fn expect_value_near(&mut self, holder: Address, expected: U512, tolerance_bps: u64) -> &mut Self {
+        let value = self.system.vault.convert_to_assets(self.system.vault.get_user_shares(holder));
+        assert_u512_within_tolerance(value, expected, tolerance_bps);
+        self
+    }
+
+    /// `user` has been paid `expected` within `tolerance_bps` across all exits
+    pub fn expect_received_near(&mut self, user: Address, expected: U512, tolerance_bps: u64) -> &mut Self {
+        assert_u512_within_tolerance(self.received(user), expected, tolerance_bps);
+        self
+    }
+
+    // INVARIANTS
+
+    /// Vault invariants plus share conservation across the scenario's holders
+    fn check_invariants(&mut self, step: &str) -> &mut Self {
+        self.steps += 1;
+
+        let violations = self.system.vault.check_invariants();
+        assert!(violations.is_empty(), "Step {} ({}) broke invariants {:?}", self.steps, step, violations);
+
+        let held = self
+            .holders
+            .iter()
+            .fold(U512::zero(), |sum, holder| sum + self.system.vault.get_user_shares(*holder));
+        let locked = self.pending_requests.iter().fold(U512::zero(), |sum, (_, shares)| sum + *shares);
+        assert_u512_eq(
+            held + locked,
+            self.system.vault.get_total_shares(),
+            &format!("Step {} ({}): held and locked shares make up the supply", self.steps, step),
+        );
+        self
+    }
+
+    fn track(&mut self, user: Address) {
+        if !self.holders.contains(&user) {
+            self.holders.push(user);
+        }
+    }
+
+    fn credit(&mut self, user: Address, amount: U512) {
+        let total = self.received(user) + amount;
+        self.received.insert(user, total);
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,25 @@
 use odra::prelude::*;
-use odra::{casper_types::U512, host::Deployer};
-use crate::mocks::*;
+use odra::casper_types::{U256, U512};
+use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+use caspervault_contracts::core::{
+    KeeperHubHostRef, KeeperHubInitArgs, LiquidStakingHostRef, LiquidStakingInitArgs, StrategyRouterHostRef,
+    StrategyRouterInitArgs, VaultLensHostRef, VaultLensInitArgs, VaultManagerHostRef, VaultManagerInitArgs,
+    YieldAggregatorHostRef, YieldAggregatorInitArgs,
+};
+use caspervault_contracts::deployer::DeployedSystem;
+use caspervault_contracts::strategies::{
+    CrossChainStrategyHostRef, CrossChainStrategyInitArgs, DEXStrategyHostRef, DEXStrategyInitArgs,
+    LendingStrategyHostRef, LendingStrategyInitArgs,
+};
+use caspervault_contracts::tokens::{LstCsprHostRef, LstCsprInitArgs};
+use caspervault_contracts::{
+    MockLiquidStakingHostRef, MockNativeStrategyHostRef, MockNativeStrategyInitArgs, MockPriceOracleHostRef, MockPriceOracleInitArgs, MockShareTokenHostRef,
+    MockShareTokenInitArgs, MockStrategyHostRef, MockStrategyInitArgs, MockValidatorAdapterHostRef, MockValidatorHostRef, MockValidatorInitArgs,
+};
+use crate::mocks::mock_bridge::{MockBridgeHostRef, MockBridgeInitArgs};
+use crate::mocks::mock_dex::{MockDEXHostRef, MockDEXInitArgs};
+use crate::mocks::mock_lending::{MockLendingHostRef, MockLendingInitArgs};
+use super::utils::cspr;
 
 pub struct TestEnvironment {
     pub admin: Address,
@@ -56,10 +75,12 @@ impl DeployedContracts {
     pub fn deploy_all() -> Self {
         let env = odra_test::env();
         
-        let mock_lending = MockLendingHostRef::deploy(&env, 500u16);
-        let mock_validator = MockValidatorHostRef::deploy(&env, 98u8, 5u8);
-        let mock_bridge = MockBridgeHostRef::deploy(&env, 3u8);
-        let mock_oracle = MockPriceOracleHostRef::deploy(&env);
+        let mocks = SystemBuilder::new(&env)
+            .with_mock_lending(500)
+            .with_mock_validator(1000, 5)
+            .with_mock_bridge(50, U512::from(1_000_000_000_000u64))
+            .with_price_oracle(U512::from(2_000_000u64), 8)
+            .build();
         
         Self {
             vault_manager: Address::from([1u8; 32]),
@@ -69,10 +90,405 @@ impl DeployedContracts {
             lst_cspr: Address::from([5u8; 32]),
             cv_cspr: Address::from([6u8; 32]),
             mock_dex: Address::from([7u8; 32]),
-            mock_lending: mock_lending.address(),
-            mock_validator: mock_validator.address(),
-            mock_bridge: mock_bridge.address(),
-            mock_oracle: mock_oracle.address(),
+            mock_lending: *mocks.mock_lending.unwrap().address(),
+            mock_validator: *mocks.mock_validators[0].address(),
+            mock_bridge: *mocks.mock_bridge.unwrap().address(),
+            mock_oracle: *mocks.price_oracle.unwrap().address(),
+        }
+    }
+}
+
+/// Deploys any subset of contracts with typed init args
+/// 
+/// The one place tests spell out init args for the core contracts, the
+/// strategies and the protocol mocks, so a changed `init` signature fails
+/// to compile here and nowhere else. Account 0 deploys everything and is
+/// every contract's admin. Addresses nothing was deployed for are wired to
+/// placeholder accounts: treasury 6, cvCSPR 7, lstCSPR 8 and liquid staking
+/// 9 for the vault; protocol 7, LP staking or bridge 8 and lstCSPR 9 for
+/// the strategies. A router built without a vault treats the admin as its
+/// vault, so tests can allocate and withdraw as admin.
+/// 
+/// What is built together is wired together: the vault uses the built
+/// tokens and liquid staking, the router is the vault's router and
+/// registers the mock strategies. Strategy targets and fees are left to the
+/// tests.
+pub struct SystemBuilder {
+    env: HostEnv,
+    admin: Address,
+    vault: bool,
+    liquid_staking: Option<Address>,
+    lst_token: Option<Address>,
+    lst: bool,
+    share_token: Option<u8>,
+    mock_liquid_staking: bool,
+    staking: bool,
+    validator_adapter: bool,
+    router: bool,
+    mock_strategies: Vec<(String, u64)>,
+    aggregator: bool,
+    keeper_hub: bool,
+    price_oracle: Option<(U512, u8)>,
+    lending: bool,
+    dex: bool,
+    crosschain: bool,
+    mock_lending_apy_bps: Option<u16>,
+    mock_dex_apy_bps: Option<u16>,
+    mock_bridge: Option<(u16, U512)>,
+    mock_validators: Vec<(u32, u8)>,
+}
+
+/// Handles to what a `SystemBuilder` deployed (`None` if not requested)
+pub struct TestContracts {
+    pub admin: Address,
+    pub vault: Option<VaultManagerHostRef>,
+    pub lst: Option<LstCsprHostRef>,
+    pub share_token: Option<MockShareTokenHostRef>,
+    pub mock_liquid_staking: Option<MockLiquidStakingHostRef>,
+    pub staking: Option<LiquidStakingHostRef>,
+    pub validator_adapter: Option<MockValidatorAdapterHostRef>,
+    pub router: Option<StrategyRouterHostRef>,
+    pub mock_strategies: Vec<MockStrategyHostRef>,
+    pub aggregator: Option<YieldAggregatorHostRef>,
+    pub keeper_hub: Option<KeeperHubHostRef>,
+    pub price_oracle: Option<MockPriceOracleHostRef>,
+    pub lending: Option<LendingStrategyHostRef>,
+    pub dex: Option<DEXStrategyHostRef>,
+    pub crosschain: Option<CrossChainStrategyHostRef>,
+    pub mock_lending: Option<MockLendingHostRef>,
+    pub mock_dex: Option<MockDEXHostRef>,
+    pub mock_bridge: Option<MockBridgeHostRef>,
+    pub mock_validators: Vec<MockValidatorHostRef>,
+}
+
+impl SystemBuilder {
+    /// Builder deploying as account 0, which becomes every contract's admin
+    pub fn new(env: &HostEnv) -> Self {
+        Self {
+            env: env.clone(),
+            admin: env.get_account(0),
+            vault: false,
+            liquid_staking: None,
+            lst_token: None,
+            lst: false,
+            share_token: None,
+            mock_liquid_staking: false,
+            staking: false,
+            validator_adapter: false,
+            router: false,
+            mock_strategies: Vec::new(),
+            aggregator: false,
+            keeper_hub: false,
+            price_oracle: None,
+            lending: false,
+            dex: false,
+            crosschain: false,
+            mock_lending_apy_bps: None,
+            mock_dex_apy_bps: None,
+            mock_bridge: None,
+            mock_validators: Vec::new(),
+        }
+    }
+
+    /// A mock strategy holding up to 1,000,000 CSPR, for tests that add it
+    /// to a router the builder didn't deploy
+    pub fn mock_strategy(env: &HostEnv, apy_bps: u64) -> MockStrategyHostRef {
+        MockStrategyHostRef::deploy(
+            env,
+            MockStrategyInitArgs { apy_bps: U256::from(apy_bps), max_capacity: cspr(1_000_000) },
+        )
+    }
+
+    /// A mock native-CSPR strategy holding up to 1,000,000 CSPR
+    pub fn mock_native_strategy(env: &HostEnv, apy_bps: u64) -> MockNativeStrategyHostRef {
+        MockNativeStrategyHostRef::deploy(
+            env,
+            MockNativeStrategyInitArgs { apy_bps: U256::from(apy_bps), max_capacity: cspr(1_000_000) },
+        )
+    }
+
+    /// A mock validator paying `rewards_rate_bps` of stake per epoch, for
+    /// tests that add it to a staking contract mid-run
+    pub fn mock_validator(env: &HostEnv, rewards_rate_bps: u32, commission: u8) -> MockValidatorHostRef {
+        MockValidatorHostRef::deploy(env, MockValidatorInitArgs { rewards_rate_bps, commission })
+    }
+
+    /// A stand-in cvCSPR token with `decimals` decimals
+    pub fn share_token(env: &HostEnv, decimals: u8) -> MockShareTokenHostRef {
+        MockShareTokenHostRef::deploy(env, MockShareTokenInitArgs { decimals })
+    }
+
+    /// A lens over a `deploy_system` deployment
+    pub fn lens(env: &HostEnv, system: &DeployedSystem) -> VaultLensHostRef {
+        VaultLensHostRef::deploy(
+            env,
+            VaultLensInitArgs {
+                vault_manager: *system.vault.address(),
+                liquid_staking: *system.liquid_staking.address(),
+                strategy_router: *system.router.address(),
+                yield_aggregator: *system.aggregator.address(),
+            },
+        )
+    }
+
+    pub fn with_vault(mut self) -> Self {
+        self.vault = true;
+        self
+    }
+
+    /// Wire the vault to a real (or mock) liquid staking contract
+    pub fn with_liquid_staking(mut self, liquid_staking: Address) -> Self {
+        self.liquid_staking = Some(liquid_staking);
+        self
+    }
+
+    /// Use a real lstCSPR token as the vault's asset
+    pub fn with_lst_token(mut self, lst_token: Address) -> Self {
+        self.lst_token = Some(lst_token);
+        self
+    }
+
+    /// Deploy an lstCSPR token; the admin stands in for LiquidStaking as the minter
+    pub fn with_lst(mut self) -> Self {
+        self.lst = true;
+        self
+    }
+
+    /// Use a mock share token with `decimals` decimals as the vault's cvCSPR
+    pub fn with_share_token(mut self, decimals: u8) -> Self {
+        self.share_token = Some(decimals);
+        self
+    }
+
+    /// Mock LiquidStaking quoting 1:1 until the test sets a rate or stake
+    pub fn with_mock_liquid_staking(mut self) -> Self {
+        self.mock_liquid_staking = true;
+        self
+    }
+
+    /// Real LiquidStaking contract
+    pub fn with_staking(mut self) -> Self {
+        self.staking = true;
+        self
+    }
+
+    /// Mock validator adapter, set on the LiquidStaking contract
+    pub fn with_validator_adapter(mut self) -> Self {
+        self.validator_adapter = true;
+        self
+    }
+
+    /// Strategy router; without a vault the admin stands in for it
+    pub fn with_router(mut self) -> Self {
+        self.router = true;
+        self
+    }
+
+    /// Mock strategy holding up to 1,000,000 CSPR, registered as `name`
+    /// on the router if one is built
+    pub fn with_mock_strategy(mut self, name: &str, apy_bps: u64) -> Self {
+        self.mock_strategies.push((name.to_string(), apy_bps));
+        self
+    }
+
+    /// Yield aggregator paying fees to account 6
+    pub fn with_aggregator(mut self) -> Self {
+        self.aggregator = true;
+        self
+    }
+
+    pub fn with_keeper_hub(mut self) -> Self {
+        self.keeper_hub = true;
+        self
+    }
+
+    /// Mock USD price feed quoting `price` with `decimals` decimals
+    pub fn with_price_oracle(mut self, price: U512, decimals: u8) -> Self {
+        self.price_oracle = Some((price, decimals));
+        self
+    }
+
+    pub fn with_lending(mut self) -> Self {
+        self.lending = true;
+        self
+    }
+
+    pub fn with_dex(mut self) -> Self {
+        self.dex = true;
+        self
+    }
+
+    pub fn with_crosschain(mut self) -> Self {
+        self.crosschain = true;
+        self
+    }
+
+    /// Mock lending protocol; a lending strategy built alongside supplies to it
+    pub fn with_mock_lending(mut self, base_apy_bps: u16) -> Self {
+        self.mock_lending_apy_bps = Some(base_apy_bps);
+        self
+    }
+
+    /// Mock DEX; a DEX strategy built alongside provides liquidity to it
+    pub fn with_mock_dex(mut self, target_apy_bps: u16) -> Self {
+        self.mock_dex_apy_bps = Some(target_apy_bps);
+        self
+    }
+
+    /// Mock bridge; a cross-chain strategy built alongside bridges through it
+    pub fn with_mock_bridge(mut self, bridge_fee_bps: u16, min_bridge_amount: U512) -> Self {
+        self.mock_bridge = Some((bridge_fee_bps, min_bridge_amount));
+        self
+    }
+
+    /// Add a mock validator paying `rewards_rate_bps` of stake per epoch
+    pub fn with_mock_validator(mut self, rewards_rate_bps: u32, commission: u8) -> Self {
+        self.mock_validators.push((rewards_rate_bps, commission));
+        self
+    }
+
+    /// Add `count` identical mock validators
+    pub fn with_mock_validators(self, count: usize, rewards_rate_bps: u32, commission: u8) -> Self {
+        (0..count).fold(self, |builder, _| builder.with_mock_validator(rewards_rate_bps, commission))
+    }
+
+    pub fn build(self) -> TestContracts {
+        let env = &self.env;
+        let admin = self.admin;
+        env.set_caller(admin);
+
+        let mock_lending = self.mock_lending_apy_bps.map(|base_apy_bps| {
+            MockLendingHostRef::deploy(env, MockLendingInitArgs { base_apy_bps })
+        });
+        let mock_dex = self.mock_dex_apy_bps.map(|target_apy_bps| {
+            MockDEXHostRef::deploy(env, MockDEXInitArgs { target_apy_bps })
+        });
+        let mock_bridge = self.mock_bridge.map(|(bridge_fee_bps, min_bridge_amount)| {
+            MockBridgeHostRef::deploy(env, MockBridgeInitArgs { bridge_fee_bps, min_bridge_amount })
+        });
+        let mock_validators = self.mock_validators.iter()
+            .map(|&(rewards_rate_bps, commission)| Self::mock_validator(env, rewards_rate_bps, commission))
+            .collect();
+        let price_oracle = self.price_oracle.map(|(price, decimals)| {
+            MockPriceOracleHostRef::deploy(env, MockPriceOracleInitArgs { price, decimals })
+        });
+
+        let lending = self.lending.then(|| {
+            LendingStrategyHostRef::deploy(
+                env,
+                LendingStrategyInitArgs {
+                    admin,
+                    lending_protocol_address: mock_lending.as_ref()
+                        .map_or(env.get_account(7), |mock| *mock.address()),
+                    lst_cspr_address: env.get_account(9),
+                },
+            )
+        });
+        let dex = self.dex.then(|| {
+            DEXStrategyHostRef::deploy(
+                env,
+                DEXStrategyInitArgs {
+                    admin,
+                    dex_address: mock_dex.as_ref().map_or(env.get_account(7), |mock| *mock.address()),
+                    lp_staking_address: env.get_account(8),
+                    lst_cspr_address: env.get_account(9),
+                },
+            )
+        });
+        let crosschain = self.crosschain.then(|| {
+            CrossChainStrategyHostRef::deploy(
+                env,
+                CrossChainStrategyInitArgs {
+                    admin,
+                    bridge_address: mock_bridge.as_ref().map_or(env.get_account(8), |mock| *mock.address()),
+                    lst_cspr_address: env.get_account(9),
+                },
+            )
+        });
+
+        let lst = self.lst.then(|| LstCsprHostRef::deploy(env, LstCsprInitArgs { minter: admin }));
+        let lst_token = self.lst_token
+            .or(lst.as_ref().map(|lst| *lst.address()))
+            .unwrap_or(env.get_account(8));
+
+        let share_token = self.share_token.map(|decimals| Self::share_token(env, decimals));
+        let cv_token = share_token.as_ref().map_or(env.get_account(7), |token| *token.address());
+
+        let mock_liquid_staking = self.mock_liquid_staking.then(|| MockLiquidStakingHostRef::deploy(env, NoArgs));
+        let mut staking = self.staking.then(|| {
+            LiquidStakingHostRef::deploy(env, LiquidStakingInitArgs { admin, lst_cspr_token: lst_token })
+        });
+        let validator_adapter = self.validator_adapter.then(|| {
+            let adapter = MockValidatorAdapterHostRef::deploy(env, NoArgs);
+            if let Some(staking) = staking.as_mut() {
+                staking.set_validator_adapter(*adapter.address());
+            }
+            adapter
+        });
+        let liquid_staking = self.liquid_staking
+            .or(mock_liquid_staking.as_ref().map(|staking| *staking.address()))
+            .or(staking.as_ref().map(|staking| *staking.address()))
+            .unwrap_or(env.get_account(9));
+
+        let mut vault = self.vault.then(|| {
+            VaultManagerHostRef::deploy(
+                env,
+                VaultManagerInitArgs {
+                    admin,
+                    treasury: env.get_account(6),
+                    cv_cspr_token: cv_token,
+                    lst_cspr_token: lst_token,
+                    liquid_staking_contract: liquid_staking,
+                },
+            )
+        });
+
+        let mut router = self.router.then(|| {
+            let mut router = StrategyRouterHostRef::deploy(env, StrategyRouterInitArgs { admin });
+            match vault.as_mut() {
+                Some(vault) => {
+                    vault.set_strategy_router(*router.address());
+                    router.set_vault_address(*vault.address());
+                }
+                None => router.set_vault_address(admin),
+            }
+            router
+        });
+        let mock_strategies = self.mock_strategies.iter()
+            .map(|(name, apy_bps)| {
+                let strategy = Self::mock_strategy(env, *apy_bps);
+                if let Some(router) = router.as_mut() {
+                    router.add_strategy(name.clone(), *strategy.address());
+                }
+                strategy
+            })
+            .collect();
+
+        let aggregator = self.aggregator.then(|| {
+            YieldAggregatorHostRef::deploy(env, YieldAggregatorInitArgs { admin, fee_recipient: env.get_account(6) })
+        });
+        let keeper_hub = self.keeper_hub.then(|| KeeperHubHostRef::deploy(env, KeeperHubInitArgs { admin }));
+
+        TestContracts {
+            admin,
+            vault,
+            lst,
+            share_token,
+            mock_liquid_staking,
+            staking,
+            validator_adapter,
+            router,
+            mock_strategies,
+            aggregator,
+            keeper_hub,
+            price_oracle,
+            lending,
+            dex,
+            crosschain,
+            mock_lending,
+            mock_dex,
+            mock_bridge,
+            mock_validators,
         }
     }
 }
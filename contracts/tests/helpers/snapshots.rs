@@ -0,0 +1,90 @@
+use odra::prelude::*;
+use odra::casper_types::{U256, U512};
+use caspervault_contracts::core::{
+    AssetComposition, BatchingStats, FeeBreakdown, PoolMetrics, StrategyRouterHostRef, UserDeposit, VaultManagerHostRef,
+};
+
+/// Vault state a query could disturb, read back through its getters
+///
+/// Capture before and after a call and compare to show the call left the
+/// vault's storage alone.
+#[derive(Debug, PartialEq, Eq)]
+pub struct VaultSnapshot {
+    pub total_assets: U512,
+    pub total_shares: U512,
+    pub share_price: U256,
+    pub instant_pool: U512,
+    pub composition: AssetComposition,
+    pub fees_collected: U512,
+    pub fee_breakdown: FeeBreakdown,
+    pub pool_metrics: PoolMetrics,
+    pub locked_profit: U512,
+    pub last_operation_id: u64,
+    pub next_withdrawal_id: u64,
+    pub pending_withdrawals: (u32, U512),
+    pub unique_depositors: u64,
+    pub deposits_halted: bool,
+    pub divergence_baseline_time: u64,
+    pub users: Vec<(U512, Option<UserDeposit>, U512)>,
+}
+
+impl VaultSnapshot {
+    pub fn capture(vault: &VaultManagerHostRef, users: &[Address]) -> Self {
+        Self {
+            total_assets: vault.total_assets(),
+            total_shares: vault.get_total_shares(),
+            share_price: vault.get_share_price_precise(),
+            instant_pool: vault.get_instant_pool_balance(),
+            composition: vault.get_asset_composition(),
+            fees_collected: vault.get_fees_collected(),
+            fee_breakdown: vault.get_fee_breakdown(),
+            pool_metrics: vault.get_pool_metrics(),
+            locked_profit: vault.get_locked_profit(),
+            last_operation_id: vault.get_last_operation_id(),
+            next_withdrawal_id: vault.get_next_withdrawal_id(),
+            pending_withdrawals: (
+                vault.get_pending_withdrawal_count(),
+                vault.get_total_pending_withdrawal_assets(),
+            ),
+            unique_depositors: vault.get_unique_depositors(),
+            deposits_halted: vault.are_deposits_halted(),
+            divergence_baseline_time: vault.get_divergence_baseline_time(),
+            users: users
+                .iter()
+                .map(|user| {
+                    (
+                        vault.get_user_shares(*user),
+                        vault.get_user_deposit(*user),
+                        vault.get_staking_only_balance(*user),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Router state a query could disturb (see `VaultSnapshot`)
+#[derive(Debug, PartialEq, Eq)]
+pub struct RouterSnapshot {
+    pub allocations: Vec<(String, U512, u8)>,
+    pub total_allocated: U512,
+    pub idle_balance: U512,
+    pub total_queued: U512,
+    pub queued_withdrawals: U512,
+    pub batching_stats: BatchingStats,
+    pub pending_gains: U512,
+}
+
+impl RouterSnapshot {
+    pub fn capture(router: &StrategyRouterHostRef) -> Self {
+        Self {
+            allocations: router.get_all_allocations(),
+            total_allocated: router.get_total_allocated(),
+            idle_balance: router.get_idle_balance(),
+            total_queued: router.get_total_queued(),
+            queued_withdrawals: router.get_queued_withdrawals(),
+            batching_stats: router.get_batching_stats(),
+            pending_gains: router.get_pending_gains(),
+        }
+    }
+}
@@ -162,3 +162,28 @@ mod tests {
         assert_eq!(avg, U512::from(160u64));
     }
 }
+
+/// Whether the backend meters gas: only CasperVM (`ODRA_BACKEND=casper`) does
+pub fn gas_metered() -> bool {
+    std::env::var("ODRA_BACKEND").map_or(false, |backend| backend == "casper")
+}
+
+/// Gas charged for the most recent contract call
+///
+/// Panics off CasperVM: OdraVM charges zero for every call, so a gas
+/// comparison there would pass whatever the contract does. Tests using it
+/// are `#[ignore]`d and run with `make test-gas`.
+pub fn last_call_gas(env: &odra::host::HostEnv) -> u64 {
+    assert!(gas_metered(), "gas is only metered on CasperVM (ODRA_BACKEND=casper)");
+    env.last_call_gas_cost()
+}
+
+/// Bookkeeping writes of a repeat deposit before `UserDeposit` became one
+/// record: total assets and shares (2), the user's shares (1), the four
+/// per-user fee tracking mappings (4) and `daily_deposits` (1)
+pub const LEGACY_REPEAT_DEPOSIT_WRITES: u32 = 8;
+
+/// Share of `before` that `after` saves, in basis points
+pub fn storage_write_reduction_bps(before: u32, after: u32) -> u32 {
+    before.saturating_sub(after) * 10_000 / before
+}
@@ -0,0 +1,311 @@
+#[cfg(test)]
+mod bootstrap_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{StrategyWithdrawal, SystemDeployed, WithdrawalsFlushed, YieldCompounded};
+    use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+    use caspervault_contracts::{Deposit, MockStrategyHostRef, VaultError, Withdraw};
+    use crate::helpers::*;
+
+    #[test]
+    fn test_bootstrap_wires_every_contract() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let system = deploy_system(&env, admin, env.get_account(6));
+
+        assert!(system.registry.verify_wiring().is_empty(), "No findings after bootstrap");
+
+        let event = env.get_event::<SystemDeployed>(system.registry.address(), -1).unwrap();
+        assert_eq!(event.vault_manager, *system.vault.address());
+        assert_eq!(event.liquid_staking, *system.liquid_staking.address());
+        assert_eq!(event.strategy_router, *system.router.address());
+        assert_eq!(event.yield_aggregator, *system.aggregator.address());
+        assert_eq!(event.lst_cspr_token, *system.lst_cspr.address());
+        assert_eq!(event.cv_cspr_token, *system.cv_cspr.address());
+
+        assert_eq!(system.lst_cspr.get_minter(), Some(*system.liquid_staking.address()));
+        assert_eq!(system.cv_cspr.get_vault_manager(), Some(*system.vault.address()));
+        assert_eq!(system.liquid_staking.get_vault_address(), Some(*system.vault.address()));
+    }
+
+    #[test]
+    fn test_vault_metadata_views() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let system = deploy_system(&env, admin, env.get_account(6));
+
+        assert_eq!(system.vault.asset(), *system.lst_cspr.address(), "Underlying is lstCSPR");
+        assert_eq!(system.vault.underlying_decimals(), 9, "CSPR motes");
+        assert_eq!(system.vault.share_decimals(), 9);
+        assert_eq!(system.vault.decimals(), system.vault.share_decimals(), "ERC-4626 decimals are the share's");
+        assert_eq!(system.cv_cspr.decimals(), system.vault.share_decimals(), "cvCSPR matches the vault");
+        assert_eq!(system.vault.name(), system.cv_cspr.name());
+        assert_eq!(system.vault.symbol(), "cvCSPR");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_init_rejects_share_token_with_wrong_decimals() {
+        let env = odra_test::env();
+        SystemBuilder::new(&env).with_share_token(18).with_vault().build();
+    }
+
+    #[test]
+    fn test_set_cv_cspr_token_checks_decimals() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        let wrong = SystemBuilder::share_token(&env, 18);
+        let matching = SystemBuilder::share_token(&env, 9);
+
+        assert_eq!(
+            system.vault.try_set_cv_cspr_token(*wrong.address()),
+            Err(VaultError::ShareDecimalsMismatch.into()),
+        );
+        assert_eq!(system.vault.get_cv_cspr_token(), Some(*system.cv_cspr.address()), "Token unchanged");
+
+        system.vault.set_cv_cspr_token(*matching.address());
+        assert_eq!(system.vault.symbol(), "mSHR", "Metadata follows the wired token");
+    }
+
+    #[test]
+    fn test_deposit_withdraw_round_trip_after_bootstrap() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+
+        env.set_caller(user);
+        let shares = system.vault.with_tokens(cspr(1000)).deposit();
+        assert_u512_eq(shares, cspr(1000), "First deposit mints 1:1");
+        assert!(env.get_event::<Deposit>(system.vault.address(), -1).is_ok());
+
+        // Past the default 60s holding period
+        env.advance_block_time(60);
+        let assets = system.vault.withdraw(shares);
+        assert_u512_eq(assets, cspr(1000), "Full round trip without profit");
+        assert!(env.get_event::<Withdraw>(system.vault.address(), -1).is_ok());
+        assert_u512_eq(system.vault.get_user_shares(user), cspr(0), "All shares burned");
+    }
+
+    #[test]
+    fn test_verify_wiring_reports_repointed_router() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+
+        let other_router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        system.vault.set_strategy_router(*other_router.address());
+
+        let findings = system.registry.verify_wiring();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].contains("strategy router"));
+    }
+
+    #[test]
+    fn test_protocol_stats_after_activity() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let (user1, user2) = (env.get_account(1), env.get_account(2));
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        // The compound accrues management fees; keep shares at 1:1
+        system.vault.set_management_fee(0);
+
+        system.liquid_staking.add_validator(env.get_account(10), 99, 5, cspr(1_000_000));
+        system.liquid_staking.add_validator(env.get_account(11), 98, 5, cspr(1_000_000));
+
+        let targets = vec![("dex".to_string(), 40u8), ("lending".to_string(), 30u8), ("crosschain".to_string(), 30u8)];
+        for (name, _) in targets.iter() {
+            let strategy = SystemBuilder::mock_strategy(&env, 1000);
+            system.router.add_strategy(name.clone(), *strategy.address());
+        }
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(targets);
+
+        // 50 of each deposit tops up the 5% pool, 950 goes to strategies
+        env.set_caller(user1);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        env.set_caller(user2);
+        system.vault.with_tokens(cspr(1000)).deposit();
+
+        // 100 CSPR compounded: reported to the vault in full and unlocked after 6 hours
+        env.advance_block_time(60 * 60);
+        env.set_caller(admin);
+        system.aggregator.compound(cspr(100));
+        env.advance_block_time(6 * 60 * 60);
+
+        let stats = system.aggregator.get_protocol_stats();
+        assert_u512_eq(stats.total_assets, cspr(2100), "Deposits plus unlocked profit");
+        assert_u512_eq(stats.total_shares, cspr(2000), "Shares minted 1:1");
+        assert_u512_eq(stats.share_price, U512::from(1_050_000_000u64), "2100 / 2000");
+        assert_eq!(stats.unique_depositors, 2);
+        assert_u512_eq(stats.total_yields_harvested, U512::zero(), "Nothing aggregated yet");
+        assert_eq!(stats.current_apy, U256::from(1485u64), "Router blend at 40/30/30");
+        // (8% staking + 14.85% blend) / 2
+        assert_eq!(stats.apy_7d, U256::from(1142u64), "One compound in the window");
+        assert_eq!(stats.apy_30d, U256::from(1142u64), "Same point over 30 days");
+        assert_u512_eq(stats.total_staked, U512::zero(), "Vault does not stake through LiquidStaking yet");
+        assert_eq!((stats.vault_staked, stats.direct_staked, stats.staker_count), (U512::zero(), U512::zero(), 0));
+        assert_eq!(stats.exchange_rate, system.liquid_staking.get_exchange_rate());
+        assert_eq!(stats.active_validators, 2);
+        assert_eq!(stats.strategy_count, 3);
+        assert_u512_eq(stats.strategy_balance, cspr(2000), "Two 950 tranches plus the re-deployed yield");
+        assert_u512_eq(stats.vault_fees_collected, U512::zero(), "No withdrawals yet");
+        assert_eq!(stats.timestamp, env.get_block_time());
+
+        // A full exit drops the depositor count
+        env.set_caller(user2);
+        let shares = system.vault.get_user_shares(user2);
+        system.vault.withdraw(shares);
+        assert_eq!(system.aggregator.get_protocol_stats().unique_depositors, 1);
+    }
+
+    #[test]
+    fn test_compound_then_exit_charges_performance_fee_once() {
+        let env = odra_test::env();
+        let (admin, user) = (env.get_account(0), env.get_account(1));
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        system.vault.set_management_fee(0);
+
+        let targets = vec![("dex".to_string(), 40u8), ("lending".to_string(), 30u8), ("crosschain".to_string(), 30u8)];
+        for (name, _) in targets.iter() {
+            let strategy = SystemBuilder::mock_strategy(&env, 1000);
+            system.router.add_strategy(name.clone(), *strategy.address());
+        }
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(targets);
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+
+        // The aggregator passes the whole 100 CSPR through to the vault
+        env.advance_block_time(60 * 60);
+        env.set_caller(admin);
+        system.aggregator.compound(cspr(100));
+        let event = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(event.amount, cspr(100), "No fee taken on compound");
+        env.advance_block_time(6 * 60 * 60);
+        assert_u512_eq(system.vault.total_assets(), cspr(1100), "Full yield unlocked");
+
+        // 10% of the 100 CSPR profit, not 10% of 90 on top of a 10 CSPR cut
+        env.set_caller(user);
+        let payout = system.vault.withdraw(cspr(1000));
+        assert_u512_eq(payout, cspr(1090), "1100 less a 10 CSPR performance fee");
+        let breakdown = system.vault.get_fee_breakdown();
+        assert_u512_eq(breakdown.performance_fees, cspr(10), "Charged once, at the vault");
+        assert_u512_eq(breakdown.total_fees, cspr(10), "No other fee on the cycle");
+    }
+
+    /// Bootstrapped system with three mock strategies at 40/30/30, batching
+    /// on with a one hour window, and 1000 CSPR deposited by each of three users
+    fn setup_batched_exits() -> (HostEnv, DeployedSystem, Vec<MockStrategyHostRef>) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        // Keep shares at 1:1 so exits pay back exactly
+        system.vault.set_management_fee(0);
+
+        let targets = vec![("dex".to_string(), 40u8), ("lending".to_string(), 30u8), ("crosschain".to_string(), 30u8)];
+        let mut strategies = Vec::new();
+        for (name, _) in targets.iter() {
+            let strategy = SystemBuilder::mock_strategy(&env, 1000);
+            system.router.add_strategy(name.clone(), *strategy.address());
+            strategies.push(strategy);
+        }
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(targets);
+        system.router.set_withdrawal_batching(true, 60 * 60);
+
+        // 50 of each deposit tops up the 5% pool, 950 goes to strategies
+        for i in 1..=3 {
+            env.set_caller(env.get_account(i));
+            system.vault.with_tokens(cspr(1000)).deposit();
+        }
+        env.advance_block_time(60);
+
+        (env, system, strategies)
+    }
+
+    #[test]
+    fn test_three_exits_share_one_strategy_withdrawal() {
+        let (env, mut system, strategies) = setup_batched_exits();
+
+        let mut claims = Vec::new();
+        for i in 1..=3 {
+            env.set_caller(env.get_account(i));
+            claims.push(system.vault.exit_batched(cspr(1000)));
+        }
+        assert_u512_eq(system.vault.get_instant_pool_balance(), U512::zero(), "First exit drained the pool");
+        assert_u512_eq(system.router.get_queued_withdrawals(), cspr(2850), "850 + 1000 + 1000 queued");
+        for strategy in strategies.iter() {
+            assert_eq!(strategy.get_withdraw_calls(), 0, "Nothing withdrawn while queued");
+        }
+
+        env.set_caller(env.get_account(1));
+        assert!(!system.vault.is_batched_exit_ready(claims[0]));
+        assert!(system.vault.try_claim_batched_exit(claims[0]).is_err(), "Batch still open");
+
+        env.set_caller(env.get_account(0));
+        assert_u512_eq(system.router.flush_withdrawals(), cspr(2850), "One consolidated withdrawal");
+        let event = env.get_event::<WithdrawalsFlushed>(system.router.address(), -1).unwrap();
+        assert_eq!(event.claims, 3);
+        assert_u512_eq(event.received, cspr(2850), "Batch received in full");
+        assert!(env.get_event::<StrategyWithdrawal>(system.router.address(), -2).is_ok(), "Single router withdraw");
+        for strategy in strategies.iter() {
+            assert_eq!(strategy.get_withdraw_calls(), 1, "Each strategy touched once for three exits");
+        }
+
+        let stats = system.router.get_batching_stats();
+        assert_eq!(stats.claims_queued, 3);
+        assert_eq!(stats.batches_flushed, 1);
+        assert_eq!(stats.withdrawals_saved, 2, "Three exits, one withdrawal");
+        assert_u512_eq(stats.volume_batched, cspr(2850), "Volume batched");
+
+        for (i, claim) in claims.iter().enumerate() {
+            env.set_caller(env.get_account(i + 1));
+            assert!(system.vault.is_batched_exit_ready(*claim));
+            assert_u512_eq(system.vault.claim_batched_exit(*claim), cspr(1000), "Exit paid in full");
+        }
+        env.set_caller(env.get_account(1));
+        assert!(system.vault.try_claim_batched_exit(claims[0]).is_err(), "Claimed once");
+    }
+
+    #[test]
+    fn test_only_the_vault_queues_router_withdrawals() {
+        let (env, mut system, _) = setup_batched_exits();
+
+        for caller in [env.get_account(0), env.get_account(1)] {
+            env.set_caller(caller);
+            assert_eq!(
+                system.router.try_queue_withdrawal(cspr(500)),
+                Err(VaultError::Unauthorized.into()),
+                "Admin and users cannot open claims on the vault's behalf"
+            );
+        }
+        assert_u512_eq(system.router.get_queued_withdrawals(), U512::zero(), "Nothing queued");
+    }
+
+    #[test]
+    fn test_batched_exit_flushes_itself_after_window() {
+        let (env, mut system, strategies) = setup_batched_exits();
+        let user = env.get_account(1);
+
+        // Within the pool: claimable right away, nothing queued
+        env.set_caller(user);
+        let instant = system.vault.exit_batched(cspr(100));
+        assert!(system.vault.is_batched_exit_ready(instant));
+        assert_u512_eq(system.vault.claim_batched_exit(instant), cspr(100), "Paid from the pool");
+        assert_u512_eq(system.router.get_queued_withdrawals(), U512::zero(), "Nothing queued");
+
+        let queued = system.vault.exit_batched(cspr(900));
+        assert!(!system.vault.is_batched_exit_ready(queued));
+
+        // No keeper: the claim flushes the batch once the window has passed
+        env.advance_block_time(60 * 60);
+        assert!(system.vault.is_batched_exit_ready(queued));
+        assert_u512_eq(system.vault.claim_batched_exit(queued), cspr(900), "Pool remainder plus the batch");
+        assert_eq!(system.router.get_batching_stats().batches_flushed, 1);
+        assert_eq!(strategies[0].get_withdraw_calls(), 1);
+    }
+}
@@ -128,16 +128,35 @@ mod compound_integration_tests {
         assert!(!should_compound, "Should not compound with zero yield");
     }
 
+    /// 2% a year for 73 days is 0.4% of assets, minted to the treasury
+    /// as shares: each accrual dilutes existing holders by that much
     #[test]
     fn test_management_fee_accrual() {
-        let tvl = cspr(1_000_000);
-        let management_fee_bps = 200u64;
-        let days_since_last = 30u64;
-        
-        let management_fee = calculate_management_fee(tvl, management_fee_bps, days_since_last);
-        
-        let expected = cspr(1_643);
-        assert_u512_within_tolerance(management_fee, expected, 100);
+        let mut scenario = Scenario::new();
+        let (user, treasury) = (scenario.user(1), scenario.treasury());
+
+        scenario
+            .management_fee(200)
+            .deposit(user, cspr(10_000))
+            .advance_days(73)
+            .collect_management_fees()
+            .expect_total_assets(cspr(10_000))
+            .expect_share_price_near(0.996, 1)
+            .expect_value_near(treasury, cspr(40), 1)
+            .expect_value_near(user, cspr(9_960), 1);
+
+        // The exit accrues the next 73 days itself: 0.4% of 10,000 again,
+        // leaving the user 9,960 x 0.996
+        scenario
+            .advance_days(73)
+            .withdraw_all(user)
+            .expect_received_near(user, milli_cspr(9_920_160), 1)
+            .expect_value_near(treasury, milli_cspr(79_840), 1);
+        assert_u512_eq(
+            scenario.system.vault.total_assets(),
+            cspr(10_000) - scenario.received(user),
+            "Only the treasury's claim is left"
+        );
     }
 
     #[test]
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod flow_id_tests {
+    use odra::prelude::*;
+    use odra::host::HostRef;
+    use caspervault_contracts::deployer::deploy_system;
+    use caspervault_contracts::{
+        AllocationUpdate, Deployed, Deposit, StrategyWithdrawal, Withdraw, Withdrawn,
+    };
+    use crate::helpers::*;
+
+    #[test]
+    fn test_flow_id_links_vault_router_and_strategy_events() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+
+        let strategy = SystemBuilder::mock_strategy(&env, 1000);
+        system.router.add_strategy("dex".to_string(), *strategy.address());
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![("dex".to_string(), 100u8)]);
+        system.vault.set_min_holding_period(0);
+
+        // Deposit: vault → router allocation → strategy deploy
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        let deposit = env.get_event::<Deposit>(system.vault.address(), -1).unwrap();
+        assert_ne!(deposit.operation_id, 0);
+
+        let allocation = env.get_event::<AllocationUpdate>(system.router.address(), -1).unwrap();
+        assert_eq!(allocation.flow_id, deposit.operation_id, "Router allocation tagged");
+        let deployed = env.get_event::<Deployed>(strategy.address(), -1).unwrap();
+        assert_eq!(deployed.flow_id, deposit.operation_id, "Strategy deploy tagged");
+        assert_eq!(deployed.amount, allocation.amount);
+
+        // Withdrawal gets its own flow id, threaded the same way
+        let shares = system.vault.get_user_shares(user);
+        system.vault.withdraw_as_lst(shares);
+        let withdraw = env.get_event::<Withdraw>(system.vault.address(), -1).unwrap();
+        assert_eq!(withdraw.operation_id, deposit.operation_id + 1);
+
+        let router_withdrawal = env.get_event::<StrategyWithdrawal>(system.router.address(), -1).unwrap();
+        assert_eq!(router_withdrawal.flow_id, withdraw.operation_id, "Router withdrawal tagged");
+        let withdrawn = env.get_event::<Withdrawn>(strategy.address(), -1).unwrap();
+        assert_eq!(withdrawn.flow_id, withdraw.operation_id, "Strategy withdrawal tagged");
+    }
+
+    #[test]
+    fn test_untagged_entrypoints_use_flow_id_zero() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+
+        let strategy = SystemBuilder::mock_strategy(&env, 1000);
+        system.router.add_strategy("dex".to_string(), *strategy.address());
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![("dex".to_string(), 100u8)]);
+        // Call the router directly, with the admin standing in for the vault
+        system.router.set_vault_address(admin);
+
+        system.router.allocate(cspr(100));
+        assert_eq!(env.get_event::<AllocationUpdate>(system.router.address(), -1).unwrap().flow_id, 0);
+        assert_eq!(env.get_event::<Deployed>(strategy.address(), -1).unwrap().flow_id, 0);
+
+        system.router.withdraw(cspr(50));
+        assert_eq!(env.get_event::<StrategyWithdrawal>(system.router.address(), -1).unwrap().flow_id, 0);
+        assert_eq!(env.get_event::<Withdrawn>(strategy.address(), -1).unwrap().flow_id, 0);
+    }
+}
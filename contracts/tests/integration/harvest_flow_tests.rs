@@ -0,0 +1,400 @@
+#[cfg(test)]
+mod harvest_flow_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{
+        CommunityFundsClaimed, HarvestForwarded, YieldCompounded, YieldDestination, YieldSplit, YIELD_SOURCE_COMPOUND,
+    };
+    use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+    use caspervault_contracts::{CompoundBountyPaid, MockStrategyHostRef, VaultError, YieldReported};
+    use crate::helpers::*;
+
+    const ONE_MONTH: u64 = 30 * 24 * 60 * 60;
+
+    /// Bootstrapped system with one mock strategy taking everything the
+    /// router deploys, after a 10,000 CSPR deposit: 500 tops up the 5%
+    /// instant pool and 9,500 is deployed
+    fn setup() -> (HostEnv, DeployedSystem, MockStrategyHostRef) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        system.vault.set_management_fee(0);
+
+        let strategy = SystemBuilder::mock_strategy(&env, 1000);
+        system.router.add_strategy("dex".to_string(), *strategy.address());
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![("dex".to_string(), 100u8)]);
+
+        env.set_caller(env.get_account(1));
+        system.vault.with_tokens(cspr(10_000)).deposit();
+        env.set_caller(admin);
+
+        (env, system, strategy)
+    }
+
+    /// Hand `strategy` `amount` lstCSPR, staked by account 3, to pay its
+    /// yield in
+    fn fund_yield_in_lst(env: &HostEnv, system: &mut DeployedSystem, strategy: &mut MockStrategyHostRef, amount: U512) {
+        // The registry caps each validator at 10% of stake
+        for i in 10..20 {
+            system.liquid_staking.add_validator(env.get_account(i), 99, 5, cspr(1_000_000));
+        }
+        let staker = env.get_account(3);
+        env.set_caller(staker);
+        let minted = system.liquid_staking.with_tokens(amount).stake();
+        system.lst_cspr.transfer(*strategy.address(), minted);
+        env.set_caller(env.get_account(0));
+        strategy.set_yield_token(*system.lst_cspr.address());
+        strategy.set_pending_yield(minted);
+    }
+
+    #[test]
+    fn test_auto_compound_redeploys_harvested_yield() {
+        let (env, mut system, mut strategy) = setup();
+        assert_u512_eq(strategy.get_balance(), cspr(9500), "Deposit deployed");
+
+        // A month of strategy yield, 1.5% of the vault, paid in lstCSPR
+        env.advance_block_time(ONE_MONTH);
+        fund_yield_in_lst(&env, &mut system, &mut strategy, cspr(150));
+        let total_assets_before = system.vault.total_assets();
+        let vault_lst_before = system.lst_cspr.balance_of(*system.vault.address());
+        assert_u512_eq(system.lst_cspr.balance_of(*strategy.address()), cspr(150), "Yield held by the strategy");
+
+        assert_u512_eq(system.aggregator.auto_compound(), cspr(150), "Strategy yield aggregated");
+
+        // The tokens went strategy → router → aggregator → vault
+        assert_u512_eq(
+            system.lst_cspr.balance_of(*system.vault.address()) - vault_lst_before,
+            cspr(150),
+            "Vault custodies the harvested lstCSPR",
+        );
+        for holder in [*strategy.address(), *system.router.address(), *system.aggregator.address()] {
+            assert_u512_eq(system.lst_cspr.balance_of(holder), U512::zero(), "Nothing stranded on the way");
+        }
+
+        let forwarded = env.get_event::<HarvestForwarded>(system.router.address(), -1).unwrap();
+        assert_eq!(forwarded.recipient, *system.aggregator.address());
+        assert_u512_eq(forwarded.amount, cspr(150), "Router forwarded the harvest");
+        assert_u512_eq(forwarded.transferred, cspr(150), "All of it in tokens");
+
+        // Principal plus the re-deployed yield, counted once
+        assert_u512_eq(strategy.get_balance(), cspr(9650), "Yield re-deployed, not stranded");
+        assert_u512_eq(system.router.get_total_allocated(), cspr(9650), "Allocation grew by the yield");
+        assert_u512_eq(system.vault.total_assets(), total_assets_before + cspr(150), "Vault assets grew");
+        assert_u512_eq(system.vault.get_instant_pool_balance(), cspr(500), "Pool untouched");
+
+        let event = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(event.reallocated, cspr(150), "All of it reallocated");
+        assert_u512_eq(event.to_instant_pool, U512::zero(), "Nothing left over");
+
+        // Nothing left in the strategy for a second harvest
+        env.advance_block_time(60 * 60);
+        system.aggregator.aggregate_yields();
+        assert_u512_eq(strategy.get_balance(), cspr(9650), "Harvest moved nothing");
+        assert_u512_eq(system.router.get_total_allocated(), cspr(9650), "No double count");
+    }
+
+    #[test]
+    fn test_instant_pool_destination_keeps_yield_liquid() {
+        let (env, mut system, mut strategy) = setup();
+        assert_eq!(
+            system.aggregator.try_set_yield_destination(2),
+            Err(VaultError::InvalidRequest.into())
+        );
+        system.aggregator.set_yield_destination(YieldDestination::InstantPool.to_u8());
+
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        system.aggregator.auto_compound();
+
+        assert_u512_eq(strategy.get_balance(), cspr(9500), "Strategy keeps only principal");
+        assert_u512_eq(system.router.get_total_allocated(), cspr(9500), "Nothing reallocated");
+        assert_u512_eq(system.vault.get_instant_pool_balance(), cspr(650), "Yield parked in the pool");
+        assert_u512_eq(system.vault.total_assets(), cspr(10_150), "Reported to the vault");
+
+        let event = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(event.to_instant_pool, cspr(150), "Credited to the pool");
+    }
+
+    /// The harvest and the vault's booking of it are one transaction, so
+    /// the next deposit is already priced with the yield in
+    #[test]
+    fn test_harvest_reports_yield_to_vault_atomically() {
+        let (env, mut system, mut strategy) = setup();
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        let total_assets_before = system.vault.total_assets();
+
+        system.aggregator.auto_compound();
+
+        let event = env.get_event::<YieldReported>(system.vault.address(), -1).unwrap();
+        assert_u512_eq(event.amount, cspr(150), "The harvested yield");
+        assert_eq!(event.source, YIELD_SOURCE_COMPOUND);
+        assert_u512_eq(event.new_total_assets, system.vault.total_assets(), "Figures after booking");
+        assert_eq!(event.new_share_price, system.vault.get_share_price_precise());
+        assert_u512_eq(event.new_total_assets, total_assets_before + cspr(150), "Booked with the harvest");
+
+        // The first deposit after the harvest pays the post-yield price
+        let user = env.get_account(2);
+        let expected_shares = system.vault.convert_to_shares(cspr(1000));
+        assert_u512_lt(expected_shares, cspr(1000), "Share price already above 1");
+        env.set_caller(user);
+        assert_u512_eq(system.vault.with_tokens(cspr(1000)).deposit(), expected_shares, "No stale price to exploit");
+    }
+
+    #[test]
+    fn test_anyone_can_auto_compound_when_due() {
+        let (env, mut system, mut strategy) = setup();
+        let stranger = env.get_account(5);
+        assert!(system.aggregator.try_aggregate_yields().is_ok(), "Admin may still harvest by hand");
+        env.set_caller(stranger);
+        assert_eq!(system.aggregator.try_compound(cspr(150)), Err(VaultError::Unauthorized.into()));
+
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        let total_assets_before = system.vault.total_assets();
+
+        assert_u512_eq(system.aggregator.auto_compound(), cspr(150), "Compounded by a stranger");
+        assert_u512_eq(system.vault.total_assets(), total_assets_before + cspr(150), "Yield booked");
+        let event = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(event.bounty, U512::zero(), "No bounty by default");
+        assert_u512_eq(system.vault.get_user_shares(stranger), U512::zero(), "Nothing paid");
+    }
+
+    #[test]
+    fn test_auto_compound_rejects_small_or_early_harvests() {
+        let (env, mut system, mut strategy) = setup();
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        system.aggregator.set_min_yield_threshold(cspr(200));
+
+        // Below the threshold the whole call reverts, harvest included
+        env.set_caller(env.get_account(5));
+        assert_eq!(system.aggregator.try_auto_compound(), Err(VaultError::AmountTooLow.into()));
+        assert_u512_eq(strategy.get_balance(), cspr(9500), "Nothing re-deployed");
+
+        env.set_caller(env.get_account(0));
+        system.aggregator.set_min_yield_threshold(cspr(100));
+        env.set_caller(env.get_account(5));
+        assert_u512_eq(system.aggregator.auto_compound(), cspr(150), "Yield still there");
+
+        // A second compound inside the interval is refused before harvesting
+        strategy.set_pending_yield(cspr(150));
+        assert_eq!(system.aggregator.try_auto_compound(), Err(VaultError::TooSoon.into()));
+        env.advance_block_time(60 * 60);
+        assert_u512_eq(system.aggregator.auto_compound(), cspr(150), "Allowed once the interval passed");
+    }
+
+    #[test]
+    fn test_auto_compound_pays_bounty_in_shares() {
+        let (env, mut system, mut strategy) = setup();
+        assert_eq!(system.aggregator.try_set_compound_bounty_bps(101), Err(VaultError::InvalidFee.into()));
+        system.aggregator.set_compound_bounty_bps(100);
+
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        let share_price_before = system.vault.get_share_price_precise();
+        let caller = env.get_account(5);
+        env.set_caller(caller);
+        system.aggregator.auto_compound();
+
+        // 1% of the yield backs the caller's shares, the rest is profit
+        let bounty = U512::from(1_500_000_000u64);
+        let reported = env.get_event::<YieldReported>(system.vault.address(), -2).unwrap();
+        assert_u512_eq(reported.amount, cspr(150) - bounty, "Profit net of the bounty");
+        let paid = env.get_event::<CompoundBountyPaid>(system.vault.address(), -1).unwrap();
+        assert_eq!(paid.recipient, caller);
+        assert_u512_eq(paid.amount, bounty, "1% of 150");
+        assert_u512_eq(system.vault.get_user_shares(caller), paid.shares, "Shares minted to the caller");
+        assert_u512_eq(paid.shares, bounty, "Minted at the pre-yield price of 1");
+        assert_u512_eq(system.vault.total_assets(), cspr(10_150), "All of the yield is in the vault");
+        assert_eq!(system.vault.get_share_price_precise(), share_price_before, "Profit still locked, price unmoved");
+
+        let event = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(event.bounty, bounty, "Bounty in the compound event");
+    }
+
+    /// Gross yield → community pool → compounded, with the 10% performance
+    /// fee taken from the compounded part when holders exit
+    #[test]
+    fn test_yield_split_three_ways() {
+        let (env, mut system, mut strategy) = setup();
+        assert_eq!(system.aggregator.try_set_community_pool_bps(1001), Err(VaultError::InvalidFee.into()));
+        system.aggregator.set_community_pool_bps(500);
+
+        let community = U512::from(7_500_000_000u64);
+        let compounded = cspr(150) - community;
+        let performance_fee = U512::from(14_250_000_000u64);
+        assert_eq!(
+            system.aggregator.preview_yield_split(cspr(150)),
+            YieldSplit {
+                gross_yield: cspr(150),
+                community_allocation: community,
+                compounded,
+                performance_fee,
+                net_to_holders: compounded - performance_fee,
+            }
+        );
+
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        system.aggregator.auto_compound();
+
+        let event = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(event.amount, cspr(150), "Gross yield");
+        assert_u512_eq(event.community_allocation, community, "5% of 150");
+        assert_u512_eq(event.reallocated, compounded, "Only the rest redeployed");
+        let reported = env.get_event::<YieldReported>(system.vault.address(), -1).unwrap();
+        assert_u512_eq(reported.amount, compounded, "Vault books the rest");
+        assert_u512_eq(system.vault.total_assets(), cspr(10_000) + compounded, "Community share not in the vault");
+
+        assert_u512_eq(system.aggregator.get_community_pool(), community, "Held for the distributor");
+        let breakdown = system.vault.get_fee_breakdown();
+        assert_u512_eq(breakdown.community_allocations, community, "Shown with the fees");
+    }
+
+    #[test]
+    fn test_performance_fee_and_community_pool_share_a_cap() {
+        let (_env, mut system, _) = setup();
+        system.vault.set_performance_fee(4500);
+        system.aggregator.set_community_pool_bps(500);
+        assert_eq!(system.aggregator.get_community_pool_bps(), 500);
+
+        // Either side may not push the total past 50%
+        assert_eq!(system.vault.try_set_performance_fee(4600), Err(VaultError::InvalidFee.into()));
+        system.vault.set_performance_fee(4000);
+        assert_eq!(system.aggregator.try_set_community_pool_bps(1001), Err(VaultError::InvalidFee.into()));
+        system.aggregator.set_community_pool_bps(1000);
+        assert_eq!(system.vault.try_set_performance_fee(4001), Err(VaultError::InvalidFee.into()));
+        assert_eq!(system.vault.get_fees().0, 4000);
+    }
+
+    /// Staking's 8% averaged with the dex's 12% is a 10% gross APY; the 10%
+    /// performance fee leaves 9% and the 2% management fee 7%
+    #[test]
+    fn test_net_apy_after_fees() {
+        let (_env, mut system, _) = setup();
+        system.vault.set_fees(0, 0, 50);
+        assert_eq!(system.aggregator.get_net_apy(), U256::from(1000u64), "No fees: net is gross");
+
+        system.vault.set_fees(1000, 200, 50);
+        assert_eq!(system.aggregator.get_net_apy(), U256::from(700u64), "7% net");
+
+        // 5% left after a 50% performance fee can't cover a 10% management fee
+        system.vault.set_fees(5000, 1000, 50);
+        assert_eq!(system.aggregator.get_net_apy(), U256::zero());
+    }
+
+    #[test]
+    fn test_only_the_distributor_claims_community_funds() {
+        let (env, mut system, mut strategy) = setup();
+        let distributor = env.get_account(4);
+        system.aggregator.set_community_pool_bps(1000);
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        system.aggregator.auto_compound();
+        assert_u512_eq(system.aggregator.get_community_pool(), cspr(15), "10% of 150");
+
+        // No distributor set yet: nobody can claim
+        assert_eq!(system.aggregator.try_claim_community_funds(cspr(5)), Err(VaultError::Unauthorized.into()));
+        system.aggregator.set_incentives_distributor(distributor);
+        assert_eq!(system.aggregator.get_incentives_distributor(), Some(distributor));
+        for caller in [env.get_account(0), env.get_account(5)] {
+            env.set_caller(caller);
+            assert_eq!(system.aggregator.try_claim_community_funds(cspr(5)), Err(VaultError::Unauthorized.into()));
+        }
+
+        env.set_caller(distributor);
+        assert_eq!(system.aggregator.try_claim_community_funds(U512::zero()), Err(VaultError::ZeroAmount.into()));
+        assert_eq!(
+            system.aggregator.try_claim_community_funds(cspr(16)),
+            Err(VaultError::InsufficientBalance.into())
+        );
+        system.aggregator.claim_community_funds(cspr(10));
+
+        let event = env.get_event::<CommunityFundsClaimed>(system.aggregator.address(), -1).unwrap();
+        assert_eq!(event.distributor, distributor);
+        assert_u512_eq(event.amount, cspr(10), "Claimed");
+        assert_u512_eq(event.remaining, cspr(5), "Left in the pool");
+        assert_u512_eq(system.aggregator.get_community_pool(), cspr(5), "Pool drawn down");
+        assert_u512_eq(system.aggregator.get_community_funds_claimed(), cspr(10), "Lifetime claims");
+    }
+
+    /// A dry run and the compound that follows it in the same block agree
+    /// to the mote
+    #[test]
+    fn test_dry_run_matches_the_compound_that_follows() {
+        let (env, mut system, mut strategy) = setup();
+        system.vault.set_profit_unlock_duration(0);
+        system.aggregator.set_community_pool_bps(500);
+        system.aggregator.set_compound_bounty_bps(100);
+
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(150));
+        env.set_caller(env.get_account(5));
+        let preview = system.aggregator.dry_run_compound();
+
+        assert!(preview.would_compound, "Due and above the threshold");
+        assert_u512_eq(preview.staking_yield, U512::zero(), "No staking rewards due");
+        assert_eq!(preview.strategy_yields, vec![("dex".to_string(), cspr(150))]);
+        assert_u512_eq(preview.total_yield, cspr(150), "Strategy yield only");
+        assert!(!preview.circuit_breaker_trips, "1.5% is inside the 2% breaker");
+        assert_u512_eq(preview.community_allocation, milli_cspr(7500), "5% of 150");
+        assert_u512_eq(preview.bounty, milli_cspr(1425), "1% of the 142.5 compounded");
+        assert_u512_eq(preview.reported_profit, milli_cspr(141_075), "The rest is profit");
+        assert_u512_eq(preview.performance_fee, U512::from(14_107_500_000u64), "10%, charged at exit");
+        assert_u512_eq(preview.total_assets, milli_cspr(10_142_500), "Profit and bounty join the vault");
+
+        assert_u512_eq(system.aggregator.auto_compound(), preview.total_yield, "Same harvest");
+        let compounded = env.get_event::<YieldCompounded>(system.aggregator.address(), -1).unwrap();
+        assert_u512_eq(compounded.community_allocation, preview.community_allocation, "Same community share");
+        assert_u512_eq(compounded.bounty, preview.bounty, "Same bounty");
+        let reported = env.get_event::<YieldReported>(system.vault.address(), -2).unwrap();
+        assert_u512_eq(reported.amount, preview.reported_profit, "Same profit");
+        assert_u512_eq(system.vault.total_assets(), preview.total_assets, "Same total assets");
+        assert_u512_eq(system.vault.get_share_price(), preview.share_price, "Same share price");
+    }
+
+    #[test]
+    fn test_dry_run_changes_nothing() {
+        let (env, mut system, mut strategy) = setup();
+        env.advance_block_time(ONE_MONTH);
+        strategy.set_pending_yield(cspr(300));
+        let users = [env.get_account(1), env.get_account(5)];
+        let vault_before = VaultSnapshot::capture(&system.vault, &users);
+        let router_before = RouterSnapshot::capture(&system.router);
+        let report_count = system.aggregator.get_report_count();
+
+        // 3% of the vault: the breaker would park all of it
+        let preview = system.aggregator.dry_run_compound();
+        assert!(preview.circuit_breaker_trips, "Past the 2% breaker");
+        assert_u512_eq(preview.change_bps, U512::from(300u64), "300 of 10,000");
+        assert_u512_eq(preview.reported_profit, U512::zero(), "Nothing booked");
+        assert_u512_eq(preview.total_assets, system.vault.total_assets(), "Vault unchanged by a parked harvest");
+
+        assert_eq!(VaultSnapshot::capture(&system.vault, &users), vault_before);
+        assert_eq!(RouterSnapshot::capture(&system.router), router_before);
+        assert_eq!(system.aggregator.get_report_count(), report_count, "No yield report recorded");
+        assert_u512_eq(strategy.preview_harvest(), cspr(300), "Yield still in the strategy");
+
+        assert_u512_eq(system.aggregator.auto_compound(), cspr(300), "Nothing was claimed by the dry run");
+        assert_u512_eq(system.aggregator.get_pending_gains(), cspr(300), "Parked as previewed");
+    }
+
+    #[test]
+    fn test_report_yield_rejects_everyone_but_the_aggregator() {
+        let (env, mut system, _) = setup();
+        let total_assets = system.vault.total_assets();
+        assert_eq!(system.vault.get_yield_aggregator(), Some(*system.aggregator.address()));
+
+        for caller in [env.get_account(0), env.get_account(3)] {
+            env.set_caller(caller);
+            assert_eq!(
+                system.vault.try_report_yield(cspr(100), YIELD_SOURCE_COMPOUND.to_string()),
+                Err(VaultError::Unauthorized.into())
+            );
+        }
+        assert_u512_eq(system.vault.total_assets(), total_assets, "Nothing booked");
+    }
+}
@@ -1,3 +1,13 @@
 pub mod vault_integration;
 pub mod strategy_integration;
 pub mod compound_integration;
+pub mod bootstrap_tests;
+pub mod staking_validator_tests;
+pub mod vault_lens_tests;
+pub mod vault_migration_tests;
+pub mod vault_yield_scenarios;
+pub mod vault_invariant_tests;
+pub mod flow_id_tests;
+pub mod staking_only_tests;
+pub mod harvest_flow_tests;
+pub mod vault_load_scenarios;
@@ -0,0 +1,246 @@
+#[cfg(test)]
+mod staking_only_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::{
+        MockLiquidStakingHostRef, RiskPreferenceSet, StakingOnlyDeposit, StakingOnlyWithdraw, VaultError,
+    };
+    use caspervault_contracts::tokens::LstCsprHostRef;
+    use caspervault_contracts::core::{StrategyExposure, StrategyRouterHostRef, VaultManagerHostRef, YIELD_INDEX_SCALE};
+    use caspervault_contracts::deployer::deploy_system;
+    use crate::helpers::*;
+
+    struct Fixture {
+        env: HostEnv,
+        admin: Address,
+        full_user: Address,
+        staking_user: Address,
+        lst: LstCsprHostRef,
+        staking: MockLiquidStakingHostRef,
+        router: StrategyRouterHostRef,
+        vault: VaultManagerHostRef,
+    }
+
+    /// lstCSPR vault at a 1:1 rate routing everything to one mock strategy,
+    /// with fees off. Both users hold 5,000 lstCSPR; `staking_user` has
+    /// opted into StakingOnly.
+    fn setup() -> Fixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let full_user = env.get_account(1);
+        let staking_user = env.get_account(2);
+
+        let built = SystemBuilder::new(&env)
+            .with_lst()
+            .with_mock_liquid_staking()
+            .with_vault()
+            .with_router()
+            .with_mock_strategy("dex", 1500)
+            .build();
+        let mut lst = built.lst.unwrap();
+        let mut staking = built.mock_liquid_staking.unwrap();
+        let mut router = built.router.unwrap();
+        let mut vault = built.vault.unwrap();
+        lst.mint(full_user, cspr(5_000));
+        lst.mint(staking_user, cspr(5_000));
+        staking.set_exchange_rate(U256::from(1_000_000_000u64));
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![("dex".to_string(), 100u8)]);
+        vault.set_min_holding_period(0);
+        vault.set_management_fee(0);
+        vault.set_performance_fee(0);
+
+        env.set_caller(staking_user);
+        vault.set_risk_preference(StrategyExposure::StakingOnly.to_u8());
+
+        Fixture { env, admin, full_user, staking_user, lst, staking, router, vault }
+    }
+
+    fn deposit_lst(f: &mut Fixture, user: Address, amount: U512) -> U512 {
+        f.env.set_caller(user);
+        f.lst.approve(*f.vault.address(), amount);
+        f.vault.deposit_lst(amount)
+    }
+
+    #[test]
+    fn test_staking_only_deposit_is_kept_out_of_strategies() {
+        let mut f = setup();
+        let event = f.env.get_event::<RiskPreferenceSet>(f.vault.address(), -1).unwrap();
+        assert_eq!(event.user, f.staking_user);
+        assert_eq!(event.exposure, StrategyExposure::StakingOnly.to_u8());
+        assert_eq!(f.vault.get_risk_preference(f.full_user), StrategyExposure::Full.to_u8());
+
+        let (full_user, staking_user) = (f.full_user, f.staking_user);
+        let shares = deposit_lst(&mut f, full_user, cspr(1000));
+        assert_u512_eq(shares, cspr(1000), "Full deposit mints shares");
+
+        let credited = deposit_lst(&mut f, staking_user, cspr(1000));
+        assert_u512_eq(credited, cspr(1000), "lstCSPR credited");
+        assert_u512_eq(f.vault.get_user_shares(f.staking_user), U512::zero(), "No shares minted");
+        assert_u512_eq(f.vault.get_staking_only_balance(f.staking_user), cspr(1000), "Bucket balance");
+        assert_u512_eq(f.vault.get_staking_only_assets(), cspr(1000), "Bucket total");
+
+        let event = f.env.get_event::<StakingOnlyDeposit>(f.vault.address(), -1).unwrap();
+        assert_eq!(event.user, f.staking_user);
+        assert_u512_eq(event.lst_cspr_amount, cspr(1000), "Event amount");
+
+        // Only the Full deposit reached the router or the share price
+        assert_u512_eq(f.router.get_total_allocated(), cspr(1000), "Router holds the Full deposit only");
+        assert_u512_eq(f.vault.total_assets(), cspr(1000), "Bucket outside total assets");
+        assert_u512_eq(f.lst.balance_of(*f.vault.address()), cspr(2000), "Vault holds both deposits");
+    }
+
+    #[test]
+    fn test_buckets_earn_differentiated_returns() {
+        let mut f = setup();
+        let (full_user, staking_user) = (f.full_user, f.staking_user);
+        let shares = deposit_lst(&mut f, full_user, cspr(1000));
+        deposit_lst(&mut f, staking_user, cspr(1000));
+
+        // Strategies return 15% on the Full bucket, backed by lstCSPR
+        // harvested into the instant pool
+        f.env.set_caller(f.admin);
+        f.vault.report_profit(cspr(150));
+        f.lst.mint(*f.vault.address(), cspr(150));
+        f.vault.test_set_instant_pool(cspr(150));
+        f.env.advance_block_time(7 * 60 * 60);
+
+        // Staking earns 8% for everyone through the lstCSPR rate
+        f.staking.set_exchange_rate(U256::from(1_080_000_000u64));
+
+        let scale = U512::from(YIELD_INDEX_SCALE);
+        assert_u512_eq(
+            f.vault.get_yield_index(StrategyExposure::StakingOnly.to_u8()),
+            scale * U512::from(108u64) / U512::from(100u64),
+            "Staking only: 8%",
+        );
+        assert_u512_within_tolerance(
+            f.vault.get_yield_index(StrategyExposure::Full.to_u8()),
+            scale * U512::from(1242u64) / U512::from(1000u64),
+            1,
+        );
+
+        f.env.set_caller(f.full_user);
+        let full_payout = f.vault.withdraw_as_lst(shares);
+        assert_u512_within_tolerance(full_payout, cspr(1150), 1);
+
+        f.env.set_caller(f.staking_user);
+        let staking_payout = f.vault.withdraw_staking_only(cspr(1000));
+        assert_u512_eq(staking_payout, cspr(1000), "Bucket paid in full");
+        assert_u512_eq(f.vault.get_staking_only_balance(f.staking_user), U512::zero(), "Bucket emptied");
+        let event = f.env.get_event::<StakingOnlyWithdraw>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.lst_cspr_amount, cspr(1000), "Event amount");
+
+        // Valued at the new rate: 1,242 CSPR against 1,080 CSPR
+        let rate = U512::from(1_080_000_000u64);
+        let full_value = f.lst.balance_of(f.full_user) - cspr(4000);
+        let staking_value = f.lst.balance_of(f.staking_user) - cspr(4000);
+        assert_u512_within_tolerance(full_value * rate / U512::from(1_000_000_000u64), cspr(1242), 1);
+        assert_u512_eq(staking_value * rate / U512::from(1_000_000_000u64), cspr(1080), "Staking yield only");
+    }
+
+    #[test]
+    fn test_staking_only_withdraw_is_limited_to_balance() {
+        let mut f = setup();
+        let staking_user = f.staking_user;
+        deposit_lst(&mut f, staking_user, cspr(500));
+
+        f.env.set_caller(f.staking_user);
+        assert_eq!(
+            f.vault.try_withdraw_staking_only(cspr(501)),
+            Err(VaultError::InsufficientBalance.into())
+        );
+        // Full depositors have nothing in the bucket
+        f.env.set_caller(f.full_user);
+        assert_eq!(
+            f.vault.try_withdraw_staking_only(cspr(1)),
+            Err(VaultError::InsufficientBalance.into())
+        );
+    }
+
+    #[test]
+    fn test_staking_only_cspr_deposit_is_staked() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let staking_user = env.get_account(2);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        system.vault.set_management_fee(0);
+        system.vault.set_min_holding_period(0);
+        // The registry caps each validator at 10% of stake
+        for i in 10..20 {
+            system.liquid_staking.add_validator(env.get_account(i), 99, 5, cspr(1_000_000));
+        }
+
+        env.set_caller(staking_user);
+        system.vault.set_risk_preference(StrategyExposure::StakingOnly.to_u8());
+        let vault_purse = env.balance_of(system.vault.address());
+        let staking_purse = env.balance_of(system.liquid_staking.address());
+        let credited = system.vault.with_tokens(cspr(1000)).deposit();
+        assert_u512_eq(credited, cspr(1000), "Staked at 1:1");
+
+        // The CSPR went to LiquidStaking and came back as lstCSPR
+        assert_u512_eq(env.balance_of(system.vault.address()), vault_purse, "Nothing kept in the purse");
+        assert_u512_eq(
+            env.balance_of(system.liquid_staking.address()) - staking_purse,
+            cspr(1000),
+            "Staked through LiquidStaking",
+        );
+        assert_u512_eq(system.liquid_staking.get_total_staked(), cspr(1000), "Delegated");
+        assert_u512_eq(system.lst_cspr.balance_of(*system.vault.address()), cspr(1000), "Minted to the vault");
+        assert_u512_eq(system.vault.get_staking_only_balance(staking_user), cspr(1000), "Bucket balance");
+        assert_u512_eq(system.vault.get_user_shares(staking_user), U512::zero(), "No shares minted");
+        assert_u512_eq(system.vault.total_assets(), U512::zero(), "Bucket outside total assets");
+        let event = env.get_event::<StakingOnlyDeposit>(system.vault.address(), -1).unwrap();
+        assert_eq!(event.user, staking_user);
+        assert_u512_eq(event.lst_cspr_amount, cspr(1000), "Event amount");
+
+        let receipt = system.vault.with_tokens(cspr(500)).deposit_with_receipt();
+        assert_u512_eq(receipt.assets_accepted, cspr(500), "Receipt carries the lstCSPR credited");
+        assert_u512_eq(receipt.shares_minted, U512::zero(), "Receipt mints nothing");
+
+        // And the bucket pays out the lstCSPR it staked into
+        assert_u512_eq(system.vault.withdraw_staking_only(cspr(1500)), cspr(1500), "Bucket paid in full");
+        assert_u512_eq(system.lst_cspr.balance_of(staking_user), cspr(1500), "Paid in lstCSPR");
+        assert_u512_eq(system.lst_cspr.balance_of(*system.vault.address()), U512::zero(), "Vault owes nothing");
+        assert_u512_eq(system.vault.get_staking_only_assets(), U512::zero(), "Bucket empty");
+    }
+
+    #[test]
+    fn test_staking_only_cspr_deposit_needs_liquid_staking() {
+        let env = odra_test::env();
+        let (full_user, staking_user) = (env.get_account(1), env.get_account(2));
+        let mut vault = SystemBuilder::new(&env).with_lst().with_vault().build().vault.unwrap();
+
+        env.set_caller(staking_user);
+        vault.set_risk_preference(StrategyExposure::StakingOnly.to_u8());
+        assert_eq!(
+            vault.with_tokens(cspr(100)).try_deposit(),
+            Err(VaultError::StakingUnavailable.into())
+        );
+        assert_eq!(
+            vault.with_tokens(cspr(100)).try_deposit_with_receipt(),
+            Err(VaultError::StakingUnavailable.into())
+        );
+
+        // Full depositors don't stake
+        env.set_caller(full_user);
+        assert_u512_eq(vault.with_tokens(cspr(100)).deposit(), cspr(100), "Full CSPR deposit mints shares");
+    }
+
+    #[test]
+    fn test_invalid_risk_preference_reverts() {
+        let mut f = setup();
+        f.env.set_caller(f.full_user);
+        assert_eq!(f.vault.try_set_risk_preference(2), Err(VaultError::InvalidRequest.into()));
+        assert_eq!(f.vault.try_get_yield_index(2), Err(VaultError::InvalidRequest.into()));
+
+        // Switching back restores share deposits
+        let staking_user = f.staking_user;
+        f.env.set_caller(staking_user);
+        f.vault.set_risk_preference(StrategyExposure::Full.to_u8());
+        let shares = deposit_lst(&mut f, staking_user, cspr(100));
+        assert_u512_eq(shares, cspr(100), "Shares minted again");
+        assert_u512_eq(f.vault.get_staking_only_balance(staking_user), U512::zero(), "Bucket untouched");
+    }
+}
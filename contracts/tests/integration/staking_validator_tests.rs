@@ -0,0 +1,942 @@
+#[cfg(test)]
+mod staking_validator_tests {
+    use odra::prelude::*;
+    use odra::casper_types::account::AccountHash;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{
+        LiquidStakingHostRef, DEFAULT_ERA_DURATION, MAX_ERA_DURATION, MAX_UNBONDING_PERIOD,
+        MIN_ERA_DURATION, RequestStatus,
+    };
+    use caspervault_contracts::{
+        AccessError, CompoundRewards, ExchangeRateSynced, InsurancePayout, MockValidatorAdapterHostRef,
+        MockValidatorHostRef, Stake, StakingError, Unstake, ValidatorEmergencyExited,
+        ValidatorRemoved, ValidatorSlashed, VaultError,
+    };
+    use caspervault_contracts::utils::{NETWORK_SHARE_PENALTY_PER_BPS, REWARD_HISTORY_SIZE};
+    use crate::helpers::*;
+
+    const TWELVE_HOURS: u64 = 12 * 60 * 60;
+    const KEEPER_ROLE: u8 = 3;
+    const ONE_DAY: u64 = 24 * 60 * 60;
+
+    struct StakingFixture {
+        env: HostEnv,
+        staking: LiquidStakingHostRef,
+        adapter: MockValidatorAdapterHostRef,
+        validators: Vec<MockValidatorHostRef>,
+    }
+
+    /// LiquidStaking routed through the mock adapter to ten validators
+    /// paying 1% per epoch at 10% commission, with 1000 CSPR staked
+    ///
+    /// The registry caps each validator at 10% of stake, so ten validators
+    /// take 100 CSPR each.
+    fn setup() -> StakingFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        let built = SystemBuilder::new(&env)
+            .with_staking()
+            .with_validator_adapter()
+            .with_mock_validators(10, 100, 10)
+            .build();
+        let mut staking = built.staking.unwrap();
+        let adapter = built.validator_adapter.unwrap();
+        let validators = built.mock_validators;
+        for validator in validators.iter() {
+            staking.add_validator(*validator.address(), 99, 10, cspr(1_000_000));
+        }
+
+        env.set_caller(env.get_account(1));
+        staking.with_tokens(cspr(1000)).stake();
+        env.set_caller(admin);
+
+        StakingFixture { env, staking, adapter, validators }
+    }
+
+    #[test]
+    fn test_epoch_rewards_flow_into_exchange_rate() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+
+        for validator in f.validators.iter() {
+            assert_u512_eq(validator.get_delegation(staking_address), cspr(100), "Stake reached the validator");
+        }
+
+        // 1 CSPR gross per validator, 0.9 after commission
+        for validator in f.validators.iter_mut() {
+            validator.distribute_epoch_rewards();
+        }
+
+        f.env.advance_block_time(TWELVE_HOURS);
+        let rewards = f.staking.compound_rewards();
+        assert_u512_eq(rewards, cspr(9), "Net epoch rewards compounded");
+        let event = f.env.get_event::<CompoundRewards>(f.staking.address(), -1).unwrap();
+        assert_u512_eq(event.gross_rewards, cspr(10), "Gross before the validators' commission");
+        assert_u512_eq(f.staking.get_total_commission_paid(), cspr(1), "Commission recorded");
+        assert_u512_eq(f.adapter.get_total_claimed(), cspr(9), "Claimed through the adapter");
+        assert_u512_eq(f.staking.get_total_staked(), cspr(1009), "Rewards added to stake");
+        assert_eq!(f.staking.get_exchange_rate(), U256::from(1_009_000_000u64), "1009 / 1000");
+
+        // Rewards were restaked at the validators
+        let restaked = U512::from(100_900_000_000u64);
+        assert_u512_eq(f.validators[0].get_delegation(staking_address), restaked, "Restaked");
+        assert_u512_eq(f.validators[0].get_pending_rewards(staking_address), U512::zero(), "Nothing left to claim");
+    }
+
+    #[test]
+    fn test_slashing_epoch_lowers_exchange_rate() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+        let slashed = *f.validators[0].address();
+
+        f.validators[0].simulate_slashing(10);
+
+        f.env.advance_block_time(TWELVE_HOURS);
+        let rewards = f.staking.compound_rewards();
+        assert_u512_eq(rewards, U512::zero(), "No rewards this epoch");
+
+        let event = f.env.get_event::<ValidatorSlashed>(f.staking.address(), -1).unwrap();
+        assert_eq!(event.validator, slashed);
+        assert_u512_eq(event.amount, cspr(10), "10% of 100 CSPR");
+        assert_u512_eq(event.remaining_stake, cspr(90), "Remaining stake");
+
+        assert_u512_eq(f.staking.get_delegation(slashed), cspr(90), "Delegation written down");
+        assert_u512_eq(f.staking.get_total_staked(), cspr(990), "Loss taken from stake");
+        assert_eq!(f.staking.get_exchange_rate(), U256::from(990_000_000u64), "990 / 1000");
+        assert_u512_eq(f.staking.lst_cspr_to_cspr_precise(cspr(100)), cspr(99), "Holders share the loss");
+        assert_u512_eq(f.validators[0].get_delegation(staking_address), cspr(90), "Matches the validator");
+    }
+
+    #[test]
+    fn test_donation_unlocks_into_rate_after_sync() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+        let donated = *f.validators[0].address();
+
+        // Delegated to the staking contract without going through stake()
+        f.validators[0].delegate(staking_address, cspr(50));
+        assert_u512_eq(f.staking.lst_cspr_to_cspr_precise(cspr(100)), cspr(100), "Donation doesn't move the rate");
+        assert_eq!(f.staking.get_backing(), (cspr(1000), cspr(1050)));
+
+        f.staking.sync_exchange_rate();
+        let event = f.env.get_event::<ExchangeRateSynced>(f.staking.address(), -1).unwrap();
+        assert_u512_eq(event.tracked, cspr(1000), "Tracked before sync");
+        assert_u512_eq(event.actual, cspr(1050), "Actual backing");
+        assert_u512_eq(event.delta, cspr(50), "Donated surplus");
+
+        assert_u512_eq(f.staking.get_total_staked(), cspr(1050), "Surplus booked");
+        assert_u512_eq(f.staking.get_delegation(donated), cspr(150), "Delegation reconciled");
+        assert_u512_eq(f.staking.get_locked_surplus(), cspr(50), "Surplus locked");
+        assert_u512_eq(f.staking.lst_cspr_to_cspr_precise(cspr(100)), cspr(100), "No instant jump");
+
+        f.env.advance_block_time(TWELVE_HOURS);
+        assert_u512_eq(f.staking.get_locked_surplus(), cspr(25), "Half unlocked");
+        assert_u512_eq(
+            f.staking.lst_cspr_to_cspr_precise(cspr(100)),
+            U512::from(102_500_000_000u64),
+            "Half the surplus in the rate",
+        );
+
+        f.env.advance_block_time(TWELVE_HOURS);
+        assert_u512_eq(f.staking.lst_cspr_to_cspr_precise(cspr(100)), cspr(105), "Fully unlocked");
+
+        // Nothing left to reconcile
+        f.staking.sync_exchange_rate();
+        let event = f.env.get_event::<ExchangeRateSynced>(f.staking.address(), -1).unwrap();
+        assert_u512_eq(event.delta, U512::zero(), "Already in sync");
+        assert_u512_eq(f.staking.get_total_staked(), cspr(1050), "Not booked twice");
+    }
+
+    #[test]
+    fn test_small_deficit_written_off_on_sync() {
+        let mut f = setup();
+        let keeper = f.env.get_account(2);
+        f.staking.grant_role(KEEPER_ROLE, keeper);
+
+        // 1 CSPR lost: 0.1% of the stake, inside the 0.5% tolerance
+        f.validators[0].simulate_slashing(1);
+
+        f.env.set_caller(f.env.get_account(5));
+        assert!(f.staking.try_sync_exchange_rate().is_err(), "Maintainers only");
+
+        f.env.set_caller(keeper);
+        f.staking.sync_exchange_rate();
+        assert_u512_eq(f.staking.get_total_staked(), cspr(999), "Deficit written off");
+        assert_u512_eq(f.staking.get_pending_deficit(), U512::zero(), "No confirmation needed");
+        assert_eq!(f.staking.get_exchange_rate(), U256::from(999_000_000u64), "999 / 1000");
+    }
+
+    #[test]
+    fn test_large_deficit_waits_for_admin_confirmation() {
+        let mut f = setup();
+        let admin = f.env.get_account(0);
+        let keeper = f.env.get_account(2);
+        f.staking.grant_role(KEEPER_ROLE, keeper);
+
+        // 10 CSPR lost: 1% of the stake
+        f.validators[0].simulate_slashing(10);
+
+        f.env.set_caller(keeper);
+        f.staking.sync_exchange_rate();
+        assert_u512_eq(f.staking.get_pending_deficit(), cspr(10), "Deficit parked");
+        assert_u512_eq(f.staking.get_total_staked(), cspr(1000), "Not written off yet");
+        assert_u512_eq(f.staking.lst_cspr_to_cspr_precise(cspr(100)), cspr(100), "Rate unchanged");
+        assert!(f.staking.try_confirm_sync_deficit().is_err(), "Admin only");
+
+        f.env.set_caller(admin);
+        f.staking.confirm_sync_deficit();
+        assert_u512_eq(f.staking.get_total_staked(), cspr(990), "Written off on confirmation");
+        assert_u512_eq(f.staking.get_pending_deficit(), U512::zero(), "Cleared");
+        assert_u512_eq(f.staking.lst_cspr_to_cspr_precise(cspr(100)), cspr(99), "Holders share the loss");
+        assert!(f.staking.try_confirm_sync_deficit().is_err(), "Nothing left to confirm");
+    }
+
+    #[test]
+    fn test_insurance_covers_slash_smaller_than_fund() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+        let slashed = *f.validators[0].address();
+
+        f.staking.with_tokens(cspr(50)).fund_insurance();
+        assert_u512_eq(f.staking.get_insurance_fund_balance(), cspr(50), "Fund topped up");
+        assert_eq!(f.staking.get_insurance_coverage_bps(), 500, "50 / 1000 staked");
+
+        f.validators[0].simulate_slashing(10);
+        f.env.advance_block_time(TWELVE_HOURS);
+        f.staking.compound_rewards();
+
+        let event = f.env.get_event::<InsurancePayout>(f.staking.address(), -1).unwrap();
+        assert_eq!(event.validator, slashed);
+        assert_u512_eq(event.covered, cspr(10), "Whole loss covered");
+        assert_u512_eq(event.uncovered, U512::zero(), "Nothing left for holders");
+        assert_u512_eq(event.fund_balance, cspr(40), "Fund after payout");
+
+        assert_u512_eq(f.staking.get_total_staked(), cspr(1000), "Stake unchanged");
+        assert_eq!(f.staking.get_exchange_rate(), U256::from(1_000_000_000u64), "Rate unchanged");
+        assert_u512_eq(f.staking.get_delegation(slashed), cspr(100), "Covered stake delegated back");
+        assert_u512_eq(f.validators[0].get_delegation(staking_address), cspr(100), "Matches the validator");
+        assert_eq!(f.staking.get_backing(), (cspr(1000), cspr(1000)), "Nothing for a sync to write off");
+    }
+
+    #[test]
+    fn test_insurance_partially_covers_larger_slash() {
+        let mut f = setup();
+
+        f.staking.with_tokens(cspr(4)).fund_insurance();
+        f.validators[0].simulate_slashing(10);
+        f.env.advance_block_time(TWELVE_HOURS);
+        f.staking.compound_rewards();
+
+        let event = f.env.get_event::<InsurancePayout>(f.staking.address(), -1).unwrap();
+        assert_u512_eq(event.covered, cspr(4), "Fund emptied");
+        assert_u512_eq(event.uncovered, cspr(6), "Rest of the 10 CSPR loss");
+
+        assert_u512_eq(f.staking.get_insurance_fund_balance(), U512::zero(), "Nothing left in the fund");
+        assert_u512_eq(f.staking.get_total_staked(), cspr(994), "Only the uncovered loss written off");
+        assert_eq!(f.staking.get_exchange_rate(), U256::from(994_000_000u64), "994 / 1000");
+    }
+
+    #[test]
+    fn test_insurance_excess_withdrawal_is_timelocked() {
+        let mut f = setup();
+        let admin = f.env.get_account(0);
+
+        f.staking.with_tokens(cspr(50)).fund_insurance();
+        f.staking.set_insurance_target(cspr(20));
+        assert_u512_eq(f.staking.get_insurance_excess(), cspr(30), "Fund above the target");
+
+        assert!(f.staking.try_request_insurance_withdrawal(cspr(31)).is_err(), "Target stays in the fund");
+        f.env.set_caller(f.env.get_account(5));
+        assert!(f.staking.try_request_insurance_withdrawal(cspr(30)).is_err(), "Admin only");
+
+        f.env.set_caller(admin);
+        f.staking.request_insurance_withdrawal(cspr(30));
+        assert!(f.staking.try_execute_insurance_withdrawal().is_err(), "Timelocked");
+
+        f.env.advance_block_time(2 * ONE_DAY);
+        let balance_before = f.env.balance_of(&admin);
+        assert_u512_eq(f.staking.execute_insurance_withdrawal(), cspr(30), "Excess withdrawn");
+        assert_u512_eq(f.env.balance_of(&admin) - balance_before, cspr(30), "Paid to the admin");
+        assert_u512_eq(f.staking.get_insurance_fund_balance(), cspr(20), "Target kept");
+        assert_eq!(f.staking.get_pending_insurance_withdrawal().0, U512::zero(), "Request consumed");
+        assert!(f.staking.try_execute_insurance_withdrawal().is_err(), "Nothing pending");
+    }
+
+    #[test]
+    fn test_only_fee_source_credits_insurance() {
+        let mut f = setup();
+        let fee_source = f.env.get_account(4);
+
+        f.env.set_caller(fee_source);
+        assert!(f.staking.with_tokens(cspr(5)).try_credit_insurance_fees().is_err(), "No fee source set");
+
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.set_insurance_fee_source(fee_source);
+
+        f.env.set_caller(fee_source);
+        let purse_before = f.env.balance_of(f.staking.address());
+        f.staking.with_tokens(cspr(5)).credit_insurance_fees();
+        assert_u512_eq(f.staking.get_insurance_fund_balance(), cspr(5), "Fee slice credited");
+        assert_u512_eq(f.env.balance_of(f.staking.address()) - purse_before, cspr(5), "Purse backs the fund");
+
+        f.env.set_caller(f.env.get_account(5));
+        assert!(f.staking.with_tokens(cspr(5)).try_credit_insurance_fees().is_err(), "Other accounts can't credit");
+    }
+
+    #[test]
+    fn test_unbonding_requests_listed_and_totalled_per_user() {
+        let mut f = setup();
+        let (user, other) = (f.env.get_account(1), f.env.get_account(3));
+
+        f.env.set_caller(user);
+        let first = f.staking.unstake(cspr(100));
+        f.env.advance_block_time(ONE_DAY);
+        let second = f.staking.unstake(cspr(200));
+        f.env.advance_block_time(ONE_DAY);
+        let third = f.staking.unstake(cspr(300));
+
+        f.env.set_caller(other);
+        f.staking.with_tokens(cspr(100)).stake();
+        let other_request = f.staking.unstake(cspr(50));
+
+        assert_eq!(f.staking.get_user_unbonding_requests(user), vec![first, second, third]);
+        assert_eq!(f.staking.get_user_unbonding_requests(other), vec![other_request]);
+        assert_u512_eq(f.staking.get_pending_unbonding_total(), cspr(650), "All requests in flight");
+        assert_u512_eq(f.staking.get_user_pending_unbonding(user), cspr(600), "User's requests");
+        assert!(f.staking.get_claimable_requests(user).is_empty(), "Nothing unlocked yet");
+
+        // The first request unlocks 14 days after it was made
+        f.env.advance_block_time(12 * ONE_DAY);
+        assert_eq!(f.staking.get_claimable_requests(user), vec![first]);
+        f.env.advance_block_time(ONE_DAY);
+        assert_eq!(f.staking.get_claimable_requests(user), vec![first, second]);
+
+        f.env.set_caller(user);
+        f.staking.complete_unbonding(first);
+        assert_eq!(f.staking.get_unbonding_request(first).unwrap().status, RequestStatus::Completed);
+        assert_eq!(f.staking.get_unbonding_request(second).unwrap().status, RequestStatus::Pending);
+        assert_eq!(f.staking.get_user_unbonding_requests(user), vec![second, third], "Completed request pruned");
+        assert_eq!(f.staking.get_claimable_requests(user), vec![second]);
+        assert_u512_eq(f.staking.get_pending_unbonding_total(), cspr(550), "Total drops on completion");
+
+        f.env.advance_block_time(ONE_DAY);
+        f.staking.complete_unbonding(second);
+        f.staking.complete_unbonding(third);
+        assert!(f.staking.get_user_unbonding_requests(user).is_empty());
+        assert_u512_eq(f.staking.get_user_pending_unbonding(user), U512::zero(), "User fully unbonded");
+        assert_u512_eq(f.staking.get_pending_unbonding_total(), cspr(50), "Other user's request remains");
+    }
+
+    #[test]
+    fn test_withdrawal_freeze_blocks_matured_unbonding() {
+        let mut f = setup();
+        let user = f.env.get_account(1);
+
+        f.env.set_caller(user);
+        let request_id = f.staking.unstake(cspr(100));
+        f.env.advance_block_time(14 * ONE_DAY);
+
+        assert_eq!(f.staking.try_freeze_withdrawals(), Err(VaultError::Unauthorized.into()));
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.freeze_withdrawals();
+
+        f.env.set_caller(user);
+        assert_eq!(f.staking.try_complete_unbonding(request_id), Err(VaultError::WithdrawalsFrozen.into()));
+        // Staking and new unstakes are unaffected
+        f.staking.with_tokens(cspr(10)).stake();
+        f.staking.unstake(cspr(10));
+
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.unfreeze_withdrawals();
+        f.env.set_caller(user);
+        assert_u512_eq(f.staking.complete_unbonding(request_id), cspr(100), "Matured request completes");
+    }
+
+    #[test]
+    fn test_unbonding_period_bounds() {
+        let mut f = setup();
+        let invalid = Err(StakingError::InvalidUnbondingPeriod.into());
+
+        let info = f.staking.get_unbonding_info();
+        assert_eq!(info.period_seconds, 14 * ONE_DAY);
+        assert_eq!(info.period_eras, 168, "14 days of 2-hour eras");
+        assert_eq!(info.era_duration, DEFAULT_ERA_DURATION);
+        assert_eq!(info.min_period_seconds, 7 * 2 * 60 * 60);
+
+        // At least seven eras, at most 30 days
+        assert_eq!(f.staking.try_set_unbonding_period(60), invalid);
+        assert_eq!(f.staking.try_set_unbonding_period(14 * 60 * 60 - 1), invalid);
+        assert_eq!(f.staking.try_set_unbonding_period(MAX_UNBONDING_PERIOD + 1), invalid);
+        f.staking.set_unbonding_period(MAX_UNBONDING_PERIOD);
+        f.staking.set_unbonding_period(14 * 60 * 60);
+        assert_eq!(f.staking.get_unbonding_info().period_eras, 7);
+
+        // Longer eras can't push the chain minimum past the current period
+        assert_eq!(f.staking.try_set_era_duration(4 * 60 * 60), invalid);
+        assert_eq!(f.staking.try_set_era_duration(MIN_ERA_DURATION - 1), invalid);
+        assert_eq!(f.staking.try_set_era_duration(MAX_ERA_DURATION + 1), invalid);
+        f.staking.set_unbonding_period(2 * ONE_DAY);
+        f.staking.set_era_duration(4 * 60 * 60);
+        let info = f.staking.get_unbonding_info();
+        assert_eq!(info.period_eras, 12, "Eras recomputed for the new length");
+        assert_eq!(info.min_period_seconds, 28 * 60 * 60);
+
+        f.env.set_caller(f.env.get_account(1));
+        assert_eq!(f.staking.try_set_unbonding_period(ONE_DAY), Err(VaultError::Unauthorized.into()));
+        assert_eq!(f.staking.try_set_era_duration(ONE_DAY), Err(VaultError::Unauthorized.into()));
+    }
+
+    #[test]
+    fn test_unbonding_request_keeps_period_after_change() {
+        let mut f = setup();
+        let user = f.env.get_account(1);
+
+        f.env.set_caller(user);
+        let before = f.staking.get_unbonding_info().earliest_completion;
+        let old_request = f.staking.unstake(cspr(100));
+        assert_eq!(f.staking.get_unbonding_request(old_request).unwrap().unlock_time, before);
+
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.set_unbonding_period(3 * ONE_DAY);
+
+        f.env.set_caller(user);
+        let new_request = f.staking.unstake(cspr(100));
+        assert_eq!(f.staking.get_unbonding_request(old_request).unwrap().unlock_time, before, "Unlock time unchanged");
+
+        f.env.advance_block_time(3 * ONE_DAY);
+        assert_eq!(f.staking.try_complete_unbonding(old_request), Err(VaultError::TimelockNotExpired.into()));
+        assert_u512_eq(f.staking.complete_unbonding(new_request), cspr(100), "New period applies to new requests");
+
+        f.env.advance_block_time(11 * ONE_DAY);
+        assert_u512_eq(f.staking.complete_unbonding(old_request), cspr(100), "Old request completes on its own schedule");
+    }
+
+    #[test]
+    fn test_complete_unbonding_enforces_era_minimum() {
+        let mut f = setup();
+        let user = f.env.get_account(1);
+        f.staking.set_unbonding_period(14 * 60 * 60);
+
+        f.env.set_caller(user);
+        let request_id = f.staking.unstake(cspr(100));
+
+        // Eras turn out twice as long: the chain releases after 28 hours
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.set_unbonding_period(2 * ONE_DAY);
+        f.staking.set_era_duration(4 * 60 * 60);
+
+        f.env.set_caller(user);
+        f.env.advance_block_time(14 * 60 * 60);
+        assert_eq!(f.staking.try_complete_unbonding(request_id), Err(VaultError::TimelockNotExpired.into()));
+
+        f.env.advance_block_time(14 * 60 * 60);
+        assert_u512_eq(f.staking.complete_unbonding(request_id), cspr(100), "Completes once seven eras have passed");
+    }
+
+    /// Three validators holding 100 CSPR each behind the mock adapter
+    fn setup_three_validators() -> (HostEnv, LiquidStakingHostRef, Vec<MockValidatorHostRef>) {
+        let env = odra_test::env();
+        let built = SystemBuilder::new(&env)
+            .with_staking()
+            .with_validator_adapter()
+            .with_mock_validators(3, 100, 10)
+            .build();
+        let mut staking = built.staking.unwrap();
+        let validators = built.mock_validators;
+        for validator in validators.iter() {
+            staking.add_validator(*validator.address(), 99, 10, cspr(1_000_000));
+        }
+
+        env.set_caller(env.get_account(1));
+        staking.with_tokens(cspr(300)).stake();
+
+        (env, staking, validators)
+    }
+
+    #[test]
+    fn test_unstake_undelegates_exact_amount_with_uneven_split() {
+        let (env, mut staking, validators) = setup_three_validators();
+        let staking_address = *staking.address();
+
+        // A prime number of motes doesn't split into thirds
+        let amount = U512::from(1_000_000_007u64);
+        let request = staking.unstake(amount);
+
+        let delegated = validators.iter().fold(U512::zero(), |sum, validator| {
+            sum + staking.get_delegation(*validator.address())
+        });
+        assert_u512_eq(delegated, cspr(300) - amount, "Exactly the unstaked amount left the validators");
+        for validator in validators.iter() {
+            assert_u512_eq(
+                validator.get_delegation(staking_address),
+                staking.get_delegation(*validator.address()),
+                "Validator agrees with the recorded delegation",
+            );
+            let undelegated = cspr(100) - staking.get_delegation(*validator.address());
+            assert!(undelegated >= U512::from(333_333_335u64), "Roughly a third each: {}", undelegated);
+        }
+
+        assert_u512_eq(staking.get_total_staked(), cspr(300) - amount, "Total staked matches delegations");
+        assert_u512_eq(staking.get_pending_unbonding_total(), amount, "Unbonding request for the full amount");
+        assert_eq!(staking.get_user_unbonding_requests(env.get_account(1)), vec![request]);
+    }
+
+    #[test]
+    fn test_repeated_uneven_unstakes_keep_delegations_in_sync() {
+        let (_env, mut staking, validators) = setup_three_validators();
+
+        let mut unstaked = U512::zero();
+        for amount in [7u64, 1_000_000_007, 13_333_333_331, 99_999_999_989] {
+            staking.unstake(U512::from(amount));
+            unstaked += U512::from(amount);
+
+            let delegated = validators.iter().fold(U512::zero(), |sum, validator| {
+                sum + staking.get_delegation(*validator.address())
+            });
+            assert_u512_eq(delegated, cspr(300) - unstaked, "No remainder left delegated");
+            assert_u512_eq(staking.get_total_staked(), delegated, "Stake and delegations agree");
+        }
+    }
+
+    #[test]
+    fn test_estimated_rewards_net_of_commission() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        let mut staking = SystemBuilder::new(&env).with_staking().build().staking.unwrap();
+
+        // Four validators at 0%, three at 5%, three at 10% commission
+        let commissions: Vec<u64> = vec![0, 0, 0, 0, 5, 5, 5, 10, 10, 10];
+        let validators: Vec<Address> = (0..10).map(|i| env.get_account(10 + i)).collect();
+        for (validator, commission) in validators.iter().zip(commissions.iter()) {
+            staking.add_validator(*validator, 99, *commission as u8, cspr(1_000_000));
+        }
+
+        env.set_caller(env.get_account(1));
+        staking.with_tokens(cspr(1000)).stake();
+        env.set_caller(admin);
+
+        env.advance_block_time(TWELVE_HOURS);
+        let rewards = staking.compound_rewards();
+
+        // One day at 10% APR on each 100 CSPR delegation, less commission
+        let gross = cspr(100) * U512::from(10u64) / U512::from(36_500u64);
+        let net = |commission: u64| gross * U512::from(100 - commission) / U512::from(100u64);
+
+        let mut expected = U512::zero();
+        for (validator, commission) in validators.iter().zip(commissions.iter()) {
+            assert_u512_eq(staking.get_delegation(*validator), cspr(100) + net(*commission), "Restaked net");
+            expected += net(*commission);
+        }
+
+        let total_gross = gross * U512::from(10u64);
+        assert_u512_eq(rewards, expected, "Net rewards compounded");
+        assert_u512_eq(staking.get_total_staked(), cspr(1000) + expected, "Only net rewards credited");
+        assert_u512_eq(staking.get_total_rewards_earned(), expected, "Lifetime rewards are net");
+        assert_u512_eq(staking.get_total_commission_paid(), total_gross - expected, "Commission recorded");
+
+        let event = env.get_event::<CompoundRewards>(staking.address(), -1).unwrap();
+        assert_u512_eq(event.gross_rewards, total_gross, "Gross in the event");
+        assert_u512_eq(event.total_rewards, expected, "Net in the event");
+    }
+
+    #[test]
+    fn test_reward_history_per_validator() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        // 1%, 2% and 3% per epoch, 10% commission everywhere
+        let rates = [100u32, 200, 300];
+        let built = rates
+            .iter()
+            .fold(SystemBuilder::new(&env).with_staking().with_validator_adapter(), |builder, rate_bps| {
+                builder.with_mock_validator(*rate_bps, 10)
+            })
+            .build();
+        let mut staking = built.staking.unwrap();
+        let mut validators: Vec<_> = built.mock_validators.into_iter().zip(rates).collect();
+        for (validator, _) in validators.iter() {
+            staking.add_validator(*validator.address(), 99, 10, cspr(1_000_000));
+        }
+
+        env.set_caller(env.get_account(1));
+        staking.with_tokens(cspr(300)).stake();
+        env.set_caller(admin);
+
+        let staking_address = *staking.address();
+        let epochs = REWARD_HISTORY_SIZE as usize + 2;
+        let mut expected: Vec<Vec<(U512, u64)>> = vec![Vec::new(); 3];
+        for _ in 0..epochs {
+            env.advance_block_time(TWELVE_HOURS);
+            for (i, (validator, rate_bps)) in validators.iter_mut().enumerate() {
+                let gross = validator.get_delegation(staking_address) * U512::from(*rate_bps) / U512::from(10000u64);
+                expected[i].push((gross - gross / U512::from(10u64), env.get_block_time()));
+                validator.distribute_epoch_rewards();
+            }
+            staking.compound_rewards();
+        }
+
+        for (i, (validator, _)) in validators.iter().enumerate() {
+            let history = staking.get_validator_rewards(*validator.address());
+            let lifetime = expected[i].iter().fold(U512::zero(), |sum, (amount, _)| sum + *amount);
+            let window = &expected[i][epochs - REWARD_HISTORY_SIZE as usize..];
+
+            assert_u512_eq(history.total_rewards_earned, lifetime, "Lifetime net rewards");
+            assert_eq!(history.recent.len(), REWARD_HISTORY_SIZE as usize, "Only the last entries kept");
+            for (entry, (amount, timestamp)) in history.recent.iter().zip(window.iter()) {
+                assert_u512_eq(entry.amount, *amount, "Entry amount, oldest first");
+                assert_eq!(entry.timestamp, *timestamp);
+            }
+            assert_u512_eq(
+                history.window_rewards,
+                window.iter().fold(U512::zero(), |sum, (amount, _)| sum + *amount),
+                "Windowed total",
+            );
+            assert!(history.window_rewards < history.total_rewards_earned, "Oldest epochs dropped from the window");
+        }
+
+        let lifetime_of = |i: usize| staking.get_validator_rewards(*validators[i].0.address()).total_rewards_earned;
+        assert_eq!(
+            staking.get_top_validators_by_rewards(2),
+            vec![(*validators[2].0.address(), lifetime_of(2)), (*validators[1].0.address(), lifetime_of(1))],
+            "Best earners first",
+        );
+        assert_eq!(staking.get_top_validators_by_rewards(50).len(), 3, "k is capped, not an error");
+        assert_u512_eq(
+            staking.get_total_rewards_earned(),
+            lifetime_of(0) + lifetime_of(1) + lifetime_of(2),
+            "Per-validator figures add up",
+        );
+    }
+
+    #[test]
+    fn test_validator_added_through_staking_is_selected_by_next_stake() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+
+        let newcomer = SystemBuilder::mock_validator(&f.env, 100, 5);
+        f.staking.add_validator(*newcomer.address(), 98, 5, cspr(1_000_000));
+        assert!(f.staking.get_active_validators().contains(newcomer.address()));
+        let metrics = f.staking.get_validator(*newcomer.address()).unwrap();
+        assert_eq!((metrics.uptime_percentage, metrics.commission_rate), (98, 5));
+
+        // The empty newcomer is the most underweight validator
+        f.env.set_caller(f.env.get_account(2));
+        f.staking.with_tokens(cspr(100)).stake();
+        assert!(!newcomer.get_delegation(staking_address).is_zero(), "New stake reached the newcomer");
+        assert_u512_eq(
+            f.staking.get_delegation(*newcomer.address()),
+            newcomer.get_delegation(staking_address),
+            "Delegation recorded",
+        );
+    }
+
+    #[test]
+    fn test_removing_validator_undelegates_its_stake() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+        let removed = *f.validators[0].address();
+
+        f.staking.remove_validator(removed, "Downtime".to_string());
+        assert!(!f.staking.get_active_validators().contains(&removed));
+        assert!(f.staking.get_delegation(removed).is_zero());
+        assert!(f.validators[0].get_delegation(staking_address).is_zero(), "Undelegated at the validator");
+        let event = f.env.get_event::<ValidatorRemoved>(f.staking.address(), -1).unwrap();
+        assert_eq!(event, ValidatorRemoved { validator: removed, reason: "Downtime".to_string() });
+
+        // New stake skips it
+        f.env.set_caller(f.env.get_account(2));
+        f.staking.with_tokens(cspr(100)).stake();
+        assert!(f.staking.get_delegation(removed).is_zero());
+
+        f.env.set_caller(f.env.get_account(0));
+        assert_eq!(
+            f.staking.try_remove_validator(f.env.get_account(7), "Unknown".to_string()),
+            Err(StakingError::ValidatorNotFound.into())
+        );
+        f.env.set_caller(f.env.get_account(3));
+        assert_eq!(
+            f.staking.try_remove_validator(*f.validators[1].address(), "Not admin".to_string()),
+            Err(AccessError::MissingRole.into())
+        );
+    }
+
+    #[test]
+    fn test_validator_registered_once() {
+        let mut f = setup();
+        let known = *f.validators[1].address();
+        assert!(f.staking.validator_exists(known));
+        assert!(!f.staking.validator_exists(f.env.get_account(7)));
+
+        assert_eq!(
+            f.staking.try_add_validator(known, 99, 10, cspr(1_000_000)),
+            Err(StakingError::ValidatorAlreadyRegistered.into())
+        );
+        assert_eq!(f.staking.get_active_validators().len(), 10);
+
+        // Removed validators keep their history and can't come back as new
+        f.staking.remove_validator(known, "Downtime".to_string());
+        assert!(!f.staking.validator_exists(known));
+        assert_eq!(
+            f.staking.try_add_validator(known, 99, 10, cspr(1_000_000)),
+            Err(StakingError::ValidatorAlreadyRegistered.into())
+        );
+    }
+
+    #[test]
+    fn test_protocol_addresses_cannot_be_validators() {
+        let mut f = setup();
+        let zero = Address::Account(AccountHash::new([0u8; 32]));
+
+        for address in [*f.staking.address(), *f.adapter.address(), f.env.get_account(8), zero] {
+            assert_eq!(
+                f.staking.try_add_validator(address, 99, 10, cspr(1_000_000)),
+                Err(StakingError::InvalidValidator.into())
+            );
+            assert!(!f.staking.validator_exists(address));
+        }
+        assert_eq!(f.staking.get_active_validators().len(), 10);
+    }
+
+    #[test]
+    fn test_duplicate_active_entry_is_not_double_counted() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+        let doubled = *f.validators[0].address();
+        let rewards_estimate = f.staking.get_pending_rewards_estimate();
+
+        f.staking.test_push_active_validator(doubled);
+        assert_eq!(f.staking.get_active_validators().len(), 10, "Read back once");
+        assert_u512_eq(f.staking.get_pending_rewards_estimate(), rewards_estimate, "Estimated once");
+
+        // Selection treats it like its twin with the same stake and terms
+        f.env.set_caller(f.env.get_account(2));
+        f.staking.with_tokens(cspr(100)).stake();
+        assert_u512_eq(
+            f.staking.get_delegation(doubled),
+            f.staking.get_delegation(*f.validators[1].address()),
+            "No extra share of the new stake",
+        );
+        let delegated = f.validators.iter().fold(U512::zero(), |sum, validator| {
+            sum + validator.get_delegation(staking_address)
+        });
+        assert_u512_eq(delegated, f.staking.get_total_staked(), "Delegations match total staked");
+    }
+
+    #[test]
+    fn test_network_share_lowers_selection_score() {
+        let (env, mut staking, validators) = setup_three_validators();
+        let (concentrated, other) = (*validators[0].address(), *validators[1].address());
+        assert_eq!(staking.get_validator_score(concentrated), staking.get_validator_score(other));
+
+        // 12% of the network is already delegated to the first validator
+        env.set_caller(env.get_account(0));
+        staking.report_validator_network_stake(concentrated, cspr(12_000), cspr(100_000));
+        assert_eq!(staking.get_validator_network_share(concentrated), 1200);
+
+        let penalized = staking.get_validator_score(concentrated).unwrap();
+        let clean = staking.get_validator_score(other).unwrap();
+        assert_eq!(clean - penalized, 1200 * NETWORK_SHARE_PENALTY_PER_BPS);
+        assert!(clean - penalized > clean / 10, "Materially lower");
+
+        // Stale data falls back to protocol stake only
+        env.advance_block_time(ONE_DAY + 1);
+        assert_eq!(staking.get_validator_network_share(concentrated), 0);
+        assert_eq!(staking.get_validator_score(concentrated), Some(clean));
+
+        env.set_caller(env.get_account(3));
+        assert_eq!(
+            staking.try_report_validator_network_stake(other, cspr(1), cspr(100)),
+            Err(VaultError::Unauthorized.into())
+        );
+        env.set_caller(env.get_account(0));
+        assert!(staking.try_report_validator_network_stake(other, cspr(2), cspr(1)).is_err(), "Share above 100%");
+    }
+
+    #[test]
+    fn test_network_share_cap_excludes_validator() {
+        let mut f = setup();
+        let staking_address = *f.staking.address();
+        let (capped, penalized) = (&f.validators[0], &f.validators[1]);
+
+        f.staking.report_validator_network_stake(*capped.address(), cspr(20_000), cspr(100_000));
+        f.staking.report_validator_network_stake(*penalized.address(), cspr(12_000), cspr(100_000));
+        assert_eq!(f.staking.get_network_share_limits(), (1500, ONE_DAY));
+
+        f.env.set_caller(f.env.get_account(2));
+        f.staking.with_tokens(cspr(100)).stake();
+        assert_u512_eq(capped.get_delegation(staking_address), cspr(100), "20% share gets nothing new");
+        assert!(penalized.get_delegation(staking_address) > cspr(100), "12% share is still eligible");
+
+        // Raising the cap readmits it
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.set_network_share_limits(2500, ONE_DAY);
+        f.env.set_caller(f.env.get_account(2));
+        f.staking.with_tokens(cspr(100)).stake();
+        assert!(capped.get_delegation(staking_address) > cspr(100));
+    }
+
+    /// One validator holding 400 of 1000 staked CSPR and three holding 200
+    /// each, the three capped at `cap`
+    ///
+    /// The first validator is capped at 400 and staked alone, so the next
+    /// stake only reaches the other three.
+    fn exit_setup(cap: U512) -> StakingFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        let built = SystemBuilder::new(&env)
+            .with_staking()
+            .with_validator_adapter()
+            .with_mock_validators(4, 100, 10)
+            .build();
+        let mut staking = built.staking.unwrap();
+        let adapter = built.validator_adapter.unwrap();
+        let validators = built.mock_validators;
+        staking.set_max_per_validator_pct(100);
+
+        for i in 0..4 {
+            if i == 0 {
+                staking.add_validator(*validators[0].address(), 99, 10, cspr(400));
+                env.set_caller(env.get_account(1));
+                staking.with_tokens(cspr(400)).stake();
+                env.set_caller(admin);
+            } else {
+                staking.add_validator(*validators[i].address(), 99, 10, cap);
+            }
+        }
+
+        env.set_caller(env.get_account(1));
+        staking.with_tokens(cspr(600)).stake();
+        env.set_caller(admin);
+
+        StakingFixture { env, staking, adapter, validators }
+    }
+
+    #[test]
+    fn test_emergency_exit_redistributes_within_caps() {
+        let mut f = exit_setup(cspr(400));
+        let staking_address = *f.staking.address();
+        let exited = *f.validators[0].address();
+        assert_u512_eq(f.validators[0].get_delegation(staking_address), cspr(400), "40% of stake");
+
+        f.env.set_caller(f.env.get_account(1));
+        assert_eq!(f.staking.try_emergency_exit_validator(exited), Err(VaultError::Unauthorized.into()));
+        f.env.set_caller(f.env.get_account(0));
+
+        assert_u512_eq(f.staking.emergency_exit_validator(exited), cspr(400), "Whole delegation moved");
+        let event = f.env.get_event::<ValidatorEmergencyExited>(&staking_address, -1).unwrap();
+        assert_eq!(event.validator, exited);
+        assert_u512_eq(event.amount_moved, cspr(400), "Amount moved");
+        assert!(!event.cap_override, "Room under every cap");
+        assert_u512_eq(event.buffered, U512::zero(), "Nothing parked");
+        assert_eq!(event.redistributed_to.len(), 3);
+
+        assert_u512_eq(f.validators[0].get_delegation(staking_address), U512::zero(), "Exited validator is empty");
+        let mut total = U512::zero();
+        for validator in f.validators[1..].iter() {
+            let delegation = validator.get_delegation(staking_address);
+            assert!(delegation <= cspr(400), "Within the validator's cap");
+            assert!(delegation >= cspr(333), "Spread evenly");
+            total += delegation;
+        }
+        assert_u512_eq(total, cspr(1000), "All stake still delegated");
+        assert_u512_eq(f.staking.get_total_staked(), cspr(1000), "Exchange rate untouched");
+
+        // Blacklisted: new stake never reaches it again
+        f.env.set_caller(f.env.get_account(2));
+        f.staking.with_tokens(cspr(30)).stake();
+        assert_u512_eq(f.validators[0].get_delegation(staking_address), U512::zero(), "Not selected again");
+        f.env.set_caller(f.env.get_account(0));
+        assert_eq!(f.staking.try_emergency_exit_validator(*f.adapter.address()), Err(StakingError::ValidatorNotFound.into()));
+    }
+
+    #[test]
+    fn test_emergency_exit_flags_cap_override() {
+        let mut f = exit_setup(cspr(200));
+        let staking_address = *f.staking.address();
+        let exited = *f.validators[0].address();
+
+        f.staking.emergency_exit_validator(exited);
+        let event = f.env.get_event::<ValidatorEmergencyExited>(&staking_address, -1).unwrap();
+        assert!(event.cap_override, "The remaining validators are full");
+        assert_u512_eq(event.buffered, U512::zero(), "Nothing parked");
+        assert_eq!(event.redistributed_to.len(), 3);
+
+        let delegations: Vec<U512> = f.validators[1..].iter().map(|v| v.get_delegation(staking_address)).collect();
+        assert_u512_eq(delegations[0], cspr(200) + U512::from(133_333_333_333u64), "Even share past the cap");
+        assert_u512_eq(delegations[1], cspr(200) + U512::from(133_333_333_333u64), "Even share past the cap");
+        assert_u512_eq(delegations[2], cspr(200) + U512::from(133_333_333_334u64), "Last takes the residual");
+    }
+
+    #[test]
+    fn test_emergency_exit_without_validators_parks_stake() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let built = SystemBuilder::new(&env).with_staking().with_mock_validator(100, 10).build();
+        let mut staking = built.staking.unwrap();
+        let validator = &built.mock_validators[0];
+        staking.set_max_per_validator_pct(100);
+        staking.add_validator(*validator.address(), 99, 10, cspr(1_000_000));
+        env.set_caller(env.get_account(1));
+        staking.with_tokens(cspr(400)).stake();
+
+        env.set_caller(admin);
+        staking.emergency_exit_validator(*validator.address());
+        let event = env.get_event::<ValidatorEmergencyExited>(staking.address(), -1).unwrap();
+        assert!(event.redistributed_to.is_empty());
+        assert!(!event.cap_override);
+        assert_u512_eq(event.buffered, cspr(400), "Parked");
+        assert_u512_eq(staking.get_liquidity_buffer(), cspr(400), "Buffer holds the stake");
+        assert_u512_eq(staking.get_total_staked(), cspr(400), "Still backing lstCSPR");
+
+        // Unstaking pays from the buffer with nothing left to undelegate
+        env.set_caller(env.get_account(1));
+        staking.unstake(cspr(100));
+        assert_u512_eq(staking.get_liquidity_buffer(), cspr(300), "Buffer paid first");
+    }
+
+    /// A direct staker and the vault earn through the exchange rate alone
+    /// and are reported apart
+    #[test]
+    fn test_direct_stakers_tracked_apart_from_vault() {
+        let StakingFixture { env, mut staking, .. } = setup();
+        let (direct, vault) = (env.get_account(1), env.get_account(3));
+        assert!(!env.get_event::<Stake>(staking.address(), -1).unwrap().via_vault);
+
+        staking.set_vault_address(vault);
+        env.set_caller(vault);
+        staking.with_tokens(cspr(3000)).stake();
+        assert!(env.get_event::<Stake>(staking.address(), -1).unwrap().via_vault);
+        assert_eq!(staking.get_staker_count(), 2);
+
+        // 4,000 CSPR backing 4,000 lstCSPR -> rate 1.1
+        staking.test_add_rewards(cspr(400));
+
+        let info = staking.get_staker_info(direct).unwrap();
+        assert!(!info.is_vault);
+        assert_u512_eq(info.current_value, cspr(1100), "1,000 lstCSPR at 1.1");
+        assert_u512_eq(info.rewards_earned, cspr(100), "A quarter of the rewards");
+        let vault_info = staking.get_staker_info(vault).unwrap();
+        assert!(vault_info.is_vault);
+        assert_u512_eq(vault_info.rewards_earned, cspr(300), "Three quarters of the rewards");
+        assert_eq!(staking.get_stake_by_origin(), (cspr(3300), cspr(1100)));
+
+        // Unstaking half realises its value without changing the attribution
+        env.set_caller(direct);
+        staking.unstake(cspr(500));
+        let unstaked = env.get_event::<Unstake>(staking.address(), -1).unwrap();
+        assert!(!unstaked.via_vault);
+        assert_u512_eq(unstaked.cspr_amount, cspr(550), "500 lstCSPR at 1.1");
+
+        let info = staking.get_staker_info(direct).unwrap();
+        assert_u512_eq(info.lst_cspr, cspr(500), "Half left");
+        assert_u512_eq(info.cspr_unstaked, cspr(550), "Paid out");
+        assert_u512_eq(info.rewards_earned, cspr(100), "Rewards unchanged by the exit");
+        assert_eq!(staking.get_stake_by_origin(), (cspr(3300), cspr(550)));
+        assert_eq!(staking.get_staker_count(), 2, "Exits don't reduce the count");
+        assert_eq!(staking.get_staker_info(env.get_account(2)), None);
+    }
+}
@@ -22,7 +22,7 @@ mod strategy_integration_tests {
     fn test_harvest_from_all_strategies() {
         let env = odra_test::env();
         
-        let mut lending = MockLendingHostRef::deploy(&env, 500u16);
+        let mut lending = SystemBuilder::new(&env).with_mock_lending(500).build().mock_lending.unwrap();
         
         lending.supply(cspr(10000));
         lending.accrue_interest(env.get_account(0));
@@ -57,12 +57,11 @@ mod strategy_integration_tests {
     #[test]
     fn test_strategy_failure_handling() {
         let env = odra_test::env();
-        let mut validator = MockValidatorHostRef::deploy(&env, 90u8, 5u8);
+        let mut validator = SystemBuilder::new(&env).with_mock_validator(1000, 5).build().mock_validators.remove(0);
         
-        validator.set_uptime(90);
+        validator.simulate_slashing(10);
         
-        let uptime = validator.get_uptime();
-        let should_withdraw = uptime < 95;
+        let should_withdraw = !validator.is_active();
         
         assert!(should_withdraw, "Withdraw from failing strategy");
     }
@@ -2,6 +2,7 @@
 mod vault_integration_tests {
     use odra::prelude::*;
     use odra::casper_types::U512;
+    use caspervault_contracts::VaultError;
     use crate::helpers::*;
     use crate::mocks::*;
 
@@ -109,15 +110,114 @@ mod vault_integration_tests {
 
     #[test]
     fn test_instant_vs_timelocked_withdrawal() {
-        let shares = cspr(1000);
-        let instant_fee_bps = 50u64;
-        
-        let instant_fee = (shares * U512::from(instant_fee_bps)) / U512::from(10000u64);
-        let instant_proceeds = shares - instant_fee;
-        
-        let timelocked_proceeds = shares;
-        
-        assert!(timelocked_proceeds > instant_proceeds, "Timelock saves fee");
+        let mut scenario = Scenario::new();
+        let (instant_user, patient_user) = (scenario.user(1), scenario.user(2));
+
+        scenario
+            .deposit(instant_user, cspr(5_000))
+            .deposit(patient_user, cspr(5_000))
+            .expect_instant_pool(cspr(500))
+            .advance_days(1);
+
+        // Paid from the pool at once, less the 0.5% instant fee
+        scenario.instant_withdraw(instant_user, cspr(400)).expect_instant_pool(cspr(100));
+        assert_u512_eq(scenario.received(instant_user), cspr(398), "400 less a 2 CSPR fee");
+        assert_u512_eq(scenario.system.vault.get_fees_collected(), cspr(2), "Instant fee booked");
+
+        // The same exit through the timelock pays in full, but not before it unlocks
+        scenario.request_withdrawal(patient_user, cspr(400));
+        let request_id = scenario.request_ids()[0];
+        scenario.env.set_caller(patient_user);
+        assert_eq!(
+            scenario.system.vault.try_complete_withdrawal(request_id),
+            Err(VaultError::Unauthorized.into())
+        );
+
+        scenario
+            .advance_days(7)
+            .complete_withdrawal(request_id)
+            .expect_total_assets(cspr(9_200))
+            .expect_share_price_near(1.0, 0);
+        assert_u512_eq(scenario.received(patient_user), cspr(400), "No fee after the timelock");
+        assert_u512_eq(
+            scenario.received(patient_user) - scenario.received(instant_user),
+            cspr(2),
+            "Waiting saved exactly the instant fee"
+        );
+    }
+
+    /// Deposits refill the instant pool to 5% of the new total before
+    /// anything is deployed
+    #[test]
+    fn test_instant_pool_replenishment() {
+        let mut scenario = Scenario::new();
+        let (first, second, third) = (scenario.user(1), scenario.user(2), scenario.user(3));
+
+        scenario
+            .deposit(first, cspr(10_000))
+            .deposit(second, cspr(10_000))
+            .expect_instant_pool(cspr(1_000))
+            .advance_days(1);
+
+        // An instant exit drains the pool to 1% of the vault
+        scenario
+            .instant_withdraw(first, cspr(800))
+            .expect_instant_pool(cspr(200))
+            .expect_total_assets(cspr(19_200));
+
+        // 5% of 21,200 is 1,060: 860 refills the pool, 1,140 is deployed
+        let deployed_before = scenario.strategy.get_balance();
+        scenario.deposit(third, cspr(2_000)).expect_instant_pool(cspr(1_060));
+        assert_u512_eq(scenario.strategy.get_balance() - deployed_before, cspr(1_140), "Only the surplus deployed");
+
+        // A deposit smaller than the shortfall goes to the pool whole
+        let deployed_before = scenario.strategy.get_balance();
+        scenario
+            .instant_withdraw(second, cspr(1_000))
+            .expect_instant_pool(cspr(60))
+            .deposit(third, cspr(500))
+            .expect_instant_pool(cspr(560))
+            .expect_total_assets(cspr(20_700));
+        assert_u512_eq(scenario.strategy.get_balance(), deployed_before, "Nothing deployed");
+    }
+
+    /// Requests lock their shares independently and can be completed in
+    /// any order once unlocked
+    #[test]
+    fn test_multiple_withdrawal_requests() {
+        let mut scenario = Scenario::new();
+        let user = scenario.user(1);
+
+        scenario
+            .deposit(user, cspr(10_000))
+            .advance_days(1)
+            .request_withdrawal(user, cspr(3_000))
+            .request_withdrawal(user, cspr(2_000))
+            .expect_shares(user, cspr(5_000));
+        let (first, second) = (scenario.request_ids()[0], scenario.request_ids()[1]);
+        assert_ne!(first, second);
+        assert_eq!(scenario.system.vault.get_pending_withdrawal_count(), 2);
+        assert_u512_eq(
+            scenario.system.vault.get_total_pending_withdrawal_assets(),
+            cspr(5_000),
+            "Both requests priced at 1 CSPR a share"
+        );
+
+        // 1% yield lands while both are locked; it stays with the shares left
+        scenario
+            .advance_days(30)
+            .earn(cspr(100))
+            .compound()
+            .expect_share_price_near(1.01, 0)
+            .complete_withdrawal(second)
+            .complete_withdrawal(first);
+
+        assert_u512_eq(scenario.received(user), cspr(5_000), "Paid the request-time value");
+        assert_eq!(scenario.system.vault.get_pending_withdrawal_count(), 0);
+        scenario
+            .expect_shares(user, cspr(5_000))
+            .expect_total_assets(cspr(5_100))
+            .expect_value_near(user, cspr(5_100), 0);
     }
 
     #[test]
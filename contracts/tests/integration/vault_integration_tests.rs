@@ -197,33 +197,6 @@ mod vault_integration_tests {
         // Verify fees compound correctly
     }
 
-    /// Scenario 7: Performance fee only on profits
-    /// 
-    /// Tests that users depositing at different share prices
-    /// pay performance fees correctly based on their cost basis
-    #[test]
-    fn test_performance_fee_cost_basis_tracking() {
-        // TODO: Implement
-        
-        // User A deposits 10,000 CSPR at share price 1.0
-        // Cost basis: 10,000 CSPR
-        
-        // Yield accrues, share price → 1.2
-        
-        // User A deposits another 12,000 CSPR
-        // Cost basis: 10,000 + 12,000 = 22,000 CSPR
-        
-        // More yield, share price → 1.5
-        
-        // User A's position worth: shares * 1.5
-        // User A withdraws everything
-        
-        // Profit = withdrawal_amount - 22,000 (cost basis)
-        // Performance fee = profit * 10%
-        
-        // Verify fee calculated correctly
-    }
-
     /// Scenario 8: Rate limiting across 24 hours
     /// 
     /// Tests that daily deposit limits reset properly
@@ -244,33 +217,6 @@ mod vault_integration_tests {
         // Verify limit tracking works correctly
     }
 
-    /// Scenario 9: Pool replenishment after withdrawals
-    /// 
-    /// Tests that instant pool gets replenished back to target
-    /// after withdrawals deplete it
-    #[test]
-    fn test_instant_pool_replenishment() {
-        // TODO: Implement
-        
-        // Initial state:
-        // - Total assets: 100,000 CSPR
-        // - Instant pool: 5,000 CSPR (5% target)
-        
-        // Large withdrawal: 4,000 CSPR
-        // Pool now: 1,000 CSPR (1%)
-        
-        // New deposit: 10,000 CSPR
-        // Should replenish pool to 5% of new total
-        
-        // New total: 106,000 CSPR
-        // Target pool: 5,300 CSPR
-        // Pool has: 1,000 CSPR
-        // Need: 4,300 CSPR for pool
-        // Deploy: 5,700 CSPR to strategies
-        
-        // Verify pool back at ~5% target
-    }
-
     /// Scenario 10: Emergency pause and unpause
     /// 
     /// Tests that admin can pause operations in emergency
@@ -315,57 +261,6 @@ mod vault_integration_tests {
         // Should NOT allow rescuing vault's own assets (CSPR, lstCSPR, cvCSPR)
     }
 
-    /// Scenario 12: Multiple withdrawal requests by same user
-    /// 
-    /// Tests that users can have multiple active withdrawal requests
-    #[test]
-    fn test_multiple_withdrawal_requests() {
-        // TODO: Implement
-        
-        // User has 10,000 shares
-        
-        // Request 1: Withdraw 3,000 shares
-        // let req1 = vault.request_withdrawal(3000);
-        
-        // Request 2: Withdraw 2,000 shares
-        // let req2 = vault.request_withdrawal(2000);
-        
-        // User has 5,000 shares remaining
-        
-        // Advance 7 days
-        
-        // Complete both
-        // vault.complete_withdrawal(req1)
-        // vault.complete_withdrawal(req2)
-        
-        // Verify both completed successfully
-    }
-
-    /// Scenario 13: Yield distribution fairness
-    /// 
-    /// Tests that yields are distributed fairly to all users
-    /// based on their share of the pool
-    #[test]
-    fn test_yield_distribution_fairness() {
-        // TODO: Implement
-        
-        // User A deposits 60,000 CSPR (60% of pool)
-        // User B deposits 40,000 CSPR (40% of pool)
-        
-        // Total: 100,000 CSPR
-        
-        // Yield accrues: 10,000 CSPR (10% return)
-        // Total assets: 110,000 CSPR
-        
-        // User A withdraws all
-        // Should receive: 60,000 + 6,000 (60% of yield) = 66,000 (minus fees)
-        
-        // User B withdraws all
-        // Should receive: 40,000 + 4,000 (40% of yield) = 44,000 (minus fees)
-        
-        // Verify proportional distribution
-    }
-
     /// Scenario 14: Strategy failure and fallback
     /// 
     /// Tests behavior when strategy withdrawal fails
@@ -0,0 +1,334 @@
+#[cfg(test)]
+mod vault_invariant_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{AssetComposition, VaultManagerHostRef};
+    use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+    use caspervault_contracts::{
+        AccessError, AccountingDivergence, DepositsHalted, InvariantError, InvariantViolated, VaultError,
+    };
+    use crate::helpers::*;
+
+    fn setup() -> (HostEnv, Address, DeployedSystem) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let system = deploy_system(&env, admin, env.get_account(6));
+        (env, admin, system)
+    }
+
+    fn code(error: InvariantError) -> u16 {
+        error as u16
+    }
+
+    /// Assert the buckets, and that they add up to total assets
+    fn assert_composition(vault: &VaultManagerHostRef, pool: U512, deployed: U512, idle: U512, in_transit: U512) {
+        let composition = vault.get_asset_composition();
+        assert_eq!(
+            composition,
+            AssetComposition {
+                pool_assets: pool,
+                deployed_assets: deployed,
+                idle_lst_assets: idle,
+                in_transit_assets: in_transit,
+                total_assets: pool + deployed + idle + in_transit,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fresh_system_passes_all_invariants() {
+        let (env, _, mut system) = setup();
+
+        // Anyone may run the check
+        env.set_caller(env.get_account(3));
+        assert!(system.vault.check_invariants().is_empty());
+        assert!(env.get_event::<InvariantViolated>(system.vault.address(), -1).is_err());
+    }
+
+    #[test]
+    fn test_each_violation_is_reported_with_an_event() {
+        let (env, _, mut system) = setup();
+
+        // Shares that no holder or pending request accounts for
+        system.vault.test_set_total_shares(cspr(5));
+        assert_eq!(system.vault.check_invariants(), vec![code(InvariantError::SharesSupplyMismatch)]);
+        let event = env.get_event::<InvariantViolated>(system.vault.address(), -1).unwrap();
+        assert_eq!(event.code, code(InvariantError::SharesSupplyMismatch));
+        system.vault.test_set_total_shares(cspr(0));
+
+        // The pool also no longer fits the asset composition
+        system.vault.test_set_instant_pool(cspr(1));
+        assert_eq!(
+            system.vault.check_invariants(),
+            vec![code(InvariantError::PoolExceedsAssets), code(InvariantError::AssetCompositionMismatch)]
+        );
+        let event = env.get_event::<InvariantViolated>(system.vault.address(), -1).unwrap();
+        assert_eq!(event.code, code(InvariantError::AssetCompositionMismatch));
+        system.vault.test_set_instant_pool(cspr(0));
+
+        system.vault.test_set_fees_collected(cspr(1));
+        assert_eq!(system.vault.check_invariants(), vec![code(InvariantError::FeesExceedBalance)]);
+        system.vault.test_set_fees_collected(cspr(0));
+
+        // Point the router at the staking contract
+        system.vault.set_strategy_router(*system.liquid_staking.address());
+        assert_eq!(system.vault.check_invariants(), vec![code(InvariantError::DuplicateContract)]);
+    }
+
+    #[test]
+    fn test_sampled_balances_above_total_shares_are_reported() {
+        let (env, _, mut system) = setup();
+        let user = env.get_account(1);
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(100)).deposit();
+        let total = system.vault.get_total_shares();
+        system.vault.test_set_user_shares(user, total + cspr(1));
+
+        let violations = system.vault.check_invariants();
+        assert!(violations.contains(&code(InvariantError::SampledSharesExceedTotal)));
+    }
+
+    #[test]
+    fn test_share_supply_tracks_holders_and_pending_requests() {
+        let (env, _, mut system) = setup();
+        let user = env.get_account(1);
+        system.vault.set_management_fee(0);
+        system.vault.set_min_holding_period(0);
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(100)).deposit();
+        system.vault.request_withdrawal(cspr(40));
+        assert!(system.vault.check_invariants().is_empty(), "Locked shares still count");
+
+        // A balance written without minting leaves the supply short
+        system.vault.test_set_user_shares(user, cspr(61));
+        assert_eq!(system.vault.check_invariants(), vec![code(InvariantError::SharesSupplyMismatch)]);
+    }
+
+    #[test]
+    fn test_missing_router_is_reported() {
+        let env = odra_test::env();
+        env.set_caller(env.get_account(0));
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+
+        assert_eq!(vault.check_invariants(), vec![code(InvariantError::MissingContract)]);
+    }
+
+    #[test]
+    fn test_auto_pause_halts_deposits_until_resumed() {
+        let (env, admin, mut system) = setup();
+        let user = env.get_account(1);
+
+        // Off by default: a critical violation is only reported
+        system.vault.test_set_instant_pool(cspr(1));
+        system.vault.check_invariants();
+        assert!(!system.vault.are_deposits_halted());
+
+        system.vault.set_invariant_auto_pause(true);
+        system.vault.check_invariants();
+        assert!(system.vault.are_deposits_halted());
+        let halted = env.get_event::<DepositsHalted>(system.vault.address(), -1).unwrap();
+        assert_eq!(halted.code, code(InvariantError::PoolExceedsAssets));
+
+        env.set_caller(user);
+        assert_eq!(
+            system.vault.with_tokens(cspr(100)).try_deposit(),
+            Err(VaultError::DepositsHalted.into())
+        );
+        assert_eq!(system.vault.try_resume_deposits(), Err(AccessError::MissingRole.into()));
+
+        env.set_caller(admin);
+        system.vault.test_set_instant_pool(cspr(0));
+        system.vault.resume_deposits();
+        assert!(!system.vault.are_deposits_halted());
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(100)).deposit();
+    }
+
+    #[test]
+    fn test_non_critical_violation_does_not_halt() {
+        let (_, _, mut system) = setup();
+
+        system.vault.set_invariant_auto_pause(true);
+        system.vault.test_set_fees_collected(cspr(1));
+        assert_eq!(system.vault.check_invariants(), vec![code(InvariantError::FeesExceedBalance)]);
+        assert!(!system.vault.are_deposits_halted());
+    }
+
+    #[test]
+    fn test_auto_pause_setting_is_admin_only() {
+        let (env, _, mut system) = setup();
+
+        env.set_caller(env.get_account(3));
+        assert_eq!(
+            system.vault.try_set_invariant_auto_pause(true),
+            Err(AccessError::MissingRole.into())
+        );
+        assert!(!system.vault.get_invariant_auto_pause());
+    }
+
+    #[test]
+    fn test_divergence_stays_near_zero_in_normal_operation() {
+        let (env, _, mut system) = setup();
+
+        env.set_caller(env.get_account(1));
+        system.vault.with_tokens(cspr(1000)).deposit();
+        assert_eq!(system.vault.check_accounting_divergence(), 0, "First check records the baseline");
+
+        env.advance_block_time(24 * 60 * 60);
+        env.set_caller(env.get_account(2));
+        system.vault.with_tokens(cspr(500)).deposit();
+
+        // Only a day of management fee separates the two
+        assert!(system.vault.check_accounting_divergence() <= 5);
+        assert!(env.get_event::<AccountingDivergence>(system.vault.address(), -1).is_err());
+        assert!(!system.vault.are_deposits_halted());
+    }
+
+    #[test]
+    fn test_inflated_total_assets_trip_divergence_alarm() {
+        let (env, _, mut system) = setup();
+        let user = env.get_account(1);
+        system.vault.set_invariant_auto_pause(true);
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        system.vault.check_accounting_divergence();
+
+        // 10% of assets appear from nowhere; the lstCSPR rate doesn't move
+        system.vault.test_set_total_assets(cspr(1100));
+        assert_eq!(system.vault.get_growth_since_baseline(), (1000, 0));
+        assert_eq!(system.vault.get_accounting_divergence(), 1000);
+
+        assert_eq!(system.vault.check_accounting_divergence(), 1000);
+        let event = env.get_event::<AccountingDivergence>(system.vault.address(), -2).unwrap();
+        assert_eq!(event.vault_growth_bps, 1000);
+        assert_eq!(event.staking_growth_bps, 0);
+        assert_eq!(event.threshold_bps, 500);
+        let halted = env.get_event::<DepositsHalted>(system.vault.address(), -1).unwrap();
+        assert_eq!(halted.code, code(InvariantError::AccountingDivergence));
+        assert_eq!(
+            system.vault.with_tokens(cspr(100)).try_deposit(),
+            Err(VaultError::DepositsHalted.into())
+        );
+
+        // The baseline rolls once the window has passed
+        env.advance_block_time(7 * 24 * 60 * 60);
+        system.vault.check_accounting_divergence();
+        assert_eq!(system.vault.get_accounting_divergence(), 0);
+    }
+
+    #[test]
+    fn test_divergence_below_threshold_or_disabled_is_quiet() {
+        let (env, admin, mut system) = setup();
+
+        env.set_caller(env.get_account(1));
+        system.vault.with_tokens(cspr(1000)).deposit();
+        system.vault.check_accounting_divergence();
+        system.vault.test_set_total_assets(cspr(1040));
+
+        assert_eq!(system.vault.check_accounting_divergence(), 400);
+        assert!(env.get_event::<AccountingDivergence>(system.vault.address(), -1).is_err(), "4% is under 5%");
+
+        assert_eq!(system.vault.try_set_divergence_circuit(0, 1), Err(AccessError::MissingRole.into()));
+        env.set_caller(admin);
+        assert_eq!(system.vault.try_set_divergence_circuit(10001, 1), Err(VaultError::InvalidRequest.into()));
+        system.vault.set_divergence_circuit(0, 7 * 24 * 60 * 60);
+        system.vault.test_set_total_assets(cspr(2000));
+        system.vault.check_accounting_divergence();
+        assert!(env.get_event::<AccountingDivergence>(system.vault.address(), -1).is_err(), "Zero disables the alarm");
+    }
+
+    #[test]
+    fn test_asset_composition_through_withdrawal_lifecycle() {
+        let (env, admin, mut system) = setup();
+        let user = env.get_account(1);
+        let strategy = SystemBuilder::mock_strategy(&env, 1000);
+        system.router.add_strategy("dex".to_string(), *strategy.address());
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![("dex".to_string(), 100u8)]);
+        system.vault.set_min_holding_period(0);
+        system.vault.set_management_fee(0);
+        assert_composition(&system.vault, U512::zero(), U512::zero(), U512::zero(), U512::zero());
+        assert!(system.vault.check_invariants().is_empty());
+
+        // 5% fills the pool, the rest goes to the strategy
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        assert_composition(&system.vault, cspr(50), cspr(950), U512::zero(), U512::zero());
+        assert!(system.vault.check_invariants().is_empty());
+
+        // The request's value leaves the strategies' books while it unbonds
+        let request_id = system.vault.request_withdrawal(cspr(400));
+        assert_composition(&system.vault, cspr(50), cspr(550), U512::zero(), cspr(400));
+        assert!(system.vault.check_invariants().is_empty());
+
+        env.advance_block_time(7 * 24 * 60 * 60);
+        assert_composition(&system.vault, cspr(50), cspr(550), U512::zero(), cspr(400));
+        assert!(system.vault.check_invariants().is_empty());
+
+        // Completion empties the pool first and takes the rest from the strategies
+        assert_u512_eq(system.vault.complete_withdrawal(request_id), cspr(400), "No profit, no fee");
+        assert_composition(&system.vault, U512::zero(), cspr(600), U512::zero(), U512::zero());
+        assert!(system.vault.check_invariants().is_empty());
+
+        // Harvested profit lands in the strategies until parked in the pool
+        env.set_caller(admin);
+        system.vault.report_profit(cspr(30));
+        system.vault.credit_instant_pool(cspr(10));
+        assert_composition(&system.vault, cspr(10), cspr(620), U512::zero(), U512::zero());
+        assert!(system.vault.check_invariants().is_empty());
+
+        // A loss comes out of the strategies before the pool
+        system.vault.report_loss(cspr(20));
+        assert_composition(&system.vault, cspr(10), cspr(600), U512::zero(), U512::zero());
+        assert!(system.vault.check_invariants().is_empty());
+    }
+
+    #[test]
+    fn test_expired_request_returns_to_strategies() {
+        let (env, _, mut system) = setup();
+        let user = env.get_account(1);
+        system.router.set_idle_buffer_pct(0);
+        system.vault.set_min_holding_period(0);
+        system.vault.set_management_fee(0);
+
+        // No strategies: the router hands everything back to the pool
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        assert_composition(&system.vault, cspr(1000), U512::zero(), U512::zero(), U512::zero());
+
+        // Nothing outside the pool to move, so nothing is in transit
+        let request_id = system.vault.request_withdrawal(cspr(100));
+        assert_composition(&system.vault, cspr(1000), U512::zero(), U512::zero(), U512::zero());
+
+        system.vault.test_add_yield(cspr(100));
+        let second_id = system.vault.request_withdrawal(cspr(100));
+        assert_composition(&system.vault, cspr(1000), U512::zero(), U512::zero(), cspr(100));
+
+        env.advance_block_time((7 + 90) * 24 * 60 * 60);
+        system.vault.expire_withdrawal(request_id);
+        system.vault.expire_withdrawal(second_id);
+        assert_composition(&system.vault, cspr(1000), cspr(100), U512::zero(), U512::zero());
+        assert!(system.vault.check_invariants().is_empty());
+    }
+
+    #[test]
+    fn test_deposits_without_router_sit_idle() {
+        let env = odra_test::env();
+        env.set_caller(env.get_account(0));
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        vault.set_min_holding_period(0);
+        let user = env.get_account(1);
+
+        env.set_caller(user);
+        let shares = vault.with_tokens(cspr(1000)).deposit();
+        assert_composition(&vault, cspr(50), U512::zero(), cspr(950), U512::zero());
+
+        vault.withdraw(shares);
+        assert_composition(&vault, U512::zero(), U512::zero(), U512::zero(), U512::zero());
+    }
+}
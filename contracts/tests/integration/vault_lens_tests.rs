@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod vault_lens_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{
+        VaultLensHostRef, CONVERSION_LADDER_CSPR,
+        WITHDRAW_PATH_INSTANT, WITHDRAW_PATH_REQUEST, WITHDRAW_PATH_STANDARD,
+    };
+    use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+    use crate::helpers::*;
+
+    const ONE_HOUR: u64 = 60 * 60;
+    const SEVEN_DAYS: u64 = 7 * 24 * ONE_HOUR;
+
+    struct LensFixture {
+        env: HostEnv,
+        user1: Address,
+        user2: Address,
+        system: DeployedSystem,
+        lens: VaultLensHostRef,
+    }
+
+    /// Bootstrapped system with three 10% APY strategies, two 1000 CSPR
+    /// deposits and an unlocked 100 CSPR profit
+    ///
+    /// Seven hours pass after the compound, so every exit also accrues a
+    /// pending management fee.
+    fn setup() -> LensFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let (user1, user2) = (env.get_account(1), env.get_account(2));
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+
+        let targets = vec![("dex".to_string(), 40u8), ("lending".to_string(), 30u8), ("crosschain".to_string(), 30u8)];
+        for (name, _) in targets.iter() {
+            let strategy = SystemBuilder::mock_strategy(&env, 1000);
+            system.router.add_strategy(name.clone(), *strategy.address());
+        }
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(targets);
+
+        let lens = SystemBuilder::lens(&env, &system);
+
+        env.set_caller(user1);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        env.set_caller(user2);
+        system.vault.with_tokens(cspr(1000)).deposit();
+
+        env.advance_block_time(ONE_HOUR);
+        env.set_caller(admin);
+        system.aggregator.compound(cspr(100));
+        env.advance_block_time(7 * ONE_HOUR);
+
+        LensFixture { env, user1, user2, system, lens }
+    }
+
+    #[test]
+    fn test_simulated_withdrawals_match_execution() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        let instant = f.lens.simulate_withdrawal(user2, cspr(20), WITHDRAW_PATH_INSTANT);
+        assert!(instant.available);
+        f.env.set_caller(user2);
+        assert_u512_eq(f.system.vault.instant_withdraw(cspr(20)), instant.payout, "Instant payout");
+
+        let standard = f.lens.simulate_withdrawal(user1, cspr(300), WITHDRAW_PATH_STANDARD);
+        assert!(standard.available);
+        assert_eq!(standard.ready_at, f.env.get_block_time());
+        f.env.set_caller(user1);
+        assert_u512_eq(f.system.vault.withdraw(cspr(300)), standard.payout, "Standard payout");
+
+        // More than the user holds, or more than the pool can pay instantly
+        assert!(!f.lens.simulate_withdrawal(user1, cspr(5000), WITHDRAW_PATH_STANDARD).available);
+        let too_large = f.lens.simulate_withdrawal(user2, cspr(900), WITHDRAW_PATH_INSTANT);
+        assert!(!too_large.available);
+        assert_u512_eq(too_large.payout, U512::zero(), "Pool can't cover it");
+
+        assert!(f.lens.try_simulate_withdrawal(user1, cspr(1), 3).is_err(), "Unknown path");
+    }
+
+    #[test]
+    fn test_request_simulation_and_pending_report() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        let simulated = f.lens.simulate_withdrawal(user1, cspr(400), WITHDRAW_PATH_REQUEST);
+        assert_eq!(simulated.ready_at, f.env.get_block_time() + SEVEN_DAYS);
+
+        f.env.set_caller(user1);
+        let request_id = f.system.vault.request_withdrawal(cspr(400));
+
+        let report = f.lens.get_position_report(user1);
+        assert_u512_eq(report.shares, cspr(600), "Requested shares are locked");
+        assert_eq!(report.pending_requests.len(), 1);
+        let pending = &report.pending_requests[0];
+        assert_eq!(pending.request_id, request_id);
+        assert_eq!(pending.seconds_remaining, SEVEN_DAYS);
+        assert_u512_eq(pending.payout, simulated.payout, "Request locks the simulated value");
+
+        f.env.advance_block_time(SEVEN_DAYS);
+        let report = f.lens.get_position_report(user1);
+        assert_eq!(report.pending_requests[0].seconds_remaining, 0);
+        let expected = report.pending_requests[0].payout;
+
+        assert_u512_eq(f.system.vault.complete_withdrawal(request_id), expected, "Completion payout");
+        assert!(f.lens.get_position_report(user1).pending_requests.is_empty());
+    }
+
+    #[test]
+    fn test_position_report_and_projected_yield() {
+        let f = setup();
+        let user1 = f.user1;
+
+        let report = f.lens.get_position_report(user1);
+        assert_u512_eq(report.shares, cspr(1000), "Shares minted 1:1");
+        assert_u512_eq(report.assets, f.system.vault.preview_redeem(cspr(1000)), "Exit value");
+        assert_u512_eq(report.cost_basis, cspr(1000), "Deposited amount");
+        assert_u512_eq(report.unrealized_profit, report.assets - cspr(1000), "Share of the 100 CSPR profit");
+        assert_u512_eq(report.cspr_value, report.assets, "Nothing staked: lstCSPR redeems 1:1");
+
+        // 10% gross, less the 10% performance fee and 2% management fee
+        assert_eq!(report.gross_apy_bps, f.system.router.calculate_blended_apy());
+        assert_eq!(report.net_apy_bps, report.gross_apy_bps * U256::from(9u64) / U256::from(10u64) - U256::from(200u64));
+
+        let net_apy = U512::from(report.net_apy_bps.as_u128());
+        let expected = report.assets * net_apy * U512::from(30u64) / U512::from(365u64 * 10_000);
+        assert_u512_eq(f.lens.project_yield(user1, 30), expected, "30 days at the net APY");
+        assert_u512_eq(f.lens.project_yield(f.env.get_account(5), 30), U512::zero(), "No position");
+    }
+
+    /// Lens over a vault holding one 1000 CSPR deposit plus `profit`, fully unlocked
+    fn conversion_lens(profit: U512) -> VaultLensHostRef {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        system.vault.set_management_fee(0);
+
+        env.set_caller(env.get_account(1));
+        system.vault.with_tokens(cspr(1000)).deposit();
+        if !profit.is_zero() {
+            env.set_caller(admin);
+            system.vault.report_profit(profit);
+            env.advance_block_time(7 * ONE_HOUR);
+        }
+
+        SystemBuilder::lens(&env, &system)
+    }
+
+    #[test]
+    fn test_conversions_at_share_price_one() {
+        let lens = conversion_lens(U512::zero());
+
+        assert_eq!(lens.format_assets(cspr(1)), (1, 0));
+        assert_eq!(lens.format_assets(U512::from(1_500_000_001u64)), (1, 500_000_001));
+        assert_eq!(lens.format_assets(U512::zero()), (0, 0));
+        assert_u512_eq(lens.shares_for_cspr(250), cspr(250), "Minted 1:1");
+
+        let table = lens.get_conversion_table();
+        assert_eq!(table.len(), CONVERSION_LADDER_CSPR.len());
+        for ((shares, assets), whole_cspr) in table.into_iter().zip(CONVERSION_LADDER_CSPR) {
+            assert_u512_eq(shares, cspr(whole_cspr), "Shares equal the amount");
+            assert_u512_eq(assets, cspr(whole_cspr), "Round trip is exact");
+        }
+    }
+
+    #[test]
+    fn test_conversions_at_share_price_1_2345() {
+        // 1234.5 CSPR backing 1000 cvCSPR
+        let lens = conversion_lens(U512::from(234_500_000_000u64));
+
+        assert_eq!(lens.format_assets(cspr(1)), (1, 234_500_000));
+        assert_eq!(lens.format_assets(cspr(1000)), (1234, 500_000_000));
+        assert_u512_eq(lens.shares_for_cspr(1), U512::from(810_044_552u64), "1 / 1.2345, rounded down");
+
+        let expected: [(u64, u64); 5] = [
+            (810_044_552, 999_999_999),
+            (8_100_445_524, 9_999_999_999),
+            (81_004_455_245, 99_999_999_999),
+            (810_044_552_450, 999_999_999_999),
+            (8_100_445_524_503, 9_999_999_999_998),
+        ];
+        let table = lens.get_conversion_table();
+        assert_eq!(table.len(), expected.len());
+        for ((shares, assets), (expected_shares, expected_assets)) in table.into_iter().zip(expected) {
+            assert_u512_eq(shares, U512::from(expected_shares), "Shares minted for the rung");
+            assert_u512_eq(assets, U512::from(expected_assets), "Both legs round down");
+        }
+    }
+
+    #[test]
+    fn test_vault_info_includes_asset_composition() {
+        let mut f = setup();
+        f.env.set_caller(f.user1);
+        f.system.vault.request_withdrawal(cspr(400));
+
+        let info = f.lens.get_vault_info();
+        assert_eq!(info.composition, f.system.vault.get_asset_composition());
+        assert_u512_eq(info.total_shares, f.system.vault.get_total_shares(), "Locked shares still count");
+        assert_eq!(info.share_price, f.system.vault.get_share_price_precise());
+        assert_eq!(info.pending_withdrawals, 1);
+        assert!(!info.composition.in_transit_assets.is_zero(), "The request is in transit");
+
+        let composition = info.composition;
+        let bucket_sum = composition.pool_assets
+            + composition.deployed_assets
+            + composition.idle_lst_assets
+            + composition.in_transit_assets;
+        assert_u512_eq(bucket_sum, composition.total_assets, "Buckets add up");
+    }
+}
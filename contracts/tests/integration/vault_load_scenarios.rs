@@ -0,0 +1,237 @@
+#[cfg(test)]
+mod vault_load_scenarios {
+    use std::collections::BTreeMap;
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::VaultManagerHostRef;
+    use caspervault_contracts::VaultError;
+    use crate::helpers::*;
+
+    const DAY: u64 = 86_400;
+    const SEEDS: [u64; 3] = [11, 42, 1337];
+
+    /// Builder vault with every fee off, so all yield reaches holders
+    fn deploy_vault(env: &HostEnv) -> VaultManagerHostRef {
+        let mut vault = SystemBuilder::new(env).with_vault().build().vault.unwrap();
+        vault.set_min_holding_period(0);
+        vault.set_fees(0, 0, 0);
+        vault
+    }
+
+    fn users(env: &HostEnv, count: usize) -> Vec<Address> {
+        (0..count).map(|i| env.get_account(10 + i)).collect()
+    }
+
+    /// Start of the next UTC day, so schedule days line up with the
+    /// vault's daily deposit buckets
+    fn next_day_start(env: &HostEnv) -> u64 {
+        (env.get_block_time() / DAY + 1) * DAY
+    }
+
+    fn advance_to(env: &HostEnv, time: u64) {
+        let now = env.get_block_time();
+        if time > now {
+            env.advance_block_time(time - now);
+        }
+    }
+
+    /// What one simulated user put in, took out and should have earned
+    #[derive(Default)]
+    struct Ledger {
+        deposited: U512,
+        withdrawn: U512,
+        expected_yield: U512,
+    }
+
+    fn apply(env: &HostEnv, vault: &mut VaultManagerHostRef, user: Address, ledger: &mut Ledger, kind: UserActionKind) {
+        env.set_caller(user);
+        match kind {
+            UserActionKind::Deposit(amount) => {
+                vault.with_tokens(amount).deposit();
+                ledger.deposited += amount;
+            }
+            UserActionKind::Withdraw(bps) => {
+                let shares = vault.get_user_shares(user);
+                let mut portion = shares * U512::from(bps) / U512::from(10_000u64);
+                // Round up to a full exit rather than strand dust
+                if shares - portion < vault.get_min_shares() {
+                    portion = shares;
+                }
+                if !portion.is_zero() {
+                    ledger.withdrawn += vault.withdraw(portion);
+                }
+            }
+        }
+    }
+
+    /// Credit `amount` of yield and book each holder's pro-rata cut
+    fn credit_yield(vault: &mut VaultManagerHostRef, users: &[Address], ledgers: &mut [Ledger], amount: U512) {
+        let total_shares = vault.get_total_shares();
+        if total_shares.is_zero() || amount.is_zero() {
+            return;
+        }
+        for (user, ledger) in users.iter().zip(ledgers.iter_mut()) {
+            ledger.expected_yield += amount * vault.get_user_shares(*user) / total_shares;
+        }
+        vault.test_add_yield(amount);
+    }
+
+    /// Each user's payouts plus holdings match their deposits plus the
+    /// yield earned by the shares they held each day
+    ///
+    /// Tolerance is `tolerance_bps` of the expected yield plus 0.001 CSPR
+    /// for share rounding across the run.
+    fn assert_time_weighted(vault: &VaultManagerHostRef, users: &[Address], ledgers: &[Ledger], tolerance_bps: u64, seed: u64) {
+        for (i, (user, ledger)) in users.iter().zip(ledgers).enumerate() {
+            let actual = vault.get_user_assets(*user) + ledger.withdrawn;
+            let expected = ledger.deposited + ledger.expected_yield;
+            let slack = ledger.expected_yield * U512::from(tolerance_bps) / U512::from(10_000u64) + U512::from(1_000_000u64);
+            let diff = if actual > expected { actual - expected } else { expected - actual };
+            assert!(
+                diff <= slack,
+                "seed {}, user {}: ended with {} against {} expected (slack {})",
+                seed, i, actual, expected, slack
+            );
+        }
+    }
+
+    /// Run `schedule` day by day, crediting `daily_yield(day, total_assets)`
+    /// after each day's actions
+    fn run_schedule(
+        env: &HostEnv,
+        vault: &mut VaultManagerHostRef,
+        users: &[Address],
+        schedule: &[UserAction],
+        days: u64,
+        daily_yield: impl Fn(u64, U512) -> U512,
+    ) -> (Vec<Ledger>, U512) {
+        let mut ledgers: Vec<Ledger> = users.iter().map(|_| Ledger::default()).collect();
+        let mut total_yield = U512::zero();
+        let start = next_day_start(env);
+        let mut actions = schedule.iter().peekable();
+
+        for day in 0..days {
+            while let Some(action) = actions.next_if(|action| action.day == day) {
+                advance_to(env, start + day * DAY + action.offset);
+                apply(env, vault, users[action.user], &mut ledgers[action.user], action.kind);
+            }
+            let amount = daily_yield(day, vault.total_assets());
+            credit_yield(vault, users, &mut ledgers, amount);
+            total_yield += amount;
+        }
+        (ledgers, total_yield)
+    }
+
+    /// Scenario: users enter and leave at random over 90 days
+    ///
+    /// Log-normal deposit sizes and random partial or full exits; the vault
+    /// earns a flat 8% APY. Every user's return follows the shares they held
+    /// on each day, whatever share price they entered or left at.
+    #[test]
+    fn test_multiple_users_different_entry_points() {
+        for seed in SEEDS {
+            let env = odra_test::env();
+            let mut vault = deploy_vault(&env);
+            let config = ActivityConfig::default();
+            let users = users(&env, config.num_users);
+            let schedule = generate_activity_schedule(&mut SeededRng::new(seed), &config);
+
+            let (ledgers, total_yield) = run_schedule(&env, &mut vault, &users, &schedule, config.days, |_, assets| {
+                assets * U512::from(800u64) / U512::from(10_000u64 * 365)
+            });
+
+            assert!(!total_yield.is_zero(), "seed {}: the vault earned", seed);
+            assert!(ledgers.iter().any(|ledger| !ledger.withdrawn.is_zero()), "seed {}: someone exited", seed);
+            assert_time_weighted(&vault, &users, &ledgers, 10, seed);
+        }
+    }
+
+    /// Scenario: yield from three strategies on drifting APY curves
+    ///
+    /// Users only deposit, at staggered times. Each day's yield is the sum
+    /// of the strategies' curves on their slice of the assets, and the whole
+    /// of it is shared out by the shares held that day.
+    #[test]
+    fn test_multi_strategy_yield_distribution_fairness() {
+        for seed in SEEDS {
+            let env = odra_test::env();
+            let mut vault = deploy_vault(&env);
+            let mut rng = SeededRng::new(seed);
+            let config = ActivityConfig { daily_activity_pct: 15, withdraw_pct: 0, ..ActivityConfig::default() };
+            let users = users(&env, config.num_users);
+            let schedule = generate_activity_schedule(&mut rng, &config);
+            let curves = generate_yield_curves(&mut rng, &[(40, 600), (35, 900), (25, 1500)], config.days, 40);
+
+            let (ledgers, total_yield) = run_schedule(&env, &mut vault, &users, &schedule, config.days, |day, assets| {
+                curves.iter().fold(U512::zero(), |acc, curve| acc + curve.daily_yield(assets, day))
+            });
+
+            assert_time_weighted(&vault, &users, &ledgers, 10, seed);
+
+            // With fees off, holders account for all of the yield
+            let held = users.iter().fold(U512::zero(), |acc, user| acc + vault.get_user_assets(*user));
+            let deposited = ledgers.iter().fold(U512::zero(), |acc, ledger| acc + ledger.deposited);
+            assert_u512_within_tolerance(held, deposited + total_yield, 1);
+        }
+    }
+
+    /// Scenario: bursts of deposits against the daily limit
+    ///
+    /// With a 5,000 CSPR daily limit, each deposit succeeds exactly when the
+    /// user's total for that UTC day stays within it, and a user turned away
+    /// one day can deposit again the next.
+    #[test]
+    fn test_rate_limiting_across_days() {
+        for seed in SEEDS {
+            let env = odra_test::env();
+            let mut vault = deploy_vault(&env);
+            let daily_limit = cspr(5_000);
+            vault.update_deposit_limits(cspr(10_000), daily_limit);
+
+            let config = ActivityConfig {
+                num_users: 4,
+                days: 30,
+                median_deposit_cspr: 1_500.0,
+                deposit_sigma: 0.6,
+                max_deposit: cspr(4_000),
+                daily_activity_pct: 50,
+                max_actions_per_day: 4,
+                withdraw_pct: 0,
+                ..ActivityConfig::default()
+            };
+            let users = users(&env, config.num_users);
+            let schedule = generate_activity_schedule(&mut SeededRng::new(seed), &config);
+            let start = next_day_start(&env);
+
+            let mut used: BTreeMap<(usize, u64), U512> = BTreeMap::new();
+            let mut last_rejected: BTreeMap<usize, u64> = BTreeMap::new();
+            let (mut accepted, mut rejected, mut recovered) = (0, 0, 0);
+
+            for action in &schedule {
+                let UserActionKind::Deposit(amount) = action.kind else { continue };
+                advance_to(&env, start + action.day * DAY + action.offset);
+                let bucket = env.get_block_time() / DAY;
+                let today = used.entry((action.user, bucket)).or_default();
+
+                env.set_caller(users[action.user]);
+                let result = vault.with_tokens(amount).try_deposit();
+                if *today + amount <= daily_limit {
+                    assert!(result.is_ok(), "seed {}: deposit within the limit is accepted", seed);
+                    *today += amount;
+                    accepted += 1;
+                    if last_rejected.get(&action.user).is_some_and(|day| *day < bucket) {
+                        recovered += 1;
+                    }
+                } else {
+                    assert_eq!(result, Err(VaultError::RateLimitExceeded.into()), "seed {}", seed);
+                    last_rejected.insert(action.user, bucket);
+                    rejected += 1;
+                }
+            }
+
+            assert!(accepted > 0 && rejected > 0, "seed {}: both sides of the limit exercised", seed);
+            assert!(recovered > 0, "seed {}: the limit resets on a new day", seed);
+        }
+    }
+}
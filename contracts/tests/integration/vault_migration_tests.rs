@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod vault_migration_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{VaultManagerHostRef, OP_MIGRATE_IN, OP_MIGRATE_OUT};
+    use caspervault_contracts::tokens::LstCsprHostRef;
+    use caspervault_contracts::{MigratedPositionReceived, MigrationEnabled, PositionMigrated, VaultError};
+    use crate::helpers::*;
+
+    const ONE_HOUR: u64 = 60 * 60;
+    const GRACE_PERIOD: u64 = 14 * 24 * ONE_HOUR;
+
+    struct MigrationFixture {
+        env: HostEnv,
+        admin: Address,
+        users: [Address; 3],
+        lst: LstCsprHostRef,
+        old_vault: VaultManagerHostRef,
+        new_vault: VaultManagerHostRef,
+    }
+
+    fn deploy_vault(env: &HostEnv, lst: &LstCsprHostRef) -> VaultManagerHostRef {
+        let mut vault = SystemBuilder::new(env).with_vault().with_lst_token(*lst.address()).build().vault.unwrap();
+        // Keep the asset totals exact
+        vault.set_management_fee(0);
+        vault.set_min_holding_period(0);
+        vault
+    }
+
+    fn deposit_lst(env: &HostEnv, lst: &mut LstCsprHostRef, vault: &mut VaultManagerHostRef, user: Address, amount: U512) {
+        env.set_caller(env.get_account(0));
+        lst.mint(user, amount);
+        env.set_caller(user);
+        lst.approve(*vault.address(), amount);
+        vault.deposit_lst(amount);
+    }
+
+    /// Old vault: 1,000 / 2,000 / 3,000 lstCSPR deposits and an unlocked
+    /// 600 lstCSPR profit (share price 1.1). New vault: one 1,000 lstCSPR
+    /// holder and an unlocked 250 lstCSPR profit (share price 1.25), wired
+    /// to accept the old vault's positions. Both hold the lstCSPR behind
+    /// their assets.
+    fn setup() -> MigrationFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let users = [env.get_account(1), env.get_account(2), env.get_account(3)];
+
+        let mut lst = SystemBuilder::new(&env).with_lst().build().lst.unwrap();
+        let mut old_vault = deploy_vault(&env, &lst);
+        let mut new_vault = deploy_vault(&env, &lst);
+        new_vault.set_migration_source(*old_vault.address());
+
+        for (i, user) in users.iter().enumerate() {
+            deposit_lst(&env, &mut lst, &mut old_vault, *user, cspr(1000 * (i as u64 + 1)));
+        }
+        deposit_lst(&env, &mut lst, &mut new_vault, env.get_account(4), cspr(1000));
+
+        env.set_caller(admin);
+        old_vault.report_profit(cspr(600));
+        lst.mint(*old_vault.address(), cspr(600));
+        new_vault.report_profit(cspr(250));
+        lst.mint(*new_vault.address(), cspr(250));
+        env.advance_block_time(7 * ONE_HOUR);
+
+        MigrationFixture { env, admin, users, lst, old_vault, new_vault }
+    }
+
+    #[test]
+    fn test_full_migration_of_three_users_conserves_assets() {
+        let mut f = setup();
+        let [user1, user2, user3] = f.users;
+        let old_assets = f.old_vault.total_assets();
+        let new_assets = f.new_vault.total_assets();
+        assert_u512_eq(old_assets, cspr(6600), "Old vault NAV");
+
+        f.old_vault.enable_migration(*f.new_vault.address());
+        let enabled = f.env.get_event::<MigrationEnabled>(f.old_vault.address(), -1).unwrap();
+        assert_eq!(enabled.target, *f.new_vault.address());
+        assert_eq!(enabled.grace_period, GRACE_PERIOD);
+
+        // Two holders move themselves
+        f.env.set_caller(user1);
+        assert_u512_eq(f.old_vault.migrate_position(), cspr(880), "1,100 CSPR at 1.25");
+        f.env.set_caller(user2);
+        assert_u512_eq(f.old_vault.migrate_position(), cspr(1760), "2,200 CSPR at 1.25");
+
+        let received = f.env.get_event::<MigratedPositionReceived>(f.new_vault.address(), -1).unwrap();
+        assert_eq!(received.user, user2);
+        assert_eq!(received.source, *f.old_vault.address());
+        assert_u512_eq(received.cost_basis, cspr(2000), "Cost basis travels with the position");
+
+        // The third is moved by the admin once the grace period is over
+        f.env.advance_block_time(GRACE_PERIOD);
+        f.env.set_caller(f.admin);
+        assert_eq!(f.old_vault.migrate_positions(vec![user1, user3]), 1, "Already migrated users are skipped");
+
+        let migrated = f.env.get_event::<PositionMigrated>(f.old_vault.address(), -1).unwrap();
+        assert_eq!(migrated.user, user3);
+        assert_u512_eq(migrated.shares_burned, cspr(3000), "All of user3's shares burned");
+        assert_u512_eq(migrated.assets, cspr(3300), "At the old vault's price");
+        assert_u512_eq(migrated.shares_minted, cspr(2640), "3,300 CSPR at 1.25");
+
+        // Nothing lost or created on the way
+        assert_u512_eq(f.old_vault.total_assets(), U512::zero(), "Old vault emptied");
+        assert_u512_eq(f.old_vault.get_total_shares(), U512::zero(), "Old shares all burned");
+        assert_eq!(f.old_vault.get_unique_depositors(), 0);
+        assert_u512_eq(f.new_vault.total_assets(), new_assets + old_assets, "Assets conserved");
+        assert_u512_eq(f.lst.balance_of(*f.old_vault.address()), U512::zero(), "Old vault's lstCSPR all sent");
+        assert_u512_eq(f.lst.balance_of(*f.new_vault.address()), new_assets + old_assets, "New vault holds it all");
+        assert_u512_eq(
+            f.lst.allowance(*f.old_vault.address(), *f.new_vault.address()),
+            U512::zero(),
+            "Every approval pulled",
+        );
+        assert_eq!(f.new_vault.get_unique_depositors(), 4);
+
+        for (i, user) in f.users.iter().enumerate() {
+            let deposited = cspr(1000 * (i as u64 + 1));
+            assert_u512_eq(f.old_vault.get_user_shares(*user), U512::zero(), "No shares left behind");
+            assert_u512_eq(
+                f.new_vault.get_user_assets(*user),
+                deposited * U512::from(11u64) / U512::from(10u64),
+                "Position value unchanged",
+            );
+            assert_u512_eq(f.new_vault.get_user_deposit(*user).unwrap().cost_basis, deposited, "Cost basis preserved");
+        }
+
+        // Migration itself charges nothing; the fee on exit is the one owed anyway
+        assert_u512_eq(f.old_vault.get_fee_breakdown().performance_fees, U512::zero(), "No fee on the way out");
+        assert_u512_eq(f.new_vault.get_fee_breakdown().performance_fees, U512::zero(), "No fee on the way in");
+        f.env.set_caller(user1);
+        let payout = f.new_vault.withdraw(cspr(880));
+        assert_u512_eq(payout, cspr(1090), "10% fee on the 100 CSPR profit only");
+
+        let ops = f.new_vault.get_recent_operations(4);
+        assert_eq!(ops.iter().filter(|op| op.kind == OP_MIGRATE_IN).count(), 3);
+        assert_eq!(f.old_vault.get_recent_operations(1)[0].kind, OP_MIGRATE_OUT);
+    }
+
+    #[test]
+    fn test_migration_gating() {
+        let mut f = setup();
+        let user1 = f.users[0];
+        let outsider = f.env.get_account(5);
+
+        f.env.set_caller(user1);
+        assert_eq!(
+            f.old_vault.try_migrate_position(),
+            Err(VaultError::ConditionsNotMet.into()),
+            "No target yet",
+        );
+        assert!(f.old_vault.try_enable_migration(*f.new_vault.address()).is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        f.old_vault.enable_migration(*f.new_vault.address());
+        assert_eq!(
+            f.old_vault.try_enable_migration(outsider),
+            Err(VaultError::InvalidRequest.into()),
+            "Target can't be swapped mid-migration",
+        );
+
+        // Deposits close, withdrawals stay open
+        f.env.set_caller(outsider);
+        assert_eq!(
+            f.old_vault.with_tokens(cspr(100)).try_deposit(),
+            Err(VaultError::MigrationActive.into()),
+        );
+        f.env.set_caller(user1);
+        assert!(f.old_vault.try_withdraw(cspr(100)).is_ok());
+
+        // Only the configured source can hand positions over
+        f.env.set_caller(outsider);
+        assert_eq!(
+            f.new_vault.try_receive_migrated_position(outsider, cspr(1000), cspr(1)),
+            Err(VaultError::Unauthorized.into()),
+        );
+        // and only with the assets behind them
+        f.env.set_caller(f.admin);
+        f.new_vault.set_migration_source(outsider);
+        f.env.set_caller(outsider);
+        assert_eq!(
+            f.new_vault.try_receive_migrated_position(outsider, cspr(1000), cspr(1)),
+            Err(VaultError::InsufficientAllowance.into()),
+        );
+        f.env.set_caller(f.admin);
+        f.new_vault.set_migration_source(*f.old_vault.address());
+
+        // The batch waits for the grace period and needs admin
+        f.env.set_caller(f.admin);
+        assert_eq!(
+            f.old_vault.try_migrate_positions(vec![user1]),
+            Err(VaultError::TimelockNotExpired.into()),
+        );
+        f.env.advance_block_time(GRACE_PERIOD);
+        f.env.set_caller(outsider);
+        assert!(f.old_vault.try_migrate_positions(vec![user1]).is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        assert_eq!(f.old_vault.migrate_positions(vec![user1]), 1);
+        assert_u512_eq(f.old_vault.get_user_shares(user1), U512::zero(), "Moved by the batch");
+    }
+
+    #[test]
+    fn test_migration_of_cspr_deposits_moves_cspr() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let mut old_vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        let mut new_vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        for vault in [&mut old_vault, &mut new_vault] {
+            vault.set_management_fee(0);
+            vault.set_min_holding_period(0);
+        }
+        new_vault.set_migration_source(*old_vault.address());
+
+        env.set_caller(user);
+        old_vault.with_tokens(cspr(1000)).deposit();
+        let old_purse = env.balance_of(old_vault.address());
+        let new_purse = env.balance_of(new_vault.address());
+
+        env.set_caller(admin);
+        old_vault.enable_migration(*new_vault.address());
+        env.set_caller(user);
+        assert_u512_eq(old_vault.migrate_position(), cspr(1000), "1:1 into an empty vault");
+
+        // The vault never held lstCSPR for this position, so the CSPR goes across
+        assert_u512_eq(old_purse - env.balance_of(old_vault.address()), cspr(1000), "Sent by the old vault");
+        assert_u512_eq(env.balance_of(new_vault.address()) - new_purse, cspr(1000), "Received by the new vault");
+        assert_u512_eq(new_vault.get_user_assets(user), cspr(1000), "Position value unchanged");
+        assert_u512_eq(new_vault.get_instant_pool_balance(), cspr(1000), "Joins the instant pool");
+    }
+}
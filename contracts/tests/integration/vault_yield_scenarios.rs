@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod vault_yield_scenarios {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::VaultManagerHostRef;
+    use crate::helpers::*;
+
+    fn deploy_vault(env: &HostEnv) -> VaultManagerHostRef {
+        let mut vault = SystemBuilder::new(env).with_vault().build().vault.unwrap();
+        vault.set_min_holding_period(0);
+        vault
+    }
+
+    /// Yield is split by share of the pool, and each exit pays the
+    /// performance fee on its own profit only
+    #[test]
+    fn test_yield_distribution_fairness() {
+        let env = odra_test::env();
+        let mut vault = deploy_vault(&env);
+        let (user_a, user_b) = (env.get_account(1), env.get_account(2));
+
+        // 60% / 40% of a 10,000 CSPR pool, then a 10% return
+        env.set_caller(user_a);
+        let shares_a = vault.with_tokens(cspr(6000)).deposit();
+        env.set_caller(user_b);
+        let shares_b = vault.with_tokens(cspr(4000)).deposit();
+        vault.test_add_yield(cspr(1000));
+
+        assert_u512_eq(vault.get_user_assets(user_a), cspr(6600), "A holds 60% of the yield");
+        assert_u512_eq(vault.get_user_assets(user_b), cspr(4400), "B holds 40% of the yield");
+
+        env.set_caller(user_a);
+        assert_u512_eq(vault.withdraw(shares_a), cspr(6540), "6,600 less 10% of 600 profit");
+        env.set_caller(user_b);
+        assert_u512_eq(vault.withdraw(shares_b), cspr(4360), "4,400 less 10% of 400 profit");
+        assert_u512_eq(vault.get_fee_breakdown().performance_fees, cspr(100), "10% of the whole yield");
+    }
+
+    /// A second deposit at a higher share price adds its full amount to the
+    /// cost basis, so only the gain above both deposits is charged
+    #[test]
+    fn test_performance_fee_cost_basis_tracking() {
+        let env = odra_test::env();
+        let mut vault = deploy_vault(&env);
+        let user = env.get_account(1);
+
+        env.set_caller(user);
+        vault.with_tokens(cspr(1000)).deposit();
+
+        // Share price 1.0 -> 1.2
+        vault.test_set_total_assets(cspr(1200));
+        let second = vault.with_tokens(cspr(1200)).deposit();
+        assert_u512_eq(second, cspr(1000), "1,200 CSPR buys 1,000 shares at 1.2");
+        assert_u512_eq(vault.get_user_deposit(user).unwrap().cost_basis, cspr(2200), "Both deposits in the basis");
+
+        // Share price 1.2 -> 1.5 on 2,000 shares
+        vault.test_set_total_assets(cspr(3000));
+        let payout = vault.withdraw(vault.get_user_shares(user));
+
+        // Profit 3,000 - 2,200 = 800, fee 80
+        assert_u512_eq(vault.get_fee_breakdown().performance_fees, cspr(80), "Fee on profit above the basis");
+        assert_u512_eq(payout, cspr(2920), "Payout after fee");
+    }
+}
@@ -299,6 +299,13 @@ impl MockDEX {
         )
     }
     
+    /// Overwrite the pool reserves, e.g. to move the price the way a
+    /// sandwich's front-running swap would
+    pub fn set_reserves(&mut self, lst_cspr_reserve: U512, cspr_reserve: U512) {
+        self.lst_cspr_reserve.set(lst_cspr_reserve);
+        self.cspr_reserve.set(cspr_reserve);
+    }
+    
     /// Get total LP supply
     pub fn get_total_supply(&self) -> U512 {
         self.total_lp_supply.get_or_default()
@@ -1,9 +1,7 @@
-pub mod mock_validator;
 pub mod mock_dex;
 pub mod mock_lending;
 pub mod mock_bridge;
 
-pub use mock_validator::*;
 pub use mock_dex::MockDEX;
 pub use mock_lending::MockLending;
 pub use mock_bridge::{MockBridge, TargetChain, BridgeStatus};
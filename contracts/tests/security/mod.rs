@@ -1 +1,3 @@
 pub mod attack_scenarios;
+pub mod sandwich_attack_tests;
+pub mod timestamp_manipulation_tests;
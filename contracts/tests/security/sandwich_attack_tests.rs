@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod sandwich_attack_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::strategies::DEXStrategyHostRef;
+    use caspervault_contracts::types::{StrategyError, VaultError};
+    use crate::mocks::mock_dex::MockDEXHostRef;
+    use crate::helpers::*;
+
+    /// Each side of the mock pool at par
+    const RESERVE: u64 = 1_000_000_000;
+
+    /// 1 CSPR per lstCSPR at the strategy's 1e9 price scale
+    const PAR: u64 = 1_000_000_000;
+
+    /// DEX strategy on a mock pool at par, with the default 2% guard and
+    /// a first observation seeding the pool average
+    fn setup() -> (HostEnv, DEXStrategyHostRef, MockDEXHostRef) {
+        let env = odra_test::env();
+        let contracts = SystemBuilder::new(&env).with_mock_dex(1200).with_dex().build();
+        let mut strategy = contracts.dex.unwrap();
+        strategy.record_price_observation();
+        (env, strategy, contracts.mock_dex.unwrap())
+    }
+
+    /// Move the pool to `price_bps` of par, as a front-running swap would
+    fn skew(pool: &mut MockDEXHostRef, price_bps: u64) {
+        pool.set_reserves(U512::from(RESERVE), U512::from(RESERVE * price_bps / 10_000));
+    }
+
+    #[test]
+    fn test_sandwiched_deploy_is_refused() {
+        let (_env, mut strategy, mut pool) = setup();
+        assert_eq!(strategy.get_price_guard(), (200, 1800));
+        assert_eq!(strategy.get_pool_price(), Some(U512::from(PAR)));
+        assert_eq!(strategy.get_reference_price(), Some(U512::from(PAR)));
+
+        // Front-run: the attacker pumps the pool 10% before the deploy lands
+        skew(&mut pool, 11_000);
+        assert_eq!(
+            strategy.try_deploy(cspr(1000)),
+            Err(StrategyError::PriceDeviationTooHigh.into())
+        );
+        assert_u512_eq(strategy.get_total_deployed(), U512::zero(), "Nothing added at the skewed ratio");
+
+        // Back-run: once the pool is back at par the same deploy goes through
+        skew(&mut pool, 10_000);
+        assert_u512_eq(strategy.deploy(cspr(1000)), cspr(1000), "Deployed at a fair price");
+    }
+
+    #[test]
+    fn test_sandwiched_withdraw_is_refused_until_price_normalizes() {
+        let (_env, mut strategy, mut pool) = setup();
+        strategy.deploy(cspr(1000));
+
+        skew(&mut pool, 9000);
+        assert_eq!(
+            strategy.try_withdraw(cspr(500)),
+            Err(StrategyError::PriceDeviationTooHigh.into())
+        );
+        assert_u512_eq(strategy.get_total_deployed(), cspr(1000), "Position untouched");
+
+        // Ordinary drift inside the 2% band doesn't block exits
+        skew(&mut pool, 9850);
+        assert_u512_eq(strategy.withdraw(cspr(500)), cspr(500), "Withdrawn within tolerance");
+    }
+
+    /// A price held for a minute only moves the average a thirtieth of
+    /// the way; one held for the full window is the new reference
+    #[test]
+    fn test_pool_average_resists_short_lived_manipulation() {
+        let (env, mut strategy, mut pool) = setup();
+
+        env.advance_block_time(60);
+        skew(&mut pool, 12_000);
+        strategy.record_price_observation();
+        assert_eq!(strategy.get_reference_price(), Some(U512::from(1_006_666_666u64)), "Moved 1/30 of 20%");
+        assert_eq!(
+            strategy.try_deploy(cspr(1000)),
+            Err(StrategyError::PriceDeviationTooHigh.into())
+        );
+
+        skew(&mut pool, 10_000);
+        assert_u512_eq(strategy.deploy(cspr(1000)), cspr(1000), "Par is within 2% of the average");
+
+        // A genuine repricing the average follows over the window
+        skew(&mut pool, 11_000);
+        env.advance_block_time(1800);
+        strategy.record_price_observation();
+        assert_eq!(strategy.get_reference_price(), Some(U512::from(1_100_000_000u64)), "Fully caught up");
+        assert_u512_eq(strategy.deploy(cspr(1000)), cspr(1000), "New price accepted");
+    }
+
+    #[test]
+    fn test_oracle_reference_ignores_the_pool() {
+        let (env, mut strategy, mut pool) = setup();
+        let mut staking = SystemBuilder::new(&env).with_mock_liquid_staking().build().mock_liquid_staking.unwrap();
+        staking.set_exchange_rate(U256::from(1_050_000_000u64));
+        strategy.set_price_oracle(*staking.address());
+        assert_eq!(strategy.get_reference_price(), Some(U512::from(1_050_000_000u64)));
+
+        // Par is 4.8% under the redemption rate
+        assert_eq!(
+            strategy.try_deploy(cspr(1000)),
+            Err(StrategyError::PriceDeviationTooHigh.into())
+        );
+        skew(&mut pool, 10_500);
+        assert_u512_eq(strategy.deploy(cspr(1000)), cspr(1000), "Pool agrees with the oracle");
+
+        // A wider band lets the strategy trade through a thinner pool
+        assert_eq!(strategy.try_set_price_guard(0, 1800), Err(VaultError::InvalidRequest.into()));
+        assert_eq!(strategy.try_set_price_guard(5001, 1800), Err(VaultError::InvalidRequest.into()));
+        assert_eq!(strategy.try_set_price_guard(500, 0), Err(VaultError::InvalidRequest.into()));
+        strategy.set_price_guard(500, 1800);
+        skew(&mut pool, 10_000);
+        assert_u512_eq(strategy.deploy(cspr(1000)), cspr(1000), "Inside a 5% band");
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod timestamp_manipulation_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{VaultManagerHostRef, DEPOSIT_DAY_SECONDS};
+    use caspervault_contracts::deployer::deploy_system;
+    use caspervault_contracts::VaultError;
+    use crate::helpers::*;
+
+    const ONE_HOUR: u64 = 60 * 60;
+    const KEEPER_ROLE: u8 = 3;
+
+    /// A proposer can skew block time by a few seconds; every checkpoint
+    /// must still hold that far short of its boundary
+    const SKEW: u64 = 3;
+
+    fn setup() -> (HostEnv, Address, Address, VaultManagerHostRef) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        vault.set_min_holding_period(0);
+        (env, admin, user, vault)
+    }
+
+    /// Move the clock to `SKEW` seconds before `target`
+    fn advance_to_just_before(env: &HostEnv, target: u64) {
+        env.advance_block_time(target - SKEW - env.get_block_time());
+    }
+
+    #[test]
+    fn test_withdrawal_timelock_holds_until_unlock_time() {
+        let (env, _admin, user, mut vault) = setup();
+        env.set_caller(user);
+        vault.with_tokens(cspr(1000)).deposit();
+        let request_id = vault.request_withdrawal(cspr(400));
+        let unlock_time = vault.get_withdrawal_request(request_id).unwrap().unlock_time;
+
+        advance_to_just_before(&env, unlock_time);
+        assert_eq!(vault.try_complete_withdrawal(request_id), Err(VaultError::Unauthorized.into()));
+
+        // Unlocks at exactly `unlock_time`
+        env.advance_block_time(SKEW);
+        assert!(vault.complete_withdrawal(request_id) > U512::zero(), "Paid at the unlock time");
+    }
+
+    #[test]
+    fn test_fee_collection_needs_a_full_hour() {
+        let (env, admin, user, mut vault) = setup();
+        env.set_caller(user);
+        vault.with_tokens(cspr(1000)).deposit();
+        env.set_caller(admin);
+        vault.grant_role(KEEPER_ROLE, admin);
+
+        env.advance_block_time(ONE_HOUR);
+        vault.collect_management_fees();
+        let collected_at = env.get_block_time();
+
+        advance_to_just_before(&env, collected_at + ONE_HOUR);
+        assert_eq!(vault.try_collect_management_fees(), Err(VaultError::RateLimitExceeded.into()));
+        env.advance_block_time(SKEW);
+        vault.collect_management_fees();
+    }
+
+    #[test]
+    fn test_daily_bucket_holds_until_midnight() {
+        let (env, _admin, user, mut vault) = setup();
+        let midnight = (env.get_block_time() / DEPOSIT_DAY_SECONDS + 1) * DEPOSIT_DAY_SECONDS;
+        advance_to_just_before(&env, midnight - 60);
+
+        env.set_caller(user);
+        for _ in 0..5 {
+            vault.with_tokens(cspr(10_000)).deposit();
+        }
+        assert_eq!(vault.max_deposit(user), U512::zero(), "Daily limit used up");
+
+        // A minute and a few seconds later the day is still the same one
+        advance_to_just_before(&env, midnight);
+        assert_eq!(vault.max_deposit(user), U512::zero(), "Still today's bucket");
+        assert_eq!(vault.with_tokens(cspr(1)).try_deposit(), Err(VaultError::RateLimitExceeded.into()));
+
+        env.advance_block_time(SKEW);
+        assert_eq!(vault.max_deposit(user), cspr(50_000), "New day, new bucket");
+    }
+
+    /// Views that measure time from a stored timestamp read zero elapsed
+    /// time in the block that stored it, rather than underflowing
+    #[test]
+    fn test_views_at_their_own_timestamp_do_not_underflow() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let system = deploy_system(&env, admin, env.get_account(6));
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(5000)).deposit();
+
+        assert_u512_eq(system.liquid_staking.get_pending_rewards_estimate(), U512::zero(), "Nothing accrued yet");
+        for (name, _, _) in system.router.get_all_allocations() {
+            let performance = system.router.get_strategy_performance(name);
+            assert_eq!(performance.days_active, 0);
+            assert_u512_eq(performance.avg_allocation, U512::zero(), "No time-weighted allocation yet");
+        }
+        assert!(system.aggregator.get_historical_apy(u64::MAX).is_zero(), "Period longer than the chain");
+    }
+}
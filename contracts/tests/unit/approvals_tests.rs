@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod approvals_tests {
+    use odra::prelude::*;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::FundsRescued;
+    use caspervault_contracts::core::VaultManagerHostRef;
+    use crate::helpers::*;
+
+    const ONE_DAY: u64 = 86400;
+
+    struct ApprovalFixture {
+        env: HostEnv,
+        admin: Address,
+        approvers: Vec<Address>,
+        token: Address,
+        recipient: Address,
+        vault: VaultManagerHostRef,
+    }
+
+    fn setup() -> ApprovalFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let approvers = vec![env.get_account(1), env.get_account(2), env.get_account(3)];
+
+        let vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+
+        let token = env.get_account(8);
+        let recipient = env.get_account(6);
+        ApprovalFixture { env, admin, approvers, token, recipient, vault }
+    }
+
+    /// Switch the fixture vault to 2-of-3 approvals with a one-day window
+    fn require_two_of_three(f: &mut ApprovalFixture) {
+        f.env.set_caller(f.admin);
+        f.vault.set_approvers(f.approvers.clone(), 2, ONE_DAY);
+    }
+
+    fn approve(f: &mut ApprovalFixture, approver: usize, action_hash: [u8; 32]) {
+        f.env.set_caller(f.approvers[approver]);
+        f.vault.approve_action(action_hash);
+    }
+
+    #[test]
+    fn test_two_of_three_flow() {
+        let mut f = setup();
+        require_two_of_three(&mut f);
+        let (token, recipient) = (f.token, f.recipient);
+        let action_hash = f.vault.get_rescue_funds_hash(token, cspr(100), recipient);
+
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_rescue_funds(token, cspr(100), recipient).is_err(), "No approvals");
+
+        approve(&mut f, 0, action_hash);
+        assert_eq!(f.vault.get_approval_count(action_hash), 1);
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_rescue_funds(token, cspr(100), recipient).is_err(), "One of two");
+
+        approve(&mut f, 2, action_hash);
+        assert_eq!(f.vault.get_approval_count(action_hash), 2);
+        f.env.set_caller(f.admin);
+        f.vault.rescue_funds(token, cspr(100), recipient);
+
+        let event = f.env.get_event::<FundsRescued>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.amount, cspr(100), "Rescued amount");
+
+        // Approvals are consumed by the execution
+        assert_eq!(f.vault.get_approval_count(action_hash), 0);
+        assert!(f.vault.try_rescue_funds(token, cspr(100), recipient).is_err(), "No replay");
+    }
+
+    #[test]
+    fn test_expired_approvals_do_not_count() {
+        let mut f = setup();
+        require_two_of_three(&mut f);
+        let (token, recipient) = (f.token, f.recipient);
+        let action_hash = f.vault.get_rescue_funds_hash(token, cspr(100), recipient);
+
+        approve(&mut f, 0, action_hash);
+        approve(&mut f, 1, action_hash);
+        f.env.advance_block_time(ONE_DAY);
+
+        assert_eq!(f.vault.get_approval_count(action_hash), 0);
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_rescue_funds(token, cspr(100), recipient).is_err());
+    }
+
+    #[test]
+    fn test_argument_mismatch_uses_different_hash() {
+        let mut f = setup();
+        require_two_of_three(&mut f);
+        let (token, recipient) = (f.token, f.recipient);
+        let approved_hash = f.vault.get_rescue_funds_hash(token, cspr(100), recipient);
+        let other_hash = f.vault.get_rescue_funds_hash(token, cspr(200), recipient);
+        assert_ne!(approved_hash, other_hash);
+
+        approve(&mut f, 0, approved_hash);
+        approve(&mut f, 1, approved_hash);
+
+        f.env.set_caller(f.admin);
+        assert!(
+            f.vault.try_rescue_funds(token, cspr(200), recipient).is_err(),
+            "Approvals for 100 don't cover 200"
+        );
+        f.vault.rescue_funds(token, cspr(100), recipient);
+    }
+
+    #[test]
+    fn test_revoke_and_non_approver() {
+        let mut f = setup();
+        require_two_of_three(&mut f);
+        let action_hash = f.vault.get_rescue_funds_hash(f.token, cspr(100), f.recipient);
+
+        f.env.set_caller(f.env.get_account(5));
+        assert!(f.vault.try_approve_action(action_hash).is_err(), "Not an approver");
+
+        approve(&mut f, 0, action_hash);
+        f.vault.revoke_approval(action_hash);
+        assert_eq!(f.vault.get_approval_count(action_hash), 0);
+        assert!(f.vault.try_revoke_approval(action_hash).is_err(), "Nothing to revoke");
+    }
+
+    #[test]
+    fn test_threshold_one_passthrough() {
+        let mut f = setup();
+        let (token, recipient) = (f.token, f.recipient);
+
+        // Default: single-admin mode
+        f.env.set_caller(f.admin);
+        f.vault.rescue_funds(token, cspr(100), recipient);
+
+        // Explicit threshold of 1 behaves the same
+        f.vault.set_approvers(Vec::new(), 1, ONE_DAY);
+        f.vault.rescue_funds(token, cspr(100), recipient);
+
+        // Threshold above the approver count is rejected
+        assert!(f.vault.try_set_approvers(vec![f.approvers[0]], 2, ONE_DAY).is_err());
+
+        f.env.set_caller(f.approvers[0]);
+        assert!(f.vault.try_rescue_funds(token, cspr(100), recipient).is_err(), "Still admin only");
+    }
+}
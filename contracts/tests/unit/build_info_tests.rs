@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod build_info_tests {
+    use odra::prelude::*;
+    use odra::contract_def::HasEntrypoints;
+    use caspervault_contracts::core::{
+        LiquidStaking, StrategyRouter, VaultManager, YieldAggregator, AGGREGATOR_FEATURES,
+        LIQUID_STAKING_FEATURES, ROUTER_FEATURES, VAULT_FEATURES,
+    };
+    use caspervault_contracts::deployer::deploy_system;
+    use caspervault_contracts::utils::{CONTRACT_VERSION, GIT_HASH};
+
+    /// Entrypoint that ships each feature; add a row with every new flag
+    const VAULT_CHECKLIST: &[(&str, &str)] = &[
+        ("deposit_lst", "lst_deposits"),
+        ("withdraw_as_lst", "lst_withdrawals"),
+        ("exit_batched", "batched_exits"),
+        ("instant_withdraw", "instant_withdrawals"),
+        ("request_withdrawal_with_auto_complete", "auto_complete_withdrawals"),
+        ("transfer_withdrawal_request", "transferable_withdrawal_requests"),
+        ("expire_withdrawal", "withdrawal_expiry"),
+        ("estimate_liquidity_schedule", "liquidity_schedule"),
+        ("set_risk_preference", "staking_only"),
+        ("migrate_position", "position_migration"),
+        ("set_fee_exempt", "fee_exemptions"),
+        ("get_fee_breakdown", "fee_breakdown"),
+        ("set_usd_deposit_limits", "usd_deposit_limits"),
+        ("freeze_account", "account_freeze"),
+        ("check_invariants", "invariant_checks"),
+        ("check_accounting_divergence", "divergence_circuit"),
+        ("run_keeper_job", "keeper_jobs"),
+        ("pay_compound_bounty", "compound_bounty"),
+        ("record_community_allocation", "community_pool"),
+        ("deposit_with_receipt", "receipts"),
+        ("get_user_realized_apy", "realized_apy"),
+        ("set_exit_fee_tiers", "exit_size_fees"),
+        ("approve_action", "multisig_approvals"),
+    ];
+
+    const LIQUID_STAKING_CHECKLIST: &[(&str, &str)] = &[
+        ("sync_exchange_rate", "exchange_rate_sync"),
+        ("run_keeper_job", "keeper_jobs"),
+        ("fund_insurance", "insurance_fund"),
+        ("set_validator_adapter", "validator_adapter"),
+        ("set_network_share_limits", "network_share_limits"),
+        ("emergency_exit_validator", "emergency_exit"),
+        ("freeze_withdrawals", "withdrawal_freeze"),
+        ("approve_action", "multisig_approvals"),
+        ("validator_exists", "validator_guards"),
+    ];
+
+    const ROUTER_CHECKLIST: &[(&str, &str)] = &[
+        ("process_deployment_queue", "deployment_queue"),
+        ("queue_withdrawal", "withdrawal_batching"),
+        ("set_withdrawal_policy", "withdrawal_policies"),
+        ("set_liquidity_tier", "liquidity_tiers"),
+        ("liquidate_rewards", "reward_liquidation"),
+        ("fund_native_float", "native_strategies"),
+        ("estimate_withdrawal_cost", "exit_cost_estimates"),
+        ("quarantine_strategy", "strategy_quarantine"),
+        ("settle_pending_withdrawal", "pending_withdrawals"),
+        ("get_reconciliation", "reconciliation"),
+        ("propose_strategy_tvl_cap", "exposure_caps"),
+        ("run_keeper_job", "keeper_jobs"),
+        ("get_allocation_history", "allocation_history"),
+    ];
+
+    const AGGREGATOR_CHECKLIST: &[(&str, &str)] = &[
+        ("auto_compound", "auto_compound"),
+        ("set_compound_bounty_bps", "compound_bounty"),
+        ("claim_community_funds", "community_pool"),
+        ("set_yield_destination", "yield_destination"),
+        ("release_pending_gains", "gain_smoothing"),
+        ("preview_yield_split", "yield_split_preview"),
+        ("get_net_apy", "net_apy"),
+        ("dry_run_compound", "compound_dry_run"),
+    ];
+
+    /// Every checklist entrypoint the contract exposes has its flag, and
+    /// every flag has a checklist row
+    fn assert_checklist<T: HasEntrypoints>(contract: &str, features: &[&str], checklist: &[(&str, &str)]) {
+        let entrypoints: Vec<String> = T::entrypoints().into_iter().map(|entrypoint| entrypoint.ident).collect();
+        for (entrypoint, flag) in checklist {
+            if entrypoints.iter().any(|ident| ident == entrypoint) {
+                assert!(features.contains(flag), "{}::{} shipped without the `{}` flag", contract, entrypoint, flag);
+            }
+        }
+        for flag in features {
+            assert!(
+                checklist.iter().any(|(_, listed)| listed == flag),
+                "{} reports `{}` but the checklist has no entrypoint for it",
+                contract,
+                flag
+            );
+        }
+    }
+
+    #[test]
+    fn test_version_matches_cargo_package() {
+        let version = format!("{}.{}.{}", CONTRACT_VERSION.0, CONTRACT_VERSION.1, CONTRACT_VERSION.2);
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+
+        let env = odra_test::env();
+        let system = deploy_system(&env, env.get_account(0), env.get_account(6));
+        assert_eq!(system.vault.get_version(), CONTRACT_VERSION);
+        assert_eq!(system.liquid_staking.get_version(), CONTRACT_VERSION);
+        assert_eq!(system.router.get_version(), CONTRACT_VERSION);
+        assert_eq!(system.aggregator.get_version(), CONTRACT_VERSION);
+        assert_eq!(system.vault.get_git_hash(), GIT_HASH.filter(|hash| !hash.is_empty()).map(String::from));
+    }
+
+    #[test]
+    fn test_deployments_report_their_feature_flags() {
+        let env = odra_test::env();
+        let system = deploy_system(&env, env.get_account(0), env.get_account(6));
+        let owned = |features: &[&str]| features.iter().map(|flag| flag.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(system.vault.get_features(), owned(VAULT_FEATURES));
+        assert_eq!(system.liquid_staking.get_features(), owned(LIQUID_STAKING_FEATURES));
+        assert_eq!(system.router.get_features(), owned(ROUTER_FEATURES));
+        assert_eq!(system.aggregator.get_features(), owned(AGGREGATOR_FEATURES));
+    }
+
+    #[test]
+    fn test_every_shipped_entrypoint_has_its_flag() {
+        assert_checklist::<VaultManager>("VaultManager", VAULT_FEATURES, VAULT_CHECKLIST);
+        assert_checklist::<LiquidStaking>("LiquidStaking", LIQUID_STAKING_FEATURES, LIQUID_STAKING_CHECKLIST);
+        assert_checklist::<StrategyRouter>("StrategyRouter", ROUTER_FEATURES, ROUTER_CHECKLIST);
+        assert_checklist::<YieldAggregator>("YieldAggregator", AGGREGATOR_FEATURES, AGGREGATOR_CHECKLIST);
+    }
+}
@@ -0,0 +1,264 @@
+/// Calls every admin setter once and checks the `ConfigChanged` it emits,
+/// doubling as the inventory of configurable parameters. Fees, treasury,
+/// approvers and migration have dedicated events and are covered by their
+/// own tests.
+#[cfg(test)]
+mod config_events_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+    use caspervault_contracts::{config_address, ConfigChanged, MockRewardTokenHostRef};
+    use crate::helpers::*;
+
+    const ONE_HOUR: u64 = 60 * 60;
+    const ONE_DAY: u64 = 24 * ONE_HOUR;
+
+    /// Check the `ConfigChanged` event `back` places from the end
+    fn assert_config(
+        env: &HostEnv,
+        contract: &Address,
+        back: i32,
+        name: &str,
+        key: &str,
+        old_value: impl ToString,
+        new_value: impl ToString,
+    ) {
+        let event = env.get_event::<ConfigChanged>(contract, back).unwrap();
+        assert_eq!(event.contract, name);
+        assert_eq!(event.key, key);
+        assert_eq!(event.old_value, old_value.to_string(), "old value of {}", key);
+        assert_eq!(event.new_value, new_value.to_string(), "new value of {}", key);
+        assert_eq!(event.caller, env.get_account(0));
+    }
+
+    fn addr(address: Address) -> String {
+        config_address(Some(address))
+    }
+
+    #[test]
+    fn test_vault_manager_setters_emit_config_changed() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let other = env.get_account(5);
+
+        env.set_caller(admin);
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        let at = *vault.address();
+        let expect = |back: i32, key: &str, old: String, new: String| {
+            assert_config(&env, &at, back, "VaultManager", key, old, new)
+        };
+
+        vault.set_liquid_staking(other);
+        expect(-1, "liquid_staking", "none".into(), addr(other));
+        vault.set_strategy_router(other);
+        expect(-1, "strategy_router", "none".into(), addr(other));
+        vault.set_yield_aggregator(other);
+        expect(-1, "yield_aggregator", "none".into(), addr(other));
+        vault.set_cv_cspr_token(other);
+        expect(-1, "cv_cspr_token", "none".into(), addr(other));
+        vault.set_migration_source(other);
+        expect(-1, "migration_source", "none".into(), addr(other));
+        vault.set_migration_grace_period(7 * ONE_DAY);
+        expect(-1, "migration_grace_period", (14 * ONE_DAY).to_string(), (7 * ONE_DAY).to_string());
+        vault.set_min_holding_period(0);
+        expect(-1, "min_holding_period", "60".into(), "0".into());
+        vault.set_request_expiry(30 * ONE_DAY);
+        expect(-1, "request_expiry", (90 * ONE_DAY).to_string(), (30 * ONE_DAY).to_string());
+
+        vault.set_harvest_before_withdraw(true, cspr(500), 3);
+        expect(-3, "harvest_before_withdraw", "false".into(), "true".into());
+        expect(-2, "harvest_withdraw_threshold", cspr(1000).to_string(), cspr(500).to_string());
+        expect(-1, "max_harvest_strategies", "5".into(), "3".into());
+
+        vault.set_instant_pool_target(1000);
+        expect(-1, "instant_pool_target_bps", "500".into(), "1000".into());
+
+        vault.update_deposit_limits(cspr(5000), cspr(20000));
+        expect(-2, "max_deposit_per_tx", "0".into(), cspr(5000).to_string());
+        expect(-1, "max_deposit_per_day", cspr(50000).to_string(), cspr(20000).to_string());
+
+        vault.set_instant_fee_to_holders(5000);
+        expect(-1, "instant_fee_to_holders_bps", "0".into(), "5000".into());
+
+        vault.set_price_oracle(other, 1800);
+        expect(-2, "price_oracle", "none".into(), addr(other));
+        expect(-1, "max_price_staleness", "3600".into(), "1800".into());
+
+        vault.set_usd_deposit_limits(true, cspr(200), cspr(1000));
+        expect(-3, "usd_limits_enabled", "false".into(), "true".into());
+        expect(-2, "max_deposit_usd", "0".into(), cspr(200).to_string());
+        expect(-1, "max_deposit_per_day_usd", "0".into(), cspr(1000).to_string());
+
+        vault.set_withdrawal_timelock(2 * ONE_DAY);
+        expect(-1, "withdrawal_timelock", (7 * ONE_DAY).to_string(), (2 * ONE_DAY).to_string());
+        vault.set_profit_unlock_duration(ONE_HOUR);
+        expect(-1, "profit_unlock_duration", (6 * ONE_HOUR).to_string(), ONE_HOUR.to_string());
+        vault.set_operation_log_size(512);
+        expect(-1, "operation_log_size", "256".into(), "512".into());
+        vault.set_invariant_auto_pause(true);
+        expect(-1, "invariant_auto_pause", "false".into(), "true".into());
+        vault.set_divergence_circuit(300, ONE_DAY);
+        expect(-2, "max_divergence_bps", "500".into(), "300".into());
+        expect(-1, "divergence_window", (7 * ONE_DAY).to_string(), ONE_DAY.to_string());
+        vault.freeze_withdrawals();
+        expect(-1, "withdrawals_frozen", "false".into(), "true".into());
+    }
+
+    #[test]
+    fn test_liquid_staking_setters_emit_config_changed() {
+        let env = odra_test::env();
+        let other = env.get_account(5);
+
+        let mut staking = SystemBuilder::new(&env).with_staking().build().staking.unwrap();
+        let at = *staking.address();
+        let expect = |key: &str, old: String, new: String| {
+            assert_config(&env, &at, -1, "LiquidStaking", key, old, new)
+        };
+
+        staking.set_unbonding_period(7 * ONE_DAY);
+        expect("unbonding_period", (14 * ONE_DAY).to_string(), (7 * ONE_DAY).to_string());
+        staking.set_era_duration(ONE_HOUR);
+        expect("era_duration", (2 * ONE_HOUR).to_string(), ONE_HOUR.to_string());
+        staking.set_min_compound_interval(ONE_HOUR);
+        expect("min_compound_interval", (12 * ONE_HOUR).to_string(), ONE_HOUR.to_string());
+        staking.set_surplus_unlock_duration(ONE_HOUR);
+        expect("surplus_unlock_duration", ONE_DAY.to_string(), ONE_HOUR.to_string());
+        staking.set_max_sync_deficit_bps(100);
+        expect("max_sync_deficit_bps", "50".into(), "100".into());
+        staking.set_validator_adapter(other);
+        expect("validator_adapter", "none".into(), addr(other));
+        staking.set_lst_cspr_token(other);
+        expect("lst_cspr_token", addr(env.get_account(8)), addr(other));
+        staking.set_vault_address(other);
+        expect("vault_address", "none".into(), addr(other));
+        staking.freeze_withdrawals();
+        expect("withdrawals_frozen", "false".into(), "true".into());
+
+        // Validator selection lives in the ValidatorRegistry submodule
+        staking.set_reward_tiebreaker(true);
+        assert_config(&env, &at, -1, "ValidatorRegistry", "reward_tiebreaker", false, true);
+        staking.set_network_share_limits(1000, ONE_HOUR);
+        assert_config(&env, &at, -2, "ValidatorRegistry", "max_network_share_bps", 1500, 1000);
+        assert_config(&env, &at, -1, "ValidatorRegistry", "network_share_max_age", ONE_DAY, ONE_HOUR);
+        staking.set_max_per_validator_pct(20);
+        assert_config(&env, &at, -1, "ValidatorRegistry", "max_per_validator_pct", 10, 20);
+    }
+
+    #[test]
+    fn test_router_setters_emit_config_changed() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        // Without a vault the router starts out pointed at the admin
+        let mut router = SystemBuilder::new(&env)
+            .with_router()
+            .with_mock_strategy("dex", 1000)
+            .build()
+            .router
+            .unwrap();
+        let token = MockRewardTokenHostRef::deploy(&env, NoArgs);
+
+        let at = *router.address();
+        let expect = |back: i32, key: &str, old: String, new: String| {
+            assert_config(&env, &at, back, "StrategyRouter", key, old, new)
+        };
+
+        router.set_target_allocations(vec![("dex".to_string(), 40u8)]);
+        expect(-1, "target_allocation.dex", "0".into(), "40".into());
+        router.set_idle_buffer_pct(10);
+        expect(-1, "idle_buffer_pct", "5".into(), "10".into());
+        router.set_withdrawal_policy(1);
+        expect(-1, "withdrawal_policy", "0".into(), "1".into());
+        router.set_liquidity_tier("dex".to_string(), 0);
+        expect(-1, "liquidity_tier.dex", "2".into(), "0".into());
+        router.set_max_price_change_bps(500);
+        expect(-1, "max_price_change_bps", "200".into(), "500".into());
+        router.set_withdrawal_deadline("dex".to_string(), ONE_DAY);
+        expect(-1, "withdrawal_deadline.dex", "0".into(), ONE_DAY.to_string());
+        router.set_allocation_history(8, ONE_DAY);
+        expect(-2, "allocation_history_size", "64".into(), "8".into());
+        expect(-1, "allocation_snapshot_epoch", "0".into(), ONE_DAY.to_string());
+        router.set_vault_address(env.get_account(5));
+        expect(-1, "vault", addr(admin), addr(env.get_account(5)));
+        router.set_yield_aggregator(env.get_account(5));
+        expect(-1, "yield_aggregator", "none".into(), addr(env.get_account(5)));
+
+        router.set_reward_route(*token.address(), env.get_account(7), true, U512::from(400_000_000u64));
+        let route = router.get_reward_route(*token.address()).unwrap();
+        expect(-1, &format!("reward_route.{}", addr(*token.address())), "none".into(), format!("{:?}", route));
+
+        router.set_health_thresholds(5, 300, 1500, U256::from(20000u64));
+        expect(-4, "health_failure_threshold", "3".into(), "5".into());
+        expect(-3, "max_balance_drift_bps", "200".into(), "300".into());
+        expect(-2, "critical_balance_drift_bps", "1000".into(), "1500".into());
+        expect(-1, "max_strategy_apy_bps", "50000".into(), "20000".into());
+    }
+
+    #[test]
+    fn test_aggregator_setters_emit_config_changed() {
+        let env = odra_test::env();
+        let other = env.get_account(5);
+
+        let mut aggregator = SystemBuilder::new(&env).with_aggregator().build().aggregator.unwrap();
+        let at = *aggregator.address();
+        let expect = |key: &str, old: String, new: String| {
+            assert_config(&env, &at, -1, "YieldAggregator", key, old, new)
+        };
+
+        aggregator.set_min_compound_interval(2 * ONE_HOUR);
+        expect("min_compound_interval", ONE_HOUR.to_string(), (2 * ONE_HOUR).to_string());
+        aggregator.set_min_yield_threshold(cspr(50));
+        expect("min_yield_threshold", cspr(100).to_string(), cspr(50).to_string());
+        aggregator.set_management_fee(100);
+        expect("management_fee_bps", "200".into(), "100".into());
+        aggregator.set_max_price_change_bps(300);
+        expect("max_price_change_bps", "200".into(), "300".into());
+        aggregator.set_vault_address(other);
+        expect("vault_address", "none".into(), addr(other));
+        aggregator.set_fee_recipient(other);
+        expect("fee_recipient", addr(env.get_account(6)), addr(other));
+        aggregator.set_yield_destination(1);
+        expect("yield_destination", "0".into(), "1".into());
+    }
+
+    #[test]
+    fn test_strategy_setters_emit_config_changed() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let other = env.get_account(5);
+
+        env.set_caller(admin);
+        let strategies = SystemBuilder::new(&env).with_dex().with_lending().with_crosschain().build();
+        let (mut dex, mut lending, mut crosschain) =
+            (strategies.dex.unwrap(), strategies.lending.unwrap(), strategies.crosschain.unwrap());
+
+        let at = *dex.address();
+        dex.set_router(other);
+        assert_config(&env, &at, -1, "DEXStrategy", "router", "none", addr(other));
+        dex.set_max_capacity(cspr(500_000));
+        assert_config(&env, &at, -1, "DEXStrategy", "max_capacity", cspr(1_000_000), cspr(500_000));
+        dex.set_max_slippage(200);
+        assert_config(&env, &at, -1, "DEXStrategy", "max_slippage_bps", 100, 200);
+
+        let at = *lending.address();
+        lending.set_router(other);
+        assert_config(&env, &at, -1, "LendingStrategy", "router", "none", addr(other));
+        lending.set_max_capacity(cspr(500_000));
+        assert_config(&env, &at, -1, "LendingStrategy", "max_capacity", cspr(5_000_000), cspr(500_000));
+        lending.set_utilization_targets(6000, 8000);
+        assert_config(&env, &at, -2, "LendingStrategy", "target_utilization_bps", 7000, 6000);
+        assert_config(&env, &at, -1, "LendingStrategy", "max_utilization_bps", 9000, 8000);
+
+        let at = *crosschain.address();
+        crosschain.set_router(other);
+        assert_config(&env, &at, -1, "CrossChainStrategy", "router", "none", addr(other));
+        crosschain.set_max_capacity(cspr(500_000));
+        assert_config(&env, &at, -1, "CrossChainStrategy", "max_capacity", cspr(2_000_000), cspr(500_000));
+        crosschain.set_return_bridge(other);
+        assert_config(&env, &at, -1, "CrossChainStrategy", "return_bridge", "none", addr(other));
+        crosschain.set_bridge_fee(100);
+        assert_config(&env, &at, -1, "CrossChainStrategy", "bridge_fee_bps", 50, 100);
+        crosschain.set_max_apy_staleness(ONE_HOUR);
+        assert_config(&env, &at, -1, "CrossChainStrategy", "max_apy_staleness", ONE_DAY, ONE_HOUR);
+    }
+}
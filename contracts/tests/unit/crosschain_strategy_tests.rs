@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod crosschain_strategy_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::HostEnv;
+    use caspervault_contracts::strategies::CrossChainStrategyHostRef;
+    use crate::helpers::*;
+
+    const ORACLE_ROLE: u8 = 4;
+    const ETHEREUM: u8 = 0;
+    const POLYGON: u8 = 1;
+    const ONE_DAY: u64 = 86400;
+    const SECONDS_PER_YEAR: u64 = 31536000;
+
+    fn setup() -> (HostEnv, Address, Address, CrossChainStrategyHostRef) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let oracle = env.get_account(1);
+
+        env.set_caller(admin);
+        let mut strategy = SystemBuilder::new(&env).with_crosschain().build().crosschain.unwrap();
+        strategy.grant_role(ORACLE_ROLE, oracle);
+
+        (env, admin, oracle, strategy)
+    }
+
+    fn report(env: &HostEnv, oracle: Address, strategy: &mut CrossChainStrategyHostRef, chain: u8, apy_bps: u16) {
+        env.set_caller(oracle);
+        strategy.report_chain_apy(chain, apy_bps, env.get_block_time());
+    }
+
+    #[test]
+    fn test_deploy_picks_best_fresh_chain() {
+        let (env, admin, oracle, mut strategy) = setup();
+        report(&env, oracle, &mut strategy, ETHEREUM, 1200);
+        report(&env, oracle, &mut strategy, POLYGON, 2500);
+
+        env.set_caller(admin);
+        let deployed = strategy.deploy(cspr(10000));
+
+        // 0.5% bridge fee
+        assert_u512_eq(deployed, cspr(9950), "Deployed after bridge fee");
+        let (_, polygon_deployed, _) = strategy.get_position(POLYGON).unwrap();
+        assert_u512_eq(polygon_deployed, cspr(9950), "Funds went to Polygon");
+        assert!(strategy.get_position(ETHEREUM).is_none(), "Nothing on Ethereum");
+    }
+
+    #[test]
+    fn test_no_fresh_data_blocks_deploy() {
+        let (env, admin, _, mut strategy) = setup();
+
+        env.set_caller(admin);
+        assert_u512_eq(strategy.deploy(cspr(10000)), U512::zero(), "No chain reported yet");
+    }
+
+    #[test]
+    fn test_stale_chain_blocks_deploy_not_withdraw() {
+        let (env, admin, oracle, mut strategy) = setup();
+        report(&env, oracle, &mut strategy, POLYGON, 2500);
+
+        env.set_caller(admin);
+        strategy.deploy(cspr(10000));
+        assert!(strategy.is_healthy());
+
+        env.advance_block_time(2 * ONE_DAY);
+
+        let (apy, _, is_stale) = strategy.get_chain_apy(POLYGON);
+        assert_eq!(apy, 2500);
+        assert!(is_stale, "Polygon data is stale");
+        assert!(!strategy.is_chain_healthy(POLYGON));
+        assert!(!strategy.is_healthy(), "Stale chain with funds is unhealthy");
+
+        assert_u512_eq(strategy.deploy(cspr(5000)), U512::zero(), "Stale chain rejects deployment");
+
+        let withdrawn = strategy.withdraw(cspr(5000));
+        assert_u512_eq(withdrawn, cspr(4975), "Withdrawals unaffected by staleness (less 0.5% bridge fee)");
+    }
+
+    #[test]
+    fn test_fresh_report_restores_eligibility() {
+        let (env, admin, oracle, mut strategy) = setup();
+        report(&env, oracle, &mut strategy, POLYGON, 2500);
+
+        env.set_caller(admin);
+        strategy.deploy(cspr(10000));
+        env.advance_block_time(2 * ONE_DAY);
+        assert!(!strategy.is_healthy());
+
+        report(&env, oracle, &mut strategy, POLYGON, 2400);
+        assert!(strategy.is_chain_healthy(POLYGON));
+        assert!(strategy.is_healthy());
+
+        env.set_caller(admin);
+        assert_u512_eq(strategy.deploy(cspr(2000)), cspr(1990), "Deployment accepted again");
+    }
+
+    #[test]
+    fn test_yield_accrues_piecewise_across_apy_change() {
+        let (env, admin, oracle, mut strategy) = setup();
+        env.set_caller(admin);
+        strategy.set_max_apy_staleness(365 * ONE_DAY);
+        report(&env, oracle, &mut strategy, ETHEREUM, 1000);
+
+        env.set_caller(admin);
+        let deployed = strategy.deploy(cspr(10000));
+
+        env.advance_block_time(100 * ONE_DAY);
+        report(&env, oracle, &mut strategy, ETHEREUM, 2000);
+
+        env.advance_block_time(100 * ONE_DAY);
+        env.set_caller(admin);
+        let harvested = strategy.harvest();
+
+        let segment = |apy: u64| {
+            deployed * U512::from(apy) * U512::from(100 * ONE_DAY)
+                / U512::from(SECONDS_PER_YEAR)
+                / U512::from(10000u64)
+        };
+        let expected = segment(1000) + segment(2000);
+
+        assert_u512_eq(harvested, expected, "10% for 100 days then 20% for 100 days");
+        assert_u512_eq(strategy.get_total_yields(), expected, "Lifetime yields");
+    }
+
+    #[test]
+    fn test_report_validation() {
+        let (env, _, oracle, mut strategy) = setup();
+        let now = env.get_block_time();
+
+        env.set_caller(env.get_account(5));
+        assert!(strategy.try_report_chain_apy(ETHEREUM, 1000, now).is_err(), "Non-oracle rejected");
+
+        env.set_caller(oracle);
+        assert!(strategy.try_report_chain_apy(ETHEREUM, 1000, now + 60).is_err(), "Future timestamp rejected");
+        assert!(strategy.try_report_chain_apy(9, 1000, now).is_err(), "Unknown chain rejected");
+
+        strategy.report_chain_apy(ETHEREUM, 1000, now);
+        env.advance_block_time(60);
+        assert!(strategy.try_report_chain_apy(ETHEREUM, 1000, now - 1).is_err(), "Older report rejected");
+    }
+
+    #[test]
+    fn test_only_funded_chains_are_active() {
+        let (env, admin, oracle, mut strategy) = setup();
+        assert!(strategy.get_active_chains().is_empty(), "Nothing deployed yet");
+
+        report(&env, oracle, &mut strategy, POLYGON, 2500);
+        env.set_caller(admin);
+        strategy.deploy(cspr(10000));
+        report(&env, oracle, &mut strategy, ETHEREUM, 3000);
+        env.set_caller(admin);
+        strategy.deploy(cspr(10000));
+
+        assert_eq!(strategy.get_active_chains(), vec![POLYGON, ETHEREUM], "In order of first deployment");
+        assert_u512_eq(strategy.get_balance(), cspr(19900), "Both chains counted");
+
+        // Principal is drawn in active order, Polygon first
+        strategy.withdraw(cspr(9950));
+        assert_eq!(strategy.get_active_chains(), vec![ETHEREUM], "Emptied chain dropped");
+        assert_u512_eq(strategy.get_balance(), cspr(9950), "Ethereum position left");
+    }
+
+    #[test]
+    fn test_added_chain_usable_end_to_end() {
+        const BASE: u8 = 5;
+        let (env, admin, oracle, mut strategy) = setup();
+
+        env.set_caller(admin);
+        strategy.add_supported_chain(BASE, "Base".to_string(), 2000);
+        assert_eq!(strategy.get_supported_chains().len(), 5);
+        assert_eq!(
+            strategy.get_supported_chains()[4],
+            (BASE, "Base".to_string(), 2000u16, true),
+            "Registered, stale until reported",
+        );
+        assert!(strategy.try_add_supported_chain(BASE, "Base".to_string(), 2000).is_err(), "Already registered");
+
+        report(&env, oracle, &mut strategy, BASE, 4000);
+        assert!(strategy.is_chain_healthy(BASE));
+
+        env.set_caller(admin);
+        let deployed = strategy.deploy(cspr(10000));
+        let (_, base_deployed, _) = strategy.get_position(BASE).unwrap();
+        assert_u512_eq(base_deployed, deployed, "Funds went to Base");
+        assert_eq!(strategy.get_active_chains(), vec![BASE]);
+
+        env.advance_block_time(ONE_DAY);
+        report(&env, oracle, &mut strategy, BASE, 4000);
+        env.set_caller(admin);
+        let expected = deployed * U512::from(4000u64) * U512::from(ONE_DAY)
+            / U512::from(SECONDS_PER_YEAR)
+            / U512::from(10000u64);
+        assert_u512_eq(strategy.harvest(), expected, "Yield at Base's rate");
+
+        // Funds still on the chain keep it registered
+        assert!(strategy.try_remove_supported_chain(BASE).is_err(), "Balance left on Base");
+
+        let balance = strategy.get_balance();
+        strategy.withdraw(balance);
+        assert!(strategy.get_active_chains().is_empty(), "Fully withdrawn");
+
+        strategy.remove_supported_chain(BASE);
+        assert!(!strategy.is_chain_healthy(BASE), "No longer supported");
+        assert_eq!(strategy.get_supported_chains().len(), 4);
+
+        env.set_caller(env.get_account(5));
+        assert!(strategy.try_add_supported_chain(6, "Linea".to_string(), 1000).is_err(), "Admin only");
+    }
+}
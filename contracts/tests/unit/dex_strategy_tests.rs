@@ -9,31 +9,21 @@
 
 #[cfg(test)]
 mod dex_strategy_tests {
-    use crate::strategies::dex_strategy::*;
-    use crate::mocks::mock_dex::*;
-    use odra::host::{Deployer, HostRef, NoArgs};
-    use odra::types::{Address, U512};
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::HostRef;
+    use caspervault_contracts::strategies::DEXStrategyHostRef;
+    use crate::mocks::mock_dex::MockDEXHostRef;
+    use crate::helpers::SystemBuilder;
     
     /// Setup test environment
-    fn setup() -> (HostRef<DEXStrategyHostRef>, HostRef<MockDEXHostRef>, Address) {
-        let admin = odra::test_env::get_account(0);
-        let lst_cspr_address = odra::test_env::get_account(10);
-        
-        // Deploy mock DEX with 12% APY
-        let mut mock_dex = MockDEXHostRef::deploy(NoArgs);
-        mock_dex.init(1200u16); // 12% APY
-        
-        // Deploy DEX strategy
-        let mut strategy = DEXStrategyHostRef::deploy(NoArgs);
-        strategy.init(
-            admin,
-            lst_cspr_address,
-            *mock_dex.address(),
-            U512::from(1_000_000_000u64), // 1M max capacity
-            U512::from(100_000u64), // 100 min deployment
-        );
+    fn setup() -> (DEXStrategyHostRef, MockDEXHostRef, Address) {
+        let env = odra_test::env();
+        
+        // Mock DEX with 12% APY and a strategy providing liquidity to it
+        let contracts = SystemBuilder::new(&env).with_mock_dex(1200).with_dex().build();
         
-        (strategy, mock_dex, admin)
+        (contracts.dex.unwrap(), contracts.mock_dex.unwrap(), contracts.admin)
     }
     
     #[test]
@@ -208,7 +198,7 @@ mod dex_strategy_tests {
         strategy.with_tokens(admin).deploy(amount);
         
         // Emergency withdraw
-        let withdrawn = strategy.with_tokens(admin).emergency_withdraw();
+        let withdrawn = strategy.with_tokens(admin).emergency_withdraw(None);
         
         assert!(withdrawn > U512::zero(), "Should withdraw all funds");
         
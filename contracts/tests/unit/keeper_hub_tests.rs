@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod keeper_hub_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+    use caspervault_contracts::core::{
+        KeeperHubHostRef, JobExecuted,
+        JOB_HARVEST_ALL, JOB_REBALANCE, JOB_HEALTH_CHECK,
+    };
+    use caspervault_contracts::MockKeeperTargetHostRef;
+    use crate::helpers::*;
+
+    const ONE_HOUR: u64 = 3600;
+    const KEEPER_ROLE: u8 = 3;
+
+    struct HubFixture {
+        env: HostEnv,
+        admin: Address,
+        hub: KeeperHubHostRef,
+        target: MockKeeperTargetHostRef,
+        harvest_job: u32,
+        rebalance_job: u32,
+        health_job: u32,
+    }
+
+    /// Hub with three jobs on one mock target: harvest every hour,
+    /// rebalance every 12 hours, health check every 6 hours
+    fn setup() -> HubFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        let mut hub = SystemBuilder::new(&env).with_keeper_hub().build().keeper_hub.unwrap();
+        let target = MockKeeperTargetHostRef::deploy(&env, NoArgs);
+
+        let harvest_job = hub.add_job(*target.address(), JOB_HARVEST_ALL, ONE_HOUR);
+        let rebalance_job = hub.add_job(*target.address(), JOB_REBALANCE, 12 * ONE_HOUR);
+        let health_job = hub.add_job(*target.address(), JOB_HEALTH_CHECK, 6 * ONE_HOUR);
+
+        HubFixture { env, admin, hub, target, harvest_job, rebalance_job, health_job }
+    }
+
+    fn runs(f: &HubFixture) -> (u32, u32, u32) {
+        (
+            f.target.get_runs(JOB_HARVEST_ALL),
+            f.target.get_runs(JOB_REBALANCE),
+            f.target.get_runs(JOB_HEALTH_CHECK),
+        )
+    }
+
+    #[test]
+    fn test_poke_runs_only_due_jobs() {
+        let mut f = setup();
+        let keeper_network = f.env.get_account(5);
+        f.env.set_caller(keeper_network);
+
+        // Never-run jobs are all due
+        assert_eq!(f.hub.poke(), 3);
+        assert_eq!(runs(&f), (1, 1, 1));
+
+        // Nothing is due again in the same block
+        assert_eq!(f.hub.poke(), 0);
+        assert_eq!(f.target.get_calls(), 3);
+
+        f.env.advance_block_time(ONE_HOUR);
+        assert_eq!(f.hub.poke(), 1);
+        assert_eq!(runs(&f), (2, 1, 1));
+
+        f.env.advance_block_time(5 * ONE_HOUR);
+        assert_eq!(f.hub.poke(), 2, "Harvest and health check due at 6h");
+        assert_eq!(runs(&f), (3, 1, 2));
+
+        f.env.advance_block_time(6 * ONE_HOUR);
+        assert_eq!(f.hub.poke(), 3, "All three due at 12h");
+        assert_eq!(runs(&f), (4, 2, 3));
+    }
+
+    #[test]
+    fn test_failed_job_does_not_abort_batch() {
+        let mut f = setup();
+        f.target.set_failing(JOB_REBALANCE, true);
+
+        f.env.set_caller(f.env.get_account(5));
+        assert_eq!(f.hub.poke(), 2);
+        assert_eq!(runs(&f), (1, 0, 1), "Jobs after the failure still ran");
+
+        let events: Vec<JobExecuted> = (0..3)
+            .map(|i| f.env.get_event::<JobExecuted>(f.hub.address(), i).unwrap())
+            .collect();
+        assert_eq!(events[0], JobExecuted { job: f.harvest_job, success: true, gas_note: "harvest_all".to_string() });
+        assert_eq!(events[1], JobExecuted { job: f.rebalance_job, success: false, gas_note: "rebalance".to_string() });
+        assert_eq!(events[2], JobExecuted { job: f.health_job, success: true, gas_note: "health_check".to_string() });
+
+        // A failed job keeps its last-run timestamp and is retried next poke
+        assert_eq!(f.hub.get_job(f.rebalance_job).unwrap().last_run, 0);
+        assert!(f.hub.is_job_due(f.rebalance_job));
+
+        f.target.set_failing(JOB_REBALANCE, false);
+        f.env.advance_block_time(60);
+        assert_eq!(f.hub.poke(), 1);
+        assert_eq!(runs(&f), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_reschedule_and_remove_jobs() {
+        let mut f = setup();
+        f.env.set_caller(f.env.get_account(5));
+        f.hub.poke();
+
+        f.env.set_caller(f.admin);
+        f.hub.reschedule_job(f.rebalance_job, 2 * ONE_HOUR);
+        f.hub.remove_job(f.health_job);
+        assert_eq!(f.hub.get_job_ids(), vec![f.harvest_job, f.rebalance_job]);
+        assert!(f.hub.get_job(f.health_job).is_none());
+
+        f.env.advance_block_time(12 * ONE_HOUR);
+        f.hub.poke();
+        assert_eq!(runs(&f), (2, 2, 1), "Removed job no longer runs");
+
+        assert!(f.hub.try_remove_job(f.health_job).is_err(), "Unknown job");
+        assert!(f.hub.try_reschedule_job(99, ONE_HOUR).is_err(), "Unknown job");
+        assert!(f.hub.try_add_job(*f.target.address(), 9, ONE_HOUR).is_err(), "Unknown entrypoint");
+    }
+
+    #[test]
+    fn test_job_admin_requires_role() {
+        let mut f = setup();
+        let target = *f.target.address();
+        f.env.set_caller(f.env.get_account(5));
+
+        assert!(f.hub.try_add_job(target, JOB_HARVEST_ALL, ONE_HOUR).is_err());
+        assert!(f.hub.try_remove_job(f.harvest_job).is_err());
+        assert!(f.hub.try_reschedule_job(f.harvest_job, 0).is_err());
+    }
+
+    #[test]
+    fn test_router_jobs_require_keeper_role_on_target() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        // The admin stands in for the vault
+        let built = SystemBuilder::new(&env)
+            .with_keeper_hub()
+            .with_router()
+            .with_mock_strategy("dex", 1200)
+            .with_mock_strategy("lending", 1500)
+            .with_mock_strategy("crosschain", 1850)
+            .build();
+        let mut hub = built.keeper_hub.unwrap();
+        let mut router = built.router.unwrap();
+        let mut dex = built.mock_strategies.into_iter().next().unwrap();
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        router.allocate(cspr(1000));
+        dex.set_pending_yield(cspr(10));
+
+        hub.add_job(*router.address(), JOB_HARVEST_ALL, ONE_HOUR);
+
+        // Hub not yet a keeper on the router: job fails without reverting
+        assert_eq!(hub.poke(), 0);
+        assert!(!env.get_event::<JobExecuted>(hub.address(), -1).unwrap().success);
+        assert_u512_eq(dex.harvest(), cspr(10), "Yield untouched");
+
+        dex.set_pending_yield(cspr(10));
+        router.grant_role(KEEPER_ROLE, *hub.address());
+        assert_eq!(hub.poke(), 1);
+        assert_u512_eq(dex.harvest(), cspr(0), "Yield harvested by the router");
+    }
+
+    #[test]
+    fn test_failed_harvest_quarantines_without_aborting_the_poke() {
+        let env = odra_test::env();
+
+        let built = SystemBuilder::new(&env)
+            .with_keeper_hub()
+            .with_router()
+            .with_mock_strategy("dex", 1200)
+            .with_mock_strategy("lending", 1500)
+            .build();
+        let mut hub = built.keeper_hub.unwrap();
+        let mut router = built.router.unwrap();
+        let mut strategies = built.mock_strategies.into_iter();
+        let (mut dex, mut lending) = (strategies.next().unwrap(), strategies.next().unwrap());
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![("dex".to_string(), 50u8), ("lending".to_string(), 50u8)]);
+        router.allocate(cspr(1000));
+        router.grant_role(KEEPER_ROLE, *hub.address());
+        hub.add_job(*router.address(), JOB_HARVEST_ALL, ONE_HOUR);
+        hub.add_job(*router.address(), JOB_HEALTH_CHECK, ONE_HOUR);
+
+        // Lending books its yield instead of paying it out
+        dex.set_pending_yield(cspr(10));
+        lending.set_pending_yield(cspr(10));
+        lending.set_retain_yield(true);
+
+        assert_eq!(hub.poke(), 1, "Only the health check succeeded");
+        let harvest = env.get_event::<JobExecuted>(hub.address(), -2).unwrap();
+        assert!(!harvest.success, "The harvest job reports the failure");
+        assert!(env.get_event::<JobExecuted>(hub.address(), -1).unwrap().success, "The batch carried on");
+
+        assert!(router.is_quarantined("lending".to_string()), "Offender quarantined");
+        assert!(!router.is_quarantined("dex".to_string()));
+        assert_u512_eq(dex.preview_harvest(), U512::zero(), "Dex still harvested");
+        assert_u512_eq(lending.get_balance(), U512::zero(), "Lending unwound");
+    }
+}
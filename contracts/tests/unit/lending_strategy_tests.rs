@@ -9,31 +9,21 @@
 
 #[cfg(test)]
 mod lending_strategy_tests {
-    use crate::strategies::lending_strategy::*;
-    use crate::mocks::mock_lending::*;
-    use odra::host::{Deployer, HostRef, NoArgs};
-    use odra::types::{Address, U512};
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::HostRef;
+    use caspervault_contracts::strategies::LendingStrategyHostRef;
+    use crate::mocks::mock_lending::MockLendingHostRef;
+    use crate::helpers::SystemBuilder;
     
     /// Setup test environment
-    fn setup() -> (HostRef<LendingStrategyHostRef>, HostRef<MockLendingHostRef>, Address) {
-        let admin = odra::test_env::get_account(0);
-        let lst_cspr_address = odra::test_env::get_account(10);
-        
-        // Deploy mock lending with 8% base APY
-        let mut mock_lending = MockLendingHostRef::deploy(NoArgs);
-        mock_lending.init(800u16); // 8% base APY
-        
-        // Deploy lending strategy
-        let mut strategy = LendingStrategyHostRef::deploy(NoArgs);
-        strategy.init(
-            admin,
-            lst_cspr_address,
-            *mock_lending.address(),
-            U512::from(5_000_000_000u64), // 5M max capacity (largest for low-risk)
-            U512::from(100_000u64), // 100 min supply
-        );
-        
-        (strategy, mock_lending, admin)
+    fn setup() -> (LendingStrategyHostRef, MockLendingHostRef, Address) {
+        let env = odra_test::env();
+        
+        // Mock lending with 8% base APY and a strategy supplying to it
+        let contracts = SystemBuilder::new(&env).with_mock_lending(800).with_lending().build();
+        
+        (contracts.lending.unwrap(), contracts.mock_lending.unwrap(), contracts.admin)
     }
     
     #[test]
@@ -126,9 +116,9 @@ mod lending_strategy_tests {
         
         assert!(interest > U512::zero(), "Interest should be earned");
         
-        // Balance should have increased
+        // Interest is paid out with the harvest
         let balance_after = strategy.get_balance();
-        assert!(balance_after > balance_initial, "Balance should increase with interest");
+        assert_eq!(balance_after, balance_initial, "Harvested interest leaves the position");
         
         // Interest should be roughly 8% of principal (800 bps)
         let expected_interest = amount
@@ -242,7 +232,7 @@ mod lending_strategy_tests {
         strategy.with_tokens(admin).deploy(amount);
         
         // Emergency withdraw
-        let withdrawn = strategy.with_tokens(admin).emergency_withdraw();
+        let withdrawn = strategy.with_tokens(admin).emergency_withdraw(None);
         
         assert!(withdrawn > U512::zero(), "Should withdraw all funds");
         
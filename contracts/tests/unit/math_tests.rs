@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod math_tests {
+    use odra::casper_types::{U256, U512};
+    use caspervault_contracts::utils::{
+        accrue_apr, annualize_bps, bps_of, mul_div, mul_div_u256, net_apy_bps, ratio_bps, safe_u256_to_u512,
+        safe_u512_to_u256, safe_u512_to_u32, safe_u512_to_u64, Rounding, SECONDS_PER_YEAR,
+    };
+    use caspervault_contracts::MathError;
+    use crate::helpers::*;
+
+    fn u256_max_as_u512() -> U512 {
+        safe_u256_to_u512(U256::MAX)
+    }
+
+    #[test]
+    fn test_bps_of_basic() {
+        assert_u512_eq(bps_of(cspr(1000), 0), U512::zero(), "0 bps");
+        assert_u512_eq(bps_of(cspr(1000), 50), cspr(5), "0.5%");
+        assert_u512_eq(bps_of(cspr(1000), 10000), cspr(1000), "100%");
+        assert_u512_eq(bps_of(cspr(1000), 25000), cspr(2500), "250%");
+        assert_u512_eq(bps_of(U512::zero(), 10000), U512::zero(), "Zero amount");
+    }
+
+    #[test]
+    fn test_bps_of_rounds_down() {
+        assert_u512_eq(bps_of(U512::from(9999u64), 1), U512::zero(), "Below one unit");
+        assert_u512_eq(bps_of(U512::from(19999u64), 1), U512::one(), "Just under two units");
+        assert_u512_eq(bps_of(U512::from(12345u64), 3333), U512::from(4114u64), "12345 * 3333 / 10000");
+    }
+
+    #[test]
+    fn test_bps_of_near_max_is_exact() {
+        // `amount * bps` alone would overflow here
+        let max = U512::MAX;
+        assert_u512_eq(bps_of(max, 10000), max, "Full amount at 100%");
+        assert_u512_eq(bps_of(max, 5000), max / U512::from(2u64), "Half of max");
+        assert_u512_eq(
+            bps_of(max, 1),
+            max / U512::from(10000u64),
+            "One bps of max",
+        );
+    }
+
+    #[test]
+    fn test_bps_of_saturates_above_100_percent_at_max() {
+        assert_u512_eq(bps_of(U512::MAX, 20000), U512::MAX, "Saturates instead of wrapping");
+    }
+
+    #[test]
+    fn test_mul_div_rounding() {
+        let seven = U512::from(7u64);
+        let three = U512::from(3u64);
+        let two = U512::from(2u64);
+
+        assert_eq!(mul_div(seven, two, three, Rounding::Down), Ok(U512::from(4u64)));
+        assert_eq!(mul_div(seven, two, three, Rounding::Up), Ok(U512::from(5u64)));
+        // Exact division does not round up
+        assert_eq!(mul_div(U512::from(6u64), two, three, Rounding::Up), Ok(U512::from(4u64)));
+        assert_eq!(mul_div(U512::zero(), two, three, Rounding::Up), Ok(U512::zero()));
+    }
+
+    #[test]
+    fn test_mul_div_division_by_zero() {
+        assert_eq!(
+            mul_div(U512::one(), U512::one(), U512::zero(), Rounding::Down),
+            Err(MathError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn test_mul_div_near_max() {
+        let max = U512::MAX;
+
+        // Product overflows but the result fits
+        assert_eq!(mul_div(max, max, max, Rounding::Down), Ok(max));
+        assert_eq!(mul_div(max, U512::from(3u64), U512::from(4u64), Rounding::Down), Ok(max / U512::from(4u64) * U512::from(3u64) + U512::from(2u64)));
+        assert_eq!(mul_div(max, U512::one(), U512::from(2u64), Rounding::Up), Ok(max / U512::from(2u64) + U512::one()));
+
+        // Result itself doesn't fit
+        assert_eq!(mul_div(max, U512::from(2u64), U512::one(), Rounding::Down), Err(MathError::Overflow));
+        assert_eq!(mul_div(max, max, U512::from(2u64), Rounding::Down), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_mul_div_u256() {
+        assert_eq!(
+            mul_div_u256(U256::MAX, U256::MAX, U256::MAX, Rounding::Down),
+            Ok(U256::MAX)
+        );
+        assert_eq!(
+            mul_div_u256(U256::MAX, U256::from(2u64), U256::one(), Rounding::Down),
+            Err(MathError::ConversionOverflow)
+        );
+    }
+
+    #[test]
+    fn test_ratio_bps() {
+        assert_eq!(ratio_bps(cspr(25), cspr(100)), Ok(U512::from(2500u64)));
+        assert_eq!(ratio_bps(cspr(300), cspr(100)), Ok(U512::from(30000u64)));
+        assert_eq!(ratio_bps(U512::one(), U512::from(3u64)), Ok(U512::from(3333u64)));
+        assert_eq!(ratio_bps(U512::MAX, U512::MAX), Ok(U512::from(10000u64)));
+        assert_eq!(ratio_bps(cspr(1), U512::zero()), Err(MathError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_u512_to_u256_roundtrip() {
+        let values = [U256::zero(), U256::one(), U256::from(u64::MAX), U256::from(u128::MAX), U256::MAX];
+        for value in values {
+            assert_eq!(safe_u512_to_u256(safe_u256_to_u512(value)), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_u512_to_u256_rejects_out_of_range() {
+        // Truncating casts would have kept the low bits or clamped to zero
+        assert_eq!(safe_u512_to_u256(u256_max_as_u512() + U512::one()), Err(MathError::ConversionOverflow));
+        assert_eq!(safe_u512_to_u256(U512::MAX), Err(MathError::ConversionOverflow));
+    }
+
+    #[test]
+    fn test_u512_to_u64_and_u32() {
+        assert_eq!(safe_u512_to_u64(U512::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(safe_u512_to_u64(U512::from(u64::MAX) + U512::one()), Err(MathError::ConversionOverflow));
+        assert_eq!(safe_u512_to_u64(U512::MAX), Err(MathError::ConversionOverflow));
+
+        assert_eq!(safe_u512_to_u32(U512::from(u32::MAX)), Ok(u32::MAX));
+        assert_eq!(safe_u512_to_u32(U512::from(u32::MAX) + U512::one()), Err(MathError::ConversionOverflow));
+        assert_eq!(safe_u512_to_u32(U512::MAX), Err(MathError::ConversionOverflow));
+    }
+
+    #[test]
+    fn test_accrue_apr() {
+        // 10% over a full year
+        assert_eq!(accrue_apr(cspr(1000), 1000, SECONDS_PER_YEAR), Ok(cspr(100)));
+        // 12% over 30 days
+        assert_eq!(
+            accrue_apr(cspr(2000), 1200, 30 * 86400),
+            Ok(cspr(2000) * U512::from(1200u64) * U512::from(30u64) / U512::from(365u64 * 10000))
+        );
+        assert_eq!(accrue_apr(cspr(1000), 1000, 0), Ok(U512::zero()));
+        assert_eq!(accrue_apr(cspr(1000), 0, SECONDS_PER_YEAR), Ok(U512::zero()));
+    }
+
+    #[test]
+    fn test_accrue_apr_near_max() {
+        // A year at 100% returns the principal itself without overflowing
+        assert_eq!(accrue_apr(U512::MAX, 10000, SECONDS_PER_YEAR), Ok(U512::MAX));
+        assert_eq!(accrue_apr(U512::MAX, 20000, SECONDS_PER_YEAR), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_annualize_bps() {
+        // 100 on 1000 over a year is 10%
+        assert_eq!(annualize_bps(cspr(100), cspr(1000), SECONDS_PER_YEAR), Ok(U512::from(1000u64)));
+        // Half a year doubles the annualized rate
+        assert_eq!(annualize_bps(cspr(100), cspr(1000), SECONDS_PER_YEAR / 2), Ok(U512::from(2000u64)));
+        assert_eq!(annualize_bps(cspr(100), U512::zero(), SECONDS_PER_YEAR), Ok(U512::zero()));
+        assert_eq!(annualize_bps(cspr(100), cspr(1000), 0), Ok(U512::zero()));
+    }
+
+    #[test]
+    fn test_annualize_inverts_accrue() {
+        let principal = cspr(12_345);
+        let elapsed = 45 * 86400;
+        for apr_bps in [100u32, 800, 1200, 1850, 10000] {
+            let accrued = accrue_apr(principal, apr_bps, elapsed).unwrap();
+            let annualized = annualize_bps(accrued, principal, elapsed).unwrap();
+            // Rounding down on both legs can lose at most one bps
+            assert!(annualized <= U512::from(apr_bps) && annualized + U512::one() >= U512::from(apr_bps));
+        }
+    }
+
+    #[test]
+    fn test_annualize_bps_near_max() {
+        assert_eq!(annualize_bps(U512::MAX, U512::MAX, SECONDS_PER_YEAR), Ok(U512::from(10000u64)));
+        assert_eq!(annualize_bps(U512::MAX, U512::one(), 1), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn test_net_apy_bps() {
+        // 10% gross, 10% performance fee, 2% management fee
+        assert_eq!(net_apy_bps(U256::from(1000u64), 1000, 200), Ok(U256::from(700u64)));
+        assert_eq!(net_apy_bps(U256::from(1000u64), 0, 0), Ok(U256::from(1000u64)));
+        assert_eq!(net_apy_bps(U256::from(1533u64), 1000, 0), Ok(U256::from(1379u64)), "Rounded down");
+        assert_eq!(net_apy_bps(U256::from(1000u64), 10000, 0), Ok(U256::zero()));
+        assert_eq!(net_apy_bps(U256::from(150u64), 1000, 200), Ok(U256::zero()), "Fees exceed the yield");
+    }
+}
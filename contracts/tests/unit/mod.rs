@@ -3,3 +3,18 @@ pub mod staking_unit_tests;
 pub mod strategy_unit_tests;
 pub mod aggregator_unit_tests;
 pub mod security_unit_tests;
+pub mod strategy_router_tests;
+pub mod vault_operations_tests;
+pub mod crosschain_strategy_tests;
+pub mod keeper_hub_tests;
+pub mod approvals_tests;
+pub mod role_permissions_tests;
+pub mod strategy_access_tests;
+pub mod vault_yield_tests;
+pub mod config_events_tests;
+pub mod strategy_apy_tests;
+pub mod math_tests;
+pub mod record_version_tests;
+pub mod view_purity_tests;
+pub mod withdrawal_cost_tests;
+pub mod build_info_tests;
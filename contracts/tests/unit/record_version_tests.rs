@@ -0,0 +1,303 @@
+#[cfg(test)]
+mod record_version_tests {
+    use odra::casper_types::bytesrepr::{self, FromBytes, ToBytes};
+    use odra::casper_types::U512;
+    use odra::host::HostEnv;
+    use caspervault_contracts::core::vault_manager::{
+        upgrade_request, upgrade_user_deposit, RequestStatus, UserDeposit, WithdrawalRequest,
+        USER_DEPOSIT_VERSION, WITHDRAWAL_REQUEST_VERSION,
+    };
+    use caspervault_contracts::utils::{check_record_version, read_field_since};
+    use crate::helpers::*;
+
+    /// `UserDeposit` as it would look once a field is appended in version 3
+    #[derive(Debug, PartialEq, Eq)]
+    struct UserDepositV3 {
+        version: u8,
+        total_deposited: U512,
+        total_shares: U512,
+        cost_basis: U512,
+        last_deposit_time: u64,
+        daily_deposited: U512,
+        deposit_day: u64,
+        first_deposit_time: u64,
+        total_withdrawn: U512,
+        fee_snapshot_bps: u32,
+    }
+
+    impl ToBytes for UserDepositV3 {
+        fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+            let mut buffer = bytesrepr::allocate_buffer(self)?;
+            buffer.extend(self.version.to_bytes()?);
+            buffer.extend(self.total_deposited.to_bytes()?);
+            buffer.extend(self.total_shares.to_bytes()?);
+            buffer.extend(self.cost_basis.to_bytes()?);
+            buffer.extend(self.last_deposit_time.to_bytes()?);
+            buffer.extend(self.daily_deposited.to_bytes()?);
+            buffer.extend(self.deposit_day.to_bytes()?);
+            buffer.extend(self.first_deposit_time.to_bytes()?);
+            buffer.extend(self.total_withdrawn.to_bytes()?);
+            buffer.extend(self.fee_snapshot_bps.to_bytes()?);
+            Ok(buffer)
+        }
+
+        fn serialized_length(&self) -> usize {
+            self.version.serialized_length()
+                + self.total_deposited.serialized_length()
+                + self.total_shares.serialized_length()
+                + self.cost_basis.serialized_length()
+                + self.last_deposit_time.serialized_length()
+                + self.daily_deposited.serialized_length()
+                + self.deposit_day.serialized_length()
+                + self.first_deposit_time.serialized_length()
+                + self.total_withdrawn.serialized_length()
+                + self.fee_snapshot_bps.serialized_length()
+        }
+    }
+
+    impl FromBytes for UserDepositV3 {
+        fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+            let (version, rest) = u8::from_bytes(bytes)?;
+            check_record_version(version, 3)?;
+            let (total_deposited, rest) = U512::from_bytes(rest)?;
+            let (total_shares, rest) = U512::from_bytes(rest)?;
+            let (cost_basis, rest) = U512::from_bytes(rest)?;
+            let (last_deposit_time, rest) = u64::from_bytes(rest)?;
+            let (daily_deposited, rest) = U512::from_bytes(rest)?;
+            let (deposit_day, rest) = u64::from_bytes(rest)?;
+            let (first_deposit_time, rest) = read_field_since(rest, version, 2)?;
+            let (total_withdrawn, rest) = read_field_since(rest, version, 2)?;
+            let (fee_snapshot_bps, rest) = read_field_since(rest, version, 3)?;
+            Ok((
+                UserDepositV3 {
+                    version,
+                    total_deposited,
+                    total_shares,
+                    cost_basis,
+                    last_deposit_time,
+                    daily_deposited,
+                    deposit_day,
+                    first_deposit_time,
+                    total_withdrawn,
+                    fee_snapshot_bps,
+                },
+                rest,
+            ))
+        }
+    }
+
+    fn sample_deposit() -> UserDeposit {
+        UserDeposit {
+            version: USER_DEPOSIT_VERSION,
+            total_deposited: cspr(1_500),
+            total_shares: cspr(1_450),
+            cost_basis: cspr(1_200),
+            last_deposit_time: 1_700_000_000,
+            daily_deposited: cspr(300),
+            deposit_day: 19_675,
+            first_deposit_time: 1_690_000_000,
+            total_withdrawn: cspr(400),
+        }
+    }
+
+    fn sample_request(env: &HostEnv) -> WithdrawalRequest {
+        WithdrawalRequest {
+            version: WITHDRAWAL_REQUEST_VERSION,
+            user: env.get_account(1),
+            shares: cspr(250),
+            assets_value: cspr(260),
+            request_time: 1_000,
+            unlock_time: 605_800,
+            status: RequestStatus::Pending,
+            cost_basis: Some(cspr(240)),
+            fee_bps: 1_000,
+            auto_complete: true,
+            loss_epoch: 2,
+            in_transit: cspr(260),
+            exit_fee: cspr(2),
+        }
+    }
+
+    #[test]
+    fn test_user_deposit_roundtrip() {
+        let deposit = sample_deposit();
+        let bytes = bytesrepr::serialize(&deposit).unwrap();
+
+        assert_eq!(bytes.len(), deposit.serialized_length());
+        assert_eq!(bytes[0], USER_DEPOSIT_VERSION, "Version leads the encoding");
+        assert_eq!(bytesrepr::deserialize::<UserDeposit>(bytes).unwrap(), deposit);
+    }
+
+    #[test]
+    fn test_withdrawal_request_roundtrip() {
+        let env = odra_test::env();
+        let request = sample_request(&env);
+        let bytes = bytesrepr::serialize(&request).unwrap();
+
+        assert_eq!(bytes.len(), request.serialized_length());
+        assert_eq!(bytes[0], WITHDRAWAL_REQUEST_VERSION, "Version leads the encoding");
+        assert_eq!(bytesrepr::deserialize::<WithdrawalRequest>(bytes).unwrap(), request);
+    }
+
+    #[test]
+    fn test_v2_blob_decodes_after_field_added() {
+        let v2 = sample_deposit();
+        let bytes = bytesrepr::serialize(&v2).unwrap();
+
+        let upgraded = bytesrepr::deserialize::<UserDepositV3>(bytes).unwrap();
+
+        assert_eq!(upgraded.version, 2, "Decoded with the version it was written at");
+        assert_eq!(upgraded.total_deposited, v2.total_deposited);
+        assert_eq!(upgraded.total_shares, v2.total_shares);
+        assert_eq!(upgraded.cost_basis, v2.cost_basis);
+        assert_eq!(upgraded.last_deposit_time, v2.last_deposit_time);
+        assert_eq!(upgraded.daily_deposited, v2.daily_deposited);
+        assert_eq!(upgraded.deposit_day, v2.deposit_day);
+        assert_eq!(upgraded.first_deposit_time, v2.first_deposit_time);
+        assert_eq!(upgraded.total_withdrawn, v2.total_withdrawn);
+        assert_eq!(upgraded.fee_snapshot_bps, 0, "Missing field reads as its default");
+    }
+
+    #[test]
+    fn test_v1_user_deposit_dates_position_from_last_deposit() {
+        let deposit = sample_deposit();
+        // Version 1 ended at `deposit_day`
+        let mut bytes = Vec::new();
+        bytes.extend(1u8.to_bytes().unwrap());
+        bytes.extend(deposit.total_deposited.to_bytes().unwrap());
+        bytes.extend(deposit.total_shares.to_bytes().unwrap());
+        bytes.extend(deposit.cost_basis.to_bytes().unwrap());
+        bytes.extend(deposit.last_deposit_time.to_bytes().unwrap());
+        bytes.extend(deposit.daily_deposited.to_bytes().unwrap());
+        bytes.extend(deposit.deposit_day.to_bytes().unwrap());
+
+        let decoded = bytesrepr::deserialize::<UserDeposit>(bytes).unwrap();
+        assert_eq!((decoded.first_deposit_time, decoded.total_withdrawn), (0, U512::zero()));
+
+        let upgraded = upgrade_user_deposit(decoded);
+        assert_eq!(upgraded.version, USER_DEPOSIT_VERSION);
+        assert_eq!(upgraded.first_deposit_time, deposit.last_deposit_time, "Latest deposit stands in for the first");
+        assert_eq!(upgraded.total_withdrawn, U512::zero());
+        assert_eq!(upgrade_user_deposit(UserDeposit::default()).first_deposit_time, 0, "No position, no start");
+    }
+
+    #[test]
+    fn test_v1_withdrawal_request_decodes_with_packed_defaults() {
+        let env = odra_test::env();
+        let request = sample_request(&env);
+        // Version 1 ended at a `completed` flag, written as a bool
+        let mut bytes = Vec::new();
+        bytes.extend(1u8.to_bytes().unwrap());
+        bytes.extend(request.user.to_bytes().unwrap());
+        bytes.extend(request.shares.to_bytes().unwrap());
+        bytes.extend(request.assets_value.to_bytes().unwrap());
+        bytes.extend(request.request_time.to_bytes().unwrap());
+        bytes.extend(request.unlock_time.to_bytes().unwrap());
+        bytes.extend(true.to_bytes().unwrap());
+
+        let decoded = upgrade_request(bytesrepr::deserialize::<WithdrawalRequest>(bytes).unwrap());
+
+        assert_eq!(decoded.status, RequestStatus::Completed, "The completed flag is the status byte");
+        assert_eq!(decoded.version, WITHDRAWAL_REQUEST_VERSION);
+        assert_eq!((decoded.user, decoded.shares, decoded.assets_value), (request.user, request.shares, request.assets_value));
+        assert_eq!((decoded.request_time, decoded.unlock_time), (request.request_time, request.unlock_time));
+        assert_eq!(decoded.cost_basis, None);
+        assert_eq!((decoded.fee_bps, decoded.auto_complete, decoded.loss_epoch), (0, false, 0));
+        assert_eq!(decoded.in_transit, U512::zero());
+        assert_eq!(decoded.exit_fee, U512::zero());
+    }
+
+    #[test]
+    fn test_request_status_byte_rejects_unknown_values() {
+        let env = odra_test::env();
+        let request = sample_request(&env);
+        let status_at = request.serialized_length()
+            - request.exit_fee.serialized_length()
+            - request.in_transit.serialized_length()
+            - request.loss_epoch.serialized_length()
+            - request.auto_complete.serialized_length()
+            - request.fee_bps.serialized_length()
+            - request.cost_basis.serialized_length()
+            - 1;
+
+        for status in [RequestStatus::Pending, RequestStatus::Completed, RequestStatus::Cancelled, RequestStatus::Expired] {
+            assert_eq!(RequestStatus::from_u8(status.to_u8()), Some(status));
+            let mut bytes = bytesrepr::serialize(&request).unwrap();
+            bytes[status_at] = status.to_u8();
+            assert_eq!(bytesrepr::deserialize::<WithdrawalRequest>(bytes).unwrap().status, status);
+        }
+
+        let mut bytes = bytesrepr::serialize(&request).unwrap();
+        bytes[status_at] = 4;
+        assert!(bytesrepr::deserialize::<WithdrawalRequest>(bytes).is_err());
+    }
+
+    #[test]
+    fn test_v3_blob_keeps_appended_field() {
+        let v2 = sample_deposit();
+        let v3 = UserDepositV3 {
+            version: 3,
+            total_deposited: v2.total_deposited,
+            total_shares: v2.total_shares,
+            cost_basis: v2.cost_basis,
+            last_deposit_time: v2.last_deposit_time,
+            daily_deposited: v2.daily_deposited,
+            deposit_day: v2.deposit_day,
+            first_deposit_time: v2.first_deposit_time,
+            total_withdrawn: v2.total_withdrawn,
+            fee_snapshot_bps: 1_000,
+        };
+        let bytes = bytesrepr::serialize(&v3).unwrap();
+
+        assert_eq!(bytesrepr::deserialize::<UserDepositV3>(bytes.clone()).unwrap(), v3);
+        // This build only knows version 2 and must not misread the newer layout
+        assert!(bytesrepr::deserialize::<UserDeposit>(bytes).is_err());
+    }
+
+    #[test]
+    fn test_unknown_version_rejected() {
+        let mut bytes = bytesrepr::serialize(&sample_deposit()).unwrap();
+        bytes[0] = 0;
+        assert!(bytesrepr::deserialize::<UserDeposit>(bytes).is_err());
+
+        let env = odra_test::env();
+        let mut bytes = bytesrepr::serialize(&sample_request(&env)).unwrap();
+        bytes[0] = WITHDRAWAL_REQUEST_VERSION + 1;
+        assert!(bytesrepr::deserialize::<WithdrawalRequest>(bytes).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_stamps_current_version() {
+        assert_eq!(UserDeposit::default().version, USER_DEPOSIT_VERSION);
+
+        let deposit = upgrade_user_deposit(UserDeposit { version: 1, ..sample_deposit() });
+        assert_eq!(deposit, sample_deposit());
+
+        let env = odra_test::env();
+        let request = upgrade_request(WithdrawalRequest { version: 1, ..sample_request(&env) });
+        assert_eq!(request, sample_request(&env));
+    }
+
+    #[test]
+    fn test_stored_records_carry_current_version() {
+        let env = odra_test::env();
+        let user = env.get_account(1);
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        vault.set_min_holding_period(0);
+
+        env.set_caller(user);
+        vault.with_tokens(cspr(100)).deposit();
+        let request_id = vault.request_withdrawal(cspr(40));
+
+        let deposit = vault.get_user_deposit(user).unwrap();
+        assert_eq!(deposit.version, USER_DEPOSIT_VERSION);
+        assert_eq!(deposit.total_deposited, cspr(100));
+        assert_eq!(deposit.first_deposit_time, env.get_block_time());
+
+        let request = vault.get_withdrawal_request(request_id).unwrap();
+        assert_eq!(request.version, WITHDRAWAL_REQUEST_VERSION);
+        assert_eq!(request.user, user);
+        assert_eq!(request.status, RequestStatus::Pending);
+        assert_eq!(request.cost_basis, Some(cspr(100)));
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod role_permissions_tests {
+    use odra::prelude::*;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::Role;
+    use caspervault_contracts::core::{
+        LiquidStakingHostRef, StrategyRouterHostRef, VaultManagerHostRef, YieldAggregatorHostRef,
+    };
+    use crate::helpers::*;
+
+    const TWELVE_HOURS: u64 = 12 * 60 * 60;
+
+    struct RolesFixture {
+        env: HostEnv,
+        admin: Address,
+        keeper: Address,
+        vault: VaultManagerHostRef,
+        staking: LiquidStakingHostRef,
+        router: StrategyRouterHostRef,
+        aggregator: YieldAggregatorHostRef,
+    }
+
+    /// Core contracts with account 5 granted only the keeper role on each
+    fn setup() -> RolesFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let keeper = env.get_account(5);
+        let keeper_role = Role::Keeper.to_u8();
+
+        let built = SystemBuilder::new(&env).with_staking().with_vault().with_router().with_aggregator().build();
+        let mut vault = built.vault.unwrap();
+        let mut staking = built.staking.unwrap();
+        let mut router = built.router.unwrap();
+        let mut aggregator = built.aggregator.unwrap();
+
+        vault.grant_role(keeper_role, keeper);
+        staking.grant_role(keeper_role, keeper);
+        router.grant_role(keeper_role, keeper);
+        aggregator.grant_role(keeper_role, keeper);
+
+        env.advance_block_time(TWELVE_HOURS);
+
+        RolesFixture { env, admin, keeper, vault, staking, router, aggregator }
+    }
+
+    #[test]
+    fn test_keeper_runs_full_maintenance_suite() {
+        let mut f = setup();
+        f.env.set_caller(f.keeper);
+
+        f.vault.collect_management_fees();
+        f.staking.compound_rewards();
+        f.router.harvest_all();
+        f.router.rebalance();
+        f.router.health_check();
+        f.router.sync();
+        f.aggregator.aggregate_yields();
+    }
+
+    #[test]
+    fn test_keeper_cannot_move_funds_or_change_allocations() {
+        let mut f = setup();
+        f.env.set_caller(f.keeper);
+
+        assert!(
+            f.router.try_set_target_allocations(vec![(String::from("dex"), 40)]).is_err(),
+            "Allocations are admin only"
+        );
+        assert!(f.vault.try_report_profit(cspr(1)).is_err(), "Profit reporting needs operator");
+        assert!(f.aggregator.try_compound(cspr(100)).is_err(), "Stated yield amounts need operator");
+        assert!(f.router.try_allocate(cspr(1)).is_err(), "Only the vault and aggregator allocate");
+        assert!(f.router.try_withdraw(cspr(1)).is_err(), "Only the vault withdraws");
+        assert!(
+            f.vault.try_grant_role(Role::Keeper.to_u8(), f.env.get_account(4)).is_err(),
+            "Keepers cannot grant roles"
+        );
+    }
+
+    #[test]
+    fn test_non_keeper_rejected_from_maintenance() {
+        let mut f = setup();
+        f.env.set_caller(f.env.get_account(4));
+
+        assert!(f.vault.try_collect_management_fees().is_err());
+        assert!(f.staking.try_compound_rewards().is_err());
+        assert!(f.router.try_harvest_all().is_err());
+        assert!(f.aggregator.try_compound(cspr(100)).is_err());
+    }
+
+    #[test]
+    fn test_role_members_track_grants_and_revokes() {
+        let mut f = setup();
+        let keeper_role = Role::Keeper.to_u8();
+        let second_keeper = f.env.get_account(4);
+
+        assert_eq!(f.vault.get_role_members(Role::Admin.to_u8()), vec![f.admin]);
+        assert_eq!(f.vault.get_role_members(keeper_role), vec![f.keeper]);
+
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(keeper_role, second_keeper);
+        assert_eq!(f.vault.get_role_members(keeper_role), vec![f.keeper, second_keeper]);
+
+        f.vault.revoke_role(keeper_role, f.keeper);
+        assert_eq!(f.vault.get_role_members(keeper_role), vec![second_keeper]);
+
+        f.env.set_caller(f.keeper);
+        assert!(f.vault.try_collect_management_fees().is_err(), "Revoked keeper rejected");
+    }
+}
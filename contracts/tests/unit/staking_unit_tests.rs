@@ -104,36 +104,43 @@ mod liquid_staking_tests {
     #[test]
     fn test_compound_rewards_calculation() {
         let env = odra_test::env();
-        let mut validator = MockValidatorHostRef::deploy(&env, 98u8, 5u8);
+        let mut validator = SystemBuilder::new(&env).with_mock_validator(1000, 5).build().mock_validators.remove(0);
+        let delegator = env.get_account(1);
         
         let delegation = cspr(10000);
-        validator.delegate(delegation);
-        validator.add_rewards(cspr(500));
+        validator.delegate(delegator, delegation);
+        validator.distribute_epoch_rewards();
         
-        let rewards = validator.claim_rewards();
+        let rewards = validator.claim_rewards(delegator);
         
-        assert!(rewards > U512::zero(), "Rewards claimed");
+        assert_u512_eq(rewards, cspr(950), "10% of the stake less 5% commission");
+        assert_u512_eq(validator.get_pending_rewards(delegator), U512::zero(), "Claimed once");
     }
 
     #[test]
     fn test_rewards_claim_from_multiple_validators() {
         let env = odra_test::env();
         
-        let mut val1 = MockValidatorHostRef::deploy(&env, 98u8, 5u8);
-        let mut val2 = MockValidatorHostRef::deploy(&env, 97u8, 6u8);
+        let mut validators = SystemBuilder::new(&env)
+            .with_mock_validator(500, 5)
+            .with_mock_validator(500, 6)
+            .build()
+            .mock_validators;
+        let (mut val2, mut val1) = (validators.pop().unwrap(), validators.pop().unwrap());
+        let delegator = env.get_account(1);
         
-        val1.delegate(cspr(5000));
-        val2.delegate(cspr(5000));
+        val1.delegate(delegator, cspr(5000));
+        val2.delegate(delegator, cspr(5000));
         
-        val1.add_rewards(cspr(250));
-        val2.add_rewards(cspr(250));
+        val1.distribute_epoch_rewards();
+        val2.distribute_epoch_rewards();
         
-        let rewards1 = val1.claim_rewards();
-        let rewards2 = val2.claim_rewards();
+        let rewards1 = val1.claim_rewards(delegator);
+        let rewards2 = val2.claim_rewards(delegator);
         
         let total_rewards = rewards1 + rewards2;
         
-        assert!(total_rewards > U512::zero(), "Total rewards > 0");
+        assert_u512_eq(total_rewards, milli_cspr(472_500), "250 each less 5% and 6% commission");
     }
 
     #[test]
@@ -178,22 +185,25 @@ mod liquid_staking_tests {
     #[test]
     fn test_remove_underperforming_validator() {
         let env = odra_test::env();
-        let mut validator = MockValidatorHostRef::deploy(&env, 90u8, 5u8);
+        let mut validator = SystemBuilder::new(&env).with_mock_validator(1000, 5).build().mock_validators.remove(0);
+        validator.delegate(env.get_account(1), cspr(1000));
         
-        let uptime = validator.get_uptime();
+        validator.simulate_slashing(10);
         
-        assert!(uptime < 95, "Validator underperforming");
+        assert_u512_eq(validator.get_total_delegated(), cspr(900), "Stake cut by 10%");
+        assert!(!validator.is_active(), "Slashed validator takes no new stake");
     }
 
     #[test]
     fn test_validator_active_status() {
         let env = odra_test::env();
-        let mut validator = MockValidatorHostRef::deploy(&env, 98u8, 5u8);
+        let mut validator = SystemBuilder::new(&env).with_mock_validator(1000, 5).build().mock_validators.remove(0);
         
         assert!(validator.is_active(), "Validator active");
         
-        validator.set_active(false);
+        validator.simulate_slashing(0);
         assert!(!validator.is_active(), "Validator inactive");
+        assert!(validator.try_delegate(env.get_account(1), cspr(100)).is_err(), "Delegation refused");
     }
 
     #[test]
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod strategy_access_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::strategies::{
+        CrossChainStrategyHostRef, DEXStrategyHostRef, LendingStrategyHostRef,
+    };
+    use caspervault_contracts::types::StrategyError;
+    use crate::helpers::*;
+
+    const ADMIN_ROLE: u8 = 0;
+    const KEEPER_ROLE: u8 = 3;
+    const ORACLE_ROLE: u8 = 4;
+    const ONE_DAY: u64 = 86400;
+
+    struct StrategiesFixture {
+        env: HostEnv,
+        admin: Address,
+        outsider: Address,
+        dex: DEXStrategyHostRef,
+        lending: LendingStrategyHostRef,
+        crosschain: CrossChainStrategyHostRef,
+    }
+
+    /// The three strategies, each holding a position, no router registered
+    fn setup() -> StrategiesFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let outsider = env.get_account(5);
+
+        env.set_caller(admin);
+        let strategies = SystemBuilder::new(&env).with_dex().with_lending().with_crosschain().build();
+        let (mut dex, mut lending, mut crosschain) =
+            (strategies.dex.unwrap(), strategies.lending.unwrap(), strategies.crosschain.unwrap());
+        crosschain.grant_role(ORACLE_ROLE, admin);
+        crosschain.report_chain_apy(0, 1800, env.get_block_time());
+
+        dex.deploy(cspr(2000));
+        lending.deploy(cspr(2000));
+        crosschain.deploy(cspr(2000));
+
+        StrategiesFixture { env, admin, outsider, dex, lending, crosschain }
+    }
+
+    #[test]
+    fn test_direct_calls_rejected_on_every_strategy() {
+        let mut f = setup();
+        f.env.advance_block_time(ONE_DAY);
+        f.env.set_caller(f.outsider);
+        let unauthorized = Err(StrategyError::Unauthorized.into());
+
+        assert_eq!(f.dex.try_deploy(cspr(1000)), unauthorized);
+        assert_eq!(f.dex.try_withdraw(cspr(1000)), unauthorized);
+        assert_eq!(f.dex.try_harvest(), unauthorized);
+
+        assert_eq!(f.lending.try_deploy(cspr(1000)), unauthorized);
+        assert_eq!(f.lending.try_withdraw(cspr(1000)), unauthorized);
+        assert_eq!(f.lending.try_harvest(), unauthorized);
+
+        assert_eq!(f.crosschain.try_deploy(cspr(1000)), unauthorized);
+        assert_eq!(f.crosschain.try_withdraw(cspr(1000)), unauthorized);
+        assert_eq!(f.crosschain.try_harvest(), unauthorized);
+
+        assert!(f.dex.try_set_router(f.outsider).is_err(), "Router is set by admin only");
+        assert_u512_eq(f.dex.get_balance(), cspr(2000), "Position untouched");
+    }
+
+    #[test]
+    fn test_keeper_may_harvest_but_not_move_funds() {
+        let mut f = setup();
+        let keeper = f.env.get_account(1);
+        f.dex.grant_role(KEEPER_ROLE, keeper);
+        f.lending.grant_role(KEEPER_ROLE, keeper);
+        f.crosschain.grant_role(KEEPER_ROLE, keeper);
+
+        f.env.advance_block_time(ONE_DAY);
+        f.env.set_caller(keeper);
+        assert!(f.dex.harvest() > U512::zero(), "DEX harvest");
+        assert!(f.lending.harvest() > U512::zero(), "Lending harvest");
+        assert!(f.crosschain.harvest() > U512::zero(), "Cross-chain harvest");
+
+        assert!(f.dex.try_withdraw(cspr(1000)).is_err(), "Keeper can't withdraw");
+        assert!(f.lending.try_deploy(cspr(1000)).is_err(), "Keeper can't deploy");
+
+        // Emergency withdrawal stays admin-only
+        assert!(f.crosschain.try_emergency_withdraw(None).is_err());
+        f.env.set_caller(f.admin);
+        f.lending.emergency_withdraw(None);
+        assert_u512_eq(f.lending.get_balance(), U512::zero(), "Admin emergency withdrawal");
+    }
+
+    #[test]
+    fn test_router_path_works_end_to_end() {
+        let mut f = setup();
+        let keeper = f.env.get_account(1);
+        let mut router = SystemBuilder::new(&f.env).with_router().build().router.unwrap();
+        let router_address = *router.address();
+
+        f.dex.grant_role(ADMIN_ROLE, router_address);
+        f.lending.grant_role(ADMIN_ROLE, router_address);
+        f.crosschain.grant_role(ADMIN_ROLE, router_address);
+        router.add_strategy("dex".to_string(), *f.dex.address());
+        router.add_strategy("lending".to_string(), *f.lending.address());
+        router.add_strategy("crosschain".to_string(), *f.crosschain.address());
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        router.grant_role(KEEPER_ROLE, keeper);
+
+        assert_eq!(f.dex.get_router(), Some(router_address), "Registration recorded the router");
+        assert_eq!(f.lending.get_router(), Some(router_address));
+        assert_eq!(f.crosschain.get_router(), Some(router_address));
+
+        assert_u512_eq(router.allocate(cspr(10000)), U512::zero(), "Fully allocated");
+        assert_u512_eq(f.dex.get_balance(), cspr(6000), "DEX received its share");
+        assert_u512_eq(f.lending.get_balance(), cspr(5000), "Lending received its share");
+
+        let withdrawn = router.withdraw(cspr(5000));
+        assert!(withdrawn > cspr(4900), "Router withdrawal goes through");
+        assert!(f.dex.get_balance() < cspr(6000), "DEX paid out");
+        assert!(f.lending.get_balance() < cspr(5000), "Lending paid out");
+
+        f.env.advance_block_time(ONE_DAY);
+        f.env.set_caller(keeper);
+        assert!(router.try_harvest_all().is_ok(), "Router harvest goes through");
+    }
+
+    #[test]
+    fn test_registration_needs_admin_on_the_strategy() {
+        let mut f = setup();
+        let mut router = SystemBuilder::new(&f.env).with_router().build().router.unwrap();
+
+        assert!(
+            router.try_add_strategy("dex".to_string(), *f.dex.address()).is_err(),
+            "Router can't register itself without the admin role",
+        );
+        assert_eq!(f.dex.get_router(), None);
+
+        // An admin can still point the strategy at the router directly
+        f.dex.set_router(*router.address());
+        assert_eq!(f.dex.get_router(), Some(*router.address()));
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod strategy_apy_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::HostEnv;
+    use caspervault_contracts::strategies::DEXStrategyHostRef;
+    use caspervault_contracts::VaultError;
+    use crate::helpers::*;
+
+    const ONE_DAY: u64 = 86400;
+    const ONE_HOUR: u64 = 3600;
+
+    fn deploy_dex(env: &HostEnv) -> DEXStrategyHostRef {
+        SystemBuilder::new(env).with_dex().build().dex.unwrap()
+    }
+
+    /// DEX position that has earned and harvested 30 days of fees
+    fn setup_harvested_dex() -> (HostEnv, DEXStrategyHostRef) {
+        let env = odra_test::env();
+        let mut dex = deploy_dex(&env);
+
+        env.advance_block_time(ONE_DAY);
+        dex.deploy(cspr(2000));
+        env.advance_block_time(30 * ONE_DAY);
+        dex.harvest();
+
+        (env, dex)
+    }
+
+    /// Simulated DEX yield: 12% a year on `amount` over `seconds`
+    fn dex_yield(amount: U512, seconds: u64) -> U512 {
+        amount * U512::from(1200u64) * U512::from(seconds) / U512::from(31_536_000u64) / U512::from(10_000u64)
+    }
+
+    #[test]
+    fn test_young_position_reports_target_apy() {
+        let env = odra_test::env();
+        let mut dex = deploy_dex(&env);
+
+        env.advance_block_time(ONE_DAY);
+        dex.deploy(cspr(2000));
+        env.advance_block_time(30);
+        assert_eq!(dex.get_apy(), U256::from(1500u64), "Target APY inside the window");
+    }
+
+    #[test]
+    fn test_top_up_keeps_measured_apy() {
+        let (env, mut dex) = setup_harvested_dex();
+
+        // Restarting the clock here would annualize 30 days of fees over seconds
+        dex.deploy(cspr(2000));
+        env.advance_block_time(30);
+        let apy = dex.get_apy();
+        assert!(apy > U256::from(1190u64) && apy <= U256::from(1200u64), "Measured APY survives a top-up: {}", apy);
+    }
+
+    #[test]
+    fn test_harvest_accrues_each_tranche_for_its_own_time() {
+        let env = odra_test::env();
+        let mut dex = deploy_dex(&env);
+
+        env.advance_block_time(ONE_DAY);
+        dex.deploy(cspr(1000));
+        env.advance_block_time(30 * ONE_DAY);
+        dex.deploy(cspr(9000));
+
+        // 30 days on the first 1,000 and nothing yet on the other 9,000
+        let harvested = dex.harvest();
+        assert_u512_eq(harvested, dex_yield(cspr(1000), 30 * ONE_DAY), "Yield on the first tranche only");
+
+        // The anchor moves to the harvest, so the next period isn't paid twice
+        env.advance_block_time(ONE_DAY);
+        let harvested = dex.harvest();
+        assert_u512_eq(harvested, dex_yield(cspr(10_000), ONE_DAY), "One day on the full position");
+    }
+
+    #[test]
+    fn test_lending_interest_accrues_per_tranche() {
+        let env = odra_test::env();
+        let mut lending = SystemBuilder::new(&env).with_lending().build().lending.unwrap();
+
+        env.advance_block_time(ONE_DAY);
+        lending.deploy(cspr(1000));
+        env.advance_block_time(30 * ONE_DAY);
+        lending.deploy(cspr(9000));
+
+        // 8% on 1,000 for 30 days
+        let expected = cspr(1000) * U512::from(800u64) * U512::from(30 * ONE_DAY)
+            / U512::from(31_536_000u64) / U512::from(10_000u64);
+        assert_u512_eq(lending.harvest(), expected, "Interest on the first tranche only");
+    }
+
+    #[test]
+    fn test_measured_apy_is_capped() {
+        let (_env, mut dex) = setup_harvested_dex();
+        assert_eq!(dex.get_apy(), U256::from(1199u64), "Measured APY, ~12%");
+
+        dex.set_apy_bounds(U256::from(1000u64), ONE_HOUR);
+        assert_eq!(dex.get_apy(), U256::from(1000u64), "Clamped to the ceiling");
+
+        dex.set_apy_bounds(U256::from(10_000u64), 60 * ONE_DAY);
+        assert_eq!(dex.get_apy(), U256::from(1500u64), "Longer window reports the target again");
+
+        assert_eq!(
+            dex.try_set_apy_bounds(U256::zero(), ONE_HOUR),
+            Err(VaultError::InvalidRequest.into())
+        );
+    }
+
+    #[test]
+    fn test_lending_apy_capped() {
+        let env = odra_test::env();
+        let mut lending = SystemBuilder::new(&env).with_lending().build().lending.unwrap();
+
+        assert_eq!(lending.get_apy(), U256::from(800u64), "Below the default ceiling");
+        lending.set_max_reported_apy(U256::from(500u64));
+        assert_eq!(lending.get_apy(), U256::from(500u64), "Cached APY clamped");
+
+        env.set_caller(env.get_account(5));
+        assert!(lending.try_set_max_reported_apy(U256::from(900u64)).is_err(), "Admin only");
+    }
+
+    #[test]
+    fn test_lending_apy_refresh_is_explicit() {
+        let env = odra_test::env();
+        let mut lending = SystemBuilder::new(&env).with_lending().build().lending.unwrap();
+
+        // Reads serve the cached estimate and never update it
+        assert_eq!(lending.get_apy(), U256::from(800u64));
+        assert_eq!(lending.get_apy(), U256::from(800u64));
+
+        // 2% base + 10% at 75% utilization
+        assert_eq!(lending.refresh_apy(), U256::from(950u64));
+        assert_eq!(lending.get_apy(), U256::from(950u64));
+
+        env.set_caller(env.get_account(5));
+        assert!(lending.try_refresh_apy().is_err(), "Harvesters only");
+    }
+}
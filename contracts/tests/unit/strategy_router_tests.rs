@@ -0,0 +1,1456 @@
+#[cfg(test)]
+mod strategy_router_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+    use caspervault_contracts::core::{
+        ApyReadingRejected, DeploymentQueueDrained, LiquidityTier, NativeConversion, NativeFloatShort,
+        RewardsEscrowed, RewardsLiquidated, RewardsSwapped, StrategyDrained, StrategyRouterHostRef,
+        StrategyWithdrawal, WithdrawalPolicy, ExposureCapHit, ExposureCapProposed, MAX_STRATEGIES,
+        ALLOCATION_ACTION_ALLOCATE, ALLOCATION_ACTION_REBALANCE, ALLOCATION_ACTION_WITHDRAW, MAX_ALLOCATION_HISTORY,
+    };
+    use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+    use caspervault_contracts::utils::TIMELOCK_DURATION;
+    use caspervault_contracts::{
+        MockBridgeHostRef, MockDEXHostRef, MockNativeStrategyHostRef, MockRewardTokenHostRef, MockStrategyHostRef,
+        StrategyError, VaultError,
+    };
+    use crate::helpers::*;
+
+    const KEEPER_ROLE: u8 = 3;
+    const ORACLE_ROLE: u8 = 4;
+
+    struct RouterFixture {
+        env: HostEnv,
+        admin: Address,
+        keeper: Address,
+        router: StrategyRouterHostRef,
+        dex: MockStrategyHostRef,
+        lending: MockStrategyHostRef,
+        crosschain: MockStrategyHostRef,
+    }
+
+    /// Router with dex/lending/crosschain mocks at 40/30/30 and 10k allocated,
+    /// no idle buffer
+    fn setup() -> RouterFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let keeper = env.get_account(1);
+
+        // Without a vault the admin stands in for it, so tests can allocate
+        // and withdraw as admin
+        let mut router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        let dex = SystemBuilder::mock_strategy(&env, 1200);
+        let lending = SystemBuilder::mock_strategy(&env, 1500);
+        let crosschain = SystemBuilder::mock_strategy(&env, 1850);
+
+        router.add_strategy("dex".to_string(), *dex.address());
+        router.add_strategy("lending".to_string(), *lending.address());
+        router.add_strategy("crosschain".to_string(), *crosschain.address());
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        router.grant_role(KEEPER_ROLE, keeper);
+        router.allocate(cspr(10000));
+
+        RouterFixture { env, admin, keeper, router, dex, lending, crosschain }
+    }
+
+    #[test]
+    fn test_allocate_deploys_to_strategies() {
+        let f = setup();
+
+        assert_u512_eq(f.dex.get_balance(), cspr(4000), "DEX received 40%");
+        assert_u512_eq(f.lending.get_balance(), cspr(3000), "Lending received 30%");
+        assert_u512_eq(f.crosschain.get_balance(), cspr(3000), "Cross-chain received 30%");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10000), "Total allocated");
+    }
+
+    #[test]
+    fn test_health_check_escalation_ladder() {
+        let mut f = setup();
+        f.dex.set_healthy(false);
+
+        f.env.set_caller(f.keeper);
+
+        f.router.health_check();
+        assert_eq!(f.router.get_health_report("dex".to_string()).consecutive_failures, 1);
+        assert_eq!(f.router.get_target_allocation("dex".to_string()), 40);
+
+        f.router.health_check();
+        assert_eq!(f.router.get_health_report("dex".to_string()).consecutive_failures, 2);
+        assert_eq!(f.router.get_target_allocation("dex".to_string()), 40);
+
+        // Third consecutive failure zeroes the target but keeps funds in place
+        f.router.health_check();
+        let report = f.router.get_health_report("dex".to_string());
+        assert_eq!(report.consecutive_failures, 3);
+        assert!(!report.healthy);
+        assert!(!report.quarantined);
+        assert_eq!(f.router.get_target_allocation("dex".to_string()), 0);
+        assert_u512_eq(f.dex.get_balance(), cspr(4000), "Funds not pulled below hard threshold");
+
+        // Other strategies unaffected
+        assert_eq!(f.router.get_health_report("lending".to_string()).consecutive_failures, 0);
+        assert_eq!(f.router.get_target_allocation("lending".to_string()), 30);
+    }
+
+    #[test]
+    fn test_apy_outlier_does_not_move_smoothed_apy() {
+        let mut f = setup();
+        f.env.set_caller(f.keeper);
+
+        f.router.health_check();
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(1200u64), "First reading seeds");
+
+        // 400% is under the quarantine threshold but far off the average
+        f.dex.set_apy(U256::from(40_000u64));
+        f.router.health_check();
+        let event = f.env.get_event::<ApyReadingRejected>(f.router.address(), -1).unwrap();
+        assert_eq!((event.reading, event.smoothed_apy), (U256::from(40_000u64), U256::from(1200u64)));
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(1200u64), "Outlier dropped");
+        assert_eq!(f.router.get_health_report("dex".to_string()).apy, U256::from(40_000u64), "Raw reading kept");
+        assert!(!f.router.is_quarantined("dex".to_string()));
+
+        // An in-range reading moves the average by its 20% weight
+        f.dex.set_apy(U256::from(1800u64));
+        f.router.health_check();
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(1320u64));
+
+        // Blended APY uses the smoothed figures: 40% * 13.2% + 30% * 15% + 30% * 18.5%
+        assert_eq!(f.router.calculate_blended_apy(), U256::from(1533u64));
+
+        f.env.set_caller(f.admin);
+        assert_eq!(f.router.try_set_max_apy_deviation(1), Err(VaultError::InvalidRequest.into()));
+    }
+
+    #[test]
+    fn test_sustained_apy_change_moves_smoothed_apy() {
+        let mut f = setup();
+        f.env.set_caller(f.keeper);
+        f.router.health_check();
+
+        // The first 400% reading is dropped, the ones confirming it are not
+        f.dex.set_apy(U256::from(40_000u64));
+        f.router.health_check();
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(1200u64), "Lone reading dropped");
+        f.router.health_check();
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(8960u64), "Confirmed reading taken");
+        f.router.health_check();
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(15_168u64));
+        f.router.health_check();
+        assert_eq!(f.router.get_smoothed_apy("dex".to_string()), U256::from(20_134u64), "Converging on the new level");
+    }
+
+    #[test]
+    fn test_health_check_recovery_resets_failures() {
+        let mut f = setup();
+        f.env.set_caller(f.keeper);
+
+        f.dex.set_healthy(false);
+        f.router.health_check();
+        f.router.health_check();
+
+        f.dex.set_healthy(true);
+        f.router.health_check();
+
+        let report = f.router.get_health_report("dex".to_string());
+        assert_eq!(report.consecutive_failures, 0);
+        assert!(report.healthy);
+
+        // The ladder restarts from scratch
+        f.dex.set_healthy(false);
+        f.router.health_check();
+        assert_eq!(f.router.get_health_report("dex".to_string()).consecutive_failures, 1);
+        assert_eq!(f.router.get_target_allocation("dex".to_string()), 40);
+    }
+
+    #[test]
+    fn test_critical_drift_quarantines_immediately() {
+        let mut f = setup();
+
+        // 25% shortfall on the DEX position
+        f.dex.set_balance(cspr(3000));
+
+        f.env.set_caller(f.keeper);
+        f.router.health_check();
+
+        let report = f.router.get_health_report("dex".to_string());
+        assert!(report.quarantined);
+        assert_eq!(report.drift_bps, 2500);
+        assert_eq!(f.dex.get_emergency_withdrawals(), 1);
+        assert_u512_eq(f.dex.get_balance(), U512::zero(), "Funds pulled from strategy");
+        assert_u512_eq(f.router.get_current_allocation("dex".to_string()), U512::zero(), "Allocation cleared");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(6000), "Total excludes quarantined strategy");
+        assert_u512_eq(f.router.get_idle_balance(), cspr(3000), "Recovered funds held idle");
+
+        // Quarantined strategies receive nothing on later allocations
+        f.env.set_caller(f.admin);
+        f.router.allocate(cspr(1000));
+        assert_u512_eq(f.dex.get_balance(), U512::zero(), "No new deployment");
+    }
+
+    #[test]
+    fn test_apy_spike_quarantines_immediately() {
+        let mut f = setup();
+        f.crosschain.set_apy(U256::from(60000u64)); // 600%
+
+        f.env.set_caller(f.keeper);
+        f.router.health_check();
+
+        assert!(f.router.is_quarantined("crosschain".to_string()));
+        assert!(!f.router.is_quarantined("dex".to_string()));
+        assert_eq!(f.crosschain.get_emergency_withdrawals(), 1);
+    }
+
+    #[test]
+    fn test_drain_strategy_in_capped_steps() {
+        let mut f = setup();
+        // DEX can only unwind 25% of its position per transaction
+        f.dex.set_emergency_pull_limit(cspr(1000));
+
+        for remaining in [3000u64, 2000, 1000, 0] {
+            let accounted = f.router.get_idle_balance() + f.router.get_total_allocated();
+            f.env.set_caller(f.keeper);
+            let withdrawn = f.router.drain_strategy("dex".to_string(), cspr(1000));
+            assert_u512_eq(withdrawn, cspr(1000), "Capped drain step");
+            assert_u512_eq(
+                f.router.get_idle_balance() + f.router.get_total_allocated(),
+                accounted,
+                "Drained funds move from allocated to idle",
+            );
+
+            let event = f.env.get_event::<StrategyDrained>(f.router.address(), -1).unwrap();
+            assert_u512_eq(event.remaining_balance, cspr(remaining), "Reported remaining balance");
+            assert_u512_eq(event.remaining_allocation, cspr(remaining), "Reported remaining allocation");
+
+            // Draining strategies receive nothing on later allocations
+            f.env.set_caller(f.admin);
+            f.router.allocate(cspr(1000));
+            assert_u512_eq(f.dex.get_balance(), cspr(remaining), "No new deployment");
+        }
+
+        assert!(f.dex.is_emergency_mode());
+        assert_eq!(f.dex.get_emergency_withdrawals(), 4);
+        assert_u512_eq(f.router.get_current_allocation("dex".to_string()), U512::zero(), "Allocation cleared");
+    }
+
+    #[test]
+    fn test_drain_strategy_requires_maintainer() {
+        let mut f = setup();
+        let stranger = f.env.get_account(5);
+
+        f.env.set_caller(stranger);
+        assert!(f.router.try_drain_strategy("dex".to_string(), cspr(1000)).is_err());
+    }
+
+    #[test]
+    fn test_allocate_and_withdraw_restricted_to_vault_and_aggregator() {
+        let mut f = setup();
+        let aggregator = f.env.get_account(7);
+
+        for caller in [f.keeper, f.env.get_account(5)] {
+            f.env.set_caller(caller);
+            assert_eq!(f.router.try_allocate(cspr(100)), Err(VaultError::Unauthorized.into()));
+            assert_eq!(f.router.try_allocate_with_id(1, cspr(100)), Err(VaultError::Unauthorized.into()));
+            assert_eq!(f.router.try_withdraw(cspr(100)), Err(VaultError::Unauthorized.into()));
+            assert_eq!(f.router.try_withdraw_with_id(1, cspr(100)), Err(VaultError::Unauthorized.into()));
+        }
+
+        // The aggregator may compound into the strategies but not withdraw
+        f.env.set_caller(f.admin);
+        f.router.set_yield_aggregator(aggregator);
+        f.env.set_caller(aggregator);
+        assert_u512_eq(f.router.allocate(cspr(1000)), U512::zero(), "Compounded yield placed");
+        assert_eq!(f.router.try_withdraw(cspr(100)), Err(VaultError::Unauthorized.into()));
+
+        // The admin role alone is not enough once another vault is wired
+        f.env.set_caller(f.admin);
+        f.router.set_vault_address(f.env.get_account(8));
+        assert_eq!(f.router.try_allocate(cspr(100)), Err(VaultError::Unauthorized.into()));
+        assert_eq!(f.router.try_withdraw(cspr(100)), Err(VaultError::Unauthorized.into()));
+        assert_u512_eq(f.router.get_total_allocated(), cspr(11000), "Only the aggregator's allocation landed");
+    }
+
+    #[test]
+    fn test_health_check_requires_role() {
+        let mut f = setup();
+        let stranger = f.env.get_account(5);
+
+        f.env.set_caller(stranger);
+        assert!(f.router.try_health_check().is_err());
+        assert!(f.router.try_quarantine_strategy("dex".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_reinstate_strategy() {
+        let mut f = setup();
+        f.dex.set_balance(U512::zero());
+
+        // Only a quarantined strategy can be reinstated
+        let not_found = Err(StrategyError::StrategyNotFound.into());
+        assert_eq!(f.router.try_reinstate_strategy("dex".to_string()), not_found);
+        assert_eq!(f.router.try_reinstate_strategy("unknown".to_string()), not_found);
+
+        f.env.set_caller(f.keeper);
+        f.router.health_check();
+        assert!(f.router.is_quarantined("dex".to_string()));
+
+        f.env.set_caller(f.admin);
+        f.router.reinstate_strategy("dex".to_string());
+
+        let report = f.router.get_health_report("dex".to_string());
+        assert!(!report.quarantined);
+        assert_eq!(report.consecutive_failures, 0);
+        assert_eq!(f.router.get_target_allocation("dex".to_string()), 0);
+        assert_eq!(f.router.try_reinstate_strategy("dex".to_string()), not_found, "Already reinstated");
+    }
+
+    #[test]
+    fn test_allocate_spills_over_full_strategy() {
+        let mut f = setup();
+
+        // DEX at 95% of capacity
+        f.dex.set_max_capacity(cspr(4200));
+
+        f.env.set_caller(f.admin);
+        let unallocated = f.router.allocate(cspr(10000));
+
+        // DEX takes its remaining 200; the 3800 overflow splits 30:30
+        assert_u512_eq(unallocated, U512::zero(), "Everything placed");
+        assert_u512_eq(f.dex.get_balance(), cspr(4200), "DEX filled to capacity");
+        assert_u512_eq(f.lending.get_balance(), cspr(3000 + 3000 + 1900), "Lending got its share of overflow");
+        assert_u512_eq(f.crosschain.get_balance(), cspr(3000 + 3000 + 1900), "Cross-chain got its share of overflow");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(20000), "Total allocated");
+    }
+
+    #[test]
+    fn test_allocate_returns_unallocated_remainder() {
+        let mut f = setup();
+        f.dex.set_max_capacity(cspr(4200));
+        f.lending.set_max_capacity(cspr(3500));
+        f.crosschain.set_max_capacity(cspr(3500));
+
+        f.env.set_caller(f.admin);
+        let unallocated = f.router.allocate(cspr(10000));
+
+        assert_u512_eq(unallocated, cspr(8800), "Only 1200 of room available");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(11200), "Only placed funds recorded");
+        assert_u512_eq(f.dex.get_balance(), cspr(4200), "DEX full");
+        assert_u512_eq(f.lending.get_balance(), cspr(3500), "Lending full");
+        assert_u512_eq(f.crosschain.get_balance(), cspr(3500), "Cross-chain full");
+    }
+
+    #[test]
+    fn test_vault_keeps_unallocated_in_instant_pool() {
+        let mut f = setup();
+        f.dex.set_max_capacity(cspr(4100));
+        f.lending.set_max_capacity(cspr(3000));
+        f.crosschain.set_max_capacity(cspr(3000));
+
+        f.env.set_caller(f.admin);
+        let mut vault = SystemBuilder::new(&f.env).with_vault().build().vault.unwrap();
+        vault.set_strategy_router(*f.router.address());
+        f.router.set_vault_address(*vault.address());
+
+        let user = f.env.get_account(2);
+        f.env.set_caller(user);
+        vault.with_tokens(cspr(1000)).deposit();
+
+        // 50 fills the 5% pool target, 950 goes to the router which can only place 100
+        assert_u512_eq(f.dex.get_balance(), cspr(4100), "DEX took its remaining room");
+        assert_u512_eq(vault.get_instant_pool_balance(), cspr(900), "Pool target plus unallocated remainder");
+    }
+
+    #[test]
+    fn test_sync_books_small_gains() {
+        let mut f = setup();
+
+        // 0.1% gain
+        f.dex.set_balance(cspr(4010));
+
+        f.env.set_caller(f.keeper);
+        let booked = f.router.sync();
+
+        assert_u512_eq(booked, cspr(10), "Gain booked");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10010), "Total includes gain");
+        assert_u512_eq(f.router.get_current_allocation("dex".to_string()), cspr(4010), "DEX marked up");
+        assert_u512_eq(f.router.get_pending_gains(), U512::zero(), "Nothing parked");
+    }
+
+    #[test]
+    fn test_sync_circuit_breaker_parks_large_gain() {
+        let mut f = setup();
+
+        // 10% gain in one sync
+        f.dex.set_balance(cspr(5000));
+
+        f.env.set_caller(f.keeper);
+        assert_u512_eq(f.router.sync(), U512::zero(), "Nothing booked");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10000), "Total unchanged");
+        assert_u512_eq(f.router.get_pending_gains(), cspr(1000), "Gain parked");
+
+        // The parked gain is not counted again on the next sync
+        assert_u512_eq(f.router.sync(), U512::zero(), "Still nothing booked");
+        assert_u512_eq(f.router.get_pending_gains(), cspr(1000), "Not double counted");
+
+        // Losses are still booked immediately
+        f.lending.set_balance(cspr(2990));
+        f.router.sync();
+        assert_u512_eq(f.router.get_total_allocated(), cspr(9990), "Loss booked");
+
+        f.env.set_caller(f.keeper);
+        assert!(f.router.try_release_pending_gains(1).is_err(), "Admin only");
+    }
+
+    #[test]
+    fn test_drip_release_raises_total_gradually() {
+        let mut f = setup();
+        f.dex.set_balance(cspr(5000));
+
+        f.env.set_caller(f.keeper);
+        f.router.sync();
+
+        f.env.set_caller(f.admin);
+        assert_u512_eq(f.router.release_pending_gains(4), cspr(250), "First tranche on release");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10250), "Quarter released");
+
+        f.env.set_caller(f.keeper);
+        for expected in [10500u64, 10750, 11000] {
+            f.router.sync();
+            assert_u512_eq(f.router.get_total_allocated(), cspr(expected), "Next tranche");
+        }
+
+        assert_u512_eq(f.router.get_pending_gains(), U512::zero(), "Fully released");
+        assert_u512_eq(f.router.get_current_allocation("dex".to_string()), cspr(5000), "DEX fully marked up");
+
+        f.router.sync();
+        assert_u512_eq(f.router.get_total_allocated(), cspr(11000), "No further changes");
+    }
+
+    #[test]
+    fn test_bridge_fees_booked_as_cost() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let keeper = env.get_account(1);
+
+        env.set_caller(admin);
+        let mut router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        let dex = SystemBuilder::mock_strategy(&env, 1200);
+        let lending = SystemBuilder::mock_strategy(&env, 1500);
+        let mut crosschain = SystemBuilder::new(&env).with_crosschain().build().crosschain.unwrap();
+        crosschain.grant_role(ORACLE_ROLE, admin);
+        crosschain.report_chain_apy(0, 1800, env.get_block_time());
+        // Registration records the router on the strategy
+        crosschain.grant_role(0, *router.address());
+
+        router.add_strategy("dex".to_string(), *dex.address());
+        router.add_strategy("lending".to_string(), *lending.address());
+        router.add_strategy("crosschain".to_string(), *crosschain.address());
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        router.grant_role(KEEPER_ROLE, keeper);
+
+        // 0.5% of the 3000 CSPR tranche is paid to the bridge
+        assert_u512_eq(router.allocate(cspr(10000)), U512::zero(), "Fee is not handed back as unallocated");
+        assert_u512_eq(router.get_bridge_fees("crosschain".to_string()), cspr(15), "Deploy fee booked");
+        assert_u512_eq(router.get_current_allocation("crosschain".to_string()), cspr(2985), "Allocation net of fee");
+        assert_u512_eq(router.get_total_allocated(), cspr(9985), "Total net of fee");
+
+        // Pulling everything back pays the return fee as well
+        let returned = router.withdraw(cspr(9985));
+        let return_fee = U512::from(14_925_000_000u64);
+        assert_u512_eq(returned, cspr(9985) - return_fee, "Return fee deducted");
+        assert_u512_eq(router.get_lifetime_bridge_fees(), cspr(15) + return_fee, "Both legs booked");
+        assert_u512_eq(
+            router.get_lifetime_bridge_fees(),
+            crosschain.get_lifetime_bridge_fees(),
+            "Router matches strategy",
+        );
+        assert_u512_eq(router.get_total_allocated(), U512::zero(), "Fee not left as phantom allocation");
+
+        // Recorded allocation matches the strategy, so sync sees no loss
+        env.set_caller(keeper);
+        router.sync();
+        assert_u512_eq(router.get_total_allocated(), U512::zero(), "No loss booked on sync");
+        assert_u512_eq(
+            router.get_current_allocation("crosschain".to_string()),
+            crosschain.get_balance(),
+            "Allocation matches balance",
+        );
+    }
+
+    #[test]
+    fn test_stuck_bridge_withdrawal_written_off_and_recovered() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let keeper = env.get_account(1);
+        let crosschain_name = "crosschain".to_string();
+
+        env.set_caller(admin);
+        let mut router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        let dex = SystemBuilder::mock_strategy(&env, 1200);
+        let lending = SystemBuilder::mock_strategy(&env, 1500);
+        let mut crosschain = SystemBuilder::new(&env).with_crosschain().build().crosschain.unwrap();
+        let mut bridge = MockBridgeHostRef::deploy(&env, NoArgs);
+        crosschain.grant_role(ORACLE_ROLE, admin);
+        crosschain.report_chain_apy(0, 1800, env.get_block_time());
+        crosschain.set_return_bridge(*bridge.address());
+        // The router needs admin on the strategy to register and quarantine it
+        crosschain.grant_role(0, *router.address());
+
+        router.add_strategy("dex".to_string(), *dex.address());
+        router.add_strategy("lending".to_string(), *lending.address());
+        router.add_strategy(crosschain_name.clone(), *crosschain.address());
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            (crosschain_name.clone(), 30u8),
+        ]);
+        router.grant_role(KEEPER_ROLE, keeper);
+        router.allocate(cspr(10000));
+
+        // The return leg fails: 2985 leaves the strategy, 0.5% goes to fees,
+        // the rest never arrives
+        bridge.set_failing(true);
+        let returned = router.withdraw(cspr(9985));
+        let return_fee = U512::from(14_925_000_000u64);
+        let stuck = cspr(2985) - return_fee;
+        assert_u512_eq(returned, cspr(7000), "Only the mock strategies paid out");
+        assert_u512_eq(bridge.get_stuck(), stuck, "Funds stuck in the bridge");
+
+        let (pending, deadline) = router.get_pending_withdrawal(crosschain_name.clone());
+        assert_u512_eq(pending, stuck, "Shortfall tracked as pending");
+        assert_eq!(deadline, env.get_block_time() + router.get_withdrawal_deadline(crosschain_name.clone()));
+        assert_u512_eq(router.get_current_allocation(crosschain_name.clone()), stuck, "Still allocated");
+
+        // Sync does not mistake funds in flight for a loss
+        env.set_caller(keeper);
+        router.sync();
+        assert_u512_eq(router.get_total_allocated(), stuck, "No loss booked while pending");
+
+        env.set_caller(admin);
+        assert!(router.try_write_off_pending(crosschain_name.clone(), stuck).is_err(), "Deadline not reached");
+
+        env.advance_block_time(3 * 24 * 60 * 60);
+        env.set_caller(keeper);
+        assert!(router.try_write_off_pending(crosschain_name.clone(), stuck).is_err(), "Admin only");
+        env.set_caller(admin);
+        assert!(
+            router.try_write_off_pending(crosschain_name.clone(), stuck + U512::one()).is_err(),
+            "Can't write off more than is pending",
+        );
+
+        router.write_off_pending(crosschain_name.clone(), stuck);
+        assert_u512_eq(router.get_total_allocated(), U512::zero(), "Loss realized");
+        assert_u512_eq(router.get_current_allocation(crosschain_name.clone()), U512::zero(), "Allocation cleared");
+        assert_u512_eq(router.get_pending_withdrawal(crosschain_name.clone()).0, U512::zero(), "Nothing pending");
+        assert_u512_eq(router.get_written_off(crosschain_name.clone()), stuck, "Write-off recorded");
+        assert!(router.is_quarantined(crosschain_name.clone()), "Strategy quarantined");
+
+        // The bridge delivers late: the funds come back as a one-off gain
+        assert_u512_eq(bridge.release_stuck(), stuck, "Bridge delivers");
+        assert!(
+            router.try_recover_written_off(crosschain_name.clone(), stuck + U512::one()).is_err(),
+            "Can't recover more than was written off",
+        );
+        router.recover_written_off(crosschain_name.clone(), stuck);
+        assert_u512_eq(router.get_written_off(crosschain_name.clone()), U512::zero(), "Write-off recovered");
+
+        env.set_caller(keeper);
+        assert_u512_eq(router.harvest_all(), stuck, "Recovery reported as harvested yield");
+        assert_u512_eq(router.harvest_all(), U512::zero(), "Reported once");
+    }
+
+    /// Setup with lending Instant, DEX Fast, cross-chain Slow, liquidity first
+    fn setup_liquidity_first() -> RouterFixture {
+        let mut f = setup();
+        f.router.set_liquidity_tier("lending".to_string(), LiquidityTier::Instant.to_u8());
+        f.router.set_liquidity_tier("dex".to_string(), LiquidityTier::Fast.to_u8());
+        f.router.set_liquidity_tier("crosschain".to_string(), LiquidityTier::Slow.to_u8());
+        f.router.set_withdrawal_policy(WithdrawalPolicy::LiquidityFirst.to_u8());
+        f
+    }
+
+    #[test]
+    fn test_liquidity_first_medium_withdrawal_only_touches_lending() {
+        let mut f = setup_liquidity_first();
+
+        let received = f.router.withdraw(cspr(2000));
+
+        assert_u512_eq(received, cspr(2000), "Fully covered");
+        assert_u512_eq(f.lending.get_balance(), cspr(1000), "Lending paid it all");
+        assert_u512_eq(f.dex.get_balance(), cspr(4000), "DEX untouched");
+        assert_u512_eq(f.crosschain.get_balance(), cspr(3000), "Cross-chain untouched");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(8000), "Allocation released");
+
+        let event = f.env.get_event::<StrategyWithdrawal>(f.router.address(), -1).unwrap();
+        assert_eq!(event.policy, WithdrawalPolicy::LiquidityFirst.to_u8());
+        assert_eq!(event.order, vec!["lending".to_string()]);
+    }
+
+    #[test]
+    fn test_liquidity_first_large_withdrawal_cascades_by_tier() {
+        let mut f = setup_liquidity_first();
+
+        let received = f.router.withdraw(cspr(9000));
+
+        assert_u512_eq(received, cspr(9000), "Fully covered");
+        assert_u512_eq(f.lending.get_balance(), U512::zero(), "Instant tier drained first");
+        assert_u512_eq(f.dex.get_balance(), U512::zero(), "Fast tier drained next");
+        assert_u512_eq(f.crosschain.get_balance(), cspr(1000), "Slow tier covers the rest");
+        assert_u512_eq(f.router.get_current_allocation("crosschain".to_string()), cspr(1000), "Recorded");
+
+        let event = f.env.get_event::<StrategyWithdrawal>(f.router.address(), -1).unwrap();
+        assert_eq!(
+            event.order,
+            vec!["lending".to_string(), "dex".to_string(), "crosschain".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_proportional_policy_is_default() {
+        let mut f = setup();
+        assert_eq!(f.router.get_withdrawal_policy(), WithdrawalPolicy::Proportional.to_u8());
+        assert_eq!(f.router.get_liquidity_tier("dex".to_string()), LiquidityTier::Slow.to_u8());
+
+        f.router.withdraw(cspr(1000));
+
+        assert_u512_eq(f.dex.get_balance(), cspr(3600), "DEX paid 40%");
+        assert_u512_eq(f.lending.get_balance(), cspr(2700), "Lending paid 30%");
+        assert_u512_eq(f.crosschain.get_balance(), cspr(2700), "Cross-chain paid 30%");
+
+        // Unknown policy and tier values are rejected
+        assert!(f.router.try_set_withdrawal_policy(3).is_err());
+        assert!(f.router.try_set_liquidity_tier("dex".to_string(), 3).is_err());
+        assert!(f.router.try_set_liquidity_tier("unknown".to_string(), 0).is_err());
+    }
+
+    #[test]
+    fn test_cheapest_first_books_exit_costs() {
+        let mut f = setup();
+        f.dex.set_withdrawal_cost(30, 30);
+        f.crosschain.set_withdrawal_cost(100, 100);
+        f.router.set_withdrawal_policy(WithdrawalPolicy::CheapestFirst.to_u8());
+
+        // Lending exits for free and covers it alone
+        assert_u512_eq(f.router.estimate_withdrawal_cost(cspr(2000)), U512::zero(), "Free source available");
+        assert_u512_eq(f.router.withdraw(cspr(2000)), cspr(2000), "Fully covered");
+        let event = f.env.get_event::<StrategyWithdrawal>(f.router.address(), -1).unwrap();
+        assert_eq!(event.policy, WithdrawalPolicy::CheapestFirst.to_u8());
+        assert_eq!(event.order, vec!["lending".to_string()]);
+        assert_eq!((event.estimated_cost, event.actual_cost), (U512::zero(), U512::zero()));
+
+        // Lending's last 1,000, then DEX at 0.3%, then cross-chain for DEX's fee
+        let expected_cost = cspr(3) + U512::from(30_000_000u64);
+        assert_u512_eq(f.router.estimate_withdrawal_cost(cspr(2000)), expected_cost, "Planned costs");
+        let received = f.router.withdraw(cspr(2000));
+        assert_u512_eq(received, cspr(2000) - U512::from(30_000_000u64), "Net of the last tranche's fee");
+
+        let event = f.env.get_event::<StrategyWithdrawal>(f.router.address(), -1).unwrap();
+        assert_eq!(event.order, vec!["lending".to_string(), "dex".to_string(), "crosschain".to_string()]);
+        assert_u512_eq(event.estimated_cost, expected_cost, "Estimated cost in event");
+        assert_u512_eq(event.actual_cost, expected_cost, "Actual cost in event");
+
+        assert_u512_eq(f.router.get_exit_costs("dex".to_string()), cspr(3), "DEX exit cost booked");
+        assert_u512_eq(f.router.get_lifetime_exit_costs(), expected_cost, "Lifetime exit costs");
+        assert_u512_eq(f.router.get_pending_withdrawal("dex".to_string()).0, U512::zero(), "Cost is not in flight");
+        assert_u512_eq(f.router.get_current_allocation("dex".to_string()), f.dex.get_balance(), "Allocation matches balance");
+    }
+
+    #[test]
+    fn test_exit_cost_overrun_stays_pending() {
+        let mut f = setup();
+        // Lending expects 0.1% but keeps 0.3%
+        f.lending.set_withdrawal_cost(10, 30);
+
+        // Proportional: lending pays 300
+        let received = f.router.withdraw(cspr(1000));
+        assert_u512_eq(received, cspr(1000) - U512::from(900_000_000u64), "Lending kept 0.9");
+
+        let event = f.env.get_event::<StrategyWithdrawal>(f.router.address(), -1).unwrap();
+        assert_u512_eq(event.estimated_cost, U512::from(300_000_000u64), "Estimate in event");
+        assert_u512_eq(event.actual_cost, U512::from(300_000_000u64), "Booked up to the estimate");
+        assert_u512_eq(f.router.get_exit_costs("lending".to_string()), U512::from(300_000_000u64), "Exit cost booked");
+
+        // The overrun is treated like any other shortfall until settled
+        assert_u512_eq(
+            f.router.get_pending_withdrawal("lending".to_string()).0,
+            U512::from(600_000_000u64),
+            "Overrun pending",
+        );
+    }
+
+    #[test]
+    fn test_cheapest_first_order_depends_on_size() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        env.set_caller(admin);
+        let mut router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        let mut dex = SystemBuilder::new(&env).with_dex().build().dex.unwrap();
+        dex.grant_role(0, *router.address());
+        let mut flat = SystemBuilder::mock_strategy(&env, 1500);
+        flat.set_withdrawal_cost(20, 20);
+
+        router.add_strategy("dex".to_string(), *dex.address());
+        router.add_strategy("flat".to_string(), *flat.address());
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![("dex".to_string(), 50u8), ("flat".to_string(), 50u8)]);
+        router.set_withdrawal_policy(WithdrawalPolicy::CheapestFirst.to_u8());
+        router.allocate(cspr(10000));
+
+        // Small exit: 15 bps of swap fee and little impact beats a flat 20 bps
+        let dex_cost = dex.estimate_withdrawal_cost(cspr(1000));
+        assert_u512_eq(router.estimate_withdrawal_cost(cspr(1000)), dex_cost, "Planned through DEX");
+        assert_u512_eq(router.withdraw(cspr(1000)), cspr(1000), "Simulated pool exits at par");
+        let event = env.get_event::<StrategyWithdrawal>(router.address(), -1).unwrap();
+        assert_eq!(event.order, vec!["dex".to_string()]);
+        assert_u512_eq(event.estimated_cost, dex_cost, "DEX estimate in event");
+        assert_u512_eq(event.actual_cost, U512::zero(), "Nothing kept back");
+
+        // Large exit: price impact pushes DEX past the flat fee
+        assert!(dex.estimate_withdrawal_cost(cspr(4000)) > cspr(8), "DEX above 20 bps at 4,000");
+        router.withdraw(cspr(4000));
+        let event = env.get_event::<StrategyWithdrawal>(router.address(), -1).unwrap();
+        assert_eq!(event.order, vec!["flat".to_string(), "dex".to_string()], "Flat first, DEX tops up its fee");
+        assert_u512_eq(router.get_exit_costs("flat".to_string()), cspr(8), "Flat fee booked");
+    }
+
+    #[test]
+    fn test_simulation_matches_rebalance() {
+        let mut f = setup();
+        f.lending.set_max_capacity(cspr(3500));
+        let targets = vec![
+            ("dex".to_string(), 30u8),
+            ("lending".to_string(), 40u8),
+            ("crosschain".to_string(), 30u8),
+        ];
+
+        // Lending can only take 500 more, so only 500 leaves DEX
+        let simulation = f.router.simulate_allocation(targets.clone());
+        assert_eq!(simulation.violations.len(), 1);
+        assert!(simulation.violations[0].contains("lending"), "Capacity violation reported");
+        assert_u512_eq(simulation.total_churn, cspr(1000), "500 out, 500 in");
+        assert_eq!(simulation.projected_apy, U256::from(1500u64), "35/35/30 blend");
+        assert_u512_eq(f.router.get_current_allocation("dex".to_string()), cspr(4000), "Simulation is read-only");
+
+        f.router.set_target_allocations(targets);
+        f.env.advance_block_time(12 * 60 * 60);
+        f.env.set_caller(f.keeper);
+        f.router.rebalance();
+
+        let balances = [f.dex.get_balance(), f.lending.get_balance(), f.crosschain.get_balance()];
+        for (strategy_move, balance) in simulation.moves.iter().zip(balances.iter()) {
+            assert_u512_eq(*balance, strategy_move.target, "Balance matches simulated target");
+            assert_u512_eq(
+                f.router.get_current_allocation(strategy_move.strategy_name.clone()),
+                strategy_move.target,
+                "Allocation matches simulated target",
+            );
+        }
+        assert_u512_eq(simulation.moves[0].withdraw, cspr(500), "DEX withdrawal");
+        assert_u512_eq(simulation.moves[1].deploy, cspr(500), "Lending deployment");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10000), "Nothing lost in the move");
+    }
+
+    #[test]
+    fn test_rebalance_keeps_what_a_strategy_refuses() {
+        let mut f = setup();
+        // Lending stops accepting deposits after the plan was made
+        f.lending.emergency_withdraw(Some(U512::zero()));
+        f.router.set_target_allocations(vec![
+            ("dex".to_string(), 30u8),
+            ("lending".to_string(), 40u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        f.env.advance_block_time(12 * 60 * 60);
+        f.env.set_caller(f.keeper);
+        f.router.rebalance();
+
+        assert_u512_eq(f.dex.get_balance(), cspr(3000), "1000 left DEX");
+        assert_u512_eq(f.lending.get_balance(), cspr(3000), "Lending took nothing");
+        assert_u512_eq(f.router.get_idle_balance(), cspr(1000), "Refused tranche held idle");
+        assert_u512_eq(
+            f.router.get_total_allocated() + f.router.get_idle_balance(),
+            cspr(10000),
+            "Nothing lost in the move",
+        );
+    }
+
+    #[test]
+    fn test_simulation_reports_violations_without_reverting() {
+        let f = setup();
+
+        let simulation = f.router.simulate_allocation(vec![
+            ("dex".to_string(), 60u8),
+            ("crosschain".to_string(), 45u8),
+            ("staking".to_string(), 15u8),
+        ]);
+
+        let violations = simulation.violations.join("; ");
+        assert!(violations.contains("unknown strategy: staking"), "Unknown name reported");
+        assert!(violations.contains("dex above max allocation"), "Per-strategy cap reported");
+        assert!(violations.contains("cross-chain above max allocation"), "Cross-chain cap reported");
+        assert!(violations.contains("sum to 105%"), "Total reported");
+
+        // Unlisted lending is planned to zero
+        assert_eq!(simulation.moves.len(), 3);
+        assert_u512_eq(simulation.moves[1].target, U512::zero(), "Lending emptied");
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10000), "No state change");
+    }
+
+    /// Reward token plus a fee-free pool selling it at 0.5 lstCSPR
+    fn deploy_reward_market(env: &HostEnv) -> (MockRewardTokenHostRef, MockDEXHostRef) {
+        let token = MockRewardTokenHostRef::deploy(env, NoArgs);
+        let mut pool = MockDEXHostRef::deploy(env, NoArgs);
+        pool.set_trading_fee(0);
+        pool.set_slippage(0);
+        pool.set_exchange_rate(U512::from(500_000_000u64));
+        (token, pool)
+    }
+
+    #[test]
+    fn test_reward_tokens_auto_swapped_on_harvest() {
+        let mut f = setup();
+        let (token, mut pool) = deploy_reward_market(&f.env);
+        let router_address = *f.router.address();
+
+        // Sell automatically as long as a token fetches at least 0.4 lstCSPR
+        f.router.set_reward_route(*token.address(), *pool.address(), true, U512::from(400_000_000u64));
+        f.dex.set_pending_yield(cspr(10));
+        f.dex.set_pending_rewards(*token.address(), cspr(100));
+
+        f.env.set_caller(f.keeper);
+        assert_u512_eq(f.router.harvest_all(), cspr(60), "10 lstCSPR yield + 100 tokens at 0.5");
+
+        let swapped = f.env.get_event::<RewardsSwapped>(&router_address, -4).unwrap();
+        assert_eq!(swapped.strategy_name, "dex");
+        assert_u512_eq(swapped.amount_out, cspr(50), "Swap proceeds");
+        assert_u512_eq(token.balance_of(*pool.address()), cspr(100), "Tokens sold into the pool");
+        assert_u512_eq(token.balance_of(router_address), U512::zero(), "Nothing left on the router");
+        assert_u512_eq(f.router.get_reward_escrow(*token.address()), U512::zero(), "Nothing escrowed");
+
+        // Below the floor the rewards are held back instead of dumped
+        f.env.set_caller(f.admin);
+        pool.set_exchange_rate(U512::from(300_000_000u64));
+        f.dex.set_pending_rewards(*token.address(), cspr(100));
+
+        f.env.set_caller(f.keeper);
+        assert_u512_eq(f.router.harvest_all(), U512::zero(), "Escrowed rewards count nothing");
+        assert_u512_eq(f.router.get_reward_escrow(*token.address()), cspr(100), "Held in escrow");
+        assert_u512_eq(token.balance_of(router_address), cspr(100), "Router holds the tokens");
+    }
+
+    #[test]
+    fn test_reward_escrow_then_liquidate() {
+        let mut f = setup();
+        let (token, pool) = deploy_reward_market(&f.env);
+        let token_address = *token.address();
+        let router_address = *f.router.address();
+
+        f.router.set_reward_route(token_address, *pool.address(), false, U512::zero());
+        f.dex.set_pending_yield(cspr(10));
+        f.dex.set_pending_rewards(token_address, cspr(100));
+
+        f.env.set_caller(f.keeper);
+        assert_u512_eq(f.router.harvest_all(), cspr(10), "Only lstCSPR yield is reported");
+        let escrowed = f.env.get_event::<RewardsEscrowed>(&router_address, -4).unwrap();
+        assert_u512_eq(escrowed.escrowed, cspr(100), "Escrow balance in the event");
+        assert!(f.router.try_liquidate_rewards(token_address, U512::zero()).is_err(), "Admin only");
+
+        // Slippage protection leaves the escrow intact
+        f.env.set_caller(f.admin);
+        assert_eq!(
+            f.router.try_liquidate_rewards(token_address, cspr(60)),
+            Err(VaultError::SlippageExceeded.into()),
+        );
+        assert_u512_eq(f.router.get_reward_escrow(token_address), cspr(100), "Escrow untouched");
+
+        assert_u512_eq(f.router.liquidate_rewards(token_address, cspr(50)), cspr(50), "100 tokens at 0.5");
+        let liquidated = f.env.get_event::<RewardsLiquidated>(&router_address, -1).unwrap();
+        assert_u512_eq(liquidated.amount_in, cspr(100), "Whole escrow sold");
+        assert_u512_eq(f.router.get_reward_escrow(token_address), U512::zero(), "Escrow emptied");
+        assert_u512_eq(f.router.get_reward_proceeds(), cspr(50), "Proceeds wait for the next harvest");
+        assert_eq!(
+            f.router.try_liquidate_rewards(token_address, U512::zero()),
+            Err(VaultError::ConditionsNotMet.into()),
+            "Nothing left to sell",
+        );
+
+        f.env.set_caller(f.keeper);
+        assert_u512_eq(f.router.harvest_all(), cspr(50), "Proceeds count toward compounding");
+        assert_u512_eq(f.router.get_reward_proceeds(), U512::zero(), "Reported once");
+    }
+
+    /// Router keeping the default 5% idle buffer, strategies at 40/30/25
+    fn setup_buffered() -> (HostEnv, StrategyRouterHostRef, [MockStrategyHostRef; 3]) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        env.set_caller(admin);
+        let mut router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        let strategies = [
+            SystemBuilder::mock_strategy(&env, 1200),
+            SystemBuilder::mock_strategy(&env, 1500),
+            SystemBuilder::mock_strategy(&env, 1850),
+        ];
+        for (name, strategy) in ["dex", "lending", "crosschain"].iter().zip(strategies.iter()) {
+            router.add_strategy(name.to_string(), *strategy.address());
+        }
+        router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 25u8),
+        ]);
+
+        (env, router, strategies)
+    }
+
+    #[test]
+    fn test_idle_buffer_held_and_drawn_first() {
+        let (_env, mut router, [dex, lending, crosschain]) = setup_buffered();
+        assert_eq!(router.get_idle_buffer_pct(), 5, "5% by default");
+
+        assert_u512_eq(router.allocate(cspr(10000)), U512::zero(), "Nothing handed back");
+        assert_u512_eq(router.get_total_allocated(), cspr(9500), "95% deployed");
+        assert_u512_eq(router.get_idle_balance(), cspr(500), "5% held in the router");
+
+        // Served from the buffer alone
+        assert_u512_eq(router.withdraw(cspr(300)), cspr(300), "Small withdrawal");
+        assert_u512_eq(router.get_idle_balance(), cspr(200), "Buffer drawn down");
+        assert_u512_eq(dex.get_balance(), cspr(4000), "DEX untouched");
+        assert_u512_eq(lending.get_balance(), cspr(3000), "Lending untouched");
+        assert_u512_eq(crosschain.get_balance(), cspr(2500), "Cross-chain untouched");
+
+        // Larger withdrawals empty the buffer before touching strategies
+        assert_u512_eq(router.withdraw(cspr(1150)), cspr(1150), "Buffer plus strategies");
+        assert_u512_eq(router.get_idle_balance(), U512::zero(), "Buffer used up");
+        assert_u512_eq(router.get_total_allocated(), cspr(8550), "950 from strategies");
+    }
+
+    #[test]
+    fn test_oversubscribed_deploy_queued_and_drained() {
+        let (env, mut router, [dex, lending, _]) = setup_buffered();
+        let keeper = env.get_account(1);
+        router.grant_role(KEEPER_ROLE, keeper);
+        router.set_max_single_deploy("dex".to_string(), cspr(40_000));
+
+        // DEX's 200k share goes out as five 40k tranches: one now, four queued
+        assert_u512_eq(router.allocate(cspr(500_000)), U512::zero(), "Nothing handed back");
+        assert_u512_eq(dex.get_balance(), cspr(40_000), "DEX capped");
+        assert_u512_eq(lending.get_balance(), cspr(150_000), "Uncapped strategy filled");
+        assert_eq!(router.get_queued_deployments(), vec![("dex".to_string(), cspr(160_000))]);
+
+        let accounted = |router: &StrategyRouterHostRef| {
+            router.get_total_allocated() + router.get_total_queued() + router.get_idle_balance()
+        };
+        assert_u512_eq(accounted(&router), cspr(500_000), "Allocated + queued + idle");
+
+        env.set_caller(keeper);
+        for drained in 1..=4u64 {
+            assert_u512_eq(router.process_deployment_queue(3), cspr(40_000), "One tranche per call");
+            assert_u512_eq(dex.get_balance(), cspr(40_000 * (drained + 1)), "DEX tranche deployed");
+            assert_u512_eq(accounted(&router), cspr(500_000), "Accounting holds while draining");
+            assert_eq!(
+                env.get_event::<DeploymentQueueDrained>(router.address(), -1).unwrap().remaining,
+                cspr(160_000 - 40_000 * drained),
+            );
+        }
+
+        assert!(router.get_queued_deployments().is_empty(), "Queue drained");
+        assert_u512_eq(router.process_deployment_queue(3), U512::zero(), "Nothing left");
+        assert_u512_eq(router.get_current_allocation("dex".to_string()), cspr(200_000), "DEX at target");
+
+        env.set_caller(env.get_account(5));
+        assert!(router.try_process_deployment_queue(3).is_err(), "Maintainers only");
+    }
+
+    #[test]
+    fn test_withdrawal_draws_on_queue_before_strategies() {
+        let (_env, mut router, [dex, _, _]) = setup_buffered();
+        router.set_max_single_deploy("dex".to_string(), cspr(1_000));
+        router.allocate(cspr(10_000));
+        assert_u512_eq(router.get_total_queued(), cspr(3_000), "4k share, 1k deployed");
+
+        // 500 idle, then 2,500 of the queue
+        assert_u512_eq(router.withdraw(cspr(3_000)), cspr(3_000), "Served without strategies");
+        assert_u512_eq(router.get_total_queued(), cspr(500), "Queue drawn down");
+        assert_u512_eq(dex.get_balance(), cspr(1_000), "DEX untouched");
+        assert_u512_eq(router.get_total_allocated(), cspr(6_500), "Allocations untouched");
+    }
+
+    #[test]
+    fn test_targets_must_leave_room_for_idle_buffer() {
+        let (_env, mut router, _) = setup_buffered();
+
+        assert_eq!(
+            router.try_set_target_allocations(vec![
+                ("dex".to_string(), 40u8),
+                ("lending".to_string(), 30u8),
+                ("crosschain".to_string(), 28u8),
+            ]),
+            Err(VaultError::InvalidRequest.into()),
+            "98% + 5% buffer",
+        );
+        assert_eq!(
+            router.try_set_idle_buffer_pct(10),
+            Err(VaultError::InvalidRequest.into()),
+            "95% of targets leave no room for 10%",
+        );
+
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![("crosschain".to_string(), 28u8)]);
+        assert_eq!(router.get_target_allocation("crosschain".to_string()), 28);
+    }
+
+    #[test]
+    fn test_allocation_views() {
+        let f = setup();
+
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10000), "Total allocated");
+        assert_eq!(f.router.get_allocation("dex".to_string()), (cspr(4000), 40));
+        assert_eq!(f.router.get_allocation("unknown".to_string()), (U512::zero(), 0), "Unknown reads as empty");
+        assert_eq!(
+            f.router.get_all_allocations(),
+            vec![
+                ("dex".to_string(), cspr(4000), 40u8),
+                ("lending".to_string(), cspr(3000), 30u8),
+                ("crosschain".to_string(), cspr(3000), 30u8),
+            ],
+        );
+        assert_eq!(f.router.get_strategy_address("lending".to_string()), Some(*f.lending.address()));
+        assert_eq!(f.router.get_strategy_address("unknown".to_string()), None);
+    }
+
+    /// dex/lending/crosschain amounts as a snapshot lists them
+    fn split(dex: u64, lending: u64, crosschain: u64) -> Vec<(String, U512)> {
+        vec![
+            ("dex".to_string(), cspr(dex)),
+            ("lending".to_string(), cspr(lending)),
+            ("crosschain".to_string(), cspr(crosschain)),
+        ]
+    }
+
+    #[test]
+    fn test_allocation_history_follows_each_transition() {
+        let mut f = setup();
+        let allocated_at = f.env.get_block_time();
+
+        f.env.advance_block_time(60 * 60);
+        f.router.allocate(cspr(5000));
+        let topped_up_at = f.env.get_block_time();
+
+        f.env.advance_block_time(60 * 60);
+        f.router.withdraw(cspr(3000));
+        let withdrawn_at = f.env.get_block_time();
+
+        f.router.set_target_allocations(vec![
+            ("dex".to_string(), 30u8),
+            ("lending".to_string(), 40u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        f.env.advance_block_time(12 * 60 * 60);
+        f.env.set_caller(f.keeper);
+        f.router.rebalance();
+
+        // Newest first: rebalance, proportional withdrawal, two allocations
+        let history = f.router.get_allocation_history(10);
+        let summary: Vec<(u64, u8, U512)> = history
+            .iter()
+            .map(|snapshot| (snapshot.id, snapshot.action, snapshot.total_allocated))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (4, ALLOCATION_ACTION_REBALANCE, cspr(12_000)),
+                (3, ALLOCATION_ACTION_WITHDRAW, cspr(12_000)),
+                (2, ALLOCATION_ACTION_ALLOCATE, cspr(15_000)),
+                (1, ALLOCATION_ACTION_ALLOCATE, cspr(10_000)),
+            ]
+        );
+        assert_eq!(history[0].allocations, split(3600, 4800, 3600), "30/40/30 after the rebalance");
+        assert_eq!(history[1].allocations, split(4800, 3600, 3600), "Each paid its share");
+        assert_eq!(history[2].allocations, split(6000, 4500, 4500), "5,000 more at 40/30/30");
+        assert_eq!(history[3].allocations, split(4000, 3000, 3000), "Initial allocation");
+        assert_eq!(history[1].timestamp, withdrawn_at);
+        assert_eq!(f.router.get_allocation_history(2).len(), 2, "Only the latest two");
+
+        // The state in force at a time is the last snapshot at or before it
+        assert_eq!(f.router.get_allocation_at(topped_up_at - 1).unwrap().id, 1);
+        assert_eq!(f.router.get_allocation_at(topped_up_at).unwrap().id, 2);
+        assert_eq!(f.router.get_allocation_at(withdrawn_at + 60).unwrap().id, 3);
+        assert_eq!(f.router.get_allocation_at(u64::MAX).unwrap().id, 4);
+        assert_eq!(f.router.get_allocation_at(allocated_at).unwrap().id, 1);
+    }
+
+    #[test]
+    fn test_allocation_history_evicts_and_merges_epochs() {
+        let mut f = setup();
+        assert_eq!(f.router.get_allocation_history_config(), (64, 0));
+        assert_eq!(f.router.try_set_allocation_history(0, 0), Err(VaultError::InvalidRequest.into()));
+        assert_eq!(
+            f.router.try_set_allocation_history(MAX_ALLOCATION_HISTORY + 1, 0),
+            Err(VaultError::InvalidRequest.into())
+        );
+        let allocated_at = f.env.get_block_time();
+
+        // Three slots: the fourth snapshot takes the first one's place
+        f.router.set_allocation_history(3, 0);
+        for _ in 0..3 {
+            f.env.advance_block_time(60 * 60);
+            f.router.allocate(cspr(1000));
+        }
+        assert!(f.router.get_allocation_snapshot(1).is_none(), "Evicted");
+        let ids: Vec<u64> = f.router.get_allocation_history(10).iter().map(|snapshot| snapshot.id).collect();
+        assert_eq!(ids, vec![4, 3, 2]);
+        assert!(f.router.get_allocation_at(allocated_at).is_none(), "Older than anything retained");
+
+        // With a daily epoch, actions within a day leave one snapshot holding the day's last state
+        let day = 24 * 60 * 60;
+        f.router.set_allocation_history(3, day);
+        let now = f.env.get_block_time();
+        f.env.advance_block_time((now / day + 1) * day - now);
+        f.router.allocate(cspr(1000));
+        f.env.advance_block_time(60 * 60);
+        f.router.withdraw(cspr(500));
+
+        let latest = &f.router.get_allocation_history(1)[0];
+        assert_eq!((latest.id, latest.action), (5, ALLOCATION_ACTION_WITHDRAW));
+        assert_u512_eq(latest.total_allocated, cspr(13_500), "After both actions");
+        assert_eq!(latest.timestamp, f.env.get_block_time());
+
+        f.env.advance_block_time(day);
+        f.router.allocate(cspr(500));
+        assert_eq!(f.router.get_allocation_history(1)[0].id, 6, "A new day starts a new snapshot");
+    }
+
+    #[test]
+    fn test_reconciliation_shows_drift_without_booking_it() {
+        let mut f = setup();
+        let reconciled = |f: &RouterFixture| f.router.get_reconciliation();
+        assert!(
+            reconciled(&f).iter().all(|(_, recorded, reported)| recorded == reported),
+            "No drift after allocation",
+        );
+
+        // Yield accrues in the strategy before any sync
+        f.dex.set_balance(cspr(4050));
+        f.crosschain.set_balance(cspr(2990));
+        assert_eq!(
+            reconciled(&f),
+            vec![
+                ("dex".to_string(), cspr(4000), cspr(4050)),
+                ("lending".to_string(), cspr(3000), cspr(3000)),
+                ("crosschain".to_string(), cspr(3000), cspr(2990)),
+            ],
+        );
+        assert_u512_eq(f.router.get_total_allocated(), cspr(10000), "View books nothing");
+    }
+
+    #[test]
+    fn test_strategy_registrations_capped() {
+        let env = odra_test::env();
+        let mut router = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        let strategy = SystemBuilder::mock_strategy(&env, 1000);
+
+        for i in 0..MAX_STRATEGIES {
+            router.add_strategy(format!("s{}", i), *strategy.address());
+        }
+        assert_eq!(
+            router.try_add_strategy("one_more".to_string(), *strategy.address()),
+            Err(VaultError::RateLimitExceeded.into()),
+        );
+        // Re-registering an existing name doesn't take a slot
+        router.add_strategy("s0".to_string(), *strategy.address());
+        assert_eq!(router.get_all_allocations().len(), MAX_STRATEGIES);
+    }
+
+    /// Router with one native strategy at 40%, no idle buffer, lstCSPR at
+    /// 1.1 CSPR, a 0.5% conversion fee and `float` CSPR in the native float
+    fn setup_native(float: u64) -> (HostEnv, StrategyRouterHostRef, MockNativeStrategyHostRef) {
+        let env = odra_test::env();
+
+        let built = SystemBuilder::new(&env).with_mock_liquid_staking().with_router().build();
+        let mut staking = built.mock_liquid_staking.unwrap();
+        staking.set_exchange_rate(U256::from(1_100_000_000u64));
+
+        let mut router = built.router.unwrap();
+        let native = SystemBuilder::mock_native_strategy(&env, 900);
+        router.set_liquid_staking(*staking.address());
+        router.add_strategy_with_options("native".to_string(), *native.address(), LiquidityTier::Slow.to_u8(), true);
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![("native".to_string(), 40u8)]);
+        router.set_native_conversion_fee_bps(50);
+        router.with_tokens(cspr(float)).fund_native_float();
+
+        (env, router, native)
+    }
+
+    #[test]
+    fn test_native_strategy_deploy_withdraw_cycle() {
+        let (env, mut router, native) = setup_native(2000);
+        assert!(router.is_native_strategy("native".to_string()));
+
+        // 400 lstCSPR tranche = 440 CSPR, less 0.5%: 437.8 CSPR deployed,
+        // recorded as 398 lstCSPR with 2 lstCSPR of conversion cost
+        assert_u512_eq(router.allocate(cspr(1000)), cspr(600), "Untargeted 60% returned");
+        assert_u512_eq(native.get_total_deployed(), U512::from(437_800_000_000u64), "CSPR received");
+        assert_u512_eq(router.get_current_allocation("native".to_string()), cspr(398), "lstCSPR-denominated");
+        assert_u512_eq(router.get_conversion_costs("native".to_string()), cspr(2), "Cost on the way in");
+        assert_eq!(router.get_native_float(), (U512::from(1_562_200_000_000u64), cspr(400)));
+
+        let event = env.get_event::<NativeConversion>(router.address(), -2).unwrap();
+        assert!(event.to_native);
+        assert_u512_eq(event.lst_amount, cspr(400), "lstCSPR swapped out");
+        assert_u512_eq(event.native_amount, U512::from(437_800_000_000u64), "CSPR deployed");
+
+        // The CSPR balance reads at its lstCSPR value
+        let (_, recorded, reported) = router.get_reconciliation()[0].clone();
+        assert_u512_eq(reported, recorded, "No drift at an unchanged rate");
+
+        // 437.8 CSPR back = 398 lstCSPR, less 0.5%
+        assert_u512_eq(router.withdraw(cspr(398)), U512::from(396_010_000_000u64), "lstCSPR returned");
+        assert_u512_eq(native.get_total_withdrawn(), U512::from(437_800_000_000u64), "CSPR pulled");
+        assert_u512_eq(router.get_current_allocation("native".to_string()), U512::zero(), "Fully released");
+        assert_u512_eq(router.get_total_allocated(), U512::zero(), "Nothing left allocated");
+        assert_u512_eq(
+            router.get_conversion_costs("native".to_string()),
+            U512::from(3_990_000_000u64),
+            "Both legs attributed to the strategy",
+        );
+        assert_u512_eq(router.get_lifetime_conversion_costs(), U512::from(3_990_000_000u64), "Lifetime costs");
+        assert_eq!(router.get_native_float(), (cspr(2000), U512::from(3_990_000_000u64)));
+
+        let event = env.get_event::<NativeConversion>(router.address(), -2).unwrap();
+        assert!(!event.to_native);
+        assert_u512_eq(event.cost, U512::from(1_990_000_000u64), "Cost on the way out");
+    }
+
+    #[test]
+    fn test_native_strategy_skipped_when_float_is_short() {
+        let (env, mut router, native) = setup_native(100);
+
+        assert_u512_eq(router.allocate(cspr(1000)), cspr(1000), "Tranche stays with the caller");
+        assert_u512_eq(native.get_balance(), U512::zero(), "Nothing deployed");
+        assert_u512_eq(router.get_current_allocation("native".to_string()), U512::zero(), "Nothing recorded");
+        assert_u512_eq(router.get_conversion_costs("native".to_string()), U512::zero(), "No cost booked");
+        assert_eq!(router.get_native_float(), (cspr(100), U512::zero()));
+
+        let event = env.get_event::<NativeFloatShort>(router.address(), -1).unwrap();
+        assert_u512_eq(event.amount, cspr(400), "Tranche");
+        assert_u512_eq(event.native_needed, U512::from(437_800_000_000u64), "CSPR needed");
+        assert_u512_eq(event.native_float, cspr(100), "Float available");
+
+        // Native strategies can't be registered without a LiquidStaking to price them
+        let mut bare = SystemBuilder::new(&env).with_router().build().router.unwrap();
+        assert_eq!(
+            bare.try_add_strategy_with_options("native".to_string(), *native.address(), LiquidityTier::Slow.to_u8(), true),
+            Err(VaultError::ConditionsNotMet.into())
+        );
+    }
+
+    #[test]
+    fn test_performance_ranking_over_a_quarter() {
+        let mut f = setup();
+        let week = 7 * 24 * 60 * 60;
+
+        // DEX earns 10 CSPR a week on 4000, lending 6 CSPR on 3000, cross-chain nothing
+        f.env.set_caller(f.keeper);
+        for _ in 0..13 {
+            f.env.advance_block_time(week);
+            f.dex.set_pending_yield(cspr(10));
+            f.lending.set_pending_yield(cspr(6));
+            f.router.harvest_all();
+        }
+
+        let dex = f.router.get_strategy_performance("dex".to_string());
+        assert_u512_eq(dex.cumulative_deployed, cspr(4000), "Deployed once");
+        assert_u512_eq(dex.cumulative_withdrawn, U512::zero(), "Nothing withdrawn");
+        assert_u512_eq(dex.cumulative_harvested, cspr(130), "13 weekly harvests");
+        assert_u512_eq(dex.avg_allocation, cspr(4000), "Allocation never moved");
+        assert_eq!(dex.days_active, 91);
+
+        // 130 / 4000 over 91 days and 78 / 3000 over 91 days, annualized
+        let dex_apy = U256::from(130u64 * 10_000 * 365 / (4000 * 91));
+        let lending_apy = U256::from(78u64 * 10_000 * 365 / (3000 * 91));
+        assert_eq!(dex.realized_apy, dex_apy);
+        assert_eq!(dex_apy, U256::from(1303u64));
+        assert_eq!(f.router.get_strategy_performance("lending".to_string()).realized_apy, lending_apy);
+
+        assert_eq!(
+            f.router.get_performance_ranking(),
+            vec![
+                ("dex".to_string(), dex_apy),
+                ("lending".to_string(), lending_apy),
+                ("crosschain".to_string(), U256::zero()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_performance_weights_allocation_by_time() {
+        let mut f = setup();
+        let day = 24 * 60 * 60;
+
+        // Half the position leaves after 30 days and stays out for 60
+        f.env.advance_block_time(30 * day);
+        f.router.withdraw(cspr(5000));
+        f.env.advance_block_time(60 * day);
+        f.dex.set_pending_yield(cspr(40));
+        f.router.harvest_all();
+
+        let dex = f.router.get_strategy_performance("dex".to_string());
+        assert_u512_eq(dex.cumulative_withdrawn, cspr(2000), "Proportional share withdrawn");
+        let avg = (cspr(4000) * U512::from(30u64) + cspr(2000) * U512::from(60u64)) / U512::from(90u64);
+        assert_u512_eq(dex.avg_allocation, avg, "Time-weighted average");
+        assert_eq!(dex.days_active, 90);
+
+        // Realized APY uses the average, not the current 2000
+        let expected = U256::from(40u64 * 10_000 * 365 * 3 / (8000 * 90));
+        assert_eq!(dex.realized_apy, expected);
+
+        // A strategy that never held funds has nothing to report
+        let idle = SystemBuilder::mock_strategy(&f.env, 500);
+        f.router.add_strategy("idle".to_string(), *idle.address());
+        let performance = f.router.get_strategy_performance("idle".to_string());
+        assert_eq!((performance.activated_at, performance.realized_apy), (None, U256::zero()));
+    }
+
+    /// Whole system with dex/lending/crosschain mocks at 40/30/30 behind
+    /// the vault, no router idle buffer and dex capped at 20% of TVL
+    fn setup_exposure_caps() -> (HostEnv, DeployedSystem, MockStrategyHostRef) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        let dex = SystemBuilder::mock_strategy(&env, 1200);
+        let lending = SystemBuilder::mock_strategy(&env, 1500);
+        let crosschain = SystemBuilder::mock_strategy(&env, 1850);
+
+        system.router.add_strategy("dex".to_string(), *dex.address());
+        system.router.add_strategy("lending".to_string(), *lending.address());
+        system.router.add_strategy("crosschain".to_string(), *crosschain.address());
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+
+        // Keep share prices flat so TVL moves 1:1 with deposits and exits
+        system.vault.set_management_fee(0);
+        system.vault.set_min_holding_period(0);
+
+        system.router.propose_strategy_tvl_cap("dex".to_string(), 2000);
+        let proposed = env.get_event::<ExposureCapProposed>(system.router.address(), -1).unwrap();
+        assert_eq!(proposed, ExposureCapProposed {
+            strategy_name: Some("dex".to_string()),
+            max_tvl_bps: 2000,
+            eta: env.get_block_time() + TIMELOCK_DURATION,
+            timestamp: env.get_block_time(),
+        });
+        env.advance_block_time(TIMELOCK_DURATION);
+        system.router.apply_strategy_tvl_cap("dex".to_string());
+
+        (env, system, dex)
+    }
+
+    #[test]
+    fn test_exposure_caps_apply_after_the_delay() {
+        let (env, mut system, _) = setup_exposure_caps();
+        assert_eq!(system.router.get_strategy_tvl_cap("dex".to_string()), 2000);
+        assert_eq!(system.router.get_vault_address(), Some(*system.vault.address()), "Wired by deploy_system");
+
+        assert_eq!(
+            system.router.try_propose_crosschain_tvl_cap(10_001),
+            Err(VaultError::InvalidRequest.into())
+        );
+        assert_eq!(
+            system.router.try_propose_strategy_tvl_cap("unknown".to_string(), 1000),
+            Err(VaultError::InvalidRequest.into())
+        );
+        assert_eq!(system.router.try_apply_crosschain_tvl_cap(), Err(VaultError::InvalidRequest.into()));
+
+        system.router.propose_crosschain_tvl_cap(3000);
+        let (cap, eta) = system.router.get_pending_crosschain_tvl_cap().unwrap();
+        assert_eq!((cap, eta), (3000, env.get_block_time() + TIMELOCK_DURATION));
+        let proposed = env.get_event::<ExposureCapProposed>(system.router.address(), -1).unwrap();
+        assert_eq!((proposed.strategy_name, proposed.max_tvl_bps, proposed.eta), (None, 3000, eta), "Cross-chain cap");
+        env.advance_block_time(TIMELOCK_DURATION - 1);
+        assert_eq!(system.router.try_apply_crosschain_tvl_cap(), Err(VaultError::TimelockNotExpired.into()));
+        env.advance_block_time(1);
+        system.router.apply_crosschain_tvl_cap();
+        assert_eq!(system.router.get_crosschain_tvl_cap(), 3000);
+        assert_eq!(system.router.get_pending_crosschain_tvl_cap(), None, "Proposal consumed");
+
+        env.set_caller(env.get_account(1));
+        assert!(system.router.try_propose_crosschain_tvl_cap(1000).is_err(), "Admin only");
+    }
+
+    #[test]
+    fn test_growing_vault_lifts_a_capped_strategy() {
+        let (env, system, dex) = setup_exposure_caps();
+        let user = env.get_account(1);
+        env.set_caller(user);
+
+        // 9,500 reaches the router after the 5% pool; dex's 40% share of it
+        // (3,800) is held to 20% of the 10,000 TVL and the rest spread out
+        system.vault.with_tokens(cspr(10_000)).deposit();
+        assert_eq!(system.router.get_exposure_limit("dex".to_string()), Some(cspr(2000)));
+        assert_u512_eq(dex.get_balance(), cspr(2000), "Truncated at the cap");
+        assert_u512_eq(system.router.get_current_allocation("lending".to_string()), cspr(3750), "Overflow to lending");
+        assert_u512_eq(system.router.get_current_allocation("crosschain".to_string()), cspr(3750), "Overflow to cross-chain");
+
+        // At 20,000 TVL the cap doubles, so dex takes another 2,000
+        system.vault.with_tokens(cspr(10_000)).deposit();
+        assert_eq!(system.router.get_exposure_limit("dex".to_string()), Some(cspr(4000)));
+        assert_u512_eq(dex.get_balance(), cspr(4000), "Eligible for more as the vault grows");
+
+        let simulation = system.router.simulate_allocation(vec![
+            ("dex".to_string(), 40u8),
+            ("lending".to_string(), 30u8),
+            ("crosschain".to_string(), 30u8),
+        ]);
+        let dex_move = simulation.moves.iter().find(|m| m.strategy_name == "dex").unwrap();
+        assert_u512_eq(dex_move.target, cspr(4000), "Rebalance target held to the cap");
+        assert!(simulation.violations.iter().any(|violation| violation.starts_with("dex over its TVL cap")));
+    }
+
+    #[test]
+    fn test_shrinking_vault_flags_over_exposure() {
+        let (env, mut system, _) = setup_exposure_caps();
+        let user = env.get_account(1);
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(10_000)).deposit();
+
+        env.set_caller(env.get_account(0));
+        system.router.health_check();
+        assert!(!system.router.get_health_report("dex".to_string()).over_exposed, "At the cap, not over it");
+
+        // Half the vault leaves; dex still holds 2,000 against a 1,000 limit
+        env.set_caller(user);
+        system.vault.withdraw(cspr(5000));
+        assert_eq!(system.router.get_exposure_limit("dex".to_string()), Some(cspr(1000)));
+
+        env.set_caller(env.get_account(0));
+        system.router.health_check();
+        let report = system.router.get_health_report("dex".to_string());
+        assert!(report.over_exposed, "Over-exposure flagged");
+        assert!(report.healthy, "Not a health failure");
+        assert!(!system.router.get_health_report("lending".to_string()).over_exposed, "Uncapped");
+
+        let event = env.get_event::<ExposureCapHit>(system.router.address(), -1).unwrap();
+        assert_eq!(event.strategy_name, "dex");
+        assert_eq!(event.cap_bps, 2000);
+        assert_u512_eq(event.limit, cspr(1000), "Limit at the smaller TVL");
+        assert_u512_eq(event.vault_tvl, cspr(5000), "TVL after the withdrawal");
+    }
+}
@@ -15,7 +15,7 @@ mod strategy_tests {
     #[test]
     fn test_lending_strategy_supply() {
         let env = odra_test::env();
-        let mut lending = MockLendingHostRef::deploy(&env, 500u16);
+        let mut lending = SystemBuilder::new(&env).with_mock_lending(500).build().mock_lending.unwrap();
         
         let supply_amount = cspr(5000);
         lending.supply(supply_amount);
@@ -26,7 +26,7 @@ mod strategy_tests {
     #[test]
     fn test_lending_strategy_redeem() {
         let env = odra_test::env();
-        let mut lending = MockLendingHostRef::deploy(&env, 500u16);
+        let mut lending = SystemBuilder::new(&env).with_mock_lending(500).build().mock_lending.unwrap();
         
         let supply_amount = cspr(5000);
         lending.supply(supply_amount);
@@ -39,7 +39,7 @@ mod strategy_tests {
     #[test]
     fn test_lending_apy() {
         let env = odra_test::env();
-        let lending = MockLendingHostRef::deploy(&env, 500u16);
+        let lending = SystemBuilder::new(&env).with_mock_lending(500).build().mock_lending.unwrap();
         
         let apy = lending.get_supply_apy();
         
@@ -49,7 +49,7 @@ mod strategy_tests {
     #[test]
     fn test_crosschain_bridge_initiation() {
         let env = odra_test::env();
-        let mut bridge = MockBridgeHostRef::deploy(&env, 3u8);
+        let mut bridge = SystemBuilder::new(&env).with_mock_bridge(50, cspr(100)).build().mock_bridge.unwrap();
         
         let amount = cspr(1000);
         let request_id = bridge.initiate_bridge(amount, "ethereum".to_string());
@@ -60,7 +60,7 @@ mod strategy_tests {
     #[test]
     fn test_bridge_confirmation() {
         let env = odra_test::env();
-        let mut bridge = MockBridgeHostRef::deploy(&env, 3u8);
+        let mut bridge = SystemBuilder::new(&env).with_mock_bridge(50, cspr(100)).build().mock_bridge.unwrap();
         
         let request_id = bridge.initiate_bridge(cspr(1000), "ethereum".to_string());
         bridge.confirm_bridge(request_id);
@@ -156,7 +156,7 @@ mod strategy_tests {
     #[test]
     fn test_interest_accrual() {
         let env = odra_test::env();
-        let mut lending = MockLendingHostRef::deploy(&env, 1000u16);
+        let mut lending = SystemBuilder::new(&env).with_mock_lending(1000).build().mock_lending.unwrap();
         
         let principal = U512::from(10000u64);
         let interest = lending.calculate_interest(principal);
@@ -0,0 +1,2236 @@
+#[cfg(test)]
+mod vault_operations_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::{
+        Deposit, InstantWithdrawal, InstantWithdrawalRejected, KeeperRebatePaid, KeeperRebatesClaimed, Withdraw,
+        WithdrawalCompleted, WithdrawalExpired, WithdrawalRequestTransferred,
+    };
+    use caspervault_contracts::{MockLiquidStakingHostRef, MockPriceOracleHostRef};
+    use caspervault_contracts::tokens::LstCsprHostRef;
+    use caspervault_contracts::core::{
+        VaultManagerHostRef,
+        OP_DEPOSIT, OP_WITHDRAW, OP_INSTANT_WITHDRAW, OP_COMPLETE_WITHDRAWAL, MAX_KEEPER_REBATE,
+        LIMIT_MODE_CSPR_FALLBACK, LIMIT_MODE_USD, MAX_FEE_EXEMPT_ACCOUNTS, FALLBACK_UNBONDING_SECONDS,
+        LIQUIDITY_PATH_INSTANT_POOL, LIQUIDITY_PATH_STRATEGIES, LIQUIDITY_PATH_TIMELOCK, LIQUIDITY_PATH_UNBONDING,
+        LiquidityTranche, RequestStatus,
+    };
+    use caspervault_contracts::deployer::deploy_system;
+    use caspervault_contracts::{
+        AccessError, AccountFrozen, AccountUnfrozen, ExitSizeFeeCharged, FeeExemptionChanged, FeesUpdated, InsolventExit, LossReported,
+        TreasuryChanged, VaultError, VaultReset,
+    };
+    use crate::helpers::*;
+
+    const SEVEN_DAYS: u64 = 7 * 24 * 60 * 60;
+    const ONE_YEAR: u64 = 365 * 24 * 60 * 60;
+    const KEEPER_ROLE: u8 = 3;
+    const ONE_HOUR: u64 = 60 * 60;
+    const ONE_SHARE: u64 = 1_000_000_000;
+    const ONE_DAY: u64 = 24 * ONE_HOUR;
+    const GUARDIAN_ROLE: u8 = 2;
+
+    struct VaultFixture {
+        env: HostEnv,
+        admin: Address,
+        user1: Address,
+        user2: Address,
+        vault: VaultManagerHostRef,
+    }
+
+    fn setup() -> VaultFixture {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user1 = env.get_account(1);
+        let user2 = env.get_account(2);
+
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        // Most tests deposit and withdraw back to back
+        vault.set_min_holding_period(0);
+
+        VaultFixture { env, admin, user1, user2, vault }
+    }
+
+    /// `setup` with the management fee off
+    /// 
+    /// Fees accrue with time and on every call, so payouts, request values
+    /// and share prices would otherwise drift from the amounts tests check.
+    fn setup_without_fees() -> VaultFixture {
+        let mut f = setup();
+        f.vault.set_management_fee(0);
+        f
+    }
+
+    fn deposit(f: &mut VaultFixture, user: Address, amount: U512) -> U512 {
+        f.env.set_caller(user);
+        f.vault.with_tokens(amount).deposit()
+    }
+
+    #[test]
+    fn test_operation_ids_strictly_increasing() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        // 10 interleaved operations
+        deposit(&mut f, user1, cspr(1000));
+        let op1 = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap().operation_id;
+
+        deposit(&mut f, user2, cspr(1000));
+        let op2 = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap().operation_id;
+
+        deposit(&mut f, user1, cspr(500));
+        let op3 = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap().operation_id;
+
+        f.env.set_caller(user1);
+        f.vault.withdraw(cspr(100));
+        let op4 = f.env.get_event::<Withdraw>(f.vault.address(), -1).unwrap().operation_id;
+
+        f.env.set_caller(user2);
+        f.vault.instant_withdraw(cspr(10));
+        let op5 = f.env.get_event::<InstantWithdrawal>(f.vault.address(), -1).unwrap().operation_id;
+
+        deposit(&mut f, user2, cspr(200));
+        let op6 = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap().operation_id;
+
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(100));
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.vault.complete_withdrawal(request_id);
+        let op7 = f.env.get_event::<WithdrawalCompleted>(f.vault.address(), -1).unwrap().operation_id;
+
+        f.env.set_caller(user2);
+        f.vault.withdraw(cspr(50));
+        let op8 = f.env.get_event::<Withdraw>(f.vault.address(), -1).unwrap().operation_id;
+
+        deposit(&mut f, user1, cspr(300));
+        let op9 = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap().operation_id;
+
+        f.env.set_caller(user2);
+        f.vault.instant_withdraw(cspr(5));
+        let op10 = f.env.get_event::<InstantWithdrawal>(f.vault.address(), -1).unwrap().operation_id;
+
+        let ids = vec![op1, op2, op3, op4, op5, op6, op7, op8, op9, op10];
+        assert_eq!(ids, (1..=10).collect::<Vec<u64>>(), "IDs are sequential");
+        assert_eq!(f.vault.get_last_operation_id(), 10);
+
+        // Records match the events
+        let kinds: Vec<u8> = f.vault.get_recent_operations(10).iter().rev().map(|r| r.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                OP_DEPOSIT, OP_DEPOSIT, OP_DEPOSIT, OP_WITHDRAW, OP_INSTANT_WITHDRAW,
+                OP_DEPOSIT, OP_COMPLETE_WITHDRAWAL, OP_WITHDRAW, OP_DEPOSIT, OP_INSTANT_WITHDRAW,
+            ]
+        );
+
+        let record = f.vault.get_operation(op4).unwrap();
+        assert_eq!(record.user, user1);
+        assert_u512_eq(record.shares, cspr(100), "Withdraw record shares");
+    }
+
+    #[test]
+    fn test_request_withdrawal_is_not_an_operation() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        f.vault.request_withdrawal(cspr(100));
+
+        assert_eq!(f.vault.get_last_operation_id(), 1);
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        f.env.set_caller(f.admin);
+        f.vault.set_operation_log_size(4);
+
+        for _ in 0..6 {
+            deposit(&mut f, user1, cspr(100));
+        }
+
+        assert!(f.vault.get_operation(1).is_none(), "Op 1 evicted");
+        assert!(f.vault.get_operation(2).is_none(), "Op 2 evicted");
+        for id in 3..=6 {
+            assert_eq!(f.vault.get_operation(id).unwrap().id, id);
+        }
+        assert!(f.vault.get_operation(7).is_none(), "Future op not present");
+
+        let recent: Vec<u64> = f.vault.get_recent_operations(10).iter().map(|r| r.id).collect();
+        assert_eq!(recent, vec![6, 5, 4, 3], "Newest first, bounded by capacity");
+
+        let latest_two: Vec<u64> = f.vault.get_recent_operations(2).iter().map(|r| r.id).collect();
+        assert_eq!(latest_two, vec![6, 5]);
+    }
+
+    #[test]
+    fn test_operation_log_size_bounds() {
+        let mut f = setup();
+
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_set_operation_log_size(0).is_err());
+
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_set_operation_log_size(16).is_err());
+    }
+
+    #[test]
+    fn test_transferred_request_pays_new_owner() {
+        let mut f = setup_without_fees();
+        let (user1, user2) = (f.user1, f.user2);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+        f.vault.transfer_withdrawal_request(request_id, user2);
+
+        let event = f.env.get_event::<WithdrawalRequestTransferred>(f.vault.address(), -1).unwrap();
+        assert_eq!((event.from, event.to), (user1, user2));
+        assert_eq!(f.vault.get_withdrawal_request(request_id).unwrap().user, user2);
+
+        f.env.advance_block_time(SEVEN_DAYS);
+
+        // Original requester lost access
+        assert!(f.vault.try_complete_withdrawal(request_id).is_err());
+
+        // New owner has no deposits of their own; the fee still uses the requester's basis
+        f.env.set_caller(user2);
+        let received = f.vault.complete_withdrawal(request_id);
+        assert_u512_eq(received, cspr(400), "No profit over requester's basis, no fee");
+
+        let completed = f.env.get_event::<WithdrawalCompleted>(f.vault.address(), -1).unwrap();
+        assert_eq!(completed.user, user2);
+
+        assert!(
+            f.vault.try_transfer_withdrawal_request(request_id, user1).is_err(),
+            "Completed requests can't be transferred"
+        );
+    }
+
+    #[test]
+    fn test_keeper_completes_auto_request_minus_rebate() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        let keeper = f.env.get_account(3);
+
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(KEEPER_ROLE, keeper);
+        f.vault.set_keeper_rebate(U512::from(ONE_SHARE / 10));
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal_with_auto_complete(cspr(400), true);
+        assert!(f.vault.is_auto_complete(request_id));
+
+        f.env.set_caller(keeper);
+        assert!(f.vault.try_complete_withdrawal_for(request_id).is_err(), "Still timelocked");
+
+        f.env.advance_block_time(SEVEN_DAYS);
+        let paid = f.vault.complete_withdrawal_for(request_id);
+        let rebate = U512::from(ONE_SHARE / 10);
+        assert_u512_eq(paid, cspr(400) - rebate, "User paid minus the rebate");
+
+        let rebate_event = f.env.get_event::<KeeperRebatePaid>(f.vault.address(), -1).unwrap();
+        assert_eq!((rebate_event.keeper, rebate_event.user), (keeper, user1));
+        let completed = f.env.get_event::<WithdrawalCompleted>(f.vault.address(), -2).unwrap();
+        assert_eq!(completed.user, user1, "Proceeds go to the requester, not the keeper");
+        assert_u512_eq(completed.assets, cspr(400) - rebate, "Event payout");
+        assert_u512_eq(f.vault.get_keeper_rebates_earned(keeper), rebate, "Keeper credited");
+
+        f.env.set_caller(user1);
+        assert!(f.vault.try_complete_withdrawal(request_id).is_err(), "Already completed");
+    }
+
+    #[test]
+    fn test_keeper_cannot_complete_unflagged_request() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        let keeper = f.env.get_account(3);
+
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(KEEPER_ROLE, keeper);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+        let flagged = f.vault.request_withdrawal_with_auto_complete(cspr(100), true);
+        assert!(!f.vault.is_auto_complete(request_id));
+
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.env.set_caller(keeper);
+        assert_eq!(
+            f.vault.try_complete_withdrawal_for(request_id),
+            Err(VaultError::Unauthorized.into()),
+            "Owner must complete unflagged requests"
+        );
+
+        f.env.set_caller(f.user2);
+        assert_eq!(
+            f.vault.try_complete_withdrawal_for(flagged),
+            Err(VaultError::Unauthorized.into()),
+            "Only keepers can push payouts"
+        );
+
+        // The owner can still complete either request themselves, rebate-free
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(400), "Owner completes");
+        assert_u512_eq(f.vault.complete_withdrawal(flagged), cspr(100), "Owner completes flagged");
+    }
+
+    #[test]
+    fn test_keeper_rebate_capped() {
+        let mut f = setup();
+
+        f.env.set_caller(f.admin);
+        assert_eq!(
+            f.vault.try_set_keeper_rebate(U512::from(MAX_KEEPER_REBATE) + 1),
+            Err(VaultError::InvalidFee.into())
+        );
+
+        f.vault.set_keeper_rebate(U512::from(MAX_KEEPER_REBATE));
+        assert_u512_eq(f.vault.get_keeper_rebate(), U512::from(MAX_KEEPER_REBATE), "Cap allowed");
+
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_set_keeper_rebate(U512::zero()).is_err(), "Admin only");
+    }
+
+    #[test]
+    fn test_keeper_claims_rebates_in_cspr() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let (user, keeper) = (env.get_account(1), env.get_account(3));
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        // No strategies: the whole deposit backs the instant pool
+        system.router.set_idle_buffer_pct(0);
+        system.vault.set_management_fee(0);
+        system.vault.set_min_holding_period(0);
+        system.vault.grant_role(KEEPER_ROLE, keeper);
+        system.vault.set_keeper_rebate(U512::from(ONE_SHARE / 10));
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        let request_id = system.vault.request_withdrawal_with_auto_complete(cspr(400), true);
+        env.advance_block_time(SEVEN_DAYS);
+
+        env.set_caller(keeper);
+        assert_eq!(system.vault.try_claim_keeper_rebates(), Err(VaultError::ZeroAmount.into()), "Nothing earned yet");
+        system.vault.complete_withdrawal_for(request_id);
+
+        let rebate = U512::from(ONE_SHARE / 10);
+        let keeper_before = env.balance_of(&keeper);
+        let purse_before = env.balance_of(system.vault.address());
+        assert_u512_eq(system.vault.claim_keeper_rebates(), rebate, "Rebate paid");
+        assert_u512_eq(env.balance_of(&keeper) - keeper_before, rebate, "Paid in CSPR at 1:1");
+        assert_u512_eq(purse_before - env.balance_of(system.vault.address()), rebate, "Out of the vault's purse");
+        assert_u512_eq(system.lst_cspr.balance_of(keeper), U512::zero(), "No lstCSPR involved");
+        assert_u512_eq(system.vault.get_keeper_rebates_earned(keeper), U512::zero(), "Nothing left to claim");
+        let event = env.get_event::<KeeperRebatesClaimed>(system.vault.address(), -1).unwrap();
+        assert_eq!((event.keeper, event.amount), (keeper, rebate));
+        assert_eq!(system.vault.try_claim_keeper_rebates(), Err(VaultError::ZeroAmount.into()), "Claimed once");
+    }
+
+    #[test]
+    fn test_insurance_slice_is_paid_in_cspr() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let user = env.get_account(1);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        system.router.set_idle_buffer_pct(0);
+        system.vault.set_management_fee(0);
+        system.vault.set_min_holding_period(0);
+        system.vault.set_insurance_fee_bps(2000);
+        system.liquid_staking.set_insurance_fee_source(*system.vault.address());
+
+        env.set_caller(user);
+        system.vault.with_tokens(cspr(1000)).deposit();
+        env.set_caller(admin);
+        system.vault.report_profit(cspr(100));
+        env.advance_block_time(SEVEN_DAYS);
+
+        // 10% performance fee on the 100 CSPR gain, a fifth of it to insurance
+        let purse_before = env.balance_of(system.liquid_staking.address());
+        env.set_caller(user);
+        system.vault.withdraw(system.vault.get_user_shares(user));
+        let fee = system.vault.get_fee_breakdown().performance_fees;
+        let slice = fee / U512::from(5u64);
+
+        assert_u512_within_tolerance(fee, cspr(10), 1);
+        assert_u512_eq(system.vault.get_fees_collected(), fee - slice, "Slice left the treasury's fees");
+        assert_u512_eq(system.vault.get_insurance_fees_credited(), slice, "Slice credited");
+        assert_u512_eq(system.liquid_staking.get_insurance_fund_balance(), slice, "Fund credited");
+        assert_u512_eq(
+            env.balance_of(system.liquid_staking.address()) - purse_before,
+            slice,
+            "Purse backs the fund",
+        );
+    }
+
+    #[test]
+    fn test_double_transfer() {
+        let mut f = setup_without_fees();
+        let (user1, user2) = (f.user1, f.user2);
+        let user3 = f.env.get_account(3);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+        f.vault.transfer_withdrawal_request(request_id, user2);
+
+        assert!(
+            f.vault.try_transfer_withdrawal_request(request_id, user3).is_err(),
+            "Previous owner can't transfer again"
+        );
+
+        f.env.set_caller(user2);
+        f.vault.transfer_withdrawal_request(request_id, user3);
+
+        f.env.advance_block_time(SEVEN_DAYS);
+        assert!(f.vault.try_complete_withdrawal(request_id).is_err(), "Intermediate owner lost access");
+
+        f.env.set_caller(user3);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(400), "Final owner paid");
+    }
+
+    #[test]
+    fn test_transfer_by_non_owner_reverts() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+
+        f.env.set_caller(user2);
+        assert!(f.vault.try_transfer_withdrawal_request(request_id, user2).is_err());
+
+        f.env.set_caller(user1);
+        assert!(f.vault.try_transfer_withdrawal_request(request_id + 1, user2).is_err(), "Unknown request");
+        assert!(f.vault.try_transfer_withdrawal_request(request_id, user1).is_err(), "Transfer to self");
+        assert_eq!(f.vault.get_withdrawal_request(request_id).unwrap().user, user1);
+    }
+
+    #[test]
+    fn test_fee_breakdown_by_stream() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+        let treasury = f.env.get_account(6);
+        let bps = |amount: U512, bps: u64| amount * U512::from(bps) / U512::from(10000u64);
+
+        deposit(&mut f, user1, cspr(1000));
+        deposit(&mut f, user2, cspr(1000));
+        let first_period = f.vault.get_current_fee_period();
+
+        // 0.5% instant fee, no profit over cost basis
+        f.env.set_caller(user2);
+        f.vault.instant_withdraw(cspr(40));
+        let instant_fee = bps(cspr(40), 50);
+
+        // One year of 2% management fee on the remaining 1960 CSPR: 39.2 CSPR,
+        // minted as 39.2 * 1960 / (1960 - 39.2) = 40 shares
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(KEEPER_ROLE, f.admin);
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        let management_shares = cspr(40);
+        f.vault.set_fee_exempt(treasury, false);
+
+        // Treasury has no cost basis: the whole withdrawal is profit
+        let assets = f.vault.convert_to_assets(cspr(10));
+        f.env.set_caller(treasury);
+        f.vault.withdraw(cspr(10));
+        let performance_fee = bps(assets, 1000);
+        let last_period = f.vault.get_current_fee_period();
+
+        let breakdown = f.vault.get_fee_breakdown();
+        assert_u512_eq(breakdown.instant_fees, instant_fee, "Instant fees");
+        assert_u512_eq(breakdown.performance_fees, performance_fee, "Performance fees");
+        assert_u512_eq(breakdown.management_fee_shares, management_shares, "Management fee shares");
+        assert_u512_eq(breakdown.total_fees, instant_fee + performance_fee, "Total");
+        assert_u512_eq(f.vault.get_fees_collected(), instant_fee + performance_fee, "Aggregate getter");
+
+        let first = f.vault.get_fee_revenue(first_period);
+        assert_u512_eq(first.instant_fees, instant_fee, "Instant fee in first period");
+        assert_u512_eq(first.performance_fees, U512::zero(), "No performance fee in first period");
+
+        let last = f.vault.get_fee_revenue(last_period);
+        assert_u512_eq(last.instant_fees, U512::zero(), "No instant fee in last period");
+        assert_u512_eq(last.performance_fees, performance_fee, "Performance fee in last period");
+        assert_u512_eq(last.management_fee_shares, management_shares, "Management shares in last period");
+
+        let empty = f.vault.get_fee_revenue(first_period + 30);
+        assert_u512_eq(empty.total_fees, U512::zero(), "Period sharing a slot reads as empty");
+    }
+
+    #[test]
+    fn test_instant_fee_split_with_remaining_holders() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+        let half_fee = U512::from(2_500_000_000u64); // 2.5 CSPR
+
+        assert_eq!(f.vault.get_instant_fee_to_holders(), 0, "All to the protocol by default");
+        f.env.set_caller(user1);
+        assert!(f.vault.try_set_instant_fee_to_holders(5000).is_err(), "Non-admin rejected");
+        f.env.set_caller(f.admin);
+        assert_eq!(f.vault.try_set_instant_fee_to_holders(10001), Err(VaultError::InvalidFee.into()));
+        f.vault.set_instant_fee_to_holders(5000);
+        // Enough pool for a 1,000 CSPR instant exit
+        f.vault.set_instant_pool_target(5000);
+
+        deposit(&mut f, user1, cspr(5000));
+        deposit(&mut f, user2, cspr(5000));
+        let price_before = f.vault.get_share_price_precise();
+
+        // 0.5% of 1,000 CSPR: 2.5 CSPR stays in the vault, 2.5 CSPR in fees
+        f.env.set_caller(user2);
+        let payout = f.vault.instant_withdraw(cspr(1000));
+        assert_u512_eq(payout, cspr(995), "User pays the full fee");
+
+        let event = f.env.get_event::<InstantWithdrawal>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.fee_to_holders, half_fee, "Holders' half in the event");
+        assert_u512_eq(event.fee_to_protocol, half_fee, "Protocol half in the event");
+        assert_u512_eq(f.vault.get_fee_breakdown().instant_fees, half_fee, "Only the protocol half booked");
+        assert_u512_eq(f.vault.get_fees_collected(), half_fee, "Aggregate getter");
+
+        assert_u512_eq(f.vault.total_assets(), cspr(9000) + half_fee, "Holders' half stays in total assets");
+        assert!(f.vault.get_share_price_precise() > price_before, "Share price raised");
+        assert_u512_eq(
+            f.vault.get_user_assets(user1),
+            cspr(5000) + half_fee * U512::from(5u64) / U512::from(9u64),
+            "Remaining holders share it pro rata",
+        );
+    }
+
+    #[test]
+    fn test_reported_profit_unlocks_linearly() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        deposit(&mut f, user1, cspr(1000));
+
+        // 10% profit, unlocking over the default 6 hours
+        f.env.set_caller(f.admin);
+        f.vault.report_profit(cspr(100));
+
+        assert_u512_eq(f.vault.get_share_price(), U512::from(ONE_SHARE), "Price unchanged right after report");
+        assert_u512_eq(f.vault.get_locked_profit(), cspr(100), "All profit locked");
+
+        // A depositor entering right after the report gets no discount
+        let shares = deposit(&mut f, user2, cspr(1000));
+        assert_u512_eq(shares, cspr(1000), "Late entrant minted at the old price");
+
+        f.env.advance_block_time(3 * ONE_HOUR);
+        assert_u512_eq(f.vault.get_locked_profit(), cspr(50), "Half unlocked");
+        assert_u512_eq(f.vault.total_assets(), cspr(2050), "Half the profit counted");
+        assert_u512_eq(f.vault.get_share_price(), U512::from(1_025_000_000u64), "Half the gain in the price");
+
+        f.env.advance_block_time(3 * ONE_HOUR);
+        assert_u512_eq(f.vault.get_locked_profit(), U512::zero(), "Fully unlocked");
+        assert_u512_eq(f.vault.get_share_price(), U512::from(1_050_000_000u64), "Full gain in the price");
+    }
+
+    #[test]
+    fn test_withdraw_during_unlock_uses_discounted_assets() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(f.admin);
+        f.vault.report_profit(cspr(100));
+
+        // Immediately after the report the profit is not withdrawable
+        assert_u512_eq(f.vault.max_withdraw(user1), cspr(1000), "Locked profit excluded");
+
+        f.env.advance_block_time(3 * ONE_HOUR);
+        assert_u512_eq(f.vault.max_withdraw(user1), cspr(1050), "Half the profit available");
+    }
+
+    #[test]
+    fn test_report_profit_access_and_duration_bounds() {
+        let mut f = setup();
+
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_report_profit(cspr(100)).is_err(), "Operator or admin only");
+        assert!(f.vault.try_set_profit_unlock_duration(ONE_HOUR).is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_set_profit_unlock_duration(8 * 24 * ONE_HOUR).is_err(), "Max 7 days");
+    }
+
+    /// Two holders and a pending request, then a loss that wipes out every asset
+    fn setup_total_loss() -> (VaultFixture, u64) {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        deposit(&mut f, user1, cspr(1000));
+        deposit(&mut f, user2, cspr(500));
+        f.env.set_caller(user2);
+        let request_id = f.vault.request_withdrawal(cspr(200));
+
+        f.env.set_caller(f.admin);
+        f.vault.report_loss(cspr(1500));
+        (f, request_id)
+    }
+
+    #[test]
+    fn test_total_loss_makes_vault_insolvent() {
+        let (mut f, _) = setup_total_loss();
+        let user1 = f.user1;
+
+        let event = f.env.get_event::<LossReported>(f.vault.address(), -1).unwrap();
+        assert!(event.insolvent);
+        assert_u512_eq(event.total_assets, U512::zero(), "Nothing left");
+        assert!(f.vault.is_insolvent());
+        assert_u512_eq(f.vault.convert_to_shares(cspr(100)), U512::zero(), "No par minting");
+
+        f.env.set_caller(f.user1);
+        assert_eq!(
+            f.vault.with_tokens(cspr(100)).try_deposit(),
+            Err(VaultError::VaultInsolvent.into()),
+            "Deposits closed"
+        );
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(1000), "Existing shares untouched");
+    }
+
+    #[test]
+    fn test_insolvent_exits_pay_zero() {
+        let (mut f, request_id) = setup_total_loss();
+        let (user1, user2) = (f.user1, f.user2);
+
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.withdraw(cspr(400)), U512::zero(), "Withdraw pays nothing");
+        let exit = f.env.get_event::<InsolventExit>(f.vault.address(), -1).unwrap();
+        assert_eq!((exit.user, exit.request_id), (user1, None));
+        assert_u512_eq(exit.shares, cspr(400), "Shares redeemed");
+
+        assert_u512_eq(f.vault.instant_withdraw(cspr(100)), U512::zero(), "Instant pays nothing");
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(500), "Shares burned");
+
+        // The request locked 200 CSPR before the loss; it still pays nothing
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.env.set_caller(user2);
+        assert_u512_eq(f.vault.preview_complete_withdrawal(request_id), U512::zero(), "Preview");
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), U512::zero(), "Request pays nothing");
+        let exit = f.env.get_event::<InsolventExit>(f.vault.address(), -1).unwrap();
+        assert_eq!(exit.request_id, Some(request_id));
+    }
+
+    #[test]
+    fn test_reset_after_total_loss_reopens_vault() {
+        let (mut f, request_id) = setup_total_loss();
+        let (user1, user2) = (f.user1, f.user2);
+        let approvers = vec![f.env.get_account(3), f.env.get_account(4)];
+
+        // Let the request unlock before the reset
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.env.set_caller(f.admin);
+        f.vault.set_approvers(approvers.clone(), 2, SEVEN_DAYS);
+        let action_hash = f.vault.get_reset_after_total_loss_hash();
+        assert!(f.vault.try_reset_after_total_loss().is_err(), "Needs approvals");
+
+        for approver in approvers {
+            f.env.set_caller(approver);
+            f.vault.approve_action(action_hash);
+        }
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_reset_after_total_loss().is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        f.vault.reset_after_total_loss();
+        let event = f.env.get_event::<VaultReset>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.shares_written_off, cspr(1500), "Balances and the request written off");
+        assert_eq!(f.vault.get_loss_epoch(), 1);
+        assert!(!f.vault.is_insolvent());
+        assert_u512_eq(f.vault.get_user_shares(user1), U512::zero(), "Old shares void");
+
+        // Solvent again: nothing to reset
+        assert_eq!(f.vault.try_reset_after_total_loss(), Err(VaultError::ConditionsNotMet.into()));
+
+        // New deposits price at par and old holders can't claim them
+        assert_u512_eq(deposit(&mut f, user1, cspr(300)), cspr(300), "1:1 after reset");
+        assert_u512_eq(f.vault.get_user_assets(user1), cspr(300), "Only the new deposit");
+
+        f.env.set_caller(user2);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), U512::zero(), "Pre-reset request void");
+        assert_u512_eq(f.vault.get_total_shares(), cspr(300), "Supply untouched by the stale request");
+    }
+
+    /// Vault wired to a mock LiquidStaking with 10,000 CSPR staked at 10% APY;
+    /// user1 deposits 1,000 CSPR, waits 10 days without a compound and
+    /// withdraws everything. Returns (payout, pending-yield estimate).
+    fn withdraw_after_uncompounded_yield(harvest: bool, min_assets: U512) -> (U512, U512) {
+        let env = odra_test::env();
+        let user = env.get_account(1);
+
+        let built = SystemBuilder::new(&env).with_mock_liquid_staking().with_vault().build();
+        built.mock_liquid_staking.unwrap().set_staking(cspr(10_000), 1000);
+        let mut vault = built.vault.unwrap();
+        // Management fees accrue on withdrawal; keep the payout down to yield
+        vault.set_management_fee(0);
+        if harvest {
+            vault.set_harvest_before_withdraw(true, min_assets, 5);
+        }
+
+        env.set_caller(user);
+        let shares = vault.with_tokens(cspr(1000)).deposit();
+
+        env.advance_block_time(10 * 24 * ONE_HOUR);
+        let estimate = vault.get_pending_yield_estimate();
+
+        (vault.withdraw(shares), estimate)
+    }
+
+    #[test]
+    fn test_harvest_on_withdraw_pays_pending_yield() {
+        let (payout_off, _) = withdraw_after_uncompounded_yield(false, cspr(100));
+        let (payout_on, estimate) = withdraw_after_uncompounded_yield(true, cspr(100));
+
+        // 10% of 10 days of 10% APY on the vault's 1,000 CSPR share of the stake
+        let expected_estimate = cspr(1000) * U512::from(10u64) / U512::from(3650u64);
+        assert_u512_within_tolerance(estimate, expected_estimate, 10);
+
+        assert_u512_eq(payout_off, cspr(1000), "Flag off: cached NAV, no profit");
+        assert!(payout_on > payout_off, "Flag on pays the accrued yield");
+
+        // The pending yield is profit, so the 10% performance fee applies
+        let expected_gain = estimate * U512::from(9u64) / U512::from(10u64);
+        assert_u512_within_tolerance(payout_on - payout_off, expected_gain, 10);
+    }
+
+    #[test]
+    fn test_harvest_on_withdraw_threshold_and_access() {
+        let (below_threshold, _) = withdraw_after_uncompounded_yield(true, cspr(5000));
+        assert_u512_eq(below_threshold, cspr(1000), "Small withdrawals use the cached NAV");
+
+        let mut f = setup();
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_set_harvest_before_withdraw(true, cspr(100), 5).is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_set_harvest_before_withdraw(true, cspr(100), 0).is_err(), "Strategy cap must be positive");
+        f.vault.set_harvest_before_withdraw(true, cspr(100), 5);
+        assert!(f.vault.get_harvest_before_withdraw());
+    }
+
+    #[test]
+    fn test_expired_request_restores_shares() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(600), "Shares locked in the request");
+
+        // Anyone can expire once the window after unlock has passed
+        f.env.advance_block_time(SEVEN_DAYS + 90 * 24 * ONE_HOUR);
+        f.env.set_caller(f.user2);
+        f.vault.expire_withdrawal(request_id);
+
+        let event = f.env.get_event::<WithdrawalExpired>(f.vault.address(), -1).unwrap();
+        assert_eq!(event.user, user1);
+        assert_u512_eq(event.shares, cspr(400), "Event shares");
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(1000), "Shares restored");
+        assert!(f.vault.is_withdrawal_expired(request_id));
+
+        f.env.set_caller(user1);
+        assert!(f.vault.try_complete_withdrawal(request_id).is_err(), "Expired request can't be completed");
+        assert!(f.vault.try_expire_withdrawal(request_id).is_err(), "Can't expire twice");
+
+        let received = f.vault.withdraw(cspr(1000));
+        assert_u512_eq(received, cspr(1000), "Restored shares withdraw normally");
+    }
+
+    #[test]
+    fn test_premature_expiry_reverts() {
+        let mut f = setup();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+
+        f.env.advance_block_time(SEVEN_DAYS + 89 * 24 * ONE_HOUR);
+        assert!(f.vault.try_expire_withdrawal(request_id).is_err(), "Window not reached");
+
+        // Completed requests can't be expired
+        f.vault.complete_withdrawal(request_id);
+        f.env.advance_block_time(2 * 24 * ONE_HOUR);
+        assert!(f.vault.try_expire_withdrawal(request_id).is_err(), "Already completed");
+
+        // Zero disables expiry
+        let second = f.vault.request_withdrawal(cspr(100));
+        f.env.set_caller(f.admin);
+        f.vault.set_request_expiry(0);
+        f.env.advance_block_time(SEVEN_DAYS + 365 * 24 * ONE_HOUR);
+        assert!(f.vault.try_expire_withdrawal(second).is_err(), "Expiry disabled");
+    }
+
+    /// Ten requests of 10, 20, ... 100 CSPR, one a day; returns their ids
+    fn queue_daily_requests(f: &mut VaultFixture) -> Vec<u64> {
+        let user1 = f.user1;
+        deposit(f, user1, cspr(1000));
+
+        f.env.set_caller(user1);
+        let mut ids = Vec::new();
+        for i in 1..=10u64 {
+            ids.push(f.vault.request_withdrawal(cspr(10 * i)));
+            f.env.advance_block_time(ONE_DAY);
+        }
+        ids
+    }
+
+    #[test]
+    fn test_pending_withdrawals_maturing_buckets() {
+        let mut f = setup_without_fees();
+        let ids = queue_daily_requests(&mut f);
+
+        assert_eq!(f.vault.get_pending_withdrawal_count(), 10);
+        assert_u512_eq(f.vault.get_total_pending_withdrawal_assets(), cspr(550), "10 + 20 + ... + 100");
+
+        let first_unlock = f.vault.get_withdrawal_request(ids[0]).unwrap().unlock_time;
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock - ONE_DAY), U512::zero(), "Nothing unlocks earlier");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock), cspr(10), "First day");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 3 * ONE_DAY), cspr(100), "First four days");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 9 * ONE_DAY), cspr(550), "All ten");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 365 * ONE_DAY), cspr(550), "Far future");
+
+        // Pages cover every open request exactly once
+        let first_page = f.vault.get_pending_withdrawals_page(0, 4);
+        let second_page = f.vault.get_pending_withdrawals_page(4, 100);
+        assert_eq!(first_page.len(), 4);
+        assert_eq!(second_page.len(), 6, "Limit is capped, not an error");
+        let mut paged: Vec<u64> = first_page.iter().chain(second_page.iter()).map(|(id, _)| *id).collect();
+        paged.sort();
+        assert_eq!(paged, ids);
+        assert_u512_eq(first_page[2].1.assets_value, cspr(30), "Requests come back whole");
+        assert!(f.vault.get_pending_withdrawals_page(10, 50).is_empty(), "Past the end");
+    }
+
+    #[test]
+    fn test_pending_withdrawals_page_limit_capped_at_fifty() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        f.env.set_caller(user1);
+        for _ in 0..55 {
+            f.vault.request_withdrawal(cspr(1));
+        }
+
+        assert_eq!(f.vault.get_pending_withdrawals_page(0, u32::MAX).len(), 50);
+        assert_eq!(f.vault.get_pending_withdrawals_page(50, u32::MAX).len(), 5);
+        assert_eq!(f.vault.get_pending_withdrawals_page(u32::MAX, u32::MAX).len(), 0);
+    }
+
+    #[test]
+    fn test_pending_withdrawal_counters_through_completion_and_expiry() {
+        let mut f = setup_without_fees();
+        let ids = queue_daily_requests(&mut f);
+        let user1 = f.user1;
+        let first_unlock = f.vault.get_withdrawal_request(ids[0]).unwrap().unlock_time;
+
+        // The first four have unlocked by the time the tenth is queued
+        f.env.set_caller(user1);
+        f.vault.complete_withdrawal(ids[0]);
+        f.vault.complete_withdrawal(ids[2]);
+
+        assert_eq!(f.vault.get_pending_withdrawal_count(), 8);
+        assert_u512_eq(f.vault.get_total_pending_withdrawal_assets(), cspr(510), "550 - 10 - 30");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 3 * ONE_DAY), cspr(60), "20 + 40 left in the first four days");
+
+        let paged: Vec<u64> = f.vault.get_pending_withdrawals_page(0, 50).iter().map(|(id, _)| *id).collect();
+        assert_eq!(paged.len(), 8);
+        assert!(!paged.contains(&ids[0]) && !paged.contains(&ids[2]), "Completed requests leave the index");
+        assert!(paged.iter().all(|id| f.vault.get_withdrawal_request(*id).unwrap().status.is_pending()));
+
+        // Expiring a request (the only way to cancel one) closes it too
+        f.env.set_caller(f.admin);
+        f.vault.set_request_expiry(ONE_DAY);
+        f.env.advance_block_time(ONE_DAY);
+        f.vault.expire_withdrawal(ids[1]);
+
+        assert_eq!(f.vault.get_pending_withdrawal_count(), 7);
+        assert_u512_eq(f.vault.get_total_pending_withdrawal_assets(), cspr(490), "Expired 20 dropped");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 3 * ONE_DAY), cspr(40), "Only the fourth day left");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 9 * ONE_DAY), cspr(490), "Rest unchanged");
+
+        // Closing everything empties the counters
+        f.env.advance_block_time(10 * ONE_DAY);
+        f.env.set_caller(user1);
+        for id in [3, 4, 5, 6, 7, 8, 9] {
+            f.vault.complete_withdrawal(ids[id]);
+        }
+        assert_eq!(f.vault.get_pending_withdrawal_count(), 0);
+        assert_u512_eq(f.vault.get_total_pending_withdrawal_assets(), U512::zero(), "Nothing pending");
+        assert_u512_eq(f.vault.get_withdrawals_maturing_before(first_unlock + 9 * ONE_DAY), U512::zero(), "Buckets drained");
+        assert!(f.vault.get_pending_withdrawals_page(0, 50).is_empty());
+    }
+
+    #[test]
+    fn test_holding_period_blocks_same_block_withdrawals() {
+        let mut f = setup();
+        let user1 = f.user1;
+        f.vault.set_min_holding_period(60);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        assert!(f.vault.try_instant_withdraw(cspr(10)).is_err(), "Instant withdraw blocked");
+        assert!(f.vault.try_withdraw(cspr(10)).is_err(), "Withdraw blocked");
+        assert!(f.vault.try_request_withdrawal(cspr(10)).is_err(), "Request blocked");
+
+        f.env.advance_block_time(60);
+        f.vault.instant_withdraw(cspr(10));
+        let request_id = f.vault.request_withdrawal(cspr(100));
+
+        // A fresh deposit does not hold up an older request
+        deposit(&mut f, user1, cspr(100));
+        f.env.advance_block_time(SEVEN_DAYS);
+        deposit(&mut f, user1, cspr(100));
+        f.env.set_caller(user1);
+        f.vault.complete_withdrawal(request_id);
+        assert!(f.vault.try_withdraw(cspr(10)).is_err(), "Still blocked after the latest deposit");
+    }
+
+    #[test]
+    fn test_holding_period_can_be_disabled() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        f.env.set_caller(user1);
+        assert!(f.vault.try_set_min_holding_period(0).is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        f.vault.set_min_holding_period(60);
+        assert_eq!(f.vault.get_min_holding_period(), 60);
+        f.vault.set_min_holding_period(0);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        f.vault.instant_withdraw(cspr(10));
+        assert!(f.env.get_event::<InstantWithdrawal>(f.vault.address(), -1).is_ok());
+    }
+
+    #[test]
+    fn test_repeat_deposit_keeps_tracking() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        assert_eq!(f.vault.max_deposit(user1), cspr(49_000));
+        deposit(&mut f, user1, cspr(1000));
+
+        // Tracking is unchanged: shares, daily usage and fee basis
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(2000), "Shares minted 1:1");
+        assert_eq!(f.vault.max_deposit(user1), cspr(48_000));
+        f.env.advance_block_time(24 * ONE_HOUR + 1);
+        assert_eq!(f.vault.max_deposit(user1), cspr(50_000));
+        deposit(&mut f, user1, cspr(500));
+        assert_eq!(f.vault.max_deposit(user1), cspr(49_500));
+
+        // No profit on a flat share price, so no performance fee
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.withdraw(cspr(2500)), cspr(2500), "Cost basis covers the exit");
+    }
+
+    #[test]
+    #[ignore = "gas is only metered on CasperVM; run with `make test-gas`"]
+    fn test_repeat_deposit_storage_cost() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        // First deposit: new depositor, fills the instant pool
+        deposit(&mut f, user1, cspr(1000));
+        let first_gas = last_call_gas(&f.env);
+
+        // Repeat deposit skips the depositor count and the pool write
+        deposit(&mut f, user1, cspr(1000));
+        let repeat_gas = last_call_gas(&f.env);
+        assert!(repeat_gas > 0, "Metered");
+        assert!(repeat_gas <= first_gas, "Repeat deposit costs no more than the first");
+    }
+
+    #[test]
+    fn test_repeat_deposit_storage_writes() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        // Same user, same day: the depositor count stays put
+        f.vault.test_reset_bookkeeping_writes();
+        deposit(&mut f, user1, cspr(1000));
+        let writes = f.vault.test_bookkeeping_writes();
+
+        // Totals (2), held and user shares (2), the `UserDeposit` record (1)
+        assert_eq!(writes, 5);
+        assert!(
+            storage_write_reduction_bps(LEGACY_REPEAT_DEPOSIT_WRITES, writes) >= 3000,
+            "At least 30% fewer writes than the flattened layout"
+        );
+    }
+
+    #[test]
+    fn test_withdrawal_requests_complete_in_order() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        f.env.set_caller(user1);
+        let first = f.vault.request_withdrawal(cspr(100));
+        let second = f.vault.request_withdrawal(cspr(100));
+        assert_eq!((first, second), (0, 1), "Ids count up from zero");
+
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.vault.complete_withdrawal(first);
+        f.vault.complete_withdrawal(second);
+
+        let request = f.vault.get_withdrawal_request(first).unwrap();
+        assert_eq!(request.status, RequestStatus::Completed);
+        assert_eq!(request.in_transit, U512::zero(), "Transit returned on completion");
+        assert_u512_eq(request.assets_value, cspr(100), "Request kept whole");
+        assert_eq!(f.vault.get_pending_withdrawal_count(), 0);
+    }
+
+    #[test]
+    #[ignore = "gas is only metered on CasperVM; run with `make test-gas`"]
+    fn test_withdrawal_request_storage_cost() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        // First request also opens the maturity range
+        f.env.set_caller(user1);
+        let first = f.vault.request_withdrawal(cspr(100));
+        let first_gas = last_call_gas(&f.env);
+        let second = f.vault.request_withdrawal(cspr(100));
+        let repeat_gas = last_call_gas(&f.env);
+        assert!(repeat_gas > 0, "Metered");
+        assert!(repeat_gas <= first_gas, "Repeat request costs no more than the first");
+
+        // Completion reads and rewrites the one packed record
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.vault.complete_withdrawal(first);
+        let complete_gas = last_call_gas(&f.env);
+        f.vault.complete_withdrawal(second);
+        assert!(last_call_gas(&f.env) <= complete_gas, "Completion cost doesn't grow with request count");
+    }
+
+    #[test]
+    fn test_daily_limit_resets_at_utc_midnight() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        // 23:59 on the current day
+        let now = f.env.get_block_time();
+        let last_minute = (now / ONE_DAY + 1) * ONE_DAY - 60;
+        f.env.advance_block_time(last_minute - now);
+
+        for _ in 0..5 {
+            deposit(&mut f, user1, cspr(10_000));
+        }
+        assert_eq!(f.vault.max_deposit(user1), U512::zero(), "Daily limit used up");
+        f.env.set_caller(user1);
+        assert_eq!(
+            f.vault.with_tokens(cspr(100)).try_deposit(),
+            Err(VaultError::RateLimitExceeded.into())
+        );
+
+        // 00:01 the next day, two minutes later
+        f.env.advance_block_time(120);
+        assert_eq!(f.vault.max_deposit(user1), cspr(50_000), "New day, new bucket");
+        deposit(&mut f, user1, cspr(10_000));
+        assert_eq!(f.vault.max_deposit(user1), cspr(40_000));
+    }
+
+    #[test]
+    fn test_regular_small_deposits_never_lock_out() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+
+        // 2,000 CSPR every 12 hours for 30 days: 120,000 CSPR, never more
+        // than 4,000 in one day
+        for _ in 0..60 {
+            deposit(&mut f, user1, cspr(2000));
+            f.env.advance_block_time(12 * ONE_HOUR);
+        }
+        assert!(f.vault.max_deposit(user1) >= cspr(48_000), "At most one deposit in today's bucket");
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(120_000), "Every deposit accepted");
+    }
+
+    #[test]
+    fn test_fee_setters_enforce_caps_and_admin() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        f.env.set_caller(user1);
+        assert!(f.vault.try_set_fees(1500, 200, 50).is_err(), "Non-admin rejected");
+        assert!(f.vault.try_set_performance_fee(1500).is_err(), "Non-admin rejected");
+
+        f.env.set_caller(f.admin);
+        let invalid_fee = Err(VaultError::InvalidFee.into());
+        assert_eq!(f.vault.try_set_fees(5001, 200, 50), invalid_fee, "Performance cap");
+        assert_eq!(f.vault.try_set_fees(1000, 1001, 50), invalid_fee, "Management cap");
+        assert_eq!(f.vault.try_set_fees(1000, 200, 501), invalid_fee, "Instant cap");
+        assert_eq!(f.vault.try_set_instant_withdrawal_fee(501), invalid_fee, "Instant cap on single setter");
+        assert_eq!(f.vault.get_fees(), (1000, 200, 50), "Defaults untouched");
+
+        f.vault.set_fees(5000, 1000, 500);
+        assert_eq!(f.vault.get_fees(), (5000, 1000, 500), "Caps are inclusive");
+
+        f.vault.set_management_fee(100);
+        let event = f.env.get_event::<FeesUpdated>(f.vault.address(), -1).unwrap();
+        assert_eq!(event.old_management_fee_bps, 1000);
+        assert_eq!(event.management_fee_bps, 100);
+        assert_eq!(event.performance_fee_bps, 5000, "Other fees carried over");
+        assert_eq!(event.updated_by, f.admin);
+    }
+
+    #[test]
+    fn test_pending_request_keeps_fee_it_was_made_under() {
+        let mut f = setup_management_fee();
+        let treasury = f.env.get_account(6);
+
+        // Give the treasury fee shares: it has no cost basis, so all of its
+        // withdrawals are profit
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        f.vault.set_management_fee(0);
+        f.vault.set_fee_exempt(treasury, false);
+
+        f.env.set_caller(treasury);
+        let request_id = f.vault.request_withdrawal(cspr(10));
+        let requested = f.vault.get_withdrawal_request(request_id).unwrap();
+
+        f.env.set_caller(f.admin);
+        f.vault.set_performance_fee(2000);
+
+        f.env.set_caller(treasury);
+        f.env.advance_block_time(SEVEN_DAYS);
+        let before = f.vault.get_fee_breakdown().performance_fees;
+        f.vault.complete_withdrawal(request_id);
+        let old_rate_fee = f.vault.get_fee_breakdown().performance_fees - before;
+        assert_u512_eq(old_rate_fee, requested.assets_value / U512::from(10u64), "Request charged the old 10%");
+
+        let assets = f.vault.convert_to_assets(cspr(10));
+        let before = f.vault.get_fee_breakdown().performance_fees;
+        f.vault.withdraw(cspr(10));
+        let new_rate_fee = f.vault.get_fee_breakdown().performance_fees - before;
+        assert_u512_eq(new_rate_fee, assets / U512::from(5u64), "Later withdrawal charged the new 20%");
+    }
+
+    #[test]
+    fn test_treasury_rotation_migrates_fee_shares() {
+        let mut f = setup_management_fee();
+        let old_treasury = f.env.get_account(6);
+        let new_treasury = f.env.get_account(5);
+
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        let fee_shares = f.vault.get_user_shares(old_treasury);
+
+        f.vault.set_treasury(new_treasury, true);
+        assert_eq!(f.vault.get_treasury(), Some(new_treasury));
+        assert!(f.vault.is_fee_exempt(new_treasury) && !f.vault.is_fee_exempt(old_treasury), "Exemption moved");
+        assert_u512_eq(f.vault.get_user_shares(old_treasury), U512::zero(), "Old treasury emptied");
+        assert_u512_eq(f.vault.get_user_shares(new_treasury), fee_shares, "Fee shares moved");
+        assert_eq!(f.vault.get_unique_depositors(), 1, "Treasuries are not depositors");
+
+        let event = f.env.get_event::<TreasuryChanged>(f.vault.address(), -1).unwrap();
+        assert_eq!((event.old, event.new), (old_treasury, new_treasury));
+        assert_u512_eq(event.shares_migrated, fee_shares, "Event reports migrated shares");
+
+        // Later fees are minted to the new treasury, which can withdraw them
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        assert!(f.vault.get_user_shares(new_treasury) > fee_shares, "New fees go to the new treasury");
+        assert_u512_eq(f.vault.get_user_shares(old_treasury), U512::zero(), "Old treasury gets nothing");
+
+        f.env.set_caller(new_treasury);
+        f.vault.withdraw(fee_shares);
+        assert!(f.env.get_event::<Withdraw>(f.vault.address(), -1).is_ok());
+    }
+
+    #[test]
+    fn test_treasury_rotation_without_migration() {
+        let mut f = setup_management_fee();
+        let old_treasury = f.env.get_account(6);
+        let new_treasury = f.env.get_account(5);
+
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        let fee_shares = f.vault.get_user_shares(old_treasury);
+
+        f.vault.set_treasury(new_treasury, false);
+        assert_u512_eq(f.vault.get_user_shares(old_treasury), fee_shares, "Old treasury keeps its shares");
+        assert_u512_eq(f.vault.get_user_shares(new_treasury), U512::zero(), "Nothing moved");
+        assert_eq!(f.vault.get_unique_depositors(), 2, "Old treasury is now a regular holder");
+        let event = f.env.get_event::<TreasuryChanged>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.shares_migrated, U512::zero(), "No shares migrated");
+
+        // Rotating again with migration doesn't reach the first treasury's shares
+        f.vault.set_treasury(f.env.get_account(4), true);
+        assert_u512_eq(f.vault.get_user_shares(old_treasury), fee_shares, "First treasury untouched");
+    }
+
+    #[test]
+    fn test_treasury_rotation_rejects_invalid_addresses() {
+        let mut f = setup();
+        let new_treasury = f.env.get_account(5);
+        let vault_address = *f.vault.address();
+
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_set_treasury(new_treasury, true).is_err(), "Non-admin rejected");
+
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_set_treasury(Address::from([0u8; 32]), true).is_err(), "Zero address rejected");
+        assert!(f.vault.try_set_treasury(vault_address, true).is_err(), "Vault address rejected");
+        assert!(f.vault.try_set_treasury(f.env.get_account(6), true).is_err(), "Same treasury rejected");
+        assert_eq!(f.vault.get_treasury(), Some(f.env.get_account(6)));
+    }
+
+    #[test]
+    fn test_treasury_exits_without_fees() {
+        let mut f = setup_management_fee();
+        let treasury = f.env.get_account(6);
+        assert_eq!(f.vault.get_fee_exempt_accounts(), vec![treasury], "Treasury exempt by default");
+
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        let fees_before = f.vault.get_fee_breakdown();
+
+        f.env.set_caller(treasury);
+        let assets = f.vault.convert_to_assets(cspr(10));
+        assert_u512_eq(f.vault.withdraw(cspr(10)), assets, "No performance fee on fee shares");
+        assert!(f.env.get_event::<Withdraw>(f.vault.address(), -1).unwrap().fee_exempt);
+
+        let assets = f.vault.convert_to_assets(cspr(5));
+        assert_u512_eq(f.vault.instant_withdraw(cspr(5)), assets, "No instant or performance fee");
+        let event = f.env.get_event::<InstantWithdrawal>(f.vault.address(), -1).unwrap();
+        assert!(event.fee_exempt);
+        assert_u512_eq(event.fee, U512::zero(), "Event reports no fee");
+
+        let request_id = f.vault.request_withdrawal(cspr(5));
+        let requested = f.vault.get_withdrawal_request(request_id).unwrap().assets_value;
+        f.env.advance_block_time(SEVEN_DAYS);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), requested, "Request completes fee-free");
+        assert!(f.env.get_event::<WithdrawalCompleted>(f.vault.address(), -1).unwrap().fee_exempt);
+
+        assert_eq!(f.vault.get_fee_breakdown(), fees_before, "Nothing booked");
+    }
+
+    #[test]
+    fn test_fee_exemption_set_management() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+        deposit(&mut f, user1, cspr(1000));
+        deposit(&mut f, user2, cspr(1000));
+
+        // 10% profit for both holders
+        f.env.set_caller(f.admin);
+        f.vault.test_set_total_assets(cspr(2200));
+        f.vault.set_fee_exempt(user1, true);
+        let event = f.env.get_event::<FeeExemptionChanged>(f.vault.address(), -1).unwrap();
+        assert_eq!((event.account, event.exempt, event.caller), (user1, true, f.admin));
+
+        let value = f.vault.preview_redeem(cspr(100));
+        assert_u512_eq(f.vault.preview_withdraw(user1, cspr(100)), value, "Exempt exit keeps all");
+        let charged = f.vault.preview_withdraw(user2, cspr(100));
+        assert!(charged < value, "Other holders still pay");
+
+        f.env.set_caller(user1);
+        let fees_before = f.vault.get_fees_collected();
+        f.vault.withdraw(cspr(100));
+        assert_u512_eq(f.vault.get_fees_collected(), fees_before, "Exempt exit books nothing");
+
+        f.env.set_caller(f.admin);
+        f.vault.set_fee_exempt(user1, false);
+        assert!(!f.env.get_event::<FeeExemptionChanged>(f.vault.address(), -1).unwrap().exempt);
+        f.env.set_caller(user1);
+        f.vault.withdraw(cspr(100));
+        assert!(f.vault.get_fees_collected() > fees_before, "Fee charged once the exemption is lifted");
+        assert!(!f.env.get_event::<Withdraw>(f.vault.address(), -1).unwrap().fee_exempt);
+    }
+
+    #[test]
+    fn test_fee_exemption_set_is_admin_only_and_bounded() {
+        let mut f = setup();
+        let treasury = f.env.get_account(6);
+
+        f.env.set_caller(f.user1);
+        assert_eq!(f.vault.try_set_fee_exempt(f.user1, true), Err(AccessError::MissingRole.into()));
+
+        f.env.set_caller(f.admin);
+        assert!(f.vault.try_set_fee_exempt(treasury, true).is_err(), "Already exempt");
+        assert!(f.vault.try_set_fee_exempt(f.user1, false).is_err(), "Not exempt");
+
+        // The treasury holds one of the slots
+        for i in 1..MAX_FEE_EXEMPT_ACCOUNTS {
+            f.vault.set_fee_exempt(Address::from([i as u8; 32]), true);
+        }
+        assert_eq!(f.vault.get_fee_exempt_accounts().len(), MAX_FEE_EXEMPT_ACCOUNTS);
+        assert_eq!(f.vault.try_set_fee_exempt(f.user1, true), Err(VaultError::RateLimitExceeded.into()));
+
+        f.vault.set_fee_exempt(treasury, false);
+        f.vault.set_fee_exempt(f.user1, true);
+        assert!(!f.vault.get_fee_exempt_accounts().contains(&treasury));
+    }
+
+    /// Vault with one 1000 CSPR holder and the admin allowed to collect fees
+    fn setup_management_fee() -> VaultFixture {
+        let mut f = setup();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(KEEPER_ROLE, f.admin);
+        f
+    }
+
+    #[test]
+    fn test_management_fee_takes_exact_share_of_assets() {
+        let mut f = setup_management_fee();
+        let treasury = f.env.get_account(6);
+
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+
+        // Holders lose exactly 2% of assets, which the treasury now owns
+        let user_assets = f.vault.convert_to_assets(f.vault.get_user_shares(f.user1));
+        let treasury_assets = f.vault.convert_to_assets(f.vault.get_user_shares(treasury));
+        assert_u512_eq(user_assets, cspr(980), "Holder value drops by the fee");
+        assert_u512_within_tolerance(treasury_assets, cspr(20), 1);
+        assert_u512_eq(f.vault.total_assets(), cspr(1000), "Fee moves value, not assets");
+    }
+
+    #[test]
+    fn test_whale_between_collections_pays_management_fee() {
+        let mut f = setup_management_fee();
+        let (user1, whale) = (f.user1, f.user2);
+        let treasury = f.env.get_account(6);
+
+        // Whale enters right after a collection...
+        f.env.advance_block_time(ONE_YEAR);
+        f.vault.collect_management_fees();
+        let shares = deposit(&mut f, whale, cspr(10_000));
+        let treasury_before = f.vault.get_user_shares(treasury);
+
+        // ...and leaves 29 days later, before the next one
+        f.env.advance_block_time(29 * 24 * ONE_HOUR);
+        f.env.set_caller(whale);
+        let received = f.vault.withdraw(shares);
+
+        // 2% a year for 29 days on 10,000 CSPR
+        let expected_fee = cspr(10_000) * U512::from(200u64 * 29) / U512::from(10_000u64 * 365);
+        assert_u512_within_tolerance(cspr(10_000) - received, expected_fee, 1);
+        assert!(f.vault.get_user_shares(treasury) > treasury_before, "Fee shares minted on the exit");
+
+        // The remaining holder is charged for its own balance only
+        let user_assets = f.vault.convert_to_assets(f.vault.get_user_shares(user1));
+        let user_fee = cspr(980) * U512::from(200u64 * 29) / U512::from(10_000u64 * 365);
+        assert_u512_within_tolerance(cspr(980) - user_assets, user_fee, 1);
+    }
+
+    #[test]
+    fn test_monthly_management_fees_match_annual() {
+        let mut monthly = setup_management_fee();
+        for _ in 0..12 {
+            monthly.env.advance_block_time(ONE_YEAR / 12);
+            monthly.vault.collect_management_fees();
+        }
+
+        let mut annual = setup_management_fee();
+        annual.env.advance_block_time(ONE_YEAR);
+        annual.vault.collect_management_fees();
+
+        // Each monthly fee is charged on a base already net of earlier fees,
+        // so holders keep ~980.18 vs 980 CSPR
+        let monthly_assets = monthly.vault.convert_to_assets(monthly.vault.get_user_shares(monthly.user1));
+        let annual_assets = annual.vault.convert_to_assets(annual.vault.get_user_shares(annual.user1));
+        assert_u512_within_tolerance(monthly_assets, annual_assets, 2);
+        assert!(monthly_assets >= annual_assets, "Compounding never overcharges");
+    }
+
+    /// $0.02 per CSPR quoted with 8 decimals
+    fn deploy_price_oracle(f: &VaultFixture) -> MockPriceOracleHostRef {
+        SystemBuilder::new(&f.env).with_price_oracle(U512::from(2_000_000u64), 8).build().price_oracle.unwrap()
+    }
+
+    #[test]
+    fn test_usd_deposit_limits_follow_price() {
+        let mut f = setup();
+        let user1 = f.user1;
+        let mut oracle = deploy_price_oracle(&f);
+
+        assert!(f.vault.try_set_usd_deposit_limits(true, cspr(200), cspr(1000)).is_err(), "Needs an oracle");
+        f.vault.set_price_oracle(*oracle.address(), ONE_HOUR);
+        f.vault.set_usd_deposit_limits(true, cspr(200), cspr(1000));
+
+        // $200 at $0.02 is 10,000 CSPR
+        deposit(&mut f, user1, cspr(6000));
+        let event = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap();
+        assert_eq!(event.limit_mode, LIMIT_MODE_USD);
+        assert_u512_eq(event.limit_applied, cspr(10000), "USD limit in CSPR");
+
+        // Price doubles: the same USD allowance buys half the CSPR
+        oracle.set_price(U512::from(4_000_000u64));
+        f.env.set_caller(user1);
+        assert!(f.vault.with_tokens(cspr(6000)).try_deposit().is_err(), "Above the halved limit");
+        deposit(&mut f, user1, cspr(5000));
+        let event = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.limit_applied, cspr(5000), "Per-transaction limit halved");
+
+        let (_, per_day, mode) = f.vault.deposit_limits();
+        assert_u512_eq(per_day, cspr(25000), "$1000 at $0.04");
+        assert_eq!(mode, LIMIT_MODE_USD);
+    }
+
+    #[test]
+    fn test_stale_price_falls_back_to_cspr_limits() {
+        let mut f = setup();
+        let user1 = f.user1;
+        let mut oracle = deploy_price_oracle(&f);
+        oracle.set_price(U512::from(4_000_000u64));
+
+        f.env.set_caller(user1);
+        assert!(f.vault.try_set_price_oracle(*oracle.address(), ONE_HOUR).is_err(), "Admin only");
+        f.env.set_caller(f.admin);
+        f.vault.set_price_oracle(*oracle.address(), ONE_HOUR);
+        f.vault.set_usd_deposit_limits(true, cspr(200), cspr(1000));
+
+        f.env.advance_block_time(ONE_HOUR + 1);
+        deposit(&mut f, user1, cspr(6000));
+        let event = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap();
+        assert_eq!(event.limit_mode, LIMIT_MODE_CSPR_FALLBACK);
+        assert_u512_eq(event.limit_applied, cspr(10000), "Default CSPR limit");
+
+        // A fresh price restores USD mode
+        oracle.set_price(U512::from(4_000_000u64));
+        assert!(f.vault.with_tokens(cspr(6000)).try_deposit().is_err(), "USD limit back in force");
+    }
+
+    #[test]
+    fn test_precise_round_trip_loses_at_most_one_mote() {
+        let mut f = setup();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+
+        let amounts = [
+            U512::one(),
+            U512::from(7u64),
+            U512::from(999_999_999u64),
+            U512::from(123_456_789_012u64),
+            cspr(1_000_000),
+        ];
+
+        // Profit raises the share price from 1.0 to roughly 1.001, 1.33, 13.3 and 1000
+        for profit in [U512::zero(), cspr(1), U512::from(333_333_333_333u64), cspr(12_000), cspr(987_000)] {
+            if !profit.is_zero() {
+                f.env.set_caller(f.admin);
+                f.vault.report_profit(profit);
+                f.env.advance_block_time(6 * ONE_HOUR);
+            }
+
+            let price = f.vault.get_share_price_precise();
+            for assets in amounts.iter() {
+                let shares = f.vault.convert_to_shares_precise(*assets);
+                let back = f.vault.convert_to_assets_precise(shares);
+                assert!(back <= *assets, "Round trip never creates value at price {}", price);
+                assert!(*assets - back <= U512::one(), "Lost more than 1 mote at price {}", price);
+            }
+        }
+
+        // The 1e9 price is the 1e18 price truncated
+        let precise = f.vault.get_share_price_precise();
+        assert_eq!(U512::from((precise / 1_000_000_000u64).as_u128()), f.vault.get_share_price());
+    }
+
+    /// user1 with 1,000 deposited, two unlocked requests (one auto-complete)
+    /// and a frozen account, on a `setup_without_fees` fixture; returns
+    /// (guardian, keeper, request, auto request)
+    fn setup_frozen_user(f: &mut VaultFixture) -> (Address, Address, u64, u64) {
+        let (guardian, keeper) = (f.env.get_account(4), f.env.get_account(3));
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(GUARDIAN_ROLE, guardian);
+        f.vault.grant_role(KEEPER_ROLE, keeper);
+
+        let user1 = f.user1;
+        deposit(f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(100));
+        let auto_request_id = f.vault.request_withdrawal_with_auto_complete(cspr(100), true);
+        f.env.advance_block_time(SEVEN_DAYS);
+
+        f.env.set_caller(guardian);
+        f.vault.freeze_account(user1, "Suspected exploit".to_string());
+
+        (guardian, keeper, request_id, auto_request_id)
+    }
+
+    #[test]
+    fn test_frozen_account_blocked_on_every_entrypoint() {
+        let mut f = setup_without_fees();
+        let (user1, user2) = (f.user1, f.user2);
+        let (guardian, keeper, request_id, auto_request_id) = setup_frozen_user(&mut f);
+
+        let event = f.env.get_event::<AccountFrozen>(f.vault.address(), -1).unwrap();
+        assert_eq!((event.account, event.caller), (user1, guardian));
+        assert_eq!(event.reason, "Suspected exploit");
+        assert_eq!(event.timestamp, f.env.get_block_time());
+        assert_eq!(f.vault.get_frozen_accounts(), vec![user1]);
+
+        let frozen = Err(VaultError::AccountFrozen.into());
+        f.env.set_caller(user1);
+        assert_eq!(f.vault.with_tokens(cspr(100)).try_deposit(), frozen, "deposit");
+        assert_eq!(f.vault.try_withdraw(cspr(100)), frozen, "withdraw");
+        assert_eq!(f.vault.try_instant_withdraw(cspr(100)), frozen, "instant_withdraw");
+        assert_eq!(f.vault.try_request_withdrawal(cspr(100)), frozen, "request_withdrawal");
+        assert_eq!(f.vault.try_complete_withdrawal(request_id), frozen, "complete_withdrawal");
+        assert_eq!(f.vault.try_transfer_withdrawal_request(request_id, user2), frozen, "transfer out");
+
+        f.env.set_caller(keeper);
+        assert_eq!(f.vault.try_complete_withdrawal_for(auto_request_id), frozen, "keeper completion");
+
+        // Shares stay put and other holders are unaffected
+        assert_u512_eq(f.vault.get_user_shares(user1), cspr(800), "Shares intact");
+        assert!(deposit(&mut f, user2, cspr(500)) > U512::zero(), "Others deposit as usual");
+    }
+
+    #[test]
+    fn test_unfreeze_restores_full_access() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        let (_, keeper, request_id, auto_request_id) = setup_frozen_user(&mut f);
+
+        f.env.set_caller(f.admin);
+        f.vault.unfreeze_account(user1);
+        let event = f.env.get_event::<AccountUnfrozen>(f.vault.address(), -1).unwrap();
+        assert_eq!((event.account, event.caller), (user1, f.admin));
+        assert!(f.vault.get_frozen_accounts().is_empty());
+        assert!(!f.vault.is_frozen(user1));
+
+        f.env.set_caller(keeper);
+        assert_u512_eq(f.vault.complete_withdrawal_for(auto_request_id), cspr(100), "Keeper completion");
+
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(100), "Own completion");
+        assert!(f.vault.withdraw(cspr(100)) > U512::zero(), "Withdraw");
+        assert!(f.vault.instant_withdraw(cspr(100)) > U512::zero(), "Instant withdraw");
+        f.vault.request_withdrawal(cspr(100));
+        f.vault.with_tokens(cspr(100)).deposit();
+    }
+
+    #[test]
+    fn test_freeze_restrictions() {
+        let mut f = setup();
+        let user1 = f.user1;
+        let guardian = f.env.get_account(4);
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(GUARDIAN_ROLE, guardian);
+
+        f.env.set_caller(guardian);
+        let treasury = f.vault.get_treasury().unwrap();
+        assert_eq!(
+            f.vault.try_freeze_account(treasury, "no".to_string()),
+            Err(VaultError::InvalidRequest.into()),
+            "Treasury can't be frozen",
+        );
+        let vault_address = *f.vault.address();
+        assert_eq!(
+            f.vault.try_freeze_account(vault_address, "no".to_string()),
+            Err(VaultError::InvalidRequest.into()),
+            "Vault can't be frozen",
+        );
+
+        f.vault.freeze_account(user1, "Incident".to_string());
+        assert!(f.vault.try_freeze_account(user1, "Again".to_string()).is_err(), "Already frozen");
+
+        f.env.set_caller(f.user2);
+        assert_eq!(
+            f.vault.try_freeze_account(f.user2, "Self".to_string()),
+            Err(VaultError::Unauthorized.into()),
+            "Guardian or admin only",
+        );
+        assert_eq!(f.vault.try_unfreeze_account(user1), Err(VaultError::Unauthorized.into()));
+    }
+
+    #[test]
+    fn test_matured_request_completes_while_paused() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+        let guardian = f.env.get_account(4);
+        f.env.set_caller(f.admin);
+        f.vault.grant_role(GUARDIAN_ROLE, guardian);
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+
+        f.env.set_caller(guardian);
+        f.vault.pause();
+        assert!(f.vault.is_paused());
+
+        // New activity stays blocked
+        f.env.set_caller(user1);
+        assert_eq!(f.vault.with_tokens(cspr(10)).try_deposit(), Err(VaultError::Paused.into()));
+        assert_eq!(f.vault.try_request_withdrawal(cspr(100)), Err(VaultError::Paused.into()));
+        assert_eq!(f.vault.try_complete_withdrawal(request_id), Err(VaultError::Unauthorized.into()), "Still timelocked");
+
+        f.env.advance_block_time(SEVEN_DAYS);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(400), "Matured request paid while paused");
+
+        f.env.set_caller(guardian);
+        assert_eq!(f.vault.try_unpause(), Err(AccessError::MissingRole.into()), "Admin only unpause");
+        f.env.set_caller(f.admin);
+        f.vault.unpause();
+        assert!(!f.vault.is_paused());
+    }
+
+    #[test]
+    fn test_withdrawal_freeze_blocks_matured_requests() {
+        let mut f = setup_without_fees();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+        f.env.advance_block_time(SEVEN_DAYS);
+
+        assert_eq!(f.vault.try_freeze_withdrawals(), Err(VaultError::Unauthorized.into()));
+        f.env.set_caller(f.admin);
+        f.vault.pause();
+        f.vault.freeze_withdrawals();
+        assert!(f.vault.are_withdrawals_frozen());
+
+        f.env.set_caller(user1);
+        assert_eq!(
+            f.vault.try_complete_withdrawal(request_id),
+            Err(VaultError::WithdrawalsFrozen.into()),
+            "Distinct from the pause error",
+        );
+
+        // Lifting the freeze alone is enough; the pause stays on
+        f.env.set_caller(f.admin);
+        f.vault.unfreeze_withdrawals();
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(400), "Paid once unfrozen");
+        assert!(f.vault.is_paused());
+    }
+
+    #[test]
+    fn test_pool_metrics_track_served_and_rejected_exits() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        // 5% target: the pool goes 50 -> 100 CSPR
+        deposit(&mut f, user1, cspr(1000));
+        deposit(&mut f, user2, cspr(1000));
+
+        f.env.set_caller(user2);
+        assert_u512_eq(f.vault.instant_withdraw_if_liquid(cspr(200)), U512::zero(), "Soft exit pays nothing");
+        let rejected = f.env.get_event::<InstantWithdrawalRejected>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(rejected.assets_requested, cspr(200), "Requested assets in the event");
+        assert_u512_eq(rejected.pool_balance, cspr(100), "Pool balance in the event");
+        assert_u512_eq(f.vault.shares_of(user2), cspr(1000), "Shares untouched");
+
+        // Pool 100 -> 60, then a reverting exit that can't be counted
+        f.vault.instant_withdraw(cspr(40));
+        assert_eq!(f.vault.try_instant_withdraw(cspr(100)), Err(VaultError::InsufficientLiquidity.into()));
+        // Pool 60 -> 30
+        assert!(f.vault.instant_withdraw_if_liquid(cspr(30)) > U512::zero(), "Soft exit served");
+
+        let metrics = f.vault.get_pool_metrics();
+        assert_eq!(metrics.instant_withdrawals_served, 2, "Served");
+        assert_u512_eq(metrics.instant_volume_served, cspr(70), "Volume");
+        assert_eq!(metrics.liquidity_rejections, 1, "Only the soft rejection counted");
+        assert_eq!(metrics.samples, 4, "Two deposits and two exits sampled");
+        assert_u512_eq(metrics.min_pool_balance, cspr(30), "Min");
+        assert_u512_eq(metrics.max_pool_balance, cspr(100), "Max");
+        assert_u512_eq(metrics.avg_pool_balance, cspr(60), "Avg of 50, 100, 60, 30");
+        assert_u512_eq(metrics.current_pool_balance, cspr(30), "Current");
+    }
+
+    #[test]
+    fn test_reset_pool_metrics_starts_new_window() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+        deposit(&mut f, user1, cspr(1000));
+        f.env.set_caller(user1);
+        f.vault.instant_withdraw_if_liquid(cspr(500));
+        f.vault.instant_withdraw(cspr(10));
+
+        f.env.set_caller(user2);
+        assert!(f.vault.try_reset_pool_metrics().is_err(), "Admin only");
+
+        f.env.advance_block_time(ONE_DAY);
+        let now = f.env.get_block_time();
+        f.env.set_caller(f.admin);
+        f.vault.reset_pool_metrics();
+
+        let metrics = f.vault.get_pool_metrics();
+        assert_eq!(metrics.instant_withdrawals_served, 0, "Served cleared");
+        assert_u512_eq(metrics.instant_volume_served, U512::zero(), "Volume cleared");
+        assert_eq!(metrics.liquidity_rejections, 0, "Rejections cleared");
+        assert_eq!(metrics.samples, 0, "Samples cleared");
+        assert_u512_eq(metrics.avg_pool_balance, U512::zero(), "No samples, no average");
+        assert_u512_eq(metrics.current_pool_balance, cspr(40), "Pool itself untouched");
+        assert_eq!(metrics.since, now, "New window starts now");
+
+        // The first sample of the new window sets both bounds
+        deposit(&mut f, user2, cspr(1000));
+        let metrics = f.vault.get_pool_metrics();
+        assert_eq!(metrics.samples, 1);
+        assert_eq!(metrics.min_pool_balance, metrics.max_pool_balance, "Single sample");
+    }
+
+    /// user1 and user2 hold 1,000 each; user1 requests 400 shares, then the
+    /// share price moves by `profit` or `loss` and the request unlocks
+    fn request_then_move_price(profit: U512, loss: U512) -> (VaultFixture, u64) {
+        let mut f = setup_without_fees();
+        let (user1, user2) = (f.user1, f.user2);
+        deposit(&mut f, user1, cspr(1000));
+        deposit(&mut f, user2, cspr(1000));
+
+        f.env.set_caller(user1);
+        let request_id = f.vault.request_withdrawal(cspr(400));
+        assert_u512_eq(f.vault.get_withdrawal_request(request_id).unwrap().assets_value, cspr(400), "Snapshot");
+
+        f.env.set_caller(f.admin);
+        if !profit.is_zero() {
+            f.vault.report_profit(profit);
+        }
+        if !loss.is_zero() {
+            f.vault.report_loss(loss);
+        }
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.env.set_caller(user1);
+        (f, request_id)
+    }
+
+    #[test]
+    fn test_request_does_not_capture_gains_during_lock() {
+        // +10%
+        let (mut f, request_id) = request_then_move_price(cspr(200), U512::zero());
+        assert_u512_eq(f.vault.convert_to_assets(cspr(400)), cspr(440), "Shares are worth 10% more");
+
+        assert_u512_eq(f.vault.preview_complete_withdrawal(request_id), cspr(400), "Preview capped");
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(400), "Paid the request value");
+        // The forgone 40 CSPR stays with the shares still in the vault
+        assert_u512_eq(f.vault.total_assets(), cspr(1800), "Only the request value left");
+        assert_u512_eq(f.vault.get_user_assets(f.user2), cspr(1125), "Holder keeps gain plus forgone yield");
+    }
+
+    #[test]
+    fn test_request_shares_losses_during_lock() {
+        // -10%
+        let (mut f, request_id) = request_then_move_price(U512::zero(), cspr(200));
+        assert_u512_eq(f.vault.convert_to_assets(cspr(400)), cspr(360), "Shares are worth 10% less");
+
+        assert_u512_eq(f.vault.preview_complete_withdrawal(request_id), cspr(360), "Preview follows the price");
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(360), "No snapshot protection");
+        assert_u512_eq(f.vault.get_user_assets(f.user2), cspr(900), "Holder bears only their own share");
+    }
+
+    struct LstVaultFixture {
+        env: HostEnv,
+        user: Address,
+        lst: LstCsprHostRef,
+        staking: MockLiquidStakingHostRef,
+        vault: VaultManagerHostRef,
+    }
+
+    /// Vault whose asset is a real lstCSPR token, priced at 1.1 CSPR per
+    /// lstCSPR by a mock LiquidStaking, deploying to one mock strategy.
+    /// The user holds 20,000 lstCSPR and no allowance.
+    fn setup_lst_vault() -> LstVaultFixture {
+        let env = odra_test::env();
+        let user = env.get_account(1);
+
+        let built = SystemBuilder::new(&env)
+            .with_lst()
+            .with_mock_liquid_staking()
+            .with_vault()
+            .with_router()
+            .with_mock_strategy("dex", 1000)
+            .build();
+        let mut lst = built.lst.unwrap();
+        let mut staking = built.mock_liquid_staking.unwrap();
+        let mut router = built.router.unwrap();
+        let mut vault = built.vault.unwrap();
+        lst.mint(user, cspr(20_000));
+        staking.set_exchange_rate(U256::from(1_100_000_000u64));
+        router.set_idle_buffer_pct(0);
+        router.set_target_allocations(vec![("dex".to_string(), 100u8)]);
+        vault.set_min_holding_period(0);
+        // Keep the round trip exact
+        vault.set_management_fee(0);
+
+        LstVaultFixture { env, user, lst, staking, vault }
+    }
+
+    fn deposit_lst(f: &mut LstVaultFixture, amount: U512) -> U512 {
+        f.env.set_caller(f.user);
+        f.lst.approve(*f.vault.address(), amount);
+        f.vault.deposit_lst(amount)
+    }
+
+    #[test]
+    fn test_lst_deposit_round_trip_preserves_value() {
+        let mut f = setup_lst_vault();
+
+        let shares = deposit_lst(&mut f, cspr(1000));
+        assert_u512_eq(shares, cspr(1000), "Shares at the CSPR deposit rate");
+        assert_u512_eq(f.lst.balance_of(*f.vault.address()), cspr(1000), "lstCSPR pulled into the vault");
+        assert_u512_eq(f.vault.get_user_deposit(f.user).unwrap().cost_basis, cspr(1100), "Basis in CSPR");
+
+        let event = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.cspr_amount, cspr(1100), "CSPR value reported");
+        assert_u512_eq(event.lst_cspr_amount, cspr(1000), "lstCSPR deposited");
+
+        // Most of the deposit sits in the strategy; the router covers the exit
+        let payout = f.vault.withdraw_as_lst(shares);
+        assert_u512_within_tolerance(payout, cspr(1000), 1);
+        assert_u512_within_tolerance(f.lst.balance_of(f.user), cspr(20_000), 1);
+        assert_u512_eq(f.vault.get_user_shares(f.user), U512::zero(), "Shares burned");
+    }
+
+    #[test]
+    fn test_lst_deposits_share_rate_limits_with_cspr() {
+        let mut f = setup_lst_vault();
+
+        // 10,000 CSPR per transaction: 9,091 lstCSPR is worth 10,000.1 CSPR
+        f.env.set_caller(f.user);
+        f.lst.approve(*f.vault.address(), cspr(9091));
+        assert_eq!(f.vault.try_deposit_lst(cspr(9091)), Err(VaultError::RateLimitExceeded.into()));
+
+        // CSPR and lstCSPR deposits draw on the same 50,000 CSPR daily bucket
+        for _ in 0..4 {
+            f.vault.with_tokens(cspr(10_000)).deposit();
+        }
+        assert_u512_eq(f.vault.max_deposit(f.user), cspr(10_000), "Bucket after CSPR deposits");
+
+        deposit_lst(&mut f, cspr(9000));
+        assert_u512_eq(f.vault.max_deposit(f.user), cspr(100), "9,000 lstCSPR counted as 9,900 CSPR");
+
+        f.lst.approve(*f.vault.address(), cspr(91));
+        assert_eq!(f.vault.try_deposit_lst(cspr(91)), Err(VaultError::RateLimitExceeded.into()));
+        deposit_lst(&mut f, cspr(90));
+        assert_u512_eq(f.vault.max_deposit(f.user), cspr(1), "Bucket nearly used up");
+    }
+
+    #[test]
+    fn test_cspr_deposit_stakes_at_exchange_rate() {
+        let mut f = setup_lst_vault();
+        f.vault.set_fees(0, 0, 0);
+
+        // 1,100 CSPR buys 1,000 lstCSPR at 1.1, and the vault books lstCSPR
+        f.env.set_caller(f.user);
+        let shares = f.vault.with_tokens(cspr(1100)).deposit();
+        assert_u512_eq(shares, cspr(1000), "Shares follow the lstCSPR received");
+        assert_u512_eq(f.vault.total_assets(), cspr(1000), "Assets in lstCSPR");
+        let event = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.cspr_amount, cspr(1100), "CSPR paid in");
+        assert_u512_eq(event.lst_cspr_amount, cspr(1000), "Converted at the rate");
+
+        // The exit is paid in CSPR at the same rate
+        assert_u512_eq(f.vault.withdraw(shares), cspr(1100), "Round trip at an unchanged rate");
+        assert_u512_eq(f.vault.total_assets(), U512::zero(), "Nothing left behind");
+    }
+
+    /// The pool holds lstCSPR, so staking rewards raise what it can pay
+    /// without any top-up
+    #[test]
+    fn test_instant_pool_appreciates_with_exchange_rate() {
+        let mut f = setup_lst_vault();
+        f.staking.set_exchange_rate(U256::from(1_000_000_000u64));
+        f.vault.set_fees(0, 0, 0);
+
+        deposit_lst(&mut f, cspr(9000));
+        assert_u512_eq(f.vault.get_instant_pool_balance(), cspr(450), "5% kept in the pool");
+        assert_u512_eq(f.vault.get_instant_pool_value_cspr(), cspr(450), "At par");
+
+        f.env.set_caller(f.env.get_account(0));
+        f.staking.set_exchange_rate(U256::from(1_100_000_000u64));
+        assert_u512_eq(f.vault.get_instant_pool_balance(), cspr(450), "Same lstCSPR");
+        assert_u512_eq(f.vault.get_instant_pool_value_cspr(), cspr(495), "Worth 10% more CSPR");
+        assert_u512_eq(f.vault.total_assets(), cspr(9000), "Assets still counted in lstCSPR");
+
+        // The whole pool serves 495 CSPR of exits instead of 450
+        f.env.set_caller(f.user);
+        assert_u512_eq(f.vault.instant_withdraw(cspr(450)), cspr(495), "Appreciation paid out");
+        let event = f.env.get_event::<InstantWithdrawal>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.assets, cspr(450), "lstCSPR out of the pool");
+        assert_u512_eq(event.cspr_amount, cspr(495), "CSPR at the new rate");
+
+        let composition = f.vault.get_asset_composition();
+        assert_u512_eq(composition.pool_assets, U512::zero(), "Pool drained");
+        assert_u512_eq(composition.total_assets, cspr(8550), "Only the pool's lstCSPR left");
+        assert_u512_eq(
+            composition.pool_assets + composition.deployed_assets + composition.idle_lst_assets + composition.in_transit_assets,
+            composition.total_assets,
+            "Buckets still add up",
+        );
+    }
+
+    #[test]
+    fn test_deposit_receipt_on_first_deposit() {
+        let mut f = setup_lst_vault();
+
+        // 1,100 CSPR stakes into 1,000 lstCSPR; 5% of it refills the empty pool
+        f.env.set_caller(f.user);
+        let receipt = f.vault.with_tokens(cspr(1100)).deposit_with_receipt();
+        assert_u512_eq(receipt.shares_minted, cspr(1000), "Minted 1:1 into an empty vault");
+        assert_eq!(receipt.share_price_at_deposit, U256::from(1_000_000_000_000_000_000u64), "Initial price");
+        assert_u512_eq(receipt.assets_accepted, cspr(1000), "lstCSPR at the exchange rate");
+        assert_u512_eq(receipt.kept_in_pool, cspr(50), "Pool filled to its 5% target");
+        assert_u512_eq(receipt.deployed_to_strategies, cspr(950), "Rest routed");
+        assert_u512_eq(
+            receipt.deployed_to_strategies + receipt.kept_in_pool,
+            receipt.assets_accepted,
+            "Every lstCSPR accounted for",
+        );
+        assert_u512_eq(receipt.remaining_daily_allowance, cspr(48_900), "CSPR left of the 50,000 bucket");
+
+        // The receipt agrees with what the vault booked
+        assert_u512_eq(f.vault.get_user_shares(f.user), receipt.shares_minted, "Shares booked");
+        assert_u512_eq(f.vault.get_instant_pool_balance(), receipt.kept_in_pool, "Pool booked");
+        let event = f.env.get_event::<Deposit>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.shares_minted, receipt.shares_minted, "Event and receipt agree");
+    }
+
+    #[test]
+    fn test_deposit_receipt_after_yield() {
+        let mut f = setup_lst_vault();
+        f.env.set_caller(f.user);
+        f.vault.with_tokens(cspr(1100)).deposit();
+
+        // 10% yield, fully unlocked
+        f.env.set_caller(f.env.get_account(0));
+        f.vault.report_profit(cspr(100));
+        f.env.advance_block_time(6 * ONE_HOUR);
+
+        f.env.set_caller(f.user);
+        let receipt = f.vault.with_tokens(cspr(1100)).deposit_with_receipt();
+        assert_eq!(
+            receipt.share_price_at_deposit,
+            U256::from(1_100_000_000_000_000_000u64),
+            "1,100 lstCSPR over 1,000 shares"
+        );
+        // 1,000 lstCSPR at 1.1 lstCSPR per share, rounded down
+        assert_u512_eq(receipt.shares_minted, U512::from(909_090_909_090u64), "Fewer shares after yield");
+        assert_u512_eq(receipt.assets_accepted, cspr(1000), "Same lstCSPR per CSPR");
+        // Target is 5% of 2,100 lstCSPR; the pool already holds 50
+        assert_u512_eq(receipt.kept_in_pool, cspr(55), "Only the pool deficit kept");
+        assert_u512_eq(receipt.deployed_to_strategies, cspr(945), "Rest routed");
+    }
+
+    #[test]
+    fn test_withdrawal_receipts_itemise_fees() {
+        let mut f = setup_without_fees();
+        let (user1, user2) = (f.user1, f.user2);
+        f.vault.set_instant_pool_target(5000);
+        deposit(&mut f, user1, cspr(1000));
+        deposit(&mut f, user2, cspr(4000));
+
+        // No gain: only the 0.5% instant fee
+        f.env.set_caller(user2);
+        let receipt = f.vault.instant_withdraw_with_receipt(cspr(1000));
+        assert_u512_eq(receipt.shares_burned, cspr(1000), "Shares burned");
+        assert_u512_eq(receipt.gross_assets, cspr(1000), "Valued at par");
+        assert_u512_eq(receipt.instant_fee, cspr(5), "0.5% instant fee");
+        assert_u512_eq(receipt.performance_fee, U512::zero(), "No profit, no fee");
+        assert_u512_eq(receipt.net_assets, cspr(995), "Gross less fees");
+        assert_u512_eq(receipt.net_cspr, cspr(995), "1:1 without LiquidStaking");
+
+        // 10% yield: the performance fee takes 10% of user1's gain
+        f.env.set_caller(f.admin);
+        f.vault.report_profit(cspr(400));
+        f.env.advance_block_time(6 * ONE_HOUR);
+        let gross = f.vault.convert_to_assets(cspr(1000));
+        let fee = (gross - cspr(1000)) / U512::from(10u64);
+
+        f.env.set_caller(user1);
+        let receipt = f.vault.withdraw_with_receipt(cspr(1000));
+        assert_u512_eq(receipt.gross_assets, gross, "Full position");
+        assert_u512_eq(receipt.performance_fee, fee, "10% of the gain");
+        assert_u512_eq(receipt.instant_fee, U512::zero(), "No instant fee on the standard path");
+        assert_u512_eq(receipt.net_assets, gross - fee, "Gross less the fee");
+        assert_u512_eq(receipt.net_cspr, receipt.net_assets, "1:1 without LiquidStaking");
+        let event = f.env.get_event::<Withdraw>(f.vault.address(), -1).unwrap();
+        assert_u512_eq(event.assets, receipt.net_assets, "Event and receipt agree");
+    }
+
+    /// 10,000 CSPR vault (user1 9,000, user2 1,000) charging 0.25% above
+    /// 5% of TVL and 0.75% above 15%
+    fn setup_exit_fee_tiers() -> VaultFixture {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+        assert!(f.vault.get_exit_fee_tiers().is_empty(), "Off by default");
+        f.vault.set_exit_fee_tiers(vec![(500, 25), (1500, 75)]);
+        deposit(&mut f, user1, cspr(9000));
+        deposit(&mut f, user2, cspr(1000));
+        f
+    }
+
+    #[test]
+    fn test_exit_below_first_tier_pays_no_size_fee() {
+        let mut f = setup_exit_fee_tiers();
+        assert_u512_eq(f.vault.get_exit_fee_quote(cspr(500)), U512::zero(), "Exactly 5% of TVL");
+
+        f.env.set_caller(f.user2);
+        let receipt = f.vault.withdraw_with_receipt(cspr(400));
+        assert_u512_eq(receipt.exit_size_fee, U512::zero(), "4% of TVL");
+        assert_u512_eq(receipt.net_assets, cspr(400), "Paid in full");
+        assert!(f.env.get_event::<ExitSizeFeeCharged>(f.vault.address(), -2).is_err(), "Nothing to announce");
+    }
+
+    #[test]
+    fn test_exit_across_tiers_pays_marginal_rates() {
+        let mut f = setup_exit_fee_tiers();
+        let user1 = f.user1;
+
+        // 20% of TVL: 0.25% on the 1,000 between 5% and 15%, 0.75% on the 500 above
+        let fee = U512::from(2_500_000_000u64) + U512::from(3_750_000_000u64);
+        assert_u512_eq(f.vault.get_exit_fee_quote(cspr(2000)), fee, "Quoted before the exit");
+
+        f.env.set_caller(user1);
+        let receipt = f.vault.withdraw_with_receipt(cspr(2000));
+        assert_u512_eq(receipt.gross_assets, cspr(2000), "Valued at par");
+        assert_u512_eq(receipt.exit_size_fee, fee, "Marginal tiering");
+        assert_u512_eq(receipt.net_assets, cspr(2000) - fee, "No gain, so only the size fee");
+
+        let event = f.env.get_event::<ExitSizeFeeCharged>(f.vault.address(), -2).unwrap();
+        assert_eq!((event.user, event.request_id), (user1, None));
+        assert_u512_eq(event.exit_value, cspr(2000), "Exit value before the fee");
+        assert_u512_eq(event.total_assets, cspr(10_000), "Thresholds measured on the TVL before the exit");
+        assert_u512_eq(event.fee, fee, "Fee in the event");
+
+        // Exempt accounts and an exit of every share pay nothing
+        f.env.set_caller(f.admin);
+        f.vault.set_exit_fee_tiers(vec![(0, 500)]);
+        f.vault.set_fee_exempt(f.user2, true);
+        f.env.set_caller(f.user2);
+        assert_u512_eq(f.vault.withdraw_with_receipt(cspr(1000)).exit_size_fee, U512::zero(), "Fee exempt");
+        assert_u512_eq(f.vault.get_exit_fee_quote(f.vault.get_total_shares()), U512::zero(), "No one left to accrue to");
+    }
+
+    #[test]
+    fn test_exit_size_fee_accrues_to_remaining_holders() {
+        let mut f = setup_exit_fee_tiers();
+        let fee = U512::from(6_250_000_000u64);
+
+        f.env.set_caller(f.user1);
+        f.vault.withdraw(cspr(2000));
+
+        // 8,000 shares now back 8,006.25 lstCSPR
+        assert_u512_eq(f.vault.total_assets(), cspr(8000) + fee, "Fee left in the vault");
+        assert_u512_eq(f.vault.convert_to_assets(cspr(1000)), U512::from(1_000_781_250_000u64), "user2's stake grew");
+        assert_u512_eq(f.vault.get_fees_collected(), U512::zero(), "Nothing to the treasury");
+    }
+
+    #[test]
+    fn test_withdrawal_request_uses_tvl_at_request_time() {
+        let mut f = setup_exit_fee_tiers();
+        let fee = U512::from(6_250_000_000u64);
+
+        f.env.set_caller(f.user1);
+        let request_id = f.vault.request_withdrawal(cspr(2000));
+        let request = f.vault.get_withdrawal_request(request_id).unwrap();
+        assert_u512_eq(request.exit_fee, fee, "Charged on today's 10,000 TVL");
+        assert_u512_eq(request.assets_value, cspr(2000) - fee, "Request value net of the fee");
+        let event = f.env.get_event::<ExitSizeFeeCharged>(f.vault.address(), -2).unwrap();
+        assert_eq!(event.request_id, Some(request_id));
+
+        // Other exits while the request waits don't change what it pays
+        f.env.set_caller(f.user2);
+        f.vault.withdraw(cspr(500));
+        f.env.advance_block_time(SEVEN_DAYS);
+        f.env.set_caller(f.user1);
+        assert_u512_eq(f.vault.complete_withdrawal(request_id), cspr(2000) - fee, "Request-time fee");
+    }
+
+    #[test]
+    fn test_exit_fee_tiers_validated() {
+        let mut f = setup();
+        for tiers in [
+            vec![(1500, 75), (500, 25)],
+            vec![(500, 25), (500, 75)],
+            vec![(10_000, 25)],
+            vec![(500, 501)],
+            vec![(100, 1), (200, 1), (300, 1), (400, 1), (500, 1), (600, 1)],
+        ] {
+            assert_eq!(f.vault.try_set_exit_fee_tiers(tiers), Err(VaultError::InvalidFee.into()));
+        }
+
+        f.env.set_caller(f.user1);
+        assert!(f.vault.try_set_exit_fee_tiers(vec![(500, 25)]).is_err(), "Admin only");
+
+        f.env.set_caller(f.admin);
+        f.vault.set_exit_fee_tiers(vec![(500, 25)]);
+        f.vault.set_exit_fee_tiers(vec![]);
+        assert!(f.vault.get_exit_fee_tiers().is_empty(), "Cleared");
+    }
+
+    #[test]
+    fn test_lst_deposit_without_allowance_reverts() {
+        let mut f = setup_lst_vault();
+        f.env.set_caller(f.user);
+
+        assert_eq!(f.vault.try_deposit_lst(cspr(100)), Err(VaultError::InsufficientAllowance.into()));
+
+        f.lst.approve(*f.vault.address(), cspr(50));
+        assert_eq!(f.vault.try_deposit_lst(cspr(100)), Err(VaultError::InsufficientAllowance.into()));
+
+        assert_u512_eq(f.lst.balance_of(f.user), cspr(20_000), "Nothing pulled");
+        assert_u512_eq(f.vault.get_user_shares(f.user), U512::zero(), "Nothing minted");
+        assert_u512_eq(f.lst.allowance(f.user, *f.vault.address()), cspr(50), "Allowance untouched");
+    }
+
+    #[test]
+    fn test_deposit_rejects_dust_positions_at_the_boundary() {
+        let mut f = setup();
+        let user = f.user1;
+        assert_u512_eq(f.vault.get_min_shares(), U512::from(1000u64), "Default minimum");
+
+        f.env.set_caller(user);
+        assert_eq!(
+            f.vault.with_tokens(U512::from(999u64)).try_deposit(),
+            Err(VaultError::AmountTooLow.into())
+        );
+        assert_u512_eq(deposit(&mut f, user, U512::from(1000u64)), U512::from(1000u64), "Exactly min_shares");
+    }
+
+    #[test]
+    fn test_lst_deposit_rejects_dust_positions() {
+        let mut f = setup_lst_vault();
+        f.env.set_caller(f.user);
+        f.lst.approve(*f.vault.address(), U512::from(999u64));
+        assert_eq!(f.vault.try_deposit_lst(U512::from(999u64)), Err(VaultError::AmountTooLow.into()));
+        assert_u512_eq(f.lst.balance_of(f.user), cspr(20_000), "Nothing pulled");
+
+        assert_u512_eq(deposit_lst(&mut f, U512::from(1000u64)), U512::from(1000u64), "Exactly min_shares");
+    }
+
+    #[test]
+    fn test_migration_receipt_rejects_dust_positions() {
+        let mut f = setup_lst_vault();
+        let source = f.env.get_account(5);
+        f.env.set_caller(f.env.get_account(0));
+        f.vault.set_migration_source(source);
+        f.lst.mint(source, U512::from(1000u64));
+
+        f.env.set_caller(source);
+        f.lst.approve(*f.vault.address(), U512::from(1000u64));
+        assert_eq!(
+            f.vault.try_receive_migrated_position(f.user, U512::from(999u64), U512::from(999u64)),
+            Err(VaultError::AmountTooLow.into())
+        );
+        let minted = f.vault.receive_migrated_position(f.user, U512::from(1000u64), U512::from(1000u64));
+        assert_u512_eq(minted, U512::from(1000u64), "Exactly min_shares");
+        assert_u512_eq(f.lst.balance_of(*f.vault.address()), U512::from(1000u64), "Delivered with the position");
+    }
+
+    #[test]
+    fn test_exits_cannot_leave_dust_behind() {
+        let mut f = setup();
+        let user = f.user1;
+        let shares = deposit(&mut f, user, cspr(10));
+        let min_shares = f.vault.get_min_shares();
+        let dust_exit = shares - min_shares + U512::one();
+
+        f.env.set_caller(user);
+        assert_eq!(f.vault.try_withdraw(dust_exit), Err(VaultError::DustRemainder.into()));
+        assert_eq!(f.vault.try_instant_withdraw(dust_exit), Err(VaultError::DustRemainder.into()));
+        assert_eq!(f.vault.try_request_withdrawal(dust_exit), Err(VaultError::DustRemainder.into()));
+        assert_eq!(f.vault.try_withdraw_as_lst(dust_exit), Err(VaultError::DustRemainder.into()));
+        assert_eq!(f.vault.try_exit_batched(dust_exit), Err(VaultError::DustRemainder.into()));
+
+        // Leaving exactly min_shares is fine, and so is exiting everything
+        f.vault.withdraw(shares - min_shares);
+        assert_u512_eq(f.vault.get_user_shares(user), min_shares, "Boundary remainder kept");
+        f.vault.withdraw(min_shares);
+        assert_u512_eq(f.vault.get_user_shares(user), U512::zero(), "Full exit");
+    }
+
+    fn tranche(amount: U512, available_at: u64, path: u8) -> LiquidityTranche {
+        LiquidityTranche { amount, available_at, path }
+    }
+
+    #[test]
+    fn test_liquidity_schedule_pool_rich() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        // No strategies: everything the router is offered comes back to the pool
+        system.router.set_idle_buffer_pct(0);
+
+        env.set_caller(env.get_account(1));
+        system.vault.with_tokens(cspr(1000)).deposit();
+
+        let now = env.get_block_time();
+        let schedule = system.vault.estimate_liquidity_schedule(cspr(400));
+        assert_u512_eq(schedule.assets, cspr(400), "Exit value");
+        assert_eq!(schedule.tranches, vec![tranche(cspr(400), now, LIQUIDITY_PATH_INSTANT_POOL)]);
+    }
+
+    #[test]
+    fn test_liquidity_schedule_strategy_rich() {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        for (name, tier) in [("lending", 0u8), ("bridge", 2u8)] {
+            let strategy = SystemBuilder::mock_strategy(&env, 1000);
+            system.router.add_strategy_with_tier(name.to_string(), *strategy.address(), tier);
+        }
+        system.router.set_idle_buffer_pct(0);
+        system.router.set_target_allocations(vec![("lending".to_string(), 60u8), ("bridge".to_string(), 40u8)]);
+
+        // 5% tops up the pool, 570 goes to lending and 380 to the bridge
+        env.set_caller(env.get_account(1));
+        system.vault.with_tokens(cspr(1000)).deposit();
+
+        let now = env.get_block_time();
+        let timelock_ready = now + system.vault.get_withdrawal_timelock();
+        let schedule = system.vault.estimate_liquidity_schedule(cspr(1000));
+        assert_u512_eq(schedule.assets, cspr(1000), "Exit value");
+        assert_eq!(
+            schedule.tranches,
+            vec![
+                tranche(cspr(50), now, LIQUIDITY_PATH_INSTANT_POOL),
+                tranche(cspr(570), now, LIQUIDITY_PATH_STRATEGIES),
+                tranche(cspr(380), timelock_ready, LIQUIDITY_PATH_TIMELOCK),
+            ]
+        );
+
+        // A smaller exit stops at the first tranches it fits in
+        let schedule = system.vault.estimate_liquidity_schedule(cspr(300));
+        assert_eq!(
+            schedule.tranches,
+            vec![
+                tranche(cspr(50), now, LIQUIDITY_PATH_INSTANT_POOL),
+                tranche(cspr(250), now, LIQUIDITY_PATH_STRATEGIES),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_liquidity_schedule_staking_heavy() {
+        // No router: what the pool doesn't keep sits as lstCSPR
+        let mut f = setup();
+        let user = f.user1;
+        deposit(&mut f, user, cspr(1000));
+
+        let now = f.env.get_block_time();
+        let schedule = f.vault.estimate_liquidity_schedule(cspr(1000));
+        assert_eq!(
+            schedule.tranches,
+            vec![
+                tranche(cspr(50), now, LIQUIDITY_PATH_INSTANT_POOL),
+                tranche(cspr(950), now + FALLBACK_UNBONDING_SECONDS, LIQUIDITY_PATH_UNBONDING),
+            ]
+        );
+        let total = schedule.tranches.iter().fold(U512::zero(), |sum, tranche| sum + tranche.amount);
+        assert_u512_eq(total, schedule.assets, "Tranches cover the whole exit");
+    }
+}
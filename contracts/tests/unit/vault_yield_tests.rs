@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod vault_yield_tests {
+    use odra::prelude::*;
+    use odra::casper_types::{U256, U512};
+    use odra::host::{HostEnv, HostRef};
+    use caspervault_contracts::core::{VaultManagerHostRef, MIN_REALIZED_APY_SECONDS};
+    use caspervault_contracts::utils::SECONDS_PER_YEAR;
+    use crate::helpers::*;
+
+    const ONE_DAY: u64 = 24 * 60 * 60;
+
+    struct YieldFixture {
+        env: HostEnv,
+        user1: Address,
+        user2: Address,
+        vault: VaultManagerHostRef,
+    }
+
+    /// Vault with yield injected through the `test-helpers` hooks
+    fn setup() -> YieldFixture {
+        let env = odra_test::env();
+        let mut vault = SystemBuilder::new(&env).with_vault().build().vault.unwrap();
+        vault.set_min_holding_period(0);
+
+        let (user1, user2) = (env.get_account(1), env.get_account(2));
+        YieldFixture { env, user1, user2, vault }
+    }
+
+    fn deposit(f: &mut YieldFixture, user: Address, amount: U512) -> U512 {
+        f.env.set_caller(user);
+        f.vault.with_tokens(amount).deposit()
+    }
+
+    #[test]
+    fn test_subsequent_deposits_with_appreciation() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        assert_u512_eq(deposit(&mut f, user1, cspr(1000)), cspr(1000), "First deposit 1:1");
+        f.vault.test_add_yield(cspr(100));
+
+        // 1000 * 1000 / 1100
+        let shares = deposit(&mut f, user2, cspr(1000));
+        assert_u512_eq(shares, U512::from(909_090_909_090u64), "Fewer shares at the higher price");
+        assert_u512_within_tolerance(f.vault.get_user_assets(user1), cspr(1100), 1);
+        assert_u512_within_tolerance(f.vault.get_user_assets(user2), cspr(1000), 1);
+    }
+
+    #[test]
+    fn test_regular_withdrawal_calculates_correct_assets() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        let shares = deposit(&mut f, user1, cspr(1000));
+        f.vault.test_add_yield(cspr(100));
+
+        // 10% performance fee on the 100 CSPR profit
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.withdraw(shares), cspr(1090), "1100 CSPR less a 10 CSPR fee");
+        assert_u512_eq(f.vault.total_assets(), U512::zero(), "Vault emptied");
+    }
+
+    #[test]
+    fn test_convert_to_shares_with_appreciation() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        f.vault.test_set_total_assets(cspr(1100));
+
+        assert_u512_eq(f.vault.convert_to_shares(cspr(1100)), cspr(1000), "1100 CSPR buys 1000 shares at 1.1");
+    }
+
+    #[test]
+    fn test_convert_to_assets_calculates_correctly() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        f.vault.test_set_total_assets(cspr(1200));
+
+        assert_u512_eq(f.vault.convert_to_assets(cspr(500)), cspr(600), "500 shares at 1.2");
+    }
+
+    #[test]
+    fn test_share_price_increases_with_yield() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        deposit(&mut f, user1, cspr(1000));
+        assert_u512_eq(f.vault.get_share_price(), U512::from(1_000_000_000u64), "Starts at 1.0");
+
+        f.vault.test_add_yield(cspr(100));
+        assert_u512_eq(f.vault.get_share_price(), U512::from(1_100_000_000u64), "1.1 after 10% yield");
+    }
+
+    #[test]
+    fn test_performance_fee_only_on_profits() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        let shares = deposit(&mut f, user1, cspr(1000));
+        f.vault.test_set_total_assets(cspr(1100));
+
+        f.env.set_caller(user1);
+        let payout = f.vault.withdraw(shares);
+        assert_u512_eq(f.vault.get_fee_breakdown().performance_fees, cspr(10), "10% of the 100 CSPR profit");
+        assert_u512_eq(payout, cspr(1090), "Principal untouched");
+    }
+
+    #[test]
+    fn test_performance_fee_zero_when_no_profit() {
+        let mut f = setup();
+        let user1 = f.user1;
+
+        let shares = deposit(&mut f, user1, cspr(1000));
+        f.vault.test_set_total_assets(cspr(900));
+
+        f.env.set_caller(user1);
+        assert_u512_eq(f.vault.withdraw(shares), cspr(900), "Loss passed through in full");
+        assert_u512_eq(f.vault.get_fee_breakdown().performance_fees, U512::zero(), "No fee on a loss");
+    }
+
+    #[test]
+    fn test_multiple_users_fair_share_distribution() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        let shares1 = deposit(&mut f, user1, cspr(1000));
+        f.vault.test_add_yield(cspr(100));
+        let shares2 = deposit(&mut f, user2, cspr(1100));
+
+        assert_u512_eq(shares1, cspr(1000), "User 1 shares");
+        assert_u512_eq(shares2, cspr(1000), "Same shares for the same value");
+        assert_eq!(f.vault.get_user_assets(user1), f.vault.get_user_assets(user2), "Equal claims");
+
+        // Later yield splits evenly
+        f.vault.test_add_yield(cspr(200));
+        assert_u512_eq(f.vault.get_user_assets(user1), cspr(1200), "User 1 after yield");
+        assert_u512_eq(f.vault.get_user_assets(user2), cspr(1200), "User 2 after yield");
+    }
+
+    #[test]
+    fn test_get_user_assets_calculates_correctly() {
+        let mut f = setup();
+        let (user1, user2) = (f.user1, f.user2);
+
+        deposit(&mut f, user1, cspr(500));
+        deposit(&mut f, user2, cspr(500));
+        f.vault.test_set_total_assets(cspr(1200));
+
+        assert_u512_eq(f.vault.get_user_assets(user1), cspr(600), "500 of 1000 shares of 1200 CSPR");
+    }
+
+    /// A year at 10% gross through the 2% management and 10% performance
+    /// fees realizes about 7%
+    #[test]
+    fn test_realized_apy_over_a_synthetic_year() {
+        let mut f = setup();
+        let user1 = f.user1;
+        deposit(&mut f, user1, cspr(1000));
+        f.vault.test_add_yield(cspr(100));
+        f.env.advance_block_time(SECONDS_PER_YEAR);
+
+        let apy = f.vault.get_user_realized_apy(user1);
+        assert!(apy >= U256::from(700u64) && apy <= U256::from(720u64), "Realized {} bps", apy);
+    }
+
+    #[test]
+    fn test_realized_apy_matches_independent_computation() {
+        let mut f = setup();
+        let user1 = f.user1;
+        let shares = deposit(&mut f, user1, cspr(1000));
+        f.vault.test_add_yield(cspr(50));
+        f.env.advance_block_time(30 * ONE_DAY);
+
+        f.env.set_caller(user1);
+        let paid = f.vault.withdraw(shares / 2);
+        deposit(&mut f, user1, cspr(500));
+        f.env.advance_block_time(60 * ONE_DAY);
+
+        let record = f.vault.get_user_deposit(user1).unwrap();
+        assert_u512_eq(record.total_deposited, cspr(1500), "Lifetime deposits");
+        assert_u512_eq(record.total_withdrawn, paid, "Paid out, after fees");
+
+        // Gain on everything deposited, annualized over the 90 days since the first deposit
+        let value = f.vault.preview_withdraw(user1, f.vault.get_user_shares(user1));
+        let gain = value + paid - cspr(1500);
+        let expected = gain * U512::from(10_000u64) * U512::from(SECONDS_PER_YEAR) / (cspr(1500) * U512::from(90 * ONE_DAY));
+        assert_eq!(f.vault.get_user_realized_apy(user1), U256::from(expected.as_u128()));
+    }
+
+    #[test]
+    fn test_realized_apy_needs_a_week_and_an_open_position() {
+        let mut f = setup();
+        let user1 = f.user1;
+        assert!(f.vault.get_user_realized_apy(user1).is_zero(), "No position");
+
+        let shares = deposit(&mut f, user1, cspr(1000));
+        f.vault.test_add_yield(cspr(100));
+        f.env.advance_block_time(MIN_REALIZED_APY_SECONDS - 1);
+        assert!(f.vault.get_user_realized_apy(user1).is_zero(), "Too young to annualize");
+        f.env.advance_block_time(1);
+        assert!(!f.vault.get_user_realized_apy(user1).is_zero());
+
+        // A full exit closes the position
+        f.env.set_caller(user1);
+        f.vault.withdraw(shares);
+        assert!(f.vault.get_user_realized_apy(user1).is_zero());
+        assert_eq!(f.vault.get_user_deposit(user1).unwrap().first_deposit_time, 0);
+    }
+}
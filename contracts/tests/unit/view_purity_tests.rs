@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod view_purity_tests {
+    use odra::prelude::*;
+    use odra::host::HostEnv;
+    use caspervault_contracts::deployer::{deploy_system, DeployedSystem};
+    use crate::helpers::*;
+
+    /// Two depositors, a pending request and an unrealised profit
+    fn setup() -> (HostEnv, DeployedSystem, Vec<Address>) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut system = deploy_system(&env, admin, env.get_account(6));
+        system.vault.set_min_holding_period(0);
+
+        let users = vec![env.get_account(1), env.get_account(2)];
+        for user in &users {
+            env.set_caller(*user);
+            system.vault.with_tokens(cspr(1000)).deposit();
+        }
+        env.set_caller(users[1]);
+        system.vault.request_withdrawal(cspr(200));
+
+        env.set_caller(admin);
+        let total_assets = system.vault.total_assets();
+        system.vault.test_set_total_assets(total_assets + cspr(300));
+        env.advance_block_time(3600);
+
+        (env, system, users)
+    }
+
+    #[test]
+    fn test_quotes_leave_state_untouched() {
+        let (env, system, users) = setup();
+        let vault = &system.vault;
+        let router = &system.router;
+
+        let vault_before = VaultSnapshot::capture(vault, &users);
+        let router_before = RouterSnapshot::capture(router);
+
+        env.set_caller(env.get_account(4));
+        for user in &users {
+            let shares = vault.get_user_shares(*user);
+            vault.preview_redeem(shares);
+            vault.preview_withdraw(*user, shares);
+            vault.preview_instant_withdraw(*user, shares);
+            vault.preview_request_withdrawal(*user, shares);
+            vault.estimate_liquidity_schedule(shares);
+            vault.max_deposit(*user);
+            vault.max_withdraw(*user);
+            vault.get_user_assets(*user);
+        }
+        vault.preview_complete_withdrawal(0);
+        vault.convert_to_shares(cspr(100));
+        vault.convert_to_assets(cspr(100));
+        vault.convert_to_shares_precise(cspr(100));
+        vault.convert_to_assets_precise(cspr(100));
+        vault.get_accounting_divergence();
+        vault.get_growth_since_baseline();
+        vault.get_pending_withdrawals_page(0, 10);
+        vault.get_withdrawals_maturing_before(env.get_block_time() + 7 * 86400);
+        router.simulate_allocation(vec![]);
+
+        assert_eq!(VaultSnapshot::capture(vault, &users), vault_before);
+        assert_eq!(RouterSnapshot::capture(router), router_before);
+    }
+
+    #[test]
+    fn test_withdraw_books_previewed_fee_only() {
+        let (env, mut system, users) = setup();
+        let user = users[0];
+        let shares = system.vault.get_user_shares(user);
+
+        let fees_before = system.vault.get_fee_breakdown();
+        let expected = system.vault.preview_withdraw(user, shares);
+        assert_eq!(system.vault.get_fee_breakdown(), fees_before, "Quoting books nothing");
+
+        env.set_caller(user);
+        let payout = system.vault.withdraw(shares);
+        assert_u512_eq(payout, expected, "Withdraw pays the quote");
+
+        let booked = system.vault.get_fee_breakdown().performance_fees - fees_before.performance_fees;
+        assert!(!booked.is_zero(), "Profit is charged on exit");
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod withdrawal_cost_tests {
+    use odra::prelude::*;
+    use odra::casper_types::U512;
+    use crate::helpers::*;
+
+    /// Cost per 10,000 of `amount`, rounded down
+    fn cost_bps(cost: U512, amount: U512) -> U512 {
+        cost * U512::from(10_000u64) / amount
+    }
+
+    #[test]
+    fn test_dex_exit_cost_grows_with_size() {
+        let env = odra_test::env();
+        let mut dex = SystemBuilder::new(&env).with_dex().build().dex.unwrap();
+        assert_eq!(dex.get_exit_cost_model(), (30, cspr(1_000_000)), "0.3% pool, 1M reserve");
+
+        // Half the exit is swapped: 15 bps of fee plus a little impact
+        let small = dex.estimate_withdrawal_cost(cspr(1000));
+        assert!(small > U512::from(1_500_000_000u64), "Fee on the swapped half: {}", small);
+        assert_eq!(cost_bps(small, cspr(1000)), U512::from(17u64), "15 bps fee, 2.5 bps impact");
+
+        // Impact is quadratic in the swap, so a larger exit costs more per unit
+        let large = dex.estimate_withdrawal_cost(cspr(100_000));
+        assert_eq!(cost_bps(large, cspr(100_000)), U512::from(251u64), "Impact dominates");
+
+        // A deeper pool absorbs the same exit more cheaply
+        dex.set_exit_cost_model(30, cspr(10_000_000));
+        assert!(dex.estimate_withdrawal_cost(cspr(100_000)) < large, "Deeper pool, less impact");
+
+        // With no reserve the swapped half is lost
+        dex.set_exit_cost_model(30, U512::zero());
+        assert_u512_eq(dex.estimate_withdrawal_cost(cspr(1000)), cspr(500), "Nothing to swap against");
+        assert!(dex.try_set_exit_cost_model(1001, cspr(1_000_000)).is_err(), "Pool fee capped at 10%");
+    }
+
+    #[test]
+    fn test_lending_exit_cost_only_above_target_utilization() {
+        let env = odra_test::env();
+        let mut lending = SystemBuilder::new(&env).with_lending().build().lending.unwrap();
+        assert_eq!(lending.get_utilization_rate(), 7500);
+
+        // 75% is a quarter of the way from the 70% target to the 90% max
+        assert_eq!(lending.get_withdrawal_penalty_bps(), 100);
+        assert_u512_eq(lending.estimate_withdrawal_cost(cspr(1000)), U512::from(2_500_000_000u64), "25 bps");
+
+        lending.set_withdrawal_penalty(200);
+        assert_u512_eq(lending.estimate_withdrawal_cost(cspr(1000)), cspr(5), "Scales with the penalty");
+        assert!(lending.try_set_withdrawal_penalty(501).is_err(), "Penalty capped at 5%");
+
+        // At or below target, exits are free
+        lending.set_utilization_targets(7500, 9000);
+        assert_u512_eq(lending.estimate_withdrawal_cost(cspr(1000)), U512::zero(), "At target");
+        lending.set_utilization_targets(8000, 9000);
+        assert_u512_eq(lending.estimate_withdrawal_cost(cspr(1000)), U512::zero(), "Below target");
+
+        // Above the max the full penalty applies
+        lending.set_utilization_targets(6000, 7000);
+        assert_u512_eq(lending.estimate_withdrawal_cost(cspr(1000)), cspr(20), "Capped at max");
+    }
+
+    #[test]
+    fn test_crosschain_exit_cost_is_a_round_trip() {
+        let env = odra_test::env();
+        let mut crosschain = SystemBuilder::new(&env).with_crosschain().build().crosschain.unwrap();
+        assert_eq!(crosschain.get_bridge_fee_bps(), 50);
+
+        // Size doesn't matter, only the fee: 0.5% back plus 0.5% out again
+        assert_u512_eq(crosschain.estimate_withdrawal_cost(cspr(1000)), cspr(10), "1% of 1,000");
+        assert_u512_eq(crosschain.estimate_withdrawal_cost(cspr(100_000)), cspr(1000), "1% of 100,000");
+
+        crosschain.set_bridge_fee(100);
+        assert_u512_eq(crosschain.estimate_withdrawal_cost(cspr(1000)), cspr(20), "Follows the bridge fee");
+    }
+}
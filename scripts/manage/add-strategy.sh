@@ -122,6 +122,11 @@ mv "$ADDRESSES_FILE.tmp" "$ADDRESSES_FILE"
 log_info ""
 log_info "Step 4/6: Adding strategy to StrategyRouter..."
 
+# add_strategy registers the router on the strategy (set_router), which
+# requires the router to hold the admin role there
+log_info "Granting StrategyRouter the admin role on the strategy..."
+# In production: strategy.grant_role(0, $STRATEGY_ROUTER)
+
 log_info "Calling StrategyRouter.add_strategy()..."
 log_info "  Strategy: $STRATEGY_ADDRESS"
 log_info "  Name: $STRATEGY_TYPE"